@@ -0,0 +1,232 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::argon2::argon2id_bytes;
+use crate::calibrate::KdfParams;
+use crate::error::CryptoError;
+use crate::fips::algorithm_allowed;
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::pbkdf2::pbkdf2_hmac_sha256_bytes;
+use crate::rng::random_below;
+use crate::secret::MasterKey;
+use crate::sha2::sha256_bytes;
+
+// Klucze konta: klucz glowny (szyfrowanie vaulta) zostaje na kliencie, serwer dostaje tylko
+// hash logowania - kolejny KDF z klucza glownego, z ktorego nie da sie odtworzyc klucza:
+//   salt        = SHA-256(email obciety i malymi literami)
+//   master_key  = Argon2id(haslo, salt)   (w profilu FIPS PBKDF2-SHA256)
+//   auth_hash   = PBKDF2-SHA256(master_key, SHA-256(haslo), 1000 iteracji)
+// Sol z SHA-256 ma stala dlugosc, wiec krotki email przechodzi minimum soli FIPS.
+// Serwer powinien hash logowania jeszcze raz zahashowac przed zapisem.
+const AUTH_HASH_ITERATIONS: u32 = 1000;
+const KEY_LEN: usize = 32;
+
+// Klucz tajny konta (dwa sekrety jak w 1Password): losowy, generowany przy zakladaniu konta
+// i trzymany tylko na urzadzeniach uzytkownika, wiec wyciek bazy serwera nie pozwala
+// na lamanie samego hasla. Zapis: A3-IIIIII-SSSSSS-SSSSS-SSSSS-SSSSS-SSSSS-SSSSS
+// (wersja, 6 znakow id konta, 26 znakow sekretu ~128 bitow). Alfabet bez 0/O, 1/I i U.
+//   salt'      = HKDF(salt, sol = id konta, info = "pm-secret-key-salt" || wersja)
+//   k_password = Argon2id/PBKDF2(haslo, salt')
+//   k_secret   = HKDF(sekret, sol = id konta, info = "pm-secret-key" || wersja)
+//   master_key = k_password XOR k_secret
+const SECRET_KEY_VERSION: &str = "A3";
+const SECRET_KEY_ALPHABET: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTVWXYZ";
+const ACCOUNT_ID_LEN: usize = 6;
+const SECRET_LEN: usize = 26;
+const SECRET_GROUPS: [usize; 5] = [6, 5, 5, 5, 5];
+const SECRET_SALT_INFO: &[u8] = b"pm-secret-key-salt";
+const SECRET_KEY_INFO: &[u8] = b"pm-secret-key";
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct AccountKeys {
+    master_key: MasterKey,
+    auth_hash: Vec<u8>,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl AccountKeys {
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn master_key(&self) -> Vec<u8> {
+        self.master_key.as_bytes().to_vec()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn auth_hash(&self) -> Vec<u8> {
+        self.auth_hash.clone()
+    }
+}
+
+pub fn account_salt(email: &str) -> Result<[u8; 32], CryptoError> {
+    let email = email.trim().to_lowercase();
+    if email.is_empty() {
+        return Err(CryptoError::InvalidInput("email must not be empty".to_string()));
+    }
+    Ok(sha256_bytes(email.as_bytes()))
+}
+
+pub fn derive_account_key(password: &[u8], salt: &[u8], params: &KdfParams) -> Result<MasterKey, CryptoError> {
+    if password.is_empty() {
+        return Err(CryptoError::InvalidInput("master password must not be empty".to_string()));
+    }
+    let key = if algorithm_allowed("argon2id") {
+        argon2id_bytes(password, salt, params.argon2_m_cost, params.argon2_t_cost, params.argon2_parallelism, KEY_LEN)?
+    } else {
+        pbkdf2_hmac_sha256_bytes(password, salt, params.pbkdf2_iterations, KEY_LEN)?
+    };
+    Ok(MasterKey::from_vec(key))
+}
+
+pub fn derive_auth_hash(master_key: &MasterKey, password: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let salt = Zeroizing::new(sha256_bytes(password));
+    pbkdf2_hmac_sha256_bytes(master_key.as_bytes(), salt.as_slice(), AUTH_HASH_ITERATIONS, KEY_LEN)
+}
+
+pub fn derive_keys_bytes(password: &[u8], email: &str, params: &KdfParams) -> Result<AccountKeys, CryptoError> {
+    let master_key = derive_account_key(password, &account_salt(email)?, params)?;
+    let auth_hash = derive_auth_hash(&master_key, password)?;
+    Ok(AccountKeys { master_key, auth_hash })
+}
+
+pub struct SecretKey {
+    pub account_id: String,
+    secret: Zeroizing<String>,
+}
+
+fn random_chars(len: usize) -> Result<String, CryptoError> {
+    (0..len)
+        .map(|_| Ok(SECRET_KEY_ALPHABET[random_below(SECRET_KEY_ALPHABET.len() as u32)? as usize] as char))
+        .collect()
+}
+
+impl SecretKey {
+    pub fn generate() -> Result<SecretKey, CryptoError> {
+        Ok(SecretKey { account_id: random_chars(ACCOUNT_ID_LEN)?, secret: Zeroizing::new(random_chars(SECRET_LEN)?) })
+    }
+
+    // Myslniki i biale znaki sa pomijane, wielkosc liter bez znaczenia (przepisywanie z Emergency Kit).
+    pub fn parse(text: &str) -> Result<SecretKey, CryptoError> {
+        let chars = Zeroizing::new(
+            text.chars()
+                .filter(|c| *c != '-' && !c.is_whitespace())
+                .map(|c| c.to_ascii_uppercase())
+                .collect::<String>(),
+        );
+        let Some(rest) = chars.strip_prefix(SECRET_KEY_VERSION) else {
+            return Err(CryptoError::UnsupportedVersion(format!("secret key must start with {SECRET_KEY_VERSION}")));
+        };
+        if rest.len() != ACCOUNT_ID_LEN + SECRET_LEN {
+            return Err(CryptoError::InvalidLength(format!(
+                "secret key must have {} characters after the version",
+                ACCOUNT_ID_LEN + SECRET_LEN
+            )));
+        }
+        if let Some(c) = rest.bytes().find(|c| !SECRET_KEY_ALPHABET.contains(c)) {
+            return Err(CryptoError::InvalidInput(format!("invalid secret key character {:?}", c as char)));
+        }
+        let (account_id, secret) = rest.split_at(ACCOUNT_ID_LEN);
+        Ok(SecretKey { account_id: account_id.to_string(), secret: Zeroizing::new(secret.to_string()) })
+    }
+
+    pub fn format(&self) -> Zeroizing<String> {
+        let mut out = Zeroizing::new(format!("{SECRET_KEY_VERSION}-{}", self.account_id));
+        let mut rest = self.secret.as_str();
+        for len in SECRET_GROUPS {
+            let (group, tail) = rest.split_at(len);
+            out.push('-');
+            out.push_str(group);
+            rest = tail;
+        }
+        out
+    }
+}
+
+pub fn derive_master_key_bytes(
+    password: &[u8],
+    secret_key: &SecretKey,
+    salt: &[u8],
+    params: &KdfParams,
+) -> Result<MasterKey, CryptoError> {
+    let account_id = secret_key.account_id.as_bytes();
+    let info = |label: &[u8]| [label, SECRET_KEY_VERSION.as_bytes()].concat();
+    let password_salt = hkdf_sha256_bytes(salt, account_id, &info(SECRET_SALT_INFO), KEY_LEN)?;
+    let password_key = derive_account_key(password, &password_salt, params)?;
+    let secret =
+        Zeroizing::new(hkdf_sha256_bytes(secret_key.secret.as_bytes(), account_id, &info(SECRET_KEY_INFO), KEY_LEN)?);
+    Ok(MasterKey::from_vec(password_key.as_bytes().iter().zip(secret.iter()).map(|(a, b)| a ^ b).collect()))
+}
+
+// Parametry z calibrate_kdf przy zakladaniu konta; przy logowaniu trzeba podac te same.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn derive_keys(master_password: &str, email: &str, kdf_params: &KdfParams) -> Result<AccountKeys, CryptoError> {
+    derive_keys_bytes(master_password.as_bytes(), email, kdf_params)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn derive_keys_raw(master_password: &[u8], email: &str, kdf_params: &KdfParams) -> Result<AccountKeys, CryptoError> {
+    derive_keys_bytes(master_password, email, kdf_params)
+}
+
+// Nowy klucz tajny w postaci A3-...; do pokazania uzytkownikowi i zapisania w Emergency Kit.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn generate_secret_key() -> Result<String, CryptoError> {
+    Ok(SecretKey::generate()?.format().to_string())
+}
+
+// Postac kanoniczna klucza wpisanego recznie (walidacja formularza).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn format_secret_key(secret_key: &str) -> Result<String, CryptoError> {
+    Ok(SecretKey::parse(secret_key)?.format().to_string())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn secret_key_account_id(secret_key: &str) -> Result<String, CryptoError> {
+    Ok(SecretKey::parse(secret_key)?.account_id)
+}
+
+// salt - sol konta zapisana na serwerze przy zakladaniu konta.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn derive_master_key(
+    password: &str,
+    secret_key: &str,
+    salt: &[u8],
+    kdf_params: &KdfParams,
+) -> Result<Vec<u8>, CryptoError> {
+    let key = derive_master_key_bytes(password.as_bytes(), &SecretKey::parse(secret_key)?, salt, kdf_params)?;
+    Ok(key.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: KdfParams =
+        KdfParams { pbkdf2_iterations: 1000, argon2_m_cost: 64, argon2_t_cost: 1, argon2_parallelism: 1 };
+
+    #[test]
+    fn raw_and_text_passwords_derive_the_same_keys() -> Result<(), CryptoError> {
+        let text = derive_keys("master password", " User@Example.com ", &PARAMS)?;
+        let raw = derive_keys_raw(b"master password", "user@example.com", &PARAMS)?;
+        assert_eq!(text.master_key(), raw.master_key());
+        assert_eq!(text.auth_hash(), raw.auth_hash());
+        assert_ne!(raw.master_key(), derive_keys_raw(b"other password", "user@example.com", &PARAMS)?.master_key());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_inputs_are_rejected() {
+        assert!(matches!(derive_keys_raw(b"", "user@example.com", &PARAMS), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(derive_keys_raw(b"pw", "  ", &PARAMS), Err(CryptoError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn secret_key_parses_its_own_format() -> Result<(), CryptoError> {
+        let key = SecretKey::generate()?;
+        let parsed = SecretKey::parse(&key.format().to_lowercase())?;
+        assert_eq!(parsed.account_id, key.account_id);
+        assert_eq!(*parsed.format(), *key.format());
+        assert!(matches!(SecretKey::parse("A2-XXXX"), Err(CryptoError::UnsupportedVersion(_))));
+        assert!(matches!(SecretKey::parse(&key.format()[..20]), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+}
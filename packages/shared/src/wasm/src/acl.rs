@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::ed25519::{sign_bytes, verify_strict};
+use crate::encoding::{bytes_to_hex, length_prefixed};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::rng::random_array;
+use crate::sha2::sha256_bytes;
+use crate::secret::EntryKey;
+use crate::x25519;
+
+// Kolekcje wspoldzielone przez grupy: klucz kolekcji szyfrowany kluczem grupy (AES-256-GCM),
+// klucz grupy opakowany dla kazdego urzadzenia czlonka (X25519 + HKDF + AES-256-GCM).
+// Uprawnienia pochodza z ACL podpisanej Ed25519 przez administratora - posiadanie klucza
+// kolekcji daje tylko odczyt, zapis musi wynikac z ACL. Kolejne ACL kolekcji tworza lancuch:
+// rewizja rosnie scisle, a previous_hash wskazuje poprzednia ACL, wiec serwer nie moze
+// podsunac starszej (np. sprzed odebrania uprawnien) podpisanej wersji.
+const ACL_SIGNATURE_LABEL: &[u8] = b"pm-collection-acl-v1\0";
+const COLLECTION_KEY_LABEL: &[u8] = b"pm-acl-collection-key\0";
+const GROUP_KEY_LABEL: &[u8] = b"pm-acl-group-key\0";
+const GROUP_WRAP_INFO: &[u8] = b"pm-acl-group-key-wrap-v1";
+const ACL_CHAIN_LABEL: &[u8] = b"pm-collection-acl-chain-v1\0";
+pub const ACL_VERSION: u32 = 1;
+pub const KEY_LEN: usize = 32;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Read,
+    Write,
+    Manage,
+}
+
+impl Permission {
+    pub fn from_name(name: &str) -> Result<Permission, CryptoError> {
+        match name {
+            "read" => Ok(Permission::Read),
+            "write" => Ok(Permission::Write),
+            "manage" => Ok(Permission::Manage),
+            other => Err(CryptoError::InvalidInput(format!("unknown permission: {other}"))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AclEntry {
+    pub group_id: String,
+    pub permission: Permission,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CollectionAcl {
+    pub version: u32,
+    pub collection_id: String,
+    pub revision: u64,
+    pub entries: Vec<AclEntry>,
+    // SHA-256 (hex) poprzedniej ACL w postaci kanonicznej; brak tylko w pierwszej rewizji.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_hash: Option<String>,
+}
+
+impl CollectionAcl {
+    // Najwyzsze uprawnienie sposrod grup, do ktorych nalezy uzytkownik.
+    pub fn effective_permission(&self, member_groups: &[&str]) -> Option<Permission> {
+        self.entries
+            .iter()
+            .filter(|e| member_groups.contains(&e.group_id.as_str()))
+            .map(|e| e.permission)
+            .max()
+    }
+
+    pub fn allows(&self, member_groups: &[&str], required: Permission) -> bool {
+        self.effective_permission(member_groups).is_some_and(|p| p >= required)
+    }
+
+    pub fn canonical(&self) -> Result<String, CryptoError> {
+        serde_json::to_string(self).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+    }
+
+    pub fn digest(&self) -> Result<String, CryptoError> {
+        let mut msg = ACL_CHAIN_LABEL.to_vec();
+        msg.extend_from_slice(self.canonical()?.as_bytes());
+        Ok(bytes_to_hex(&sha256_bytes(&msg)))
+    }
+
+    // previous - ostatnia zaakceptowana ACL tej kolekcji. Ta sama rewizja to juz powtorka.
+    pub fn check_successor(&self, previous: &CollectionAcl) -> Result<(), CryptoError> {
+        if self.collection_id != previous.collection_id {
+            return Err(CryptoError::VerificationFailed("acl belongs to a different collection".to_string()));
+        }
+        if self.revision <= previous.revision {
+            return Err(CryptoError::VerificationFailed(format!(
+                "acl revision {} is not newer than accepted revision {}",
+                self.revision, previous.revision
+            )));
+        }
+        if self.previous_hash.as_deref() != Some(previous.digest()?.as_str()) {
+            return Err(CryptoError::VerificationFailed("acl does not chain to the accepted revision".to_string()));
+        }
+        Ok(())
+    }
+}
+
+fn check_key(key: &[u8], what: &str) -> Result<(), CryptoError> {
+    if key.len() != KEY_LEN {
+        return Err(CryptoError::InvalidLength(format!("{what} must be {KEY_LEN} bytes")));
+    }
+    Ok(())
+}
+
+fn collection_aad(group_id: &str, collection_id: &str) -> Vec<u8> {
+    let mut aad = COLLECTION_KEY_LABEL.to_vec();
+    aad.extend_from_slice(&length_prefixed(&[group_id.as_bytes(), collection_id.as_bytes()]));
+    aad
+}
+
+fn group_aad(group_id: &str, device_id: &str) -> Vec<u8> {
+    let mut aad = GROUP_KEY_LABEL.to_vec();
+    aad.extend_from_slice(&length_prefixed(&[group_id.as_bytes(), device_id.as_bytes()]));
+    aad
+}
+
+// nonce (12) || ciphertext || tag
+pub fn encrypt_collection_key_bytes(
+    group_key: &EntryKey,
+    group_id: &str,
+    collection_id: &str,
+    collection_key: &EntryKey,
+) -> Result<Vec<u8>, CryptoError> {
+    check_key(group_key.as_bytes(), "group key")?;
+    check_key(collection_key.as_bytes(), "collection key")?;
+    let nonce = random_array::<NONCE_LEN>()?;
+    let sealed = AesGcm::new(group_key.as_bytes())?.encrypt(
+        &nonce,
+        &collection_aad(group_id, collection_id),
+        collection_key.as_bytes(),
+    )?;
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+pub fn decrypt_collection_key_bytes(
+    group_key: &EntryKey,
+    group_id: &str,
+    collection_id: &str,
+    blob: &[u8],
+) -> Result<EntryKey, CryptoError> {
+    check_key(group_key.as_bytes(), "group key")?;
+    if blob.len() != NONCE_LEN + KEY_LEN + TAG_LEN {
+        return Err(CryptoError::InvalidLength("encrypted collection key has wrong length".to_string()));
+    }
+    let (nonce, sealed) = blob.split_at(NONCE_LEN);
+    let key = AesGcm::new(group_key.as_bytes())?.decrypt(nonce, &collection_aad(group_id, collection_id), sealed)?;
+    Ok(EntryKey::from_vec(key))
+}
+
+fn wrap_key(shared: &[u8; 32], ephemeral_public: &[u8; 32], device_public: &[u8; 32]) -> Result<EntryKey, CryptoError> {
+    let mut salt = ephemeral_public.to_vec();
+    salt.extend_from_slice(device_public);
+    Ok(EntryKey::from_vec(hkdf_sha256_bytes(shared, &salt, GROUP_WRAP_INFO, KEY_LEN)?))
+}
+
+// efemeryczny klucz publiczny (32) || nonce (12) || ciphertext || tag
+pub fn wrap_group_key_bytes(
+    device_public_key: &[u8; 32],
+    device_id: &str,
+    group_id: &str,
+    group_key: &EntryKey,
+) -> Result<Vec<u8>, CryptoError> {
+    check_key(group_key.as_bytes(), "group key")?;
+    let mut ephemeral = x25519::generate_secret()?;
+    let ephemeral_public = x25519::public_key_bytes(&ephemeral);
+    let shared = x25519::shared_secret(&ephemeral, device_public_key);
+    ephemeral.zeroize();
+    let mut shared = shared?;
+    let key = wrap_key(&shared, &ephemeral_public, device_public_key);
+    shared.zeroize();
+    let key = key?;
+
+    let nonce = random_array::<NONCE_LEN>()?;
+    let sealed = AesGcm::new(key.as_bytes())?.encrypt(&nonce, &group_aad(group_id, device_id), group_key.as_bytes())?;
+    let mut out = ephemeral_public.to_vec();
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+pub fn unwrap_group_key_bytes(
+    device_secret_key: &[u8; 32],
+    device_id: &str,
+    group_id: &str,
+    wrapped: &[u8],
+) -> Result<EntryKey, CryptoError> {
+    if wrapped.len() != 32 + NONCE_LEN + KEY_LEN + TAG_LEN {
+        return Err(CryptoError::InvalidLength("wrapped group key has wrong length".to_string()));
+    }
+    let (ephemeral_public, rest) = wrapped.split_at(32);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let ephemeral_public = x25519::to_key(ephemeral_public, "ephemeral public key")?;
+    let device_public = x25519::public_key_bytes(device_secret_key);
+    let mut shared = x25519::shared_secret(device_secret_key, &ephemeral_public)?;
+    let key = wrap_key(&shared, &ephemeral_public, &device_public);
+    shared.zeroize();
+    let key = key?;
+    let group_key = AesGcm::new(key.as_bytes())?.decrypt(nonce, &group_aad(group_id, device_id), sealed)?;
+    Ok(EntryKey::from_vec(group_key))
+}
+
+fn signed_message(payload: &str) -> Vec<u8> {
+    let mut msg = ACL_SIGNATURE_LABEL.to_vec();
+    msg.extend_from_slice(payload.as_bytes());
+    msg
+}
+
+pub fn parse_acl(payload: &str) -> Result<CollectionAcl, CryptoError> {
+    let acl: CollectionAcl =
+        serde_json::from_str(payload).map_err(|e| CryptoError::InvalidInput(format!("malformed acl: {e}")))?;
+    if acl.version != ACL_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("acl version {}", acl.version)));
+    }
+    for (i, entry) in acl.entries.iter().enumerate() {
+        if acl.entries[..i].iter().any(|e| e.group_id == entry.group_id) {
+            return Err(CryptoError::InvalidInput(format!("duplicate acl entry for group {}", entry.group_id)));
+        }
+    }
+    Ok(acl)
+}
+
+pub fn verify_signed_acl(
+    payload: &str,
+    signature: &[u8],
+    admin_public_key: &[u8],
+    previous: Option<&CollectionAcl>,
+) -> Result<CollectionAcl, CryptoError> {
+    verify_strict(admin_public_key, &signed_message(payload), signature)?;
+    let acl = parse_acl(payload)?;
+    if let Some(previous) = previous {
+        acl.check_successor(previous)?;
+    }
+    Ok(acl)
+}
+
+pub fn sign_acl_bytes(payload: &str, admin_secret_key: &[u8; 32]) -> Result<[u8; 64], CryptoError> {
+    parse_acl(payload)?;
+    Ok(sign_bytes(admin_secret_key, &signed_message(payload)))
+}
+
+// ACL podpisana dla innej kolekcji nie moze byc podstawiona.
+pub fn check_permission_bytes(
+    payload: &str,
+    signature: &[u8],
+    admin_public_key: &[u8],
+    previous: Option<&CollectionAcl>,
+    collection_id: &str,
+    member_groups: &[&str],
+    required: Permission,
+) -> Result<bool, CryptoError> {
+    let acl = verify_signed_acl(payload, signature, admin_public_key, previous)?;
+    if acl.collection_id != collection_id {
+        return Err(CryptoError::VerificationFailed("acl belongs to a different collection".to_string()));
+    }
+    Ok(acl.allows(member_groups, required))
+}
+
+fn to_secret(bytes: &[u8], what: &str) -> Result<[u8; 32], CryptoError> {
+    bytes
+        .try_into()
+        .map_err(|_| CryptoError::InvalidLength(format!("{what} must be 32 bytes")))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn encrypt_collection_key(
+    group_key: &[u8],
+    group_id: &str,
+    collection_id: &str,
+    collection_key: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    encrypt_collection_key_bytes(
+        &EntryKey::from_slice(group_key),
+        group_id,
+        collection_id,
+        &EntryKey::from_slice(collection_key),
+    )
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn decrypt_collection_key(
+    group_key: &[u8],
+    group_id: &str,
+    collection_id: &str,
+    encrypted: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let key = decrypt_collection_key_bytes(&EntryKey::from_slice(group_key), group_id, collection_id, encrypted)?;
+    Ok(key.as_bytes().to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn wrap_group_key(
+    device_public_key: &[u8],
+    device_id: &str,
+    group_id: &str,
+    group_key: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let device_public = x25519::to_key(device_public_key, "device public key")?;
+    wrap_group_key_bytes(&device_public, device_id, group_id, &EntryKey::from_slice(group_key))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn unwrap_group_key(
+    device_secret_key: &[u8],
+    device_id: &str,
+    group_id: &str,
+    wrapped: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let mut secret = to_secret(device_secret_key, "device secret key")?;
+    let key = unwrap_group_key_bytes(&secret, device_id, group_id, wrapped);
+    secret.zeroize();
+    Ok(key?.as_bytes().to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn sign_acl(payload: &str, admin_secret_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut key = to_secret(admin_secret_key, "ed25519 secret key")?;
+    let signature = sign_acl_bytes(payload, &key);
+    key.zeroize();
+    Ok(signature?.to_vec())
+}
+
+// previous - ostatnia zaakceptowana ACL tej kolekcji (JSON zwrocony przez verify_acl), jesli
+// klient ja ma. Zwraca zweryfikowana ACL jako JSON (po normalizacji).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_acl(
+    payload: &str,
+    signature: &[u8],
+    admin_public_key: &[u8],
+    previous: Option<String>,
+) -> Result<String, CryptoError> {
+    let previous = previous.as_deref().map(parse_acl).transpose()?;
+    verify_signed_acl(payload, signature, admin_public_key, previous.as_ref())?.canonical()
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn check_acl_permission(
+    payload: &str,
+    signature: &[u8],
+    admin_public_key: &[u8],
+    previous: Option<String>,
+    collection_id: &str,
+    member_groups: Vec<String>,
+    permission: &str,
+) -> Result<bool, CryptoError> {
+    let previous = previous.as_deref().map(parse_acl).transpose()?;
+    let groups: Vec<&str> = member_groups.iter().map(String::as_str).collect();
+    check_permission_bytes(
+        payload,
+        signature,
+        admin_public_key,
+        previous.as_ref(),
+        collection_id,
+        &groups,
+        Permission::from_name(permission)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ed25519;
+
+    const ADMIN: [u8; 32] = [5u8; 32];
+
+    fn acl_json(revision: u64, permission: &str, previous: Option<&CollectionAcl>) -> Result<String, CryptoError> {
+        let previous_hash = previous.map(CollectionAcl::digest).transpose()?;
+        CollectionAcl {
+            version: ACL_VERSION,
+            collection_id: "col-1".to_string(),
+            revision,
+            entries: vec![AclEntry { group_id: "eng".to_string(), permission: Permission::from_name(permission)? }],
+            previous_hash,
+        }
+        .canonical()
+    }
+
+    #[test]
+    fn chained_acl_is_accepted() -> Result<(), CryptoError> {
+        let public = ed25519::public_key_bytes(&ADMIN);
+        let first = acl_json(1, "write", None)?;
+        let accepted = verify_signed_acl(&first, &sign_acl(&first, &ADMIN)?, &public, None)?;
+        let second = acl_json(2, "read", Some(&accepted))?;
+        let signature = sign_acl(&second, &ADMIN)?;
+        assert!(check_permission_bytes(&second, &signature, &public, Some(&accepted), "col-1", &["eng"], Permission::Read)?);
+        assert!(!check_permission_bytes(&second, &signature, &public, Some(&accepted), "col-1", &["eng"], Permission::Write)?);
+        Ok(())
+    }
+
+    #[test]
+    fn replayed_or_unchained_acl_is_rejected() -> Result<(), CryptoError> {
+        let public = ed25519::public_key_bytes(&ADMIN);
+        let first = acl_json(1, "write", None)?;
+        let first_sig = sign_acl(&first, &ADMIN)?;
+        let v1 = verify_signed_acl(&first, &first_sig, &public, None)?;
+        let second = acl_json(2, "read", Some(&v1))?;
+        let v2 = verify_signed_acl(&second, &sign_acl(&second, &ADMIN)?, &public, Some(&v1))?;
+
+        // starsza ACL z waznym podpisem po odebraniu zapisu
+        let replay = check_permission_bytes(&first, &first_sig, &public, Some(&v2), "col-1", &["eng"], Permission::Write);
+        assert!(matches!(replay, Err(CryptoError::VerificationFailed(_))));
+        assert!(matches!(
+            verify_signed_acl(&second, &sign_acl(&second, &ADMIN)?, &public, Some(&v2)),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+        let unchained = acl_json(3, "write", None)?;
+        assert!(matches!(
+            verify_signed_acl(&unchained, &sign_acl(&unchained, &ADMIN)?, &public, Some(&v2)),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+        let forked = acl_json(3, "write", Some(&v1))?;
+        assert!(matches!(
+            verify_signed_acl(&forked, &sign_acl(&forked, &ADMIN)?, &public, Some(&v2)),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_or_forged_acl_is_rejected() -> Result<(), CryptoError> {
+        let public = ed25519::public_key_bytes(&ADMIN);
+        let acl = acl_json(1, "read", None)?;
+        let signature = sign_acl(&acl, &ADMIN)?;
+        let forged = acl.replace("\"read\"", "\"manage\"");
+        assert!(verify_signed_acl(&forged, &signature, &public, None).is_err());
+        assert!(matches!(
+            check_permission_bytes(&acl, &signature, &public, None, "col-2", &["eng"], Permission::Read),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+        let duplicate = r#"{"version":1,"collection_id":"c","revision":1,
+            "entries":[{"group_id":"g","permission":"read"},{"group_id":"g","permission":"write"}]}"#;
+        assert!(matches!(parse_acl(duplicate), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(Permission::from_name("owner"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn collection_key_round_trip() -> Result<(), CryptoError> {
+        let group_key = [3u8; KEY_LEN];
+        let encrypted = encrypt_collection_key(&group_key, "eng", "col-1", &[4u8; KEY_LEN])?;
+        assert_eq!(decrypt_collection_key(&group_key, "eng", "col-1", &encrypted)?, [4u8; KEY_LEN]);
+        assert!(decrypt_collection_key(&group_key, "eng", "col-2", &encrypted).is_err());
+        Ok(())
+    }
+
+    // x25519 poza profilem FIPS
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn group_key_round_trip() -> Result<(), CryptoError> {
+        let group_key = [3u8; KEY_LEN];
+        let device_secret = x25519::generate_secret()?;
+        let device_public = x25519::public_key_bytes(&device_secret);
+        let wrapped = wrap_group_key(&device_public, "dev-1", "eng", &group_key)?;
+        assert_eq!(unwrap_group_key(&device_secret, "dev-1", "eng", &wrapped)?, group_key);
+        assert!(unwrap_group_key(&device_secret, "dev-2", "eng", &wrapped).is_err());
+        let truncated = unwrap_group_key(&device_secret, "dev-1", "eng", &wrapped[1..]);
+        assert!(matches!(truncated, Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+use crate::fips::check_aead;
+use crate::gcm::{AesGcm, NONCE_LEN, Sm4Gcm, TAG_LEN};
+use crate::rng::random_array;
+
+// Rejestr algorytmow AEAD - algorytm wybierany po identyfikatorze z naglowka/profilu,
+// bez zmian w kodzie wywolujacym.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AeadAlgorithm {
+    Aes256Gcm,
+    Sm4Gcm,
+}
+
+impl AeadAlgorithm {
+    pub fn from_name(name: &str) -> Result<Self, CryptoError> {
+        let algorithm = match name.to_ascii_lowercase().as_str() {
+            "aes-256-gcm" | "aes256gcm" => AeadAlgorithm::Aes256Gcm,
+            "sm4-gcm" | "sm4gcm" => AeadAlgorithm::Sm4Gcm,
+            _ => return Err(CryptoError::UnsupportedAlgorithm(name.to_string())),
+        };
+        check_aead(algorithm)?;
+        Ok(algorithm)
+    }
+
+    // Identyfikator zapisywany w naglowkach binarnych - stabilny, nie zmieniac.
+    pub fn id(self) -> u8 {
+        match self {
+            AeadAlgorithm::Aes256Gcm => 1,
+            AeadAlgorithm::Sm4Gcm => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self, CryptoError> {
+        let algorithm = match id {
+            1 => AeadAlgorithm::Aes256Gcm,
+            2 => AeadAlgorithm::Sm4Gcm,
+            _ => return Err(CryptoError::UnsupportedAlgorithm(format!("aead id {id}"))),
+        };
+        check_aead(algorithm)?;
+        Ok(algorithm)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AeadAlgorithm::Aes256Gcm => "aes-256-gcm",
+            AeadAlgorithm::Sm4Gcm => "sm4-gcm",
+        }
+    }
+
+    pub fn key_len(self) -> usize {
+        match self {
+            AeadAlgorithm::Aes256Gcm => 32,
+            AeadAlgorithm::Sm4Gcm => 16,
+        }
+    }
+
+    pub fn nonce_len(self) -> usize {
+        NONCE_LEN
+    }
+
+    pub fn tag_len(self) -> usize {
+        TAG_LEN
+    }
+}
+
+pub enum AeadCipher {
+    Aes256Gcm(AesGcm),
+    Sm4Gcm(Sm4Gcm),
+}
+
+impl AeadCipher {
+    pub fn new(algorithm: AeadAlgorithm, key: &[u8]) -> Result<Self, CryptoError> {
+        check_aead(algorithm)?;
+        if key.len() != algorithm.key_len() {
+            return Err(CryptoError::InvalidLength(format!(
+                "{} key must be {} bytes",
+                algorithm.name(),
+                algorithm.key_len()
+            )));
+        }
+        Ok(match algorithm {
+            AeadAlgorithm::Aes256Gcm => AeadCipher::Aes256Gcm(AesGcm::new(key)?),
+            AeadAlgorithm::Sm4Gcm => AeadCipher::Sm4Gcm(Sm4Gcm::new(key)?),
+        })
+    }
+
+    pub fn algorithm(&self) -> AeadAlgorithm {
+        match self {
+            AeadCipher::Aes256Gcm(_) => AeadAlgorithm::Aes256Gcm,
+            AeadCipher::Sm4Gcm(_) => AeadAlgorithm::Sm4Gcm,
+        }
+    }
+
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match self {
+            AeadCipher::Aes256Gcm(c) => c.encrypt(nonce, aad, plaintext),
+            AeadCipher::Sm4Gcm(c) => c.encrypt(nonce, aad, plaintext),
+        }
+    }
+
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match self {
+            AeadCipher::Aes256Gcm(c) => c.decrypt(nonce, aad, ciphertext),
+            AeadCipher::Sm4Gcm(c) => c.decrypt(nonce, aad, ciphertext),
+        }
+    }
+}
+
+// Losowy nonce; wynik: nonce || ciphertext || tag
+pub fn aead_seal_bytes(algorithm: AeadAlgorithm, key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let cipher = AeadCipher::new(algorithm, key)?;
+    let nonce = random_array::<NONCE_LEN>()?;
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&cipher.encrypt(&nonce, aad, plaintext)?);
+    Ok(out)
+}
+
+pub fn aead_open_bytes(algorithm: AeadAlgorithm, key: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let cipher = AeadCipher::new(algorithm, key)?;
+    if sealed.len() < algorithm.nonce_len() + algorithm.tag_len() {
+        return Err(CryptoError::InvalidLength("sealed data too short".to_string()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(algorithm.nonce_len());
+    cipher.decrypt(nonce, aad, ciphertext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn aead_seal(algorithm: AeadAlgorithm, key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    aead_seal_bytes(algorithm, key, aad, plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn aead_open(algorithm: AeadAlgorithm, key: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    aead_open_bytes(algorithm, key, aad, sealed)
+}
+
+// Jawny nonce (12 B) podawany przez wywolujacego - nigdy nie powtarzac pary klucz/nonce.
+// Wynik: ciphertext || tag (16 B), bez nonce.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn aes256_gcm_encrypt(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    AeadCipher::new(AeadAlgorithm::Aes256Gcm, key)?.encrypt(nonce, aad, plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn aes256_gcm_decrypt(key: &[u8], nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    AeadCipher::new(AeadAlgorithm::Aes256Gcm, key)?.decrypt(nonce, aad, ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::{bytes_to_hex, hex_to_bytes};
+
+    // Przypadki 13-16 ze specyfikacji GCM (McGrew, Viega, "The Galois/Counter Mode of Operation"),
+    // AES-256: (klucz, nonce, aad, plaintext, ciphertext || tag).
+    const GCM_SPEC: [(&str, &str, &str, &str, &str); 4] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000",
+            "",
+            "",
+            "530f8afbc74536b9a963b4f1c4cb738b",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000",
+            "",
+            "00000000000000000000000000000000",
+            "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+            "cafebabefacedbaddecaf888",
+            "",
+            concat!(
+                "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72",
+                "1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255"
+            ),
+            concat!(
+                "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa",
+                "8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662898015ad",
+                "b094dac5d93471bdec1a502270e3cc6c"
+            ),
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+            "cafebabefacedbaddecaf888",
+            "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+            concat!(
+                "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72",
+                "1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39"
+            ),
+            concat!(
+                "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa",
+                "8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662",
+                "76fc6ece0f4e1768cddf8853bb2d551b"
+            ),
+        ),
+    ];
+
+    #[test]
+    fn aes256_gcm_spec_vectors() -> Result<(), CryptoError> {
+        for (key, nonce, aad, plaintext, sealed) in GCM_SPEC {
+            let (key, nonce, aad) = (hex_to_bytes(key)?, hex_to_bytes(nonce)?, hex_to_bytes(aad)?);
+            let plaintext = hex_to_bytes(plaintext)?;
+            assert_eq!(bytes_to_hex(&aes256_gcm_encrypt(&key, &nonce, &aad, &plaintext)?), sealed);
+            assert_eq!(aes256_gcm_decrypt(&key, &nonce, &aad, &hex_to_bytes(sealed)?)?, plaintext);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn aes256_gcm_rejects_tampering_and_bad_lengths() -> Result<(), CryptoError> {
+        let (key, nonce, aad, _, sealed) = GCM_SPEC[3];
+        let (key, nonce, aad) = (hex_to_bytes(key)?, hex_to_bytes(nonce)?, hex_to_bytes(aad)?);
+        let sealed = hex_to_bytes(sealed)?;
+        for at in [0, sealed.len() - 1] {
+            let mut tampered = sealed.clone();
+            tampered[at] ^= 1;
+            assert!(matches!(aes256_gcm_decrypt(&key, &nonce, &aad, &tampered), Err(CryptoError::DecryptFailed(_))));
+        }
+        assert!(matches!(aes256_gcm_decrypt(&key, &nonce, b"", &sealed), Err(CryptoError::DecryptFailed(_))));
+        assert!(matches!(aes256_gcm_encrypt(&key[..16], &nonce, b"", b""), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(aes256_gcm_encrypt(&key, &nonce[..8], b"", b""), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(aes256_gcm_decrypt(&key, &nonce, b"", &sealed[..15]), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,486 @@
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::CryptoError;
+
+// AES w postaci bitslice: stan bloku to 8 plaszczyzn u16, plaszczyzna b zawiera bit b
+// kazdego z 16 bajtow (bajt j = kolumna*4 + wiersz). S-box liczony jest obwodem
+// logicznym Boyara-Peralty, wiec nie ma tablic indeksowanych sekretem (odpornosc na
+// ataki cache-timing). Ta implementacja jest jedynym AES uzywanym przez tryby AEAD.
+type State = [u16; 8];
+
+pub const BLOCK_LEN: usize = 16;
+
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+#[derive(Clone)]
+pub struct Aes {
+    round_keys: [State; 15],
+    rounds: usize,
+}
+
+impl Aes {
+    pub fn new(key: &[u8]) -> Result<Aes, CryptoError> {
+        let (nk, rounds) = match key.len() {
+            16 => (4, 10),
+            24 => (6, 12),
+            32 => (8, 14),
+            n => return Err(CryptoError::InvalidLength(format!("AES key must be 16, 24 or 32 bytes, got {n}"))),
+        };
+
+        let total = 4 * (rounds + 1);
+        let mut w = [[0u8; 4]; 60];
+        for (i, word) in key.chunks_exact(4).enumerate() {
+            w[i] = [word[0], word[1], word[2], word[3]];
+        }
+        for i in nk..total {
+            let mut temp = w[i - 1];
+            if i % nk == 0 {
+                temp = sub_word([temp[1], temp[2], temp[3], temp[0]]);
+                temp[0] ^= RCON[i / nk - 1];
+            } else if nk > 6 && i % nk == 4 {
+                temp = sub_word(temp);
+            }
+            for k in 0..4 {
+                w[i][k] = w[i - nk][k] ^ temp[k];
+            }
+        }
+
+        let mut round_keys = [[0u16; 8]; 15];
+        for (r, rk) in round_keys.iter_mut().enumerate().take(rounds + 1) {
+            let mut block = [0u8; 16];
+            for c in 0..4 {
+                block[c * 4..c * 4 + 4].copy_from_slice(&w[r * 4 + c]);
+            }
+            *rk = pack(&block);
+            block.zeroize();
+        }
+        w.zeroize();
+
+        Ok(Aes { round_keys, rounds })
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        let mut s = pack(block);
+        add_round_key(&mut s, &self.round_keys[0]);
+        for r in 1..self.rounds {
+            sub_bytes(&mut s);
+            shift_rows(&mut s);
+            mix_columns(&mut s);
+            add_round_key(&mut s, &self.round_keys[r]);
+        }
+        sub_bytes(&mut s);
+        shift_rows(&mut s);
+        add_round_key(&mut s, &self.round_keys[self.rounds]);
+        *block = unpack(&s);
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        let mut s = pack(block);
+        add_round_key(&mut s, &self.round_keys[self.rounds]);
+        for r in (1..self.rounds).rev() {
+            inv_shift_rows(&mut s);
+            inv_sub_bytes(&mut s);
+            add_round_key(&mut s, &self.round_keys[r]);
+            inv_mix_columns(&mut s);
+        }
+        inv_shift_rows(&mut s);
+        inv_sub_bytes(&mut s);
+        add_round_key(&mut s, &self.round_keys[0]);
+        *block = unpack(&s);
+    }
+}
+
+impl Drop for Aes {
+    fn drop(&mut self) {
+        self.round_keys.zeroize();
+    }
+}
+
+fn pack(block: &[u8; 16]) -> State {
+    let mut s = [0u16; 8];
+    for (j, &byte) in block.iter().enumerate() {
+        for (b, plane) in s.iter_mut().enumerate() {
+            *plane |= (((byte >> b) & 1) as u16) << j;
+        }
+    }
+    s
+}
+
+fn unpack(s: &State) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    for (j, byte) in block.iter_mut().enumerate() {
+        for (b, plane) in s.iter().enumerate() {
+            *byte |= (((plane >> j) & 1) as u8) << b;
+        }
+    }
+    block
+}
+
+fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    let mut block = [0u8; 16];
+    block[..4].copy_from_slice(&word);
+    let mut s = pack(&block);
+    sub_bytes(&mut s);
+    let out = unpack(&s);
+    [out[0], out[1], out[2], out[3]]
+}
+
+fn add_round_key(s: &mut State, rk: &State) {
+    for (p, k) in s.iter_mut().zip(rk.iter()) {
+        *p ^= k;
+    }
+}
+
+// AES-256-CBC z dopelnieniem PKCS#7 - tylko dla formatow zewnetrznych (Bitwarden, KDBX),
+// zawsze za MAC-iem sprawdzanym przed deszyfrowaniem.
+pub fn aes_256_cbc_encrypt(key: &[u8], iv: &[u8; BLOCK_LEN], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let aes = Aes::new(key)?;
+    let pad = BLOCK_LEN - plaintext.len() % BLOCK_LEN;
+    let mut out = Vec::with_capacity(plaintext.len() + pad);
+    out.extend_from_slice(plaintext);
+    out.resize(plaintext.len() + pad, pad as u8);
+    let mut prev = *iv;
+    for chunk in out.chunks_exact_mut(BLOCK_LEN) {
+        let mut block = [0u8; BLOCK_LEN];
+        for (b, (&p, &c)) in block.iter_mut().zip(chunk.iter().zip(prev.iter())) {
+            *b = p ^ c;
+        }
+        aes.encrypt_block(&mut block);
+        chunk.copy_from_slice(&block);
+        prev = block;
+    }
+    Ok(out)
+}
+
+pub fn aes_256_cbc_decrypt(key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    if iv.len() != BLOCK_LEN || ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_LEN) {
+        return Err(CryptoError::InvalidLength("cbc ciphertext must be whole 16-byte blocks".to_string()));
+    }
+    let aes = Aes::new(key)?;
+    let mut out = Zeroizing::new(Vec::with_capacity(ciphertext.len()));
+    let mut prev = [0u8; BLOCK_LEN];
+    prev.copy_from_slice(iv);
+    for chunk in ciphertext.chunks_exact(BLOCK_LEN) {
+        let mut block = [0u8; BLOCK_LEN];
+        block.copy_from_slice(chunk);
+        aes.decrypt_block(&mut block);
+        out.extend(block.iter().zip(prev.iter()).map(|(b, p)| b ^ p));
+        prev.copy_from_slice(chunk);
+    }
+    let pad = out[out.len() - 1] as usize;
+    if pad == 0 || pad > BLOCK_LEN || !out[out.len() - pad..].iter().all(|&b| b as usize == pad) {
+        return Err(CryptoError::DecryptFailed("invalid cbc padding".to_string()));
+    }
+    let len = out.len() - pad;
+    out.truncate(len);
+    Ok(out)
+}
+
+// Obwod Boyara-Peralty (113 bramek XOR/AND); u0/s0 to najstarszy bit.
+fn sub_bytes(s: &mut State) {
+    let u7 = s[0];
+    let u6 = s[1];
+    let u5 = s[2];
+    let u4 = s[3];
+    let u3 = s[4];
+    let u2 = s[5];
+    let u1 = s[6];
+    let u0 = s[7];
+
+    let y14 = u3 ^ u5;
+    let y13 = u0 ^ u6;
+    let y12 = y13 ^ y14;
+    let t1 = u4 ^ y12;
+    let y15 = t1 ^ u5;
+    let t2 = y12 & y15;
+    let y6 = y15 ^ u7;
+    let y20 = t1 ^ u1;
+    let y9 = u0 ^ u3;
+    let y11 = y20 ^ y9;
+    let t12 = y9 & y11;
+    let y7 = u7 ^ y11;
+    let y8 = u0 ^ u5;
+    let t0 = u1 ^ u2;
+    let y10 = y15 ^ t0;
+    let y17 = y10 ^ y11;
+    let t13 = y14 & y17;
+    let t14 = t13 ^ t12;
+    let y19 = y10 ^ y8;
+    let t15 = y8 & y10;
+    let t16 = t15 ^ t12;
+    let y16 = t0 ^ y11;
+    let y21 = y13 ^ y16;
+    let t7 = y13 & y16;
+    let y18 = u0 ^ y16;
+    let y1 = t0 ^ u7;
+    let y4 = y1 ^ u3;
+    let t5 = y4 & u7;
+    let t6 = t5 ^ t2;
+    let t18 = t6 ^ t16;
+    let t22 = t18 ^ y19;
+    let y2 = y1 ^ u0;
+    let t10 = y2 & y7;
+    let t11 = t10 ^ t7;
+    let t20 = t11 ^ t16;
+    let t24 = t20 ^ y18;
+    let y5 = y1 ^ u6;
+    let t8 = y5 & y1;
+    let t9 = t8 ^ t7;
+    let t19 = t9 ^ t14;
+    let t23 = t19 ^ y21;
+    let y3 = y5 ^ y8;
+    let t3 = y3 & y6;
+    let t4 = t3 ^ t2;
+    let t17 = t4 ^ y20;
+    let t21 = t17 ^ t14;
+    let t26 = t21 & t23;
+    let t27 = t24 ^ t26;
+    let t31 = t22 ^ t26;
+    let t25 = t21 ^ t22;
+    let t28 = t25 & t27;
+    let t29 = t28 ^ t22;
+    let z14 = t29 & y2;
+    let z5 = t29 & y7;
+    let t30 = t23 ^ t24;
+    let t32 = t31 & t30;
+    let t33 = t32 ^ t24;
+    let t35 = t27 ^ t33;
+    let t36 = t24 & t35;
+    let t38 = t27 ^ t36;
+    let t39 = t29 & t38;
+    let t40 = t25 ^ t39;
+    let t43 = t29 ^ t40;
+    let z3 = t43 & y16;
+    let tc12 = z3 ^ z5;
+    let z12 = t43 & y13;
+    let z13 = t40 & y5;
+    let z4 = t40 & y1;
+    let tc6 = z3 ^ z4;
+    let t34 = t23 ^ t33;
+    let t37 = t36 ^ t34;
+    let t41 = t40 ^ t37;
+    let z8 = t41 & y10;
+    let z17 = t41 & y8;
+    let t44 = t33 ^ t37;
+    let z0 = t44 & y15;
+    let z9 = t44 & y12;
+    let z10 = t37 & y3;
+    let z1 = t37 & y6;
+    let tc5 = z1 ^ z0;
+    let tc11 = tc6 ^ tc5;
+    let z11 = t33 & y4;
+    let t42 = t29 ^ t33;
+    let t45 = t42 ^ t41;
+    let z7 = t45 & y17;
+    let tc8 = z7 ^ tc6;
+    let z16 = t45 & y14;
+    let z6 = t42 & y11;
+    let tc16 = z6 ^ tc8;
+    let z15 = t42 & y9;
+    let tc20 = z15 ^ tc16;
+    let tc1 = z15 ^ z16;
+    let tc2 = z10 ^ tc1;
+    let tc21 = tc2 ^ z11;
+    let tc3 = z9 ^ tc2;
+    let s0 = tc3 ^ tc16;
+    let s3 = tc3 ^ tc11;
+    let s1 = s3 ^ tc16;
+    let tc13 = z13 ^ tc1;
+    let z2 = t33 & u7;
+    let tc4 = z0 ^ z2;
+    let tc7 = z12 ^ tc4;
+    let tc9 = z8 ^ tc7;
+    let tc10 = tc8 ^ tc9;
+    let tc17 = z14 ^ tc10;
+    let s5 = tc21 ^ tc17;
+    let tc26 = tc17 ^ tc20;
+    let s2 = tc26 ^ z17;
+    let tc14 = tc4 ^ tc12;
+    let tc18 = tc13 ^ tc14;
+    let s6 = tc10 ^ tc18;
+    let s7 = z12 ^ tc18;
+    let s4 = tc14 ^ s3;
+
+    // stala 0x63 z przeksztalcenia afinicznego
+    s[0] = !s7;
+    s[1] = !s6;
+    s[2] = s5;
+    s[3] = s4;
+    s[4] = s3;
+    s[5] = !s2;
+    s[6] = !s1;
+    s[7] = s0;
+}
+
+// Odwrotnosc przeksztalcenia afinicznego S-boxa: y<<<1 ^ y<<<3 ^ y<<<6 ^ 0x05
+fn inv_affine(s: &mut State) {
+    let mut out = [0u16; 8];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = s[(i + 7) % 8] ^ s[(i + 5) % 8] ^ s[(i + 2) % 8];
+    }
+    out[0] = !out[0];
+    out[2] = !out[2];
+    *s = out;
+}
+
+// S^-1(y) = A^-1(S(A^-1(y))), bo x^-1 = A^-1(S(x)) - wystarcza jeden obwod S-boxa.
+fn inv_sub_bytes(s: &mut State) {
+    inv_affine(s);
+    sub_bytes(s);
+    inv_affine(s);
+}
+
+// Wiersz r to bity {r, r+4, r+8, r+12}; przesuniecie o r kolumn = rotacja o 4r bitow.
+fn shift_rows(s: &mut State) {
+    for p in s.iter_mut() {
+        let v = *p;
+        *p = (v & 0x1111)
+            | (v & 0x2222).rotate_right(4)
+            | (v & 0x4444).rotate_right(8)
+            | (v & 0x8888).rotate_right(12);
+    }
+}
+
+fn inv_shift_rows(s: &mut State) {
+    for p in s.iter_mut() {
+        let v = *p;
+        *p = (v & 0x1111)
+            | (v & 0x2222).rotate_left(4)
+            | (v & 0x4444).rotate_left(8)
+            | (v & 0x8888).rotate_left(12);
+    }
+}
+
+// a[r+k] w obrebie kolumny (nibble)
+fn rot1(p: u16) -> u16 {
+    ((p >> 1) & 0x7777) | ((p << 3) & 0x8888)
+}
+
+fn rot2(p: u16) -> u16 {
+    ((p >> 2) & 0x3333) | ((p << 2) & 0xcccc)
+}
+
+fn rot3(p: u16) -> u16 {
+    ((p >> 3) & 0x1111) | ((p << 1) & 0xeeee)
+}
+
+// mnozenie przez x w GF(2^8) (modulo 0x11b) na plaszczyznach
+fn xtime(a: &State) -> State {
+    [
+        a[7],
+        a[0] ^ a[7],
+        a[1],
+        a[2] ^ a[7],
+        a[3] ^ a[7],
+        a[4],
+        a[5],
+        a[6],
+    ]
+}
+
+// b[r] = 2(a[r] ^ a[r+1]) ^ a[r+1] ^ a[r+2] ^ a[r+3]
+fn mix_columns(s: &mut State) {
+    let mut t = [0u16; 8];
+    for i in 0..8 {
+        t[i] = s[i] ^ rot1(s[i]);
+    }
+    let t2 = xtime(&t);
+    for i in 0..8 {
+        s[i] = t2[i] ^ rot1(s[i]) ^ rot2(s[i]) ^ rot3(s[i]);
+    }
+}
+
+// InvMixColumns = MixColumns po dodaniu 4(a[r] ^ a[r+2]) do a[r] i a[r+2]
+fn inv_mix_columns(s: &mut State) {
+    let mut t = [0u16; 8];
+    for i in 0..8 {
+        t[i] = s[i] ^ rot2(s[i]);
+    }
+    let t4 = xtime(&xtime(&t));
+    for i in 0..8 {
+        s[i] ^= t4[i];
+    }
+    mix_columns(s);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::{bytes_to_hex, hex_to_bytes};
+
+    // SP 800-38A F.2.5 (CBC-AES256); ostatni blok to dopelnienie PKCS#7 pelnym blokiem 0x10.
+    const CBC_KEY: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+    const CBC_PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                                 30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+    const CBC_CIPHERTEXT: &str = "f58c4c04d6e5f1ba779eabfb5f7bfbd69cfc4e967edb808d679f777bc6702c7d\
+                                  39f23369a9d9bacfa530e26304231461b2eb05e2c39be9fcda6c19078c6a9d1b";
+    const CBC_PAD_BLOCK: &str = "3f461796d6b0d6b2e0c2a72b4d80e644";
+
+    fn iv() -> [u8; BLOCK_LEN] {
+        core::array::from_fn(|i| i as u8)
+    }
+
+    // FIPS-197, dodatek C.1-C.3
+    #[test]
+    fn fips197_known_answers() -> Result<(), CryptoError> {
+        let vectors = [
+            (16, "69c4e0d86a7b0430d8cdb78070b4c55a"),
+            (24, "dda97ca4864cdfe06eaf70a0ec0d7191"),
+            (32, "8ea2b7ca516745bfeafc49904b496089"),
+        ];
+        for (key_len, expected) in vectors {
+            let key: Vec<u8> = (0..key_len as u8).collect();
+            let aes = Aes::new(&key)?;
+            let mut block = [0u8; BLOCK_LEN];
+            block.copy_from_slice(&hex_to_bytes("00112233445566778899aabbccddeeff")?);
+            aes.encrypt_block(&mut block);
+            assert_eq!(bytes_to_hex(&block), expected);
+            aes.decrypt_block(&mut block);
+            assert_eq!(bytes_to_hex(&block), "00112233445566778899aabbccddeeff");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sp800_38a_cbc_vectors() -> Result<(), CryptoError> {
+        let key = hex_to_bytes(CBC_KEY)?;
+        let ciphertext = aes_256_cbc_encrypt(&key, &iv(), &hex_to_bytes(CBC_PLAINTEXT)?)?;
+        assert_eq!(bytes_to_hex(&ciphertext), format!("{CBC_CIPHERTEXT}{CBC_PAD_BLOCK}"));
+        let plaintext = aes_256_cbc_decrypt(&key, &iv(), &ciphertext)?;
+        assert_eq!(bytes_to_hex(&plaintext), CBC_PLAINTEXT);
+        Ok(())
+    }
+
+    #[test]
+    fn cbc_round_trip() -> Result<(), CryptoError> {
+        let key = [7u8; 32];
+        for len in [0, 1, 15, 16, 17, 100] {
+            let plaintext: Vec<u8> = (0..len as u8).collect();
+            let ciphertext = aes_256_cbc_encrypt(&key, &iv(), &plaintext)?;
+            assert_eq!(ciphertext.len(), (len / BLOCK_LEN + 1) * BLOCK_LEN);
+            assert_eq!(*aes_256_cbc_decrypt(&key, &iv(), &ciphertext)?, plaintext);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn cbc_rejects_bad_input() -> Result<(), CryptoError> {
+        let key = hex_to_bytes(CBC_KEY)?;
+        let ciphertext = aes_256_cbc_encrypt(&key, &iv(), b"attack at dawn")?;
+        assert!(matches!(aes_256_cbc_encrypt(&key[..31], &iv(), b"x"), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(aes_256_cbc_decrypt(&key[..31], &iv(), &ciphertext), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(aes_256_cbc_decrypt(&key, &iv()[..15], &ciphertext), Err(CryptoError::InvalidLength(_))));
+        let ragged = &ciphertext[..ciphertext.len() - 1];
+        assert!(matches!(aes_256_cbc_decrypt(&key, &iv(), ragged), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(aes_256_cbc_decrypt(&key, &iv(), &[]), Err(CryptoError::InvalidLength(_))));
+
+        // Bajt dopelnienia 0x10 -> 0x11 (zmiana bloku poprzedzajacego) oraz blok bez dopelnienia
+        let mut ciphertext = hex_to_bytes(&format!("{CBC_CIPHERTEXT}{CBC_PAD_BLOCK}"))?;
+        let last = ciphertext.len() - BLOCK_LEN - 1;
+        ciphertext[last] ^= 0x01;
+        assert!(matches!(aes_256_cbc_decrypt(&key, &iv(), &ciphertext), Err(CryptoError::DecryptFailed(_))));
+        let unpadded = hex_to_bytes(CBC_CIPHERTEXT)?;
+        assert!(matches!(aes_256_cbc_decrypt(&key, &iv(), &unpadded), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,604 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::chacha20::{ChaCha20Poly1305, NONCE_LEN, TAG_LEN};
+use crate::ct::ct_eq;
+use crate::encoding::{BASE64_STD, base64_decode_with, base64_to_bytes, base64_with, bytes_to_base64};
+use crate::error::CryptoError;
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::hmac::hmac_sha256_bytes;
+use crate::rng::random_array;
+use crate::scrypt::{ScryptParams, scrypt_bytes};
+use crate::x25519;
+
+// Format age v1 (age-encryption.org/v1) do eksportu pojedynczych sekretow - plik da sie
+// odszyfrowac zwyklym `age -d`. Klucz pliku (16 B) jest opakowany dla kazdego odbiorcy:
+//   X25519: klucz = HKDF(DH(e, odbiorca), sol = udzial e || odbiorca, "age-encryption.org/v1/X25519")
+//   scrypt: klucz = scrypt(haslo, "age-encryption.org/v1/scrypt" || sol, N = 2^log_n, r = 8, p = 1)
+// a opakowanie to ChaCha20-Poly1305 z zerowym nonce. Naglowek tekstowy konczy HMAC-SHA256
+// (klucz HKDF z klucza pliku, info "header"). Tresc: losowy nonce (16 B), potem STREAM
+// ChaCha20-Poly1305 w kawalkach 64 KiB, nonce kawalka = licznik (11 B BE) || flaga ostatniego.
+// Klucze w bech32: odbiorca "age1...", tozsamosc "AGE-SECRET-KEY-1...". Opcjonalny pancerz
+// PEM (BEGIN AGE ENCRYPTED FILE) dla kanalow tekstowych.
+const VERSION_LINE: &str = "age-encryption.org/v1";
+const X25519_LABEL: &[u8] = b"age-encryption.org/v1/X25519";
+const SCRYPT_LABEL: &[u8] = b"age-encryption.org/v1/scrypt";
+const RECIPIENT_HRP: &str = "age";
+const IDENTITY_HRP: &str = "age-secret-key-";
+const ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const ARMOR_END: &str = "-----END AGE ENCRYPTED FILE-----";
+const FILE_KEY_LEN: usize = 16;
+const PAYLOAD_NONCE_LEN: usize = 16;
+const CHUNK_LEN: usize = 64 * 1024;
+const COLUMNS: usize = 64;
+const SCRYPT_SALT_LEN: usize = 16;
+// Domyslny wspolczynnik pracy age (N = 2^18, 256 MiB).
+pub const AGE_SCRYPT_LOG_N: u8 = 18;
+
+pub enum AgeRecipient {
+    X25519([u8; 32]),
+    Scrypt { passphrase: Zeroizing<Vec<u8>>, log_n: u8 },
+}
+
+pub enum AgeIdentity {
+    X25519(Zeroizing<[u8; 32]>),
+    Scrypt(Zeroizing<Vec<u8>>),
+}
+
+fn malformed(what: &str) -> CryptoError {
+    CryptoError::CorruptHeader(format!("malformed age file: {what}"))
+}
+
+// ---- bech32 (BIP 173, bez limitu 90 znakow - jak w age) ----
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a_57b2, 0x2650_8e6d, 0x1ea1_19fa, 0x3d42_33dd, 0x2a14_62b3];
+    values.fold(1, |chk, v| {
+        let top = chk >> 25;
+        let chk = ((chk & 0x01ff_ffff) << 5) ^ v as u32;
+        (0..5).filter(|i| (top >> i) & 1 == 1).fold(chk, |chk, i| chk ^ GEN[i])
+    })
+}
+
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes().map(|c| c >> 5).chain([0]).chain(hrp.bytes().map(|c| c & 31))
+}
+
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let (mut acc, mut bits, mut out) = (0u32, 0u32, Vec::new());
+    for &value in data {
+        acc = (acc << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push((acc >> bits) as u8 & ((1 << to) - 1) as u8);
+        }
+    }
+    if pad && bits > 0 {
+        out.push((acc << (to - bits)) as u8 & ((1 << to) - 1) as u8);
+    } else if !pad && (bits >= from || (acc << (to - bits)) as u8 & ((1 << to) - 1) as u8 != 0) {
+        return None;
+    }
+    Some(out)
+}
+
+fn bech32_encode(hrp: &str, data: &[u8]) -> Option<String> {
+    let values = convert_bits(data, 8, 5, true)?;
+    let polymod = bech32_polymod(hrp_expand(hrp).chain(values.iter().copied()).chain([0; 6])) ^ 1;
+    let checksum = (0..6).map(|i| (polymod >> (5 * (5 - i))) as u8 & 31);
+    let mut out = format!("{hrp}1");
+    out.extend(values.iter().copied().chain(checksum).map(|v| BECH32_CHARSET[v as usize] as char));
+    Some(out)
+}
+
+fn bech32_decode(text: &str, hrp: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let invalid = || CryptoError::InvalidInput(format!("invalid {hrp} bech32 key"));
+    if text.bytes().any(|c| c.is_ascii_lowercase()) && text.bytes().any(|c| c.is_ascii_uppercase()) {
+        return Err(invalid());
+    }
+    let text = Zeroizing::new(text.to_ascii_lowercase());
+    let (prefix, data) = text.rsplit_once('1').ok_or_else(invalid)?;
+    if prefix != hrp || data.len() < 6 {
+        return Err(invalid());
+    }
+    let values = Zeroizing::new(
+        data.bytes()
+            .map(|c| BECH32_CHARSET.iter().position(|&x| x == c).map(|v| v as u8))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?,
+    );
+    if bech32_polymod(hrp_expand(hrp).chain(values.iter().copied())) != 1 {
+        return Err(invalid());
+    }
+    convert_bits(&values[..values.len() - 6], 5, 8, false).map(Zeroizing::new).ok_or_else(invalid)
+}
+
+fn key_32(bytes: &[u8], what: &str) -> Result<[u8; 32], CryptoError> {
+    x25519::to_key(bytes, what)
+}
+
+impl AgeRecipient {
+    pub fn parse(text: &str) -> Result<AgeRecipient, CryptoError> {
+        Ok(AgeRecipient::X25519(key_32(&bech32_decode(text, RECIPIENT_HRP)?, "age recipient")?))
+    }
+}
+
+impl AgeIdentity {
+    pub fn parse(text: &str) -> Result<AgeIdentity, CryptoError> {
+        let secret = bech32_decode(text, IDENTITY_HRP)?;
+        Ok(AgeIdentity::X25519(Zeroizing::new(key_32(&secret, "age identity")?)))
+    }
+}
+
+pub fn recipient_string(public: &[u8; 32]) -> Result<String, CryptoError> {
+    bech32_encode(RECIPIENT_HRP, public).ok_or_else(|| CryptoError::InvalidInput("bech32 encoding failed".to_string()))
+}
+
+pub fn identity_string(secret: &[u8; 32]) -> Result<Zeroizing<String>, CryptoError> {
+    let text = Zeroizing::new(
+        bech32_encode(IDENTITY_HRP, secret)
+            .ok_or_else(|| CryptoError::InvalidInput("bech32 encoding failed".to_string()))?,
+    );
+    Ok(Zeroizing::new(text.to_ascii_uppercase()))
+}
+
+// Plik kluczy age-keygen / lista odbiorcow: jeden klucz w linii, # to komentarz.
+fn key_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+// ---- naglowek ----
+
+struct Stanza {
+    kind: String,
+    args: Vec<String>,
+    body: Vec<u8>,
+}
+
+fn unpadded_base64(data: &[u8]) -> String {
+    base64_with(data, BASE64_STD, false)
+}
+
+fn strict_base64(text: &str) -> Result<Vec<u8>, CryptoError> {
+    if text.contains('=') {
+        return Err(malformed("padded base64"));
+    }
+    base64_decode_with(text, BASE64_STD).map_err(|_| malformed("invalid base64"))
+}
+
+fn wrap_key(key: &[u8], file_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    ChaCha20Poly1305::new(key)?.encrypt(&[0u8; NONCE_LEN], &[], file_key)
+}
+
+fn unwrap_key(key: &[u8], body: &[u8]) -> Result<Option<Zeroizing<Vec<u8>>>, CryptoError> {
+    if body.len() != FILE_KEY_LEN + TAG_LEN {
+        return Err(malformed("wrapped file key has wrong length"));
+    }
+    Ok(ChaCha20Poly1305::new(key)?.decrypt(&[0u8; NONCE_LEN], &[], body).ok().map(Zeroizing::new))
+}
+
+fn scrypt_key(passphrase: &[u8], salt: &[u8], log_n: u8) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let params = ScryptParams { log_n, r: 8, p: 1 };
+    Ok(Zeroizing::new(scrypt_bytes(passphrase, &[SCRYPT_LABEL, salt].concat(), &params, 32)?))
+}
+
+fn x25519_key(shared: &[u8; 32], share: &[u8; 32], recipient: &[u8; 32]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let salt = [share.as_slice(), recipient].concat();
+    Ok(Zeroizing::new(hkdf_sha256_bytes(shared, &salt, X25519_LABEL, 32)?))
+}
+
+fn wrap_for(recipient: &AgeRecipient, file_key: &[u8]) -> Result<Stanza, CryptoError> {
+    match recipient {
+        AgeRecipient::X25519(public) => {
+            let ephemeral = Zeroizing::new(x25519::generate_secret()?);
+            let share = x25519::public_key_bytes(&ephemeral);
+            let shared = Zeroizing::new(x25519::shared_secret(&ephemeral, public)?);
+            let key = x25519_key(&shared, &share, public)?;
+            Ok(Stanza {
+                kind: "X25519".to_string(),
+                args: vec![unpadded_base64(&share)],
+                body: wrap_key(&key, file_key)?,
+            })
+        }
+        AgeRecipient::Scrypt { passphrase, log_n } => {
+            let salt = random_array::<SCRYPT_SALT_LEN>()?;
+            let key = scrypt_key(passphrase, &salt, *log_n)?;
+            Ok(Stanza {
+                kind: "scrypt".to_string(),
+                args: vec![unpadded_base64(&salt), log_n.to_string()],
+                body: wrap_key(&key, file_key)?,
+            })
+        }
+    }
+}
+
+// Zwraca None, gdy stanza nie jest dla tej tozsamosci.
+fn unwrap_with(identity: &AgeIdentity, stanza: &Stanza) -> Result<Option<Zeroizing<Vec<u8>>>, CryptoError> {
+    match (identity, stanza.kind.as_str()) {
+        (AgeIdentity::X25519(secret), "X25519") => {
+            let [share] = stanza.args.as_slice() else { return Err(malformed("X25519 stanza needs one argument")) };
+            let share = key_32(&strict_base64(share)?, "X25519 stanza share").map_err(|_| malformed("bad share"))?;
+            let shared = Zeroizing::new(x25519::shared_secret(secret, &share)?);
+            let key = x25519_key(&shared, &share, &x25519::public_key_bytes(secret))?;
+            unwrap_key(&key, &stanza.body)
+        }
+        (AgeIdentity::Scrypt(passphrase), "scrypt") => {
+            let [salt, log_n] = stanza.args.as_slice() else {
+                return Err(malformed("scrypt stanza needs two arguments"));
+            };
+            let salt = strict_base64(salt)?;
+            if salt.len() != SCRYPT_SALT_LEN {
+                return Err(malformed("scrypt salt must be 16 bytes"));
+            }
+            if log_n.starts_with('0') || !log_n.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(malformed("invalid scrypt work factor"));
+            }
+            let log_n: u8 = log_n.parse().map_err(|_| malformed("invalid scrypt work factor"))?;
+            unwrap_key(&scrypt_key(passphrase, &salt, log_n)?, &stanza.body)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn header_mac(file_key: &[u8], header: &[u8]) -> Result<[u8; 32], CryptoError> {
+    let key = Zeroizing::new(hkdf_sha256_bytes(file_key, &[], b"header", 32)?);
+    Ok(hmac_sha256_bytes(&key, header))
+}
+
+fn write_header(stanzas: &[Stanza], file_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut header = format!("{VERSION_LINE}\n");
+    for stanza in stanzas {
+        header.push_str("-> ");
+        header.push_str(
+            &std::iter::once(&stanza.kind).chain(&stanza.args).map(String::as_str).collect::<Vec<_>>().join(" "),
+        );
+        header.push('\n');
+        // Ostatnia linia tresci zawsze krotsza niz 64 znaki (w razie potrzeby pusta)
+        let body = unpadded_base64(&stanza.body);
+        let mut rest = body.as_str();
+        loop {
+            let (line, tail) = rest.split_at(rest.len().min(COLUMNS));
+            header.push_str(line);
+            header.push('\n');
+            rest = tail;
+            if line.len() < COLUMNS {
+                break;
+            }
+        }
+    }
+    header.push_str("---");
+    let mac = header_mac(file_key, header.as_bytes())?;
+    header.push(' ');
+    header.push_str(&unpadded_base64(&mac));
+    header.push('\n');
+    Ok(header.into_bytes())
+}
+
+struct Header {
+    stanzas: Vec<Stanza>,
+    mac_input_len: usize,
+    mac: Vec<u8>,
+    len: usize,
+}
+
+fn read_line(data: &[u8], pos: &mut usize) -> Result<String, CryptoError> {
+    let rest = data.get(*pos..).ok_or_else(|| malformed("truncated header"))?;
+    let end = rest.iter().position(|&b| b == b'\n').ok_or_else(|| malformed("truncated header"))?;
+    let line = std::str::from_utf8(&rest[..end]).map_err(|_| malformed("header is not UTF-8"))?;
+    *pos += end + 1;
+    Ok(line.to_string())
+}
+
+fn read_header(data: &[u8]) -> Result<Header, CryptoError> {
+    let mut pos = 0;
+    if read_line(data, &mut pos)? != VERSION_LINE {
+        return Err(CryptoError::UnsupportedVersion("not an age v1 file".to_string()));
+    }
+    let mut stanzas = Vec::new();
+    loop {
+        let line_start = pos;
+        let line = read_line(data, &mut pos)?;
+        if let Some(mac) = line.strip_prefix("--- ") {
+            return Ok(Header { stanzas, mac_input_len: line_start + 3, mac: strict_base64(mac)?, len: pos });
+        }
+        let args = line.strip_prefix("-> ").ok_or_else(|| malformed("expected a stanza"))?;
+        let mut args = args.split(' ').map(str::to_string);
+        let kind = args.next().filter(|k| !k.is_empty()).ok_or_else(|| malformed("stanza without type"))?;
+        let args: Vec<String> = args.collect();
+        if args.iter().any(String::is_empty) {
+            return Err(malformed("empty stanza argument"));
+        }
+        let mut body = String::new();
+        loop {
+            let line = read_line(data, &mut pos)?;
+            if line.len() > COLUMNS {
+                return Err(malformed("stanza body line too long"));
+            }
+            body.push_str(&line);
+            if line.len() < COLUMNS {
+                break;
+            }
+        }
+        stanzas.push(Stanza { kind, args, body: strict_base64(&body)? });
+    }
+}
+
+// ---- tresc ----
+
+fn chunk_nonce(counter: u64, last: bool) -> [u8; NONCE_LEN] {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+fn payload_cipher(file_key: &[u8], nonce: &[u8]) -> Result<ChaCha20Poly1305, CryptoError> {
+    ChaCha20Poly1305::new(&Zeroizing::new(hkdf_sha256_bytes(file_key, nonce, b"payload", 32)?))
+}
+
+fn encrypt_payload(file_key: &[u8], plaintext: &[u8], out: &mut Vec<u8>) -> Result<(), CryptoError> {
+    let nonce = random_array::<PAYLOAD_NONCE_LEN>()?;
+    let cipher = payload_cipher(file_key, &nonce)?;
+    out.extend_from_slice(&nonce);
+    let chunks: Vec<&[u8]> = if plaintext.is_empty() { vec![&[]] } else { plaintext.chunks(CHUNK_LEN).collect() };
+    for (i, chunk) in chunks.iter().enumerate() {
+        out.extend_from_slice(&cipher.encrypt(&chunk_nonce(i as u64, i + 1 == chunks.len()), &[], chunk)?);
+    }
+    Ok(())
+}
+
+fn decrypt_payload(file_key: &[u8], payload: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    if payload.len() < PAYLOAD_NONCE_LEN + TAG_LEN {
+        return Err(malformed("truncated payload"));
+    }
+    let (nonce, body) = payload.split_at(PAYLOAD_NONCE_LEN);
+    let cipher = payload_cipher(file_key, nonce)?;
+    let chunks: Vec<&[u8]> = body.chunks(CHUNK_LEN + TAG_LEN).collect();
+    let mut out = Zeroizing::new(Vec::with_capacity(body.len()));
+    for (i, chunk) in chunks.iter().enumerate() {
+        let last = i + 1 == chunks.len();
+        // Pusty ostatni kawalek tylko przy pustym pliku
+        if last && i > 0 && chunk.len() == TAG_LEN {
+            return Err(malformed("empty final chunk"));
+        }
+        let plaintext = Zeroizing::new(
+            cipher
+                .decrypt(&chunk_nonce(i as u64, last), &[], chunk)
+                .map_err(|_| CryptoError::DecryptFailed("age payload authentication failed".to_string()))?,
+        );
+        out.extend_from_slice(&plaintext);
+    }
+    Ok(out)
+}
+
+// ---- pancerz ----
+
+fn armor(data: &[u8]) -> Vec<u8> {
+    let body = bytes_to_base64(data);
+    let mut out = format!("{ARMOR_BEGIN}\n");
+    for line in body.as_bytes().chunks(COLUMNS) {
+        out.push_str(&String::from_utf8_lossy(line));
+        out.push('\n');
+    }
+    out.push_str(ARMOR_END);
+    out.push('\n');
+    out.into_bytes()
+}
+
+fn dearmor(data: &[u8]) -> Result<Option<Vec<u8>>, CryptoError> {
+    let Ok(text) = std::str::from_utf8(data) else { return Ok(None) };
+    let Some(body) = text.trim().strip_prefix(ARMOR_BEGIN) else { return Ok(None) };
+    let body = body.strip_suffix(ARMOR_END).ok_or_else(|| malformed("armor without end line"))?;
+    let body: String = body.lines().map(str::trim).collect();
+    base64_to_bytes(&body).map(Some).map_err(|_| malformed("invalid armor base64"))
+}
+
+// ---- API ----
+
+pub fn age_encrypt_bytes(recipients: &[AgeRecipient], plaintext: &[u8], armored: bool) -> Result<Vec<u8>, CryptoError> {
+    if recipients.is_empty() {
+        return Err(CryptoError::InvalidInput("age file needs at least one recipient".to_string()));
+    }
+    if recipients.len() > 1 && recipients.iter().any(|r| matches!(r, AgeRecipient::Scrypt { .. })) {
+        return Err(CryptoError::InvalidInput("an age passphrase can't be combined with other recipients".to_string()));
+    }
+    let file_key = Zeroizing::new(random_array::<FILE_KEY_LEN>()?);
+    let stanzas = recipients.iter().map(|r| wrap_for(r, file_key.as_slice())).collect::<Result<Vec<_>, _>>()?;
+    let mut out = write_header(&stanzas, file_key.as_slice())?;
+    encrypt_payload(file_key.as_slice(), plaintext, &mut out)?;
+    Ok(if armored { armor(&out) } else { out })
+}
+
+pub fn age_decrypt_bytes(identities: &[AgeIdentity], file: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let dearmored = dearmor(file)?;
+    let file = dearmored.as_deref().unwrap_or(file);
+    let header = read_header(file)?;
+    let has_scrypt = header.stanzas.iter().any(|s| s.kind == "scrypt");
+    if has_scrypt && header.stanzas.len() != 1 {
+        return Err(malformed("scrypt stanza must be the only stanza"));
+    }
+    let passphrase_only = identities.iter().all(|i| matches!(i, AgeIdentity::Scrypt(_)));
+    let mut file_key = None;
+    'stanzas: for stanza in &header.stanzas {
+        for identity in identities {
+            if let Some(key) = unwrap_with(identity, stanza)? {
+                file_key = Some(key);
+                break 'stanzas;
+            }
+        }
+    }
+    let Some(file_key) = file_key else {
+        return Err(if has_scrypt && passphrase_only {
+            CryptoError::WrongPassword("wrong age passphrase".to_string())
+        } else {
+            CryptoError::DecryptFailed("no age identity matched any recipient".to_string())
+        });
+    };
+    if !ct_eq(&header_mac(&file_key, &file[..header.mac_input_len])?, &header.mac) {
+        return Err(CryptoError::BadMac("age header MAC mismatch".to_string()));
+    }
+    decrypt_payload(&file_key, &file[header.len..])
+}
+
+// Nowa tozsamosc X25519 w formacie age-keygen (AGE-SECRET-KEY-1...).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_generate_identity() -> Result<String, CryptoError> {
+    let secret = Zeroizing::new(x25519::generate_secret()?);
+    Ok(identity_string(&secret)?.to_string())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_identity_to_recipient(identity: &str) -> Result<String, CryptoError> {
+    match AgeIdentity::parse(identity.trim())? {
+        AgeIdentity::X25519(secret) => recipient_string(&x25519::public_key_bytes(&secret)),
+        AgeIdentity::Scrypt(_) => Err(CryptoError::InvalidInput("not an X25519 identity".to_string())),
+    }
+}
+
+// recipients - odbiorcy age1..., po jednym w linii (jak plik -R).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_encrypt(recipients: &str, plaintext: &[u8], armored: bool) -> Result<Vec<u8>, CryptoError> {
+    let recipients = key_lines(recipients).map(AgeRecipient::parse).collect::<Result<Vec<_>, _>>()?;
+    age_encrypt_bytes(&recipients, plaintext, armored)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_encrypt_passphrase(passphrase: &str, plaintext: &[u8], armored: bool) -> Result<Vec<u8>, CryptoError> {
+    age_encrypt_passphrase_raw(passphrase.as_bytes(), plaintext, armored)
+}
+
+// Warianty _raw przyjmuja haslo jako bajty UTF-8 (Uint8Array), ktore JS moze wyczyscic.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_encrypt_passphrase_raw(passphrase: &[u8], plaintext: &[u8], armored: bool) -> Result<Vec<u8>, CryptoError> {
+    if passphrase.is_empty() {
+        return Err(CryptoError::InvalidInput("age passphrase must not be empty".to_string()));
+    }
+    let recipient = AgeRecipient::Scrypt { passphrase: Zeroizing::new(passphrase.to_vec()), log_n: AGE_SCRYPT_LOG_N };
+    age_encrypt_bytes(&[recipient], plaintext, armored)
+}
+
+// identities - zawartosc pliku kluczy age-keygen (komentarze # sa pomijane).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_decrypt(identities: &str, file: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let identities = key_lines(identities).map(AgeIdentity::parse).collect::<Result<Vec<_>, _>>()?;
+    let mut plaintext = age_decrypt_bytes(&identities, file)?;
+    Ok(std::mem::take(&mut *plaintext))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_decrypt_passphrase(passphrase: &str, file: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    age_decrypt_passphrase_raw(passphrase.as_bytes(), file)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn age_decrypt_passphrase_raw(passphrase: &[u8], file: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let identity = AgeIdentity::Scrypt(Zeroizing::new(passphrase.to_vec()));
+    let mut plaintext = age_decrypt_bytes(&[identity], file)?;
+    Ok(std::mem::take(&mut *plaintext))
+}
+
+// X25519, scrypt i ChaCha20 poza profilem FIPS
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+
+    const IDENTITY: &str = "AGE-SECRET-KEY-1YZPVC35MY2JA5SAH2YDM7KK59R5L0YC4R8A3EUPANJ2GHK759E9Q0NSU2J";
+    const RECIPIENT: &str = "age1vn92y8tpdn49fn307jdnvfxs39kxpsj4rg046n29pcc78ngkp9csa30a0y";
+    // Stale: udzial efemeryczny z sekretu [7; 32], klucz pliku 00..0f, nonce tresci [0xaa; 16]
+    const FIXTURE_HEADER: &str = "age-encryption.org/v1\n\
+        -> X25519 E75P6uryBMf9M1j8nAByGIHRdCeBKCJ+xnTzf3/pe20\n\
+        /4PLhDZjpStTzYTZTsnv8pjuyaZu5teDTTOPmPaCbTk\n\
+        --- M6bVllX//qh7TQh4BKmGw8ixUDQUFS7A8G8Ugd/Xjys\n";
+    const FIXTURE_PAYLOAD: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\
+        fe9550b3cf7e878a1add5914b1d86f5722385fb40abdbba78945cf73fd71f830e3bd42d24ff14c5b";
+    const FIXTURE_PLAINTEXT: &[u8] = b"hello from the age spec\n";
+
+    fn fixture(header: &str) -> Result<Vec<u8>, CryptoError> {
+        let mut file = header.as_bytes().to_vec();
+        file.extend_from_slice(&hex_to_bytes(FIXTURE_PAYLOAD)?);
+        Ok(file)
+    }
+
+    fn passphrase(text: &[u8]) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(text.to_vec())
+    }
+
+    #[test]
+    fn x25519_round_trip() -> Result<(), CryptoError> {
+        let recipient = AgeRecipient::parse(RECIPIENT)?;
+        let identity = AgeIdentity::parse(IDENTITY)?;
+        let plaintext = vec![0x5a; CHUNK_LEN + 17];
+        for armored in [false, true] {
+            let file = age_encrypt_bytes(std::slice::from_ref(&recipient), &plaintext, armored)?;
+            assert_eq!(*age_decrypt_bytes(std::slice::from_ref(&identity), &file)?, plaintext);
+        }
+        let other = AgeIdentity::X25519(Zeroizing::new(x25519::generate_secret()?));
+        let file = age_encrypt_bytes(&[recipient], b"", false)?;
+        assert!(age_decrypt_bytes(&[identity], &file)?.is_empty());
+        assert!(matches!(age_decrypt_bytes(&[other], &file), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn scrypt_round_trip() -> Result<(), CryptoError> {
+        let recipient = AgeRecipient::Scrypt { passphrase: passphrase(b"correct horse"), log_n: 10 };
+        let file = age_encrypt_bytes(&[recipient], b"secret", false)?;
+        assert_eq!(*age_decrypt_bytes(&[AgeIdentity::Scrypt(passphrase(b"correct horse"))], &file)?, b"secret");
+        assert!(matches!(
+            age_decrypt_bytes(&[AgeIdentity::Scrypt(passphrase(b"wrong horse"))], &file),
+            Err(CryptoError::WrongPassword(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn decrypts_fixed_fixture() -> Result<(), CryptoError> {
+        let identity = AgeIdentity::parse(IDENTITY)?;
+        assert_eq!(*age_decrypt_bytes(&[identity], &fixture(FIXTURE_HEADER)?)?, FIXTURE_PLAINTEXT);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_header_mac() -> Result<(), CryptoError> {
+        let header = FIXTURE_HEADER.replace("--- M6bV", "--- N6bV");
+        let identity = AgeIdentity::parse(IDENTITY)?;
+        assert!(matches!(age_decrypt_bytes(&[identity], &fixture(&header)?), Err(CryptoError::BadMac(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_scrypt_mixed_with_other_stanzas() -> Result<(), CryptoError> {
+        let header = FIXTURE_HEADER.replace("---", "-> scrypt AAAAAAAAAAAAAAAAAAAAAA 10\n\n---");
+        let identity = AgeIdentity::Scrypt(passphrase(b"correct horse"));
+        assert!(matches!(age_decrypt_bytes(&[identity], &fixture(&header)?), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_leading_zero_work_factor() -> Result<(), CryptoError> {
+        let recipient = AgeRecipient::Scrypt { passphrase: passphrase(b"correct horse"), log_n: 10 };
+        let file = age_encrypt_bytes(&[recipient], b"secret", false)?;
+        let end = file.windows(4).position(|w| w == b"\n---").unwrap_or_default();
+        let header = String::from_utf8_lossy(&file[..end]).replace(" 10\n", " 010\n");
+        let mut tampered = header.into_bytes();
+        tampered.extend_from_slice(&file[end..]);
+        let identity = AgeIdentity::Scrypt(passphrase(b"correct horse"));
+        assert!(matches!(age_decrypt_bytes(&[identity], &tampered), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_non_canonical_stanza_base64() -> Result<(), CryptoError> {
+        // Ostatni znak niesie 2 bity dopelnienia - "l" zamiast "k" ustawia je na niezerowe
+        for body in ["PmPaCbTl\n", "PmPaCbTk=\n"] {
+            let header = FIXTURE_HEADER.replace("PmPaCbTk\n", body);
+            let identity = AgeIdentity::parse(IDENTITY)?;
+            assert!(matches!(age_decrypt_bytes(&[identity], &fixture(&header)?), Err(CryptoError::CorruptHeader(_))));
+        }
+        Ok(())
+    }
+}
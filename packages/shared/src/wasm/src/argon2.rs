@@ -0,0 +1,578 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::blake2b::Blake2b;
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+use crate::limits::{check_argon2_iterations, check_dk_len, check_memory_kib, check_salt_len};
+use crate::phc::PhcString;
+use crate::rng::random_array;
+
+// Argon2 (RFC 9106), wszystkie trzy warianty i obie wersje (0x10 dla importowanych danych,
+// 0x13 domyslnie). Pasy liczone po kolei w jednym watku - wynik nie zalezy od liczby watkow.
+// Stringi PHC: $argon2id$v=19$m=<KiB>,t=<przebiegi>,p=<pasy>$<sol>$<hash>
+const BLOCK_WORDS: usize = 128;
+const SYNC_POINTS: usize = 4;
+const MIN_SALT_LEN: usize = 8;
+const MIN_OUTPUT_LEN: usize = 4;
+const MAX_LANES: u32 = 0x00ff_ffff;
+
+// Domyslne parametry nowych hashy PHC (OWASP: m=19 MiB, t=2, p=1).
+pub const DEFAULT_MEMORY_KIB: u32 = 19 * 1024;
+pub const DEFAULT_ITERATIONS: u32 = 2;
+pub const DEFAULT_PARALLELISM: u32 = 1;
+const DEFAULT_SALT_LEN: usize = 16;
+const DEFAULT_HASH_LEN: usize = 32;
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Argon2Variant {
+    Argon2d = 0,
+    Argon2i = 1,
+    Argon2id = 2,
+}
+
+impl Argon2Variant {
+    pub fn name(self) -> &'static str {
+        match self {
+            Argon2Variant::Argon2d => "argon2d",
+            Argon2Variant::Argon2i => "argon2i",
+            Argon2Variant::Argon2id => "argon2id",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, CryptoError> {
+        match name {
+            "argon2d" => Ok(Argon2Variant::Argon2d),
+            "argon2i" => Ok(Argon2Variant::Argon2i),
+            "argon2id" => Ok(Argon2Variant::Argon2id),
+            other => Err(CryptoError::UnsupportedAlgorithm(other.to_string())),
+        }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Argon2Version {
+    V0x10 = 0x10,
+    V0x13 = 0x13,
+}
+
+impl Argon2Version {
+    pub fn from_number(v: u32) -> Result<Self, CryptoError> {
+        match v {
+            0x10 => Ok(Argon2Version::V0x10),
+            0x13 => Ok(Argon2Version::V0x13),
+            other => Err(CryptoError::UnsupportedVersion(format!("argon2 version {other}"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Argon2Params {
+    pub variant: Argon2Variant,
+    pub version: Argon2Version,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+type Block = [u64; BLOCK_WORDS];
+
+fn blake2b_long(out: &mut [u8], parts: &[&[u8]]) -> Result<(), CryptoError> {
+    let len = (out.len() as u32).to_le_bytes();
+    if out.len() <= 64 {
+        let mut h = Blake2b::new(out.len())?;
+        h.update(&len);
+        for p in parts {
+            h.update(p);
+        }
+        out.copy_from_slice(&h.finalize());
+        return Ok(());
+    }
+    // H' (RFC 9106, 3.3): kolejne V_i po 64 bajty, z kazdego bierzemy pierwsze 32
+    let mut h = Blake2b::new(64)?;
+    h.update(&len);
+    for p in parts {
+        h.update(p);
+    }
+    let mut v = Zeroizing::new(h.finalize());
+    let mut pos = 0;
+    while out.len() - pos > 64 {
+        out[pos..pos + 32].copy_from_slice(&v[..32]);
+        pos += 32;
+        let mut h = Blake2b::new((out.len() - pos).min(64))?;
+        h.update(&v);
+        v = Zeroizing::new(h.finalize());
+    }
+    out[pos..].copy_from_slice(&v);
+    Ok(())
+}
+
+fn bla_mka(x: u64, y: u64) -> u64 {
+    let m = (x & 0xffff_ffff).wrapping_mul(y & 0xffff_ffff);
+    x.wrapping_add(y).wrapping_add(m.wrapping_mul(2))
+}
+
+fn gb(v: &mut Block, a: usize, b: usize, c: usize, d: usize) {
+    v[a] = bla_mka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = bla_mka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = bla_mka(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = bla_mka(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+// Runda P na 16 slowach o podanych indeksach.
+fn permute(v: &mut Block, i: [usize; 16]) {
+    gb(v, i[0], i[4], i[8], i[12]);
+    gb(v, i[1], i[5], i[9], i[13]);
+    gb(v, i[2], i[6], i[10], i[14]);
+    gb(v, i[3], i[7], i[11], i[15]);
+    gb(v, i[0], i[5], i[10], i[15]);
+    gb(v, i[1], i[6], i[11], i[12]);
+    gb(v, i[2], i[7], i[8], i[13]);
+    gb(v, i[3], i[4], i[9], i[14]);
+}
+
+// G(X, Y): R = X ^ Y, P po wierszach i kolumnach macierzy 8x8 rejestrow 16-bajtowych,
+// wynik Z ^ R (przy with_xor dodatkowo ^ poprzednia zawartosc bloku - wersja 0x13).
+fn compress(prev: &Block, reference: &Block, out: &mut Block, with_xor: bool) {
+    let mut r = [0u64; BLOCK_WORDS];
+    for (i, w) in r.iter_mut().enumerate() {
+        *w = prev[i] ^ reference[i];
+    }
+    let mut tmp = r;
+    if with_xor {
+        for (t, o) in tmp.iter_mut().zip(out.iter()) {
+            *t ^= o;
+        }
+    }
+    for row in 0..8 {
+        let base = row * 16;
+        permute(&mut r, core::array::from_fn(|k| base + k));
+    }
+    for col in 0..8 {
+        permute(&mut r, core::array::from_fn(|k| 2 * col + (k / 2) * 16 + (k % 2)));
+    }
+    for (o, (t, z)) in out.iter_mut().zip(tmp.iter().zip(r.iter())) {
+        *o = t ^ z;
+    }
+    r.zeroize();
+    tmp.zeroize();
+}
+
+fn block_from_bytes(bytes: &[u8]) -> Block {
+    let mut block = [0u64; BLOCK_WORDS];
+    for (w, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+        *w = u64::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7]]);
+    }
+    block
+}
+
+pub fn check_params(params: &Argon2Params, salt_len: usize, out_len: usize) -> Result<(), CryptoError> {
+    require_approved(params.variant.name())?;
+    check_argon2_iterations(params.iterations)?;
+    check_memory_kib(params.memory_kib as u64)?;
+    check_dk_len(out_len)?;
+    check_salt_len(salt_len)?;
+    if params.parallelism == 0 || params.parallelism > MAX_LANES {
+        return Err(CryptoError::OutOfRange(format!("argon2 parallelism must be in 1..={MAX_LANES}")));
+    }
+    if (params.memory_kib as u64) < 8 * params.parallelism as u64 {
+        return Err(CryptoError::OutOfRange("argon2 memory must be at least 8 KiB per lane".to_string()));
+    }
+    if salt_len < MIN_SALT_LEN {
+        return Err(CryptoError::OutOfRange(format!("argon2 salt must be at least {MIN_SALT_LEN} bytes")));
+    }
+    if out_len < MIN_OUTPUT_LEN {
+        return Err(CryptoError::OutOfRange(format!("argon2 output must be at least {MIN_OUTPUT_LEN} bytes")));
+    }
+    Ok(())
+}
+
+struct Position {
+    pass: u32,
+    lane: u32,
+    slice: u32,
+}
+
+struct Instance<'a> {
+    params: &'a Argon2Params,
+    memory: Vec<Block>,
+    lanes: u32,
+    lane_length: u32,
+    segment_length: u32,
+}
+
+impl Instance<'_> {
+    // Indeks bloku odniesienia (RFC 9106, 3.4.1.2) w pasie ref_lane.
+    fn reference_index(&self, pos: &Position, index: u32, j1: u32, same_lane: bool) -> usize {
+        let seg = self.segment_length;
+        let area = if pos.pass == 0 {
+            if pos.slice == 0 || same_lane {
+                pos.slice * seg + index - 1
+            } else {
+                pos.slice * seg - (index == 0) as u32
+            }
+        } else if same_lane {
+            self.lane_length - seg + index - 1
+        } else {
+            self.lane_length - seg - (index == 0) as u32
+        };
+        let x = ((j1 as u64) * (j1 as u64)) >> 32;
+        let relative = area as u64 - 1 - ((area as u64 * x) >> 32);
+        let start = if pos.pass == 0 || pos.slice == SYNC_POINTS as u32 - 1 { 0 } else { (pos.slice + 1) * seg };
+        ((start as u64 + relative) % self.lane_length as u64) as usize
+    }
+
+    fn fill_segment(&mut self, pos: &Position) {
+        let params = self.params;
+        let data_independent = params.variant == Argon2Variant::Argon2i
+            || (params.variant == Argon2Variant::Argon2id && pos.pass == 0 && pos.slice < 2);
+        let zero = [0u64; BLOCK_WORDS];
+        let mut input = [0u64; BLOCK_WORDS];
+        let mut addresses = [0u64; BLOCK_WORDS];
+        if data_independent {
+            input[0] = pos.pass as u64;
+            input[1] = pos.lane as u64;
+            input[2] = pos.slice as u64;
+            input[3] = self.memory.len() as u64;
+            input[4] = params.iterations as u64;
+            input[5] = params.variant as u64;
+        }
+        let next_addresses = |input: &mut Block, addresses: &mut Block| {
+            input[6] += 1;
+            let mut tmp = [0u64; BLOCK_WORDS];
+            compress(&zero, input, &mut tmp, false);
+            compress(&zero, &tmp, addresses, false);
+        };
+
+        let start_index = if pos.pass == 0 && pos.slice == 0 {
+            if data_independent {
+                next_addresses(&mut input, &mut addresses);
+            }
+            2
+        } else {
+            0
+        };
+        let lane_start = (pos.lane * self.lane_length) as usize;
+        for index in start_index..self.segment_length {
+            let column = pos.slice * self.segment_length + index;
+            let current = lane_start + column as usize;
+            let prev = if column == 0 { lane_start + self.lane_length as usize - 1 } else { current - 1 };
+            let pseudo_random = if data_independent {
+                if (index as usize).is_multiple_of(BLOCK_WORDS) {
+                    next_addresses(&mut input, &mut addresses);
+                }
+                addresses[index as usize % BLOCK_WORDS]
+            } else {
+                self.memory[prev][0]
+            };
+            let ref_lane = if pos.pass == 0 && pos.slice == 0 {
+                pos.lane
+            } else {
+                ((pseudo_random >> 32) % self.lanes as u64) as u32
+            };
+            let ref_index = self.reference_index(pos, index, pseudo_random as u32, ref_lane == pos.lane);
+            let reference = (ref_lane * self.lane_length) as usize + ref_index;
+
+            let with_xor = params.version == Argon2Version::V0x13 && pos.pass > 0;
+            let (prev_block, ref_block) = (self.memory[prev], self.memory[reference]);
+            compress(&prev_block, &ref_block, &mut self.memory[current], with_xor);
+        }
+        addresses.zeroize();
+    }
+}
+
+impl Drop for Instance<'_> {
+    fn drop(&mut self) {
+        for block in self.memory.iter_mut() {
+            block.zeroize();
+        }
+    }
+}
+
+pub fn argon2_bytes(
+    params: &Argon2Params,
+    password: &[u8],
+    salt: &[u8],
+    secret: &[u8],
+    associated_data: &[u8],
+    out_len: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    check_params(params, salt.len(), out_len)?;
+    let lanes = params.parallelism;
+    let memory_blocks = (params.memory_kib / (SYNC_POINTS as u32 * lanes)) * SYNC_POINTS as u32 * lanes;
+    let lane_length = memory_blocks / lanes;
+
+    // H0
+    let mut h = Blake2b::new(64)?;
+    for v in [lanes, out_len as u32, params.memory_kib, params.iterations, params.version as u32, params.variant as u32] {
+        h.update(&v.to_le_bytes());
+    }
+    for part in [password, salt, secret, associated_data] {
+        h.update(&(part.len() as u32).to_le_bytes());
+        h.update(part);
+    }
+    let h0 = Zeroizing::new(h.finalize());
+
+    let mut instance = Instance {
+        params,
+        memory: vec![[0u64; BLOCK_WORDS]; memory_blocks as usize],
+        lanes,
+        lane_length,
+        segment_length: lane_length / SYNC_POINTS as u32,
+    };
+    let mut block_bytes = Zeroizing::new([0u8; 1024]);
+    for lane in 0..lanes {
+        for column in 0..2u32 {
+            blake2b_long(block_bytes.as_mut(), &[&h0, &column.to_le_bytes(), &lane.to_le_bytes()])?;
+            instance.memory[(lane * lane_length + column) as usize] = block_from_bytes(block_bytes.as_ref());
+        }
+    }
+
+    for pass in 0..params.iterations {
+        for slice in 0..SYNC_POINTS as u32 {
+            for lane in 0..lanes {
+                instance.fill_segment(&Position { pass, lane, slice });
+            }
+        }
+    }
+
+    let mut last = instance.memory[(lane_length - 1) as usize];
+    for lane in 1..lanes {
+        let block = &instance.memory[(lane * lane_length + lane_length - 1) as usize];
+        for (a, b) in last.iter_mut().zip(block.iter()) {
+            *a ^= b;
+        }
+    }
+    for (chunk, w) in block_bytes.chunks_exact_mut(8).zip(last.iter()) {
+        chunk.copy_from_slice(&w.to_le_bytes());
+    }
+    last.zeroize();
+    let mut out = vec![0u8; out_len];
+    blake2b_long(&mut out, &[block_bytes.as_ref()])?;
+    Ok(out)
+}
+
+pub fn argon2_hash_bytes(params: &Argon2Params, password: &[u8], salt: &[u8], out_len: usize) -> Result<Vec<u8>, CryptoError> {
+    argon2_bytes(params, password, salt, &[], &[], out_len)
+}
+
+// KDF klucza glownego z hasla (zamiast PBKDF2): argon2id v=0x13, m_cost w KiB.
+pub fn argon2id_bytes(
+    password: &[u8],
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    parallelism: u32,
+    dk_len: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    let params = Argon2Params {
+        variant: Argon2Variant::Argon2id,
+        version: Argon2Version::V0x13,
+        memory_kib: m_cost,
+        iterations: t_cost,
+        parallelism,
+    };
+    argon2_hash_bytes(&params, password, salt, dk_len)
+}
+
+// ---- PHC ----
+
+// Brak v= oznacza 0x10, jak w libargon2 i passlib - tak zapisywaly hashe starsze biblioteki.
+pub fn parse_argon2_phc(text: &str) -> Result<(Argon2Params, Vec<u8>, Vec<u8>), CryptoError> {
+    let phc = PhcString::parse(text)?;
+    let variant = Argon2Variant::from_name(&phc.algorithm)?;
+    let version = Argon2Version::from_number(phc.version.unwrap_or(Argon2Version::V0x10 as u32))?;
+    phc.require_known_params(&["m", "t", "p"])?;
+    let required = |name: &str| {
+        phc.decimal_param(name)?
+            .ok_or_else(|| CryptoError::InvalidInput(format!("argon2 PHC string is missing parameter {name}")))
+    };
+    let params = Argon2Params {
+        variant,
+        version,
+        memory_kib: required("m")?,
+        iterations: required("t")?,
+        parallelism: required("p")?,
+    };
+    let salt = phc.salt.clone().ok_or_else(|| CryptoError::InvalidInput("argon2 PHC string has no salt".to_string()))?;
+    let hash = phc.hash.clone().ok_or_else(|| CryptoError::InvalidInput("argon2 PHC string has no hash".to_string()))?;
+    Ok((params, salt, hash))
+}
+
+pub fn encode_argon2_phc(params: &Argon2Params, salt: &[u8], hash: &[u8]) -> String {
+    PhcString {
+        algorithm: params.variant.name().to_string(),
+        version: Some(params.version as u32),
+        params: vec![
+            ("m".to_string(), params.memory_kib.to_string()),
+            ("t".to_string(), params.iterations.to_string()),
+            ("p".to_string(), params.parallelism.to_string()),
+        ],
+        salt: Some(salt.to_vec()),
+        hash: Some(hash.to_vec()),
+    }
+    .encode()
+}
+
+pub fn argon2_hash_phc_bytes(params: &Argon2Params, password: &[u8]) -> Result<String, CryptoError> {
+    let salt = random_array::<DEFAULT_SALT_LEN>()?;
+    let hash = Zeroizing::new(argon2_hash_bytes(params, password, &salt, DEFAULT_HASH_LEN)?);
+    Ok(encode_argon2_phc(params, &salt, &hash))
+}
+
+pub fn argon2_verify_bytes(password: &[u8], phc: &str) -> Result<bool, CryptoError> {
+    let (params, salt, expected) = parse_argon2_phc(phc)?;
+    let computed = Zeroizing::new(argon2_hash_bytes(&params, password, &salt, expected.len())?);
+    Ok(ct_eq(&computed, &expected))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2_hash(
+    variant: Argon2Variant,
+    password: &[u8],
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    dk_len: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    let params = Argon2Params { variant, version: Argon2Version::V0x13, memory_kib, iterations, parallelism };
+    argon2_hash_bytes(&params, password, salt, dk_len)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    parallelism: u32,
+    dk_len: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    argon2id_bytes(password, salt, m_cost, t_cost, parallelism, dk_len)
+}
+
+// Nowy rekord w formacie PHC (argon2id, v=19, losowa sol 16 B, hash 32 B).
+// Parametry 0 = domyslne (m=19456, t=2, p=1).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2id_hash_phc(password: &str, memory_kib: u32, iterations: u32, parallelism: u32) -> Result<String, CryptoError> {
+    argon2id_hash_phc_raw(password.as_bytes(), memory_kib, iterations, parallelism)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2id_hash_phc_raw(
+    password: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+) -> Result<String, CryptoError> {
+    let or_default = |v: u32, d: u32| if v == 0 { d } else { v };
+    let params = Argon2Params {
+        variant: Argon2Variant::Argon2id,
+        version: Argon2Version::V0x13,
+        memory_kib: or_default(memory_kib, DEFAULT_MEMORY_KIB),
+        iterations: or_default(iterations, DEFAULT_ITERATIONS),
+        parallelism: or_default(parallelism, DEFAULT_PARALLELISM),
+    };
+    argon2_hash_phc_bytes(&params, password)
+}
+
+// Dowolny wariant/wersja z PHC; parametry sprawdzane wzgledem limitow KDF przed liczeniem.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2_verify(password: &str, phc_string: &str) -> Result<bool, CryptoError> {
+    argon2_verify_bytes(password.as_bytes(), phc_string)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2_verify_raw(password: &[u8], phc_string: &str) -> Result<bool, CryptoError> {
+    argon2_verify_bytes(password, phc_string)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2_phc_encode(
+    variant: Argon2Variant,
+    version: Argon2Version,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: &[u8],
+    hash: &[u8],
+) -> String {
+    encode_argon2_phc(&Argon2Params { variant, version, memory_kib, iterations, parallelism }, salt, hash)
+}
+
+// Zwraca JSON {algorithm, version, memory_kib, iterations, parallelism, salt, hash} (sol i hash w hex).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn argon2_phc_parse(phc_string: &str) -> Result<String, CryptoError> {
+    let (params, salt, hash) = parse_argon2_phc(phc_string)?;
+    Ok(serde_json::json!({
+        "algorithm": params.variant.name(),
+        "version": params.version as u32,
+        "memory_kib": params.memory_kib,
+        "iterations": params.iterations,
+        "parallelism": params.parallelism,
+        "salt": crate::encoding::bytes_to_hex(&salt),
+        "hash": crate::encoding::bytes_to_hex(&hash),
+    })
+    .to_string())
+}
+
+// Argon2 poza profilem FIPS
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+
+    // Z argon2id OpenSSL (haslo "password")
+    const KNOWN_PHC: &str =
+        "$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqvgR3wcPX7SleI4c9NtXk6E";
+
+    #[test]
+    fn verifies_known_phc_string() -> Result<(), CryptoError> {
+        assert!(argon2_verify("password", KNOWN_PHC)?);
+        assert!(!argon2_verify("passwore", KNOWN_PHC)?);
+        let (params, salt, hash) = parse_argon2_phc(KNOWN_PHC)?;
+        assert_eq!((params.memory_kib, params.iterations, params.parallelism), (64, 2, 1));
+        assert_eq!(encode_argon2_phc(&params, &salt, &hash), KNOWN_PHC);
+        Ok(())
+    }
+
+    // Z testow libargon2 (wersja 0x10, bez v=)
+    const LEGACY_PHC: &str = "$argon2i$m=256,t=2,p=1$c29tZXNhbHQ$/U3YPXYsSb3q9XxHvc0MLxur+GP960kN9j7emXX8zwY";
+
+    #[test]
+    fn missing_version_means_0x10() -> Result<(), CryptoError> {
+        let (params, _, _) = parse_argon2_phc(LEGACY_PHC)?;
+        assert_eq!(params.version, Argon2Version::V0x10);
+        assert!(argon2_verify("password", LEGACY_PHC)?);
+        assert!(!argon2_verify("password", &LEGACY_PHC.replace("$m=", "$v=19$m="))?);
+        // Hash 0x13 bez v= liczony jako 0x10 po prostu sie nie zgadza
+        assert!(!argon2_verify("password", &KNOWN_PHC.replace("$v=19", ""))?);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let unknown = KNOWN_PHC.replace("v=19", "v=18");
+        assert!(matches!(parse_argon2_phc(&unknown), Err(CryptoError::UnsupportedVersion(_))));
+    }
+
+    #[test]
+    fn rejects_out_of_range_params() {
+        let with = |params: &str| KNOWN_PHC.replace("m=64,t=2,p=1", params);
+        for params in ["m=7,t=2,p=1", "m=64,t=2,p=0", "m=64,t=2,p=16777216", "m=64,t=2,p=9", "m=4294967296,t=2,p=1"] {
+            assert!(matches!(argon2_verify("password", &with(params)), Err(CryptoError::OutOfRange(_))), "{params}");
+        }
+        assert!(matches!(argon2_verify("password", &with("m=64,t=0,p=1")), Err(CryptoError::InvalidIterations(_))));
+        for params in ["m=064,t=2,p=1", "m=64,t=-2,p=1", "m=64,t=2", "m=64,t=2,p=1,x=1"] {
+            assert!(argon2_verify("password", &with(params)).is_err(), "{params}");
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::cdc::{
+    ChunkKeys, ChunkParams, ChunkRef, ContentChunks, decrypt_chunk_bytes, derive_chunk_keys, encrypt_chunk_bytes,
+};
+use crate::ct::ct_eq;
+use crate::encoding::{bytes_to_hex, hex_to_bytes};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::rng::random_array;
+use crate::scope::{VaultScope, derive_scope_key};
+use crate::secret::MasterKey;
+use crate::sha2::Sha256;
+
+// Zalaczniki z fragmentow adresowanych trescia (cdc.rs) plus zaszyfrowany manifest.
+// AttachmentChunker przyjmuje plik kawalkami i zwraca gotowe zaszyfrowane fragmenty, wiec
+// caly plik nie musi byc w pamieci wasm. Szyfrowanie fragmentow jest deterministyczne:
+// po przerwanym uploadzie ponowne pociecie pliku daje te same identyfikatory, a
+// missing_chunks wskazuje, ktorych serwer jeszcze nie ma (takze fragmenty juz obecne
+// z innych zalacznikow - deduplikacja).
+// Manifest (nazwa, typ, rozmiar, SHA-256 calego pliku, lista fragmentow ze skrotami)
+// szyfrowany AES-256-GCM, bo skroty fragmentow sa kluczami do ich odszyfrowania:
+//   klucz = HKDF(klucz partycji "attachments", info = "pm-attachment-manifest")
+//   blob  = "PMAM" || 1 || nonce (12 B) || ciphertext || tag, AAD = "PMAM" || 1
+// Serwer dostaje jawnie tylko liste identyfikatorow (AttachmentManifest w gc.rs).
+const MANIFEST_MAGIC: &[u8; 4] = b"PMAM";
+const MANIFEST_VERSION: u8 = 1;
+const MANIFEST_HEADER_LEN: usize = 5;
+const MANIFEST_KEY_INFO: &[u8] = b"pm-attachment-manifest";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkManifest {
+    pub name: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub sha256: String,
+    pub chunks: Vec<ChunkRef>,
+}
+
+fn manifest_cipher(vault_key: &MasterKey) -> Result<AesGcm, CryptoError> {
+    let scope_key = derive_scope_key(vault_key, VaultScope::Attachments)?;
+    let key = Zeroizing::new(hkdf_sha256_bytes(scope_key.as_bytes(), &[], MANIFEST_KEY_INFO, 32)?);
+    AesGcm::new(&key)
+}
+
+fn manifest_header() -> Vec<u8> {
+    let mut header = MANIFEST_MAGIC.to_vec();
+    header.push(MANIFEST_VERSION);
+    header
+}
+
+pub fn seal_manifest_bytes(vault_key: &MasterKey, manifest: &ChunkManifest) -> Result<Vec<u8>, CryptoError> {
+    let json = Zeroizing::new(serde_json::to_vec(manifest).map_err(|e| CryptoError::InvalidInput(e.to_string()))?);
+    let nonce = random_array::<NONCE_LEN>()?;
+    let mut out = manifest_header();
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&manifest_cipher(vault_key)?.encrypt(&nonce, &out[..MANIFEST_HEADER_LEN], &json)?);
+    Ok(out)
+}
+
+pub fn open_manifest_bytes(vault_key: &MasterKey, blob: &[u8]) -> Result<ChunkManifest, CryptoError> {
+    if blob.len() < MANIFEST_HEADER_LEN + NONCE_LEN + TAG_LEN || &blob[..4] != MANIFEST_MAGIC {
+        return Err(CryptoError::CorruptHeader("not an attachment manifest".to_string()));
+    }
+    if blob[4] != MANIFEST_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("attachment manifest version {}", blob[4])));
+    }
+    let (header, rest) = blob.split_at(MANIFEST_HEADER_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let json = Zeroizing::new(
+        manifest_cipher(vault_key)?
+            .decrypt(nonce, header, ciphertext)
+            .map_err(|_| CryptoError::DecryptFailed("attachment manifest authentication failed".to_string()))?,
+    );
+    serde_json::from_slice(&json).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+// Identyfikatory fragmentow, ktorych brakuje w magazynie - bez powtorzen, w kolejnosci pliku.
+pub fn missing_chunk_ids(chunk_ids: &[String], stored_ids: &[String]) -> Vec<String> {
+    let mut seen: BTreeSet<&str> = stored_ids.iter().map(String::as_str).collect();
+    chunk_ids.iter().filter(|id| seen.insert(id.as_str())).cloned().collect()
+}
+
+fn finished() -> CryptoError {
+    CryptoError::InvalidInput("attachment is already finished".to_string())
+}
+
+// push zwraca fragmenty gotowe do wyslania; ostatni kawalek danych jest wstrzymywany, dopoki
+// nie uzbiera sie max_size (granica moze zalezec od dalszych bajtow). Podzial jest taki sam
+// jak w chunk_attachment dla calego pliku naraz.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct AttachmentChunker {
+    keys: ChunkKeys,
+    params: ChunkParams,
+    pending: Zeroizing<Vec<u8>>,
+    offset: u64,
+    hasher: Sha256,
+    refs: Vec<ChunkRef>,
+    done: bool,
+}
+
+impl AttachmentChunker {
+    pub fn with_params(vault_key: &MasterKey, params: ChunkParams) -> Result<AttachmentChunker, CryptoError> {
+        Ok(AttachmentChunker {
+            keys: derive_chunk_keys(vault_key)?,
+            params,
+            pending: Zeroizing::new(Vec::new()),
+            offset: 0,
+            hasher: Sha256::new(),
+            refs: Vec::new(),
+            done: false,
+        })
+    }
+
+    fn emit(&mut self, all: bool) -> Result<ContentChunks, CryptoError> {
+        let max = self.params.max_size as usize;
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < self.pending.len() && (all || self.pending.len() - start >= max) {
+            let len = self.params.cut(&self.pending[start..]);
+            let chunk = encrypt_chunk_bytes(&self.keys, self.offset, &self.pending[start..start + len])?;
+            self.refs.push(chunk.0.clone());
+            chunks.push(chunk);
+            self.offset += len as u64;
+            start += len;
+        }
+        self.pending.drain(..start);
+        Ok(ContentChunks::new(chunks))
+    }
+
+    pub fn manifest_of(&self, name: &str, mime_type: &str) -> Result<ChunkManifest, CryptoError> {
+        if !self.done {
+            return Err(CryptoError::InvalidInput("attachment is not finished".to_string()));
+        }
+        Ok(ChunkManifest {
+            name: name.to_string(),
+            mime_type: mime_type.to_string(),
+            size: self.offset,
+            sha256: bytes_to_hex(&self.hasher.clone().finalize()),
+            chunks: self.refs.clone(),
+        })
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl AttachmentChunker {
+    // Rozmiary 0 = domyslne (16 KiB / 64 KiB / 256 KiB).
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(
+        vault_key: &[u8],
+        min_size: u32,
+        avg_size: u32,
+        max_size: u32,
+    ) -> Result<AttachmentChunker, CryptoError> {
+        AttachmentChunker::with_params(
+            &MasterKey::from_slice(vault_key),
+            ChunkParams::new(min_size, avg_size, max_size)?,
+        )
+    }
+
+    pub fn push(&mut self, data: &[u8]) -> Result<ContentChunks, CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        self.hasher.update(data);
+        self.pending.extend_from_slice(data);
+        self.emit(false)
+    }
+
+    pub fn finish(&mut self) -> Result<ContentChunks, CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        self.done = true;
+        self.emit(true)
+    }
+
+    // Po finish: identyfikatory wszystkich fragmentow (z powtorzeniami) - jawna czesc dla serwera.
+    pub fn chunk_ids(&self) -> Vec<String> {
+        self.refs.iter().map(|r| r.id.clone()).collect()
+    }
+
+    // Po finish: zaszyfrowany manifest do zapisania przy wpisie.
+    pub fn seal_manifest(&self, vault_key: &[u8], name: &str, mime_type: &str) -> Result<Vec<u8>, CryptoError> {
+        seal_manifest_bytes(&MasterKey::from_slice(vault_key), &self.manifest_of(name, mime_type)?)
+    }
+}
+
+// Pobieranie: fragmenty podawane w kolejnosci manifestu (chunk_id(next_index) mowi, ktory
+// pobrac), push zwraca odszyfrowany fragment, finish sprawdza rozmiar i SHA-256 calego pliku.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct AttachmentAssembler {
+    keys: ChunkKeys,
+    manifest: ChunkManifest,
+    next: usize,
+    offset: u64,
+    hasher: Sha256,
+    done: bool,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl AttachmentAssembler {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(vault_key: &[u8], sealed_manifest: &[u8]) -> Result<AttachmentAssembler, CryptoError> {
+        let vault_key = MasterKey::from_slice(vault_key);
+        Ok(AttachmentAssembler {
+            keys: derive_chunk_keys(&vault_key)?,
+            manifest: open_manifest_bytes(&vault_key, sealed_manifest)?,
+            next: 0,
+            offset: 0,
+            hasher: Sha256::new(),
+            done: false,
+        })
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn name(&self) -> String {
+        self.manifest.name.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn mime_type(&self) -> String {
+        self.manifest.mime_type.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn size(&self) -> u64 {
+        self.manifest.size
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn count(&self) -> usize {
+        self.manifest.chunks.len()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn next_index(&self) -> usize {
+        self.next
+    }
+
+    pub fn chunk_id(&self, index: usize) -> Result<String, CryptoError> {
+        self.manifest
+            .chunks
+            .get(index)
+            .map(|r| r.id.clone())
+            .ok_or_else(|| CryptoError::OutOfRange(format!("chunk index {index}")))
+    }
+
+    pub fn chunk_ids(&self) -> Vec<String> {
+        self.manifest.chunks.iter().map(|r| r.id.clone()).collect()
+    }
+
+    pub fn push(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        let chunk_ref = self
+            .manifest
+            .chunks
+            .get(self.next)
+            .ok_or_else(|| CryptoError::OutOfRange("attachment has no more chunks".to_string()))?;
+        let hash: [u8; 32] = hex_to_bytes(&chunk_ref.hash)?
+            .try_into()
+            .map_err(|_| CryptoError::InvalidLength("chunk hash must be 32 bytes".to_string()))?;
+        let chunk = decrypt_chunk_bytes(&self.keys, &hash, ciphertext)?;
+        if chunk_ref.offset != self.offset || chunk_ref.size as usize != chunk.len() {
+            return Err(CryptoError::VerificationFailed(format!(
+                "attachment chunk {} does not match manifest",
+                self.next
+            )));
+        }
+        self.hasher.update(&chunk);
+        self.offset += chunk.len() as u64;
+        self.next += 1;
+        Ok(chunk)
+    }
+
+    pub fn finish(&mut self) -> Result<(), CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        self.done = true;
+        if self.next != self.manifest.chunks.len() || self.offset != self.manifest.size {
+            return Err(CryptoError::VerificationFailed("attachment is incomplete".to_string()));
+        }
+        let digest = self.hasher.clone().finalize();
+        if !ct_eq(&hex_to_bytes(&self.manifest.sha256)?, &digest) {
+            return Err(CryptoError::VerificationFailed("attachment hash mismatch".to_string()));
+        }
+        Ok(())
+    }
+}
+
+// JSON manifestu ({"name", "mime_type", "size", "sha256", "chunks": [...]}).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn open_attachment_manifest(vault_key: &[u8], sealed_manifest: &[u8]) -> Result<String, CryptoError> {
+    let manifest = open_manifest_bytes(&MasterKey::from_slice(vault_key), sealed_manifest)?;
+    serde_json::to_string(&manifest).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+// Wznowienie uploadu: stored_ids - identyfikatory, ktore serwer juz ma.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn missing_chunks(chunk_ids: Vec<String>, stored_ids: Vec<String>) -> Vec<String> {
+    missing_chunk_ids(&chunk_ids, &stored_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_KEY: [u8; 32] = [0x24; 32];
+
+    // Dane pseudolosowe - na stalym wzorze CDC tnie tylko na max_size
+    fn data(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn chunker() -> Result<AttachmentChunker, CryptoError> {
+        AttachmentChunker::new(&VAULT_KEY, 256, 1024, 4096)
+    }
+
+    fn collect(chunks: &ContentChunks, ids: &mut Vec<String>, blobs: &mut Vec<Vec<u8>>) -> Result<(), CryptoError> {
+        for i in 0..chunks.count() {
+            ids.push(chunks.id(i)?);
+            blobs.push(chunks.ciphertext(i)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn push_finish_round_trip_with_unaligned_pushes() -> Result<(), CryptoError> {
+        let plaintext = data(20_000);
+        let (mut whole_ids, mut whole_blobs) = (Vec::new(), Vec::new());
+        let mut whole = chunker()?;
+        collect(&whole.push(&plaintext)?, &mut whole_ids, &mut whole_blobs)?;
+        collect(&whole.finish()?, &mut whole_ids, &mut whole_blobs)?;
+
+        let (mut ids, mut blobs) = (Vec::new(), Vec::new());
+        let mut streamed = chunker()?;
+        for part in plaintext.chunks(777) {
+            collect(&streamed.push(part)?, &mut ids, &mut blobs)?;
+        }
+        collect(&streamed.finish()?, &mut ids, &mut blobs)?;
+        assert!(ids.len() > 1);
+        assert_eq!(ids, whole_ids);
+        assert_eq!(streamed.chunk_ids(), ids);
+
+        let manifest = streamed.seal_manifest(&VAULT_KEY, "report.pdf", "application/pdf")?;
+        let mut assembler = AttachmentAssembler::new(&VAULT_KEY, &manifest)?;
+        assert_eq!((assembler.name(), assembler.size()), ("report.pdf".to_string(), plaintext.len() as u64));
+        let mut out = Vec::new();
+        for blob in &blobs {
+            out.extend_from_slice(&assembler.push(blob)?);
+        }
+        assembler.finish()?;
+        assert_eq!(out, plaintext);
+        Ok(())
+    }
+
+    #[test]
+    fn push_after_finish_fails() -> Result<(), CryptoError> {
+        let mut streamed = chunker()?;
+        let mut blobs = Vec::new();
+        collect(&streamed.push(&data(3000))?, &mut Vec::new(), &mut blobs)?;
+        collect(&streamed.finish()?, &mut Vec::new(), &mut blobs)?;
+        assert!(matches!(streamed.push(b"late"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(streamed.finish(), Err(CryptoError::InvalidInput(_))));
+
+        let manifest = streamed.seal_manifest(&VAULT_KEY, "a", "b")?;
+        let mut assembler = AttachmentAssembler::new(&VAULT_KEY, &manifest)?;
+        for blob in &blobs {
+            assembler.push(blob)?;
+        }
+        assembler.finish()?;
+        assert!(matches!(assembler.push(&blobs[0]), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(assembler.finish(), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn assembler_rejects_incomplete_or_reordered_chunks() -> Result<(), CryptoError> {
+        let mut streamed = chunker()?;
+        let mut blobs = Vec::new();
+        collect(&streamed.push(&data(10_000))?, &mut Vec::new(), &mut blobs)?;
+        collect(&streamed.finish()?, &mut Vec::new(), &mut blobs)?;
+        assert!(matches!(streamed.manifest_of("a", "b"), Ok(m) if m.chunks.len() == blobs.len()));
+        let manifest = streamed.seal_manifest(&VAULT_KEY, "a", "b")?;
+
+        let mut reordered = AttachmentAssembler::new(&VAULT_KEY, &manifest)?;
+        assert!(reordered.push(&blobs[1]).is_err());
+
+        let mut incomplete = AttachmentAssembler::new(&VAULT_KEY, &manifest)?;
+        incomplete.push(&blobs[0])?;
+        assert!(matches!(incomplete.finish(), Err(CryptoError::VerificationFailed(_))));
+        assert!(matches!(chunker()?.manifest_of("a", "b"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::dedupe::{MergeProposal, vault_duplicates};
+use crate::error::CryptoError;
+use crate::hmac::hmac_sha256_bytes;
+use crate::item::{ItemType, VaultItem};
+use crate::rng::random_array;
+use crate::strength::estimate_strength_report;
+
+// Audyt hasel vaulta: ponownie uzyte, slabe, loginy bez TOTP, dawno niezmieniane i duplikaty.
+// Hasla porownywane po odciskach HMAC-SHA256 z losowa sola jednego audytu (normalizacja jak
+// w historii hasel - obciete biale znaki), wiec ani JS, ani raport nie widza hasel ani
+// stalych skrotow, ktore dalo sie porownac miedzy audytami. Raport zawiera tylko id wpisow.
+// Wiek hasla to wiek wpisu (updated_at) - model wpisu nie ma osobnego czasu zmiany hasla.
+const FINGERPRINT_LABEL: &[u8] = b"pm-audit-fingerprint-v1\0";
+const SECONDS_PER_DAY: u64 = 86_400;
+pub const DEFAULT_MAX_AGE_DAYS: u32 = 365;
+// Wynik estymatora (0..4) ponizej tego progu to haslo slabe
+pub const MIN_STRONG_SCORE: u8 = 3;
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct WeakPassword {
+    pub item: String,
+    pub score: u8,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct StalePassword {
+    pub item: String,
+    pub age_days: u64,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditReport {
+    pub audited: u32,
+    // Grupy id wpisow z tym samym haslem (kazda >= 2)
+    pub reused: Vec<Vec<String>>,
+    pub weak: Vec<WeakPassword>,
+    pub missing_totp: Vec<String>,
+    pub stale: Vec<StalePassword>,
+    // Zduplikowane loginy (strona + uzytkownik + haslo) z propozycja scalenia
+    pub duplicates: Vec<MergeProposal>,
+}
+
+pub struct Fingerprinter {
+    salt: Zeroizing<[u8; 32]>,
+}
+
+impl Fingerprinter {
+    pub fn new() -> Result<Fingerprinter, CryptoError> {
+        Ok(Fingerprinter { salt: Zeroizing::new(random_array::<32>()?) })
+    }
+
+    // None dla pustego hasla.
+    pub fn password(&self, password: &str) -> Option<[u8; 32]> {
+        let normalized = password.trim();
+        if normalized.is_empty() {
+            return None;
+        }
+        let msg = Zeroizing::new([FINGERPRINT_LABEL, normalized.as_bytes()].concat());
+        Some(hmac_sha256_bytes(&*self.salt, &msg))
+    }
+}
+
+fn login_password(item: &VaultItem) -> Option<&str> {
+    item.login().and_then(|login| login.password.as_deref()).filter(|p| !p.trim().is_empty())
+}
+
+pub fn audit_items(items: &[VaultItem], now: u64, max_age_days: u32) -> Result<AuditReport, CryptoError> {
+    let max_age_days = if max_age_days == 0 { DEFAULT_MAX_AGE_DAYS } else { max_age_days } as u64;
+    let fingerprinter = Fingerprinter::new()?;
+    let mut report = AuditReport::default();
+    let mut by_fingerprint: BTreeMap<[u8; 32], Vec<String>> = BTreeMap::new();
+    for item in items {
+        let Some(password) = login_password(item) else { continue };
+        report.audited += 1;
+        if let Some(fingerprint) = fingerprinter.password(password) {
+            by_fingerprint.entry(fingerprint).or_default().push(item.id());
+        }
+        let score = estimate_strength_report(password).score;
+        if score < MIN_STRONG_SCORE {
+            report.weak.push(WeakPassword { item: item.id(), score });
+        }
+        if item.item_type() == ItemType::Login && item.login().is_some_and(|login| login.totp.is_none()) {
+            report.missing_totp.push(item.id());
+        }
+        let age_days = now.saturating_sub(item.updated_at()) / SECONDS_PER_DAY;
+        if age_days >= max_age_days {
+            report.stale.push(StalePassword { item: item.id(), age_days });
+        }
+    }
+    report.reused = by_fingerprint.into_values().filter(|ids| ids.len() > 1).collect();
+    report.reused.sort();
+    report.duplicates = vault_duplicates(items, &fingerprinter)?;
+    Ok(report)
+}
+
+// items - tablica JSON wpisow (VaultItem.to_json); max_age_days 0 = domyslne (365).
+// Wynik: {audited, reused: [[id...]...], weak: [{item, score}...], missing_totp: [id...],
+// stale: [{item, age_days}...], duplicates: [{keep, duplicates, add_uris}...]}.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn audit_vault(items: &str, now: u64, max_age_days: u32) -> Result<String, CryptoError> {
+    let values: Vec<Value> =
+        serde_json::from_str(items).map_err(|e| CryptoError::InvalidInput(format!("malformed items: {e}")))?;
+    let items = values.iter().map(|value| VaultItem::from_json(&value.to_string())).collect::<Result<Vec<_>, _>>()?;
+    let report = audit_items(&items, now, max_age_days)?;
+    serde_json::to_string(&report).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRONG: &str = "v8#Qm2!zR7pL@x4Wk9$e";
+
+    fn login(id: &str, data: &str, updated_at: u64) -> Result<VaultItem, CryptoError> {
+        let mut item = VaultItem::create(id, ItemType::Login, id, updated_at)?;
+        item.set_data(data, updated_at)?;
+        Ok(item)
+    }
+
+    #[test]
+    fn fingerprints_normalized_passwords() -> Result<(), CryptoError> {
+        let fingerprinter = Fingerprinter::new()?;
+        assert_eq!(fingerprinter.password(" hunter2\n"), fingerprinter.password("hunter2"));
+        assert_ne!(fingerprinter.password("hunter2"), fingerprinter.password("Hunter2"));
+        assert_eq!(fingerprinter.password("  "), None);
+        // Sol losowana na audyt - odciski nie daja sie porownac miedzy audytami
+        assert_ne!(Fingerprinter::new()?.password("hunter2"), fingerprinter.password("hunter2"));
+        Ok(())
+    }
+
+    #[test]
+    fn reports_reused_weak_stale_and_missing_totp() -> Result<(), CryptoError> {
+        let now = 1000 * SECONDS_PER_DAY;
+        let totp = r#","totp":"otpauth://totp/x?secret=JBSWY3DPEHPK3PXP""#;
+        let items = [
+            login("a", &format!(r#"{{"uris":["https://a.example"],"password":"{STRONG}"{totp}}}"#), now)?,
+            login("b", &format!(r#"{{"uris":["https://b.example"],"password":" {STRONG} "{totp}}}"#), now)?,
+            login("c", r#"{"uris":["https://c.example"],"password":"password"}"#, now - 400 * SECONDS_PER_DAY)?,
+            login("d", r#"{"uris":["https://d.example"],"password":""}"#, 0)?,
+            VaultItem::create("note", ItemType::SecureNote, "note", 0)?,
+        ];
+        let report = audit_items(&items, now, 0)?;
+        assert_eq!(report.audited, 3);
+        assert_eq!(report.reused, vec![vec!["a".to_string(), "b".to_string()]]);
+        assert_eq!(report.weak.iter().map(|w| w.item.as_str()).collect::<Vec<_>>(), vec!["c"]);
+        assert_eq!(report.missing_totp, vec!["c".to_string()]);
+        assert_eq!(report.stale, vec![StalePassword { item: "c".to_string(), age_days: 400 }]);
+        assert!(report.duplicates.is_empty());
+
+        // Wlasny prog wieku
+        assert!(audit_items(&items, now, 500)?.stale.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn reports_duplicate_logins() -> Result<(), CryptoError> {
+        let data = format!(r#"{{"uris":["https://example.com/login"],"username":"Alice","password":"{STRONG}"}}"#);
+        let items = [login("old", &data, 100)?, login("new", &data.replace("Alice", " alice "), 200)?];
+        let report = audit_items(&items, 300, 0)?;
+        let expected = MergeProposal {
+            keep: crate::dedupe::ItemRef::Vault("new".to_string()),
+            duplicates: vec![crate::dedupe::ItemRef::Vault("old".to_string())],
+            add_uris: Vec::new(),
+        };
+        assert_eq!(report.duplicates, vec![expected]);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_items() {
+        assert!(matches!(audit_vault("{", 0, 0), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(audit_vault(r#"[{"id":"a"}]"#, 0, 0), Err(CryptoError::InvalidInput(_))));
+    }
+}
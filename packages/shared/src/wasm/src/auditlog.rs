@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::ct::ct_eq;
+use crate::encoding::{bytes_to_hex, hex_to_bytes};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::rng::random_array;
+use crate::secret::{EntryKey, MasterKey};
+use crate::sha2::sha256_bytes;
+
+// Dziennik zdarzen vaulta (odczyty, zmiany, eksporty) tylko do dopisywania. Kazdy wpis:
+//   "PMAL" || wersja (1) || numer (u64 BE) || SHA-256 poprzedniego wpisu || nonce || ct || tag
+// naglowek jako AAD, tresc {timestamp, event} szyfrowana kluczem dziennika. Serwer widzi
+// lancuch, ale nie tresc; bez klucza nie dopisze ani nie podmieni wpisu, a usuniecie albo
+// przestawienie wpisu w srodku zrywa lancuch. Obciecie konca wykrywa klient, ktory pamieta
+// ostatnia znana glowe (skrot ostatniego wpisu) - musi ona nadal byc w lancuchu.
+// Dziennik to sklejone wpisy z prefiksem dlugosci (u32 BE); pierwszy wpis ma poprzednika 0^32.
+const LOG_MAGIC: &[u8; 4] = b"PMAL";
+const LOG_VERSION: u8 = 1;
+const HASH_LEN: usize = 32;
+const HEADER_LEN: usize = 4 + 1 + 8 + HASH_LEN;
+const AUDIT_KEY_INFO: &[u8] = b"pm-audit-log";
+
+#[derive(Serialize, Deserialize)]
+struct EventBody {
+    timestamp: u64,
+    event: Value,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct AuditEvent {
+    pub seq: u64,
+    pub timestamp: u64,
+    pub event: Value,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct AuditChain {
+    pub head: String,
+    pub count: u64,
+    pub events: Vec<AuditEvent>,
+}
+
+// Osobny klucz dziennika wyprowadzany z klucza vaulta.
+pub fn derive_audit_key(vault_key: &MasterKey) -> Result<EntryKey, CryptoError> {
+    Ok(EntryKey::from_vec(hkdf_sha256_bytes(vault_key.as_bytes(), &[], AUDIT_KEY_INFO, 32)?))
+}
+
+fn cipher(log_key: &EntryKey) -> Result<AesGcm, CryptoError> {
+    if log_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("audit log key must be 32 bytes".to_string()));
+    }
+    AesGcm::new(log_key.as_bytes())
+}
+
+fn records(log: &[u8]) -> Result<Vec<&[u8]>, CryptoError> {
+    let mut out = Vec::new();
+    let mut rest = log;
+    while !rest.is_empty() {
+        if rest.len() < 4 {
+            return Err(CryptoError::CorruptHeader("audit log record length truncated".to_string()));
+        }
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        if rest.len() - 4 < len {
+            return Err(CryptoError::CorruptHeader("audit log record truncated".to_string()));
+        }
+        out.push(&rest[4..4 + len]);
+        rest = &rest[4 + len..];
+    }
+    Ok(out)
+}
+
+// Zwraca numer, poprzednika i zdarzenie z uwierzytelnionego wpisu.
+fn open_record(cipher: &AesGcm, record: &[u8]) -> Result<(u64, [u8; HASH_LEN], EventBody), CryptoError> {
+    if record.len() < HEADER_LEN + NONCE_LEN + TAG_LEN || &record[..4] != LOG_MAGIC {
+        return Err(CryptoError::CorruptHeader("not an audit log record".to_string()));
+    }
+    if record[4] != LOG_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("audit log version {}", record[4])));
+    }
+    let (header, rest) = record.split_at(HEADER_LEN);
+    let mut seq = [0u8; 8];
+    seq.copy_from_slice(&header[5..13]);
+    let mut prev = [0u8; HASH_LEN];
+    prev.copy_from_slice(&header[13..]);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let json = cipher.decrypt(nonce, header, sealed)?;
+    let body = serde_json::from_slice(&json)
+        .map_err(|e| CryptoError::CorruptHeader(format!("malformed audit log event: {e}")))?;
+    Ok((u64::from_be_bytes(seq), prev, body))
+}
+
+// Zwraca nowy wpis z prefiksem dlugosci - do doklejenia na koniec dziennika.
+pub fn append_event_bytes(
+    log_key: &EntryKey,
+    log: &[u8],
+    event: &Value,
+    timestamp: u64,
+) -> Result<Vec<u8>, CryptoError> {
+    if !event.is_object() {
+        return Err(CryptoError::InvalidInput("audit event must be a JSON object".to_string()));
+    }
+    let cipher = cipher(log_key)?;
+    let (seq, prev) = match records(log)?.last() {
+        Some(last) => {
+            let (seq, _, _) = open_record(&cipher, last)?;
+            (
+                seq.checked_add(1).ok_or_else(|| CryptoError::OutOfRange("audit log is full".to_string()))?,
+                sha256_bytes(last),
+            )
+        }
+        None => (0, [0u8; HASH_LEN]),
+    };
+    let mut record = LOG_MAGIC.to_vec();
+    record.push(LOG_VERSION);
+    record.extend_from_slice(&seq.to_be_bytes());
+    record.extend_from_slice(&prev);
+    let body = serde_json::to_vec(&EventBody { timestamp, event: event.clone() })
+        .map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+    let nonce = random_array::<NONCE_LEN>()?;
+    let sealed = cipher.encrypt(&nonce, &record, &body)?;
+    record.extend_from_slice(&nonce);
+    record.extend_from_slice(&sealed);
+    let mut out = (record.len() as u32).to_be_bytes().to_vec();
+    out.extend_from_slice(&record);
+    Ok(out)
+}
+
+// pinned_head - skrot ostatniego wpisu z poprzedniej weryfikacji; musi wystapic w lancuchu.
+pub fn verify_chain_bytes(
+    log_key: &EntryKey,
+    log: &[u8],
+    pinned_head: Option<&[u8]>,
+) -> Result<AuditChain, CryptoError> {
+    let cipher = cipher(log_key)?;
+    let mut prev_hash = [0u8; HASH_LEN];
+    let mut pinned_found = pinned_head.is_none();
+    let mut events = Vec::new();
+    for (index, record) in records(log)?.into_iter().enumerate() {
+        let broken = || CryptoError::VerificationFailed(format!("audit log chain broken at entry {index}"));
+        let (seq, prev, body) = open_record(&cipher, record).map_err(|_| broken())?;
+        if seq != index as u64 || !ct_eq(&prev, &prev_hash) {
+            return Err(broken());
+        }
+        prev_hash = sha256_bytes(record);
+        if let Some(pinned) = pinned_head {
+            pinned_found |= ct_eq(&prev_hash, pinned);
+        }
+        events.push(AuditEvent { seq, timestamp: body.timestamp, event: body.event });
+    }
+    if !pinned_found {
+        return Err(CryptoError::VerificationFailed("audit log truncated: pinned head not found".to_string()));
+    }
+    Ok(AuditChain { head: bytes_to_hex(&prev_hash), count: events.len() as u64, events })
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn audit_log_key(vault_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(derive_audit_key(&MasterKey::from_slice(vault_key))?.as_bytes().to_vec())
+}
+
+// event - obiekt JSON; wynik doklejany przez wywolujacego na koniec dziennika.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn append_event(log_key: &[u8], log: &[u8], event: &str, timestamp: u64) -> Result<Vec<u8>, CryptoError> {
+    let event: Value =
+        serde_json::from_str(event).map_err(|e| CryptoError::InvalidInput(format!("malformed audit event: {e}")))?;
+    append_event_bytes(&EntryKey::from_slice(log_key), log, &event, timestamp)
+}
+
+// Wynik: {"head": hex, "count": n, "events": [{seq, timestamp, event}...]}; head zapamietac
+// i podac przy nastepnej weryfikacji jako pinned_head.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_chain(log_key: &[u8], log: &[u8], pinned_head: Option<String>) -> Result<String, CryptoError> {
+    let pinned = pinned_head.as_deref().map(hex_to_bytes).transpose()?;
+    let chain = verify_chain_bytes(&EntryKey::from_slice(log_key), log, pinned.as_deref())?;
+    serde_json::to_string(&chain).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key() -> EntryKey {
+        EntryKey::from_slice(&[5u8; 32])
+    }
+
+    // Zwraca dziennik i granice kolejnych wpisow (z prefiksem dlugosci).
+    fn build_log(count: u64) -> Result<(Vec<u8>, Vec<usize>), CryptoError> {
+        let mut log = Vec::new();
+        let mut ends = Vec::new();
+        for i in 0..count {
+            let record = append_event_bytes(&key(), &log, &json!({"action": "read", "item": i}), 1000 + i)?;
+            log.extend_from_slice(&record);
+            ends.push(log.len());
+        }
+        Ok((log, ends))
+    }
+
+    #[test]
+    fn appends_and_verifies_chain() -> Result<(), CryptoError> {
+        let (log, _) = build_log(3)?;
+        let chain = verify_chain_bytes(&key(), &log, None)?;
+        assert_eq!(chain.count, 3);
+        let seqs: Vec<u64> = chain.events.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, vec![0, 1, 2]);
+        assert_eq!(chain.events.get(2).map(|e| e.event.clone()), Some(json!({"action": "read", "item": 2})));
+        assert_eq!(chain.events.get(1).map(|e| e.timestamp), Some(1001));
+
+        // Glowa zapamietana wczesniej nadal jest w dluzszym lancuchu
+        let pinned = hex_to_bytes(&chain.head)?;
+        let mut extended = log.clone();
+        extended.extend_from_slice(&append_event_bytes(&key(), &log, &json!({"action": "export"}), 2000)?);
+        assert_eq!(verify_chain_bytes(&key(), &extended, Some(&pinned))?.count, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn detects_truncation_against_pinned_head() -> Result<(), CryptoError> {
+        let (log, ends) = build_log(3)?;
+        let pinned = hex_to_bytes(&verify_chain_bytes(&key(), &log, None)?.head)?;
+        let cut = ends.get(1).copied().unwrap_or_default();
+        // Obciety dziennik jest sam w sobie spojny - wykrywa go dopiero przypieta glowa
+        assert_eq!(verify_chain_bytes(&key(), &log[..cut], None)?.count, 2);
+        let truncated = verify_chain_bytes(&key(), &log[..cut], Some(&pinned));
+        assert!(matches!(truncated, Err(CryptoError::VerificationFailed(_))));
+        // Wpis obciety w polowie
+        assert!(matches!(verify_chain_bytes(&key(), &log[..cut - 1], None), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn detects_removed_reordered_and_tampered_entries() -> Result<(), CryptoError> {
+        let (log, ends) = build_log(3)?;
+        let (first, second) = (ends.first().copied().unwrap_or_default(), ends.get(1).copied().unwrap_or_default());
+        let removed = [&log[..first], &log[second..]].concat();
+        assert!(matches!(verify_chain_bytes(&key(), &removed, None), Err(CryptoError::VerificationFailed(_))));
+        let reordered = [&log[first..second], &log[..first], &log[second..]].concat();
+        assert!(matches!(verify_chain_bytes(&key(), &reordered, None), Err(CryptoError::VerificationFailed(_))));
+        let mut tampered = log.clone();
+        if let Some(byte) = tampered.get_mut(second - 1) {
+            *byte ^= 1;
+        }
+        assert!(matches!(verify_chain_bytes(&key(), &tampered, None), Err(CryptoError::VerificationFailed(_))));
+        let other = EntryKey::from_slice(&[6u8; 32]);
+        assert!(matches!(verify_chain_bytes(&other, &log, None), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_events_and_keys() {
+        assert!(matches!(append_event(&[5u8; 32], &[], "[1]", 1), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(append_event(&[5u8; 32], &[], "{", 1), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(append_event(&[5u8; 16], &[], "{}", 1), Err(CryptoError::InvalidLength(_))));
+    }
+}
@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::ct::ct_eq;
+use crate::encoding::bytes_to_hex;
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::hmac::hmac_sha256_bytes;
+use crate::rng::random_array;
+use crate::secret::EntryKey;
+use crate::sha2::sha256_bytes;
+
+// Lokalne kopie zapasowe vaulta. Blob:
+//   "PMBK" || wersja (1) || dlugosc metadanych (u32 BE) || metadane JSON || nonce || ciphertext || tag
+// Naglowek (wszystko przed nonce) jest AAD, wiec metadanych nie da sie podmienic.
+// Manifest wymienia kolejne generacje z dlugoscia i SHA-256 blobu; jest chroniony HMAC,
+// wiec usuniecie wpisu albo podstawienie starszej kopii jako nowszej jest wykrywane.
+// Klucze do szyfrowania i do MAC manifestu wyprowadzane HKDF z jednego klucza backupu.
+const BACKUP_MAGIC: &[u8; 4] = b"PMBK";
+const BACKUP_VERSION: u8 = 1;
+const MANIFEST_VERSION: u32 = 1;
+const MANIFEST_LABEL: &[u8] = b"pm-backup-manifest-v1\0";
+const BLOB_KEY_INFO: &[u8] = b"pm-backup-blob";
+const MANIFEST_KEY_INFO: &[u8] = b"pm-backup-manifest";
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetentionHint {
+    // Ile najnowszych generacji zawsze zachowac (min. 1)
+    pub keep_last: u32,
+    // Starsze generacje usuwane po tylu dniach; 0 = bez limitu wieku
+    #[serde(default)]
+    pub max_age_days: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BackupMetadata {
+    pub vault_id: String,
+    pub generation: u64,
+    pub created_at: u64,
+    pub retention: RetentionHint,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub generation: u64,
+    pub created_at: u64,
+    pub size: u64,
+    pub digest: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ManifestBody {
+    pub version: u32,
+    pub vault_id: String,
+    pub retention: RetentionHint,
+    pub generations: Vec<ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BackupManifest {
+    #[serde(flatten)]
+    pub body: ManifestBody,
+    pub mac: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PruneResult {
+    pub manifest: BackupManifest,
+    pub removed: Vec<ManifestEntry>,
+}
+
+struct BackupKeys {
+    blob: EntryKey,
+    manifest: EntryKey,
+}
+
+fn derive_keys(backup_key: &EntryKey) -> Result<BackupKeys, CryptoError> {
+    if backup_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("backup key must be 32 bytes".to_string()));
+    }
+    Ok(BackupKeys {
+        blob: EntryKey::from_vec(hkdf_sha256_bytes(backup_key.as_bytes(), &[], BLOB_KEY_INFO, 32)?),
+        manifest: EntryKey::from_vec(hkdf_sha256_bytes(backup_key.as_bytes(), &[], MANIFEST_KEY_INFO, 32)?),
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, CryptoError> {
+    serde_json::to_string(value).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+fn manifest_mac(keys: &BackupKeys, body: &ManifestBody) -> Result<String, CryptoError> {
+    let mut msg = MANIFEST_LABEL.to_vec();
+    msg.extend_from_slice(to_json(body)?.as_bytes());
+    Ok(bytes_to_hex(&hmac_sha256_bytes(keys.manifest.as_bytes(), &msg)))
+}
+
+fn seal_manifest(keys: &BackupKeys, body: ManifestBody) -> Result<BackupManifest, CryptoError> {
+    let mac = manifest_mac(keys, &body)?;
+    Ok(BackupManifest { body, mac })
+}
+
+pub fn new_manifest(backup_key: &EntryKey, vault_id: &str, retention: RetentionHint) -> Result<BackupManifest, CryptoError> {
+    if retention.keep_last == 0 {
+        return Err(CryptoError::OutOfRange("keep_last must be at least 1".to_string()));
+    }
+    let keys = derive_keys(backup_key)?;
+    seal_manifest(
+        &keys,
+        ManifestBody { version: MANIFEST_VERSION, vault_id: vault_id.to_string(), retention, generations: Vec::new() },
+    )
+}
+
+fn verify_manifest(keys: &BackupKeys, manifest_json: &str) -> Result<BackupManifest, CryptoError> {
+    let manifest: BackupManifest = serde_json::from_str(manifest_json)
+        .map_err(|e| CryptoError::InvalidInput(format!("malformed backup manifest: {e}")))?;
+    if manifest.body.version != MANIFEST_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("manifest version {}", manifest.body.version)));
+    }
+    let expected = manifest_mac(keys, &manifest.body)?;
+    if !ct_eq(expected.as_bytes(), manifest.mac.as_bytes()) {
+        return Err(CryptoError::BadMac("backup manifest".to_string()));
+    }
+    Ok(manifest)
+}
+
+pub fn parse_manifest(backup_key: &EntryKey, manifest_json: &str) -> Result<BackupManifest, CryptoError> {
+    verify_manifest(&derive_keys(backup_key)?, manifest_json)
+}
+
+struct ParsedBlob<'a> {
+    metadata: BackupMetadata,
+    header: &'a [u8],
+    nonce: &'a [u8],
+    sealed: &'a [u8],
+}
+
+fn parse_blob(blob: &[u8]) -> Result<ParsedBlob<'_>, CryptoError> {
+    if blob.len() < 9 || &blob[..4] != BACKUP_MAGIC {
+        return Err(CryptoError::CorruptHeader("not a backup blob".to_string()));
+    }
+    if blob[4] != BACKUP_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("backup version {}", blob[4])));
+    }
+    let meta_len = u32::from_be_bytes([blob[5], blob[6], blob[7], blob[8]]) as usize;
+    // Bez dodawania do meta_len - na wasm32 suma moglaby sie przekrecic
+    if meta_len > blob.len().saturating_sub(9 + NONCE_LEN + TAG_LEN) {
+        return Err(CryptoError::CorruptHeader("backup blob truncated".to_string()));
+    }
+    let header_len = 9 + meta_len;
+    let (header, rest) = blob.split_at(header_len);
+    let metadata: BackupMetadata = serde_json::from_slice(&header[9..])
+        .map_err(|e| CryptoError::CorruptHeader(format!("malformed backup metadata: {e}")))?;
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    Ok(ParsedBlob { metadata, header, nonce, sealed })
+}
+
+// Szyfruje snapshot jako kolejna generacje i zwraca (blob, zaktualizowany manifest).
+pub fn create_backup_bytes(
+    backup_key: &EntryKey,
+    manifest_json: &str,
+    snapshot: &[u8],
+    created_at: u64,
+) -> Result<(Vec<u8>, BackupManifest), CryptoError> {
+    let keys = derive_keys(backup_key)?;
+    let mut manifest = verify_manifest(&keys, manifest_json)?;
+    let last = manifest.body.generations.iter().map(|g| g.generation).max().unwrap_or(0);
+    let generation =
+        last.checked_add(1).ok_or_else(|| CryptoError::OutOfRange("backup generation counter overflow".to_string()))?;
+    let metadata = BackupMetadata {
+        vault_id: manifest.body.vault_id.clone(),
+        generation,
+        created_at,
+        retention: manifest.body.retention,
+    };
+    let meta = to_json(&metadata)?;
+    let mut header = BACKUP_MAGIC.to_vec();
+    header.push(BACKUP_VERSION);
+    header.extend_from_slice(&(meta.len() as u32).to_be_bytes());
+    header.extend_from_slice(meta.as_bytes());
+
+    let nonce = random_array::<NONCE_LEN>()?;
+    let sealed = AesGcm::new(keys.blob.as_bytes())?.encrypt(&nonce, &header, snapshot)?;
+    let mut blob = header;
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&sealed);
+
+    manifest.body.generations.push(ManifestEntry {
+        generation: metadata.generation,
+        created_at,
+        size: blob.len() as u64,
+        digest: bytes_to_hex(&sha256_bytes(&blob)),
+    });
+    let manifest = seal_manifest(&keys, manifest.body)?;
+    Ok((blob, manifest))
+}
+
+// Sprawdza, ze blob jest wymieniony w manifescie (generacja, dlugosc, skrot) i ze
+// odszyfrowuje sie poprawnie. Zwraca metadane kopii.
+pub fn verify_backup_bytes(backup_key: &EntryKey, manifest_json: &str, blob: &[u8]) -> Result<BackupMetadata, CryptoError> {
+    let keys = derive_keys(backup_key)?;
+    let manifest = verify_manifest(&keys, manifest_json)?;
+    let parsed = parse_blob(blob)?;
+    if parsed.metadata.vault_id != manifest.body.vault_id {
+        return Err(CryptoError::VerificationFailed("backup belongs to a different vault".to_string()));
+    }
+    let entry = manifest
+        .body
+        .generations
+        .iter()
+        .find(|g| g.generation == parsed.metadata.generation)
+        .ok_or_else(|| {
+            CryptoError::VerificationFailed(format!("generation {} not in manifest", parsed.metadata.generation))
+        })?;
+    let digest = bytes_to_hex(&sha256_bytes(blob));
+    if entry.size != blob.len() as u64 || !ct_eq(digest.as_bytes(), entry.digest.as_bytes()) {
+        return Err(CryptoError::VerificationFailed(format!(
+            "generation {} does not match manifest digest",
+            entry.generation
+        )));
+    }
+    AesGcm::new(keys.blob.as_bytes())?.decrypt(parsed.nonce, parsed.header, parsed.sealed)?;
+    Ok(parsed.metadata)
+}
+
+pub fn open_backup_bytes(backup_key: &EntryKey, blob: &[u8]) -> Result<(BackupMetadata, Vec<u8>), CryptoError> {
+    let keys = derive_keys(backup_key)?;
+    let parsed = parse_blob(blob)?;
+    let snapshot = AesGcm::new(keys.blob.as_bytes())?.decrypt(parsed.nonce, parsed.header, parsed.sealed)?;
+    Ok((parsed.metadata, snapshot))
+}
+
+// Zachowuje keep_last najnowszych generacji; starsze usuwa, jesli przekroczyly max_age_days.
+// Zwraca nowy manifest i liste wpisow, ktorych pliki klient powinien skasowac.
+pub fn prune_backups_bytes(backup_key: &EntryKey, manifest_json: &str, now: u64) -> Result<PruneResult, CryptoError> {
+    let keys = derive_keys(backup_key)?;
+    let mut manifest = verify_manifest(&keys, manifest_json)?;
+    let retention = manifest.body.retention;
+    let mut generations = std::mem::take(&mut manifest.body.generations);
+    generations.sort_by_key(|g| std::cmp::Reverse(g.generation));
+
+    let max_age = retention.max_age_days as u64 * SECONDS_PER_DAY;
+    let (mut kept, mut removed) = (Vec::new(), Vec::new());
+    for (i, entry) in generations.into_iter().enumerate() {
+        let expired = max_age != 0 && now.saturating_sub(entry.created_at) > max_age;
+        if i < retention.keep_last as usize || !expired {
+            kept.push(entry);
+        } else {
+            removed.push(entry);
+        }
+    }
+    kept.reverse();
+    removed.reverse();
+    manifest.body.generations = kept;
+    Ok(PruneResult { manifest: seal_manifest(&keys, manifest.body)?, removed })
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct CreatedBackup {
+    blob: Vec<u8>,
+    manifest: String,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl CreatedBackup {
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn blob(&self) -> Vec<u8> {
+        self.blob.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn manifest(&self) -> String {
+        self.manifest.clone()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn create_backup_manifest(
+    backup_key: &[u8],
+    vault_id: &str,
+    keep_last: u32,
+    max_age_days: u32,
+) -> Result<String, CryptoError> {
+    let retention = RetentionHint { keep_last, max_age_days };
+    to_json(&new_manifest(&EntryKey::from_slice(backup_key), vault_id, retention)?)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn create_backup(
+    backup_key: &[u8],
+    manifest: &str,
+    snapshot: &[u8],
+    created_at: u64,
+) -> Result<CreatedBackup, CryptoError> {
+    let (blob, manifest) = create_backup_bytes(&EntryKey::from_slice(backup_key), manifest, snapshot, created_at)?;
+    Ok(CreatedBackup { blob, manifest: to_json(&manifest)? })
+}
+
+// Zwraca metadane zweryfikowanej kopii jako JSON.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_backup(backup_key: &[u8], manifest: &str, blob: &[u8]) -> Result<String, CryptoError> {
+    to_json(&verify_backup_bytes(&EntryKey::from_slice(backup_key), manifest, blob)?)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn open_backup(backup_key: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(open_backup_bytes(&EntryKey::from_slice(backup_key), blob)?.1)
+}
+
+// Zwraca JSON {"manifest": ..., "removed": [...]}.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn prune_backups(backup_key: &[u8], manifest: &str, now: u64) -> Result<String, CryptoError> {
+    to_json(&prune_backups_bytes(&EntryKey::from_slice(backup_key), manifest, now)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [0x42; 32];
+    const DAY: u64 = SECONDS_PER_DAY;
+
+    fn manifest(keep_last: u32, max_age_days: u32) -> Result<String, CryptoError> {
+        create_backup_manifest(&KEY, "vault-1", keep_last, max_age_days)
+    }
+
+    #[test]
+    fn backups_chain_through_the_manifest() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let (first, m1) = create_backup_bytes(&key, &manifest(1, 30)?, b"snapshot one", DAY)?;
+        let (second, m2) = create_backup_bytes(&key, &to_json(&m1)?, b"snapshot two", 2 * DAY)?;
+        let m2 = to_json(&m2)?;
+        assert_eq!(verify_backup_bytes(&key, &m2, &first)?.generation, 1);
+        assert_eq!(verify_backup_bytes(&key, &m2, &second)?.generation, 2);
+        assert_eq!(open_backup_bytes(&key, &second)?.1, b"snapshot two");
+
+        let pruned = prune_backups_bytes(&key, &m2, 40 * DAY)?;
+        assert_eq!(pruned.removed.iter().map(|g| g.generation).collect::<Vec<_>>(), [1]);
+        assert_eq!(pruned.manifest.body.generations.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn tampering_is_detected() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let (mut blob, m1) = create_backup_bytes(&key, &manifest(3, 0)?, b"snapshot", DAY)?;
+        let mut forged = m1.clone();
+        forged.body.generations.clear();
+        assert!(matches!(verify_backup_bytes(&key, &to_json(&forged)?, &blob), Err(CryptoError::BadMac(_))));
+        if let Some(last) = blob.last_mut() {
+            *last ^= 1;
+        }
+        assert!(matches!(verify_backup_bytes(&key, &to_json(&m1)?, &blob), Err(CryptoError::VerificationFailed(_))));
+        assert!(open_backup_bytes(&EntryKey::from_slice(&[0x43; 32]), &blob).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn generation_counter_overflow_is_an_error() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let keys = derive_keys(&key)?;
+        let mut body = parse_manifest(&key, &manifest(1, 0)?)?.body;
+        body.generations.push(ManifestEntry { generation: u64::MAX, created_at: 0, size: 0, digest: String::new() });
+        let full = to_json(&seal_manifest(&keys, body)?)?;
+        assert!(matches!(create_backup_bytes(&key, &full, b"snapshot", DAY), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_and_oversized_headers_are_rejected() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let (blob, _) = create_backup_bytes(&key, &manifest(1, 0)?, b"snapshot", DAY)?;
+        let corrupt = |r: Result<_, CryptoError>| matches!(r, Err(CryptoError::CorruptHeader(_)));
+        for len in [0, 8, 9, 20, blob.len() - TAG_LEN - NONCE_LEN - 1] {
+            assert!(corrupt(open_backup_bytes(&key, &blob[..len])), "{len}");
+        }
+        // Obciety ciphertext - naglowek caly, tag nie pasuje
+        assert!(matches!(open_backup_bytes(&key, &blob[..blob.len() - 1]), Err(CryptoError::DecryptFailed(_))));
+        for meta_len in [u32::MAX, u32::MAX - 8, blob.len() as u32] {
+            let mut hostile = blob.clone();
+            hostile[5..9].copy_from_slice(&meta_len.to_be_bytes());
+            assert!(corrupt(open_backup_bytes(&key, &hostile)), "{meta_len}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_metadata_and_stale_manifest_are_rejected() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let (first, m1) = create_backup_bytes(&key, &manifest(3, 0)?, b"snapshot one", DAY)?;
+        let (second, m2) = create_backup_bytes(&key, &to_json(&m1)?, b"snapshot two", 2 * DAY)?;
+        let (m1, m2) = (to_json(&m1)?, to_json(&m2)?);
+
+        // Starsza kopia podana jako generacja 2: metadane sa w AAD, skrot nie pasuje
+        let from = br#""generation":1"#;
+        let at = first
+            .windows(from.len())
+            .position(|w| w == from)
+            .ok_or_else(|| CryptoError::InvalidInput("generation not in metadata".to_string()))?;
+        let mut relabeled = first.clone();
+        relabeled[at + from.len() - 1] = b'2';
+        assert!(matches!(verify_backup_bytes(&key, &m2, &relabeled), Err(CryptoError::VerificationFailed(_))));
+        assert!(matches!(open_backup_bytes(&key, &relabeled), Err(CryptoError::DecryptFailed(_))));
+
+        // Nieaktualny manifest nie zna nowszej generacji
+        assert!(matches!(verify_backup_bytes(&key, &m1, &second), Err(CryptoError::VerificationFailed(_))));
+        assert_eq!(verify_backup_bytes(&key, &m1, &first)?.generation, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+// Narzedzie CLI nad tym samym rdzeniem co modul wasm (do skryptow i dla zaawansowanych).
+// Dane wejsciowe z stdin, wynik na stdout. Budowanie: cargo build --features cli --bin pm-crypto
+//
+//   pm-crypto hash <sha256|sha512> [--encoding hex|base64|base64url|raw]
+//   pm-crypto kdf <pbkdf2-sha256|pbkdf2-sha512> --salt S --iterations N [--length L] [--encoding ...]
+//   pm-crypto encrypt [--key-file F | --key-env V] [--segment-size N]   (format strumieniowy stream.rs)
+//   pm-crypto decrypt [--key-file F | --key-env V]
+//   pm-crypto import <csv:ZRODLO|bitwarden|kdbx|1pux|encrypted> [--password-file F | --password-env V]
+//   pm-crypto export <kdbx|encrypted> [--password-file F | --password-env V]
+//
+// Klucze i hasla nigdy z argv (widoczne w ps i historii powloki): z pliku albo zmiennej
+// srodowiskowej, domyslnie PM_CRYPTO_KEY / PM_CRYPTO_PASSWORD. Klucz to 64 cyfry hex.
+// Import zwraca JSON {"items": [...]}, eksport czyta taki JSON ze stdin.
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use wasm_crypto::CryptoError;
+use wasm_crypto::calibrate::KdfParams;
+use wasm_crypto::digest::{HashAlgorithm, digest_reader};
+use wasm_crypto::encoding::{bytes_to_base64, bytes_to_base64url, bytes_to_hex, hex_to_bytes};
+use wasm_crypto::import::bitwarden::import_bitwarden_bytes;
+use wasm_crypto::import::csv::import_csv;
+use wasm_crypto::import::encrypted::{export_encrypted_raw, import_encrypted_raw};
+use wasm_crypto::import::kdbx::{export_kdbx_raw, import_kdbx_raw};
+use wasm_crypto::import::onepux::import_1pux;
+use wasm_crypto::import::to_json;
+use wasm_crypto::pbkdf2::{pbkdf2_hmac_sha256_bytes, pbkdf2_hmac_sha512_bytes};
+use wasm_crypto::stream::{AttachmentDecryptor, AttachmentEncryptor};
+use zeroize::{Zeroize, Zeroizing};
+
+const USAGE: &str = "usage: pm-crypto <hash|kdf|encrypt|decrypt|import|export> [options]";
+const KEY_ENV: &str = "PM_CRYPTO_KEY";
+const PASSWORD_ENV: &str = "PM_CRYPTO_PASSWORD";
+const CHUNK_LEN: usize = 64 * 1024;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Kod wyjscia = numer ErrorCode, zeby skrypty nie musialy parsowac komunikatu
+        Err(e) => {
+            eprintln!("pm-crypto: [{}] {e}", e.code().as_str());
+            ExitCode::from(e.code() as u8)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), CryptoError> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(CryptoError::InvalidInput(USAGE.to_string()));
+    };
+    match command.as_str() {
+        "hash" => hash(rest),
+        "kdf" => kdf(rest),
+        "encrypt" => encrypt(rest),
+        "decrypt" => decrypt(rest),
+        "import" => import(rest),
+        "export" => export(rest),
+        _ => Err(CryptoError::InvalidInput(USAGE.to_string())),
+    }
+}
+
+// --nazwa wartosc
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+fn positional(args: &[String]) -> Result<&str, CryptoError> {
+    args.first()
+        .filter(|a| !a.starts_with("--"))
+        .map(String::as_str)
+        .ok_or_else(|| CryptoError::InvalidInput(USAGE.to_string()))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, CryptoError> {
+    value
+        .parse()
+        .map_err(|_| CryptoError::InvalidParameter(format!("{what} must be a number")))
+}
+
+fn io_error(e: io::Error) -> CryptoError {
+    CryptoError::InvalidInput(format!("i/o error: {e}"))
+}
+
+fn read_stdin() -> Result<Vec<u8>, CryptoError> {
+    let mut data = Vec::new();
+    io::stdin().lock().read_to_end(&mut data).map_err(io_error)?;
+    Ok(data)
+}
+
+fn write_stdout(data: &[u8]) -> Result<(), CryptoError> {
+    let mut out = io::stdout().lock();
+    out.write_all(data).and_then(|_| out.flush()).map_err(io_error)
+}
+
+fn write_encoded(data: &[u8], args: &[String]) -> Result<(), CryptoError> {
+    let text = match option(args, "--encoding").unwrap_or("hex") {
+        "hex" => bytes_to_hex(data),
+        "base64" => bytes_to_base64(data),
+        "base64url" => bytes_to_base64url(data),
+        "raw" => return write_stdout(data),
+        other => return Err(CryptoError::InvalidParameter(format!("unknown encoding: {other}"))),
+    };
+    write_stdout(format!("{text}\n").as_bytes())
+}
+
+// Strumieniowo, bez wczytywania calego wejscia do pamieci.
+fn hash(args: &[String]) -> Result<(), CryptoError> {
+    let algorithm = HashAlgorithm::from_name(positional(args)?)?;
+    let digest = digest_reader(algorithm, &mut io::stdin().lock())?;
+    write_encoded(&digest, args)
+}
+
+fn kdf(args: &[String]) -> Result<(), CryptoError> {
+    let f = match positional(args)? {
+        "pbkdf2-sha256" => pbkdf2_hmac_sha256_bytes,
+        "pbkdf2-sha512" => pbkdf2_hmac_sha512_bytes,
+        other => return Err(CryptoError::UnsupportedAlgorithm(other.to_string())),
+    };
+    let salt = option(args, "--salt").ok_or_else(|| CryptoError::InvalidParameter("--salt is required".to_string()))?;
+    let iterations = option(args, "--iterations")
+        .ok_or_else(|| CryptoError::InvalidParameter("--iterations is required".to_string()))
+        .and_then(|v| parse_number(v, "--iterations"))?;
+    let length = option(args, "--length").map_or(Ok(32), |v| parse_number(v, "--length"))?;
+
+    // Haslo z stdin; koncowy znak nowej linii (z echo/heredoc) nie jest czescia hasla
+    let mut password = read_stdin()?;
+    trim_newline(&mut password);
+    let dk = f(&password, salt.as_bytes(), iterations, length);
+    password.zeroize();
+    let mut dk = dk?;
+    let result = write_encoded(&dk, args);
+    dk.zeroize();
+    result
+}
+
+fn trim_newline(data: &mut Vec<u8>) {
+    while data.last().is_some_and(|&b| b == b'\n' || b == b'\r') {
+        data.pop();
+    }
+}
+
+// Sekret z pliku (--*-file) albo zmiennej srodowiskowej (--*-env, domyslnie default_env).
+// Koncowy znak nowej linii nie jest czescia sekretu.
+fn read_secret(args: &[String], name: &str, default_env: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    if option(args, &format!("--{name}")).is_some() {
+        return Err(CryptoError::InvalidParameter(format!(
+            "--{name} on the command line is not accepted; use --{name}-file or --{name}-env"
+        )));
+    }
+    let mut secret = Zeroizing::new(match option(args, &format!("--{name}-file")) {
+        Some(path) => std::fs::read(path).map_err(io_error)?,
+        None => {
+            let var = option(args, &format!("--{name}-env")).unwrap_or(default_env);
+            std::env::var_os(var)
+                .ok_or_else(|| CryptoError::InvalidParameter(format!("{name} not given: set {var} or --{name}-file")))?
+                .into_encoded_bytes()
+        }
+    });
+    trim_newline(&mut secret);
+    Ok(secret)
+}
+
+fn parse_key(text: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let text = std::str::from_utf8(text).map_err(|_| CryptoError::InvalidInput("key must be hex".to_string()))?;
+    let key = Zeroizing::new(hex_to_bytes(text.trim())?);
+    if key.len() != 32 {
+        return Err(CryptoError::InvalidLength("key must be 32 bytes (64 hex digits)".to_string()));
+    }
+    Ok(key)
+}
+
+// Kawalkami po CHUNK_LEN - ani wejscie, ani wynik nie sa trzymane w pamieci w calosci.
+fn pipe_chunks(
+    mut push: impl FnMut(&[u8]) -> Result<Vec<u8>, CryptoError>,
+    finish: impl FnOnce() -> Result<Vec<u8>, CryptoError>,
+) -> Result<(), CryptoError> {
+    let mut input = io::stdin().lock();
+    let mut out = io::stdout().lock();
+    let mut buf = Zeroizing::new(vec![0u8; CHUNK_LEN]);
+    loop {
+        let n = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(io_error(e)),
+        };
+        let chunk = Zeroizing::new(push(&buf[..n])?);
+        out.write_all(&chunk).map_err(io_error)?;
+    }
+    let last = Zeroizing::new(finish()?);
+    out.write_all(&last).and_then(|_| out.flush()).map_err(io_error)
+}
+
+fn encrypt(args: &[String]) -> Result<(), CryptoError> {
+    let key = parse_key(&read_secret(args, "key", KEY_ENV)?)?;
+    let segment_size = option(args, "--segment-size").map_or(Ok(0), |v| parse_number(v, "--segment-size"))?;
+    let encryptor = std::cell::RefCell::new(AttachmentEncryptor::new(&key, segment_size)?);
+    pipe_chunks(|data| encryptor.borrow_mut().push(data), || encryptor.borrow_mut().finish())
+}
+
+// Odszyfrowane segmenty ida na stdout od razu; obciety albo zmieniony plik konczy sie bledem
+// (i niezerowym kodem wyjscia) dopiero przy ostatnim segmencie.
+fn decrypt(args: &[String]) -> Result<(), CryptoError> {
+    let key = parse_key(&read_secret(args, "key", KEY_ENV)?)?;
+    let decryptor = std::cell::RefCell::new(AttachmentDecryptor::new(&key)?);
+    pipe_chunks(|data| decryptor.borrow_mut().push(data), || decryptor.borrow_mut().finish())
+}
+
+fn utf8_input(data: &[u8]) -> Result<&str, CryptoError> {
+    std::str::from_utf8(data).map_err(|_| CryptoError::InvalidInput("input is not UTF-8".to_string()))
+}
+
+fn import(args: &[String]) -> Result<(), CryptoError> {
+    let format = positional(args)?;
+    if !matches!(format, "bitwarden" | "kdbx" | "1pux" | "encrypted") && !format.starts_with("csv:") {
+        return Err(CryptoError::UnsupportedAlgorithm(format!("import format: {format}")));
+    }
+    let data = Zeroizing::new(read_stdin()?);
+    let json = Zeroizing::new(match format {
+        "bitwarden" => {
+            // Haslo tylko dla eksportu chronionego; zwykly eksport go nie potrzebuje
+            let password = read_secret(args, "password", PASSWORD_ENV).unwrap_or_default();
+            to_json(import_bitwarden_bytes(&data, &password)?)?
+        }
+        "kdbx" => import_kdbx_raw(&data, &read_secret(args, "password", PASSWORD_ENV)?)?,
+        "1pux" => import_1pux(&data)?,
+        "encrypted" => import_encrypted_raw(utf8_input(&data)?, &read_secret(args, "password", PASSWORD_ENV)?)?,
+        csv => import_csv(csv.trim_start_matches("csv:"), utf8_input(&data)?)?,
+    });
+    write_stdout(format!("{}\n", json.as_str()).as_bytes())
+}
+
+fn export(args: &[String]) -> Result<(), CryptoError> {
+    let format = positional(args)?;
+    if !matches!(format, "kdbx" | "encrypted") {
+        return Err(CryptoError::UnsupportedAlgorithm(format!("export format: {format}")));
+    }
+    let password = read_secret(args, "password", PASSWORD_ENV)?;
+    let items = Zeroizing::new(read_stdin()?);
+    let items = utf8_input(&items)?;
+    if format == "kdbx" {
+        write_stdout(&export_kdbx_raw(items, &password, &KdfParams::default())?)
+    } else {
+        write_stdout(format!("{}\n", export_encrypted_raw(items, &password)?).as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn keys_on_argv_are_rejected() {
+        let result = read_secret(&args(&["--key", "00"]), "key", KEY_ENV);
+        assert!(matches!(result, Err(CryptoError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn key_file_is_read_and_checked() -> Result<(), CryptoError> {
+        let path = std::env::temp_dir().join(format!("pm-crypto-key-{}", std::process::id()));
+        std::fs::write(&path, format!("{}\n", "ab".repeat(32))).map_err(io_error)?;
+        let secret = read_secret(&args(&["--key-file", &path.to_string_lossy()]), "key", KEY_ENV);
+        std::fs::remove_file(&path).map_err(io_error)?;
+        assert_eq!(parse_key(&secret?)?.as_slice(), [0xab; 32]);
+        assert!(matches!(parse_key(b"abcd"), Err(CryptoError::InvalidLength(_))));
+        assert!(parse_key(b"zz").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_secret_names_the_variable() {
+        let result = read_secret(&args(&["--key-env", "PM_CRYPTO_TEST_UNSET_VARIABLE"]), "key", KEY_ENV);
+        assert!(matches!(result, Err(CryptoError::InvalidParameter(m)) if m.contains("PM_CRYPTO_TEST_UNSET_VARIABLE")));
+    }
+
+    #[test]
+    fn unknown_formats_are_rejected() {
+        assert!(matches!(run(&args(&["import", "xml"])), Err(CryptoError::UnsupportedAlgorithm(_))));
+        assert!(matches!(run(&args(&["export", "csv:chrome"])), Err(CryptoError::UnsupportedAlgorithm(_))));
+        assert!(matches!(run(&args(&["frobnicate"])), Err(CryptoError::InvalidInput(_))));
+    }
+}
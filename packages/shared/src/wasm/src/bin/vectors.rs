@@ -0,0 +1,654 @@
+// Generuje wektory testowe (JSON) dla implementacji w Swift/Kotlin/TS.
+// Uzycie: cargo run --bin vectors > vectors.json
+
+// Mapa wektorow w jednym json! przekracza domyslny limit rekurencji makra.
+#![recursion_limit = "256"]
+
+use serde_json::{Value, json};
+use wasm_crypto::{AeadAlgorithm, CryptoError};
+use wasm_crypto::aes::Aes;
+use wasm_crypto::argon2::{Argon2Params, Argon2Variant, Argon2Version, argon2_hash_bytes, encode_argon2_phc};
+use wasm_crypto::bcrypt::{bcrypt_bytes, encode_bcrypt};
+use wasm_crypto::blake2b::blake2b_keyed_bytes;
+use wasm_crypto::blake3::{blake3_bytes, blake3_derive_key_bytes, blake3_keyed_bytes};
+use wasm_crypto::chacha20::{ChaCha20Poly1305, XChaCha20Poly1305};
+use wasm_crypto::crc::{crc32_bytes, crc32c_bytes};
+use wasm_crypto::ed25519::{public_key_bytes, sign_bytes};
+use wasm_crypto::encoding::{bytes_to_base32, bytes_to_base64, bytes_to_base64url, bytes_to_hex};
+use wasm_crypto::gcm::{AesGcm, Sm4Gcm};
+use wasm_crypto::hkdf::{hkdf_sha256_bytes, hkdf_sha512_bytes};
+use wasm_crypto::hmac::{
+    hmac_sha1_bytes, hmac_sha3_256_bytes, hmac_sha3_512_bytes, hmac_sha256_bytes, hmac_sha512_bytes,
+};
+use wasm_crypto::ids::{deterministic_id_bytes, derive_namespace_key, format_uuid};
+use wasm_crypto::keywrap::aes_kw_wrap_bytes;
+use wasm_crypto::migrate::{CiphertextFormat, seal_envelope_with_nonce};
+use wasm_crypto::otp::{OtpAlgorithm, hotp_bytes, totp_bytes};
+use wasm_crypto::pbkdf2::{
+    Pbkdf2Layout, pbkdf2_hmac_sha256_bytes, pbkdf2_hmac_sha256_layout_bytes, pbkdf2_hmac_sha512_bytes,
+    pbkdf2_hmac_sha512_layout_bytes,
+};
+use wasm_crypto::scrypt::{ScryptParams, encode_scrypt_mcf, encode_scrypt_phc, scrypt_bytes};
+use wasm_crypto::secret::{EntryKey, MasterKey};
+use wasm_crypto::sha1::sha1_bytes;
+use wasm_crypto::sha2::{sha256_bytes, sha384_bytes, sha512_256_bytes, sha512_bytes};
+use wasm_crypto::sha3::{sha3_256_bytes, sha3_512_bytes};
+use wasm_crypto::siphash::siphash24_bytes;
+use wasm_crypto::sm3::sm3_bytes;
+use wasm_crypto::vault::{VaultKdf, vault_open_bytes, vault_seal_with_nonce};
+use wasm_crypto::x25519::{public_key_bytes as x25519_public_key, x25519_bytes};
+
+const MESSAGES: &[&str] = &[
+    "",
+    "abc",
+    "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+    "The quick brown fox jumps over the lazy dog",
+    "zażółć gęślą jaźń",
+];
+
+const HMAC_KEYS: &[&str] = &[
+    "",
+    "key",
+    "Jefe",
+    "a key that is definitely longer than the one hundred and twenty eight byte block size of sha512 so that it gets hashed down first",
+];
+
+const PBKDF2_CASES: &[(&str, &str, u32, usize)] = &[
+    ("password", "salt", 1, 32),
+    ("password", "salt", 2, 32),
+    ("password", "salt", 4096, 32),
+    ("passwordPASSWORDpassword", "saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 64),
+    ("master password", "user_salt", 1000, 32),
+    ("password", "salt", 2, 40),
+    ("password", "salt", 2, 100),
+];
+
+fn main() {
+    #[allow(unused_mut)]
+    let mut out = json!({
+        "generator": "wasm-crypto",
+        "version": env!("CARGO_PKG_VERSION"),
+        "encoding": {
+            "input": "utf-8",
+            "output": "lowercase hex",
+        },
+        "vectors": {
+            "sha1": digest_vectors(|m| sha1_bytes(m).to_vec()),
+            "sha256": digest_vectors(|m| sha256_bytes(m).to_vec()),
+            "sha384": digest_vectors(|m| sha384_bytes(m).to_vec()),
+            "sha512": digest_vectors(|m| sha512_bytes(m).to_vec()),
+            "sha512_256": digest_vectors(|m| sha512_256_bytes(m).to_vec()),
+            "hmac_sha1": hmac_vectors(|k, m| hmac_sha1_bytes(k, m).to_vec()),
+            "hmac_sha256": hmac_vectors(|k, m| hmac_sha256_bytes(k, m).to_vec()),
+            "hmac_sha512": hmac_vectors(|k, m| hmac_sha512_bytes(k, m).to_vec()),
+            "sha3_256": digest_vectors(|m| sha3_256_bytes(m).to_vec()),
+            "sha3_512": digest_vectors(|m| sha3_512_bytes(m).to_vec()),
+            "hmac_sha3_256": hmac_vectors(|k, m| hmac_sha3_256_bytes(k, m).to_vec()),
+            "hmac_sha3_512": hmac_vectors(|k, m| hmac_sha3_512_bytes(k, m).to_vec()),
+            "sm3": digest_vectors(|m| sm3_bytes(m).to_vec()),
+            "blake2b": blake2b_vectors(),
+            "blake3": digest_vectors(|m| blake3_bytes(m).to_vec()),
+            "blake3_keyed": blake3_keyed_vectors(),
+            "blake3_derive_key": blake3_derive_key_vectors(),
+            "siphash24": siphash_vectors(),
+            // Jak eksporty pbkdf2_hmac_sha256/512 - uklad blokow V0
+            "pbkdf2_hmac_sha256": pbkdf2_vectors(|p, s, c, n| {
+                pbkdf2_hmac_sha256_layout_bytes(p, s, c, n, Pbkdf2Layout::V0)
+            }),
+            "pbkdf2_hmac_sha512": pbkdf2_vectors(|p, s, c, n| {
+                pbkdf2_hmac_sha512_layout_bytes(p, s, c, n, Pbkdf2Layout::V0)
+            }),
+            "pbkdf2_hmac_sha256_rfc8018": pbkdf2_vectors(pbkdf2_hmac_sha256_bytes),
+            "pbkdf2_hmac_sha512_rfc8018": pbkdf2_vectors(pbkdf2_hmac_sha512_bytes),
+            "crc32": digest_vectors(|m| crc32_bytes(m).to_be_bytes().to_vec()),
+            "crc32c": digest_vectors(|m| crc32c_bytes(m).to_be_bytes().to_vec()),
+            "deterministic_id": deterministic_id_vectors(),
+            "aes_block": aes_block_vectors(),
+            "ed25519": ed25519_vectors(),
+            "aes_gcm": aes_gcm_vectors(),
+            "x25519": x25519_vectors(),
+            "hkdf_sha256": hkdf_vectors(hkdf_sha256_bytes),
+            "hkdf_sha512": hkdf_vectors(hkdf_sha512_bytes),
+            "aes_kw": aes_kw_vectors(),
+            "sm4_gcm": sm4_gcm_vectors(),
+            "chacha20_poly1305": chacha20_poly1305_vectors(),
+            "xchacha20_poly1305": xchacha20_poly1305_vectors(),
+            "ciphertext_formats": ciphertext_format_vectors(),
+            "vault_envelope": vault_envelope_vectors(),
+            "argon2": argon2_vectors(),
+            "scrypt": scrypt_vectors(),
+            "bcrypt": bcrypt_vectors(),
+            "hotp": hotp_vectors(),
+            "totp": totp_vectors(),
+            "base32": encoding_vectors(|d| bytes_to_base32(d, true)),
+            "base32_unpadded": encoding_vectors(|d| bytes_to_base32(d, false)),
+            "base64": encoding_vectors(bytes_to_base64),
+            "base64url": encoding_vectors(bytes_to_base64url),
+        },
+    });
+
+    // Tylko w kompilacji z --features streebog
+    #[cfg(feature = "streebog")]
+    {
+        use wasm_crypto::hmac::{hmac_streebog256_bytes, hmac_streebog512_bytes};
+        use wasm_crypto::streebog::{streebog256_bytes, streebog512_bytes};
+        let vectors = &mut out["vectors"];
+        vectors["streebog256"] = digest_vectors(|m| streebog256_bytes(m).to_vec());
+        vectors["streebog512"] = digest_vectors(|m| streebog512_bytes(m).to_vec());
+        vectors["hmac_streebog256"] = hmac_vectors(|k, m| hmac_streebog256_bytes(k, m).to_vec());
+        vectors["hmac_streebog512"] = hmac_vectors(|k, m| hmac_streebog512_bytes(k, m).to_vec());
+    }
+
+    println!("{}", serde_json::to_string_pretty(&out).expect("serializable vectors"));
+}
+
+fn long_message() -> String {
+    "a".repeat(1000)
+}
+
+fn digest_vectors(f: fn(&[u8]) -> Vec<u8>) -> Value {
+    let mut cases: Vec<Value> = MESSAGES
+        .iter()
+        .map(|m| json!({ "input": m, "expected": bytes_to_hex(&f(m.as_bytes())) }))
+        .collect();
+    let long = long_message();
+    cases.push(json!({ "input": long, "expected": bytes_to_hex(&f(long.as_bytes())) }));
+    Value::Array(cases)
+}
+
+fn hmac_vectors(f: fn(&[u8], &[u8]) -> Vec<u8>) -> Value {
+    let mut cases = Vec::new();
+    for key in HMAC_KEYS {
+        for m in MESSAGES {
+            let mac = f(key.as_bytes(), m.as_bytes());
+            cases.push(json!({ "key": key, "input": m, "expected": bytes_to_hex(&mac) }));
+        }
+    }
+    Value::Array(cases)
+}
+
+type Pbkdf2Fn = fn(&[u8], &[u8], u32, usize) -> Result<Vec<u8>, CryptoError>;
+
+fn pbkdf2_vectors(f: Pbkdf2Fn) -> Value {
+    let cases = PBKDF2_CASES
+        .iter()
+        .map(|&(password, salt, iterations, dk_len)| {
+            let dk = f(password.as_bytes(), salt.as_bytes(), iterations, dk_len)
+                .expect("valid pbkdf2 parameters");
+            json!({
+                "password": password,
+                "salt": salt,
+                "iterations": iterations,
+                "dk_len": dk_len,
+                "expected": bytes_to_hex(&dk),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn deterministic_id_vectors() -> Value {
+    let root_key = MasterKey::from_slice(&[0x0bu8; 32]);
+    let cases: &[(&str, &[&str])] = &[
+        ("login", &["https://example.com", "alice"]),
+        ("login", &["https://example.com/", "alice"]),
+        ("login", &["ab", "c"]),
+        ("login", &["a", "bc"]),
+        ("attachment", &["https://example.com", "alice"]),
+    ];
+    let cases = cases
+        .iter()
+        .map(|&(namespace, parts)| {
+            let ns_key = derive_namespace_key(&root_key, namespace);
+            let bytes: Vec<&[u8]> = parts.iter().map(|p| p.as_bytes()).collect();
+            let id = deterministic_id_bytes(&ns_key, &bytes).expect("valid namespace key");
+            json!({
+                "root_key": bytes_to_hex(root_key.as_bytes()),
+                "namespace": namespace,
+                "namespace_key": bytes_to_hex(ns_key.as_bytes()),
+                "parts": parts,
+                "expected": format_uuid(&id),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn aes_block_vectors() -> Value {
+    let plaintext: [u8; 16] = core::array::from_fn(|i| (i as u8) * 0x11);
+    let cases = [16usize, 24, 32]
+        .iter()
+        .map(|&key_len| {
+            let key: Vec<u8> = (0..key_len as u8).collect();
+            let aes = Aes::new(&key).expect("valid AES key length");
+            let mut block = plaintext;
+            aes.encrypt_block(&mut block);
+            json!({
+                "key": bytes_to_hex(&key),
+                "plaintext": bytes_to_hex(&plaintext),
+                "expected": bytes_to_hex(&block),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn ed25519_vectors() -> Value {
+    let cases = MESSAGES
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let secret: [u8; 32] = core::array::from_fn(|j| (i * 32 + j) as u8);
+            json!({
+                "secret_key": bytes_to_hex(&secret),
+                "public_key": bytes_to_hex(&public_key_bytes(&secret)),
+                "message": m,
+                "signature": bytes_to_hex(&sign_bytes(&secret, m.as_bytes())),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn aes_gcm_vectors() -> Value {
+    let cases = [16usize, 32]
+        .iter()
+        .flat_map(|&key_len| MESSAGES.iter().map(move |m| (key_len, m)))
+        .map(|(key_len, m)| {
+            let key: Vec<u8> = (0..key_len as u8).collect();
+            let nonce: [u8; 12] = core::array::from_fn(|i| 0xa0 + i as u8);
+            let aad = b"pm-vectors";
+            let sealed = AesGcm::new(&key)
+                .and_then(|gcm| gcm.encrypt(&nonce, aad, m.as_bytes()))
+                .expect("valid AES-GCM parameters");
+            json!({
+                "key": bytes_to_hex(&key),
+                "nonce": bytes_to_hex(&nonce),
+                "aad": bytes_to_hex(aad),
+                "plaintext": m,
+                "expected": bytes_to_hex(&sealed),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn sm4_gcm_vectors() -> Value {
+    let cases = MESSAGES
+        .iter()
+        .map(|m| {
+            let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+            let nonce: [u8; 12] = core::array::from_fn(|i| 0xa0 + i as u8);
+            let aad = b"pm-vectors";
+            let sealed = Sm4Gcm::new(&key)
+                .and_then(|gcm| gcm.encrypt(&nonce, aad, m.as_bytes()))
+                .expect("valid SM4-GCM parameters");
+            json!({
+                "key": bytes_to_hex(&key),
+                "nonce": bytes_to_hex(&nonce),
+                "aad": bytes_to_hex(aad),
+                "plaintext": m,
+                "expected": bytes_to_hex(&sealed),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn xchacha20_poly1305_vectors() -> Value {
+    let cases = MESSAGES
+        .iter()
+        .map(|m| {
+            let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+            let nonce: [u8; 24] = core::array::from_fn(|i| 0xa0 + i as u8);
+            let aad = b"pm-vectors";
+            let sealed = XChaCha20Poly1305::new(&key)
+                .and_then(|c| c.encrypt(&nonce, aad, m.as_bytes()))
+                .expect("valid XChaCha20-Poly1305 parameters");
+            json!({
+                "key": bytes_to_hex(&key),
+                "nonce": bytes_to_hex(&nonce),
+                "aad": bytes_to_hex(aad),
+                "plaintext": m,
+                "expected": bytes_to_hex(&sealed),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn chacha20_poly1305_vectors() -> Value {
+    let cases = MESSAGES
+        .iter()
+        .map(|m| {
+            let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+            let nonce: [u8; 12] = core::array::from_fn(|i| 0xa0 + i as u8);
+            let aad = b"pm-vectors";
+            let sealed = ChaCha20Poly1305::new(&key)
+                .and_then(|c| c.encrypt(&nonce, aad, m.as_bytes()))
+                .expect("valid ChaCha20-Poly1305 parameters");
+            json!({
+                "key": bytes_to_hex(&key),
+                "nonce": bytes_to_hex(&nonce),
+                "aad": bytes_to_hex(aad),
+                "plaintext": m,
+                "expected": bytes_to_hex(&sealed),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn aes_kw_vectors() -> Value {
+    let cases = [(16usize, 16usize), (24, 16), (32, 16), (32, 32), (32, 40)]
+        .iter()
+        .map(|&(kek_len, key_len)| {
+            let kek: Vec<u8> = (0..kek_len as u8).collect();
+            let key: Vec<u8> = (0..key_len as u8).map(|i| i.wrapping_mul(0x11)).collect();
+            let wrapped = aes_kw_wrap_bytes(&kek, &key).expect("valid AES-KW parameters");
+            json!({ "kek": bytes_to_hex(&kek), "key": bytes_to_hex(&key), "expected": bytes_to_hex(&wrapped) })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+// Po jednym blobie na kazdy rozpoznawany format - stare bloby musza sie dalej otwierac.
+fn ciphertext_format_vectors() -> Value {
+    let key: [u8; 32] = core::array::from_fn(|i| 0x40 + i as u8);
+    let nonce: [u8; 12] = core::array::from_fn(|i| 0xc0 + i as u8);
+    let aad = b"pm-vectors";
+    let plaintext = "zażółć gęślą jaźń";
+    let mut legacy = nonce.to_vec();
+    legacy.extend_from_slice(
+        &AesGcm::new(&key)
+            .and_then(|gcm| gcm.encrypt(&nonce, aad, plaintext.as_bytes()))
+            .expect("valid AES-GCM parameters"),
+    );
+    let envelope_key = EntryKey::from_slice(&key);
+    let envelope = seal_envelope_with_nonce(AeadAlgorithm::Aes256Gcm, &envelope_key, &nonce, aad, plaintext.as_bytes())
+        .expect("valid envelope parameters");
+    let cases = [
+        (CiphertextFormat::LegacyRaw, bytes_to_hex(&legacy)),
+        (CiphertextFormat::LegacyHex, bytes_to_hex(bytes_to_hex(&legacy).as_bytes())),
+        (CiphertextFormat::Envelope1, bytes_to_hex(&envelope)),
+    ]
+    .into_iter()
+    .map(|(format, blob)| {
+        json!({
+            "format": format.name(),
+            "key": bytes_to_hex(&key),
+            "aad": bytes_to_hex(aad),
+            "blob": blob,
+            "plaintext": plaintext,
+        })
+    })
+    .collect();
+    Value::Array(cases)
+}
+
+// Koperta vaulta (vault_seal/vault_open) w obu wersjach, bez kompresji. Tanie parametry KDF -
+// koperta zapisuje je w naglowku, wiec vault_open musi je przyjac tak samo jak domyslne.
+fn vault_envelope_vectors() -> Value {
+    let password = "correct horse battery staple";
+    let plaintext = "{\"items\":[]} zażółć";
+    let salt: [u8; 16] = core::array::from_fn(|i| 0x10 + i as u8);
+    let nonce: [u8; 24] = core::array::from_fn(|i| 0x20 + i as u8);
+    let cases = [
+        (1, VaultKdf::Pbkdf2Sha256 { iterations: 1000 }, 12),
+        (2, VaultKdf::Argon2id { m_cost: 64, t_cost: 1, parallelism: 1 }, 24),
+    ]
+    .into_iter()
+    .map(|(version, kdf, nonce_len)| {
+        let nonce = &nonce[..nonce_len];
+        let blob = vault_seal_with_nonce(password.as_bytes(), plaintext.as_bytes(), version, kdf, &salt, nonce)
+            .expect("valid vault envelope parameters");
+        let opened = vault_open_bytes(password.as_bytes(), &blob).expect("vault envelope opens");
+        assert_eq!(opened.as_slice(), plaintext.as_bytes());
+        json!({
+            "version": version,
+            "password": password,
+            "salt": bytes_to_hex(&salt),
+            "nonce": bytes_to_hex(nonce),
+            "plaintext": plaintext,
+            "blob": bytes_to_hex(&blob),
+        })
+    })
+    .collect();
+    Value::Array(cases)
+}
+
+fn x25519_vectors() -> Value {
+    let cases = (0..4u8)
+        .map(|i| {
+            let a: [u8; 32] = core::array::from_fn(|j| (j as u8).wrapping_mul(3).wrapping_add(i));
+            let b: [u8; 32] = core::array::from_fn(|j| (j as u8).wrapping_mul(5) ^ (0x80 + i));
+            let b_public = x25519_public_key(&b);
+            json!({
+                "secret_key": bytes_to_hex(&a),
+                "public_key": bytes_to_hex(&x25519_public_key(&a)),
+                "peer_public_key": bytes_to_hex(&b_public),
+                "shared_secret": bytes_to_hex(&x25519_bytes(&a, &b_public)),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+type HkdfFn = fn(&[u8], &[u8], &[u8], usize) -> Result<Vec<u8>, CryptoError>;
+
+fn hkdf_vectors(f: HkdfFn) -> Value {
+    let cases: &[(&str, &str, &str, usize)] = &[
+        ("input key material", "", "", 32),
+        ("input key material", "salt", "info", 42),
+        ("master password", "user_salt", "pm-vault-key", 100),
+    ];
+    let cases = cases
+        .iter()
+        .map(|&(ikm, salt, info, len)| {
+            let okm = f(ikm.as_bytes(), salt.as_bytes(), info.as_bytes(), len).expect("valid HKDF length");
+            json!({ "ikm": ikm, "salt": salt, "info": info, "length": len, "expected": bytes_to_hex(&okm) })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn argon2_vectors() -> Value {
+    let cases: &[(Argon2Variant, Argon2Version, u32, u32, u32, &str, &str)] = &[
+        (Argon2Variant::Argon2id, Argon2Version::V0x13, 64, 3, 4, "password", "somesaltsomesalt"),
+        (Argon2Variant::Argon2id, Argon2Version::V0x13, 19 * 1024, 2, 1, "master password", "user_salt_16byte"),
+        (Argon2Variant::Argon2i, Argon2Version::V0x13, 32, 3, 4, "password", "somesalt"),
+        (Argon2Variant::Argon2d, Argon2Version::V0x13, 32, 3, 4, "password", "somesalt"),
+        (Argon2Variant::Argon2i, Argon2Version::V0x10, 256, 2, 1, "password", "somesalt"),
+    ];
+    let cases = cases
+        .iter()
+        .map(|&(variant, version, memory_kib, iterations, parallelism, password, salt)| {
+            let params = Argon2Params { variant, version, memory_kib, iterations, parallelism };
+            let hash = argon2_hash_bytes(&params, password.as_bytes(), salt.as_bytes(), 32)
+                .expect("valid argon2 parameters");
+            json!({
+                "password": password,
+                "salt": salt,
+                "expected": bytes_to_hex(&hash),
+                "phc": encode_argon2_phc(&params, salt.as_bytes(), &hash),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn scrypt_vectors() -> Value {
+    // Pierwsze trzy z RFC 7914, sekcja 12
+    let cases: &[(&str, &str, u8, u32, u32, usize)] = &[
+        ("", "", 4, 1, 1, 64),
+        ("password", "NaCl", 10, 8, 16, 64),
+        ("pleaseletmein", "SodiumChloride", 14, 8, 1, 64),
+        ("master password", "saltysaltysalt", 10, 8, 1, 32),
+    ];
+    let cases = cases
+        .iter()
+        .map(|&(password, salt, log_n, r, p, dk_len)| {
+            let params = ScryptParams { log_n, r, p };
+            let hash = scrypt_bytes(password.as_bytes(), salt.as_bytes(), &params, dk_len)
+                .expect("valid scrypt parameters");
+            let mut case = json!({
+                "password": password,
+                "salt": salt,
+                "log_n": log_n,
+                "r": r,
+                "p": p,
+                "expected": bytes_to_hex(&hash),
+            });
+            if dk_len == 32 {
+                case["phc"] = json!(encode_scrypt_phc(&params, salt.as_bytes(), &hash));
+                case["mcf"] = json!(encode_scrypt_mcf(&params, salt, &hash));
+            }
+            case
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn bcrypt_vectors() -> Value {
+    // Ostatnie haslo ma 73 bajty - sprawdza obciecie do 72
+    let cases: &[(&str, u32)] = &[
+        ("", 4),
+        ("password", 5),
+        ("zażółć gęślą jaźń", 4),
+        ("0123456789012345678901234567890123456789012345678901234567890123456789012", 4),
+    ];
+    let cases = cases
+        .iter()
+        .enumerate()
+        .map(|(i, &(password, cost))| {
+            let salt = [i as u8 * 17 + 1; 16];
+            let hash = bcrypt_bytes(password.as_bytes(), &salt, cost).expect("valid bcrypt parameters");
+            json!({ "password": password, "expected": encode_bcrypt(cost, &salt, &hash) })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+fn blake2b_vectors() -> Value {
+    let keys: &[&[u8]] = &[b"", b"key", &[0x42; 64]];
+    let mut cases = Vec::new();
+    for key in keys {
+        for out_len in [32usize, 64] {
+            for m in MESSAGES {
+                let hash = blake2b_keyed_bytes(m.as_bytes(), key, out_len).expect("valid BLAKE2b parameters");
+                cases.push(json!({
+                    "key": bytes_to_hex(key),
+                    "out_len": out_len,
+                    "input": m,
+                    "expected": bytes_to_hex(&hash),
+                }));
+            }
+        }
+    }
+    Value::Array(cases)
+}
+
+fn blake3_keyed_vectors() -> Value {
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+    let long = long_message();
+    let cases = MESSAGES
+        .iter()
+        .copied()
+        .chain([long.as_str()])
+        .map(|m| {
+            json!({
+                "key": bytes_to_hex(&key),
+                "input": m,
+                "expected": bytes_to_hex(&blake3_keyed_bytes(&key, m.as_bytes())),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+// Eksport blake3_derive_key zwraca 32 bajty, dluzsze wyjscie (XOF) tylko przez rdzen.
+fn blake3_derive_key_vectors() -> Value {
+    let context = "pm-vectors 2026-01-01 blake3 derive_key";
+    let mut cases = Vec::new();
+    for m in MESSAGES {
+        for out_len in [32usize, 64] {
+            let key = blake3_derive_key_bytes(context, m.as_bytes(), out_len).expect("valid derive_key parameters");
+            cases.push(json!({
+                "context": context,
+                "key_material": m,
+                "out_len": out_len,
+                "expected": bytes_to_hex(&key),
+            }));
+        }
+    }
+    Value::Array(cases)
+}
+
+// Wynik jako 64-bitowa liczba w hex (big-endian) - tak jak BigInt z eksportu siphash24.
+fn siphash_vectors() -> Value {
+    let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+    let cases = MESSAGES
+        .iter()
+        .map(|m| {
+            json!({
+                "key": bytes_to_hex(&key),
+                "input": m,
+                "expected": format!("{:016x}", siphash24_bytes(&key, m.as_bytes())),
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+// Sekret z RFC 4226, dodatek D
+fn hotp_vectors() -> Value {
+    let secret = b"12345678901234567890";
+    let cases = [(0u64, 6u32, OtpAlgorithm::Sha1), (1, 6, OtpAlgorithm::Sha1), (9, 6, OtpAlgorithm::Sha1)]
+        .into_iter()
+        .chain([(0, 8, OtpAlgorithm::Sha256), (1, 8, OtpAlgorithm::Sha512)])
+        .map(|(counter, digits, algorithm)| {
+            let code = hotp_bytes(secret, counter, digits, algorithm).expect("valid HOTP parameters");
+            json!({
+                "secret_base32": bytes_to_base32(secret, false),
+                "counter": counter,
+                "digits": digits,
+                "algorithm": algorithm.name(),
+                "expected": code,
+            })
+        })
+        .collect();
+    Value::Array(cases)
+}
+
+// Sekrety i czasy z RFC 6238, dodatek B
+fn totp_vectors() -> Value {
+    let secrets: [(OtpAlgorithm, &[u8]); 3] = [
+        (OtpAlgorithm::Sha1, b"12345678901234567890"),
+        (OtpAlgorithm::Sha256, b"12345678901234567890123456789012"),
+        (OtpAlgorithm::Sha512, b"1234567890123456789012345678901234567890123456789012345678901234"),
+    ];
+    let mut cases = Vec::new();
+    for (algorithm, secret) in secrets {
+        for time in [59u64, 1111111109, 1234567890, 20000000000] {
+            let code = totp_bytes(secret, time, 30, 8, algorithm).expect("valid TOTP parameters");
+            cases.push(json!({
+                "secret_base32": bytes_to_base32(secret, false),
+                "time": time,
+                "period": 30,
+                "digits": 8,
+                "algorithm": algorithm.name(),
+                "expected": code,
+            }));
+        }
+    }
+    Value::Array(cases)
+}
+
+// Wektory RFC 4648 (sekcja 10) i wiadomosci wspolne z reszta pliku.
+fn encoding_vectors(f: fn(&[u8]) -> String) -> Value {
+    let inputs = ["f", "fo", "foo", "foob", "fooba", "foobar"].into_iter().chain(MESSAGES.iter().copied());
+    let mut cases: Vec<Value> = inputs.map(|m| json!({ "input": m, "expected": f(m.as_bytes()) })).collect();
+    let binary: Vec<u8> = (0..=255).collect();
+    cases.push(json!({ "input_hex": bytes_to_hex(&binary), "expected": f(&binary) }));
+    Value::Array(cases)
+}
@@ -0,0 +1,74 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::aes::{BLOCK_LEN, aes_256_cbc_decrypt, aes_256_cbc_encrypt};
+use crate::ct::ct_eq;
+use crate::encoding::{base64_to_bytes, bytes_to_base64};
+use crate::error::CryptoError;
+use crate::hmac::hmac_sha256_bytes;
+use crate::rng::random_array;
+
+// EncString Bitwardena typu 2 (AesCbc256_HmacSha256_B64), do importu eksportow:
+// "2.<iv b64>|<ct b64>|<mac b64>", AES-256-CBC z dopelnieniem PKCS#7, potem
+// HMAC-SHA256(mac_key, iv || ct) - encrypt-then-MAC. MAC sprawdzany przed deszyfrowaniem,
+// wiec bledy paddingu nie sa wyrocznia. Inne typy (0, 1, RSA) nie sa obslugiwane.
+pub const ENC_TYPE: u8 = 2;
+const KEY_LEN: usize = 32;
+const MAC_LEN: usize = 32;
+
+fn check_keys(key: &[u8], mac_key: &[u8]) -> Result<(), CryptoError> {
+    if key.len() != KEY_LEN || mac_key.len() != KEY_LEN {
+        return Err(CryptoError::InvalidLength(format!("enc string keys must be {KEY_LEN} bytes each")));
+    }
+    Ok(())
+}
+
+fn enc_string_mac(mac_key: &[u8], iv: &[u8], ciphertext: &[u8]) -> [u8; MAC_LEN] {
+    hmac_sha256_bytes(mac_key, &[iv, ciphertext].concat())
+}
+
+pub fn enc_string_encrypt_bytes(key: &[u8], mac_key: &[u8], plaintext: &[u8]) -> Result<String, CryptoError> {
+    check_keys(key, mac_key)?;
+    let iv = random_array::<BLOCK_LEN>()?;
+    let ciphertext = aes_256_cbc_encrypt(key, &iv, plaintext)?;
+    let mac = enc_string_mac(mac_key, &iv, &ciphertext);
+    Ok(format!("{ENC_TYPE}.{}|{}|{}", bytes_to_base64(&iv), bytes_to_base64(&ciphertext), bytes_to_base64(&mac)))
+}
+
+pub fn enc_string_decrypt_bytes(
+    key: &[u8],
+    mac_key: &[u8],
+    cipher_string: &str,
+) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    check_keys(key, mac_key)?;
+    let (enc_type, body) = cipher_string
+        .trim()
+        .split_once('.')
+        .ok_or_else(|| CryptoError::CorruptHeader("enc string has no type prefix".to_string()))?;
+    if enc_type != ENC_TYPE.to_string() {
+        return Err(CryptoError::UnsupportedAlgorithm(format!("enc string type {enc_type}")));
+    }
+    let parts: Vec<&str> = body.split('|').collect();
+    let [iv, ciphertext, mac] = parts.as_slice() else {
+        return Err(CryptoError::CorruptHeader("enc string type 2 must have iv|ct|mac".to_string()));
+    };
+    let iv = base64_to_bytes(iv)?;
+    let ciphertext = base64_to_bytes(ciphertext)?;
+    let mac = base64_to_bytes(mac)?;
+    if !ct_eq(&enc_string_mac(mac_key, &iv, &ciphertext), &mac) {
+        return Err(CryptoError::BadMac("enc string mac does not match".to_string()));
+    }
+    aes_256_cbc_decrypt(key, &iv, &ciphertext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn enc_string_encrypt(key: &[u8], mac_key: &[u8], plaintext: &[u8]) -> Result<String, CryptoError> {
+    enc_string_encrypt_bytes(key, mac_key, plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn enc_string_decrypt(key: &[u8], mac_key: &[u8], cipher_string: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut plaintext = enc_string_decrypt_bytes(key, mac_key, cipher_string)?;
+    Ok(std::mem::take(&mut *plaintext))
+}
@@ -0,0 +1,213 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+
+// BLAKE2b (RFC 7693) ze zmienna dlugoscia wyjscia 1..=64. Bez klucza potrzebny dla Argon2,
+// z kluczem (do 64 bajtow) jako MAC - szybszy od HMAC-SHA-512 i bez podwojnego haszowania.
+// Klucz dopelniony zerami do 128 bajtow to pierwszy blok wiadomosci.
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+#[derive(Clone)]
+pub struct Blake2b {
+    h: [u64; 8],
+    buf: [u8; 128],
+    buf_len: usize,
+    total_len: u128,
+    out_len: usize,
+}
+
+impl Drop for Blake2b {
+    fn drop(&mut self) {
+        self.h.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn compress(h: &mut [u64; 8], block: &[u8], counter: u128, last: bool) {
+    let mut m = [0u64; 16];
+    for (w, chunk) in m.iter_mut().zip(block.chunks_exact(8)) {
+        *w = u64::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7]]);
+    }
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= counter as u64;
+    v[13] ^= (counter >> 64) as u64;
+    if last {
+        v[14] = !v[14];
+    }
+    for s in &SIGMA {
+        g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+    m.zeroize();
+    v.zeroize();
+}
+
+impl Blake2b {
+    pub fn new(out_len: usize) -> Result<Self, CryptoError> {
+        if out_len == 0 || out_len > 64 {
+            return Err(CryptoError::OutOfRange("blake2b output length must be 1..=64".to_string()));
+        }
+        let mut h = IV;
+        h[0] ^= 0x0101_0000 ^ out_len as u64;
+        Ok(Blake2b { h, buf: [0u8; 128], buf_len: 0, total_len: 0, out_len })
+    }
+
+    pub fn new_keyed(key: &[u8], out_len: usize) -> Result<Self, CryptoError> {
+        if key.len() > 64 {
+            return Err(CryptoError::InvalidLength("blake2b key must be at most 64 bytes".to_string()));
+        }
+        let mut hasher = Blake2b::new(out_len)?;
+        if !key.is_empty() {
+            hasher.h[0] ^= (key.len() as u64) << 8;
+            hasher.buf[..key.len()].copy_from_slice(key);
+            hasher.buf_len = 128;
+        }
+        Ok(hasher)
+    }
+
+    // Ostatni blok musi zostac w buforze (flaga "last"), wiec kompresujemy tylko,
+    // gdy za pelnym buforem sa jeszcze dane.
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buf_len == 128 {
+                self.total_len += 128;
+                let block = self.buf;
+                compress(&mut self.h, &block, self.total_len, false);
+                self.buf_len = 0;
+            }
+            let take = (128 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+        }
+    }
+
+    pub fn finalize(mut self) -> Vec<u8> {
+        self.total_len += self.buf_len as u128;
+        self.buf[self.buf_len..].fill(0);
+        let block = self.buf;
+        compress(&mut self.h, &block, self.total_len, true);
+        let mut out = Vec::with_capacity(64);
+        for w in &self.h {
+            out.extend_from_slice(&w.to_le_bytes());
+        }
+        out.truncate(self.out_len);
+        out
+    }
+}
+
+pub fn blake2b_bytes(data: &[u8], out_len: usize) -> Result<Vec<u8>, CryptoError> {
+    let mut hasher = Blake2b::new(out_len)?;
+    hasher.update(data);
+    Ok(hasher.finalize())
+}
+
+pub fn blake2b_keyed_bytes(data: &[u8], key: &[u8], out_len: usize) -> Result<Vec<u8>, CryptoError> {
+    let mut hasher = Blake2b::new_keyed(key, out_len)?;
+    hasher.update(data);
+    Ok(hasher.finalize())
+}
+
+// Pusty klucz - zwykly skrot. W profilu FIPS NotApproved (Argon2 uzywa rdzenia bezposrednio).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn blake2b(data: &[u8], key: &[u8], out_len: usize) -> Result<Vec<u8>, CryptoError> {
+    require_approved("blake2b")?;
+    blake2b_keyed_bytes(data, key, out_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    #[test]
+    fn rfc_7693_vector() -> Result<(), CryptoError> {
+        assert_eq!(
+            bytes_to_hex(&blake2b_bytes(b"abc", 64)?),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keyed_vector() -> Result<(), CryptoError> {
+        // blake2b-kat.txt: klucz 00..3f, pusta wiadomosc
+        let key: Vec<u8> = (0..64).collect();
+        assert_eq!(
+            bytes_to_hex(&blake2b_keyed_bytes(b"", &key, 64)?),
+            "10ebb67700b1868efb4417987acf4690ae9d972fb7a590c2f02871799aaa4786\
+             b5e996e8f0f4eb981fc214b005f42d2ff4233499391653df7aefcbc13fc51568"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_lengths() {
+        assert!(blake2b_bytes(b"abc", 0).is_err());
+        assert!(blake2b_bytes(b"abc", 65).is_err());
+        assert!(blake2b_keyed_bytes(b"abc", &[0u8; 65], 32).is_err());
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn export_matches_core() -> Result<(), CryptoError> {
+        assert_eq!(blake2b(b"abc", b"", 32)?, blake2b_bytes(b"abc", 32)?);
+        Ok(())
+    }
+
+    #[cfg(feature = "fips-profile")]
+    #[test]
+    fn export_is_refused_in_fips_profile() {
+        assert!(matches!(blake2b(b"abc", b"", 32), Err(CryptoError::NotApproved(_))));
+    }
+}
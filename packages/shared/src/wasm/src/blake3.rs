@@ -0,0 +1,504 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+
+// BLAKE3 (wersja jednowatkowa wg implementacji referencyjnej): kawalki po 1024 bajty,
+// wierzcholki drzewa laczone na stosie CV, wyjscie XOF z wezla korzenia. Trzy tryby:
+// zwykly skrot (zalaczniki), klucz 32 bajty (MAC) i derive_key - kontekst staly dla
+// aplikacji ("pm 2026-01 attachment key"), material klucza zmienny.
+pub const OUT_LEN: usize = 32;
+pub const KEY_LEN: usize = 32;
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+const KEYED_HASH: u32 = 1 << 4;
+const DERIVE_KEY_CONTEXT: u32 = 1 << 5;
+const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
+const IV: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn round(state: &mut [u32; 16], m: &[u32; 16]) {
+    g(state, 0, 4, 8, 12, m[0], m[1]);
+    g(state, 1, 5, 9, 13, m[2], m[3]);
+    g(state, 2, 6, 10, 14, m[4], m[5]);
+    g(state, 3, 7, 11, 15, m[6], m[7]);
+    g(state, 0, 5, 10, 15, m[8], m[9]);
+    g(state, 1, 6, 11, 12, m[10], m[11]);
+    g(state, 2, 7, 8, 13, m[12], m[13]);
+    g(state, 3, 4, 9, 14, m[14], m[15]);
+}
+
+fn compress(cv: &[u32; 8], block: &[u32; 16], counter: u64, block_len: u32, flags: u32) -> [u32; 16] {
+    let mut state = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = *block;
+    for r in 0..7 {
+        round(&mut state, &m);
+        if r < 6 {
+            let mut permuted = [0u32; 16];
+            for (dst, &src) in permuted.iter_mut().zip(MSG_PERMUTATION.iter()) {
+                *dst = m[src];
+            }
+            m = permuted;
+        }
+    }
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= cv[i];
+    }
+    m.zeroize();
+    state
+}
+
+fn first_8(words: [u32; 16]) -> [u32; 8] {
+    let mut out = [0u32; 8];
+    out.copy_from_slice(&words[..8]);
+    out
+}
+
+fn words_from_le(bytes: &[u8], words: &mut [u32]) {
+    for (w, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *w = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+}
+
+// Wezel gotowy do wyprowadzenia wyjscia - CV albo (dla korzenia) strumien XOF.
+struct Output {
+    input_cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        self.input_cv.zeroize();
+        self.block.zeroize();
+    }
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8(compress(&self.input_cv, &self.block, self.counter, self.block_len, self.flags))
+    }
+
+    fn root_bytes(&self, out: &mut [u8]) {
+        for (block_counter, out_block) in out.chunks_mut(BLOCK_LEN).enumerate() {
+            let mut words =
+                compress(&self.input_cv, &self.block, block_counter as u64, self.block_len, self.flags | ROOT);
+            for (word, dst) in words.iter().zip(out_block.chunks_mut(4)) {
+                dst.copy_from_slice(&word.to_le_bytes()[..dst.len()]);
+            }
+            words.zeroize();
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ChunkState {
+    cv: [u32; 8],
+    chunk_counter: u64,
+    block: [u8; BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: u8,
+    flags: u32,
+}
+
+impl Drop for ChunkState {
+    fn drop(&mut self) {
+        self.cv.zeroize();
+        self.block.zeroize();
+    }
+}
+
+impl ChunkState {
+    fn new(key: [u32; 8], chunk_counter: u64, flags: u32) -> Self {
+        ChunkState { cv: key, chunk_counter, block: [0; BLOCK_LEN], block_len: 0, blocks_compressed: 0, flags }
+    }
+
+    fn len(&self) -> usize {
+        BLOCK_LEN * self.blocks_compressed as usize + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 { CHUNK_START } else { 0 }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // Pelny blok kompresujemy dopiero, gdy przychodza dalsze dane - ostatni
+            // blok kawalka dostaje CHUNK_END.
+            if self.block_len == BLOCK_LEN {
+                let mut words = [0u32; 16];
+                words_from_le(&self.block, &mut words);
+                self.cv = first_8(compress(
+                    &self.cv,
+                    &words,
+                    self.chunk_counter,
+                    BLOCK_LEN as u32,
+                    self.flags | self.start_flag(),
+                ));
+                words.zeroize();
+                self.blocks_compressed += 1;
+                self.block = [0; BLOCK_LEN];
+                self.block_len = 0;
+            }
+            let take = (BLOCK_LEN - self.block_len).min(input.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&input[..take]);
+            self.block_len += take;
+            input = &input[take..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        let mut block = [0u32; 16];
+        words_from_le(&self.block, &mut block);
+        Output {
+            input_cv: self.cv,
+            block,
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.flags | self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+fn parent_output(left: &[u32; 8], right: &[u32; 8], key: &[u32; 8], flags: u32) -> Output {
+    let mut block = [0u32; 16];
+    block[..8].copy_from_slice(left);
+    block[8..].copy_from_slice(right);
+    Output { input_cv: *key, block, counter: 0, block_len: BLOCK_LEN as u32, flags: PARENT | flags }
+}
+
+#[derive(Clone)]
+pub struct Blake3 {
+    chunk: ChunkState,
+    key: [u32; 8],
+    cv_stack: Vec<[u32; 8]>,
+    flags: u32,
+}
+
+impl Drop for Blake3 {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.cv_stack.zeroize();
+    }
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Blake3 {
+    fn with_key_words(key: [u32; 8], flags: u32) -> Self {
+        Blake3 { chunk: ChunkState::new(key, 0, flags), key, cv_stack: Vec::with_capacity(54), flags }
+    }
+
+    pub fn new() -> Self {
+        Blake3::with_key_words(IV, 0)
+    }
+
+    pub fn new_keyed(key: &[u8; KEY_LEN]) -> Self {
+        let mut words = [0u32; 8];
+        words_from_le(key, &mut words);
+        let hasher = Blake3::with_key_words(words, KEYED_HASH);
+        words.zeroize();
+        hasher
+    }
+
+    pub fn new_derive_key(context: &str) -> Self {
+        let mut context_hasher = Blake3::with_key_words(IV, DERIVE_KEY_CONTEXT);
+        context_hasher.update(context.as_bytes());
+        let mut context_key = [0u8; KEY_LEN];
+        context_hasher.finalize_into(&mut context_key);
+        let mut words = [0u32; 8];
+        words_from_le(&context_key, &mut words);
+        context_key.zeroize();
+        let hasher = Blake3::with_key_words(words, DERIVE_KEY_MATERIAL);
+        words.zeroize();
+        hasher
+    }
+
+    // Po kazdym pelnym kawalku laczymy tyle par, ile zer na koncu ma licznik kawalkow.
+    fn add_chunk_cv(&mut self, mut cv: [u32; 8], mut total_chunks: u64) {
+        while total_chunks & 1 == 0
+            && let Some(left) = self.cv_stack.pop()
+        {
+            cv = parent_output(&left, &cv, &self.key, self.flags).chaining_value();
+            total_chunks >>= 1;
+        }
+        self.cv_stack.push(cv);
+    }
+
+    pub fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            if self.chunk.len() == CHUNK_LEN {
+                let cv = self.chunk.output().chaining_value();
+                let total_chunks = self.chunk.chunk_counter + 1;
+                self.add_chunk_cv(cv, total_chunks);
+                self.chunk = ChunkState::new(self.key, total_chunks, self.flags);
+            }
+            let take = (CHUNK_LEN - self.chunk.len()).min(input.len());
+            self.chunk.update(&input[..take]);
+            input = &input[take..];
+        }
+    }
+
+    pub fn finalize_into(&self, out: &mut [u8]) {
+        let mut output = self.chunk.output();
+        for left in self.cv_stack.iter().rev() {
+            output = parent_output(left, &output.chaining_value(), &self.key, self.flags);
+        }
+        output.root_bytes(out);
+    }
+
+    pub fn finalize(self) -> [u8; OUT_LEN] {
+        let mut out = [0u8; OUT_LEN];
+        self.finalize_into(&mut out);
+        out
+    }
+}
+
+pub fn blake3_bytes(data: &[u8]) -> [u8; OUT_LEN] {
+    let mut hasher = Blake3::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn blake3_keyed_bytes(key: &[u8; KEY_LEN], data: &[u8]) -> [u8; OUT_LEN] {
+    let mut hasher = Blake3::new_keyed(key);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn blake3_derive_key_bytes(context: &str, key_material: &[u8], out_len: usize) -> Result<Vec<u8>, CryptoError> {
+    if out_len == 0 || out_len > 1024 {
+        return Err(CryptoError::OutOfRange("blake3 derived key length must be 1..=1024".to_string()));
+    }
+    if context.is_empty() {
+        return Err(CryptoError::InvalidParameter("blake3 derive_key context must not be empty".to_string()));
+    }
+    let mut hasher = Blake3::new_derive_key(context);
+    hasher.update(key_material);
+    let mut out = vec![0u8; out_len];
+    hasher.finalize_into(&mut out);
+    Ok(out)
+}
+
+// Eksporty zwracaja NotApproved w profilu FIPS, jak SHA-3/SM3 przez digest().
+// Klasa dla JS: skrot zalacznika kawalkami, jak Sha256Hasher.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct Blake3Hasher {
+    state: Blake3,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl Blake3Hasher {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new() -> Result<Blake3Hasher, CryptoError> {
+        require_approved("blake3")?;
+        Ok(Blake3Hasher { state: Blake3::new() })
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.state.update(chunk);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        self.state.finalize().to_vec()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn blake3_hash(data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    require_approved("blake3")?;
+    Ok(blake3_bytes(data).to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn blake3_keyed(key: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    require_approved("blake3")?;
+    let key: &[u8; KEY_LEN] =
+        key.try_into().map_err(|_| CryptoError::InvalidLength(format!("blake3 key must be {KEY_LEN} bytes")))?;
+    Ok(blake3_keyed_bytes(key, data).to_vec())
+}
+
+// Zawsze 32 bajty; dluzsze klucze przez blake3_derive_key_bytes.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn blake3_derive_key(context: &str, key_material: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    require_approved("blake3")?;
+    blake3_derive_key_bytes(context, key_material, OUT_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::{DigestState, HashAlgorithm};
+    use crate::encoding::bytes_to_hex;
+
+    // Wektory z test_vectors.json implementacji referencyjnej (dane: bajty i % 251).
+    const KEY: &[u8; KEY_LEN] = b"whats the Elvish word for friend";
+    const CONTEXT: &str = "BLAKE3 2019-12-27 16:29:52 test vectors context";
+
+    fn input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn reference_vectors() -> Result<(), CryptoError> {
+        assert_eq!(
+            bytes_to_hex(&blake3_bytes(&input(0))),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            bytes_to_hex(&blake3_bytes(&input(1))),
+            "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213"
+        );
+        assert_eq!(
+            bytes_to_hex(&blake3_keyed_bytes(KEY, &input(0))),
+            "92b2b75604ed3c761f9d6f62392c8a9227ad0ea3f09573e783f1498a4ed60d26"
+        );
+        assert_eq!(
+            bytes_to_hex(&blake3_derive_key_bytes(CONTEXT, &input(0), OUT_LEN)?),
+            "2cc39783c223154fea8dfb7c1b1660f2ac2dcbd1c1de8277b0b0dd39b7e50d7d"
+        );
+        Ok(())
+    }
+
+    // Wejscia powyzej jednego kawalka (1024 B): drzewo z wezlami rodzicow. Pelne 131 bajtow
+    // wyjscia XOF jak w test_vectors.json; dla dluzszych wejsc pierwsze 32 bajty.
+    const XOF_1025: [&str; 3] = [
+        "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444f4c4a22b4b399155358a994e52bf255d\
+        e60035742ec71bd08ac275a1b51cc6bfe332b0ef84b409108cda080e6269ed4b3e2c3f7d722aa4cdc98d16deb554e562\
+        7be8f955c98e1d5f9565a9194cad0c4285f93700062d9595adb992ae68ff12800ab67a",
+        "357dc55de0c7e382c900fd6e320acc04146be01db6a8ce7210b7189bd664ea69362396b77fdc0d2634a5529708437220\
+        66c3c15902ae5097e00ff53f1e116f1cd5352720113a837ab2452cafbde4d54085d9cf5d21ca613071551b25d52e69d6\
+        c81123872b6f19cd3bc1333edf0c52b94de23ba772cf82636cff4542540a7738d5b930",
+        "effaa245f065fbf82ac186839a249707c3bddf6d3fdda22d1b95a3c970379bcb5d31013a167509e9066273ab6e2123bc\
+        835b408b067d88f96addb550d96b6852dad38e320b9d940f86db74d398c770f462118b35d2724efa13da97194491d96d\
+        d37c3c09cbef665953f2ee85ec83d88b88d11547a6f911c8217cca46defa2751e7f3ad",
+    ];
+    const LONG: [(usize, [&str; 3]); 2] = [
+        (
+            3073,
+            [
+                "7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3",
+                "68dede9bef00ba89e43f31a6825f4cf433389fedae75c04ee9f0cf16a427c95a",
+                "72613c9ec9ff7e40f8f5c173784c532ad852e827dba2bf85b2ab4b76f7079081",
+            ],
+        ),
+        (
+            31744,
+            [
+                "62b6960e1a44bcc1eb1a611a8d6235b6b4b78f32e7abc4fb4c6cdcce94895c47",
+                "efa53b389ab67c593dba624d898d0f7353ab99e4ac9d42302ee64cbf9939a419",
+                "39772aef80e0ebe60596361e45b061e8f417429d529171b6764468c22928e28e",
+            ],
+        ),
+    ];
+
+    #[test]
+    fn multi_chunk_vectors() -> Result<(), CryptoError> {
+        let data = input(1025);
+        let mut out = [0u8; 131];
+        let mut hasher = Blake3::new();
+        hasher.update(&data);
+        hasher.finalize_into(&mut out);
+        assert_eq!(bytes_to_hex(&out), XOF_1025[0]);
+        let mut hasher = Blake3::new_keyed(KEY);
+        hasher.update(&data);
+        hasher.finalize_into(&mut out);
+        assert_eq!(bytes_to_hex(&out), XOF_1025[1]);
+        assert_eq!(bytes_to_hex(&blake3_derive_key_bytes(CONTEXT, &data, 131)?), XOF_1025[2]);
+        assert_eq!(bytes_to_hex(&blake3_bytes(&data)), XOF_1025[0][..64]);
+
+        for (len, [hash, keyed, derived]) in LONG {
+            let data = input(len);
+            assert_eq!(bytes_to_hex(&blake3_bytes(&data)), hash, "hash {len}");
+            assert_eq!(bytes_to_hex(&blake3_keyed_bytes(KEY, &data)), keyed, "keyed {len}");
+            assert_eq!(bytes_to_hex(&blake3_derive_key_bytes(CONTEXT, &data, OUT_LEN)?), derived, "derive {len}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn streaming_across_chunks_matches_one_shot() {
+        let data = input(3 * CHUNK_LEN + 17);
+        let mut hasher = Blake3::new();
+        for part in data.chunks(100) {
+            hasher.update(part);
+        }
+        assert_eq!(hasher.finalize(), blake3_bytes(&data));
+        let mut state = DigestState::new(HashAlgorithm::Blake3);
+        state.update(&data);
+        assert_eq!(state.finalize(), blake3_bytes(&data));
+    }
+
+    #[test]
+    fn derive_key_rejects_bad_parameters() {
+        assert!(matches!(blake3_derive_key_bytes("", b"material", 32), Err(CryptoError::InvalidParameter(_))));
+        assert!(matches!(blake3_derive_key_bytes(CONTEXT, b"material", 0), Err(CryptoError::OutOfRange(_))));
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn exports_work_outside_fips_profile() -> Result<(), CryptoError> {
+        assert_eq!(blake3_hash(b"abc")?, blake3_bytes(b"abc"));
+        assert!(matches!(blake3_keyed(&KEY[..31], b"abc"), Err(CryptoError::InvalidLength(_))));
+        assert_eq!(blake3_derive_key(CONTEXT, b"abc")?.len(), OUT_LEN);
+        let mut hasher = Blake3Hasher::new()?;
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize(), blake3_bytes(b"abc"));
+        Ok(())
+    }
+
+    #[cfg(feature = "fips-profile")]
+    #[test]
+    fn exports_are_refused_in_fips_profile() {
+        let refused = |r: Result<Vec<u8>, CryptoError>| matches!(r, Err(CryptoError::NotApproved(_)));
+        assert!(refused(blake3_hash(b"abc")));
+        assert!(refused(blake3_keyed(KEY, b"abc")));
+        assert!(refused(blake3_derive_key(CONTEXT, b"abc")));
+        assert!(Blake3Hasher::new().is_err());
+        assert!(HashAlgorithm::from_name("blake3").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::argon2::{self, argon2id_bytes};
+use crate::error::CryptoError;
+use crate::fips::algorithm_allowed;
+use crate::limits::kdf_limits;
+use crate::pbkdf2::pbkdf2_hmac_sha256_bytes;
+use crate::vault::{ENVELOPE_PBKDF2_ITERATIONS, VaultKdf};
+
+// Kalibracja KDF na urzadzeniu: krotki pomiar probny, potem skalowanie liniowe do docelowego
+// czasu. Wynik nigdy nie schodzi ponizej domyslnych parametrow (slabe urzadzenie dostaje
+// wolniejsze odblokowanie, a nie slabszy klucz) i nie przekracza limitow z limits.rs.
+// Argon2id: pamiec rosnie przed liczba przebiegow; t_cost dopiero po osiagnieciu limitu pamieci.
+// Zegar: Date.now() w przegladarce (rozdzielczosc ~1 ms), wiec probka musi trwac kilkadziesiat ms.
+pub const DEFAULT_TARGET_MS: u32 = 500;
+const MAX_TARGET_MS: u32 = 10_000;
+const MIN_SAMPLE_MS: f64 = 25.0;
+const PROBE_SALT: &[u8; 16] = b"pm-kdf-calibrate";
+const PROBE_PASSWORD: &[u8] = b"calibration";
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    pub pbkdf2_iterations: u32,
+    pub argon2_m_cost: u32,
+    pub argon2_t_cost: u32,
+    pub argon2_parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            pbkdf2_iterations: ENVELOPE_PBKDF2_ITERATIONS,
+            argon2_m_cost: argon2::DEFAULT_MEMORY_KIB,
+            argon2_t_cost: argon2::DEFAULT_ITERATIONS,
+            argon2_parallelism: argon2::DEFAULT_PARALLELISM,
+        }
+    }
+}
+
+impl KdfParams {
+    // KDF koperty vaulta dla danej wersji (v1 PBKDF2, v2 Argon2id).
+    pub fn vault_kdf(&self, version: u8) -> VaultKdf {
+        match version {
+            1 => VaultKdf::Pbkdf2Sha256 { iterations: self.pbkdf2_iterations },
+            _ => VaultKdf::Argon2id {
+                m_cost: self.argon2_m_cost,
+                t_cost: self.argon2_t_cost,
+                parallelism: self.argon2_parallelism,
+            },
+        }
+    }
+}
+
+#[cfg(all(feature = "js", target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(all(feature = "js", target_arch = "wasm32")))]
+fn now_ms() -> f64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+}
+
+// Podwaja prace, az pomiar bedzie dosc dlugi. Zwraca (praca, czas w ms).
+fn sample(
+    mut work: u32,
+    max_work: u32,
+    run: impl Fn(u32) -> Result<(), CryptoError>,
+) -> Result<(u32, f64), CryptoError> {
+    loop {
+        let start = now_ms();
+        run(work)?;
+        let elapsed = now_ms() - start;
+        if elapsed >= MIN_SAMPLE_MS || work >= max_work {
+            return Ok((work, elapsed.max(1.0)));
+        }
+        work = work.saturating_mul(2).min(max_work);
+    }
+}
+
+fn scale(work: u32, elapsed_ms: f64, target_ms: u32) -> u64 {
+    (work as f64 * target_ms as f64 / elapsed_ms) as u64
+}
+
+fn calibrate_pbkdf2(target_ms: u32, max_iterations: u32) -> Result<u32, CryptoError> {
+    let (iterations, elapsed) = sample(10_000.min(max_iterations), max_iterations, |c| {
+        pbkdf2_hmac_sha256_bytes(PROBE_PASSWORD, PROBE_SALT, c, 32).map(|_| ())
+    })?;
+    let scaled = scale(iterations, elapsed, target_ms).min(max_iterations as u64) as u32;
+    Ok(scaled.max(ENVELOPE_PBKDF2_ITERATIONS.min(max_iterations)))
+}
+
+// Probka: domyslna pamiec, rosnaca liczba przebiegow. Czas ~ m_cost * t_cost.
+fn calibrate_argon2(target_ms: u32, max_memory_kib: u32, max_iterations: u32) -> Result<(u32, u32), CryptoError> {
+    let m_probe = argon2::DEFAULT_MEMORY_KIB.min(max_memory_kib);
+    let p = argon2::DEFAULT_PARALLELISM;
+    let (passes, elapsed) =
+        sample(1, max_iterations, |t| argon2id_bytes(PROBE_PASSWORD, PROBE_SALT, m_probe, t, p, 32).map(|_| ()))?;
+    let budget = scale(passes, elapsed, target_ms).saturating_mul(m_probe as u64);
+
+    let t_min = argon2::DEFAULT_ITERATIONS.min(max_iterations);
+    let m_cost = (budget / t_min as u64).clamp(m_probe as u64, max_memory_kib as u64) as u32;
+    let t_cost = (budget / m_cost as u64).clamp(t_min as u64, max_iterations as u64) as u32;
+    Ok((m_cost, t_cost))
+}
+
+// target_ms 0 = domyslne (500 ms). W profilu FIPS Argon2 nie jest mierzony - zostaja domyslne.
+pub fn calibrate_kdf_params(target_ms: u32) -> Result<KdfParams, CryptoError> {
+    let target_ms = if target_ms == 0 { DEFAULT_TARGET_MS } else { target_ms };
+    if target_ms > MAX_TARGET_MS {
+        return Err(CryptoError::OutOfRange(format!("calibration target must be at most {MAX_TARGET_MS} ms")));
+    }
+    let limits = kdf_limits();
+    let mut params =
+        KdfParams { pbkdf2_iterations: calibrate_pbkdf2(target_ms, limits.max_iterations)?, ..KdfParams::default() };
+    if algorithm_allowed("argon2id") {
+        (params.argon2_m_cost, params.argon2_t_cost) =
+            calibrate_argon2(target_ms, limits.max_memory_kib, limits.max_argon2_iterations)?;
+    }
+    Ok(params)
+}
+
+// Mierzy KDF na biezacym urzadzeniu (blokuje watek na kilkaset ms) - wolac raz, przy
+// tworzeniu vaulta, a wynik przekazac do vault_seal_with.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn calibrate_kdf(target_ms: u32) -> Result<KdfParams, CryptoError> {
+    calibrate_kdf_params(target_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::DEFAULT_KDF_LIMITS;
+
+    // Limity podawane jawnie - globalne KdfLimits sa wspoldzielone z rownolegle dzialajacymi testami.
+    #[test]
+    fn pbkdf2_stays_within_the_iteration_limit() -> Result<(), CryptoError> {
+        assert_eq!(calibrate_pbkdf2(MAX_TARGET_MS, 5_000)?, 5_000);
+        let iterations = calibrate_pbkdf2(1, DEFAULT_KDF_LIMITS.max_iterations)?;
+        assert!((ENVELOPE_PBKDF2_ITERATIONS..=DEFAULT_KDF_LIMITS.max_iterations).contains(&iterations));
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn argon2_stays_within_the_memory_and_pass_limits() -> Result<(), CryptoError> {
+        // Duzy cel: oba parametry dobijaja do limitow, ale ich nie przekraczaja.
+        assert_eq!(calibrate_argon2(MAX_TARGET_MS, 64, 3)?, (64, 3));
+        // Maly cel: nie schodzi ponizej domyslnej liczby przebiegow ani pamieci probki.
+        let (m_cost, t_cost) = calibrate_argon2(1, 1024, DEFAULT_KDF_LIMITS.max_argon2_iterations)?;
+        assert_eq!(m_cost, 1024);
+        assert!((argon2::DEFAULT_ITERATIONS..=DEFAULT_KDF_LIMITS.max_argon2_iterations).contains(&t_cost));
+        Ok(())
+    }
+
+    #[test]
+    fn target_is_bounded() {
+        assert!(matches!(calibrate_kdf(MAX_TARGET_MS + 1), Err(CryptoError::OutOfRange(_))));
+        assert_eq!(scale(1000, 10.0, 500), 50_000);
+    }
+}
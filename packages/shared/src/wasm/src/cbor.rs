@@ -0,0 +1,406 @@
+use serde_json::{Map, Number, Value};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::encoding::bytes_to_base64;
+use crate::error::CryptoError;
+
+// Kanoniczny CBOR (RFC 8949, 4.2.1 "core deterministic encoding") dla struktur vaulta:
+// najkrotsze naglowki liczb i dlugosci, tylko dlugosci okreslone, klucze map posortowane
+// bajtowo po ich zakodowanej postaci, liczby zmiennoprzecinkowe w najkrotszej dokladnej
+// postaci (f16 / f32 / f64), NaN jako 0xf97e00. Ta sama wartosc ma zawsze te same bajty,
+// wiec MAC i podpis mozna liczyc bezposrednio nad kodowaniem.
+// Dekoder jest scisly: odrzuca tagi, undefined i inne proste wartosci, dlugosci
+// nieokreslone, duplikaty kluczy i kazde kodowanie niekanoniczne (wynik musi sie
+// zakodowac z powrotem do identycznych bajtow).
+const MAX_DEPTH: usize = 64;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_SIMPLE: u8 = 7;
+const SIMPLE_FALSE: u8 = 20;
+const SIMPLE_TRUE: u8 = 21;
+const SIMPLE_NULL: u8 = 22;
+const FLOAT16: u8 = 25;
+const FLOAT32: u8 = 26;
+const FLOAT64: u8 = 27;
+const CANONICAL_NAN: u16 = 0x7e00;
+
+// Negative(n) to wartosc -1 - n (jak w kodowaniu).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cbor {
+    Unsigned(u64),
+    Negative(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+    Bool(bool),
+    Null,
+    Float(f64),
+}
+
+fn malformed(what: &str) -> CryptoError {
+    CryptoError::InvalidInput(format!("malformed cbor: {what}"))
+}
+
+fn write_head(out: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    if n < 24 {
+        out.push(major | n as u8);
+    } else if n <= u8::MAX as u64 {
+        out.extend_from_slice(&[major | 24, n as u8]);
+    } else if n <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(n as u16).to_be_bytes());
+    } else if n <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(n as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+// f16 dokladnie rowny x albo None.
+fn to_f16(x: f64) -> Option<u16> {
+    if x.is_nan() {
+        return Some(CANONICAL_NAN);
+    }
+    let sign = if x.is_sign_negative() { 0x8000 } else { 0 };
+    let a = x.abs();
+    if a.is_infinite() {
+        return Some(sign | 0x7c00);
+    }
+    if a < 2f64.powi(-14) {
+        // subnormalne (i zero): m * 2^-24, m < 1024
+        let m = a * 2f64.powi(24);
+        return (m.fract() == 0.0).then_some(sign | m as u16);
+    }
+    let bits = a.to_bits();
+    let exp = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    if exp > 15 || bits & ((1 << 42) - 1) != 0 {
+        return None;
+    }
+    Some(sign | ((exp + 15) as u16) << 10 | ((bits >> 42) & 0x3ff) as u16)
+}
+
+fn from_f16(h: u16) -> f64 {
+    let sign = if h & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((h >> 10) & 0x1f) as i32;
+    let mant = (h & 0x3ff) as f64;
+    sign * match exp {
+        0 => mant * 2f64.powi(-24),
+        31 if mant == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1024.0 + mant) * 2f64.powi(exp - 25),
+    }
+}
+
+impl Cbor {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Cbor::Unsigned(n) => write_head(out, MAJOR_UNSIGNED, *n),
+            Cbor::Negative(n) => write_head(out, MAJOR_NEGATIVE, *n),
+            Cbor::Bytes(b) => {
+                write_head(out, MAJOR_BYTES, b.len() as u64);
+                out.extend_from_slice(b);
+            }
+            Cbor::Text(t) => {
+                write_head(out, MAJOR_TEXT, t.len() as u64);
+                out.extend_from_slice(t.as_bytes());
+            }
+            Cbor::Array(items) => {
+                write_head(out, MAJOR_ARRAY, items.len() as u64);
+                for item in items {
+                    item.encode_into(out);
+                }
+            }
+            Cbor::Map(entries) => {
+                let mut encoded: Vec<(Vec<u8>, &Cbor)> = entries.iter().map(|(k, v)| (k.encode(), v)).collect();
+                encoded.sort_by(|a, b| a.0.cmp(&b.0));
+                write_head(out, MAJOR_MAP, encoded.len() as u64);
+                for (key, value) in encoded {
+                    out.extend_from_slice(&key);
+                    value.encode_into(out);
+                }
+            }
+            Cbor::Bool(b) => out.push(MAJOR_SIMPLE << 5 | if *b { SIMPLE_TRUE } else { SIMPLE_FALSE }),
+            Cbor::Null => out.push(MAJOR_SIMPLE << 5 | SIMPLE_NULL),
+            Cbor::Float(x) => {
+                if let Some(h) = to_f16(*x) {
+                    out.push(MAJOR_SIMPLE << 5 | FLOAT16);
+                    out.extend_from_slice(&h.to_be_bytes());
+                } else if (*x as f32) as f64 == *x {
+                    out.push(MAJOR_SIMPLE << 5 | FLOAT32);
+                    out.extend_from_slice(&(*x as f32).to_be_bytes());
+                } else {
+                    out.push(MAJOR_SIMPLE << 5 | FLOAT64);
+                    out.extend_from_slice(&x.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Cbor, CryptoError> {
+        let mut reader = Reader { data, pos: 0 };
+        let value = reader.value(0)?;
+        if reader.pos != data.len() {
+            return Err(malformed("trailing bytes"));
+        }
+        if value.encode() != data {
+            return Err(malformed("not in canonical form"));
+        }
+        Ok(value)
+    }
+
+    pub fn map(entries: Vec<(&str, Cbor)>) -> Cbor {
+        Cbor::Map(entries.into_iter().map(|(k, v)| (Cbor::Text(k.to_string()), v)).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Cbor> {
+        match self {
+            Cbor::Map(entries) => entries.iter().find(|(k, _)| matches!(k, Cbor::Text(t) if t == key)).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Cbor::Unsigned(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Cbor::Text(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Cbor::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn from_json(value: &Value) -> Cbor {
+        match value {
+            Value::Null => Cbor::Null,
+            Value::Bool(b) => Cbor::Bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(u), _) => Cbor::Unsigned(u),
+                (None, Some(i)) => Cbor::Negative(!(i as u64)),
+                _ => Cbor::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            Value::String(s) => Cbor::Text(s.clone()),
+            Value::Array(items) => Cbor::Array(items.iter().map(Cbor::from_json).collect()),
+            Value::Object(map) => {
+                Cbor::Map(map.iter().map(|(k, v)| (Cbor::Text(k.clone()), Cbor::from_json(v))).collect())
+            }
+        }
+    }
+
+    // Ciagi bajtow jako base64; klucze map musza byc tekstem.
+    pub fn to_json(&self) -> Result<Value, CryptoError> {
+        Ok(match self {
+            Cbor::Unsigned(n) => Value::Number((*n).into()),
+            Cbor::Negative(n) => {
+                let n =
+                    i64::try_from(*n).map_err(|_| CryptoError::OutOfRange("cbor integer out of range".to_string()))?;
+                Value::Number((-1 - n).into())
+            }
+            Cbor::Bytes(b) => Value::String(bytes_to_base64(b)),
+            Cbor::Text(t) => Value::String(t.clone()),
+            Cbor::Array(items) => Value::Array(items.iter().map(Cbor::to_json).collect::<Result<_, _>>()?),
+            Cbor::Map(entries) => {
+                let mut map = Map::new();
+                for (k, v) in entries {
+                    let Cbor::Text(k) = k else {
+                        return Err(CryptoError::InvalidInput("cbor map key is not text".to_string()));
+                    };
+                    map.insert(k.clone(), v.to_json()?);
+                }
+                Value::Object(map)
+            }
+            Cbor::Bool(b) => Value::Bool(*b),
+            Cbor::Null => Value::Null,
+            Cbor::Float(x) => Value::Number(
+                Number::from_f64(*x)
+                    .ok_or_else(|| CryptoError::InvalidInput("cbor float is not representable in json".to_string()))?,
+            ),
+        })
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], CryptoError> {
+        let bytes = self.data.get(self.pos..self.pos.saturating_add(len)).ok_or_else(|| malformed("truncated"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn uint(&mut self, len: usize) -> Result<u64, CryptoError> {
+        Ok(self.take(len)?.iter().fold(0u64, |acc, &b| acc << 8 | b as u64))
+    }
+
+    // Dlugosc elementow nie moze przekraczac pozostalych bajtow (kazdy zajmuje co najmniej 1 B).
+    fn len(&mut self, n: u64) -> Result<usize, CryptoError> {
+        usize::try_from(n)
+            .ok()
+            .filter(|&n| n <= self.data.len() - self.pos)
+            .ok_or_else(|| malformed("length too large"))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Cbor, CryptoError> {
+        if depth > MAX_DEPTH {
+            return Err(malformed("nesting too deep"));
+        }
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        if major == MAJOR_SIMPLE {
+            return match info {
+                SIMPLE_FALSE => Ok(Cbor::Bool(false)),
+                SIMPLE_TRUE => Ok(Cbor::Bool(true)),
+                SIMPLE_NULL => Ok(Cbor::Null),
+                FLOAT16 => Ok(Cbor::Float(from_f16(self.uint(2)? as u16))),
+                FLOAT32 => Ok(Cbor::Float(f32::from_bits(self.uint(4)? as u32) as f64)),
+                FLOAT64 => Ok(Cbor::Float(f64::from_bits(self.uint(8)?))),
+                _ => Err(malformed("unsupported simple value")),
+            };
+        }
+        let n = match info {
+            0..=23 => info as u64,
+            24 => self.uint(1)?,
+            25 => self.uint(2)?,
+            26 => self.uint(4)?,
+            27 => self.uint(8)?,
+            _ => return Err(malformed("indefinite length or reserved value")),
+        };
+        match major {
+            MAJOR_UNSIGNED => Ok(Cbor::Unsigned(n)),
+            MAJOR_NEGATIVE => Ok(Cbor::Negative(n)),
+            MAJOR_BYTES => {
+                let len = self.len(n)?;
+                Ok(Cbor::Bytes(self.take(len)?.to_vec()))
+            }
+            MAJOR_TEXT => {
+                let len = self.len(n)?;
+                let text = std::str::from_utf8(self.take(len)?).map_err(|_| malformed("text is not UTF-8"))?;
+                Ok(Cbor::Text(text.to_string()))
+            }
+            MAJOR_ARRAY => {
+                let len = self.len(n)?;
+                (0..len).map(|_| self.value(depth + 1)).collect::<Result<_, _>>().map(Cbor::Array)
+            }
+            MAJOR_MAP => {
+                let len = self.len(n)?;
+                let mut entries: Vec<(Cbor, Cbor)> = Vec::with_capacity(len);
+                let mut last_key: Option<Vec<u8>> = None;
+                for _ in 0..len {
+                    let start = self.pos;
+                    let key = self.value(depth + 1)?;
+                    let key_bytes = self.data[start..self.pos].to_vec();
+                    if last_key.as_ref().is_some_and(|last| *last >= key_bytes) {
+                        return Err(malformed("map keys unsorted or duplicated"));
+                    }
+                    last_key = Some(key_bytes);
+                    entries.push((key, self.value(depth + 1)?));
+                }
+                Ok(Cbor::Map(entries))
+            }
+            _ => Err(malformed("tags are not supported")),
+        }
+    }
+}
+
+pub fn json_to_cbor_bytes(json: &str) -> Result<Vec<u8>, CryptoError> {
+    let value: Value = serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+    Ok(Cbor::from_json(&value).encode())
+}
+
+pub fn cbor_to_json_string(data: &[u8]) -> Result<String, CryptoError> {
+    serde_json::to_string(&Cbor::decode(data)?.to_json()?).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn json_to_cbor(json: &str) -> Result<Vec<u8>, CryptoError> {
+    json_to_cbor_bytes(json)
+}
+
+// Tylko kanoniczny CBOR; ciagi bajtow wracaja jako base64.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn cbor_to_json(data: &[u8]) -> Result<String, CryptoError> {
+    cbor_to_json_string(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+
+    fn decode_hex(hex: &str) -> Result<Cbor, CryptoError> {
+        Cbor::decode(&hex_to_bytes(hex)?)
+    }
+
+    #[test]
+    fn json_round_trip() -> Result<(), CryptoError> {
+        // RFC 8949 dodatek A: {"a": 1, "b": [2, 3]}
+        assert_eq!(json_to_cbor_bytes(r#"{"b":[2,3],"a":1}"#)?, hex_to_bytes("a26161016162820203")?);
+        let json = r#"{"name":"vault","n":-500,"ratio":1.5,"tags":["a",null,true,false],"nested":{"z":0,"aa":[]}}"#;
+        let cbor = json_to_cbor_bytes(json)?;
+        let back: Value = serde_json::from_str(&cbor_to_json_string(&cbor)?).unwrap_or_default();
+        assert_eq!(back, serde_json::from_str::<Value>(json).unwrap_or_default());
+        assert_eq!(json_to_cbor_bytes(&cbor_to_json_string(&cbor)?)?, cbor);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unsorted_and_duplicate_keys() -> Result<(), CryptoError> {
+        assert!(decode_hex("a2616101616202").is_ok());
+        assert!(matches!(decode_hex("a2616201616102"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(decode_hex("a2616101616102"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_indefinite_lengths_and_tags() -> Result<(), CryptoError> {
+        for hex in ["9f01ff", "bf616101ff", "5f4101ff", "7f6161ff", "c11a514b67b0", "d82076687474703a2f2f612e6363"] {
+            assert!(matches!(decode_hex(hex), Err(CryptoError::InvalidInput(_))), "{hex}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_nesting_beyond_max_depth() -> Result<(), CryptoError> {
+        let nested = |depth: usize| [vec![0x81; depth], vec![0x00]].concat();
+        assert!(Cbor::decode(&nested(MAX_DEPTH)).is_ok());
+        assert!(matches!(Cbor::decode(&nested(MAX_DEPTH + 1)), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_length_beyond_input() -> Result<(), CryptoError> {
+        for hex in ["5affffffff00", "636161", "9b00000000ffffffff01", "a1"] {
+            assert!(matches!(decode_hex(hex), Err(CryptoError::InvalidInput(_))), "{hex}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::ct::ct_eq;
+use crate::encoding::{bytes_to_hex, hex_to_bytes};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::hmac::hmac_sha256_bytes;
+use crate::scope::{VaultScope, derive_scope_key};
+use crate::secret::{EntryKey, MasterKey};
+use crate::sha2::sha256_bytes;
+
+// Deduplikacja zalacznikow: podzial na fragmenty wyznaczane przez tresc (FastCDC z
+// normalizacja), wiec drobna zmiana dokumentu zmienia tylko sasiednie fragmenty.
+// Fragment szyfrowany zbieznie w obrebie vaulta: klucz i nonce z HKDF(klucz tresci, sol =
+// SHA-256 fragmentu), identyfikator = HMAC(klucz id, SHA-256 fragmentu). Ten sam fragment
+// w tym samym vaulcie daje ten sam identyfikator i szyfrogram (mozna go uzyc ponownie),
+// a w innym vaulcie - inne (brak porownywania tresci miedzy vaultami).
+// Oba klucze wyprowadzane z klucza partycji "attachments". Skrot fragmentu jest potrzebny
+// do odszyfrowania, wiec manifest z lista fragmentow musi byc szyfrowany.
+// Fragment: "PMCK" || 1 || ciphertext || tag, AAD = naglowek || identyfikator.
+const CHUNK_MAGIC: &[u8; 4] = b"PMCK";
+const CHUNK_VERSION: u8 = 1;
+const CHUNK_HEADER_LEN: usize = 5;
+const CHUNK_ID_INFO: &[u8] = b"pm-cdc-id";
+const CHUNK_KEY_INFO: &[u8] = b"pm-cdc-key";
+const CHUNK_CIPHER_INFO: &[u8] = b"pm-cdc-chunk";
+pub const DEFAULT_MIN_SIZE: u32 = 16 * 1024;
+pub const DEFAULT_AVG_SIZE: u32 = 64 * 1024;
+pub const DEFAULT_MAX_SIZE: u32 = 256 * 1024;
+const MIN_AVG_SIZE: u32 = 256;
+const MAX_MAX_SIZE: u32 = 64 * 1024 * 1024;
+
+// Tablica gear z SplitMix64 (stale ziarno) - wartosci sa czescia formatu, nie zmieniac.
+const GEAR: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x706d_2d63_6463_2d31;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkParams {
+    pub min_size: u32,
+    pub avg_size: u32,
+    pub max_size: u32,
+    mask_s: u64,
+    mask_l: u64,
+}
+
+impl ChunkParams {
+    // 0 = wartosc domyslna. Srednia musi byc potega dwojki.
+    pub fn new(min_size: u32, avg_size: u32, max_size: u32) -> Result<ChunkParams, CryptoError> {
+        let or = |v: u32, d: u32| if v == 0 { d } else { v };
+        let (min_size, avg_size, max_size) =
+            (or(min_size, DEFAULT_MIN_SIZE), or(avg_size, DEFAULT_AVG_SIZE), or(max_size, DEFAULT_MAX_SIZE));
+        if !avg_size.is_power_of_two() || avg_size < MIN_AVG_SIZE {
+            return Err(CryptoError::InvalidParameter(format!(
+                "average chunk size must be a power of two >= {MIN_AVG_SIZE}"
+            )));
+        }
+        if min_size == 0 || min_size >= avg_size || avg_size >= max_size || max_size > MAX_MAX_SIZE {
+            return Err(CryptoError::InvalidParameter(format!(
+                "chunk sizes must satisfy 0 < min < avg < max <= {MAX_MAX_SIZE}"
+            )));
+        }
+        // Normalizacja poziomu 2: przed srednia trudniej o ciecie, za nia latwiej.
+        // Maski na starszych bitach, bo w hashu gear to one zaleza od calego okna.
+        let bits = avg_size.trailing_zeros();
+        let top = |n: u32| !0u64 << (64 - n);
+        Ok(ChunkParams { min_size, avg_size, max_size, mask_s: top(bits + 2), mask_l: top(bits - 2) })
+    }
+
+    // Dlugosc pierwszego fragmentu danych.
+    pub fn cut(&self, data: &[u8]) -> usize {
+        let min = self.min_size as usize;
+        if data.len() <= min {
+            return data.len();
+        }
+        let end = data.len().min(self.max_size as usize);
+        let normal = end.min(self.avg_size as usize);
+        let mut hash = 0u64;
+        let mut i = min;
+        while i < normal {
+            hash = (hash << 1).wrapping_add(GEAR[data[i] as usize]);
+            if hash & self.mask_s == 0 {
+                return i + 1;
+            }
+            i += 1;
+        }
+        while i < end {
+            hash = (hash << 1).wrapping_add(GEAR[data[i] as usize]);
+            if hash & self.mask_l == 0 {
+                return i + 1;
+            }
+            i += 1;
+        }
+        end
+    }
+
+    // Konce kolejnych fragmentow (offsety wylaczne).
+    pub fn boundaries(&self, data: &[u8]) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            offset += self.cut(&data[offset..]);
+            out.push(offset);
+        }
+        out
+    }
+}
+
+pub struct ChunkKeys {
+    id: EntryKey,
+    content: EntryKey,
+}
+
+pub fn derive_chunk_keys(vault_key: &MasterKey) -> Result<ChunkKeys, CryptoError> {
+    let scope_key = derive_scope_key(vault_key, VaultScope::Attachments)?;
+    Ok(ChunkKeys {
+        id: EntryKey::from_vec(hkdf_sha256_bytes(scope_key.as_bytes(), &[], CHUNK_ID_INFO, 32)?),
+        content: EntryKey::from_vec(hkdf_sha256_bytes(scope_key.as_bytes(), &[], CHUNK_KEY_INFO, 32)?),
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkRef {
+    pub id: String,
+    pub hash: String,
+    pub offset: u64,
+    pub size: u32,
+}
+
+fn chunk_id(keys: &ChunkKeys, hash: &[u8; 32]) -> [u8; 32] {
+    hmac_sha256_bytes(keys.id.as_bytes(), hash)
+}
+
+fn chunk_cipher(keys: &ChunkKeys, hash: &[u8; 32]) -> Result<(AesGcm, [u8; NONCE_LEN]), CryptoError> {
+    let okm = Zeroizing::new(hkdf_sha256_bytes(keys.content.as_bytes(), hash, CHUNK_CIPHER_INFO, 32 + NONCE_LEN)?);
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&okm[32..]);
+    Ok((AesGcm::new(&okm[..32])?, nonce))
+}
+
+fn chunk_aad(id: &[u8; 32]) -> Vec<u8> {
+    let mut aad = CHUNK_MAGIC.to_vec();
+    aad.push(CHUNK_VERSION);
+    aad.extend_from_slice(id);
+    aad
+}
+
+// Zwraca (opis fragmentu, szyfrogram). Deterministyczne dla danego vaulta i tresci.
+pub fn encrypt_chunk_bytes(keys: &ChunkKeys, offset: u64, chunk: &[u8]) -> Result<(ChunkRef, Vec<u8>), CryptoError> {
+    let hash = sha256_bytes(chunk);
+    let id = chunk_id(keys, &hash);
+    let (cipher, nonce) = chunk_cipher(keys, &hash)?;
+    let aad = chunk_aad(&id);
+    let mut out = aad[..CHUNK_HEADER_LEN].to_vec();
+    out.extend_from_slice(&cipher.encrypt(&nonce, &aad, chunk)?);
+    let chunk_ref = ChunkRef { id: bytes_to_hex(&id), hash: bytes_to_hex(&hash), offset, size: chunk.len() as u32 };
+    Ok((chunk_ref, out))
+}
+
+pub fn decrypt_chunk_bytes(keys: &ChunkKeys, hash: &[u8; 32], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if blob.len() < CHUNK_HEADER_LEN + TAG_LEN || &blob[..4] != CHUNK_MAGIC {
+        return Err(CryptoError::CorruptHeader("not an attachment chunk".to_string()));
+    }
+    if blob[4] != CHUNK_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("attachment chunk version {}", blob[4])));
+    }
+    let id = chunk_id(keys, hash);
+    let (cipher, nonce) = chunk_cipher(keys, hash)?;
+    let chunk = cipher.decrypt(&nonce, &chunk_aad(&id), &blob[CHUNK_HEADER_LEN..])?;
+    if !ct_eq(&sha256_bytes(&chunk), hash) {
+        return Err(CryptoError::VerificationFailed("attachment chunk hash mismatch".to_string()));
+    }
+    Ok(chunk)
+}
+
+pub fn chunk_data_bytes(
+    vault_key: &MasterKey,
+    data: &[u8],
+    params: &ChunkParams,
+) -> Result<Vec<(ChunkRef, Vec<u8>)>, CryptoError> {
+    let keys = derive_chunk_keys(vault_key)?;
+    let mut start = 0;
+    let mut out = Vec::new();
+    for end in params.boundaries(data) {
+        out.push(encrypt_chunk_bytes(&keys, start as u64, &data[start..end])?);
+        start = end;
+    }
+    Ok(out)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct ContentChunks {
+    refs: Vec<ChunkRef>,
+    blobs: Vec<Vec<u8>>,
+}
+
+impl ContentChunks {
+    pub fn new(chunks: Vec<(ChunkRef, Vec<u8>)>) -> ContentChunks {
+        let (refs, blobs) = chunks.into_iter().unzip();
+        ContentChunks { refs, blobs }
+    }
+
+    pub fn refs(&self) -> &[ChunkRef] {
+        &self.refs
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl ContentChunks {
+    // JSON [{"id", "hash", "offset", "size"}] - do zaszyfrowanego manifestu zalacznika.
+    pub fn manifest(&self) -> Result<String, CryptoError> {
+        serde_json::to_string(&self.refs).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn count(&self) -> usize {
+        self.refs.len()
+    }
+
+    pub fn id(&self, index: usize) -> Result<String, CryptoError> {
+        self.refs
+            .get(index)
+            .map(|r| r.id.clone())
+            .ok_or_else(|| CryptoError::OutOfRange(format!("chunk index {index}")))
+    }
+
+    pub fn ciphertext(&self, index: usize) -> Result<Vec<u8>, CryptoError> {
+        self.blobs.get(index).cloned().ok_or_else(|| CryptoError::OutOfRange(format!("chunk index {index}")))
+    }
+}
+
+// Rozmiary 0 = domyslne (16 KiB / 64 KiB / 256 KiB).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn cdc_boundaries(data: &[u8], min_size: u32, avg_size: u32, max_size: u32) -> Result<Vec<u32>, CryptoError> {
+    let params = ChunkParams::new(min_size, avg_size, max_size)?;
+    Ok(params.boundaries(data).into_iter().map(|b| b as u32).collect())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn chunk_attachment(
+    vault_key: &[u8],
+    data: &[u8],
+    min_size: u32,
+    avg_size: u32,
+    max_size: u32,
+) -> Result<ContentChunks, CryptoError> {
+    let params = ChunkParams::new(min_size, avg_size, max_size)?;
+    Ok(ContentChunks::new(chunk_data_bytes(&MasterKey::from_slice(vault_key), data, &params)?))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn decrypt_attachment_chunk(vault_key: &[u8], hash: &str, ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let hash: [u8; 32] = hex_to_bytes(hash)?
+        .try_into()
+        .map_err(|_| CryptoError::InvalidLength("chunk hash must be 32 bytes".to_string()))?;
+    decrypt_chunk_bytes(&derive_chunk_keys(&MasterKey::from_slice(vault_key))?, &hash, ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_KEY: [u8; 32] = [0x42; 32];
+
+    fn noise(len: usize) -> Vec<u8> {
+        let mut x = 0x9e37_79b9_7f4a_7c15u64;
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn boundaries_respect_the_size_limits() -> Result<(), CryptoError> {
+        let params = ChunkParams::new(256, 1024, 4096)?;
+        let data = [noise(100_000), vec![0; 20_000]].concat();
+        let ends = params.boundaries(&data);
+        assert_eq!(ends.last(), Some(&data.len()));
+        let sizes: Vec<usize> = ends.iter().scan(0, |start, &end| Some(end - std::mem::replace(start, end))).collect();
+        assert!(sizes[..sizes.len() - 1].iter().all(|&s| (256..=4096).contains(&s)));
+        // Zera nie daja ciec - fragmenty maksymalnej dlugosci.
+        assert!(sizes[sizes.len() - 4..sizes.len() - 1].iter().all(|&s| s == 4096));
+        assert!(params.boundaries(&[]).is_empty());
+        assert!(matches!(ChunkParams::new(256, 1000, 4096), Err(CryptoError::InvalidParameter(_))));
+        assert!(matches!(ChunkParams::new(2048, 1024, 4096), Err(CryptoError::InvalidParameter(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn boundaries_resynchronize_after_an_insertion() -> Result<(), CryptoError> {
+        let params = ChunkParams::new(256, 1024, 4096)?;
+        let original = noise(64 * 1024);
+        let at = 30_000;
+        let edited = [&original[..at], b"inserted!!", &original[at..]].concat();
+        let (before, after) = (params.boundaries(&original), params.boundaries(&edited));
+        // Przed wstawka granice identyczne, za nia przesuniete o dlugosc wstawki.
+        let shifted: Vec<usize> = before.iter().map(|&b| if b > at { b + 10 } else { b }).collect();
+        let common = shifted.iter().filter(|b| after.contains(b)).count();
+        assert!(common + 2 >= before.len(), "{common} of {} boundaries kept", before.len());
+
+        let vault_key = MasterKey::from_slice(&VAULT_KEY);
+        let old_ids: Vec<String> =
+            chunk_data_bytes(&vault_key, &original, &params)?.into_iter().map(|c| c.0.id).collect();
+        let new_chunks = chunk_data_bytes(&vault_key, &edited, &params)?;
+        let reused = new_chunks.iter().filter(|c| old_ids.contains(&c.0.id)).count();
+        assert!(reused + 2 >= new_chunks.len());
+        Ok(())
+    }
+
+    #[test]
+    fn chunks_are_convergent_within_a_vault_only() -> Result<(), CryptoError> {
+        let params = ChunkParams::new(256, 1024, 4096)?;
+        let data = noise(10_000);
+        let first = chunk_data_bytes(&MasterKey::from_slice(&VAULT_KEY), &data, &params)?;
+        let again = chunk_data_bytes(&MasterKey::from_slice(&VAULT_KEY), &data, &params)?;
+        let other = chunk_data_bytes(&MasterKey::from_slice(&[0x43; 32]), &data, &params)?;
+        assert_eq!(first, again);
+        assert!(first.iter().zip(&other).all(|(a, b)| a.0.hash == b.0.hash && a.0.id != b.0.id && a.1 != b.1));
+
+        let (chunk_ref, blob) = &first[1];
+        let start = chunk_ref.offset as usize;
+        let plain = decrypt_attachment_chunk(&VAULT_KEY, &chunk_ref.hash, blob)?;
+        assert_eq!(plain, &data[start..start + chunk_ref.size as usize]);
+        assert!(matches!(
+            decrypt_attachment_chunk(&[0x43; 32], &chunk_ref.hash, blob),
+            Err(CryptoError::DecryptFailed(_))
+        ));
+        Ok(())
+    }
+}
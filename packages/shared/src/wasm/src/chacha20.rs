@@ -0,0 +1,512 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+use crate::rng::random_array;
+
+// ChaCha20-Poly1305 (RFC 8439) i XChaCha20-Poly1305 (draft-irtf-cfrg-xchacha): podklucz
+// HChaCha20 z klucza i pierwszych 16 B nonce, reszta nonce (8 B) po 4 bajtach zer.
+// 192-bitowy nonce mozna losowac dla kazdego wpisu bez licznika - kolizja praktycznie
+// niemozliwa, w przeciwienstwie do 96-bitowego nonce GCM.
+// Wynik szyfrowania: ciphertext || tag (16 B); seal/open: nonce (24 B) || ciphertext || tag.
+pub const KEY_LEN: usize = 32;
+pub const NONCE_LEN: usize = 12;
+pub const XNONCE_LEN: usize = 24;
+pub const TAG_LEN: usize = 16;
+
+const SIGMA: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+fn le32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn initial_state(key: &[u8; KEY_LEN], tail: [u32; 4]) -> [u32; 16] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&SIGMA);
+    for (w, chunk) in state[4..12].iter_mut().zip(key.chunks_exact(4)) {
+        *w = le32(chunk);
+    }
+    state[12..].copy_from_slice(&tail);
+    state
+}
+
+fn rounds(state: &mut [u32; 16]) {
+    for _ in 0..10 {
+        quarter_round(state, 0, 4, 8, 12);
+        quarter_round(state, 1, 5, 9, 13);
+        quarter_round(state, 2, 6, 10, 14);
+        quarter_round(state, 3, 7, 11, 15);
+        quarter_round(state, 0, 5, 10, 15);
+        quarter_round(state, 1, 6, 11, 12);
+        quarter_round(state, 2, 7, 8, 13);
+        quarter_round(state, 3, 4, 9, 14);
+    }
+}
+
+pub fn chacha20_block(key: &[u8; KEY_LEN], counter: u32, nonce: &[u8; NONCE_LEN]) -> [u8; 64] {
+    let mut state = initial_state(key, [counter, le32(&nonce[0..4]), le32(&nonce[4..8]), le32(&nonce[8..12])]);
+    let mut working = state;
+    rounds(&mut working);
+    let mut out = [0u8; 64];
+    for (i, (w, s)) in working.iter().zip(&state).enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&w.wrapping_add(*s).to_le_bytes());
+    }
+    working.zeroize();
+    state.zeroize();
+    out
+}
+
+pub fn hchacha20(key: &[u8; KEY_LEN], nonce: &[u8; 16]) -> [u8; 32] {
+    let mut state =
+        initial_state(key, [le32(&nonce[0..4]), le32(&nonce[4..8]), le32(&nonce[8..12]), le32(&nonce[12..16])]);
+    rounds(&mut state);
+    let mut out = [0u8; 32];
+    for (i, w) in state[..4].iter().chain(&state[12..]).enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&w.to_le_bytes());
+    }
+    state.zeroize();
+    out
+}
+
+pub fn chacha20_xor(
+    key: &[u8; KEY_LEN],
+    counter: u32,
+    nonce: &[u8; NONCE_LEN],
+    data: &mut [u8],
+) -> Result<(), CryptoError> {
+    if data.len() as u64 > (u32::MAX as u64 - counter as u64 + 1) * 64 {
+        return Err(CryptoError::OutOfRange("data too long for ChaCha20".to_string()));
+    }
+    for (i, chunk) in data.chunks_mut(64).enumerate() {
+        let mut ks = chacha20_block(key, counter.wrapping_add(i as u32), nonce);
+        for (b, k) in chunk.iter_mut().zip(ks.iter()) {
+            *b ^= k;
+        }
+        ks.zeroize();
+    }
+    Ok(())
+}
+
+// Poly1305 na 26-bitowych limbach (jak poly1305-donna-32) - iloczyny mieszcza sie w u64,
+// bez arytmetyki 128-bitowej emulowanej na wasm32.
+pub struct Poly1305 {
+    r: [u32; 5],
+    pad: [u32; 4],
+    h: [u32; 5],
+}
+
+impl Drop for Poly1305 {
+    fn drop(&mut self) {
+        self.r.zeroize();
+        self.pad.zeroize();
+        self.h.zeroize();
+    }
+}
+
+impl Poly1305 {
+    pub fn new(key: &[u8; 32]) -> Poly1305 {
+        Poly1305 {
+            r: [
+                le32(&key[0..4]) & 0x03ff_ffff,
+                (le32(&key[3..7]) >> 2) & 0x03ff_ff03,
+                (le32(&key[6..10]) >> 4) & 0x03ff_c0ff,
+                (le32(&key[9..13]) >> 6) & 0x03f0_3fff,
+                (le32(&key[12..16]) >> 8) & 0x000f_ffff,
+            ],
+            pad: [le32(&key[16..20]), le32(&key[20..24]), le32(&key[24..28]), le32(&key[28..32])],
+            h: [0; 5],
+        }
+    }
+
+    fn block(&mut self, m: &[u8; 16], hibit: u32) {
+        const MASK: u64 = 0x03ff_ffff;
+        let [r0, r1, r2, r3, r4] = self.r.map(u64::from);
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+        let h0 = (self.h[0] + (le32(&m[0..4]) & 0x03ff_ffff)) as u64;
+        let h1 = (self.h[1] + ((le32(&m[3..7]) >> 2) & 0x03ff_ffff)) as u64;
+        let h2 = (self.h[2] + ((le32(&m[6..10]) >> 4) & 0x03ff_ffff)) as u64;
+        let h3 = (self.h[3] + ((le32(&m[9..13]) >> 6) & 0x03ff_ffff)) as u64;
+        let h4 = (self.h[4] + ((le32(&m[12..16]) >> 8) | hibit)) as u64;
+
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let mut d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let mut d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        d1 += d0 >> 26;
+        d2 += d1 >> 26;
+        d3 += d2 >> 26;
+        d4 += d3 >> 26;
+        let mut h0 = (d0 & MASK) + (d4 >> 26) * 5;
+        let h1 = (d1 & MASK) + (h0 >> 26);
+        h0 &= MASK;
+        self.h = [h0 as u32, h1 as u32, (d2 & MASK) as u32, (d3 & MASK) as u32, (d4 & MASK) as u32];
+    }
+
+    // Dane dopelnione zerami do wielokrotnosci 16 B (tak jak w konstrukcji AEAD).
+    pub fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut m = [0u8; 16];
+            m[..chunk.len()].copy_from_slice(chunk);
+            self.block(&m, 1 << 24);
+            m.zeroize();
+        }
+    }
+
+    // Zwykly MAC wiadomosci: ostatni niepelny blok z bajtem 0x01 zamiast bitu 2^128.
+    pub fn update_message(&mut self, data: &[u8]) {
+        let mut chunks = data.chunks_exact(16);
+        for chunk in &mut chunks {
+            let mut m = [0u8; 16];
+            m.copy_from_slice(chunk);
+            self.block(&m, 1 << 24);
+            m.zeroize();
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut m = [0u8; 16];
+            m[..rest.len()].copy_from_slice(rest);
+            m[rest.len()] = 1;
+            self.block(&m, 0);
+            m.zeroize();
+        }
+    }
+
+    pub fn finalize(self) -> [u8; TAG_LEN] {
+        const MASK: u32 = 0x03ff_ffff;
+        let mut h = self.h;
+        let mut c;
+        for i in 1..5 {
+            c = h[i] >> 26;
+            h[i] &= MASK;
+            if i < 4 {
+                h[i + 1] += c;
+            } else {
+                h[0] += c * 5;
+            }
+        }
+        c = h[0] >> 26;
+        h[0] &= MASK;
+        h[1] += c;
+
+        // g = h + 5 - 2^130; wybor h lub g bez rozgalezien
+        let mut g = [0u32; 5];
+        c = 5;
+        for i in 0..4 {
+            g[i] = h[i] + c;
+            c = g[i] >> 26;
+            g[i] &= MASK;
+        }
+        g[4] = h[4].wrapping_add(c).wrapping_sub(1 << 26);
+        let select = (g[4] >> 31).wrapping_sub(1);
+        for (hi, gi) in h.iter_mut().zip(g.iter()) {
+            *hi = (*hi & !select) | (gi & select);
+        }
+        g.zeroize();
+
+        let words =
+            [h[0] | (h[1] << 26), (h[1] >> 6) | (h[2] << 20), (h[2] >> 12) | (h[3] << 14), (h[3] >> 18) | (h[4] << 8)];
+        h.zeroize();
+        let mut out = [0u8; TAG_LEN];
+        let mut carry = 0u64;
+        for (i, (w, p)) in words.iter().zip(self.pad.iter()).enumerate() {
+            carry += *w as u64 + *p as u64;
+            out[i * 4..i * 4 + 4].copy_from_slice(&(carry as u32).to_le_bytes());
+            carry >>= 32;
+        }
+        out
+    }
+}
+
+pub fn poly1305_bytes(key: &[u8; 32], message: &[u8]) -> [u8; TAG_LEN] {
+    let mut mac = Poly1305::new(key);
+    mac.update_message(message);
+    mac.finalize()
+}
+
+fn key_array(key: &[u8]) -> Result<[u8; KEY_LEN], CryptoError> {
+    key.try_into().map_err(|_| CryptoError::InvalidLength(format!("ChaCha20 key must be {KEY_LEN} bytes")))
+}
+
+pub struct ChaCha20Poly1305 {
+    key: [u8; KEY_LEN],
+}
+
+impl Drop for ChaCha20Poly1305 {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8]) -> Result<ChaCha20Poly1305, CryptoError> {
+        require_approved("chacha20-poly1305")?;
+        Ok(ChaCha20Poly1305 { key: key_array(key)? })
+    }
+
+    fn tag(&self, nonce: &[u8; NONCE_LEN], aad: &[u8], ct: &[u8]) -> [u8; TAG_LEN] {
+        let mut block = chacha20_block(&self.key, 0, nonce);
+        let mut otk = [0u8; 32];
+        otk.copy_from_slice(&block[..32]);
+        block.zeroize();
+        let mut mac = Poly1305::new(&otk);
+        otk.zeroize();
+        mac.update_padded(aad);
+        mac.update_padded(ct);
+        let mut lengths = [0u8; 16];
+        lengths[..8].copy_from_slice(&(aad.len() as u64).to_le_bytes());
+        lengths[8..].copy_from_slice(&(ct.len() as u64).to_le_bytes());
+        mac.update_padded(&lengths);
+        mac.finalize()
+    }
+
+    fn nonce(nonce: &[u8]) -> Result<[u8; NONCE_LEN], CryptoError> {
+        nonce
+            .try_into()
+            .map_err(|_| CryptoError::InvalidLength(format!("ChaCha20-Poly1305 nonce must be {NONCE_LEN} bytes")))
+    }
+
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let nonce = Self::nonce(nonce)?;
+        let mut out = plaintext.to_vec();
+        chacha20_xor(&self.key, 1, &nonce, &mut out)?;
+        let tag = self.tag(&nonce, aad, &out);
+        out.extend_from_slice(&tag);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let nonce = Self::nonce(nonce)?;
+        if ciphertext.len() < TAG_LEN {
+            return Err(CryptoError::InvalidLength("ciphertext shorter than Poly1305 tag".to_string()));
+        }
+        let (ct, tag) = ciphertext.split_at(ciphertext.len() - TAG_LEN);
+        if !ct_eq(&self.tag(&nonce, aad, ct), tag) {
+            return Err(CryptoError::DecryptFailed("authentication tag mismatch".to_string()));
+        }
+        let mut out = ct.to_vec();
+        chacha20_xor(&self.key, 1, &nonce, &mut out)?;
+        Ok(out)
+    }
+}
+
+pub struct XChaCha20Poly1305 {
+    key: [u8; KEY_LEN],
+}
+
+impl Drop for XChaCha20Poly1305 {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl XChaCha20Poly1305 {
+    pub fn new(key: &[u8]) -> Result<XChaCha20Poly1305, CryptoError> {
+        require_approved("xchacha20-poly1305")?;
+        Ok(XChaCha20Poly1305 { key: key_array(key)? })
+    }
+
+    fn inner(&self, nonce: &[u8]) -> Result<(ChaCha20Poly1305, [u8; NONCE_LEN]), CryptoError> {
+        if nonce.len() != XNONCE_LEN {
+            return Err(CryptoError::InvalidLength(format!("XChaCha20-Poly1305 nonce must be {XNONCE_LEN} bytes")));
+        }
+        let mut prefix = [0u8; 16];
+        prefix.copy_from_slice(&nonce[..16]);
+        let mut inner_nonce = [0u8; NONCE_LEN];
+        inner_nonce[4..].copy_from_slice(&nonce[16..]);
+        Ok((ChaCha20Poly1305 { key: hchacha20(&self.key, &prefix) }, inner_nonce))
+    }
+
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let (cipher, nonce) = self.inner(nonce)?;
+        cipher.encrypt(&nonce, aad, plaintext)
+    }
+
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let (cipher, nonce) = self.inner(nonce)?;
+        cipher.decrypt(&nonce, aad, ciphertext)
+    }
+}
+
+// Losowy nonce; wynik: nonce || ciphertext || tag
+pub fn xchacha20_poly1305_seal_bytes(key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let cipher = XChaCha20Poly1305::new(key)?;
+    let nonce = random_array::<XNONCE_LEN>()?;
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&cipher.encrypt(&nonce, aad, plaintext)?);
+    Ok(out)
+}
+
+pub fn xchacha20_poly1305_open_bytes(key: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let cipher = XChaCha20Poly1305::new(key)?;
+    if sealed.len() < XNONCE_LEN + TAG_LEN {
+        return Err(CryptoError::InvalidLength("sealed data too short".to_string()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(XNONCE_LEN);
+    cipher.decrypt(nonce, aad, ciphertext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn xchacha20_poly1305_encrypt(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    XChaCha20Poly1305::new(key)?.encrypt(nonce, aad, plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn xchacha20_poly1305_decrypt(
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    XChaCha20Poly1305::new(key)?.decrypt(nonce, aad, ciphertext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn xchacha20_poly1305_seal(key: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    xchacha20_poly1305_seal_bytes(key, aad, plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn xchacha20_poly1305_open(key: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    xchacha20_poly1305_open_bytes(key, aad, sealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::{bytes_to_hex, hex_to_bytes};
+
+    const SUNSCREEN: &[u8] = b"Ladies and Gentlemen of the class of '99: \
+        If I could offer you only one tip for the future, sunscreen would be it.";
+
+    fn key_00_1f() -> [u8; KEY_LEN] {
+        std::array::from_fn(|i| i as u8)
+    }
+
+    fn nonce12(hex: &str) -> Result<[u8; NONCE_LEN], CryptoError> {
+        hex_to_bytes(hex)?.try_into().map_err(|_| CryptoError::InvalidLength("nonce".to_string()))
+    }
+
+    // RFC 8439, 2.3.2
+    #[test]
+    fn rfc8439_block() -> Result<(), CryptoError> {
+        let block = chacha20_block(&key_00_1f(), 1, &nonce12("000000090000004a00000000")?);
+        assert_eq!(
+            bytes_to_hex(&block),
+            concat!(
+                "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e",
+                "d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+            )
+        );
+        Ok(())
+    }
+
+    // RFC 8439, 2.4.2
+    #[test]
+    fn rfc8439_encryption() -> Result<(), CryptoError> {
+        let mut data = SUNSCREEN.to_vec();
+        chacha20_xor(&key_00_1f(), 1, &nonce12("000000000000004a00000000")?, &mut data)?;
+        assert_eq!(
+            bytes_to_hex(&data),
+            concat!(
+                "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b",
+                "f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8",
+                "07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736",
+                "5af90bbf74a35be6b40b8eedf2785e42874d"
+            )
+        );
+        Ok(())
+    }
+
+    // RFC 8439, 2.5.2
+    #[test]
+    fn rfc8439_poly1305() -> Result<(), CryptoError> {
+        let key: [u8; 32] = hex_to_bytes("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b")?
+            .try_into()
+            .map_err(|_| CryptoError::InvalidLength("key".to_string()))?;
+        let tag = poly1305_bytes(&key, b"Cryptographic Forum Research Group");
+        assert_eq!(bytes_to_hex(&tag), "a8061dc1305136c6c22b8baf0c0127a9");
+        Ok(())
+    }
+
+    // draft-irtf-cfrg-xchacha-03, 2.2.1
+    #[test]
+    fn hchacha20_vector() -> Result<(), CryptoError> {
+        let nonce: [u8; 16] = hex_to_bytes("000000090000004a0000000031415927")?
+            .try_into()
+            .map_err(|_| CryptoError::InvalidLength("nonce".to_string()))?;
+        assert_eq!(
+            bytes_to_hex(&hchacha20(&key_00_1f(), &nonce)),
+            "82413b4227b27bfed30e42508a877d73a0f9e4d58a74a853c12ec41326d3ecdc"
+        );
+        Ok(())
+    }
+
+    // RFC 8439, 2.8.2
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn rfc8439_aead() -> Result<(), CryptoError> {
+        let key: Vec<u8> = (0x80..=0x9f).collect();
+        let nonce = hex_to_bytes("070000004041424344454647")?;
+        let aad = hex_to_bytes("50515253c0c1c2c3c4c5c6c7")?;
+        let cipher = ChaCha20Poly1305::new(&key)?;
+        let sealed = cipher.encrypt(&nonce, &aad, SUNSCREEN)?;
+        assert_eq!(
+            bytes_to_hex(&sealed),
+            concat!(
+                "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6",
+                "3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36",
+                "92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc",
+                "3ff4def08e4b7a9de576d26586cec64b6116",
+                "1ae10b594f09e26a7e902ecbd0600691"
+            )
+        );
+        assert_eq!(cipher.decrypt(&nonce, &aad, &sealed)?, SUNSCREEN);
+        let mut tampered = sealed.clone();
+        tampered[0] ^= 1;
+        assert!(matches!(cipher.decrypt(&nonce, &aad, &tampered), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+
+    // draft-irtf-cfrg-xchacha-03, A.3.1
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn xchacha20_poly1305_aead() -> Result<(), CryptoError> {
+        let key: Vec<u8> = (0x80..=0x9f).collect();
+        let nonce: Vec<u8> = (0x40..=0x57).collect();
+        let aad = hex_to_bytes("50515253c0c1c2c3c4c5c6c7")?;
+        let sealed = xchacha20_poly1305_encrypt(&key, &nonce, &aad, SUNSCREEN)?;
+        assert_eq!(
+            bytes_to_hex(&sealed),
+            concat!(
+                "bd6d179d3e83d43b9576579493c0e939572a1700252bfaccbed2902c21396cbb",
+                "731c7f1b0b4aa6440bf3a82f4eda7e39ae64c6708c54c216cb96b72e1213b452",
+                "2f8c9ba40db5d945b11b69b982c1bb9e3f3fac2bc369488f76b2383565d3fff9",
+                "21f9664c97637da9768812f615c68b13b52e",
+                "c0875924c1c7987947deafd8780acf49"
+            )
+        );
+        assert_eq!(xchacha20_poly1305_decrypt(&key, &nonce, &aad, &sealed)?, SUNSCREEN);
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::gcm::AesGcm;
+use crate::hkdf::{hkdf_expand_sha256, hkdf_extract_sha256};
+use crate::rng::random_array;
+use crate::sha2::{Sha256, sha256_bytes};
+use crate::x25519;
+
+// Szyfrowany kanal rozszerzenie <-> aplikacja desktopowa (native messaging).
+// Handshake z zobowiazaniem (commit-then-reveal), jak porownanie liczb w Bluetooth/ZRTP:
+//   1. I -> R: C = SHA-256(COMMIT_LABEL || pk_I || n_I)
+//   2. R -> I: pk_R || n_R
+//   3. I -> R: pk_I || n_I      (R sprawdza C)
+// Inicjator wiaze sie ze swoim kluczem, zanim zobaczy klucz odpowiadajacego, a ten odslania
+// swoj dopiero po otrzymaniu zobowiazania - posrednik nie moze dobierac kluczy tak, zeby
+// trafic w ten sam kod SAS po obu stronach (jedna proba na 10^6).
+// Transkrypt = SHA-256(CHANNEL_LABEL || C || pk_I || n_I || pk_R || n_R). HKDF (sol =
+// transkrypt, IKM = DH) daje osobny klucz AES-256-GCM dla kazdego kierunku oraz 6-cyfrowy
+// kod SAS (info zawiera tez transkrypt), ktory uzytkownik porownuje w obu aplikacjach.
+// Podmieniony klucz albo nonce zmienia transkrypt, a wiec i SAS.
+// W drzewie nie ma modulu Noise - kanal ma wlasny, powyzszy handshake.
+// Wiadomosc: numer sekwencyjny (u64 BE) || ciphertext || tag. Nonce = 0^4 || seq, wiec
+// para (klucz, nonce) nigdy sie nie powtarza. Kanal stdio jest uporzadkowany, wiec
+// przyjmowany jest tylko dokladnie kolejny numer - powtorki i przestawienia sa odrzucane.
+const CHANNEL_LABEL: &[u8] = b"pm-native-channel-v2\0";
+const COMMIT_LABEL: &[u8] = b"pm-native-channel-commit\0";
+const KEYS_INFO: &[u8] = b"pm-native-channel-keys";
+const SAS_INFO: &[u8] = b"pm-native-channel-sas";
+const SEQ_LEN: usize = 8;
+const NONCE_LEN: usize = 32;
+// Klucz publiczny || nonce (wiadomosci 2 i 3)
+const REVEAL_LEN: usize = 32 + NONCE_LEN;
+
+struct Session {
+    send: AesGcm,
+    recv: AesGcm,
+    send_seq: u64,
+    recv_seq: u64,
+    sas: u32,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct SecureChannel {
+    initiator: bool,
+    ephemeral: [u8; 32],
+    public: [u8; 32],
+    nonce: [u8; NONCE_LEN],
+    // Zobowiazanie inicjatora (u inicjatora wlasne, u odpowiadajacego otrzymane w kroku 1)
+    commitment: Option<[u8; 32]>,
+    session: Option<Session>,
+}
+
+impl Drop for SecureChannel {
+    fn drop(&mut self) {
+        self.ephemeral.zeroize();
+    }
+}
+
+fn nonce(seq: u64) -> [u8; 12] {
+    let mut n = [0u8; 12];
+    n[4..].copy_from_slice(&seq.to_be_bytes());
+    n
+}
+
+fn direction_aad(from_initiator: bool, seq: &[u8]) -> Vec<u8> {
+    let mut aad = CHANNEL_LABEL.to_vec();
+    aad.push(from_initiator as u8);
+    aad.extend_from_slice(seq);
+    aad
+}
+
+fn commit(public: &[u8; 32], nonce: &[u8; NONCE_LEN]) -> [u8; 32] {
+    sha256_bytes(&[COMMIT_LABEL, public, nonce].concat())
+}
+
+fn split_reveal(message: &[u8]) -> Result<([u8; 32], [u8; NONCE_LEN]), CryptoError> {
+    if message.len() != REVEAL_LEN {
+        return Err(CryptoError::InvalidLength(format!("channel handshake message must be {REVEAL_LEN} bytes")));
+    }
+    let (public, nonce) = message.split_at(32);
+    let mut n = [0u8; NONCE_LEN];
+    n.copy_from_slice(nonce);
+    Ok((x25519::to_key(public, "channel handshake")?, n))
+}
+
+fn handshake_state(message: &str) -> CryptoError {
+    CryptoError::InvalidInput(message.to_string())
+}
+
+impl SecureChannel {
+    pub fn with_role(initiator: bool) -> Result<SecureChannel, CryptoError> {
+        let ephemeral = x25519::generate_secret()?;
+        let public = x25519::public_key_bytes(&ephemeral);
+        let nonce = random_array::<NONCE_LEN>()?;
+        let commitment = initiator.then(|| commit(&public, &nonce));
+        Ok(SecureChannel { initiator, ephemeral, public, nonce, commitment, session: None })
+    }
+
+    fn session(&self) -> Result<&Session, CryptoError> {
+        self.session.as_ref().ok_or_else(|| handshake_state("channel handshake not completed"))
+    }
+
+    fn session_mut(&mut self) -> Result<&mut Session, CryptoError> {
+        self.session.as_mut().ok_or_else(|| handshake_state("channel handshake not completed"))
+    }
+
+    fn reveal(&self) -> Vec<u8> {
+        [&self.public[..], &self.nonce].concat()
+    }
+
+    fn establish(&mut self, peer_public: &[u8; 32], peer_nonce: &[u8; NONCE_LEN]) -> Result<(), CryptoError> {
+        let commitment = self.commitment.ok_or_else(|| handshake_state("channel commitment missing"))?;
+        let mine = (&self.public, &self.nonce);
+        let peer = (peer_public, peer_nonce);
+        let ((initiator_pk, initiator_nonce), (responder_pk, responder_nonce)) =
+            if self.initiator { (mine, peer) } else { (peer, mine) };
+        let mut transcript = Sha256::new();
+        for part in [CHANNEL_LABEL, &commitment, initiator_pk, initiator_nonce, responder_pk, responder_nonce] {
+            transcript.update(part);
+        }
+        let transcript = transcript.finalize();
+
+        let mut shared = x25519::shared_secret(&self.ephemeral, peer_public)?;
+        self.ephemeral.zeroize();
+        let mut prk = hkdf_extract_sha256(&transcript, &shared);
+        shared.zeroize();
+        let keys = hkdf_expand_sha256(&prk, KEYS_INFO, 64);
+        let sas = hkdf_expand_sha256(&prk, &[SAS_INFO, &transcript].concat(), 8);
+        prk.zeroize();
+        let mut keys = keys?;
+        let sas = sas?;
+
+        let (i2r, r2i) = keys.split_at(32);
+        let (send, recv) = if self.initiator { (i2r, r2i) } else { (r2i, i2r) };
+        let mut code = [0u8; 8];
+        code.copy_from_slice(&sas);
+        let session = Session {
+            send: AesGcm::new(send)?,
+            recv: AesGcm::new(recv)?,
+            send_seq: 0,
+            recv_seq: 0,
+            // 64 bity mod 10^6 - obciazenie pomijalne (< 2^-40)
+            sas: (u64::from_be_bytes(code) % 1_000_000) as u32,
+        };
+        keys.zeroize();
+        self.session = Some(session);
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl SecureChannel {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(initiator: bool) -> Result<SecureChannel, CryptoError> {
+        SecureChannel::with_role(initiator)
+    }
+
+    // Krok 1 (inicjator): zobowiazanie do wyslania odpowiadajacemu.
+    pub fn commitment(&self) -> Result<Vec<u8>, CryptoError> {
+        match (self.initiator, self.commitment) {
+            (true, Some(commitment)) => Ok(commitment.to_vec()),
+            _ => Err(handshake_state("only the initiator sends a channel commitment")),
+        }
+    }
+
+    // Krok 2 (odpowiadajacy): przyjmuje zobowiazanie, zwraca wlasny klucz i nonce do wyslania.
+    pub fn accept_commitment(&mut self, commitment: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if self.initiator || self.commitment.is_some() {
+            return Err(handshake_state("channel commitment already accepted or sent by this side"));
+        }
+        let commitment: [u8; 32] = commitment
+            .try_into()
+            .map_err(|_| CryptoError::InvalidLength("channel commitment must be 32 bytes".to_string()))?;
+        self.commitment = Some(commitment);
+        Ok(self.reveal())
+    }
+
+    // Inicjator: wiadomosc z kroku 2, zwraca odsloniecie (krok 3) do wyslania.
+    // Odpowiadajacy: odsloniecie z kroku 3, sprawdza zobowiazanie; zwraca pusta wiadomosc.
+    pub fn complete_handshake(&mut self, peer_message: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if self.session.is_some() {
+            return Err(handshake_state("channel handshake already completed"));
+        }
+        let (peer_public, peer_nonce) = split_reveal(peer_message)?;
+        if self.initiator {
+            self.establish(&peer_public, &peer_nonce)?;
+            return Ok(self.reveal());
+        }
+        let commitment = self.commitment.ok_or_else(|| handshake_state("channel commitment not received yet"))?;
+        if !ct_eq(&commit(&peer_public, &peer_nonce), &commitment) {
+            return Err(CryptoError::VerificationFailed("channel key does not match the commitment".to_string()));
+        }
+        self.establish(&peer_public, &peer_nonce)?;
+        Ok(Vec::new())
+    }
+
+    // Kod do porownania przez uzytkownika, np. "042817".
+    pub fn sas(&self) -> Result<String, CryptoError> {
+        Ok(format!("{:06}", self.session()?.sas))
+    }
+
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let initiator = self.initiator;
+        let session = self.session_mut()?;
+        let seq = session.send_seq;
+        session.send_seq = seq
+            .checked_add(1)
+            .ok_or_else(|| CryptoError::InvalidInput("channel sequence number exhausted".to_string()))?;
+        let seq_bytes = seq.to_be_bytes();
+        let sealed = session.send.encrypt(&nonce(seq), &direction_aad(initiator, &seq_bytes), plaintext)?;
+        let mut out = seq_bytes.to_vec();
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    pub fn decrypt(&mut self, message: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let from_initiator = !self.initiator;
+        let session = self.session_mut()?;
+        if message.len() < SEQ_LEN {
+            return Err(CryptoError::InvalidLength("channel message too short".to_string()));
+        }
+        let (seq_bytes, sealed) = message.split_at(SEQ_LEN);
+        let mut seq = [0u8; SEQ_LEN];
+        seq.copy_from_slice(seq_bytes);
+        let seq = u64::from_be_bytes(seq);
+        if seq < session.recv_seq {
+            return Err(CryptoError::VerificationFailed(format!("replayed channel message {seq}")));
+        }
+        if seq > session.recv_seq {
+            return Err(CryptoError::VerificationFailed(format!(
+                "out-of-order channel message {seq}, expected {}",
+                session.recv_seq
+            )));
+        }
+        let plaintext = session.recv.decrypt(&nonce(seq), &direction_aad(from_initiator, seq_bytes), sealed)?;
+        session.recv_seq += 1;
+        Ok(plaintext)
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn established(&self) -> bool {
+        self.session.is_some()
+    }
+}
+
+// X25519 jest poza profilem FIPS - tam kanal nie powstaje (SecureChannel::new zwraca blad).
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+
+    // Pelny handshake; zwraca (inicjator, odpowiadajacy).
+    fn connect() -> Result<(SecureChannel, SecureChannel), CryptoError> {
+        let mut initiator = SecureChannel::new(true)?;
+        let mut responder = SecureChannel::new(false)?;
+        let offer = responder.accept_commitment(&initiator.commitment()?)?;
+        let reveal = initiator.complete_handshake(&offer)?;
+        assert!(responder.complete_handshake(&reveal)?.is_empty());
+        Ok((initiator, responder))
+    }
+
+    #[test]
+    fn handshake_agrees_on_keys_and_sas() -> Result<(), CryptoError> {
+        let (mut initiator, mut responder) = connect()?;
+        assert_eq!(initiator.sas()?, responder.sas()?);
+        assert_eq!(initiator.sas()?.len(), 6);
+        let message = initiator.encrypt(b"fill github.com")?;
+        assert_eq!(responder.decrypt(&message)?, b"fill github.com");
+        let reply = responder.encrypt(b"ok")?;
+        assert_eq!(initiator.decrypt(&reply)?, b"ok");
+        Ok(())
+    }
+
+    #[test]
+    fn substituted_keys_change_the_sas() -> Result<(), CryptoError> {
+        // Posrednik prowadzi osobny handshake z kazda strona i podmienia klucze
+        let mut initiator = SecureChannel::new(true)?;
+        let mut responder = SecureChannel::new(false)?;
+        let mut fake_responder = SecureChannel::new(false)?;
+        let mut fake_initiator = SecureChannel::new(true)?;
+
+        let offer = fake_responder.accept_commitment(&initiator.commitment()?)?;
+        fake_responder.complete_handshake(&initiator.complete_handshake(&offer)?)?;
+        let offer = responder.accept_commitment(&fake_initiator.commitment()?)?;
+        responder.complete_handshake(&fake_initiator.complete_handshake(&offer)?)?;
+
+        assert_eq!(initiator.sas()?, fake_responder.sas()?);
+        assert_eq!(responder.sas()?, fake_initiator.sas()?);
+        assert_ne!(initiator.sas()?, responder.sas()?);
+        Ok(())
+    }
+
+    #[test]
+    fn reveal_must_match_the_commitment() -> Result<(), CryptoError> {
+        let mut initiator = SecureChannel::new(true)?;
+        let mut responder = SecureChannel::new(false)?;
+        let offer = responder.accept_commitment(&initiator.commitment()?)?;
+        let mut reveal = initiator.complete_handshake(&offer)?;
+        // Klucz podmieniony po zobaczeniu klucza odpowiadajacego
+        let mut other = SecureChannel::new(true)?;
+        reveal[..32].copy_from_slice(&other.complete_handshake(&offer)?[..32]);
+        assert!(matches!(responder.complete_handshake(&reveal), Err(CryptoError::VerificationFailed(_))));
+        assert!(!responder.established());
+        Ok(())
+    }
+
+    #[test]
+    fn handshake_steps_out_of_order_are_rejected() -> Result<(), CryptoError> {
+        let mut initiator = SecureChannel::new(true)?;
+        let mut responder = SecureChannel::new(false)?;
+        assert!(matches!(responder.commitment(), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(initiator.accept_commitment(&[0u8; 32]), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(responder.complete_handshake(&[9u8; REVEAL_LEN]), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(responder.accept_commitment(&[0u8; 31]), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(initiator.encrypt(b"too early"), Err(CryptoError::InvalidInput(_))));
+        let (mut initiator, _) = connect()?;
+        assert!(matches!(initiator.complete_handshake(&[9u8; REVEAL_LEN]), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn replayed_and_reordered_messages_are_rejected() -> Result<(), CryptoError> {
+        let (mut initiator, mut responder) = connect()?;
+        let first = initiator.encrypt(b"one")?;
+        let second = initiator.encrypt(b"two")?;
+        assert!(matches!(responder.decrypt(&second), Err(CryptoError::VerificationFailed(_))));
+        assert_eq!(responder.decrypt(&first)?, b"one");
+        assert!(matches!(responder.decrypt(&first), Err(CryptoError::VerificationFailed(_))));
+        let mut tampered = second.clone();
+        tampered[SEQ_LEN] ^= 1;
+        assert!(responder.decrypt(&tampered).is_err());
+        assert_eq!(responder.decrypt(&second)?, b"two");
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::digest::{DigestState, HashAlgorithm};
+
+// Weryfikacja skrotu w trakcie strumieniowania zalacznika.
+// Po przekroczeniu oczekiwanej dlugosci weryfikator jest trwale oznaczony jako nieudany.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct ChecksumVerifier {
+    state: DigestState,
+    expected_digest: Vec<u8>,
+    expected_len: u64,
+    processed: u64,
+    failed: bool,
+}
+
+impl ChecksumVerifier {
+    pub fn with_algorithm(
+        algorithm: HashAlgorithm,
+        expected_digest: &[u8],
+        expected_len: u64,
+    ) -> Result<ChecksumVerifier, CryptoError> {
+        if expected_digest.len() != algorithm.output_len() {
+            return Err(CryptoError::InvalidLength(format!(
+                "expected digest must be {} bytes for {}",
+                algorithm.output_len(),
+                algorithm.name()
+            )));
+        }
+        Ok(ChecksumVerifier {
+            state: DigestState::new(algorithm),
+            expected_digest: expected_digest.to_vec(),
+            expected_len,
+            processed: 0,
+            failed: false,
+        })
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl ChecksumVerifier {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(algorithm: &str, expected_digest: &[u8], expected_len: u64) -> Result<ChecksumVerifier, CryptoError> {
+        ChecksumVerifier::with_algorithm(HashAlgorithm::from_name(algorithm)?, expected_digest, expected_len)
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) -> Result<(), CryptoError> {
+        if self.failed {
+            return Err(CryptoError::VerificationFailed("checksum verification already failed".to_string()));
+        }
+        let remaining = self.expected_len - self.processed;
+        if chunk.len() as u64 > remaining {
+            self.failed = true;
+            return Err(CryptoError::InvalidLength(format!(
+                "data exceeds expected length of {} bytes",
+                self.expected_len
+            )));
+        }
+        self.state.update(chunk);
+        self.processed += chunk.len() as u64;
+        Ok(())
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn processed(&self) -> u64 {
+        self.processed
+    }
+
+    pub fn finalize(self) -> Result<(), CryptoError> {
+        if self.failed {
+            return Err(CryptoError::VerificationFailed("checksum verification already failed".to_string()));
+        }
+        if self.processed != self.expected_len {
+            return Err(CryptoError::InvalidLength(format!(
+                "length mismatch: expected {} bytes, got {}",
+                self.expected_len, self.processed
+            )));
+        }
+        let digest = self.state.finalize();
+        if !ct_eq(&digest, &self.expected_digest) {
+            return Err(CryptoError::VerificationFailed("checksum mismatch".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+
+    // SHA-256("abc")
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn chunked_data_matches_digest() -> Result<(), CryptoError> {
+        let mut verifier = ChecksumVerifier::new("sha-256", &hex_to_bytes(ABC_SHA256)?, 3)?;
+        verifier.update(b"a")?;
+        verifier.update(b"")?;
+        verifier.update(b"bc")?;
+        assert_eq!(verifier.processed(), 3);
+        verifier.finalize()?;
+
+        let mut verifier = ChecksumVerifier::new("sha-256", &hex_to_bytes(ABC_SHA256)?, 3)?;
+        verifier.update(b"abd")?;
+        assert!(matches!(verifier.finalize(), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn length_guard_aborts_early() -> Result<(), CryptoError> {
+        let mut verifier = ChecksumVerifier::new("sha-256", &hex_to_bytes(ABC_SHA256)?, 3)?;
+        verifier.update(b"ab")?;
+        assert!(matches!(verifier.update(b"cd"), Err(CryptoError::InvalidLength(_))));
+        // Po przekroczeniu weryfikator zostaje nieudany, nawet dla pasujacej reszty
+        assert!(matches!(verifier.update(b"c"), Err(CryptoError::VerificationFailed(_))));
+        assert_eq!(verifier.processed(), 2);
+        assert!(matches!(verifier.finalize(), Err(CryptoError::VerificationFailed(_))));
+
+        let mut short = ChecksumVerifier::new("sha-256", &hex_to_bytes(ABC_SHA256)?, 3)?;
+        short.update(b"ab")?;
+        assert!(matches!(short.finalize(), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn expected_digest_length_is_checked() -> Result<(), CryptoError> {
+        let digest = hex_to_bytes(ABC_SHA256)?;
+        assert!(matches!(ChecksumVerifier::new("sha-256", &digest[..31], 3), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(ChecksumVerifier::new("sha-512", &digest, 3), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(ChecksumVerifier::new("md5", &digest, 3), Err(CryptoError::UnsupportedAlgorithm(_))));
+        Ok(())
+    }
+}
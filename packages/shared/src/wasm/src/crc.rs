@@ -0,0 +1,116 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+// CRC32 (IEEE, zlib/PNG) i CRC32C (Castagnoli, iSCSI) - tylko do wykrywania
+// przypadkowych uszkodzen, nie chroni przed celowa modyfikacja (od tego jest tag AEAD).
+const CRC32_POLY: u32 = 0xedb88320;
+const CRC32C_POLY: u32 = 0x82f63b78;
+
+static CRC32_TABLES: [[u32; 256]; 8] = make_tables(CRC32_POLY);
+static CRC32C_TABLES: [[u32; 256]; 8] = make_tables(CRC32C_POLY);
+
+const fn make_tables(poly: u32) -> [[u32; 256]; 8] {
+    let mut t = [[0u32; 256]; 8];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ poly } else { crc >> 1 };
+            j += 1;
+        }
+        t[0][i] = crc;
+        i += 1;
+    }
+    let mut k = 1;
+    while k < 8 {
+        let mut i = 0;
+        while i < 256 {
+            let prev = t[k - 1][i];
+            t[k][i] = (prev >> 8) ^ t[0][(prev & 0xff) as usize];
+            i += 1;
+        }
+        k += 1;
+    }
+    t
+}
+
+// slicing-by-8: 8 bajtow na iteracje, reszta bajt po bajcie
+fn update(t: &[[u32; 256]; 8], crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    let mut chunks = data.chunks_exact(8);
+    for c in &mut chunks {
+        let one = u32::from_le_bytes([c[0], c[1], c[2], c[3]]) ^ crc;
+        let two = u32::from_le_bytes([c[4], c[5], c[6], c[7]]);
+        crc = t[7][(one & 0xff) as usize]
+            ^ t[6][((one >> 8) & 0xff) as usize]
+            ^ t[5][((one >> 16) & 0xff) as usize]
+            ^ t[4][(one >> 24) as usize]
+            ^ t[3][(two & 0xff) as usize]
+            ^ t[2][((two >> 8) & 0xff) as usize]
+            ^ t[1][((two >> 16) & 0xff) as usize]
+            ^ t[0][(two >> 24) as usize];
+    }
+    for &b in chunks.remainder() {
+        crc = t[0][((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+pub fn crc32_bytes(data: &[u8]) -> u32 {
+    update(&CRC32_TABLES, 0, data)
+}
+
+pub fn crc32c_bytes(data: &[u8]) -> u32 {
+    update(&CRC32C_TABLES, 0, data)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn crc32(data: &[u8]) -> u32 {
+    crc32_bytes(data)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn crc32c(data: &[u8]) -> u32 {
+    crc32c_bytes(data)
+}
+
+// Kontynuacja sumy dla kolejnych kawalkow: crc32_update(crc32_update(0, a), b) == crc32(a ++ b)
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn crc32_update(crc: u32, chunk: &[u8]) -> u32 {
+    update(&CRC32_TABLES, crc, chunk)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn crc32c_update(crc: u32, chunk: &[u8]) -> u32 {
+    update(&CRC32C_TABLES, crc, chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOX: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn check_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32c(b"123456789"), 0xe3069283);
+        assert_eq!(crc32(FOX), 0x414fa339);
+        assert_eq!(crc32c(FOX), 0x22620404);
+    }
+
+    #[test]
+    fn unaligned_input_and_chunking() {
+        // 42 bajty od przesuniecia 1 - petla po 8 bajtow i reszta, poza wyrownaniem bufora
+        let data = &FOX[1..];
+        assert_eq!(crc32(data), 0xf5786573);
+        assert_eq!(crc32c(data), 0xae11f7f5);
+        for split in [0, 3, 8, 13, data.len()] {
+            let (a, b) = data.split_at(split);
+            assert_eq!(crc32_update(crc32_update(0, a), b), 0xf5786573);
+            assert_eq!(crc32c_update(crc32c_update(0, a), b), 0xae11f7f5);
+        }
+    }
+}
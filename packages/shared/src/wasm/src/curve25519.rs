@@ -0,0 +1,262 @@
+use zeroize::Zeroize;
+
+// Arytmetyka w ciele GF(2^255 - 19), 5 limbow po 51 bitow (mnozenie na u128).
+// Wspolna dla Ed25519 i X25519. Wszystkie operacje sa w stalym czasie.
+const MASK51: u64 = (1 << 51) - 1;
+
+#[derive(Clone, Copy)]
+pub struct Fe(pub [u64; 5]);
+
+pub const FE_D: [u8; 32] = [
+    0xa3, 0x78, 0x59, 0x13, 0xca, 0x4d, 0xeb, 0x75, 0xab, 0xd8, 0x41, 0x41, 0x4d, 0x0a, 0x70, 0x00,
+    0x98, 0xe8, 0x79, 0x77, 0x79, 0x40, 0xc7, 0x8c, 0x73, 0xfe, 0x6f, 0x2b, 0xee, 0x6c, 0x03, 0x52,
+];
+
+pub const FE_D2: [u8; 32] = [
+    0x59, 0xf1, 0xb2, 0x26, 0x94, 0x9b, 0xd6, 0xeb, 0x56, 0xb1, 0x83, 0x82, 0x9a, 0x14, 0xe0, 0x00,
+    0x30, 0xd1, 0xf3, 0xee, 0xf2, 0x80, 0x8e, 0x19, 0xe7, 0xfc, 0xdf, 0x56, 0xdc, 0xd9, 0x06, 0x24,
+];
+
+pub const FE_SQRT_M1: [u8; 32] = [
+    0xb0, 0xa0, 0x0e, 0x4a, 0x27, 0x1b, 0xee, 0xc4, 0x78, 0xe4, 0x2f, 0xad, 0x06, 0x18, 0x43, 0x2f,
+    0xa7, 0xd7, 0xfb, 0x3d, 0x99, 0x00, 0x4d, 0x2b, 0x0b, 0xdf, 0xc1, 0x4f, 0x80, 0x24, 0x83, 0x2b,
+];
+
+impl Zeroize for Fe {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+fn load8(b: &[u8], i: usize) -> u64 {
+    u64::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3], b[i + 4], b[i + 5], b[i + 6], b[i + 7]])
+}
+
+impl Fe {
+    pub const ZERO: Fe = Fe([0, 0, 0, 0, 0]);
+    pub const ONE: Fe = Fe([1, 0, 0, 0, 0]);
+
+    // Najstarszy bit (255) jest ignorowany.
+    pub fn from_bytes(b: &[u8; 32]) -> Fe {
+        Fe([
+            load8(b, 0) & MASK51,
+            (load8(b, 6) >> 3) & MASK51,
+            (load8(b, 12) >> 6) & MASK51,
+            (load8(b, 19) >> 1) & MASK51,
+            (load8(b, 24) >> 12) & MASK51,
+        ])
+    }
+
+    pub fn from_u64(v: u64) -> Fe {
+        Fe([v & MASK51, v >> 51, 0, 0, 0])
+    }
+
+    // Kanoniczna postac (< p), little-endian.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut l = self.carry().0;
+
+        let mut q = (l[0] + 19) >> 51;
+        q = (l[1] + q) >> 51;
+        q = (l[2] + q) >> 51;
+        q = (l[3] + q) >> 51;
+        q = (l[4] + q) >> 51;
+
+        l[0] += 19 * q;
+        l[1] += l[0] >> 51;
+        l[0] &= MASK51;
+        l[2] += l[1] >> 51;
+        l[1] &= MASK51;
+        l[3] += l[2] >> 51;
+        l[2] &= MASK51;
+        l[4] += l[3] >> 51;
+        l[3] &= MASK51;
+        l[4] &= MASK51;
+
+        let mut out = [0u8; 32];
+        let mut acc: u128 = 0;
+        let mut bits = 0;
+        let mut idx = 0;
+        for limb in l {
+            acc |= (limb as u128) << bits;
+            bits += 51;
+            while bits >= 8 {
+                out[idx] = acc as u8;
+                acc >>= 8;
+                bits -= 8;
+                idx += 1;
+            }
+        }
+        out[idx] = acc as u8;
+        out
+    }
+
+    fn carry(&self) -> Fe {
+        let mut l = self.0;
+        let c = l[0] >> 51;
+        l[0] &= MASK51;
+        l[1] += c;
+        let c = l[1] >> 51;
+        l[1] &= MASK51;
+        l[2] += c;
+        let c = l[2] >> 51;
+        l[2] &= MASK51;
+        l[3] += c;
+        let c = l[3] >> 51;
+        l[3] &= MASK51;
+        l[4] += c;
+        let c = l[4] >> 51;
+        l[4] &= MASK51;
+        l[0] += c * 19;
+        Fe(l)
+    }
+
+    pub fn add(&self, rhs: &Fe) -> Fe {
+        let a = self.0;
+        let b = rhs.0;
+        Fe([a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3], a[4] + b[4]]).carry()
+    }
+
+    // a + 4p - b, zeby uniknac ujemnych limbow
+    pub fn sub(&self, rhs: &Fe) -> Fe {
+        let a = self.0;
+        let b = rhs.0;
+        Fe([
+            (a[0] + 0x1fffffffffffb4) - b[0],
+            (a[1] + 0x1ffffffffffffc) - b[1],
+            (a[2] + 0x1ffffffffffffc) - b[2],
+            (a[3] + 0x1ffffffffffffc) - b[3],
+            (a[4] + 0x1ffffffffffffc) - b[4],
+        ])
+        .carry()
+    }
+
+    pub fn neg(&self) -> Fe {
+        Fe::ZERO.sub(self)
+    }
+
+    pub fn mul(&self, rhs: &Fe) -> Fe {
+        let a = self.0;
+        let b = rhs.0;
+        let m = |x: u64, y: u64| (x as u128) * (y as u128);
+
+        let b1_19 = b[1] * 19;
+        let b2_19 = b[2] * 19;
+        let b3_19 = b[3] * 19;
+        let b4_19 = b[4] * 19;
+
+        let c0 = m(a[0], b[0]) + m(a[4], b1_19) + m(a[3], b2_19) + m(a[2], b3_19) + m(a[1], b4_19);
+        let mut c1 = m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b2_19) + m(a[3], b3_19) + m(a[2], b4_19);
+        let mut c2 = m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b3_19) + m(a[3], b4_19);
+        let mut c3 = m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b4_19);
+        let mut c4 = m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]);
+
+        c1 += c0 >> 51;
+        c2 += c1 >> 51;
+        c3 += c2 >> 51;
+        c4 += c3 >> 51;
+        let l0 = (c0 as u64 & MASK51) as u128 + (c4 >> 51) * 19;
+
+        Fe([
+            l0 as u64 & MASK51,
+            (c1 as u64 & MASK51) + (l0 >> 51) as u64,
+            c2 as u64 & MASK51,
+            c3 as u64 & MASK51,
+            c4 as u64 & MASK51,
+        ])
+    }
+
+    pub fn square(&self) -> Fe {
+        self.mul(self)
+    }
+
+    pub fn mul_small(&self, k: u64) -> Fe {
+        self.mul(&Fe::from_u64(k))
+    }
+
+    fn pow2k(&self, k: u32) -> Fe {
+        let mut r = *self;
+        for _ in 0..k {
+            r = r.square();
+        }
+        r
+    }
+
+    // (x^(2^250 - 1), x^11)
+    fn pow22501(&self) -> (Fe, Fe) {
+        let t0 = self.square();
+        let t1 = t0.square().square();
+        let t2 = self.mul(&t1);
+        let t3 = t0.mul(&t2);
+        let t4 = t3.square();
+        let t5 = t2.mul(&t4);
+        let t6 = t5.pow2k(5);
+        let t7 = t6.mul(&t5);
+        let t8 = t7.pow2k(10);
+        let t9 = t8.mul(&t7);
+        let t10 = t9.pow2k(20);
+        let t11 = t10.mul(&t9);
+        let t12 = t11.pow2k(10);
+        let t13 = t12.mul(&t7);
+        let t14 = t13.pow2k(50);
+        let t15 = t14.mul(&t13);
+        let t16 = t15.pow2k(100);
+        let t17 = t16.mul(&t15);
+        let t18 = t17.pow2k(50);
+        let t19 = t18.mul(&t13);
+        (t19, t3)
+    }
+
+    // x^(p-2)
+    pub fn invert(&self) -> Fe {
+        let (t19, t3) = self.pow22501();
+        t19.pow2k(5).mul(&t3)
+    }
+
+    // x^((p-5)/8)
+    fn pow_p58(&self) -> Fe {
+        let (t19, _) = self.pow22501();
+        t19.pow2k(2).mul(self)
+    }
+
+    // Pierwiastek z u/v; zwraca (true, r) gdy u/v jest reszta kwadratowa.
+    pub fn sqrt_ratio(u: &Fe, v: &Fe) -> (bool, Fe) {
+        let v3 = v.square().mul(v);
+        let v7 = v3.square().mul(v);
+        let mut r = u.mul(&v3).mul(&u.mul(&v7).pow_p58());
+        let check = v.mul(&r.square());
+
+        let correct = check.ct_eq(u);
+        let flipped = check.ct_eq(&u.neg());
+        let r_prime = r.mul(&Fe::from_bytes(&FE_SQRT_M1));
+        r.cmov(&r_prime, flipped);
+        (correct | flipped, r)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.ct_eq(&Fe::ZERO)
+    }
+
+    pub fn ct_eq(&self, other: &Fe) -> bool {
+        crate::ct::ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+
+    pub fn cmov(&mut self, other: &Fe, choice: bool) {
+        let mask = 0u64.wrapping_sub(choice as u64);
+        for i in 0..5 {
+            self.0[i] ^= mask & (self.0[i] ^ other.0[i]);
+        }
+    }
+
+    pub fn cswap(a: &mut Fe, b: &mut Fe, choice: bool) {
+        let mask = 0u64.wrapping_sub(choice as u64);
+        for i in 0..5 {
+            let t = mask & (a.0[i] ^ b.0[i]);
+            a.0[i] ^= t;
+            b.0[i] ^= t;
+        }
+    }
+}
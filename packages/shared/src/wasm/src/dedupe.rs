@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::audit::Fingerprinter;
+use crate::error::CryptoError;
+use crate::import::{ImportedItem, LoginData, parse_items};
+use crate::item::VaultItem;
+use crate::siphash::SessionSipHash;
+use crate::url::canonicalize_url;
+
+// Wykrywanie zduplikowanych loginow (typowe po kolejnych importach CSV). Dwa loginy sa
+// duplikatami, gdy maja ta sama strone, nazwe uzytkownika (bez wielkosci liter i bialych
+// znakow na brzegach) i haslo - porownywane po odcisku HMAC z audytu, nie jawnie.
+// Strona to kazdy adres z uris po canonicalize_url (host[:port]); login bez poprawnego
+// adresu porownywany jest po nazwie wpisu. Wspolna strona laczy grupy przechodnio.
+// Propozycja scalenia: zostaje wpis z vaulta (najnowszy), a przy samym imporcie pierwszy
+// z pliku; pozostale do usuniecia, ich adresy z innych hostow do dopisania.
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemRef {
+    Vault(String),
+    // Indeks w liscie importu - importy CSV czesto nie maja id
+    Import(usize),
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct MergeProposal {
+    pub keep: ItemRef,
+    pub duplicates: Vec<ItemRef>,
+    pub add_uris: Vec<String>,
+}
+
+pub struct Candidate<'a> {
+    pub item: ItemRef,
+    pub name: String,
+    pub login: &'a LoginData,
+    pub updated_at: u64,
+}
+
+impl<'a> Candidate<'a> {
+    pub fn from_vault(item: &'a VaultItem) -> Option<Candidate<'a>> {
+        Some(Candidate {
+            item: ItemRef::Vault(item.id()),
+            name: item.name(),
+            login: item.login()?,
+            updated_at: item.updated_at(),
+        })
+    }
+
+    pub fn from_import(index: usize, item: &'a ImportedItem) -> Option<Candidate<'a>> {
+        Some(Candidate {
+            item: ItemRef::Import(index),
+            name: item.name.clone(),
+            login: item.login.as_ref()?,
+            updated_at: 0,
+        })
+    }
+
+    // Wpis z vaulta przed importem, potem nowszy; przy remisie wczesniejszy na liscie.
+    fn rank(&self) -> (bool, u64) {
+        (matches!(self.item, ItemRef::Vault(_)), self.updated_at)
+    }
+
+    fn keys(&self, fingerprinter: &Fingerprinter) -> Vec<(String, String, [u8; 32])> {
+        let Some(fingerprint) = self.login.password.as_deref().and_then(|p| fingerprinter.password(p)) else {
+            return Vec::new();
+        };
+        let username = self.login.username.as_deref().unwrap_or("").trim().to_lowercase();
+        let mut sites: Vec<String> = self.login.uris.iter().filter_map(|uri| canonicalize_url(uri).ok()).collect();
+        if sites.is_empty() {
+            sites.push(format!("name:{}", self.name.trim().to_lowercase()));
+        }
+        sites.into_iter().map(|site| (site, username.clone(), fingerprint)).collect()
+    }
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+// Klucze (strona, uzytkownik) pochodza z importowanego pliku, wiec tablica jest haszowana
+// SipHash z kluczem sesji - spreparowany CSV nie wymusi kolizji.
+pub fn find_duplicates(candidates: &[Candidate], fingerprinter: &Fingerprinter) -> Result<Vec<MergeProposal>, CryptoError> {
+    let mut parent: Vec<usize> = (0..candidates.len()).collect();
+    let mut first_with_key = HashMap::with_hasher(SessionSipHash::new()?);
+    for (i, candidate) in candidates.iter().enumerate() {
+        for key in candidate.keys(fingerprinter) {
+            let first = *first_with_key.entry(key).or_insert(i);
+            let (a, b) = (find(&mut parent, first), find(&mut parent, i));
+            parent[a.max(b)] = a.min(b);
+        }
+    }
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..candidates.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+    let mut proposals = Vec::new();
+    for members in groups.into_values().filter(|m| m.len() > 1) {
+        let mut keep = members[0];
+        for &i in &members[1..] {
+            if candidates[i].rank() > candidates[keep].rank() {
+                keep = i;
+            }
+        }
+        // Adresy duplikatow na stronach, ktorych zachowany wpis jeszcze nie ma
+        let site = |uri: &String| canonicalize_url(uri).unwrap_or_else(|_| uri.clone());
+        let mut sites: Vec<String> = candidates[keep].login.uris.iter().map(site).collect();
+        let mut add_uris = Vec::new();
+        for &i in members.iter().filter(|&&i| i != keep) {
+            for uri in &candidates[i].login.uris {
+                if !sites.contains(&site(uri)) {
+                    sites.push(site(uri));
+                    add_uris.push(uri.clone());
+                }
+            }
+        }
+        proposals.push(MergeProposal {
+            keep: candidates[keep].item.clone(),
+            duplicates: members.iter().filter(|&&i| i != keep).map(|&i| candidates[i].item.clone()).collect(),
+            add_uris,
+        });
+    }
+    Ok(proposals)
+}
+
+pub fn vault_duplicates(items: &[VaultItem], fingerprinter: &Fingerprinter) -> Result<Vec<MergeProposal>, CryptoError> {
+    let candidates: Vec<Candidate> = items.iter().filter_map(Candidate::from_vault).collect();
+    find_duplicates(&candidates, fingerprinter)
+}
+
+// Duplikaty w obrebie importu i wzgledem wpisow juz w vaulcie.
+pub fn import_duplicates(imported: &[ImportedItem], existing: &[VaultItem]) -> Result<Vec<MergeProposal>, CryptoError> {
+    let candidates: Vec<Candidate> = existing
+        .iter()
+        .filter_map(Candidate::from_vault)
+        .chain(imported.iter().enumerate().filter_map(|(i, item)| Candidate::from_import(i, item)))
+        .collect();
+    find_duplicates(&candidates, &Fingerprinter::new()?)
+}
+
+// imported - wynik import_* ({"items": [...]}); existing - tablica JSON wpisow vaulta albo pusta.
+// Wynik: tablica {keep, duplicates, add_uris}, gdzie odwolanie to {"vault": id} albo {"import": indeks}.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn find_import_duplicates(imported: &str, existing: Option<String>) -> Result<String, CryptoError> {
+    let imported = parse_items(imported)?;
+    let existing = match existing {
+        Some(json) => {
+            let values: Vec<Value> =
+                serde_json::from_str(&json).map_err(|e| CryptoError::InvalidInput(format!("malformed items: {e}")))?;
+            values.iter().map(|value| VaultItem::from_json(&value.to_string())).collect::<Result<Vec<_>, _>>()?
+        }
+        None => Vec::new(),
+    };
+    let proposals = import_duplicates(&imported, &existing)?;
+    serde_json::to_string(&proposals).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::ItemType;
+
+    fn imported(name: &str, uris: &[&str], username: &str, password: &str) -> ImportedItem {
+        let login = LoginData {
+            username: Some(username.to_string()),
+            password: Some(password.to_string()),
+            uris: uris.iter().map(|uri| uri.to_string()).collect(),
+            totp: None,
+        };
+        ImportedItem { name: name.to_string(), kind: ItemType::Login, login: Some(login), ..Default::default() }
+    }
+
+    fn vault(id: &str, item: &ImportedItem, updated_at: u64) -> Result<VaultItem, CryptoError> {
+        let mut out = VaultItem::create(id, ItemType::Login, &item.name, updated_at)?;
+        let data = serde_json::to_string(&item.login).map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        out.set_data(&data, updated_at)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn groups_import_duplicates_transitively() -> Result<(), CryptoError> {
+        let items = [
+            imported("A", &["https://www.example.com/login"], "alice", "pw"),
+            imported("A", &["https://example.com", "https://example.org"], " Alice ", "pw"),
+            imported("A", &["https://example.org/x", "https://example.net"], "alice", "pw"),
+            imported("B", &["https://example.com"], "alice", "other"),
+            imported("C", &["https://example.com"], "bob", "pw"),
+        ];
+        let proposals = import_duplicates(&items, &[])?;
+        let expected = MergeProposal {
+            keep: ItemRef::Import(0),
+            duplicates: vec![ItemRef::Import(1), ItemRef::Import(2)],
+            add_uris: vec!["https://example.org".to_string(), "https://example.net".to_string()],
+        };
+        assert_eq!(proposals, vec![expected]);
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_newest_vault_item() -> Result<(), CryptoError> {
+        let item = imported("Mail", &["https://mail.example"], "alice", "pw");
+        let existing = [vault("old", &item, 100)?, vault("new", &item, 200)?];
+        let proposals = import_duplicates(std::slice::from_ref(&item), &existing)?;
+        let expected = MergeProposal {
+            keep: ItemRef::Vault("new".to_string()),
+            duplicates: vec![ItemRef::Vault("old".to_string()), ItemRef::Import(0)],
+            add_uris: Vec::new(),
+        };
+        assert_eq!(proposals, vec![expected]);
+        Ok(())
+    }
+
+    #[test]
+    fn falls_back_to_name_and_skips_empty_passwords() -> Result<(), CryptoError> {
+        let items = [
+            imported(" Router ", &["not a url"], "admin", "pw"),
+            imported("router", &[], "admin", "pw"),
+            imported("Router", &[], "admin", ""),
+            imported("Router", &[], "admin", "  "),
+        ];
+        let proposals = import_duplicates(&items, &[])?;
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals.first().map(|p| p.duplicates.clone()), Some(vec![ItemRef::Import(1)]));
+        // Niepoprawny adres nie jest dopisywany jako osobna strona
+        assert_eq!(proposals.first().map(|p| p.add_uris.len()), Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn find_import_duplicates_json() -> Result<(), CryptoError> {
+        let item = imported("Mail", &["https://mail.example"], "alice", "pw");
+        let existing = format!("[{}]", vault("v1", &item, 1)?.to_json()?);
+        let imported = serde_json::to_string(&crate::import::ImportedVault { items: vec![item] })
+            .map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        let json = find_import_duplicates(&imported, Some(existing))?;
+        assert_eq!(json, r#"[{"keep":{"vault":"v1"},"duplicates":[{"import":0}],"add_uris":[]}]"#);
+        assert_eq!(find_import_duplicates(&imported, None)?, "[]");
+
+        assert!(matches!(find_import_duplicates(&imported, Some("{".to_string())), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(find_import_duplicates("{", None), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+}
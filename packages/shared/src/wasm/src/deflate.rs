@@ -0,0 +1,174 @@
+use zeroize::Zeroizing;
+
+use crate::import::inflate::{DIST_BASE, DIST_EXTRA, LENGTH_BASE, LENGTH_EXTRA};
+
+// Kompresja DEFLATE (RFC 1951) payloadu vaulta przed szyfrowaniem; dekompresja to
+// inflate_bytes z importu. LZ77 z lancuchami hashy (okno 32 KiB, zachlanne dopasowanie)
+// i jeden blok ze stalymi kodami Huffmana - JSON vaulta kompresuje sie tym dobrze, a
+// koder zostaje prosty. Bufory z danymi jawnymi zerowane po uzyciu.
+const WINDOW: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: u32 = 15;
+const MAX_CHAIN: usize = 128;
+const END_OF_BLOCK: u16 = 256;
+const NONE: usize = usize::MAX;
+
+struct BitWriter {
+    out: Zeroizing<Vec<u8>>,
+    bit_buf: u64,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    // Pola naglowkow i bity dodatkowe - od najmlodszego bitu.
+    fn bits(&mut self, value: u32, count: u32) {
+        self.bit_buf |= (value as u64) << self.bit_count;
+        self.bit_count += count;
+        while self.bit_count >= 8 {
+            self.out.push(self.bit_buf as u8);
+            self.bit_buf >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    // Kody Huffmana - od najstarszego bitu.
+    fn code(&mut self, code: u32, len: u32) {
+        self.bits(code.reverse_bits() >> (32 - len), len);
+    }
+
+    fn symbol(&mut self, symbol: u16) {
+        let s = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + s, 8),
+            144..=255 => self.code(0x190 + s - 144, 9),
+            256..=279 => self.code(s - 256, 7),
+            _ => self.code(0xc0 + s - 280, 8),
+        }
+    }
+
+    fn finish(mut self) -> Zeroizing<Vec<u8>> {
+        if self.bit_count > 0 {
+            self.out.push(self.bit_buf as u8);
+        }
+        self.out
+    }
+}
+
+fn hash3(data: &[u8]) -> usize {
+    let v = (data[0] as u32) << 16 | (data[1] as u32) << 8 | data[2] as u32;
+    (v.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
+
+fn insert(head: &mut [usize], prev: &mut [usize], data: &[u8], pos: usize) {
+    if pos + MIN_MATCH <= data.len() {
+        let h = hash3(&data[pos..]);
+        prev[pos % WINDOW] = head[h];
+        head[h] = pos;
+    }
+}
+
+// Indeks w tablicy baz (dlugosci albo odleglosci) i wartosc bitow dodatkowych.
+fn base_index(bases: &[u16], value: usize) -> (usize, u32) {
+    let index = bases.partition_point(|&b| b as usize <= value) - 1;
+    (index, (value - bases[index] as usize) as u32)
+}
+
+fn longest_match(data: &[u8], head: &[usize], prev: &[usize], pos: usize) -> (usize, usize) {
+    let (mut best_len, mut best_dist) = (0, 0);
+    if pos + MIN_MATCH > data.len() {
+        return (best_len, best_dist);
+    }
+    let max = (data.len() - pos).min(MAX_MATCH);
+    let mut candidate = head[hash3(&data[pos..])];
+    let mut chain = 0;
+    while candidate != NONE && pos - candidate <= WINDOW && chain < MAX_CHAIN {
+        let len = data[candidate..].iter().zip(&data[pos..pos + max]).take_while(|(a, b)| a == b).count();
+        if len > best_len {
+            (best_len, best_dist) = (len, pos - candidate);
+            if len == max {
+                break;
+            }
+        }
+        candidate = prev[candidate % WINDOW];
+        chain += 1;
+    }
+    (best_len, best_dist)
+}
+
+// Surowy strumien DEFLATE (bez naglowka zlib/gzip).
+pub fn deflate_bytes(data: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut writer = BitWriter { out: Zeroizing::new(Vec::with_capacity(data.len() / 2)), bit_buf: 0, bit_count: 0 };
+    // BFINAL = 1, BTYPE = 01 (stale kody)
+    writer.bits(1, 1);
+    writer.bits(1, 2);
+    let mut head = vec![NONE; 1 << HASH_BITS];
+    let mut prev = vec![NONE; WINDOW];
+    let mut pos = 0;
+    while pos < data.len() {
+        let (len, dist) = longest_match(data, &head, &prev, pos);
+        if len >= MIN_MATCH {
+            let (index, extra) = base_index(&LENGTH_BASE, len);
+            writer.symbol(257 + index as u16);
+            writer.bits(extra, LENGTH_EXTRA[index] as u32);
+            let (index, extra) = base_index(&DIST_BASE, dist);
+            writer.code(index as u32, 5);
+            writer.bits(extra, DIST_EXTRA[index] as u32);
+            for p in pos..pos + len {
+                insert(&mut head, &mut prev, data, p);
+            }
+            pos += len;
+        } else {
+            writer.symbol(data[pos] as u16);
+            insert(&mut head, &mut prev, data, pos);
+            pos += 1;
+        }
+    }
+    writer.symbol(END_OF_BLOCK);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CryptoError;
+    use crate::import::inflate::{inflate_bytes, inflate_bytes_limited};
+
+    // Deterministyczny szum (xorshift) - praktycznie nie do skompresowania.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut x = 0x2545_f491_4f6c_dd1du64;
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                x as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), CryptoError> {
+        let json = "{\"name\":\"example.com\",\"username\":\"alice\",\"password\":\"hunter2\"},".repeat(200);
+        let long_matches = [vec![b'a'; 70_000], noise(3000)].concat();
+        for data in [&b""[..], b"a", b"abcabcabcabc", json.as_bytes(), &noise(5000), &long_matches] {
+            let packed = deflate_bytes(data);
+            let (unpacked, used) = inflate_bytes(&packed)?;
+            assert_eq!(unpacked.as_slice(), data);
+            assert_eq!(used, packed.len());
+        }
+        assert!(deflate_bytes(json.as_bytes()).len() < json.len() / 10);
+        // Stale kody Huffmana: szum rosnie najwyzej o 1/8 (9-bitowe literaly) plus koniec bloku.
+        assert!(deflate_bytes(&noise(5000)).len() <= 5000 * 9 / 8 + 2);
+        Ok(())
+    }
+
+    #[test]
+    fn bomb_output_is_bounded() -> Result<(), CryptoError> {
+        let bomb = deflate_bytes(&vec![0; 4 << 20]);
+        assert!(bomb.len() < 64 * 1024);
+        assert!(matches!(inflate_bytes_limited(&bomb, 1 << 20), Err(CryptoError::OutOfRange(_))));
+        assert_eq!(inflate_bytes_limited(&bomb, 4 << 20)?.0.len(), 4 << 20);
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::backup::open_backup_bytes;
+use crate::error::CryptoError;
+use crate::roundtrip::{flatten, index_items, key_label, parse_json_items};
+use crate::secret::EntryKey;
+
+// Roznice miedzy dwoma snapshotami vaulta (wsparcie, debugowanie synchronizacji).
+// Wpisy dopasowywane i normalizowane tak jak w weryfikacji importu/eksportu (modul roundtrip),
+// pola porownywane po splaszczonych sciezkach ("login.password", "fields[0].value").
+// Wartosci sa domyslnie ukryte - raport zawiera tylko sciezke i rodzaj zmiany. Pole jest
+// odslaniane tylko, gdy wywolujacy poda je w `reveal` (dokladna sciezka albo jej prefiks,
+// np. "login" odslania "login.username" i "login.password").
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub change: DiffKind,
+    pub revealed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ItemDiff {
+    pub item: String,
+    pub fields: Vec<FieldDiff>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VaultDiff {
+    pub old_items: usize,
+    pub new_items: usize,
+    pub unchanged: usize,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<ItemDiff>,
+}
+
+fn is_revealed(field: &str, reveal: &[String]) -> bool {
+    reveal.iter().any(|r| {
+        field == r || field.strip_prefix(r.as_str()).is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+    })
+}
+
+fn field_diff(field: String, change: DiffKind, old: Option<Value>, new: Option<Value>, reveal: &[String]) -> FieldDiff {
+    let revealed = is_revealed(&field, reveal);
+    FieldDiff { field, change, revealed, old: old.filter(|_| revealed), new: new.filter(|_| revealed) }
+}
+
+pub fn diff_items(old: &[Value], new: &[Value], reveal: &[String]) -> VaultDiff {
+    let old_map = index_items(old);
+    let mut new_map = index_items(new);
+    let mut diff = VaultDiff { old_items: old.len(), new_items: new.len(), ..VaultDiff::default() };
+
+    for (item_key, before) in old_map {
+        let key = key_label(&item_key);
+        let Some(after) = new_map.remove(&item_key) else {
+            diff.removed.push(key);
+            continue;
+        };
+        let (mut old_fields, mut new_fields) = (BTreeMap::new(), BTreeMap::new());
+        flatten("", &before, &mut old_fields);
+        flatten("", &after, &mut new_fields);
+
+        let mut fields = Vec::new();
+        for (field, value) in old_fields {
+            match new_fields.remove(&field) {
+                None => fields.push(field_diff(field, DiffKind::Removed, Some(value), None, reveal)),
+                Some(v) if v != value => {
+                    fields.push(field_diff(field, DiffKind::Modified, Some(value), Some(v), reveal))
+                }
+                Some(_) => {}
+            }
+        }
+        for (field, value) in new_fields {
+            fields.push(field_diff(field, DiffKind::Added, None, Some(value), reveal));
+        }
+        if fields.is_empty() {
+            diff.unchanged += 1;
+        } else {
+            fields.sort_by(|a, b| a.field.cmp(&b.field));
+            diff.modified.push(ItemDiff { item: key, fields });
+        }
+    }
+    diff.added = new_map.keys().map(key_label).collect();
+    diff
+}
+
+pub fn diff_vaults_bytes(old: &str, new: &str, reveal: &[String]) -> Result<VaultDiff, CryptoError> {
+    Ok(diff_items(&parse_json_items(old)?, &parse_json_items(new)?, reveal))
+}
+
+// Dwie kopie zapasowe tego samego klucza backupu (snapshot w kopii to JSON wpisow).
+pub fn diff_backups_bytes(
+    backup_key: &EntryKey,
+    old_blob: &[u8],
+    new_blob: &[u8],
+    reveal: &[String],
+) -> Result<VaultDiff, CryptoError> {
+    let old = Zeroizing::new(open_backup_bytes(backup_key, old_blob)?.1);
+    let new = Zeroizing::new(open_backup_bytes(backup_key, new_blob)?.1);
+    let text = |snapshot: &[u8]| {
+        std::str::from_utf8(snapshot)
+            .map_err(|_| CryptoError::InvalidInput("backup snapshot is not UTF-8 JSON".to_string()))
+            .and_then(parse_json_items)
+    };
+    Ok(diff_items(&text(&old)?, &text(&new)?, reveal))
+}
+
+fn to_json(diff: &VaultDiff) -> Result<String, CryptoError> {
+    serde_json::to_string(diff).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+// old/new - wpisy vaulta jako JSON (tablica albo {"items": [...]}), reveal - sciezki pol,
+// ktorych wartosci maja trafic do raportu. Zwraca raport jako JSON.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn diff_vaults(old: &str, new: &str, reveal: Option<Vec<String>>) -> Result<String, CryptoError> {
+    to_json(&diff_vaults_bytes(old, new, &reveal.unwrap_or_default())?)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn diff_backups(
+    backup_key: &[u8],
+    old_blob: &[u8],
+    new_blob: &[u8],
+    reveal: Option<Vec<String>>,
+) -> Result<String, CryptoError> {
+    to_json(&diff_backups_bytes(&EntryKey::from_slice(backup_key), old_blob, new_blob, &reveal.unwrap_or_default())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r#"{"items":[
+        {"id":"1","name":"mail","login":{"username":"alice","password":"s3cret-old"},"notes":"a"},
+        {"id":"2","name":"bank","login":{"username":"bob","password":"pin"}},
+        {"id":"3","name":"gone","notes":"bye"}
+    ]}"#;
+    const NEW: &str = r#"[
+        {"id":"1","name":"mail","login":{"username":"alice","password":"s3cret-new","totp":"seed"}},
+        {"id":"2","name":"bank","login":{"username":"bob","password":"pin"}},
+        {"id":"4","name":"fresh"}
+    ]"#;
+
+    fn reveal(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn changes_are_reported_and_redacted() -> Result<(), CryptoError> {
+        let diff = diff_vaults_bytes(OLD, NEW, &[])?;
+        assert_eq!((diff.old_items, diff.new_items, diff.unchanged), (3, 3, 1));
+        assert_eq!(diff.added, ["4"]);
+        assert_eq!(diff.removed, ["3"]);
+        let fields: Vec<_> = diff.modified[0].fields.iter().map(|f| (f.field.as_str(), f.change)).collect();
+        assert_eq!(
+            fields,
+            [("login.password", DiffKind::Modified), ("login.totp", DiffKind::Added), ("notes", DiffKind::Removed)]
+        );
+        assert!(diff.modified[0].fields.iter().all(|f| !f.revealed && f.old.is_none() && f.new.is_none()));
+        assert!(!to_json(&diff)?.contains("s3cret"));
+
+        // Prefiks "login" odslania login.*, ale nie "loginx" ani "notes".
+        let diff = diff_vaults_bytes(OLD, NEW, &reveal(&["login"]))?;
+        let password = &diff.modified[0].fields[0];
+        assert_eq!(password.old, Some("s3cret-old".into()));
+        assert_eq!(password.new, Some("s3cret-new".into()));
+        assert!(!diff.modified[0].fields[2].revealed);
+        assert!(!is_revealed("loginx", &reveal(&["login"])));
+        Ok(())
+    }
+
+    // Odslonieta roznica wystarcza, zeby odtworzyc nowe pola ze starych (i odwrotnie).
+    #[test]
+    fn revealed_diff_patches_old_into_new() -> Result<(), CryptoError> {
+        let all = reveal(&["id", "name", "login", "notes"]);
+        let (old, new) = (parse_json_items(OLD)?, parse_json_items(NEW)?);
+        for (from, to) in [(&old, &new), (&new, &old)] {
+            let diff = diff_items(from, to, &all);
+            let (from_map, to_map) = (index_items(from), index_items(to));
+            for item in &diff.modified {
+                let find = |map: &BTreeMap<_, Value>| {
+                    map.iter().find(|(k, _)| key_label(k) == item.item).map(|(_, v)| v.clone()).unwrap_or_default()
+                };
+                let (mut patched, mut expected) = (BTreeMap::new(), BTreeMap::new());
+                flatten("", &find(&from_map), &mut patched);
+                flatten("", &find(&to_map), &mut expected);
+                for field in &item.fields {
+                    match &field.new {
+                        Some(value) => patched.insert(field.field.clone(), value.clone()),
+                        None => patched.remove(&field.field),
+                    };
+                }
+                assert_eq!(patched, expected);
+            }
+            let reverse = diff_items(to, from, &all);
+            assert_eq!((reverse.added, reverse.removed), (diff.removed, diff.added));
+        }
+        assert!(diff_vaults_bytes(NEW, NEW, &[])?.modified.is_empty());
+        Ok(())
+    }
+}
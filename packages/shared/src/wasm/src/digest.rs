@@ -0,0 +1,223 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(feature = "js")]
+use crate::encoding::{OutputEncoding, encode_output};
+use crate::blake3::{Blake3, OUT_LEN as BLAKE3_OUT_LEN};
+use crate::error::CryptoError;
+use crate::fips::check_hash;
+use crate::sha1::Sha1;
+use crate::sha2::{Sha256, Sha512};
+use crate::sha3::{Sha3_256, Sha3_512};
+use crate::sm3::Sm3;
+#[cfg(feature = "streebog")]
+use crate::streebog::{Streebog256, Streebog512};
+
+// wasm_bindgen nie obsluguje #[cfg] na wariantach, wiec lista wariantow zalezy od feature.
+// Wartosci liczbowe sa widoczne w JS - stale niezaleznie od feature, nowe tylko na koncu.
+// SHA-1 tylko dla cudzych formatow (import, HIBP, OTP), BLAKE3 (32 bajty) dla zalacznikow;
+// oba poza profilem FIPS.
+macro_rules! hash_algorithm_enum {
+    ($($variant:ident = $value:literal),*) => {
+        #[cfg_attr(feature = "js", wasm_bindgen)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum HashAlgorithm {
+            $($variant = $value),*
+        }
+    };
+}
+
+#[cfg(not(feature = "streebog"))]
+hash_algorithm_enum!(Sha256 = 0, Sha512 = 1, Sha3_256 = 2, Sha3_512 = 3, Sm3 = 4, Sha1 = 7, Blake3 = 8);
+#[cfg(feature = "streebog")]
+hash_algorithm_enum!(
+    Sha256 = 0,
+    Sha512 = 1,
+    Sha3_256 = 2,
+    Sha3_512 = 3,
+    Sm3 = 4,
+    Streebog256 = 5,
+    Streebog512 = 6,
+    Sha1 = 7,
+    Blake3 = 8
+);
+
+impl HashAlgorithm {
+    pub fn from_name(name: &str) -> Result<Self, CryptoError> {
+        let algorithm = match name.to_ascii_lowercase().as_str() {
+            "sha256" | "sha-256" => HashAlgorithm::Sha256,
+            "sha512" | "sha-512" => HashAlgorithm::Sha512,
+            "sha3-256" | "sha3_256" => HashAlgorithm::Sha3_256,
+            "sha3-512" | "sha3_512" => HashAlgorithm::Sha3_512,
+            "sm3" => HashAlgorithm::Sm3,
+            "sha1" | "sha-1" => HashAlgorithm::Sha1,
+            "blake3" => HashAlgorithm::Blake3,
+            #[cfg(feature = "streebog")]
+            "streebog256" | "streebog-256" => HashAlgorithm::Streebog256,
+            #[cfg(feature = "streebog")]
+            "streebog512" | "streebog-512" => HashAlgorithm::Streebog512,
+            _ => return Err(CryptoError::UnsupportedAlgorithm(name.to_string())),
+        };
+        check_hash(algorithm)?;
+        Ok(algorithm)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha512 => "sha512",
+            HashAlgorithm::Sha3_256 => "sha3-256",
+            HashAlgorithm::Sha3_512 => "sha3-512",
+            HashAlgorithm::Sm3 => "sm3",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Blake3 => "blake3",
+            #[cfg(feature = "streebog")]
+            HashAlgorithm::Streebog256 => "streebog256",
+            #[cfg(feature = "streebog")]
+            HashAlgorithm::Streebog512 => "streebog512",
+        }
+    }
+
+    pub fn output_len(self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 => 32,
+            HashAlgorithm::Sha512 => 64,
+            HashAlgorithm::Sha3_256 => 32,
+            HashAlgorithm::Sha3_512 => 64,
+            HashAlgorithm::Sm3 => 32,
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Blake3 => BLAKE3_OUT_LEN,
+            #[cfg(feature = "streebog")]
+            HashAlgorithm::Streebog256 => 32,
+            #[cfg(feature = "streebog")]
+            HashAlgorithm::Streebog512 => 64,
+        }
+    }
+
+    // Rozmiar bloku dla HMAC (dla SHA-3 rowny rate gabki).
+    pub fn block_size(self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 => 64,
+            HashAlgorithm::Sha512 => 128,
+            HashAlgorithm::Sha3_256 => 136,
+            HashAlgorithm::Sha3_512 => 72,
+            HashAlgorithm::Sm3 => 64,
+            HashAlgorithm::Sha1 => 64,
+            HashAlgorithm::Blake3 => 64,
+            #[cfg(feature = "streebog")]
+            HashAlgorithm::Streebog256 | HashAlgorithm::Streebog512 => 64,
+        }
+    }
+}
+
+// Wspolny interfejs dla stanow przyrostowych wszystkich obslugiwanych skrotow.
+#[derive(Clone)]
+pub enum DigestState {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Sha3_256(Sha3_256),
+    Sha3_512(Sha3_512),
+    Sm3(Sm3),
+    Sha1(Sha1),
+    Blake3(Blake3),
+    #[cfg(feature = "streebog")]
+    Streebog256(Streebog256),
+    #[cfg(feature = "streebog")]
+    Streebog512(Streebog512),
+}
+
+impl DigestState {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => DigestState::Sha256(Sha256::new()),
+            HashAlgorithm::Sha512 => DigestState::Sha512(Sha512::new()),
+            HashAlgorithm::Sha3_256 => DigestState::Sha3_256(Sha3_256::new()),
+            HashAlgorithm::Sha3_512 => DigestState::Sha3_512(Sha3_512::new()),
+            HashAlgorithm::Sm3 => DigestState::Sm3(Sm3::new()),
+            HashAlgorithm::Sha1 => DigestState::Sha1(Sha1::new()),
+            HashAlgorithm::Blake3 => DigestState::Blake3(Blake3::new()),
+            #[cfg(feature = "streebog")]
+            HashAlgorithm::Streebog256 => DigestState::Streebog256(Streebog256::new()),
+            #[cfg(feature = "streebog")]
+            HashAlgorithm::Streebog512 => DigestState::Streebog512(Streebog512::new()),
+        }
+    }
+
+    pub fn algorithm(&self) -> HashAlgorithm {
+        match self {
+            DigestState::Sha256(_) => HashAlgorithm::Sha256,
+            DigestState::Sha512(_) => HashAlgorithm::Sha512,
+            DigestState::Sha3_256(_) => HashAlgorithm::Sha3_256,
+            DigestState::Sha3_512(_) => HashAlgorithm::Sha3_512,
+            DigestState::Sm3(_) => HashAlgorithm::Sm3,
+            DigestState::Sha1(_) => HashAlgorithm::Sha1,
+            DigestState::Blake3(_) => HashAlgorithm::Blake3,
+            #[cfg(feature = "streebog")]
+            DigestState::Streebog256(_) => HashAlgorithm::Streebog256,
+            #[cfg(feature = "streebog")]
+            DigestState::Streebog512(_) => HashAlgorithm::Streebog512,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            DigestState::Sha256(h) => h.update(data),
+            DigestState::Sha512(h) => h.update(data),
+            DigestState::Sha3_256(h) => h.update(data),
+            DigestState::Sha3_512(h) => h.update(data),
+            DigestState::Sm3(h) => h.update(data),
+            DigestState::Sha1(h) => h.update(data),
+            DigestState::Blake3(h) => h.update(data),
+            #[cfg(feature = "streebog")]
+            DigestState::Streebog256(h) => h.update(data),
+            #[cfg(feature = "streebog")]
+            DigestState::Streebog512(h) => h.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            DigestState::Sha256(h) => h.finalize().to_vec(),
+            DigestState::Sha512(h) => h.finalize().to_vec(),
+            DigestState::Sha3_256(h) => h.finalize().to_vec(),
+            DigestState::Sha3_512(h) => h.finalize().to_vec(),
+            DigestState::Sm3(h) => h.finalize().to_vec(),
+            DigestState::Sha1(h) => h.finalize().to_vec(),
+            DigestState::Blake3(h) => h.finalize().to_vec(),
+            #[cfg(feature = "streebog")]
+            DigestState::Streebog256(h) => h.finalize().to_vec(),
+            #[cfg(feature = "streebog")]
+            DigestState::Streebog512(h) => h.finalize().to_vec(),
+        }
+    }
+}
+
+pub fn digest_bytes(algorithm: HashAlgorithm, data: &[u8]) -> Vec<u8> {
+    let mut state = DigestState::new(algorithm);
+    state.update(data);
+    state.finalize()
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn digest(algorithm: HashAlgorithm, data: &[u8], encoding: Option<OutputEncoding>) -> Result<JsValue, CryptoError> {
+    check_hash(algorithm)?;
+    Ok(encode_output(&digest_bytes(algorithm, data), encoding))
+}
+
+// Strumieniowo z dowolnego zrodla std::io (plik w sandboxie WASI, stdin w CLI).
+pub fn digest_reader<R: std::io::Read>(algorithm: HashAlgorithm, reader: &mut R) -> Result<Vec<u8>, CryptoError> {
+    check_hash(algorithm)?;
+    let mut state = DigestState::new(algorithm);
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(CryptoError::InvalidInput(format!("i/o error: {e}"))),
+        };
+        state.update(&buf[..n]);
+    }
+    Ok(state.finalize())
+}
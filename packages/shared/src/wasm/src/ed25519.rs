@@ -0,0 +1,396 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::curve25519::{FE_D, FE_D2, Fe};
+use crate::error::CryptoError;
+use crate::sha2::Sha512;
+
+// Ed25519 (RFC 8032). Punkty w wspolrzednych rozszerzonych (X:Y:Z:T), mnozenie
+// przez skalar drabinka w stalym czasie, skalary modulo l = 2^252 + 2774...3ed.
+
+const BASE_POINT: [u8; 32] = [
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+];
+
+const L: [u64; 4] = [0x5812631a5cf5d3ed, 0x14def9dea2f79cd6, 0, 0x1000000000000000];
+
+pub const PUBLIC_KEY_LEN: usize = 32;
+pub const SECRET_KEY_LEN: usize = 32;
+pub const SIGNATURE_LEN: usize = 64;
+
+#[derive(Clone, Copy)]
+pub struct EdwardsPoint {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+    t: Fe,
+}
+
+impl EdwardsPoint {
+    pub fn identity() -> EdwardsPoint {
+        EdwardsPoint { x: Fe::ZERO, y: Fe::ONE, z: Fe::ONE, t: Fe::ZERO }
+    }
+
+    pub fn base() -> EdwardsPoint {
+        // punkt bazowy jest poprawny z definicji
+        EdwardsPoint::decompress(&BASE_POINT).unwrap_or_else(EdwardsPoint::identity)
+    }
+
+    // add-2008-hwcd-3 (pelne dla a = -1)
+    pub fn add(&self, q: &EdwardsPoint) -> EdwardsPoint {
+        let d2 = Fe::from_bytes(&FE_D2);
+        let a = self.y.sub(&self.x).mul(&q.y.sub(&q.x));
+        let b = self.y.add(&self.x).mul(&q.y.add(&q.x));
+        let c = self.t.mul(&d2).mul(&q.t);
+        let d = self.z.add(&self.z).mul(&q.z);
+        let e = b.sub(&a);
+        let f = d.sub(&c);
+        let g = d.add(&c);
+        let h = b.add(&a);
+        EdwardsPoint { x: e.mul(&f), y: g.mul(&h), z: f.mul(&g), t: e.mul(&h) }
+    }
+
+    // dbl-2008-hwcd z a = -1
+    pub fn double(&self) -> EdwardsPoint {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square().add(&self.z.square());
+        let d = a.neg();
+        let e = self.x.add(&self.y).square().sub(&a).sub(&b);
+        let g = d.add(&b);
+        let f = g.sub(&c);
+        let h = d.sub(&b);
+        EdwardsPoint { x: e.mul(&f), y: g.mul(&h), z: f.mul(&g), t: e.mul(&h) }
+    }
+
+    pub fn neg(&self) -> EdwardsPoint {
+        EdwardsPoint { x: self.x.neg(), y: self.y, z: self.z, t: self.t.neg() }
+    }
+
+    pub fn sub(&self, q: &EdwardsPoint) -> EdwardsPoint {
+        self.add(&q.neg())
+    }
+
+    fn cmov(&mut self, other: &EdwardsPoint, choice: bool) {
+        self.x.cmov(&other.x, choice);
+        self.y.cmov(&other.y, choice);
+        self.z.cmov(&other.z, choice);
+        self.t.cmov(&other.t, choice);
+    }
+
+    // Skalar little-endian (256 bitow, nie musi byc zredukowany). Zawsze ta sama
+    // sekwencja operacji niezaleznie od bitow skalara.
+    pub fn mul_scalar(&self, scalar: &[u8; 32]) -> EdwardsPoint {
+        let mut acc = EdwardsPoint::identity();
+        for i in (0..256).rev() {
+            acc = acc.double();
+            let sum = acc.add(self);
+            let bit = (scalar[i / 8] >> (i % 8)) & 1 == 1;
+            acc.cmov(&sum, bit);
+        }
+        acc
+    }
+
+    pub fn mul_base(scalar: &[u8; 32]) -> EdwardsPoint {
+        EdwardsPoint::base().mul_scalar(scalar)
+    }
+
+    // [8]P - do sprawdzania, czy punkt nie lezy w podgrupie malego rzedu
+    pub fn mul_by_cofactor(&self) -> EdwardsPoint {
+        self.double().double().double()
+    }
+
+    // [l]P == O, czyli punkt lezy w podgrupie rzedu pierwszego (bez skladowej skretnej)
+    pub fn is_torsion_free(&self) -> bool {
+        let mut l = [0u8; 32];
+        for (k, limb) in L.iter().enumerate() {
+            l[k * 8..k * 8 + 8].copy_from_slice(&limb.to_le_bytes());
+        }
+        self.mul_scalar(&l).is_identity()
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.compress() == EdwardsPoint::identity().compress()
+    }
+
+    pub fn compress(&self) -> [u8; 32] {
+        let zinv = self.z.invert();
+        let x = self.x.mul(&zinv);
+        let y = self.y.mul(&zinv);
+        let mut s = y.to_bytes();
+        s[31] ^= (x.is_negative() as u8) << 7;
+        s
+    }
+
+    // Odrzuca niekanoniczne y (>= p) oraz punkty spoza krzywej.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
+        let sign = bytes[31] >> 7 == 1;
+        let y = Fe::from_bytes(bytes);
+        let mut canonical = *bytes;
+        canonical[31] &= 0x7f;
+        if y.to_bytes() != canonical {
+            return None;
+        }
+
+        let y2 = y.square();
+        let u = y2.sub(&Fe::ONE);
+        let v = y2.mul(&Fe::from_bytes(&FE_D)).add(&Fe::ONE);
+        let (is_square, mut x) = Fe::sqrt_ratio(&u, &v);
+        if !is_square {
+            return None;
+        }
+        if x.is_zero() && sign {
+            return None;
+        }
+        if x.is_negative() != sign {
+            x = x.neg();
+        }
+        Some(EdwardsPoint { x, y, z: Fe::ONE, t: x.mul(&y) })
+    }
+}
+
+// ---- skalary modulo l ----
+
+fn limbs_from_bytes(b: &[u8]) -> Vec<u64> {
+    b.chunks(8)
+        .map(|c| {
+            let mut w = [0u8; 8];
+            w[..c.len()].copy_from_slice(c);
+            u64::from_le_bytes(w)
+        })
+        .collect()
+}
+
+fn sub_l(r: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut borrow = 0u64;
+    for i in 0..4 {
+        let (d1, b1) = r[i].overflowing_sub(L[i]);
+        let (d2, b2) = d1.overflowing_sub(borrow);
+        out[i] = d2;
+        borrow = (b1 | b2) as u64;
+    }
+    (out, borrow == 1)
+}
+
+// Redukcja dowolnie dlugiej liczby (little-endian limby) modulo l, bit po bicie,
+// bez rozgalezien zaleznych od wartosci.
+fn reduce_limbs(limbs: &[u64]) -> [u8; 32] {
+    let mut r = [0u64; 4];
+    for i in (0..limbs.len() * 64).rev() {
+        let bit = (limbs[i / 64] >> (i % 64)) & 1;
+        r[3] = (r[3] << 1) | (r[2] >> 63);
+        r[2] = (r[2] << 1) | (r[1] >> 63);
+        r[1] = (r[1] << 1) | (r[0] >> 63);
+        r[0] = (r[0] << 1) | bit;
+        let (d, borrow) = sub_l(&r);
+        let mask = 0u64.wrapping_sub((!borrow) as u64);
+        for k in 0..4 {
+            r[k] ^= mask & (r[k] ^ d[k]);
+        }
+    }
+    let mut out = [0u8; 32];
+    for (k, limb) in r.iter().enumerate() {
+        out[k * 8..k * 8 + 8].copy_from_slice(&limb.to_le_bytes());
+    }
+    r.zeroize();
+    out
+}
+
+pub fn scalar_reduce(bytes: &[u8]) -> [u8; 32] {
+    let mut limbs = limbs_from_bytes(bytes);
+    let out = reduce_limbs(&limbs);
+    limbs.zeroize();
+    out
+}
+
+// (a * b + c) mod l
+pub fn scalar_mul_add(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    let a = limbs_from_bytes(a);
+    let b = limbs_from_bytes(b);
+    let c = limbs_from_bytes(c);
+    let mut wide = [0u64; 9];
+    for i in 0..4 {
+        let mut carry: u128 = 0;
+        for j in 0..4 {
+            let t = (a[i] as u128) * (b[j] as u128) + wide[i + j] as u128 + carry;
+            wide[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        wide[i + 4] = carry as u64;
+    }
+    let mut carry: u128 = 0;
+    for (k, w) in wide.iter_mut().enumerate() {
+        let t = *w as u128 + *c.get(k).unwrap_or(&0) as u128 + carry;
+        *w = t as u64;
+        carry = t >> 64;
+    }
+    let out = reduce_limbs(&wide);
+    wide.zeroize();
+    out
+}
+
+pub fn scalar_add(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let mut one = [0u8; 32];
+    one[0] = 1;
+    scalar_mul_add(a, &one, b)
+}
+
+pub fn scalar_mul(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    scalar_mul_add(a, b, &[0u8; 32])
+}
+
+pub fn scalar_neg(a: &[u8; 32]) -> [u8; 32] {
+    // l - a = (l - 1) * a mod l
+    let mut l_minus_1 = [0u8; 32];
+    for (k, limb) in L.iter().enumerate() {
+        l_minus_1[k * 8..k * 8 + 8].copy_from_slice(&limb.to_le_bytes());
+    }
+    l_minus_1[0] -= 1;
+    scalar_mul(a, &l_minus_1)
+}
+
+pub fn scalar_is_canonical(s: &[u8; 32]) -> bool {
+    let limbs = limbs_from_bytes(s);
+    let r = [limbs[0], limbs[1], limbs[2], limbs[3]];
+    sub_l(&r).1
+}
+
+// Odwrotnosc modulo l: a^(l-2)
+pub fn scalar_invert(a: &[u8; 32]) -> [u8; 32] {
+    let mut e = [0u8; 32];
+    for (k, limb) in L.iter().enumerate() {
+        e[k * 8..k * 8 + 8].copy_from_slice(&limb.to_le_bytes());
+    }
+    e[0] -= 2;
+    let mut result = [0u8; 32];
+    result[0] = 1;
+    for i in (0..253).rev() {
+        result = scalar_mul(&result, &result);
+        if (e[i / 8] >> (i % 8)) & 1 == 1 {
+            result = scalar_mul(&result, a);
+        }
+    }
+    result
+}
+
+// ---- podpisy ----
+
+struct ExpandedKey {
+    scalar: [u8; 32],
+    prefix: [u8; 32],
+}
+
+impl Drop for ExpandedKey {
+    fn drop(&mut self) {
+        self.scalar.zeroize();
+        self.prefix.zeroize();
+    }
+}
+
+fn expand_secret(secret: &[u8; 32]) -> ExpandedKey {
+    let mut h = Sha512::new();
+    h.update(secret);
+    let mut digest = h.finalize();
+    let mut scalar = [0u8; 32];
+    let mut prefix = [0u8; 32];
+    scalar.copy_from_slice(&digest[..32]);
+    prefix.copy_from_slice(&digest[32..]);
+    digest.zeroize();
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    ExpandedKey { scalar, prefix }
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> [u8; 32] {
+    let mut h = Sha512::new();
+    for p in parts {
+        h.update(p);
+    }
+    let mut digest = h.finalize();
+    let out = scalar_reduce(&digest);
+    digest.zeroize();
+    out
+}
+
+pub fn public_key_bytes(secret: &[u8; 32]) -> [u8; 32] {
+    let key = expand_secret(secret);
+    EdwardsPoint::mul_base(&key.scalar).compress()
+}
+
+pub fn sign_bytes(secret: &[u8; 32], message: &[u8]) -> [u8; 64] {
+    let key = expand_secret(secret);
+    let public = EdwardsPoint::mul_base(&key.scalar).compress();
+
+    let mut r = hash_to_scalar(&[&key.prefix, message]);
+    let big_r = EdwardsPoint::mul_base(&r).compress();
+    let k = hash_to_scalar(&[&big_r, &public, message]);
+    let a = scalar_reduce(&key.scalar);
+    let s = scalar_mul_add(&k, &a, &r);
+    r.zeroize();
+
+    let mut sig = [0u8; 64];
+    sig[..32].copy_from_slice(&big_r);
+    sig[32..].copy_from_slice(&s);
+    sig
+}
+
+pub fn verify_bytes(public: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let a = match EdwardsPoint::decompress(public) {
+        Some(p) => p,
+        None => return false,
+    };
+    let mut big_r = [0u8; 32];
+    let mut s = [0u8; 32];
+    big_r.copy_from_slice(&signature[..32]);
+    s.copy_from_slice(&signature[32..]);
+    if !scalar_is_canonical(&s) {
+        return false;
+    }
+
+    let k = hash_to_scalar(&[&big_r, public, message]);
+    let check = EdwardsPoint::mul_base(&s).sub(&a.mul_scalar(&k));
+    check.compress() == big_r
+}
+
+fn to_array<const N: usize>(bytes: &[u8], what: &str) -> Result<[u8; N], CryptoError> {
+    bytes
+        .try_into()
+        .map_err(|_| CryptoError::InvalidLength(format!("{what} must be {N} bytes")))
+}
+
+pub fn verify_strict(public: &[u8], message: &[u8], signature: &[u8]) -> Result<(), CryptoError> {
+    let public: [u8; 32] = to_array(public, "ed25519 public key")?;
+    let signature: [u8; 64] = to_array(signature, "ed25519 signature")?;
+    if verify_bytes(&public, message, &signature) {
+        Ok(())
+    } else {
+        Err(CryptoError::VerificationFailed("invalid ed25519 signature".to_string()))
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn ed25519_public_key(secret_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut secret: [u8; 32] = to_array(secret_key, "ed25519 secret key")?;
+    let public = public_key_bytes(&secret);
+    secret.zeroize();
+    Ok(public.to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn ed25519_sign(secret_key: &[u8], message: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut secret: [u8; 32] = to_array(secret_key, "ed25519 secret key")?;
+    let sig = sign_bytes(&secret, message);
+    secret.zeroize();
+    Ok(sig.to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn ed25519_verify(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool, CryptoError> {
+    let public: [u8; 32] = to_array(public_key, "ed25519 public key")?;
+    let signature: [u8; 64] = to_array(signature, "ed25519 signature")?;
+    Ok(verify_bytes(&public, message, &signature))
+}
@@ -0,0 +1,342 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+
+// Format wyjscia dla funkcji skrotu/HMAC/KDF. Domyslnie hex (zgodnosc wsteczna).
+// Base64 jest z paddingiem, Base64Url bez paddingu (jak w JWK/WebCrypto).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OutputEncoding {
+    #[default]
+    Hex,
+    Base64,
+    Base64Url,
+    Raw,
+}
+
+// Kazda czesc poprzedzona dlugoscia (u32 BE), zeby ("ab","c") != ("a","bc").
+pub fn length_prefixed(parts: &[&[u8]]) -> Vec<u8> {
+    let mut msg = Vec::new();
+    for part in parts {
+        msg.extend_from_slice(&(part.len() as u32).to_be_bytes());
+        msg.extend_from_slice(part);
+    }
+    msg
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn bytes_to_hex(data: &[u8]) -> String {
+    const TABLE: &[u8] = b"0123456789abcdef";
+    let mut s = String::with_capacity(data.len() * 2);
+    for &b in data {
+        s.push(TABLE[(b >> 4) as usize] as char);
+        s.push(TABLE[(b & 0xf) as usize] as char);
+    }
+    s
+}
+
+// Scisle: parzysta dlugosc, tylko cyfry szesnastkowe (obie wielkosci liter), bez spacji i 0x.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hex_to_bytes(text: &str) -> Result<Vec<u8>, CryptoError> {
+    if !text.len().is_multiple_of(2) {
+        return Err(CryptoError::InvalidInput("hex string has odd length".to_string()));
+    }
+    text.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| {
+            let hi = (pair[0] as char).to_digit(16);
+            let lo = (pair[1] as char).to_digit(16);
+            match (hi, lo) {
+                (Some(hi), Some(lo)) => Ok((hi << 4 | lo) as u8),
+                _ => Err(CryptoError::InvalidInput("invalid hex digit".to_string())),
+            }
+        })
+        .collect()
+}
+
+// Pola [u8; 32] w JSON jako hex: #[serde(with = "crate::encoding::hex32")].
+pub mod hex32 {
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{bytes_to_hex, hex_to_bytes};
+
+    pub fn serialize<S: Serializer>(value: &[u8; 32], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&bytes_to_hex(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<[u8; 32], D::Error> {
+        let text = String::deserialize(d)?;
+        hex_to_bytes(&text)
+            .ok()
+            .and_then(|v| v.try_into().ok())
+            .ok_or_else(|| D::Error::custom("expected 32 hex-encoded bytes"))
+    }
+}
+
+pub const BASE64_STD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+pub fn base64_with(data: &[u8], table: &[u8; 64], pad: bool) -> String {
+    let mut s = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = chunk.get(1).copied().unwrap_or(0) as u32;
+        let b2 = chunk.get(2).copied().unwrap_or(0) as u32;
+        let n = (b0 << 16) | (b1 << 8) | b2;
+
+        s.push(table[(n >> 18) as usize & 63] as char);
+        s.push(table[(n >> 12) as usize & 63] as char);
+        if chunk.len() > 1 {
+            s.push(table[(n >> 6) as usize & 63] as char);
+        } else if pad {
+            s.push('=');
+        }
+        if chunk.len() > 2 {
+            s.push(table[n as usize & 63] as char);
+        } else if pad {
+            s.push('=');
+        }
+    }
+    s
+}
+
+pub fn bytes_to_base64(data: &[u8]) -> String {
+    base64_with(data, BASE64_STD, true)
+}
+
+pub fn bytes_to_base64url(data: &[u8]) -> String {
+    base64_with(data, BASE64_URL, false)
+}
+
+// Padding opcjonalny, ale jesli jest - musi sie zgadzac z dlugoscia. Bity dopelnienia
+// ostatniego znaku musza byc zerami, zeby jeden blob mial tylko jedna postac tekstowa.
+pub fn base64_decode_with(text: &str, table: &[u8; 64]) -> Result<Vec<u8>, CryptoError> {
+    let bytes = text.as_bytes();
+    let data_len = bytes.iter().rposition(|&c| c != b'=').map_or(0, |i| i + 1);
+    let padding = bytes.len() - data_len;
+    if data_len % 4 == 1 || padding > 2 || (padding > 0 && !bytes.len().is_multiple_of(4)) {
+        return Err(CryptoError::InvalidInput("invalid base64 length".to_string()));
+    }
+    let mut out = Vec::with_capacity(data_len * 3 / 4);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for &c in &bytes[..data_len] {
+        let v = table
+            .iter()
+            .position(|&x| x == c)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("invalid base64 character '{}'", c as char)))?;
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    if acc & ((1 << bits) - 1) != 0 {
+        return Err(CryptoError::InvalidInput("invalid base64 padding bits".to_string()));
+    }
+    Ok(out)
+}
+
+pub fn base64_to_bytes(text: &str) -> Result<Vec<u8>, CryptoError> {
+    base64_decode_with(text, BASE64_STD)
+}
+
+pub fn base64url_to_bytes(text: &str) -> Result<Vec<u8>, CryptoError> {
+    base64_decode_with(text, BASE64_URL)
+}
+
+// Base32 RFC 4648 (sekrety TOTP, URI otpauth://). Dekoder toleruje male litery, spacje
+// i brak paddingu - tak sekrety wpisuja uzytkownicy i wydaja je serwisy.
+pub const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn base32_with(data: &[u8], table: &[u8; 32], pad: bool) -> String {
+    let mut s = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for &b in data {
+        acc = (acc << 8) | b as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            s.push(table[(acc >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        s.push(table[(acc << (5 - bits)) as usize & 31] as char);
+    }
+    while pad && !s.len().is_multiple_of(8) {
+        s.push('=');
+    }
+    s
+}
+
+pub fn bytes_to_base32(data: &[u8], pad: bool) -> String {
+    base32_with(data, BASE32, pad)
+}
+
+pub fn base32_to_bytes(text: &str) -> Result<Vec<u8>, CryptoError> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let trimmed = text.trim_end_matches('=');
+    if trimmed.contains('=') || (text.len() != trimmed.len() && !text.len().is_multiple_of(8)) {
+        return Err(CryptoError::InvalidInput("invalid base32 padding".to_string()));
+    }
+    let mut out = Vec::with_capacity(trimmed.len() * 5 / 8);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for c in trimmed.chars() {
+        let v = BASE32
+            .iter()
+            .position(|&x| x as char == c.to_ascii_uppercase())
+            .ok_or_else(|| CryptoError::InvalidInput(format!("invalid base32 character '{c}'")))?;
+        acc = (acc << 5) | v as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err(CryptoError::InvalidInput("invalid base32 padding".to_string()));
+    }
+    Ok(out)
+}
+
+// Crockford base32 (bez paddingu). Dekoder toleruje wielkosc liter, myslniki/spacje
+// i pomylki przy przepisywaniu: I/L -> 1, O -> 0.
+pub const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+pub fn bytes_to_crockford(data: &[u8]) -> String {
+    base32_with(data, CROCKFORD, false)
+}
+
+pub fn crockford_value(c: char) -> Option<u8> {
+    let c = match c.to_ascii_uppercase() {
+        'I' | 'L' => '1',
+        'O' => '0',
+        c => c,
+    };
+    CROCKFORD.iter().position(|&x| x as char == c).map(|v| v as u8)
+}
+
+pub fn crockford_to_bytes(text: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut out = Vec::with_capacity(text.len() * 5 / 8);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for c in text.chars().filter(|&c| c != '-' && !c.is_whitespace()) {
+        let v = crockford_value(c)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("invalid base32 character '{c}'")))?;
+        acc = (acc << 5) | v as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    // Pozostale bity to tylko dopelnienie - musza byc zerami
+    if bits >= 5 || acc & ((1 << bits) - 1) != 0 {
+        return Err(CryptoError::InvalidInput("invalid base32 padding".to_string()));
+    }
+    Ok(out)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn base64_encode(data: &[u8]) -> String {
+    bytes_to_base64(data)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn base64_decode(text: &str) -> Result<Vec<u8>, CryptoError> {
+    base64_to_bytes(text)
+}
+
+// Bez paddingu przy kodowaniu (JWK, tokeny w URL); dekoder przyjmuje oba warianty.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn base64url_encode(data: &[u8]) -> String {
+    bytes_to_base64url(data)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn base64url_decode(text: &str) -> Result<Vec<u8>, CryptoError> {
+    base64url_to_bytes(text)
+}
+
+// pad = false dla otpauth:// (Google Authenticator pomija '='), true dla scislego RFC 4648.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn base32_encode(data: &[u8], pad: bool) -> String {
+    bytes_to_base32(data, pad)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn base32_decode(text: &str) -> Result<Vec<u8>, CryptoError> {
+    base32_to_bytes(text)
+}
+
+// Zwraca string dla formatow tekstowych albo Uint8Array dla Raw.
+#[cfg(feature = "js")]
+pub fn encode_output(data: &[u8], encoding: Option<OutputEncoding>) -> JsValue {
+    match encoding.unwrap_or_default() {
+        OutputEncoding::Hex => JsValue::from_str(&bytes_to_hex(data)),
+        OutputEncoding::Base64 => JsValue::from_str(&bytes_to_base64(data)),
+        OutputEncoding::Base64Url => JsValue::from_str(&bytes_to_base64url(data)),
+        OutputEncoding::Raw => js_sys::Uint8Array::from(data).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648 10: (dane, base16, base32, base64)
+    const VECTORS: [(&str, &str, &str, &str); 7] = [
+        ("", "", "", ""),
+        ("f", "66", "MY======", "Zg=="),
+        ("fo", "666F", "MZXQ====", "Zm8="),
+        ("foo", "666F6F", "MZXW6===", "Zm9v"),
+        ("foob", "666F6F62", "MZXW6YQ=", "Zm9vYg=="),
+        ("fooba", "666F6F6261", "MZXW6YTB", "Zm9vYmE="),
+        ("foobar", "666F6F626172", "MZXW6YTBOI======", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn rfc4648_vectors() -> Result<(), CryptoError> {
+        for (data, hex, base32, base64) in VECTORS {
+            assert_eq!(bytes_to_hex(data.as_bytes()), hex.to_lowercase());
+            assert_eq!(hex_to_bytes(hex)?, data.as_bytes());
+            assert_eq!(hex_to_bytes(&hex.to_lowercase())?, data.as_bytes());
+            assert_eq!(bytes_to_base32(data.as_bytes(), true), base32);
+            assert_eq!(base32_to_bytes(base32)?, data.as_bytes());
+            assert_eq!(base32_to_bytes(base32.trim_end_matches('='))?, data.as_bytes());
+            assert_eq!(bytes_to_base64(data.as_bytes()), base64);
+            assert_eq!(base64_decode_with(base64, BASE64_STD)?, data.as_bytes());
+            assert_eq!(base64_decode_with(base64.trim_end_matches('='), BASE64_STD)?, data.as_bytes());
+        }
+        assert_eq!(base32_to_bytes("mzxw 6ytb oi")?, b"foobar");
+        Ok(())
+    }
+
+    #[test]
+    fn hex_rejects_odd_length_and_bad_digits() {
+        for bad in ["6", "666", "6g", "0x66", "66 ", "é6"] {
+            assert!(matches!(hex_to_bytes(bad), Err(CryptoError::InvalidInput(_))), "{bad}");
+        }
+    }
+
+    #[test]
+    fn base64_rejects_pad_bits_and_misplaced_padding() {
+        for bad in ["Zh==", "Zh", "Zm9=", "Zm9", "Zm9vYh==", "Zm=v", "=Zm9", "Zg=", "Zg===", "Zg=a", "Z", "Zm9v!"] {
+            assert!(matches!(base64_decode_with(bad, BASE64_STD), Err(CryptoError::InvalidInput(_))), "{bad}");
+        }
+        assert!(base64_decode_with("-_8", BASE64_STD).is_err());
+        assert!(matches!(base64url_to_bytes("-_8"), Ok(v) if v == [0xfb, 0xff]));
+    }
+
+    #[test]
+    fn base32_rejects_pad_bits_and_misplaced_padding() {
+        for bad in ["MZ======", "MZXR====", "MY=A====", "MY=====", "M", "MY1=====", "=MY"] {
+            assert!(matches!(base32_to_bytes(bad), Err(CryptoError::InvalidInput(_))), "{bad}");
+        }
+    }
+}
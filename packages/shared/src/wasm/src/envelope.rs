@@ -0,0 +1,159 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::keywrap::{aes_kw_unwrap_bytes, aes_kw_wrap_bytes};
+use crate::rng::random_array;
+
+// Szyfrowanie kopertowe DEK/KEK: kazdy blob ma wlasny losowy klucz danych (DEK), ktorym
+// szyfrowana jest tresc, a DEK jest opakowany kluczem KEK (AES-KW). Zmiana KEK przepisuje
+// tylko opakowany klucz (envelope_rewrap), bez ponownego szyfrowania tresci.
+//   "PMDE" || wersja (1) || opakowany DEK (40 B) || nonce (12 B) || ciphertext || tag
+// Tresc: AES-256-GCM kluczem DEK, AAD = magic || wersja. Opakowanego klucza nie trzeba
+// wiazac w AAD - podmieniony daje inny DEK (albo blad AES-KW), wiec tag GCM sie nie zgodzi.
+const MAGIC: &[u8; 4] = b"PMDE";
+const VERSION: u8 = 1;
+const DEK_LEN: usize = 32;
+const WRAPPED_LEN: usize = DEK_LEN + 8;
+const PREFIX_LEN: usize = MAGIC.len() + 1;
+const HEADER_LEN: usize = PREFIX_LEN + WRAPPED_LEN;
+
+fn split_header(blob: &[u8]) -> Result<(&[u8], &[u8]), CryptoError> {
+    if !blob.starts_with(MAGIC) {
+        return Err(CryptoError::CorruptHeader("not a data key envelope".to_string()));
+    }
+    if blob.len() < HEADER_LEN + NONCE_LEN + TAG_LEN {
+        return Err(CryptoError::CorruptHeader("data key envelope truncated".to_string()));
+    }
+    if blob[4] != VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("data key envelope version {}", blob[4])));
+    }
+    Ok(blob.split_at(HEADER_LEN))
+}
+
+fn unwrap_dek(kek: &[u8], header: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    aes_kw_unwrap_bytes(kek, &header[PREFIX_LEN..]).map_err(|e| match e {
+        CryptoError::DecryptFailed(_) => CryptoError::DecryptFailed("wrong key encryption key".to_string()),
+        other => other,
+    })
+}
+
+pub fn envelope_encrypt_bytes(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let dek = Zeroizing::new(random_array::<DEK_LEN>()?);
+    let nonce = random_array::<NONCE_LEN>()?;
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+    out.extend_from_slice(&aes_kw_wrap_bytes(kek, dek.as_slice())?);
+    let sealed = AesGcm::new(dek.as_slice())?.encrypt(&nonce, &out[..PREFIX_LEN], plaintext)?;
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+pub fn envelope_decrypt_bytes(kek: &[u8], blob: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let (header, body) = split_header(blob)?;
+    let dek = unwrap_dek(kek, header)?;
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    Ok(Zeroizing::new(AesGcm::new(&dek)?.decrypt(nonce, &header[..PREFIX_LEN], sealed)?))
+}
+
+// Nowy KEK dla istniejacego blobu - podmieniany jest tylko opakowany DEK. Tresc jest
+// najpierw sprawdzana (tag GCM), zeby nie przepisac uszkodzonego blobu.
+pub fn envelope_rewrap_bytes(old_kek: &[u8], new_kek: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let (header, body) = split_header(blob)?;
+    let dek = unwrap_dek(old_kek, header)?;
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    AesGcm::new(&dek)?.decrypt(nonce, &header[..PREFIX_LEN], sealed)?.zeroize();
+    let mut out = header[..PREFIX_LEN].to_vec();
+    out.extend_from_slice(&aes_kw_wrap_bytes(new_kek, &dek)?);
+    out.extend_from_slice(body);
+    Ok(out)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn envelope_encrypt(kek: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    envelope_encrypt_bytes(kek, plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn envelope_decrypt(kek: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut plaintext = envelope_decrypt_bytes(kek, blob)?;
+    Ok(std::mem::take(&mut *plaintext))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn envelope_rewrap(old_kek: &[u8], new_kek: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    envelope_rewrap_bytes(old_kek, new_kek, blob)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEK: [u8; 32] = [0x11; 32];
+    const NEW_KEK: [u8; 32] = [0x22; 32];
+
+    #[test]
+    fn round_trip() -> Result<(), CryptoError> {
+        for plaintext in [&b""[..], b"secret", &[0xa5; 1000]] {
+            let blob = envelope_encrypt_bytes(&KEK, plaintext)?;
+            assert!(blob.starts_with(b"PMDE\x01"));
+            assert_eq!(blob.len(), HEADER_LEN + NONCE_LEN + plaintext.len() + TAG_LEN);
+            assert_eq!(&*envelope_decrypt_bytes(&KEK, &blob)?, plaintext);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rewrap_keeps_the_body_and_changes_the_kek() -> Result<(), CryptoError> {
+        let blob = envelope_encrypt_bytes(&KEK, b"secret")?;
+        let rewrapped = envelope_rewrap_bytes(&KEK, &NEW_KEK, &blob)?;
+        assert_eq!(rewrapped.len(), blob.len());
+        assert_eq!(rewrapped[..PREFIX_LEN], blob[..PREFIX_LEN]);
+        assert_eq!(rewrapped[HEADER_LEN..], blob[HEADER_LEN..]);
+        assert_ne!(rewrapped[PREFIX_LEN..HEADER_LEN], blob[PREFIX_LEN..HEADER_LEN]);
+        assert_eq!(&*envelope_decrypt_bytes(&NEW_KEK, &rewrapped)?, b"secret");
+        assert!(matches!(envelope_decrypt_bytes(&KEK, &rewrapped), Err(CryptoError::DecryptFailed(_))));
+        assert!(matches!(envelope_rewrap_bytes(&NEW_KEK, &KEK, &blob), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_kek_fails() -> Result<(), CryptoError> {
+        let blob = envelope_encrypt_bytes(&KEK, b"secret")?;
+        assert_eq!(
+            envelope_decrypt_bytes(&NEW_KEK, &blob),
+            Err(CryptoError::DecryptFailed("wrong key encryption key".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tampering_is_detected() -> Result<(), CryptoError> {
+        let blob = envelope_encrypt_bytes(&KEK, b"secret")?;
+        // opakowany DEK, nonce, ciphertext, tag
+        for at in [PREFIX_LEN, HEADER_LEN - 1, HEADER_LEN, HEADER_LEN + NONCE_LEN, blob.len() - 1] {
+            let mut tampered = blob.clone();
+            tampered[at] ^= 1;
+            assert!(matches!(envelope_decrypt_bytes(&KEK, &tampered), Err(CryptoError::DecryptFailed(_))));
+            assert!(matches!(envelope_rewrap_bytes(&KEK, &NEW_KEK, &tampered), Err(CryptoError::DecryptFailed(_))));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bad_magic_version_and_length_are_rejected() -> Result<(), CryptoError> {
+        let blob = envelope_encrypt_bytes(&KEK, b"secret")?;
+        let mut magic = blob.clone();
+        magic[0] = b'X';
+        assert!(matches!(envelope_decrypt_bytes(&KEK, &magic), Err(CryptoError::CorruptHeader(_))));
+        let mut version = blob.clone();
+        version[4] = 2;
+        assert!(matches!(envelope_decrypt_bytes(&KEK, &version), Err(CryptoError::UnsupportedVersion(_))));
+        let truncated = &blob[..HEADER_LEN + NONCE_LEN + TAG_LEN - 1];
+        assert!(matches!(envelope_decrypt_bytes(&KEK, truncated), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt;
+
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+// Wszystkie bledy zwracane przez crate. Eksporty wasm nie panikuja - kazda
+// porazka wraca jako Err i po stronie JS jest rzucana jako Error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    InvalidLength(String),
+    InvalidParameter(String),
+    InvalidInput(String),
+    UnsupportedAlgorithm(String),
+    VerificationFailed(String),
+    DecryptFailed(String),
+    Rng(String),
+    NotApproved(String),
+    OutOfRange(String),
+    CorruptHeader(String),
+    UnsupportedVersion(String),
+    BadMac(String),
+    WrongPassword(String),
+    InvalidIterations(String),
+}
+
+// Stabilne kody bledow dla klientow (lokalizacja komunikatow po kodzie, nie po tekscie).
+// Numery i nazwy sa czescia API - nowe kody tylko dopisywac na koncu.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    InvalidLength = 1,
+    InvalidParameter = 2,
+    InvalidInput = 3,
+    UnsupportedAlgorithm = 4,
+    VerificationFailed = 5,
+    DecryptFailed = 6,
+    Rng = 7,
+    NotApproved = 8,
+    OutOfRange = 9,
+    CorruptHeader = 10,
+    UnsupportedVersion = 11,
+    BadMac = 12,
+    WrongPassword = 13,
+    InvalidIterations = 14,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidLength => "invalid_length",
+            ErrorCode::InvalidParameter => "invalid_parameter",
+            ErrorCode::InvalidInput => "invalid_input",
+            ErrorCode::UnsupportedAlgorithm => "unsupported_algorithm",
+            ErrorCode::VerificationFailed => "verification_failed",
+            ErrorCode::DecryptFailed => "decrypt_failed",
+            ErrorCode::Rng => "rng_failure",
+            ErrorCode::NotApproved => "not_approved",
+            ErrorCode::OutOfRange => "out_of_range",
+            ErrorCode::CorruptHeader => "corrupt_header",
+            ErrorCode::UnsupportedVersion => "unsupported_version",
+            ErrorCode::BadMac => "bad_mac",
+            ErrorCode::WrongPassword => "wrong_password",
+            ErrorCode::InvalidIterations => "invalid_iterations",
+        }
+    }
+}
+
+impl CryptoError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CryptoError::InvalidLength(_) => ErrorCode::InvalidLength,
+            CryptoError::InvalidParameter(_) => ErrorCode::InvalidParameter,
+            CryptoError::InvalidInput(_) => ErrorCode::InvalidInput,
+            CryptoError::UnsupportedAlgorithm(_) => ErrorCode::UnsupportedAlgorithm,
+            CryptoError::VerificationFailed(_) => ErrorCode::VerificationFailed,
+            CryptoError::DecryptFailed(_) => ErrorCode::DecryptFailed,
+            CryptoError::Rng(_) => ErrorCode::Rng,
+            CryptoError::NotApproved(_) => ErrorCode::NotApproved,
+            CryptoError::OutOfRange(_) => ErrorCode::OutOfRange,
+            CryptoError::CorruptHeader(_) => ErrorCode::CorruptHeader,
+            CryptoError::UnsupportedVersion(_) => ErrorCode::UnsupportedVersion,
+            CryptoError::BadMac(_) => ErrorCode::BadMac,
+            CryptoError::WrongPassword(_) => ErrorCode::WrongPassword,
+            CryptoError::InvalidIterations(_) => ErrorCode::InvalidIterations,
+        }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn error_code_name(code: ErrorCode) -> String {
+    code.as_str().to_string()
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::InvalidLength(msg) => write!(f, "invalid length: {msg}"),
+            CryptoError::InvalidParameter(msg) => write!(f, "invalid parameter: {msg}"),
+            CryptoError::InvalidInput(msg) => write!(f, "invalid input: {msg}"),
+            CryptoError::UnsupportedAlgorithm(msg) => write!(f, "unsupported algorithm: {msg}"),
+            CryptoError::VerificationFailed(msg) => write!(f, "verification failed: {msg}"),
+            CryptoError::DecryptFailed(msg) => write!(f, "decryption failed: {msg}"),
+            CryptoError::Rng(msg) => write!(f, "random generator failure: {msg}"),
+            CryptoError::NotApproved(msg) => write!(f, "not approved in FIPS profile: {msg}"),
+            CryptoError::OutOfRange(msg) => write!(f, "parameter out of range: {msg}"),
+            CryptoError::CorruptHeader(msg) => write!(f, "corrupt header: {msg}"),
+            CryptoError::UnsupportedVersion(msg) => write!(f, "unsupported version: {msg}"),
+            CryptoError::BadMac(msg) => write!(f, "MAC mismatch: {msg}"),
+            CryptoError::WrongPassword(msg) => write!(f, "wrong password: {msg}"),
+            CryptoError::InvalidIterations(msg) => write!(f, "invalid iteration count: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+// Po stronie JS: err.code - nazwa kodu, err.errno - numer.
+#[cfg(feature = "js")]
+impl From<CryptoError> for JsValue {
+    fn from(err: CryptoError) -> JsValue {
+        let js_err = js_sys::Error::new(&err.to_string());
+        let code = err.code();
+        let _ = js_sys::Reflect::set(&js_err, &"code".into(), &code.as_str().into());
+        let _ = js_sys::Reflect::set(&js_err, &"errno".into(), &(code as u32).into());
+        js_err.into()
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::ct::ct_eq;
+use crate::encoding::bytes_to_hex;
+use crate::error::CryptoError;
+use crate::hmac::hmac_sha256_bytes;
+
+// Podpis zdarzen z eksportu serwera: HMAC-SHA256(key, "<timestamp>.<payload>") jako hex.
+// Timestamp w sekundach (unix) jest czescia podpisu, wiec nie da sie go przesunac,
+// a okno tolerancji ogranicza ponowne wyslanie starego zdarzenia.
+pub const DEFAULT_TOLERANCE_SECS: u32 = 300;
+
+fn signed_message(payload: &[u8], timestamp: u64) -> Vec<u8> {
+    let mut msg = timestamp.to_string().into_bytes();
+    msg.push(b'.');
+    msg.extend_from_slice(payload);
+    msg
+}
+
+pub fn sign_event_bytes(key: &[u8], payload: &[u8], timestamp: u64) -> Result<[u8; 32], CryptoError> {
+    if key.len() < 16 {
+        return Err(CryptoError::InvalidLength("event signing key must be at least 16 bytes".to_string()));
+    }
+    Ok(hmac_sha256_bytes(key, &signed_message(payload, timestamp)))
+}
+
+pub fn verify_event_at(
+    key: &[u8],
+    payload: &[u8],
+    timestamp: u64,
+    signature: &str,
+    now: u64,
+    tolerance_secs: u32,
+) -> Result<(), CryptoError> {
+    let expected = bytes_to_hex(&sign_event_bytes(key, payload, timestamp)?);
+    // Najpierw podpis, potem czas - zeby nie zdradzac, ktory warunek zawiodl dla falszywek
+    if !ct_eq(expected.as_bytes(), signature.to_ascii_lowercase().as_bytes()) {
+        return Err(CryptoError::BadMac("event signature".to_string()));
+    }
+    if now.abs_diff(timestamp) > tolerance_secs as u64 {
+        return Err(CryptoError::VerificationFailed("event timestamp outside replay window".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "js")]
+fn now_secs() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+#[cfg(not(feature = "js"))]
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn sign_event(key: &[u8], payload: &str, timestamp: u64) -> Result<String, CryptoError> {
+    Ok(bytes_to_hex(&sign_event_bytes(key, payload.as_bytes(), timestamp)?))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_event(
+    key: &[u8],
+    payload: &str,
+    timestamp: u64,
+    signature: &str,
+    tolerance_secs: Option<u32>,
+) -> Result<(), CryptoError> {
+    verify_event_at(
+        key,
+        payload.as_bytes(),
+        timestamp,
+        signature,
+        now_secs(),
+        tolerance_secs.unwrap_or(DEFAULT_TOLERANCE_SECS),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"0123456789abcdef";
+    const PAYLOAD: &[u8] = br#"{"type":"item.updated","id":"a"}"#;
+    const TIMESTAMP: u64 = 1_700_000_000;
+
+    fn signature() -> Result<String, CryptoError> {
+        Ok(bytes_to_hex(&sign_event_bytes(KEY, PAYLOAD, TIMESTAMP)?))
+    }
+
+    #[test]
+    fn accepts_valid_event() -> Result<(), CryptoError> {
+        let signature = signature()?;
+        verify_event_at(KEY, PAYLOAD, TIMESTAMP, &signature, TIMESTAMP, DEFAULT_TOLERANCE_SECS)?;
+        verify_event_at(KEY, PAYLOAD, TIMESTAMP, &signature.to_uppercase(), TIMESTAMP, DEFAULT_TOLERANCE_SECS)?;
+        // HMAC-SHA256(key, "<timestamp>.<payload>")
+        let expected = hmac_sha256_bytes(KEY, &[b"1700000000.", PAYLOAD].concat());
+        assert_eq!(signature, bytes_to_hex(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_forged_signature() -> Result<(), CryptoError> {
+        let mut signature = signature()?.into_bytes();
+        signature[0] = if signature[0] == b'0' { b'1' } else { b'0' };
+        let signature = String::from_utf8(signature).unwrap_or_default();
+        let verified = verify_event_at(KEY, PAYLOAD, TIMESTAMP, &signature, TIMESTAMP, DEFAULT_TOLERANCE_SECS);
+        assert!(matches!(verified, Err(CryptoError::BadMac(_))));
+        // Podpis innego timestampu - czasu nie da sie przesunac
+        let shifted = bytes_to_hex(&sign_event_bytes(KEY, PAYLOAD, TIMESTAMP + 1)?);
+        let verified = verify_event_at(KEY, PAYLOAD, TIMESTAMP, &shifted, TIMESTAMP, DEFAULT_TOLERANCE_SECS);
+        assert!(matches!(verified, Err(CryptoError::BadMac(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn enforces_tolerance_window_on_both_sides() -> Result<(), CryptoError> {
+        let signature = signature()?;
+        let tolerance = 60;
+        for now in [TIMESTAMP - 60, TIMESTAMP + 60] {
+            verify_event_at(KEY, PAYLOAD, TIMESTAMP, &signature, now, tolerance)?;
+        }
+        for now in [TIMESTAMP - 61, TIMESTAMP + 61] {
+            let verified = verify_event_at(KEY, PAYLOAD, TIMESTAMP, &signature, now, tolerance);
+            assert!(matches!(verified, Err(CryptoError::VerificationFailed(_))));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_short_key() {
+        assert!(matches!(sign_event_bytes(&KEY[..15], PAYLOAD, TIMESTAMP), Err(CryptoError::InvalidLength(_))));
+        let verified = verify_event_at(&KEY[..15], PAYLOAD, TIMESTAMP, "00", TIMESTAMP, DEFAULT_TOLERANCE_SECS);
+        assert!(matches!(verified, Err(CryptoError::InvalidLength(_))));
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::aead::AeadAlgorithm;
+use crate::digest::HashAlgorithm;
+use crate::error::CryptoError;
+
+// Profil FIPS (cargo build --features fips-profile): konstruowac mozna tylko algorytmy
+// zatwierdzone (FIPS 180-4, 202, 197 + SP 800-38D, SP 800-56C, SP 800-132, FIPS 186-5).
+// SM3/SM4, SHA-1, BLAKE2b/BLAKE3, X25519 i (X)ChaCha20-Poly1305 zwracaja NotApproved.
+// CRC i wewnetrzny SipHash (klucz sesji, tablice deduplikacji) zostaja - nie chronia danych;
+// eksport siphash24 z kluczem wywolujacego to PRF, wiec zwraca NotApproved.
+pub const FIPS_PROFILE: bool = cfg!(feature = "fips-profile");
+
+// SP 800-132: sol min. 128 bitow, klucz min. 112 bitow.
+pub const MIN_PBKDF2_SALT_LEN: usize = 16;
+pub const MIN_PBKDF2_DK_LEN: usize = 14;
+pub const MIN_PBKDF2_ITERATIONS: u32 = 1000;
+
+// HMAC-SHA-1 zostaje (SP 800-131A) - HOTP/TOTP; sam SHA-1 (HIBP, sumy importow) nie.
+const APPROVED: &[&str] = &[
+    "sha256",
+    "sha384",
+    "sha512",
+    "sha512-256",
+    "sha3-256",
+    "sha3-512",
+    "hmac-sha1",
+    "hmac-sha256",
+    "hmac-sha512",
+    "hmac-sha3-256",
+    "hmac-sha3-512",
+    "hkdf-sha256",
+    "hkdf-sha512",
+    "pbkdf2-sha256",
+    "pbkdf2-sha512",
+    "aes-256-gcm",
+    "aes-kw",
+    "ed25519",
+];
+
+pub fn is_approved(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    APPROVED.contains(&name.as_str())
+}
+
+pub fn require_approved(name: &str) -> Result<(), CryptoError> {
+    if FIPS_PROFILE && !is_approved(name) {
+        return Err(CryptoError::NotApproved(name.to_string()));
+    }
+    Ok(())
+}
+
+pub fn check_hash(algorithm: HashAlgorithm) -> Result<(), CryptoError> {
+    require_approved(algorithm.name())
+}
+
+pub fn check_aead(algorithm: AeadAlgorithm) -> Result<(), CryptoError> {
+    require_approved(algorithm.name())
+}
+
+pub fn check_pbkdf2(salt_len: usize, iterations: u32, dk_len: usize) -> Result<(), CryptoError> {
+    if !FIPS_PROFILE {
+        return Ok(());
+    }
+    if salt_len < MIN_PBKDF2_SALT_LEN {
+        return Err(CryptoError::NotApproved(format!("pbkdf2 salt shorter than {MIN_PBKDF2_SALT_LEN} bytes")));
+    }
+    if dk_len < MIN_PBKDF2_DK_LEN {
+        return Err(CryptoError::NotApproved(format!("pbkdf2 key shorter than {MIN_PBKDF2_DK_LEN} bytes")));
+    }
+    if iterations < MIN_PBKDF2_ITERATIONS {
+        return Err(CryptoError::NotApproved(format!("pbkdf2 with fewer than {MIN_PBKDF2_ITERATIONS} iterations")));
+    }
+    Ok(())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn fips_profile_enabled() -> bool {
+    FIPS_PROFILE
+}
+
+// Czy vault moze uzyc algorytmu w tej kompilacji (poza profilem FIPS - zawsze true).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn algorithm_allowed(name: &str) -> bool {
+    require_approved(name).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argon2::{Argon2Variant, argon2_hash, argon2_verify_raw, argon2id, argon2id_hash_phc_raw};
+    use crate::bcrypt::{bcrypt_hash_raw, bcrypt_verify_raw};
+    use crate::blake2b::blake2b;
+    use crate::blake3::{Blake3Hasher, blake3_derive_key, blake3_hash, blake3_keyed};
+    use crate::checksum::ChecksumVerifier;
+    use crate::crc::crc32;
+    use crate::digest::digest_reader;
+    use crate::hibp::{hibp_prefix, hibp_prefix_raw};
+    use crate::hkdf::{hkdf_sha256, hkdf_sha512};
+    use crate::hmac::{hmac_sha1_interop_raw, hmac_sha3_256_raw, hmac_sha3_512_raw};
+    use crate::legacy::{legacy_hmac_sha256, legacy_pbkdf2_hmac_sha256, legacy_pbkdf2_hmac_sha512, legacy_vault_key};
+    use crate::multihash::MultiHasher;
+    use crate::scrypt::{scrypt, scrypt_hash_mcf_raw, scrypt_hash_phc_raw, scrypt_verify_raw};
+    use crate::sha1::sha1_interop_raw;
+    use crate::siphash::siphash24;
+    use crate::{
+        hmac_sha256_raw, hmac_sha512_raw, pbkdf2_hmac_sha256_raw, pbkdf2_hmac_sha256_rfc8018_raw, pbkdf2_hmac_sha512_raw,
+        pbkdf2_hmac_sha512_rfc8018_raw, sha256_raw,
+    };
+
+    type Probe = fn() -> Result<(), CryptoError>;
+
+    fn ok<T>(result: Result<T, CryptoError>) -> Result<(), CryptoError> {
+        result.map(|_| ())
+    }
+
+    // Eksporty bez Result - algorytm zatwierdzony albo poza ochrona danych.
+    fn done<T>(_: T) -> Result<(), CryptoError> {
+        Ok(())
+    }
+
+    const SALT: &[u8] = b"sixteen byte salt";
+    const ARGON2_PHC: &str = "$argon2id$v=19$m=64,t=1,p=1$/rbcrzneUJwn85KPsqEqdQ$+1A78EBqiHQv0LVnT+p6BKQ1bUAOyIB3B/BMVmfjxns";
+    const SCRYPT_PHC: &str = "$scrypt$ln=4,r=1,p=1$QdBW6xM+zwVl/VYukFHAGQ$xKsSxCNYMflyX1nwfVrKnYZOZyQQOFBBPidFJRvC3SU";
+    const BCRYPT_MCF: &str = "$2b$04$0W95S/86RceZc3wJdW73RuKKrMrLNVAOYo1CkthHZSgOW3LsZ4K.u";
+
+    // Kazdy eksport skrotu/KDF (wersje _raw zamiast zwracajacych JsValue, ktore wywoluja ten sam
+    // rdzen) i czy dziala w profilu FIPS. Nowy eksport = nowy wiersz.
+    const EXPORTS: &[(&str, bool, Probe)] = &[
+        ("sha256_raw", true, || done(sha256_raw(b"abc"))),
+        ("hmac_sha256_raw", true, || done(hmac_sha256_raw(b"key", b"abc"))),
+        ("hmac_sha512_raw", true, || done(hmac_sha512_raw(b"key", b"abc"))),
+        ("hmac_sha3_256_raw", true, || done(hmac_sha3_256_raw(b"key", b"abc"))),
+        ("hmac_sha3_512_raw", true, || done(hmac_sha3_512_raw(b"key", b"abc"))),
+        ("hmac_sha1_interop_raw", true, || ok(hmac_sha1_interop_raw(b"key", b"abc"))),
+        ("hkdf_sha256", true, || ok(hkdf_sha256(b"ikm", SALT, b"info", 32))),
+        ("hkdf_sha512", true, || ok(hkdf_sha512(b"ikm", SALT, b"info", 32))),
+        ("pbkdf2_hmac_sha256_raw", true, || ok(pbkdf2_hmac_sha256_raw(b"pw", SALT, 1000, 32))),
+        ("pbkdf2_hmac_sha512_raw", true, || ok(pbkdf2_hmac_sha512_raw(b"pw", SALT, 1000, 32))),
+        ("pbkdf2_hmac_sha256_rfc8018_raw", true, || ok(pbkdf2_hmac_sha256_rfc8018_raw(b"pw", SALT, 1000, 32))),
+        ("pbkdf2_hmac_sha512_rfc8018_raw", true, || ok(pbkdf2_hmac_sha512_rfc8018_raw(b"pw", SALT, 1000, 32))),
+        ("legacy_hmac_sha256", true, || done(legacy_hmac_sha256("key", "abc"))),
+        ("legacy_pbkdf2_hmac_sha256", true, || ok(legacy_pbkdf2_hmac_sha256("pw", "sixteen byte salt", 1000, 32))),
+        ("legacy_pbkdf2_hmac_sha512", true, || ok(legacy_pbkdf2_hmac_sha512("pw", "sixteen byte salt", 1000, 32))),
+        ("legacy_vault_key", true, || ok(legacy_vault_key("pw", "secret", "sixteen byte salt", 1000))),
+        ("digest(sha3-256)", true, || ok(digest_reader(HashAlgorithm::Sha3_256, &mut &b"abc"[..]))),
+        ("digest(sm3)", false, || ok(digest_reader(HashAlgorithm::Sm3, &mut &b"abc"[..]))),
+        ("digest(sha1)", false, || ok(digest_reader(HashAlgorithm::Sha1, &mut &b"abc"[..]))),
+        ("digest(blake3)", false, || ok(digest_reader(HashAlgorithm::Blake3, &mut &b"abc"[..]))),
+        ("MultiHasher(sha256, sha512)", true, || ok(MultiHasher::new(vec!["sha256".into(), "sha512".into()]))),
+        ("MultiHasher(sha256, sha1)", false, || ok(MultiHasher::new(vec!["sha256".into(), "sha1".into()]))),
+        ("ChecksumVerifier(sha256)", true, || ok(ChecksumVerifier::new("sha256", &[0; 32], 0))),
+        ("ChecksumVerifier(sm3)", false, || ok(ChecksumVerifier::new("sm3", &[0; 32], 0))),
+        ("sha1_interop_raw", false, || ok(sha1_interop_raw(b"abc"))),
+        ("hibp_prefix", false, || ok(hibp_prefix("password"))),
+        ("hibp_prefix_raw", false, || ok(hibp_prefix_raw(b"password"))),
+        ("blake2b", false, || ok(blake2b(b"abc", b"", 32))),
+        ("blake3_hash", false, || ok(blake3_hash(b"abc"))),
+        ("blake3_keyed", false, || ok(blake3_keyed(&[0; 32], b"abc"))),
+        ("blake3_derive_key", false, || ok(blake3_derive_key("pm test", b"abc"))),
+        ("Blake3Hasher", false, || ok(Blake3Hasher::new())),
+        ("argon2id", false, || ok(argon2id(b"pw", SALT, 64, 1, 1, 32))),
+        ("argon2_hash", false, || ok(argon2_hash(Argon2Variant::Argon2d, b"pw", SALT, 64, 1, 1, 32))),
+        ("argon2id_hash_phc_raw", false, || ok(argon2id_hash_phc_raw(b"pw", 64, 1, 1))),
+        ("argon2_verify_raw", false, || ok(argon2_verify_raw(b"pw", ARGON2_PHC))),
+        ("scrypt", false, || ok(scrypt(b"pw", SALT, 16, 1, 1, 32))),
+        ("scrypt_hash_phc_raw", false, || ok(scrypt_hash_phc_raw(b"pw", 4, 1, 1))),
+        ("scrypt_hash_mcf_raw", false, || ok(scrypt_hash_mcf_raw(b"pw", 4, 1, 1))),
+        ("scrypt_verify_raw", false, || ok(scrypt_verify_raw(b"pw", SCRYPT_PHC))),
+        ("bcrypt_hash_raw", false, || ok(bcrypt_hash_raw(b"pw", 4))),
+        ("bcrypt_verify_raw", false, || ok(bcrypt_verify_raw(b"pw", BCRYPT_MCF))),
+        // Suma kontrolna - nie chroni danych, poza profilem
+        ("crc32", true, || done(crc32(b"abc"))),
+        ("siphash24", false, || ok(siphash24(&[0; 16], b"abc"))),
+    ];
+
+    #[test]
+    fn hash_and_kdf_exports_follow_the_profile() {
+        for (name, approved, probe) in EXPORTS {
+            let result = probe();
+            if FIPS_PROFILE && !approved {
+                assert!(matches!(result, Err(CryptoError::NotApproved(_))), "{name} should be refused: {result:?}");
+            } else {
+                assert!(result.is_ok(), "{name} should be allowed: {result:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn approval_list_matches_names() {
+        for name in ["sha256", "SHA512", "hmac-sha1", "aes-256-gcm", "pbkdf2-sha256"] {
+            assert!(is_approved(name), "{name}");
+        }
+        for name in ["sha1", "sm3", "blake2b", "blake3", "argon2id", "scrypt", "bcrypt", "x25519"] {
+            assert!(!is_approved(name), "{name}");
+            assert_eq!(algorithm_allowed(name), !FIPS_PROFILE, "{name}");
+        }
+    }
+
+    #[test]
+    fn pbkdf2_parameters_are_checked_only_in_profile() {
+        let weak = [check_pbkdf2(8, 1000, 32), check_pbkdf2(16, 999, 32), check_pbkdf2(16, 1000, 8)];
+        for result in weak {
+            assert_eq!(result.is_err(), FIPS_PROFILE);
+        }
+        assert!(check_pbkdf2(MIN_PBKDF2_SALT_LEN, MIN_PBKDF2_ITERATIONS, MIN_PBKDF2_DK_LEN).is_ok());
+    }
+}
@@ -0,0 +1,768 @@
+use std::collections::BTreeMap;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::ed25519::{EdwardsPoint, scalar_add, scalar_invert, scalar_is_canonical, scalar_mul, scalar_mul_add, scalar_neg, scalar_reduce};
+use crate::encoding::{bytes_to_hex, hex_to_bytes, hex32};
+use crate::error::CryptoError;
+use crate::rng::random_array;
+use crate::sha2::Sha512;
+
+// Progowe podpisy FROST(Ed25519, SHA-512) wg RFC 9591 - zatwierdzenia k-z-n adminow
+// organizacji (reset odzyskiwania, eksport wspolnych kolekcji). Wynikowy podpis to zwykly
+// podpis Ed25519 pod kluczem grupy, weryfikowany przez ed25519_verify.
+//
+// Klucze: DKG Pedersena (jak w pracy o FROST) - nikt, takze koordynator, nie zna calego
+// klucza. Runda 1: kazdy rozglasza zobowiazania do wielomianu stopnia t-1 i dowod znajomosci
+// wyrazu wolnego. Runda 2: kazdy wysyla uczestnikowi j udzial f_i(j) - kanalem szyfrowanym
+// (np. SecureChannel albo wrap_group_key), bo to material tajny.
+//
+// Podpis: kazdy podpisujacy tworzy FrostSigner (nonce jednorazowe), wysyla zobowiazania,
+// koordynator sklada pakiet do podpisu, zbiera udzialy i agreguje je frost_aggregate.
+// Identyfikatory uczestnikow: 1..=max_signers.
+const CONTEXT: &[u8] = b"FROST-ED25519-SHA512-v1";
+
+mod hex32_vec {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &[[u8; 32]], s: S) -> Result<S::Ok, S::Error> {
+        s.collect_seq(value.iter().map(|v| bytes_to_hex(v)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<[u8; 32]>, D::Error> {
+        Vec::<String>::deserialize(d)?
+            .iter()
+            .map(|text| {
+                hex_to_bytes(text)
+                    .ok()
+                    .and_then(|v| v.try_into().ok())
+                    .ok_or_else(|| D::Error::custom("expected 32 hex-encoded bytes"))
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Round1Package {
+    pub identifier: u16,
+    #[serde(with = "hex32_vec")]
+    pub commitment: Vec<[u8; 32]>,
+    #[serde(with = "hex32")]
+    pub proof_r: [u8; 32],
+    #[serde(with = "hex32")]
+    pub proof_mu: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Round2Package {
+    pub sender: u16,
+    pub recipient: u16,
+    #[serde(with = "hex32")]
+    pub share: [u8; 32],
+}
+
+impl Drop for Round2Package {
+    fn drop(&mut self) {
+        self.share.zeroize();
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyPackage {
+    pub identifier: u16,
+    #[serde(with = "hex32")]
+    pub signing_share: [u8; 32],
+    #[serde(with = "hex32")]
+    pub verifying_share: [u8; 32],
+    #[serde(with = "hex32")]
+    pub group_public_key: [u8; 32],
+    pub min_signers: u16,
+}
+
+impl Drop for KeyPackage {
+    fn drop(&mut self) {
+        self.signing_share.zeroize();
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PublicKeyPackage {
+    #[serde(with = "hex32")]
+    pub group_public_key: [u8; 32],
+    pub verifying_shares: BTreeMap<u16, String>,
+    pub min_signers: u16,
+}
+
+#[derive(Serialize)]
+struct DkgOutput<'a> {
+    key_package: &'a KeyPackage,
+    public_key_package: &'a PublicKeyPackage,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SigningCommitments {
+    pub identifier: u16,
+    #[serde(with = "hex32")]
+    pub hiding: [u8; 32],
+    #[serde(with = "hex32")]
+    pub binding: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SigningPackage {
+    pub message: String,
+    pub commitments: Vec<SigningCommitments>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SignatureShare {
+    pub identifier: u16,
+    #[serde(with = "hex32")]
+    pub share: [u8; 32],
+}
+
+fn parse<'a, T: Deserialize<'a>>(json: &'a str, what: &str) -> Result<T, CryptoError> {
+    serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(format!("malformed {what}: {e}")))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, CryptoError> {
+    serde_json::to_string(value).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+// ---- funkcje skrotu (RFC 9591, sekcja 6.5) ----
+
+fn h(tag: &[u8], parts: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(CONTEXT);
+    hasher.update(tag);
+    for p in parts {
+        hasher.update(p);
+    }
+    hasher.finalize()
+}
+
+fn h_scalar(tag: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut digest = h(tag, parts);
+    let out = scalar_reduce(&digest);
+    digest.zeroize();
+    out
+}
+
+// H2 bez kontekstu - dokladnie wyzwanie Ed25519
+fn challenge(group_commitment: &[u8; 32], group_public_key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha512::new();
+    hasher.update(group_commitment);
+    hasher.update(group_public_key);
+    hasher.update(message);
+    scalar_reduce(&hasher.finalize())
+}
+
+fn nonce_generate(secret: &[u8; 32]) -> Result<[u8; 32], CryptoError> {
+    let mut random = random_array::<32>()?;
+    let nonce = h_scalar(b"nonce", &[&random, secret]);
+    random.zeroize();
+    Ok(nonce)
+}
+
+fn random_scalar() -> Result<[u8; 32], CryptoError> {
+    let mut wide = random_array::<64>()?;
+    let out = scalar_reduce(&wide);
+    wide.zeroize();
+    Ok(out)
+}
+
+// ---- skalary i elementy grupy ----
+
+fn id_scalar(id: u16) -> [u8; 32] {
+    let mut s = [0u8; 32];
+    s[..2].copy_from_slice(&id.to_le_bytes());
+    s
+}
+
+fn scalar_sub(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    scalar_add(a, &scalar_neg(b))
+}
+
+fn check_scalar(s: &[u8; 32], what: &str) -> Result<(), CryptoError> {
+    if !scalar_is_canonical(s) {
+        return Err(CryptoError::InvalidInput(format!("{what} is not a canonical scalar")));
+    }
+    Ok(())
+}
+
+// DeserializeElement: odrzuca element neutralny i punkty ze skladowa skretna.
+fn element(bytes: &[u8; 32], what: &str) -> Result<EdwardsPoint, CryptoError> {
+    EdwardsPoint::decompress(bytes)
+        .filter(|p| !p.is_identity() && p.is_torsion_free())
+        .ok_or_else(|| CryptoError::InvalidInput(format!("{what} is not a valid group element")))
+}
+
+fn check_identifier(id: u16, max_signers: u16) -> Result<(), CryptoError> {
+    if id == 0 || id > max_signers {
+        return Err(CryptoError::OutOfRange(format!("participant identifier {id} must be in 1..={max_signers}")));
+    }
+    Ok(())
+}
+
+// f(x) schematem Hornera
+fn poly_eval(coefficients: &[[u8; 32]], x: u16) -> [u8; 32] {
+    let x = id_scalar(x);
+    coefficients.iter().rev().fold([0u8; 32], |acc, c| scalar_mul_add(&acc, &x, c))
+}
+
+// sum_k C_k * x^k - publiczny odpowiednik poly_eval
+fn commitment_eval(commitment: &[EdwardsPoint], x: u16) -> EdwardsPoint {
+    let x = id_scalar(x);
+    commitment
+        .iter()
+        .rev()
+        .fold(EdwardsPoint::identity(), |acc, c| acc.mul_scalar(&x).add(c))
+}
+
+// Wspolczynnik Lagrange'a w zerze dla uczestnika id sposrod ids.
+fn interpolating_value(ids: &[u16], id: u16) -> [u8; 32] {
+    let mut num = id_scalar(1);
+    let mut den = id_scalar(1);
+    let x_i = id_scalar(id);
+    for &j in ids.iter().filter(|&&j| j != id) {
+        let x_j = id_scalar(j);
+        num = scalar_mul(&num, &x_j);
+        den = scalar_mul(&den, &scalar_sub(&x_j, &x_i));
+    }
+    scalar_mul(&num, &scalar_invert(&den))
+}
+
+fn dkg_challenge(id: u16, constant: &[u8; 32], r: &[u8; 32]) -> [u8; 32] {
+    h_scalar(b"dkg", &[&id_scalar(id), constant, r])
+}
+
+// ---- DKG ----
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct FrostDkg {
+    identifier: u16,
+    max_signers: u16,
+    min_signers: u16,
+    coefficients: Vec<[u8; 32]>,
+    commitment: Vec<[u8; 32]>,
+    proof: ([u8; 32], [u8; 32]),
+    received: Option<BTreeMap<u16, Vec<EdwardsPoint>>>,
+    finished: bool,
+}
+
+impl Drop for FrostDkg {
+    fn drop(&mut self) {
+        self.coefficients.zeroize();
+    }
+}
+
+impl FrostDkg {
+    pub fn start(identifier: u16, max_signers: u16, min_signers: u16) -> Result<FrostDkg, CryptoError> {
+        if min_signers < 2 || min_signers > max_signers {
+            return Err(CryptoError::OutOfRange(format!(
+                "threshold must satisfy 2 <= min_signers <= max_signers, got {min_signers} of {max_signers}"
+            )));
+        }
+        check_identifier(identifier, max_signers)?;
+        let coefficients = (0..min_signers).map(|_| random_scalar()).collect::<Result<Vec<_>, _>>()?;
+        let commitment: Vec<[u8; 32]> = coefficients.iter().map(|a| EdwardsPoint::mul_base(a).compress()).collect();
+
+        // Schnorr: znajomosc a_0, powiazana z identyfikatorem (chroni przed atakiem rogue-key)
+        let mut k = random_scalar()?;
+        let r = EdwardsPoint::mul_base(&k).compress();
+        let c = dkg_challenge(identifier, &commitment[0], &r);
+        let mu = scalar_mul_add(&coefficients[0], &c, &k);
+        k.zeroize();
+
+        Ok(FrostDkg {
+            identifier,
+            max_signers,
+            min_signers,
+            coefficients,
+            commitment,
+            proof: (r, mu),
+            received: None,
+            finished: false,
+        })
+    }
+
+    fn verify_round1(&self, package: &Round1Package) -> Result<Vec<EdwardsPoint>, CryptoError> {
+        let id = package.identifier;
+        if package.commitment.len() != self.min_signers as usize {
+            return Err(CryptoError::InvalidInput(format!("participant {id} committed to a polynomial of wrong degree")));
+        }
+        let points = package
+            .commitment
+            .iter()
+            .map(|c| element(c, "dkg commitment"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let r = element(&package.proof_r, "dkg proof")?;
+        check_scalar(&package.proof_mu, "dkg proof")?;
+        let c = dkg_challenge(id, &package.commitment[0], &package.proof_r);
+        let expected = EdwardsPoint::mul_base(&package.proof_mu).sub(&points[0].mul_scalar(&c));
+        if expected.compress() != r.compress() {
+            return Err(CryptoError::VerificationFailed(format!("invalid dkg proof from participant {id}")));
+        }
+        Ok(points)
+    }
+
+    pub fn round2_bytes(&mut self, packages: &[Round1Package]) -> Result<Vec<Round2Package>, CryptoError> {
+        if self.received.is_some() {
+            return Err(CryptoError::InvalidInput("dkg round 2 already done".to_string()));
+        }
+        let mut received = BTreeMap::new();
+        for package in packages.iter().filter(|p| p.identifier != self.identifier) {
+            check_identifier(package.identifier, self.max_signers)?;
+            let points = self.verify_round1(package)?;
+            if received.insert(package.identifier, points).is_some() {
+                return Err(CryptoError::InvalidInput(format!("duplicate round 1 package from {}", package.identifier)));
+            }
+        }
+        if received.len() != self.max_signers as usize - 1 {
+            return Err(CryptoError::InvalidInput(format!(
+                "expected round 1 packages from {} other participants, got {}",
+                self.max_signers - 1,
+                received.len()
+            )));
+        }
+        let out = received
+            .keys()
+            .map(|&j| Round2Package { sender: self.identifier, recipient: j, share: poly_eval(&self.coefficients, j) })
+            .collect();
+        self.received = Some(received);
+        Ok(out)
+    }
+
+    pub fn finish_bytes(&mut self, packages: &[Round2Package]) -> Result<(KeyPackage, PublicKeyPackage), CryptoError> {
+        if self.finished {
+            return Err(CryptoError::InvalidInput("dkg already finished".to_string()));
+        }
+        let received = self
+            .received
+            .as_ref()
+            .ok_or_else(|| CryptoError::InvalidInput("dkg round 2 not done yet".to_string()))?;
+
+        // Zeroizing - udzial jest czyszczony takze na kazdej sciezce bledu (`?`)
+        let mut signing_share = Zeroizing::new(poly_eval(&self.coefficients, self.identifier));
+        let mut seen = Vec::new();
+        for package in packages.iter().filter(|p| p.recipient == self.identifier) {
+            let commitment = received
+                .get(&package.sender)
+                .ok_or_else(|| CryptoError::InvalidInput(format!("unexpected share from participant {}", package.sender)))?;
+            if seen.contains(&package.sender) {
+                return Err(CryptoError::InvalidInput(format!("duplicate share from participant {}", package.sender)));
+            }
+            check_scalar(&package.share, "dkg share")?;
+            // Udzial musi lezec na wielomianie, do ktorego nadawca sie zobowiazal
+            let expected = commitment_eval(commitment, self.identifier);
+            if EdwardsPoint::mul_base(&package.share).compress() != expected.compress() {
+                return Err(CryptoError::VerificationFailed(format!("invalid dkg share from participant {}", package.sender)));
+            }
+            *signing_share = scalar_add(&signing_share, &package.share);
+            seen.push(package.sender);
+        }
+        if seen.len() != received.len() {
+            return Err(CryptoError::InvalidInput(format!(
+                "expected {} shares, got {}",
+                received.len(),
+                seen.len()
+            )));
+        }
+
+        let own: Vec<EdwardsPoint> = self
+            .commitment
+            .iter()
+            .map(|c| element(c, "dkg commitment"))
+            .collect::<Result<_, _>>()?;
+        let all: Vec<&Vec<EdwardsPoint>> = std::iter::once(&own).chain(received.values()).collect();
+        let group_public_key = all.iter().fold(EdwardsPoint::identity(), |acc, c| acc.add(&c[0])).compress();
+        let verifying_shares: BTreeMap<u16, String> = (1..=self.max_signers)
+            .map(|j| {
+                let share = all.iter().fold(EdwardsPoint::identity(), |acc, c| acc.add(&commitment_eval(c, j)));
+                (j, bytes_to_hex(&share.compress()))
+            })
+            .collect();
+
+        self.coefficients.zeroize();
+        self.finished = true;
+        let key_package = KeyPackage {
+            identifier: self.identifier,
+            verifying_share: EdwardsPoint::mul_base(&signing_share).compress(),
+            signing_share: *signing_share,
+            group_public_key,
+            min_signers: self.min_signers,
+        };
+        let public_key_package = PublicKeyPackage { group_public_key, verifying_shares, min_signers: self.min_signers };
+        Ok((key_package, public_key_package))
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl FrostDkg {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(identifier: u16, max_signers: u16, min_signers: u16) -> Result<FrostDkg, CryptoError> {
+        FrostDkg::start(identifier, max_signers, min_signers)
+    }
+
+    // Do rozgloszenia wszystkim uczestnikom.
+    pub fn round1_package(&self) -> Result<String, CryptoError> {
+        to_json(&Round1Package {
+            identifier: self.identifier,
+            commitment: self.commitment.clone(),
+            proof_r: self.proof.0,
+            proof_mu: self.proof.1,
+        })
+    }
+
+    // Wejscie: tablica pakietow rundy 1 (wlasny moze byc w srodku). Wynik: tablica
+    // {sender, recipient, share} - kazdy element tylko do swojego odbiorcy, zaszyfrowany.
+    pub fn round2(&mut self, round1_packages: &str) -> Result<String, CryptoError> {
+        let packages: Vec<Round1Package> = parse(round1_packages, "round 1 packages")?;
+        to_json(&self.round2_bytes(&packages)?)
+    }
+
+    // Wynik: {key_package (tajny - przechowywac zaszyfrowany), public_key_package}.
+    pub fn finish(&mut self, round2_packages: &str) -> Result<String, CryptoError> {
+        let packages: Vec<Round2Package> = parse(round2_packages, "round 2 packages")?;
+        let (key_package, public_key_package) = self.finish_bytes(&packages)?;
+        to_json(&DkgOutput { key_package: &key_package, public_key_package: &public_key_package })
+    }
+}
+
+// ---- podpis ----
+
+struct Prepared {
+    binding_factors: BTreeMap<u16, [u8; 32]>,
+    commitments: BTreeMap<u16, (EdwardsPoint, EdwardsPoint)>,
+    group_commitment: [u8; 32],
+    challenge: [u8; 32],
+}
+
+impl Prepared {
+    fn ids(&self) -> Vec<u16> {
+        self.commitments.keys().copied().collect()
+    }
+
+    // Wklad uczestnika do R: D_i + rho_i * E_i
+    fn commitment_share(&self, id: u16) -> Result<EdwardsPoint, CryptoError> {
+        let (hiding, binding) = self
+            .commitments
+            .get(&id)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("no commitment from participant {id}")))?;
+        let rho = self.binding_factors.get(&id).copied().unwrap_or_default();
+        Ok(hiding.add(&binding.mul_scalar(&rho)))
+    }
+}
+
+fn prepare(package: &SigningPackage, group_public_key: &[u8; 32], min_signers: u16) -> Result<(Vec<u8>, Prepared), CryptoError> {
+    let message = hex_to_bytes(&package.message)?;
+    let mut sorted: BTreeMap<u16, &SigningCommitments> = BTreeMap::new();
+    for c in &package.commitments {
+        if c.identifier == 0 || sorted.insert(c.identifier, c).is_some() {
+            return Err(CryptoError::InvalidInput(format!("invalid or duplicate commitment identifier {}", c.identifier)));
+        }
+    }
+    if sorted.len() < min_signers as usize {
+        return Err(CryptoError::OutOfRange(format!(
+            "signing needs at least {min_signers} participants, got {}",
+            sorted.len()
+        )));
+    }
+
+    let mut encoded = Vec::with_capacity(sorted.len() * 96);
+    let mut commitments = BTreeMap::new();
+    for (&id, c) in &sorted {
+        encoded.extend_from_slice(&id_scalar(id));
+        encoded.extend_from_slice(&c.hiding);
+        encoded.extend_from_slice(&c.binding);
+        commitments.insert(id, (element(&c.hiding, "hiding commitment")?, element(&c.binding, "binding commitment")?));
+    }
+    let msg_hash = h(b"msg", &[&message]);
+    let commitment_hash = h(b"com", &[&encoded]);
+    let binding_factors: BTreeMap<u16, [u8; 32]> = sorted
+        .keys()
+        .map(|&id| (id, h_scalar(b"rho", &[group_public_key, &msg_hash, &commitment_hash, &id_scalar(id)])))
+        .collect();
+
+    let mut prepared = Prepared { binding_factors, commitments, group_commitment: [0; 32], challenge: [0; 32] };
+    let mut r = EdwardsPoint::identity();
+    for id in prepared.ids() {
+        r = r.add(&prepared.commitment_share(id)?);
+    }
+    prepared.group_commitment = r.compress();
+    prepared.challenge = challenge(&prepared.group_commitment, group_public_key, &message);
+    Ok((message, prepared))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct FrostSigner {
+    key: KeyPackage,
+    nonces: Option<([u8; 32], [u8; 32])>,
+    commitments: SigningCommitments,
+}
+
+impl Drop for FrostSigner {
+    fn drop(&mut self) {
+        if let Some((mut hiding, mut binding)) = self.nonces.take() {
+            hiding.zeroize();
+            binding.zeroize();
+        }
+    }
+}
+
+impl FrostSigner {
+    pub fn with_key(key: KeyPackage) -> Result<FrostSigner, CryptoError> {
+        check_scalar(&key.signing_share, "signing share")?;
+        element(&key.group_public_key, "group public key")?;
+        if EdwardsPoint::mul_base(&key.signing_share).compress() != key.verifying_share {
+            return Err(CryptoError::InvalidInput("key package signing share does not match verifying share".to_string()));
+        }
+        let hiding = nonce_generate(&key.signing_share)?;
+        let binding = nonce_generate(&key.signing_share)?;
+        let commitments = SigningCommitments {
+            identifier: key.identifier,
+            hiding: EdwardsPoint::mul_base(&hiding).compress(),
+            binding: EdwardsPoint::mul_base(&binding).compress(),
+        };
+        Ok(FrostSigner { key, nonces: Some((hiding, binding)), commitments })
+    }
+
+    pub fn sign_bytes(&mut self, package: &SigningPackage) -> Result<SignatureShare, CryptoError> {
+        // Nonce jednorazowe: drugi podpis tymi samymi nonce zdradzilby udzial klucza
+        let (mut hiding, mut binding) = self
+            .nonces
+            .take()
+            .ok_or_else(|| CryptoError::InvalidInput("signing nonces already used".to_string()))?;
+        let result = self.sign_with(package, &hiding, &binding);
+        hiding.zeroize();
+        binding.zeroize();
+        result
+    }
+
+    fn sign_with(&self, package: &SigningPackage, hiding: &[u8; 32], binding: &[u8; 32]) -> Result<SignatureShare, CryptoError> {
+        let id = self.key.identifier;
+        if !package.commitments.contains(&self.commitments) {
+            return Err(CryptoError::InvalidInput("signing package does not contain our commitments".to_string()));
+        }
+        let (_, prepared) = prepare(package, &self.key.group_public_key, self.key.min_signers)?;
+        let rho = prepared.binding_factors.get(&id).copied().unwrap_or_default();
+        let lambda = interpolating_value(&prepared.ids(), id);
+        let mut lambda_sk = scalar_mul(&lambda, &self.key.signing_share);
+        let share = scalar_mul_add(&lambda_sk, &prepared.challenge, &scalar_mul_add(binding, &rho, hiding));
+        lambda_sk.zeroize();
+        Ok(SignatureShare { identifier: id, share })
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl FrostSigner {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(key_package: &str) -> Result<FrostSigner, CryptoError> {
+        FrostSigner::with_key(parse(key_package, "key package")?)
+    }
+
+    // Zobowiazania do wyslania koordynatorowi.
+    pub fn commitments(&self) -> Result<String, CryptoError> {
+        to_json(&self.commitments)
+    }
+
+    pub fn sign(&mut self, signing_package: &str) -> Result<String, CryptoError> {
+        let package: SigningPackage = parse(signing_package, "signing package")?;
+        to_json(&self.sign_bytes(&package)?)
+    }
+}
+
+// Sprawdza kazdy udzial osobno (wskazuje nieuczciwego uczestnika), a potem caly podpis.
+pub fn aggregate_bytes(
+    package: &SigningPackage,
+    shares: &[SignatureShare],
+    public_key_package: &PublicKeyPackage,
+) -> Result<[u8; 64], CryptoError> {
+    let group_public_key = public_key_package.group_public_key;
+    let (message, prepared) = prepare(package, &group_public_key, public_key_package.min_signers)?;
+    let ids = prepared.ids();
+    if shares.len() != ids.len() {
+        return Err(CryptoError::InvalidInput(format!("expected {} signature shares, got {}", ids.len(), shares.len())));
+    }
+
+    let mut z = [0u8; 32];
+    let mut seen = Vec::new();
+    for share in shares {
+        let id = share.identifier;
+        if !ids.contains(&id) || seen.contains(&id) {
+            return Err(CryptoError::InvalidInput(format!("unexpected signature share from participant {id}")));
+        }
+        check_scalar(&share.share, "signature share")?;
+        let verifying_share = public_key_package
+            .verifying_shares
+            .get(&id)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("no verifying share for participant {id}")))
+            .and_then(|hex| {
+                hex_to_bytes(hex)?
+                    .try_into()
+                    .map_err(|_| CryptoError::InvalidLength("verifying share must be 32 bytes".to_string()))
+            })?;
+        let verifying_share = element(&verifying_share, "verifying share")?;
+        let lambda = interpolating_value(&ids, id);
+        let expected = prepared
+            .commitment_share(id)?
+            .add(&verifying_share.mul_scalar(&scalar_mul(&prepared.challenge, &lambda)));
+        if EdwardsPoint::mul_base(&share.share).compress() != expected.compress() {
+            return Err(CryptoError::VerificationFailed(format!("invalid signature share from participant {id}")));
+        }
+        z = scalar_add(&z, &share.share);
+        seen.push(id);
+    }
+
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&prepared.group_commitment);
+    signature[32..].copy_from_slice(&z);
+    if !crate::ed25519::verify_bytes(&group_public_key, &message, &signature) {
+        return Err(CryptoError::VerificationFailed("aggregated signature is invalid".to_string()));
+    }
+    Ok(signature)
+}
+
+// Pakiet do podpisu: {"message": hex, "commitments": [zobowiazania podpisujacych]}.
+// Wynik: 64-bajtowy podpis Ed25519 pod group_public_key.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn frost_aggregate(signing_package: &str, signature_shares: &str, public_key_package: &str) -> Result<Vec<u8>, CryptoError> {
+    let package: SigningPackage = parse(signing_package, "signing package")?;
+    let shares: Vec<SignatureShare> = parse(signature_shares, "signature shares")?;
+    let public_key_package: PublicKeyPackage = parse(public_key_package, "public key package")?;
+    Ok(aggregate_bytes(&package, &shares, &public_key_package)?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"approve recovery reset";
+
+    // Rundy 1 i 2 DKG t-z-n; zwraca uczestnikow i wszystkie udzialy rundy 2.
+    fn dkg_rounds(max_signers: u16, min_signers: u16) -> Result<(Vec<FrostDkg>, Vec<Round2Package>), CryptoError> {
+        let mut parties: Vec<FrostDkg> =
+            (1..=max_signers).map(|id| FrostDkg::start(id, max_signers, min_signers)).collect::<Result<_, _>>()?;
+        let round1: Vec<Round1Package> = parties
+            .iter()
+            .map(|p| parse(&p.round1_package()?, "round 1 package"))
+            .collect::<Result<_, _>>()?;
+        let mut round2 = Vec::new();
+        for party in parties.iter_mut() {
+            round2.extend(party.round2_bytes(&round1)?);
+        }
+        Ok((parties, round2))
+    }
+
+    fn dkg(max_signers: u16, min_signers: u16) -> Result<Vec<(KeyPackage, PublicKeyPackage)>, CryptoError> {
+        let (mut parties, round2) = dkg_rounds(max_signers, min_signers)?;
+        parties.iter_mut().map(|party| party.finish_bytes(&round2)).collect()
+    }
+
+    fn copy_key(key: &KeyPackage) -> Result<KeyPackage, CryptoError> {
+        parse(&to_json(key)?, "key package")
+    }
+
+    fn signers(keys: &[(KeyPackage, PublicKeyPackage)], ids: &[u16]) -> Result<Vec<FrostSigner>, CryptoError> {
+        ids.iter().map(|&id| FrostSigner::with_key(copy_key(&keys[id as usize - 1].0)?)).collect()
+    }
+
+    fn package(signers: &[FrostSigner]) -> SigningPackage {
+        SigningPackage { message: bytes_to_hex(MESSAGE), commitments: signers.iter().map(|s| s.commitments).collect() }
+    }
+
+    fn sign(keys: &[(KeyPackage, PublicKeyPackage)], ids: &[u16]) -> Result<[u8; 64], CryptoError> {
+        let mut signers = signers(keys, ids)?;
+        let package = package(&signers);
+        let shares: Vec<SignatureShare> = signers.iter_mut().map(|s| s.sign_bytes(&package)).collect::<Result<_, _>>()?;
+        aggregate_bytes(&package, &shares, &keys[0].1)
+    }
+
+    #[test]
+    fn dkg_sign_and_aggregate() -> Result<(), CryptoError> {
+        let keys = dkg(3, 2)?;
+        let group_public_key = keys[0].1.group_public_key;
+        assert!(keys.iter().all(|(key, public)| key.group_public_key == group_public_key
+            && public.verifying_shares.get(&key.identifier) == Some(&bytes_to_hex(&key.verifying_share))));
+        let signature = sign(&keys, &[1, 2])?;
+        crate::ed25519::verify_strict(&group_public_key, MESSAGE, &signature)?;
+        assert!(crate::ed25519::verify_strict(&group_public_key, b"other message", &signature).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn any_signer_subset_produces_valid_signature() -> Result<(), CryptoError> {
+        let keys = dkg(5, 3)?;
+        let group_public_key = keys[0].1.group_public_key;
+        for ids in [[1, 2, 3], [2, 4, 5], [5, 3, 1]] {
+            let signature = sign(&keys, &ids)?;
+            crate::ed25519::verify_strict(&group_public_key, MESSAGE, &signature)?;
+        }
+        // Wiecej niz prog tez dziala
+        crate::ed25519::verify_strict(&group_public_key, MESSAGE, &sign(&keys, &[1, 2, 3, 4, 5])?)?;
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_corrupted_shares() -> Result<(), CryptoError> {
+        // Udzial DKG spoza wielomianu nadawcy
+        let (mut parties, mut round2) = dkg_rounds(3, 2)?;
+        if let Some(package) = round2.iter_mut().find(|p| p.sender == 2 && p.recipient == 1) {
+            package.share = scalar_add(&package.share, &id_scalar(1));
+        }
+        assert!(matches!(parties[0].finish_bytes(&round2), Err(CryptoError::VerificationFailed(_))));
+
+        // Udzial podpisu
+        let keys = dkg(3, 2)?;
+        let mut signers = signers(&keys, &[1, 3])?;
+        let package = package(&signers);
+        let mut shares: Vec<SignatureShare> =
+            signers.iter_mut().map(|s| s.sign_bytes(&package)).collect::<Result<_, _>>()?;
+        shares[1].share = scalar_add(&shares[1].share, &id_scalar(1));
+        assert!(matches!(aggregate_bytes(&package, &shares, &keys[0].1), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_duplicate_identifiers() -> Result<(), CryptoError> {
+        let keys = dkg(3, 2)?;
+        let mut signers = signers(&keys, &[1, 2])?;
+        let package = package(&signers);
+        let shares: Vec<SignatureShare> = signers.iter_mut().map(|s| s.sign_bytes(&package)).collect::<Result<_, _>>()?;
+        let duplicated = vec![
+            SignatureShare { identifier: shares[0].identifier, share: shares[0].share },
+            SignatureShare { identifier: shares[0].identifier, share: shares[0].share },
+        ];
+        assert!(matches!(aggregate_bytes(&package, &duplicated, &keys[0].1), Err(CryptoError::InvalidInput(_))));
+        let mut doubled = package.commitments.clone();
+        doubled.push(package.commitments[0]);
+        let doubled = SigningPackage { message: package.message.clone(), commitments: doubled };
+        assert!(matches!(aggregate_bytes(&doubled, &shares, &keys[0].1), Err(CryptoError::InvalidInput(_))));
+
+        let mut party = FrostDkg::start(1, 3, 2)?;
+        let other = FrostDkg::start(2, 3, 2)?;
+        let round1: Vec<Round1Package> =
+            [&other, &other].iter().map(|p| parse(&p.round1_package()?, "round 1 package")).collect::<Result<_, _>>()?;
+        assert!(matches!(party.round2_bytes(&round1), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_too_few_signers_and_nonce_reuse() -> Result<(), CryptoError> {
+        let keys = dkg(3, 2)?;
+        let mut signers = signers(&keys, &[2])?;
+        let package = package(&signers);
+        assert!(matches!(signers[0].sign_bytes(&package), Err(CryptoError::OutOfRange(_))));
+        // Nonce zostaly zuzyte mimo bledu - drugi podpis nimi jest niemozliwy
+        assert!(matches!(signers[0].sign_bytes(&package), Err(CryptoError::InvalidInput(_))));
+        let shares = [SignatureShare { identifier: 2, share: [0u8; 32] }];
+        assert!(matches!(aggregate_bytes(&package, &shares, &keys[0].1), Err(CryptoError::OutOfRange(_))));
+        assert!(matches!(FrostDkg::start(1, 3, 1), Err(CryptoError::OutOfRange(_))));
+        assert!(matches!(FrostDkg::start(1, 2, 3), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::ct::ct_eq;
+use crate::encoding::{bytes_to_hex, length_prefixed};
+use crate::error::CryptoError;
+use crate::sha2::sha256_bytes;
+
+// Odsmiecanie fragmentow (chunkow) zalacznikow. Wejscie to stan z serwera: manifesty
+// zalacznikow (lista chunkow, wpis, do ktorego naleza, czy upload sie zakonczyl), lista
+// zywych wpisow i lista chunkow w magazynie. Zywe sa chunki osiagalne z manifestu
+// zakonczonego uploadu zywego wpisu albo uploadu w toku mlodszego niz okres karencji.
+// Pozostale manifesty (usuniety wpis, porzucony upload) i nieosiagalne chunki trafiaja
+// do planu usuniecia; chunk wspoldzielony z zywym manifestem nigdy.
+// Plan ma skrot SHA-256 listy usuwanych identyfikatorow: dry run zwraca plan do przegladu,
+// a wykonanie z expected_digest odmawia, jesli stan zmienil sie od dry runu.
+const PLAN_DIGEST_LABEL: &[u8] = b"pm-attachment-gc-v1";
+
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AttachmentManifest {
+    pub id: String,
+    pub item_id: String,
+    pub chunks: Vec<String>,
+    #[serde(default = "default_complete")]
+    pub complete: bool,
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+fn default_complete() -> bool {
+    true
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StoredChunk {
+    pub id: String,
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GcInput {
+    pub manifests: Vec<AttachmentManifest>,
+    pub live_items: Vec<String>,
+    pub chunks: Vec<StoredChunk>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum GcReason {
+    Unreferenced,
+    DeletedItem,
+    AbandonedUpload,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct GcCandidate {
+    pub id: String,
+    pub reason: GcReason,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GcPlan {
+    pub dry_run: bool,
+    pub digest: String,
+    pub delete_manifests: Vec<GcCandidate>,
+    pub delete_chunks: Vec<GcCandidate>,
+    pub live_chunks: usize,
+    // Chunki bez manifestu, ale mlodsze niz karencja (upload moze jeszcze trwac)
+    pub kept_recent: Vec<String>,
+    // Chunki wymienione w zywym manifescie, ktorych nie ma w magazynie
+    pub missing_chunks: Vec<String>,
+}
+
+fn plan_digest(manifests: &[GcCandidate], chunks: &[GcCandidate]) -> String {
+    let mut parts: Vec<&[u8]> = vec![PLAN_DIGEST_LABEL, b"manifests"];
+    parts.extend(manifests.iter().map(|c| c.id.as_bytes()));
+    parts.push(b"chunks");
+    parts.extend(chunks.iter().map(|c| c.id.as_bytes()));
+    bytes_to_hex(&sha256_bytes(&length_prefixed(&parts)))
+}
+
+pub fn plan_chunk_gc(input: &GcInput, now: u64, grace_seconds: u64) -> GcPlan {
+    let live_items: BTreeSet<&str> = input.live_items.iter().map(String::as_str).collect();
+    let recent = |t: u64| now.saturating_sub(t) < grace_seconds;
+
+    let mut live: BTreeSet<&str> = BTreeSet::new();
+    let mut dead_manifests = Vec::new();
+    // Powod usuniecia chunka to powod jego manifestu (pierwszego martwego, ktory go wymienia)
+    let mut dead_refs: BTreeMap<&str, GcReason> = BTreeMap::new();
+    for m in &input.manifests {
+        let reason = if !live_items.contains(m.item_id.as_str()) {
+            Some(GcReason::DeletedItem)
+        } else if !m.complete && !recent(m.updated_at) {
+            Some(GcReason::AbandonedUpload)
+        } else {
+            None
+        };
+        match reason {
+            None => live.extend(m.chunks.iter().map(String::as_str)),
+            Some(reason) => {
+                dead_manifests.push(GcCandidate { id: m.id.clone(), reason });
+                for c in &m.chunks {
+                    dead_refs.entry(c.as_str()).or_insert(reason);
+                }
+            }
+        }
+    }
+
+    let stored: BTreeSet<&str> = input.chunks.iter().map(|c| c.id.as_str()).collect();
+    let mut plan = GcPlan {
+        live_chunks: stored.iter().filter(|c| live.contains(*c)).count(),
+        missing_chunks: live.iter().filter(|c| !stored.contains(*c)).map(|c| c.to_string()).collect(),
+        ..GcPlan::default()
+    };
+    let mut seen = BTreeSet::new();
+    for chunk in &input.chunks {
+        let id = chunk.id.as_str();
+        if live.contains(id) || !seen.insert(id) {
+            continue;
+        }
+        match dead_refs.get(id) {
+            Some(&reason) => plan.delete_chunks.push(GcCandidate { id: id.to_string(), reason }),
+            None if recent(chunk.created_at) => plan.kept_recent.push(id.to_string()),
+            None => plan.delete_chunks.push(GcCandidate { id: id.to_string(), reason: GcReason::Unreferenced }),
+        }
+    }
+    plan.delete_chunks.sort_by(|a, b| a.id.cmp(&b.id));
+    dead_manifests.sort_by(|a, b| a.id.cmp(&b.id));
+    dead_manifests.dedup_by(|a, b| a.id == b.id);
+    plan.delete_manifests = dead_manifests;
+    plan.kept_recent.sort();
+    plan.digest = plan_digest(&plan.delete_manifests, &plan.delete_chunks);
+    plan
+}
+
+// Druga kontrola przed zwroceniem planu: zaden usuwany chunk nie moze byc wymieniony
+// w manifescie, ktory nie jest usuwany.
+fn verify_plan(input: &GcInput, plan: &GcPlan) -> Result<(), CryptoError> {
+    let deleted_manifests: BTreeSet<&str> = plan.delete_manifests.iter().map(|c| c.id.as_str()).collect();
+    let doomed: BTreeSet<&str> = plan.delete_chunks.iter().map(|c| c.id.as_str()).collect();
+    for m in input.manifests.iter().filter(|m| !deleted_manifests.contains(m.id.as_str())) {
+        if let Some(c) = m.chunks.iter().find(|c| doomed.contains(c.as_str())) {
+            return Err(CryptoError::VerificationFailed(format!(
+                "chunk {c} is still referenced by attachment {}",
+                m.id
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn plan_chunk_gc_bytes(
+    input: &GcInput,
+    now: u64,
+    grace_seconds: u64,
+    dry_run: bool,
+    expected_digest: Option<&str>,
+) -> Result<GcPlan, CryptoError> {
+    let mut plan = plan_chunk_gc(input, now, grace_seconds);
+    verify_plan(input, &plan)?;
+    if let Some(expected) = expected_digest
+        && !ct_eq(expected.as_bytes(), plan.digest.as_bytes())
+    {
+        return Err(CryptoError::VerificationFailed("attachment state changed since the dry run".to_string()));
+    }
+    plan.dry_run = dry_run;
+    Ok(plan)
+}
+
+// input - JSON {"manifests": [...], "live_items": [...], "chunks": [...]}; zwraca plan jako JSON.
+// expected_digest - skrot planu z dry runu (pusty = bez sprawdzania).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn plan_attachment_gc(
+    input: &str,
+    now: u64,
+    grace_seconds: u64,
+    dry_run: bool,
+    expected_digest: Option<String>,
+) -> Result<String, CryptoError> {
+    let input: GcInput = serde_json::from_str(input)
+        .map_err(|e| CryptoError::InvalidInput(format!("malformed attachment GC input: {e}")))?;
+    let expected = expected_digest.filter(|d| !d.is_empty());
+    let plan = plan_chunk_gc_bytes(&input, now, grace_seconds, dry_run, expected.as_deref())?;
+    serde_json::to_string(&plan).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_000_000;
+    const GRACE: u64 = 3600;
+
+    fn manifest(id: &str, item_id: &str, chunks: &[&str], complete: bool, updated_at: u64) -> AttachmentManifest {
+        let chunks = chunks.iter().map(|c| c.to_string()).collect();
+        AttachmentManifest { id: id.to_string(), item_id: item_id.to_string(), chunks, complete, updated_at }
+    }
+
+    fn input() -> GcInput {
+        GcInput {
+            manifests: vec![
+                manifest("live", "item-1", &["c1", "shared", "lost"], true, 0),
+                manifest("deleted", "item-gone", &["c2", "shared"], true, 0),
+                manifest("abandoned", "item-1", &["c3"], false, NOW - 2 * GRACE),
+                manifest("uploading", "item-1", &["c4"], false, NOW - 60),
+            ],
+            live_items: vec!["item-1".to_string()],
+            chunks: ["c1", "c2", "c3", "c4", "shared", "orphan", "fresh"]
+                .iter()
+                .map(|id| StoredChunk { id: id.to_string(), created_at: if *id == "fresh" { NOW - 10 } else { 0 } })
+                .collect(),
+        }
+    }
+
+    fn ids(candidates: &[GcCandidate]) -> Vec<(&str, GcReason)> {
+        candidates.iter().map(|c| (c.id.as_str(), c.reason)).collect()
+    }
+
+    #[test]
+    fn keeps_referenced_chunks_and_drops_the_rest() -> Result<(), CryptoError> {
+        let plan = plan_chunk_gc_bytes(&input(), NOW, GRACE, true, None)?;
+        assert!(plan.dry_run);
+        assert_eq!(
+            ids(&plan.delete_manifests),
+            [("abandoned", GcReason::AbandonedUpload), ("deleted", GcReason::DeletedItem)]
+        );
+        // "shared" jest tez w usuwanym manifescie, ale zywy manifest go trzyma.
+        assert_eq!(
+            ids(&plan.delete_chunks),
+            [("c2", GcReason::DeletedItem), ("c3", GcReason::AbandonedUpload), ("orphan", GcReason::Unreferenced)]
+        );
+        assert_eq!(plan.live_chunks, 3);
+        assert_eq!(plan.kept_recent, ["fresh"]);
+        assert_eq!(plan.missing_chunks, ["lost"]);
+        Ok(())
+    }
+
+    #[test]
+    fn execution_requires_the_dry_run_digest() -> Result<(), CryptoError> {
+        let dry = plan_chunk_gc_bytes(&input(), NOW, GRACE, true, None)?;
+        let run = plan_chunk_gc_bytes(&input(), NOW, GRACE, false, Some(&dry.digest))?;
+        assert_eq!((run.dry_run, &run.delete_chunks), (false, &dry.delete_chunks));
+
+        // Nowy manifest zywego wpisu odwoluje sie do "orphan" - plan sie zmienia.
+        let mut changed = input();
+        changed.manifests.push(manifest("late", "item-1", &["orphan"], true, NOW));
+        assert!(matches!(
+            plan_chunk_gc_bytes(&changed, NOW, GRACE, false, Some(&dry.digest)),
+            Err(CryptoError::VerificationFailed(_))
+        ));
+        let replanned = plan_chunk_gc_bytes(&changed, NOW, GRACE, true, None)?;
+        assert!(replanned.delete_chunks.iter().all(|c| c.id != "orphan"));
+        Ok(())
+    }
+
+    #[test]
+    fn json_entry_point_parses_defaults() -> Result<(), CryptoError> {
+        let json = r#"{"manifests":[{"id":"m","item_id":"i","chunks":["a"]}],"live_items":["i"],
+            "chunks":[{"id":"a"},{"id":"b"}]}"#;
+        let plan = plan_attachment_gc(json, NOW, GRACE, true, Some(String::new()))?;
+        assert!(plan.contains(r#""delete_chunks":[{"id":"b","reason":"unreferenced"}]"#));
+        assert!(matches!(plan_attachment_gc("{", NOW, GRACE, true, None), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use zeroize::Zeroize;
+
+use crate::aes::Aes;
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::sm4::Sm4;
+
+// GCM (NIST SP 800-38D) z 96-bitowym nonce. GHASH liczony bit po bicie z maskami,
+// bez tablic zaleznych od H, zeby nie zdradzac klucza przez czas dostepu do pamieci.
+pub const NONCE_LEN: usize = 12;
+pub const TAG_LEN: usize = 16;
+
+const R: u128 = 0xe1 << 120;
+
+fn gf_mul(x: u128, y: u128) -> u128 {
+    let mut z = 0u128;
+    let mut v = y;
+    for i in 0..128 {
+        let bit = (x >> (127 - i)) & 1;
+        z ^= v & 0u128.wrapping_sub(bit);
+        let lsb = v & 1;
+        v = (v >> 1) ^ (R & 0u128.wrapping_sub(lsb));
+    }
+    z
+}
+
+struct GHash {
+    h: u128,
+    y: u128,
+}
+
+impl GHash {
+    fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.y = gf_mul(self.y ^ u128::from_be_bytes(block), self.h);
+        }
+    }
+
+    fn finalize(mut self, aad_len: usize, ct_len: usize) -> u128 {
+        let lens = ((aad_len as u128 * 8) << 64) | (ct_len as u128 * 8);
+        self.y = gf_mul(self.y ^ lens, self.h);
+        self.y
+    }
+}
+
+impl Drop for GHash {
+    fn drop(&mut self) {
+        self.h.zeroize();
+        self.y.zeroize();
+    }
+}
+
+// Tryb GCM dla dowolnego szyfru blokowego 128-bit (AES, SM4).
+pub trait BlockCipher {
+    fn encrypt_block(&self, block: &mut [u8; 16]);
+}
+
+impl BlockCipher for Aes {
+    fn encrypt_block(&self, block: &mut [u8; 16]) {
+        Aes::encrypt_block(self, block);
+    }
+}
+
+#[derive(Clone)]
+pub struct Gcm<C: BlockCipher> {
+    cipher: C,
+    h: u128,
+}
+
+pub type AesGcm = Gcm<Aes>;
+pub type Sm4Gcm = Gcm<Sm4>;
+
+impl<C: BlockCipher> Drop for Gcm<C> {
+    fn drop(&mut self) {
+        self.h.zeroize();
+    }
+}
+
+impl Gcm<Aes> {
+    pub fn new(key: &[u8]) -> Result<AesGcm, CryptoError> {
+        Ok(Gcm::with_cipher(Aes::new(key)?))
+    }
+}
+
+impl Gcm<Sm4> {
+    pub fn new(key: &[u8]) -> Result<Sm4Gcm, CryptoError> {
+        Ok(Gcm::with_cipher(Sm4::new(key)?))
+    }
+}
+
+impl<C: BlockCipher> Gcm<C> {
+    pub fn with_cipher(cipher: C) -> Gcm<C> {
+        let mut h = [0u8; 16];
+        cipher.encrypt_block(&mut h);
+        Gcm { cipher, h: u128::from_be_bytes(h) }
+    }
+
+    fn ctr_xor(&self, j0: u128, data: &mut [u8]) {
+        let mut counter = j0;
+        for chunk in data.chunks_mut(16) {
+            counter = (counter & !0xffff_ffffu128) | ((counter as u32).wrapping_add(1) as u128);
+            let mut ks = counter.to_be_bytes();
+            self.cipher.encrypt_block(&mut ks);
+            for (b, k) in chunk.iter_mut().zip(ks.iter()) {
+                *b ^= k;
+            }
+            ks.zeroize();
+        }
+    }
+
+    fn tag(&self, j0: u128, aad: &[u8], ct: &[u8]) -> [u8; 16] {
+        let mut ghash = GHash { h: self.h, y: 0 };
+        ghash.update_padded(aad);
+        ghash.update_padded(ct);
+        let s = ghash.finalize(aad.len(), ct.len());
+        let mut ek = j0.to_be_bytes();
+        self.cipher.encrypt_block(&mut ek);
+        (u128::from_be_bytes(ek) ^ s).to_be_bytes()
+    }
+
+    fn j0(nonce: &[u8]) -> Result<u128, CryptoError> {
+        if nonce.len() != NONCE_LEN {
+            return Err(CryptoError::InvalidLength(format!("GCM nonce must be {NONCE_LEN} bytes")));
+        }
+        let mut block = [0u8; 16];
+        block[..12].copy_from_slice(nonce);
+        block[15] = 1;
+        Ok(u128::from_be_bytes(block))
+    }
+
+    // Zwraca ciphertext || tag (16 bajtow).
+    pub fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let j0 = Self::j0(nonce)?;
+        if plaintext.len() as u64 > (1u64 << 36) - 32 {
+            return Err(CryptoError::OutOfRange("plaintext too long for GCM".to_string()));
+        }
+        let mut out = plaintext.to_vec();
+        self.ctr_xor(j0, &mut out);
+        let tag = self.tag(j0, aad, &out);
+        out.extend_from_slice(&tag);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let j0 = Self::j0(nonce)?;
+        if ciphertext.len() < TAG_LEN {
+            return Err(CryptoError::InvalidLength("ciphertext shorter than GCM tag".to_string()));
+        }
+        let (ct, tag) = ciphertext.split_at(ciphertext.len() - TAG_LEN);
+        let expected = self.tag(j0, aad, ct);
+        if !ct_eq(&expected, tag) {
+            return Err(CryptoError::DecryptFailed("authentication tag mismatch".to_string()));
+        }
+        let mut out = ct.to_vec();
+        self.ctr_xor(j0, &mut out);
+        Ok(out)
+    }
+}
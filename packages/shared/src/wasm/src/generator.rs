@@ -0,0 +1,168 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::error::CryptoError;
+use crate::rng::random_below;
+
+// Generator hasel dla wszystkich klientow. Najpierw min_per_class znakow z kazdej wlaczonej
+// klasy, reszta z sumy klas, na koncu tasowanie Fishera-Yatesa - pozycje znakow obowiazkowych
+// nie sa przewidywalne. Kazde losowanie przez random_below (odrzucanie, bez biasu modulo).
+// exclude_ambiguous usuwa znaki mylone przy przepisywaniu (I l 1 | O 0 o oraz cudzyslowy).
+pub const MIN_LENGTH: u32 = 4;
+pub const MAX_LENGTH: u32 = 256;
+const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!#$%&()*+,-./:;<=>?@[]^_{|}~'\"`";
+const AMBIGUOUS: &str = "Il1|O0o'\"`";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PasswordRules {
+    pub length: u32,
+    pub lower: bool,
+    pub upper: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub exclude_ambiguous: bool,
+    pub min_per_class: u32,
+}
+
+impl PasswordRules {
+    fn classes(&self) -> Vec<Vec<u8>> {
+        [(self.lower, LOWER), (self.upper, UPPER), (self.digits, DIGITS), (self.symbols, SYMBOLS)]
+            .into_iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, chars)| {
+                chars.bytes().filter(|c| !(self.exclude_ambiguous && AMBIGUOUS.as_bytes().contains(c))).collect()
+            })
+            .collect()
+    }
+}
+
+fn pick(alphabet: &[u8]) -> Result<u8, CryptoError> {
+    Ok(alphabet[random_below(alphabet.len() as u32)? as usize])
+}
+
+pub fn generate_password_with(rules: &PasswordRules) -> Result<Zeroizing<String>, CryptoError> {
+    if !(MIN_LENGTH..=MAX_LENGTH).contains(&rules.length) {
+        return Err(CryptoError::OutOfRange(format!("password length must be in {MIN_LENGTH}..={MAX_LENGTH}")));
+    }
+    let classes = rules.classes();
+    if classes.is_empty() {
+        return Err(CryptoError::InvalidParameter("at least one character class must be enabled".to_string()));
+    }
+    if (rules.min_per_class as u64) * (classes.len() as u64) > rules.length as u64 {
+        return Err(CryptoError::InvalidParameter(format!(
+            "{} classes with at least {} characters each do not fit in length {}",
+            classes.len(),
+            rules.min_per_class,
+            rules.length
+        )));
+    }
+
+    let all = classes.concat();
+    let mut out = Zeroizing::new(Vec::with_capacity(rules.length as usize));
+    for class in &classes {
+        for _ in 0..rules.min_per_class {
+            out.push(pick(class)?);
+        }
+    }
+    while out.len() < rules.length as usize {
+        out.push(pick(&all)?);
+    }
+    for i in (1..out.len()).rev() {
+        let j = random_below(i as u32 + 1)? as usize;
+        out.swap(i, j);
+    }
+    let password = String::from_utf8(std::mem::take(&mut *out))
+        .map_err(|_| CryptoError::InvalidInput("generated password is not ASCII".to_string()))?;
+    Ok(Zeroizing::new(password))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn generate_password(
+    length: u32,
+    lower: bool,
+    upper: bool,
+    digits: bool,
+    symbols: bool,
+    exclude_ambiguous: bool,
+    min_per_class: u32,
+) -> Result<String, CryptoError> {
+    let rules = PasswordRules { length, lower, upper, digits, symbols, exclude_ambiguous, min_per_class };
+    let mut password = generate_password_with(&rules)?;
+    Ok(std::mem::take(&mut *password))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: PasswordRules = PasswordRules {
+        length: 16,
+        lower: true,
+        upper: true,
+        digits: true,
+        symbols: true,
+        exclude_ambiguous: false,
+        min_per_class: 0,
+    };
+
+    fn count_in(password: &str, class: &str) -> usize {
+        password.bytes().filter(|c| class.as_bytes().contains(c)).count()
+    }
+
+    #[test]
+    fn exact_length() -> Result<(), CryptoError> {
+        for length in [MIN_LENGTH, 16, 64, MAX_LENGTH] {
+            assert_eq!(generate_password_with(&PasswordRules { length, ..ALL })?.len(), length as usize);
+        }
+        assert!(matches!(
+            generate_password(MIN_LENGTH - 1, true, true, true, true, false, 0),
+            Err(CryptoError::OutOfRange(_))
+        ));
+        assert!(matches!(
+            generate_password(MAX_LENGTH + 1, true, true, true, true, false, 0),
+            Err(CryptoError::OutOfRange(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn min_per_class_is_met_for_each_enabled_class() -> Result<(), CryptoError> {
+        let rules = PasswordRules { length: 12, min_per_class: 3, ..ALL };
+        for _ in 0..50 {
+            let password = generate_password_with(&rules)?;
+            for class in [LOWER, UPPER, DIGITS, SYMBOLS] {
+                assert!(count_in(&password, class) >= 3, "{} in {}", class, password.as_str());
+            }
+        }
+        let digits_only = PasswordRules { lower: false, upper: false, symbols: false, min_per_class: 8, ..ALL };
+        let password = generate_password_with(&digits_only)?;
+        assert_eq!(count_in(&password, DIGITS), 16);
+        Ok(())
+    }
+
+    #[test]
+    fn exclude_ambiguous_removes_lookalikes() -> Result<(), CryptoError> {
+        let rules = PasswordRules { length: MAX_LENGTH, exclude_ambiguous: true, min_per_class: 10, ..ALL };
+        for _ in 0..20 {
+            let password = generate_password_with(&rules)?;
+            assert_eq!(count_in(&password, AMBIGUOUS), 0, "{}", password.as_str());
+            assert_eq!(count_in(&password, "0O1lI"), 0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn impossible_rules_are_rejected() {
+        let none = PasswordRules { lower: false, upper: false, digits: false, symbols: false, ..ALL };
+        assert!(matches!(generate_password_with(&none), Err(CryptoError::InvalidParameter(_))));
+        let too_many = PasswordRules { length: 11, min_per_class: 3, ..ALL };
+        assert!(matches!(generate_password_with(&too_many), Err(CryptoError::InvalidParameter(_))));
+        let overflow = PasswordRules { min_per_class: u32::MAX, ..ALL };
+        assert!(matches!(generate_password_with(&overflow), Err(CryptoError::InvalidParameter(_))));
+        assert!(generate_password_with(&PasswordRules { length: 12, min_per_class: 3, ..ALL }).is_ok());
+    }
+}
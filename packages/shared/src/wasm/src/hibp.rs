@@ -0,0 +1,108 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::encoding::bytes_to_hex;
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+use crate::sha1::sha1_bytes;
+
+// Have I Been Pwned, Pwned Passwords (k-anonimowosc): do API idzie tylko 5 pierwszych znakow
+// hex SHA-1 hasla, odpowiedz to wszystkie sufiksy z tego zakresu jako "SUFIKS:LICZBA" w liniach.
+// Haslo i pelny skrot nie opuszczaja wasm - JS dostaje prefiks do zapytania i sufiks do
+// porownania. Odpowiedzi z Add-Padding: true zawieraja sztuczne wpisy z liczba 0.
+// W profilu FIPS hibp_prefix zwraca NotApproved (SHA-1).
+pub const PREFIX_LEN: usize = 5;
+const SUFFIX_LEN: usize = 35;
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct HibpQuery {
+    prefix: String,
+    suffix: String,
+}
+
+pub fn hibp_split(password: &[u8]) -> (String, String) {
+    let mut hash = bytes_to_hex(&sha1_bytes(password));
+    hash.make_ascii_uppercase();
+    let suffix = hash.split_off(PREFIX_LEN);
+    (hash, suffix)
+}
+
+// Liczba wyciekow dla sufiksu (0 - brak w zakresie albo wpis z paddingu).
+pub fn hibp_count(suffix: &str, range_response: &str) -> Result<u32, CryptoError> {
+    let suffix = suffix.trim();
+    if suffix.len() != SUFFIX_LEN || !suffix.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(CryptoError::InvalidInput(format!("hibp suffix must be {SUFFIX_LEN} hex characters")));
+    }
+    for (line_no, line) in range_response.lines().map(str::trim).enumerate().filter(|(_, l)| !l.is_empty()) {
+        let malformed = || CryptoError::InvalidInput(format!("hibp range response line {} is malformed", line_no + 1));
+        let (candidate, count) = line.split_once(':').ok_or_else(malformed)?;
+        if candidate.eq_ignore_ascii_case(suffix) {
+            return count.trim().parse::<u32>().map_err(|_| malformed());
+        }
+    }
+    Ok(0)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl HibpQuery {
+    // Do GET https://api.pwnedpasswords.com/range/{prefix}
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn prefix(&self) -> String {
+        self.prefix.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn suffix(&self) -> String {
+        self.suffix.clone()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hibp_prefix(password: &str) -> Result<HibpQuery, CryptoError> {
+    hibp_prefix_raw(password.as_bytes())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hibp_prefix_raw(password: &[u8]) -> Result<HibpQuery, CryptoError> {
+    require_approved("sha1")?;
+    let (prefix, suffix) = hibp_split(password);
+    Ok(HibpQuery { prefix, suffix })
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hibp_match(suffix: &str, range_response: &str) -> Result<u32, CryptoError> {
+    hibp_count(suffix, range_response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn prefix_and_suffix_split_sha1() -> Result<(), CryptoError> {
+        // SHA-1("password") = 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let query = hibp_prefix_raw(b"password")?;
+        assert_eq!(query.prefix(), "5BAA6");
+        assert_eq!(query.suffix(), "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+        assert_eq!(hibp_prefix("password")?.suffix(), query.suffix());
+        let response = "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n1E4C9B93F3F0682250B6CF8331B7EE68FD8:9659365\r\n";
+        assert_eq!(hibp_match(&query.suffix(), response)?, 9_659_365);
+        assert_eq!(hibp_match("0000000000000000000000000000000000A", response)?, 0);
+        Ok(())
+    }
+
+    #[cfg(feature = "fips-profile")]
+    #[test]
+    fn prefix_is_refused_in_fips_profile() {
+        assert!(matches!(hibp_prefix_raw(b"password"), Err(CryptoError::NotApproved(_))));
+    }
+
+    #[test]
+    fn malformed_range_is_rejected() {
+        let suffix = "1E4C9B93F3F0682250B6CF8331B7EE68FD8";
+        assert!(matches!(hibp_match("1E4C9", "1E4C9:1"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(hibp_match(suffix, "no colon here"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(hibp_match(suffix, &format!("{suffix}:lots")), Err(CryptoError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::ct::ct_eq;
+use crate::encoding::bytes_to_hex;
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::hmac::hmac_sha256_bytes;
+use crate::rng::random_array;
+use crate::secret::{EntryKey, MasterKey};
+
+// Historia hasel do ostrzegania o ponownym uzyciu (takze hasel z usunietych wpisow).
+// Ledger nie zawiera hasel - tylko HMAC-SHA256 znormalizowanego hasla pod kluczem historii
+// i czas dodania. Calosc jest szyfrowana, wiec nie widac nawet liczby zmian hasel:
+//   "PMPH" || wersja (1) || nonce || ciphertext || tag,  naglowek jako AAD
+// Normalizacja: obciecie bialych znakow na brzegach (wklejanie ze schowka). NFC robi
+// strona JS (String.normalize) przed wywolaniem.
+const LEDGER_MAGIC: &[u8; 4] = b"PMPH";
+const LEDGER_VERSION: u8 = 1;
+const LEDGER_HEADER_LEN: usize = 5;
+const HISTORY_KEY_INFO: &[u8] = b"pm-password-history";
+const LEDGER_KEY_INFO: &[u8] = b"pm-history-ledger";
+const TAG_KEY_INFO: &[u8] = b"pm-history-tag";
+const TAG_LABEL: &[u8] = b"pm-history-v1\0";
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub tag: String,
+    pub added_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistoryLedger {
+    pub entries: Vec<HistoryEntry>,
+}
+
+struct HistoryKeys {
+    ledger: EntryKey,
+    tag: EntryKey,
+}
+
+// Osobny klucz historii wyprowadzany z klucza vaulta.
+pub fn derive_history_key(vault_key: &MasterKey) -> Result<EntryKey, CryptoError> {
+    Ok(EntryKey::from_vec(hkdf_sha256_bytes(vault_key.as_bytes(), &[], HISTORY_KEY_INFO, 32)?))
+}
+
+fn derive_keys(history_key: &EntryKey) -> Result<HistoryKeys, CryptoError> {
+    if history_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("history key must be 32 bytes".to_string()));
+    }
+    Ok(HistoryKeys {
+        ledger: EntryKey::from_vec(hkdf_sha256_bytes(history_key.as_bytes(), &[], LEDGER_KEY_INFO, 32)?),
+        tag: EntryKey::from_vec(hkdf_sha256_bytes(history_key.as_bytes(), &[], TAG_KEY_INFO, 32)?),
+    })
+}
+
+// Normalizacja (trim) dziala na tekscie, wiec wariant _raw wymaga poprawnego UTF-8.
+fn utf8_password(password: &[u8]) -> Result<&str, CryptoError> {
+    std::str::from_utf8(password).map_err(|_| CryptoError::InvalidInput("password is not valid UTF-8".to_string()))
+}
+
+fn password_tag(keys: &HistoryKeys, password: &str) -> Result<String, CryptoError> {
+    let normalized = password.trim();
+    if normalized.is_empty() {
+        return Err(CryptoError::InvalidInput("password is empty".to_string()));
+    }
+    let msg = Zeroizing::new([TAG_LABEL, normalized.as_bytes()].concat());
+    Ok(bytes_to_hex(&hmac_sha256_bytes(keys.tag.as_bytes(), &msg)))
+}
+
+fn seal_ledger(keys: &HistoryKeys, ledger: &HistoryLedger) -> Result<Vec<u8>, CryptoError> {
+    let json = serde_json::to_vec(ledger).map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+    let mut blob = LEDGER_MAGIC.to_vec();
+    blob.push(LEDGER_VERSION);
+    let nonce = random_array::<NONCE_LEN>()?;
+    let sealed = AesGcm::new(keys.ledger.as_bytes())?.encrypt(&nonce, &blob, &json)?;
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&sealed);
+    Ok(blob)
+}
+
+fn open_ledger(keys: &HistoryKeys, blob: &[u8]) -> Result<HistoryLedger, CryptoError> {
+    if blob.len() < LEDGER_HEADER_LEN + NONCE_LEN + TAG_LEN || &blob[..4] != LEDGER_MAGIC {
+        return Err(CryptoError::CorruptHeader("not a password history ledger".to_string()));
+    }
+    if blob[4] != LEDGER_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("password history version {}", blob[4])));
+    }
+    let (header, rest) = blob.split_at(LEDGER_HEADER_LEN);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let json = AesGcm::new(keys.ledger.as_bytes())?.decrypt(nonce, header, sealed)?;
+    serde_json::from_slice(&json).map_err(|e| CryptoError::CorruptHeader(format!("malformed password history: {e}")))
+}
+
+fn contains(ledger: &HistoryLedger, tag: &str) -> bool {
+    // Bez wczesnego wyjscia - czas nie zdradza pozycji trafienia
+    ledger.entries.iter().fold(false, |found, e| found | ct_eq(e.tag.as_bytes(), tag.as_bytes()))
+}
+
+// Ponowne dodanie tego samego hasla tylko odswieza czas (bez duplikatow).
+fn append(ledger: &mut HistoryLedger, tag: String, now: u64) {
+    ledger.entries.retain(|e| e.tag != tag);
+    ledger.entries.push(HistoryEntry { tag, added_at: now });
+}
+
+pub fn new_history_bytes(history_key: &EntryKey) -> Result<Vec<u8>, CryptoError> {
+    seal_ledger(&derive_keys(history_key)?, &HistoryLedger::default())
+}
+
+pub fn history_append_bytes(history_key: &EntryKey, ledger: &[u8], password: &str, now: u64) -> Result<Vec<u8>, CryptoError> {
+    let keys = derive_keys(history_key)?;
+    let mut parsed = open_ledger(&keys, ledger)?;
+    append(&mut parsed, password_tag(&keys, password)?, now);
+    seal_ledger(&keys, &parsed)
+}
+
+pub fn history_contains_bytes(history_key: &EntryKey, ledger: &[u8], password: &str) -> Result<bool, CryptoError> {
+    let keys = derive_keys(history_key)?;
+    let parsed = open_ledger(&keys, ledger)?;
+    Ok(contains(&parsed, &password_tag(&keys, password)?))
+}
+
+// Zachowuje keep_last najnowszych wpisow; starsze usuwa, jesli przekroczyly max_age_days
+// (0 = bez limitu wieku). Te same zasady co retencja kopii zapasowych.
+pub fn history_prune_bytes(
+    history_key: &EntryKey,
+    ledger: &[u8],
+    keep_last: u32,
+    max_age_days: u32,
+    now: u64,
+) -> Result<Vec<u8>, CryptoError> {
+    if keep_last == 0 {
+        return Err(CryptoError::OutOfRange("keep_last must be at least 1".to_string()));
+    }
+    let keys = derive_keys(history_key)?;
+    let mut parsed = open_ledger(&keys, ledger)?;
+    parsed.entries.sort_by_key(|e| std::cmp::Reverse(e.added_at));
+    let max_age = max_age_days as u64 * SECONDS_PER_DAY;
+    let mut i = 0;
+    parsed.entries.retain(|e| {
+        let keep = i < keep_last as usize || max_age == 0 || now.saturating_sub(e.added_at) <= max_age;
+        i += 1;
+        keep
+    });
+    parsed.entries.reverse();
+    seal_ledger(&keys, &parsed)
+}
+
+// Sciezka aktualizacji wpisu: sprawdza nowe haslo wzgledem historii i dopisuje stare.
+// Zwraca (czy nowe haslo bylo juz uzyte, nowy ledger). Nowe haslo trafia do historii
+// dopiero przy kolejnej zmianie, wiec zapis tego samego hasla nie ostrzega sam o sobie.
+pub fn history_record_change_bytes(
+    history_key: &EntryKey,
+    ledger: &[u8],
+    old_password: Option<&str>,
+    new_password: &str,
+    now: u64,
+) -> Result<(bool, Vec<u8>), CryptoError> {
+    let keys = derive_keys(history_key)?;
+    let mut parsed = open_ledger(&keys, ledger)?;
+    let reused = contains(&parsed, &password_tag(&keys, new_password)?);
+    if let Some(old) = old_password.filter(|p| !p.trim().is_empty()) {
+        append(&mut parsed, password_tag(&keys, old)?, now);
+    }
+    Ok((reused, seal_ledger(&keys, &parsed)?))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct HistoryUpdate {
+    reused: bool,
+    ledger: Vec<u8>,
+}
+
+impl HistoryUpdate {
+    pub(crate) fn new(reused: bool, ledger: Vec<u8>) -> HistoryUpdate {
+        HistoryUpdate { reused, ledger }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl HistoryUpdate {
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn reused(&self) -> bool {
+        self.reused
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn ledger(&self) -> Vec<u8> {
+        self.ledger.clone()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn password_history_key(vault_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(derive_history_key(&MasterKey::from_slice(vault_key))?.as_bytes().to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn new_password_history(history_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    new_history_bytes(&EntryKey::from_slice(history_key))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn password_history_append(history_key: &[u8], ledger: &[u8], password: &str, now: u64) -> Result<Vec<u8>, CryptoError> {
+    history_append_bytes(&EntryKey::from_slice(history_key), ledger, password, now)
+}
+
+// Haslo jako bajty UTF-8 (Uint8Array), zeby strona JS mogla je wyczyscic po wywolaniu.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn password_history_append_raw(history_key: &[u8], ledger: &[u8], password: &[u8], now: u64) -> Result<Vec<u8>, CryptoError> {
+    history_append_bytes(&EntryKey::from_slice(history_key), ledger, utf8_password(password)?, now)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn password_history_contains(history_key: &[u8], ledger: &[u8], password: &str) -> Result<bool, CryptoError> {
+    history_contains_bytes(&EntryKey::from_slice(history_key), ledger, password)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn password_history_contains_raw(history_key: &[u8], ledger: &[u8], password: &[u8]) -> Result<bool, CryptoError> {
+    history_contains_bytes(&EntryKey::from_slice(history_key), ledger, utf8_password(password)?)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn password_history_prune(
+    history_key: &[u8],
+    ledger: &[u8],
+    keep_last: u32,
+    max_age_days: u32,
+    now: u64,
+) -> Result<Vec<u8>, CryptoError> {
+    history_prune_bytes(&EntryKey::from_slice(history_key), ledger, keep_last, max_age_days, now)
+}
+
+// Wywolywane przy zapisie wpisu ze zmienionym haslem (old_password puste dla nowego wpisu).
+// Dla wpisow VaultItem robi to VaultItem::set_data_with_history.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn password_history_record_change(
+    history_key: &[u8],
+    ledger: &[u8],
+    old_password: &str,
+    new_password: &str,
+    now: u64,
+) -> Result<HistoryUpdate, CryptoError> {
+    let (reused, ledger) =
+        history_record_change_bytes(&EntryKey::from_slice(history_key), ledger, Some(old_password), new_password, now)?;
+    Ok(HistoryUpdate::new(reused, ledger))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [6u8; 32];
+
+    #[test]
+    fn raw_and_text_passwords_share_tags() -> Result<(), CryptoError> {
+        let ledger = new_password_history(&KEY)?;
+        let ledger = password_history_append_raw(&KEY, &ledger, " hunter2\n".as_bytes(), 1_000)?;
+        assert!(password_history_contains(&KEY, &ledger, "hunter2")?);
+        assert!(password_history_contains_raw(&KEY, &ledger, b"hunter2")?);
+        assert!(!password_history_contains_raw(&KEY, &ledger, b"hunter3")?);
+        let update = password_history_record_change(&KEY, &ledger, "hunter2", "hunter2", 2_000)?;
+        assert!(update.reused());
+        Ok(())
+    }
+
+    #[test]
+    fn bad_input_is_rejected() -> Result<(), CryptoError> {
+        let ledger = new_password_history(&KEY)?;
+        assert!(matches!(password_history_append_raw(&KEY, &ledger, &[0xff, 0xfe], 0), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(password_history_contains_raw(&KEY, &ledger, b"  "), Err(CryptoError::InvalidInput(_))));
+        assert!(password_history_contains(&[7u8; 32], &ledger, "hunter2").is_err());
+        assert!(matches!(password_history_contains(&KEY[..16], &ledger, "hunter2"), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(password_history_contains(&KEY, b"PMXX\x01", "hunter2"), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn prune_keeps_the_newest_and_drops_only_expired() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let day = SECONDS_PER_DAY;
+        let mut ledger = new_history_bytes(&key)?;
+        for (i, password) in ["one", "two", "three", "four"].iter().enumerate() {
+            ledger = history_append_bytes(&key, &ledger, password, i as u64 * 10 * day)?;
+        }
+        let now = 40 * day;
+        let has = |ledger: &[u8], password| history_contains_bytes(&key, ledger, password);
+
+        // Wieki 40, 30, 20, 10 dni: keep_last 1 + limit 25 dni zostawia "three" i "four"
+        let pruned = history_prune_bytes(&key, &ledger, 1, 25, now)?;
+        assert!(!has(&pruned, "one")? && !has(&pruned, "two")?);
+        assert!(has(&pruned, "three")? && has(&pruned, "four")?);
+        // keep_last chroni najnowsze wpisy, nawet przeterminowane
+        let pruned = history_prune_bytes(&key, &ledger, 3, 1, now)?;
+        assert!(!has(&pruned, "one")? && has(&pruned, "two")?);
+        // max_age_days 0 = bez limitu wieku - keep_last niczego nie usuwa
+        let pruned = history_prune_bytes(&key, &ledger, 1, 0, now)?;
+        assert!(has(&pruned, "one")? && has(&pruned, "four")?);
+        assert!(matches!(history_prune_bytes(&key, &ledger, 0, 25, now), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn item_password_changes_feed_the_ledger() -> Result<(), CryptoError> {
+        use crate::item::{ItemType, VaultItem};
+
+        let mut item = VaultItem::create("item-1", ItemType::Login, "Example", 100)?;
+        let ledger = new_password_history(&KEY)?;
+        let update = item.set_data_with_history(&KEY, &ledger, r#"{"username":"alice","password":"first"}"#, 200)?;
+        assert!(!update.reused());
+        let update = item.set_data_with_history(&KEY, &update.ledger(), r#"{"password":"second"}"#, 300)?;
+        assert!(!update.reused());
+        assert!(password_history_contains(&KEY, &update.ledger(), "first")?);
+        assert!(!password_history_contains(&KEY, &update.ledger(), "second")?);
+
+        // Powrot do starego hasla ostrzega; zapis bez zmiany hasla nie rusza ledgera
+        let reverted = item.set_data_with_history(&KEY, &update.ledger(), r#"{"password":"first"}"#, 400)?;
+        assert!(reverted.reused());
+        let unchanged = item.set_data_with_history(&KEY, &reverted.ledger(), r#"{"password":"first"}"#, 500)?;
+        assert!(!unchanged.reused());
+        assert_eq!(unchanged.ledger(), reverted.ledger());
+
+        // Zly klucz historii - wpis zostaje bez zmian
+        assert!(item.set_data_with_history(&[7u8; 32], &reverted.ledger(), r#"{"password":"third"}"#, 600).is_err());
+        assert_eq!(item.login().and_then(|l| l.password.as_deref()), Some("first"));
+        Ok(())
+    }
+}
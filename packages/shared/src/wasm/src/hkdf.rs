@@ -0,0 +1,168 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::error::CryptoError;
+use crate::hmac::{hmac_sha256_bytes, hmac_sha512_bytes};
+
+// HKDF (RFC 5869). Pusta sol zastepowana blokiem zer dlugosci skrotu.
+pub fn hkdf_extract_sha256(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+    let zeros = [0u8; 32];
+    let salt = if salt.is_empty() { &zeros[..] } else { salt };
+    hmac_sha256_bytes(salt, ikm)
+}
+
+pub fn hkdf_extract_sha512(salt: &[u8], ikm: &[u8]) -> [u8; 64] {
+    let zeros = [0u8; 64];
+    let salt = if salt.is_empty() { &zeros[..] } else { salt };
+    hmac_sha512_bytes(salt, ikm)
+}
+
+fn expand<const H: usize>(
+    prk: &[u8],
+    info: &[u8],
+    len: usize,
+    mac: fn(&[u8], &[u8]) -> [u8; H],
+) -> Result<Vec<u8>, CryptoError> {
+    if len == 0 || len > 255 * H {
+        return Err(CryptoError::OutOfRange(format!("HKDF output length must be 1..={}", 255 * H)));
+    }
+    let mut okm = Vec::with_capacity(len);
+    let mut t: Vec<u8> = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        let mut msg = std::mem::take(&mut t);
+        msg.extend_from_slice(info);
+        msg.push(counter);
+        let mut block = mac(prk, &msg);
+        msg.zeroize();
+        let take = (len - okm.len()).min(H);
+        okm.extend_from_slice(&block[..take]);
+        t = block.to_vec();
+        block.zeroize();
+        counter = counter.wrapping_add(1);
+    }
+    t.zeroize();
+    Ok(okm)
+}
+
+pub fn hkdf_expand_sha256(prk: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, CryptoError> {
+    expand::<32>(prk, info, len, hmac_sha256_bytes)
+}
+
+pub fn hkdf_expand_sha512(prk: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, CryptoError> {
+    expand::<64>(prk, info, len, hmac_sha512_bytes)
+}
+
+pub fn hkdf_sha256_bytes(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, CryptoError> {
+    let mut prk = hkdf_extract_sha256(salt, ikm);
+    let out = hkdf_expand_sha256(&prk, info, len);
+    prk.zeroize();
+    out
+}
+
+pub fn hkdf_sha512_bytes(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, CryptoError> {
+    let mut prk = hkdf_extract_sha512(salt, ikm);
+    let out = hkdf_expand_sha512(&prk, info, len);
+    prk.zeroize();
+    out
+}
+
+// Podklucze (np. szyfrowanie + MAC) z wyniku PBKDF2/Argon2 - rozne info dla kazdego celu.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, CryptoError> {
+    hkdf_sha256_bytes(ikm, salt, info, len)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hkdf_sha512(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Result<Vec<u8>, CryptoError> {
+    hkdf_sha512_bytes(ikm, salt, info, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    struct Case {
+        ikm: Vec<u8>,
+        salt: Vec<u8>,
+        info: Vec<u8>,
+        len: usize,
+    }
+
+    // Wejscia przypadkow testowych 1-3 z RFC 5869, dodatek A.
+    fn rfc5869_cases() -> [Case; 3] {
+        [
+            Case { ikm: vec![0x0b; 22], salt: (0x00..=0x0c).collect(), info: (0xf0..=0xf9).collect(), len: 42 },
+            Case {
+                ikm: (0x00..=0x4f).collect(),
+                salt: (0x60..=0xaf).collect(),
+                info: (0xb0..=0xff).collect(),
+                len: 82,
+            },
+            Case { ikm: vec![0x0b; 22], salt: vec![], info: vec![], len: 42 },
+        ]
+    }
+
+    #[test]
+    fn rfc5869_sha256() -> Result<(), CryptoError> {
+        let expected = [
+            (
+                "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+                "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+            ),
+            (
+                "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+                concat!(
+                    "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c",
+                    "59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71",
+                    "cc30c58179ec3e87c14c01d5c1f3434f1d87"
+                ),
+            ),
+            (
+                "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+                "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+            ),
+        ];
+        for (case, (prk, okm)) in rfc5869_cases().iter().zip(expected) {
+            assert_eq!(bytes_to_hex(&hkdf_extract_sha256(&case.salt, &case.ikm)), prk);
+            assert_eq!(bytes_to_hex(&hkdf_sha256_bytes(&case.ikm, &case.salt, &case.info, case.len)?), okm);
+        }
+        Ok(())
+    }
+
+    // RFC 5869 nie ma wektorow SHA-512 - te same wejscia, wyniki z pyca/cryptography.
+    #[test]
+    fn rfc5869_inputs_sha512() -> Result<(), CryptoError> {
+        let expected = [
+            "832390086cda71fb47625bb5ceb168e4c8e26a1a16ed34d9fc7fe92c1481579338da362cb8d9f925d7cb",
+            concat!(
+                "ce6c97192805b346e6161e821ed165673b84f400a2b514b2fe23d84cd189ddf1",
+                "b695b48cbd1c8388441137b3ce28f16aa64ba33ba466b24df6cfcb021ecff235",
+                "f6a2056ce3af1de44d572097a8505d9e7a93"
+            ),
+            "f5fa02b18298a72a8c23898a8703472c6eb179dc204c03425c970e3b164bf90fff22d04836d0e2343bac",
+        ];
+        for (case, okm) in rfc5869_cases().iter().zip(expected) {
+            assert_eq!(bytes_to_hex(&hkdf_sha512_bytes(&case.ikm, &case.salt, &case.info, case.len)?), okm);
+        }
+        assert_eq!(
+            bytes_to_hex(&hkdf_extract_sha512(&[], &[0x0b; 22])),
+            concat!(
+                "fd200c4987ac491313bd4a2a13287121247239e11c9ef82802044b66ef357e5b",
+                "194498d0682611382348572a7b1611de54764094286320578a863f36562b0df6"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn output_length_is_bounded() -> Result<(), CryptoError> {
+        assert_eq!(hkdf_sha256_bytes(b"ikm", b"", b"", 255 * 32)?.len(), 255 * 32);
+        assert!(matches!(hkdf_sha256_bytes(b"ikm", b"", b"", 255 * 32 + 1), Err(CryptoError::OutOfRange(_))));
+        assert!(matches!(hkdf_sha512_bytes(b"ikm", b"", b"", 255 * 64 + 1), Err(CryptoError::OutOfRange(_))));
+        assert!(matches!(hkdf_sha256_bytes(b"ikm", b"", b"", 0), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,378 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::digest::{DigestState, HashAlgorithm, digest_bytes};
+#[cfg(feature = "js")]
+use crate::encoding::{OutputEncoding, encode_output};
+use crate::error::CryptoError;
+#[cfg(feature = "js")]
+use crate::fips::check_hash;
+use crate::fips::require_approved;
+use crate::sha1::{Sha1, sha1_bytes};
+use crate::sha2::{Sha256, Sha512, sha256_bytes, sha512_bytes};
+
+// Kontekst HMAC z kluczem przetworzonym raz: stany SHA po bloku ipad/opad. Kolejne MAC-i
+// tym samym kluczem (PBKDF2: c iteracji) tylko klonuja stany zamiast padowac klucz od nowa.
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    pub fn new(key: &[u8]) -> HmacSha256 {
+        const BLOCK_SIZE: usize = 64;
+        let mut k = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            let mut hashed = sha256_bytes(key);
+            k[..32].copy_from_slice(&hashed);
+            hashed.zeroize();
+        } else {
+            k[..key.len()].copy_from_slice(key);
+        }
+        let mut pad = k.map(|b| b ^ 0x36);
+        let mut inner = Sha256::new();
+        inner.update(&pad);
+        pad = k.map(|b| b ^ 0x5c);
+        let mut outer = Sha256::new();
+        outer.update(&pad);
+        k.zeroize();
+        pad.zeroize();
+        HmacSha256 { inner, outer }
+    }
+
+    pub fn mac(&self, data: &[u8]) -> [u8; 32] {
+        let mut inner = self.inner.clone();
+        inner.update(data);
+        let mut inner_hash = inner.finalize();
+        let mut outer = self.outer.clone();
+        outer.update(&inner_hash);
+        inner_hash.zeroize();
+        outer.finalize()
+    }
+}
+
+#[derive(Clone)]
+pub struct HmacSha512 {
+    inner: Sha512,
+    outer: Sha512,
+}
+
+impl HmacSha512 {
+    pub fn new(key: &[u8]) -> HmacSha512 {
+        const BLOCK_SIZE: usize = 128;
+        let mut k = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            let mut hashed = sha512_bytes(key);
+            k[..64].copy_from_slice(&hashed);
+            hashed.zeroize();
+        } else {
+            k[..key.len()].copy_from_slice(key);
+        }
+        let mut pad = k.map(|b| b ^ 0x36);
+        let mut inner = Sha512::new();
+        inner.update(&pad);
+        pad = k.map(|b| b ^ 0x5c);
+        let mut outer = Sha512::new();
+        outer.update(&pad);
+        k.zeroize();
+        pad.zeroize();
+        HmacSha512 { inner, outer }
+    }
+
+    pub fn mac(&self, data: &[u8]) -> [u8; 64] {
+        let mut inner = self.inner.clone();
+        inner.update(data);
+        let mut inner_hash = inner.finalize();
+        let mut outer = self.outer.clone();
+        outer.update(&inner_hash);
+        inner_hash.zeroize();
+        outer.finalize()
+    }
+}
+
+// Tylko dla HOTP/TOTP (RFC 4226/6238), zob. sha1.rs.
+#[derive(Clone)]
+pub struct HmacSha1 {
+    inner: Sha1,
+    outer: Sha1,
+}
+
+impl HmacSha1 {
+    pub fn new(key: &[u8]) -> HmacSha1 {
+        const BLOCK_SIZE: usize = 64;
+        let mut k = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            let mut hashed = sha1_bytes(key);
+            k[..20].copy_from_slice(&hashed);
+            hashed.zeroize();
+        } else {
+            k[..key.len()].copy_from_slice(key);
+        }
+        let mut pad = k.map(|b| b ^ 0x36);
+        let mut inner = Sha1::new();
+        inner.update(&pad);
+        pad = k.map(|b| b ^ 0x5c);
+        let mut outer = Sha1::new();
+        outer.update(&pad);
+        k.zeroize();
+        pad.zeroize();
+        HmacSha1 { inner, outer }
+    }
+
+    pub fn mac(&self, data: &[u8]) -> [u8; 20] {
+        let mut inner = self.inner.clone();
+        inner.update(data);
+        let mut inner_hash = inner.finalize();
+        let mut outer = self.outer.clone();
+        outer.update(&inner_hash);
+        inner_hash.zeroize();
+        outer.finalize()
+    }
+}
+
+pub fn hmac_sha1_bytes(key: &[u8], data: &[u8]) -> [u8; 20] {
+    HmacSha1::new(key).mac(data)
+}
+
+pub fn hmac_sha512_bytes(key: &[u8], data: &[u8]) -> [u8; 64] {
+    HmacSha512::new(key).mac(data)
+}
+
+pub fn hmac_sha256_bytes(key: &[u8], data: &[u8]) -> [u8; 32] {
+    HmacSha256::new(key).mac(data)
+}
+
+// HMAC nad dowolnym skrotem z rejestru - algorytm moze pochodzic z naglowka formatu.
+pub fn hmac_bytes(algorithm: HashAlgorithm, key: &[u8], data: &[u8]) -> Vec<u8> {
+    let block_size = algorithm.block_size();
+    let mut k = if key.len() > block_size { digest_bytes(algorithm, key) } else { key.to_vec() };
+    k.resize(block_size, 0);
+
+    let mut pad: Vec<u8> = k.iter().map(|b| b ^ 0x36).collect();
+    let mut inner = DigestState::new(algorithm);
+    inner.update(&pad);
+    inner.update(data);
+    let mut inner_hash = inner.finalize();
+
+    for (p, b) in pad.iter_mut().zip(k.iter()) {
+        *p = b ^ 0x5c;
+    }
+    let mut outer = DigestState::new(algorithm);
+    outer.update(&pad);
+    outer.update(&inner_hash);
+    k.zeroize();
+    pad.zeroize();
+    inner_hash.zeroize();
+    outer.finalize()
+}
+
+pub fn hmac_sha3_256_bytes(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&Zeroizing::new(hmac_bytes(HashAlgorithm::Sha3_256, key, data)));
+    out
+}
+
+pub fn hmac_sha3_512_bytes(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&Zeroizing::new(hmac_bytes(HashAlgorithm::Sha3_512, key, data)));
+    out
+}
+
+#[cfg(feature = "streebog")]
+pub fn hmac_streebog256_bytes(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&Zeroizing::new(hmac_bytes(HashAlgorithm::Streebog256, key, data)));
+    out
+}
+
+#[cfg(feature = "streebog")]
+pub fn hmac_streebog512_bytes(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut out = [0u8; 64];
+    out.copy_from_slice(&Zeroizing::new(hmac_bytes(HashAlgorithm::Streebog512, key, data)));
+    out
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn hmac_sha3_256(key: &str, input: &str, encoding: Option<OutputEncoding>) -> JsValue {
+    encode_output(&hmac_sha3_256_bytes(key.as_bytes(), input.as_bytes()), encoding)
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn hmac_sha3_512(key: &str, input: &str, encoding: Option<OutputEncoding>) -> JsValue {
+    encode_output(&hmac_sha3_512_bytes(key.as_bytes(), input.as_bytes()), encoding)
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn hmac_sha1_interop(key: &str, input: &str, encoding: Option<OutputEncoding>) -> Result<JsValue, CryptoError> {
+    require_approved("hmac-sha1")?;
+    Ok(encode_output(&hmac_sha1_bytes(key.as_bytes(), input.as_bytes()), encoding))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hmac_sha1_interop_raw(key: &[u8], input: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    require_approved("hmac-sha1")?;
+    Ok(hmac_sha1_bytes(key, input).to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hmac_sha3_256_raw(key: &[u8], input: &[u8]) -> Vec<u8> {
+    hmac_sha3_256_bytes(key, input).to_vec()
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hmac_sha3_512_raw(key: &[u8], input: &[u8]) -> Vec<u8> {
+    hmac_sha3_512_bytes(key, input).to_vec()
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn hmac_with(
+    algorithm: HashAlgorithm,
+    key: &[u8],
+    data: &[u8],
+    encoding: Option<OutputEncoding>,
+) -> Result<JsValue, CryptoError> {
+    check_hash(algorithm)?;
+    Ok(encode_output(&hmac_bytes(algorithm, key, data), encoding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    const LARGE_DATA: &[u8] = b"This is a test using a larger than block-size key and a larger than block-size data. \
+        The key needs to be hashed before being used by the HMAC algorithm.";
+
+    // Przypadki testowe 1-7 z RFC 4231: (klucz, dane).
+    fn rfc4231_inputs() -> [(Vec<u8>, &'static [u8]); 7] {
+        [
+            (vec![0x0b; 20], b"Hi There"),
+            (b"Jefe".to_vec(), b"what do ya want for nothing?"),
+            (vec![0xaa; 20], &[0xdd; 50]),
+            ((0x01..=0x19).collect(), &[0xcd; 50]),
+            (vec![0x0c; 20], b"Test With Truncation"),
+            (vec![0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First"),
+            (vec![0xaa; 131], LARGE_DATA),
+        ]
+    }
+
+    // RFC podaje dla przypadku 5 tylko 128 bitow - tu pelny MAC, z ktorego je obcieto.
+    fn check(expected: [&str; 7], mac: impl Fn(&[u8], &[u8]) -> Vec<u8>) {
+        for (i, ((key, data), expected)) in rfc4231_inputs().iter().zip(expected).enumerate() {
+            assert_eq!(bytes_to_hex(&mac(key, data)), expected, "case {}", i + 1);
+        }
+    }
+
+    #[test]
+    fn rfc4231_hmac_sha256() {
+        let expected = [
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+            "a3b6167473100ee06e0c796c2955552bfa6f7c0a6a8aef8b93f860aab0cd20c5",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+        ];
+        check(expected, |k, d| hmac_sha256_bytes(k, d).to_vec());
+    }
+
+    #[test]
+    fn rfc4231_hmac_sha512() {
+        let expected = [
+            concat!(
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde",
+                "daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+            ),
+            concat!(
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554",
+                "9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+            ),
+            concat!(
+                "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39",
+                "bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb"
+            ),
+            concat!(
+                "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db",
+                "a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd"
+            ),
+            concat!(
+                "415fad6271580a531d4179bc891d87a650188707922a4fbb36663a1eb16da008",
+                "711c5b50ddd0fc235084eb9d3364a1454fb2ef67cd1d29fe6773068ea266e96b"
+            ),
+            concat!(
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352",
+                "6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"
+            ),
+            concat!(
+                "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944",
+                "b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58"
+            ),
+        ];
+        check(expected, |k, d| hmac_sha512_bytes(k, d).to_vec());
+    }
+
+    // RFC 4231 nie obejmuje SHA-3 - te same wejscia, wyniki z hashlib/hmac (Python).
+    #[test]
+    fn rfc4231_inputs_hmac_sha3_256() {
+        let expected = [
+            "ba85192310dffa96e2a3a40e69774351140bb7185e1202cdcc917589f95e16bb",
+            "c7d4072e788877ae3596bbb0da73b887c9171f93095b294ae857fbe2645e1ba5",
+            "84ec79124a27107865cedd8bd82da9965e5ed8c37b0ac98005a7f39ed58a4207",
+            "57366a45e2305321a4bc5aa5fe2ef8a921f6af8273d7fe7be6cfedb3f0aea6d7",
+            "6e02c64537fb118057abb7fb66a23b3c5d31bc4b9832edf9528474ce498bdd97",
+            "ed73a374b96c005235f948032f09674a58c0ce555cfc1f223b02356560312c3b",
+            "65c5b06d4c3de32a7aef8763261e49adb6e2293ec8e7c61e8de61701fc63e123",
+        ];
+        check(expected, |k, d| hmac_sha3_256_bytes(k, d).to_vec());
+    }
+
+    #[test]
+    fn rfc4231_inputs_hmac_sha3_512() {
+        let expected = [
+            concat!(
+                "eb3fbd4b2eaab8f5c504bd3a41465aacec15770a7cabac531e482f860b5ec7ba",
+                "47ccb2c6f2afce8f88d22b6dc61380f23a668fd3888bb80537c0a0b86407689e"
+            ),
+            concat!(
+                "5a4bfeab6166427c7a3647b747292b8384537cdb89afb3bf5665e4c5e709350b",
+                "287baec921fd7ca0ee7a0c31d022a95e1fc92ba9d77df883960275beb4e62024"
+            ),
+            concat!(
+                "309e99f9ec075ec6c6d475eda1180687fcf1531195802a99b5677449a8625182",
+                "851cb332afb6a89c411325fbcbcd42afcb7b6e5aab7ea42c660f97fd8584bf03"
+            ),
+            concat!(
+                "b27eab1d6e8d87461c29f7f5739dd58e98aa35f8e823ad38c5492a2088fa0281",
+                "993bbfff9a0e9c6bf121ae9ec9bb09d84a5ebac817182ea974673fb133ca0d1d"
+            ),
+            concat!(
+                "0fa7475948f43f48ca0516671e18978c6e21415f4e7d4e47ab7659e73acffa9f",
+                "0665eb712bfe8369ed2d3d0bda0a6d5dff68918662d639abfd1725187d85a665"
+            ),
+            concat!(
+                "00f751a9e50695b090ed6911a4b65524951cdc15a73a5d58bb55215ea2cd839a",
+                "c79d2b44a39bafab27e83fde9e11f6340b11d991b1b91bf2eee7fc872426c3a4"
+            ),
+            concat!(
+                "38a456a004bd10d32c9ab8336684112862c3db61adcca31829355eaf46fd5c73",
+                "d06a1f0d13fec9a652fb3811b577b1b1d1b9789f97ae5b83c6f44dfcf1d67eba"
+            ),
+        ];
+        check(expected, |k, d| hmac_sha3_512_bytes(k, d).to_vec());
+    }
+
+    #[test]
+    fn registry_matches_the_fixed_functions() {
+        for (key, data) in rfc4231_inputs() {
+            assert_eq!(hmac_bytes(HashAlgorithm::Sha256, &key, data), hmac_sha256_bytes(&key, data));
+            assert_eq!(hmac_bytes(HashAlgorithm::Sha512, &key, data), hmac_sha512_bytes(&key, data));
+        }
+    }
+}
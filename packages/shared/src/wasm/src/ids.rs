@@ -0,0 +1,103 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+use crate::encoding::{bytes_to_hex, length_prefixed};
+use crate::hmac::hmac_sha256_bytes;
+use crate::secret::{EntryKey, MasterKey};
+
+const NAMESPACE_LABEL: &[u8] = b"pm-id-namespace\0";
+
+// Klucz przestrzeni nazw wyprowadzany z klucza w hierarchii (np. klucza vaulta),
+// osobny dla kazdego zastosowania ("login", "attachment", ...).
+pub fn derive_namespace_key(root_key: &MasterKey, namespace: &str) -> EntryKey {
+    let mut msg = NAMESPACE_LABEL.to_vec();
+    msg.extend_from_slice(namespace.as_bytes());
+    EntryKey::from_slice(&hmac_sha256_bytes(root_key.as_bytes(), &msg))
+}
+
+// 16 bajtow HMAC-SHA256 z ustawionymi bitami wersji/wariantu. Wersja 8 (RFC 9562,
+// "custom"), bo to nie jest SHA-1 z UUIDv5, ale uklad pol jest taki sam.
+pub fn deterministic_id_bytes(namespace_key: &EntryKey, parts: &[&[u8]]) -> Result<[u8; 16], CryptoError> {
+    if namespace_key.len() < 32 {
+        return Err(CryptoError::InvalidLength("namespace key must be at least 32 bytes".to_string()));
+    }
+    let mac = hmac_sha256_bytes(namespace_key.as_bytes(), &length_prefixed(parts));
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&mac[..16]);
+    id[6] = (id[6] & 0x0f) | 0x80;
+    id[8] = (id[8] & 0x3f) | 0x80;
+    Ok(id)
+}
+
+pub fn format_uuid(id: &[u8; 16]) -> String {
+    let hex = bytes_to_hex(id);
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn namespace_key(root_key: &[u8], namespace: &str) -> Result<Vec<u8>, CryptoError> {
+    if root_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("root key must be 32 bytes".to_string()));
+    }
+    Ok(derive_namespace_key(&MasterKey::from_slice(root_key), namespace).as_bytes().to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn deterministic_id(namespace_key: &[u8], parts: Vec<String>) -> Result<String, CryptoError> {
+    let parts: Vec<&[u8]> = parts.iter().map(|p| p.as_bytes()).collect();
+    let id = deterministic_id_bytes(&EntryKey::from_slice(namespace_key), &parts)?;
+    Ok(format_uuid(&id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT_KEY: [u8; 32] = [0x0b; 32];
+
+    fn id(namespace: &str, parts: &[&str]) -> Result<[u8; 16], CryptoError> {
+        let key = EntryKey::from_slice(&namespace_key(&ROOT_KEY, namespace)?);
+        let parts: Vec<&[u8]> = parts.iter().map(|p| p.as_bytes()).collect();
+        deterministic_id_bytes(&key, &parts)
+    }
+
+    // Ten sam przypadek co pierwszy "deterministic_id" w bin/vectors.rs (sprawdzony w Pythonie).
+    #[test]
+    fn derived_id_is_pinned() -> Result<(), CryptoError> {
+        let login = id("login", &["https://example.com", "alice"])?;
+        assert_eq!(format_uuid(&login), "4a193c7a-afd6-8f43-b941-6c8a02ceeee6");
+        assert_eq!(
+            deterministic_id(&namespace_key(&ROOT_KEY, "login")?, vec!["https://example.com".into(), "alice".into()])?,
+            format_uuid(&login)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn version_and_variant_bits_are_set() -> Result<(), CryptoError> {
+        for parts in [&["a"][..], &["b", "c"], &[], &["\u{0}"; 4]] {
+            let id = id("login", parts)?;
+            assert_eq!(id[6] >> 4, 8);
+            assert_eq!(id[8] >> 6, 0b10);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn namespaces_and_part_boundaries_are_separated() -> Result<(), CryptoError> {
+        let parts = ["https://example.com", "alice"];
+        assert_ne!(id("login", &parts)?, id("attachment", &parts)?);
+        assert_ne!(id("ab", &["c"])?, id("a", &["bc"])?);
+        assert_ne!(id("login", &["ab", "c"])?, id("login", &["a", "bc"])?);
+        Ok(())
+    }
+
+    #[test]
+    fn key_lengths_are_checked() {
+        assert!(matches!(namespace_key(&[0x0b; 31], "login"), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(namespace_key(&[0x0b; 64], "login"), Err(CryptoError::InvalidLength(_))));
+        let short = EntryKey::from_slice(&[0x0b; 16]);
+        assert!(matches!(deterministic_id_bytes(&short, &[b"a"]), Err(CryptoError::InvalidLength(_))));
+    }
+}
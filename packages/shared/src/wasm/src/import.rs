@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::CryptoError;
+use crate::roundtrip::parse_json_items;
+
+pub use crate::item::{CardData, IdentityData, ItemType, LoginData};
+
+pub mod bitwarden;
+pub mod csv;
+pub mod encrypted;
+pub mod inflate;
+pub mod kdbx;
+pub mod onepux;
+pub mod xml;
+pub mod zip;
+
+// Import z innych menedzerow hasel. Kazdy format mapowany jest na wspolny model wpisu
+// ponizej; wynik dla JS to JSON {"items": [...]} - ten sam ksztalt, ktory przyjmuje
+// weryfikacja importu/eksportu (roundtrip). Puste pola sa pomijane, jak w eksporterach.
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CustomField {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+// Zawartosc zalacznika w base64.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportedAttachment {
+    pub name: String,
+    pub size: usize,
+    pub data: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportedItem {
+    pub id: String,
+    pub kind: ItemType,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login: Option<LoginData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card: Option<CardData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<IdentityData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<CustomField>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<ImportedAttachment>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportedVault {
+    pub items: Vec<ImportedItem>,
+}
+
+// Blad pojedynczego wiersza (numer linii zrodla) - reszta pliku jest importowana.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RowError {
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportResult {
+    pub items: Vec<ImportedItem>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportedItem {
+    // Dane karty/tozsamosci jako pola wlasne - dla formatow bez tych typow (KDBX).
+    pub fn detail_fields(&self) -> Vec<CustomField> {
+        let mut values: Vec<(&str, &Option<String>, bool)> = Vec::new();
+        if let Some(card) = &self.card {
+            values.extend([
+                ("CardCardholderName", &card.cardholder_name, false),
+                ("CardBrand", &card.brand, false),
+                ("CardNumber", &card.number, true),
+                ("CardExpMonth", &card.exp_month, false),
+                ("CardExpYear", &card.exp_year, false),
+                ("CardCode", &card.code, true),
+            ]);
+        }
+        if let Some(identity) = &self.identity {
+            values.extend([
+                ("IdentityTitle", &identity.title, false),
+                ("IdentityFirstName", &identity.first_name, false),
+                ("IdentityMiddleName", &identity.middle_name, false),
+                ("IdentityLastName", &identity.last_name, false),
+                ("IdentityAddress1", &identity.address1, false),
+                ("IdentityAddress2", &identity.address2, false),
+                ("IdentityAddress3", &identity.address3, false),
+                ("IdentityCity", &identity.city, false),
+                ("IdentityState", &identity.state, false),
+                ("IdentityPostalCode", &identity.postal_code, false),
+                ("IdentityCountry", &identity.country, false),
+                ("IdentityCompany", &identity.company, false),
+                ("IdentityEmail", &identity.email, false),
+                ("IdentityPhone", &identity.phone, false),
+                ("IdentitySsn", &identity.ssn, true),
+                ("IdentityUsername", &identity.username, false),
+                ("IdentityPassportNumber", &identity.passport_number, true),
+                ("IdentityLicenseNumber", &identity.license_number, true),
+            ]);
+        }
+        values
+            .into_iter()
+            .filter_map(|(name, value, hidden)| {
+                value.as_ref().map(|value| CustomField { name: name.to_string(), value: value.clone(), hidden })
+            })
+            .collect()
+    }
+}
+
+// "" -> None: formaty zrodlowe nie odrozniaja pustego pola od brakujacego.
+pub fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+pub fn to_json(items: Vec<ImportedItem>) -> Result<String, CryptoError> {
+    serde_json::to_string(&ImportedVault { items }).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+pub fn parse_items(text: &str) -> Result<Vec<ImportedItem>, CryptoError> {
+    parse_json_items(text)?
+        .into_iter()
+        .map(|item| serde_json::from_value(item).map_err(|e| CryptoError::InvalidInput(format!("malformed item: {e}"))))
+        .collect()
+}
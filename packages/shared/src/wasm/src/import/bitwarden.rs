@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::argon2::{Argon2Params, Argon2Variant, Argon2Version, argon2_bytes};
+use crate::bitwarden::enc_string_decrypt_bytes;
+use crate::error::CryptoError;
+use crate::hkdf::hkdf_expand_sha256;
+use crate::import::{CardData, CustomField, IdentityData, ImportedItem, ItemType, LoginData, non_empty, to_json};
+use crate::pbkdf2::pbkdf2_hmac_sha256_bytes;
+use crate::sha2::sha256_bytes;
+
+// Import eksportu JSON Bitwardena: zwyklego ("encrypted": false) i chronionego haslem
+// ("passwordProtected": true). Klucz eksportu chronionego: PBKDF2-SHA256 (kdfType 0) albo
+// Argon2id (kdfType 1, sol = SHA-256(salt), pamiec w MiB) z hasla i napisu salt, potem
+// rozciagniety HKDF-Expand-SHA256 na klucze "enc" i "mac". encKeyValidation_DO_NOT_EDIT
+// odroznia zle haslo od uszkodzonych danych; "data" to EncString zwyklego eksportu.
+// Eksport szyfrowany kluczem konta (bez passwordProtected) wymaga konta - nieobslugiwany.
+const KDF_PBKDF2: u32 = 0;
+const KDF_ARGON2ID: u32 = 1;
+const KEY_LEN: usize = 32;
+
+const TYPE_LOGIN: u32 = 1;
+const TYPE_CARD: u32 = 3;
+const TYPE_IDENTITY: u32 = 4;
+
+const FIELD_TEXT: u32 = 0;
+const FIELD_HIDDEN: u32 = 1;
+const FIELD_BOOLEAN: u32 = 2;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    password_protected: bool,
+    salt: Option<String>,
+    kdf_type: Option<u32>,
+    kdf_iterations: Option<u32>,
+    kdf_memory: Option<u32>,
+    kdf_parallelism: Option<u32>,
+    #[serde(rename = "encKeyValidation_DO_NOT_EDIT")]
+    enc_key_validation: Option<String>,
+    data: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct NamedRef {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Export {
+    #[serde(default)]
+    folders: Vec<NamedRef>,
+    #[serde(default)]
+    collections: Vec<NamedRef>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+struct Uri {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Login {
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+    #[serde(default)]
+    uris: Option<Vec<Uri>>,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    name: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "type", default)]
+    kind: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    cardholder_name: Option<String>,
+    brand: Option<String>,
+    number: Option<String>,
+    exp_month: Option<String>,
+    exp_year: Option<String>,
+    code: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Identity {
+    title: Option<String>,
+    first_name: Option<String>,
+    middle_name: Option<String>,
+    last_name: Option<String>,
+    address1: Option<String>,
+    address2: Option<String>,
+    address3: Option<String>,
+    city: Option<String>,
+    state: Option<String>,
+    postal_code: Option<String>,
+    country: Option<String>,
+    company: Option<String>,
+    email: Option<String>,
+    phone: Option<String>,
+    ssn: Option<String>,
+    username: Option<String>,
+    passport_number: Option<String>,
+    license_number: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    id: Option<String>,
+    #[serde(rename = "type")]
+    kind: u32,
+    #[serde(default)]
+    name: String,
+    notes: Option<String>,
+    folder_id: Option<String>,
+    #[serde(default)]
+    collection_ids: Option<Vec<String>>,
+    #[serde(default)]
+    fields: Option<Vec<Field>>,
+    login: Option<Login>,
+    card: Option<Card>,
+    identity: Option<Identity>,
+}
+
+fn malformed(e: serde_json::Error) -> CryptoError {
+    CryptoError::InvalidInput(format!("malformed bitwarden export: {e}"))
+}
+
+// null i "" traktowane jednakowo.
+fn text(value: Option<String>) -> Option<String> {
+    value.as_deref().and_then(non_empty)
+}
+
+struct ExportKeys {
+    enc_key: Zeroizing<Vec<u8>>,
+    mac_key: Zeroizing<Vec<u8>>,
+}
+
+fn export_keys(envelope: &Envelope, password: &[u8]) -> Result<ExportKeys, CryptoError> {
+    let missing = |what: &str| CryptoError::CorruptHeader(format!("bitwarden export is missing {what}"));
+    let salt = envelope.salt.as_deref().ok_or_else(|| missing("salt"))?;
+    let iterations = envelope.kdf_iterations.ok_or_else(|| missing("kdfIterations"))?;
+    let master = Zeroizing::new(match envelope.kdf_type.unwrap_or(KDF_PBKDF2) {
+        KDF_PBKDF2 => pbkdf2_hmac_sha256_bytes(password, salt.as_bytes(), iterations, KEY_LEN)?,
+        KDF_ARGON2ID => {
+            let memory_mib = envelope.kdf_memory.ok_or_else(|| missing("kdfMemory"))?;
+            let params = Argon2Params {
+                variant: Argon2Variant::Argon2id,
+                version: Argon2Version::V0x13,
+                memory_kib: memory_mib
+                    .checked_mul(1024)
+                    .ok_or_else(|| CryptoError::OutOfRange(format!("kdfMemory {memory_mib} MiB is too large")))?,
+                iterations,
+                parallelism: envelope.kdf_parallelism.ok_or_else(|| missing("kdfParallelism"))?,
+            };
+            argon2_bytes(&params, password, &sha256_bytes(salt.as_bytes()), &[], &[], KEY_LEN)?
+        }
+        other => return Err(CryptoError::UnsupportedAlgorithm(format!("bitwarden kdf type {other}"))),
+    });
+    let enc_key = Zeroizing::new(hkdf_expand_sha256(&master, b"enc", KEY_LEN)?);
+    let mac_key = Zeroizing::new(hkdf_expand_sha256(&master, b"mac", KEY_LEN)?);
+    Ok(ExportKeys { enc_key, mac_key })
+}
+
+fn decrypt_export(envelope: &Envelope, password: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    if !envelope.password_protected {
+        return Err(CryptoError::UnsupportedAlgorithm(
+            "account-restricted bitwarden exports need the account key; export with a file password".to_string(),
+        ));
+    }
+    let ExportKeys { enc_key, mac_key } = export_keys(envelope, password)?;
+    let validation = envelope
+        .enc_key_validation
+        .as_deref()
+        .ok_or_else(|| CryptoError::CorruptHeader("bitwarden export is missing key validation".to_string()))?;
+    enc_string_decrypt_bytes(&enc_key, &mac_key, validation).map_err(|e| match e {
+        CryptoError::BadMac(_) => CryptoError::WrongPassword("wrong bitwarden export password".to_string()),
+        other => other,
+    })?;
+    let data = envelope
+        .data
+        .as_deref()
+        .ok_or_else(|| CryptoError::CorruptHeader("bitwarden export is missing data".to_string()))?;
+    enc_string_decrypt_bytes(&enc_key, &mac_key, data)
+}
+
+fn convert_item(item: Item, folders: &HashMap<String, String>, collections: &HashMap<String, String>) -> ImportedItem {
+    // Folder osobisty, a w eksporcie organizacji pierwsza kolekcja
+    let folder = item
+        .folder_id
+        .as_ref()
+        .and_then(|id| folders.get(id))
+        .or_else(|| item.collection_ids.iter().flatten().find_map(|id| collections.get(id)))
+        .cloned();
+    let mut imported = ImportedItem {
+        id: item.id.unwrap_or_default(),
+        kind: ItemType::SecureNote,
+        name: item.name,
+        folder,
+        notes: text(item.notes),
+        ..ImportedItem::default()
+    };
+    match item.kind {
+        TYPE_LOGIN => {
+            imported.kind = ItemType::Login;
+            let login = item.login.map(|login| LoginData {
+                username: text(login.username),
+                password: text(login.password),
+                uris: login.uris.into_iter().flatten().filter_map(|uri| text(uri.uri)).collect(),
+                totp: text(login.totp),
+            });
+            imported.login = Some(login.unwrap_or_default());
+        }
+        TYPE_CARD => {
+            imported.kind = ItemType::Card;
+            imported.card = item.card.map(|card| CardData {
+                cardholder_name: text(card.cardholder_name),
+                brand: text(card.brand),
+                number: text(card.number),
+                exp_month: text(card.exp_month),
+                exp_year: text(card.exp_year),
+                code: text(card.code),
+            });
+        }
+        TYPE_IDENTITY => {
+            imported.kind = ItemType::Identity;
+            imported.identity = item.identity.map(|identity| IdentityData {
+                title: text(identity.title),
+                first_name: text(identity.first_name),
+                middle_name: text(identity.middle_name),
+                last_name: text(identity.last_name),
+                address1: text(identity.address1),
+                address2: text(identity.address2),
+                address3: text(identity.address3),
+                city: text(identity.city),
+                state: text(identity.state),
+                postal_code: text(identity.postal_code),
+                country: text(identity.country),
+                company: text(identity.company),
+                email: text(identity.email),
+                phone: text(identity.phone),
+                ssn: text(identity.ssn),
+                username: text(identity.username),
+                passport_number: text(identity.passport_number),
+                license_number: text(identity.license_number),
+            });
+        }
+        // Notatki i typy bez odpowiednika (np. klucze SSH) - notatka z polami wlasnymi
+        _ => {}
+    }
+    // Pola "linked" (typ 3) wskazuja na inne pola wpisu i nie maja wlasnej wartosci
+    for field in item.fields.into_iter().flatten() {
+        if matches!(field.kind, FIELD_TEXT | FIELD_HIDDEN | FIELD_BOOLEAN) {
+            imported.fields.push(CustomField {
+                name: field.name.unwrap_or_default(),
+                value: field.value.unwrap_or_default(),
+                hidden: field.kind == FIELD_HIDDEN,
+            });
+        }
+    }
+    imported
+}
+
+pub fn import_bitwarden_bytes(data: &[u8], password: &[u8]) -> Result<Vec<ImportedItem>, CryptoError> {
+    let envelope: Envelope = serde_json::from_slice(data).map_err(malformed)?;
+    let export: Export = if envelope.encrypted {
+        let plain = decrypt_export(&envelope, password)?;
+        serde_json::from_slice(&plain).map_err(malformed)?
+    } else {
+        serde_json::from_slice(data).map_err(malformed)?
+    };
+    let by_id = |refs: Vec<NamedRef>| refs.into_iter().map(|r| (r.id, r.name)).collect::<HashMap<_, _>>();
+    let folders = by_id(export.folders);
+    let collections = by_id(export.collections);
+    Ok(export.items.into_iter().map(|item| convert_item(item, &folders, &collections)).collect())
+}
+
+// password - haslo pliku dla eksportu chronionego, dla zwyklego ignorowane.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_bitwarden(data: &str, password: &str) -> Result<String, CryptoError> {
+    import_bitwarden_raw(data, password.as_bytes())
+}
+
+// Haslo jako bajty UTF-8 (Uint8Array), ktore JS moze wyczyscic.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_bitwarden_raw(data: &str, password: &[u8]) -> Result<String, CryptoError> {
+    to_json(import_bitwarden_bytes(data.as_bytes(), password)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitwarden::enc_string_encrypt_bytes;
+
+    const PLAIN: &str = r#"{
+        "encrypted": false,
+        "folders": [{"id": "f1", "name": "Work"}],
+        "items": [
+            {"id": "i1", "type": 1, "name": "GitHub", "folderId": "f1", "notes": "",
+             "login": {"username": "octo", "password": "pw", "totp": null,
+                       "uris": [{"uri": "https://github.com"}, {"uri": ""}]},
+             "fields": [{"name": "pin", "value": "1234", "type": 1}, {"name": "link", "value": null, "type": 3}]},
+            {"id": "i2", "type": 3, "name": "Visa", "card": {"number": "4111111111111111", "code": "123"}},
+            {"id": "i3", "type": 2, "name": "Note", "notes": "text"}
+        ]
+    }"#;
+
+    fn protected_export(password: &[u8], inner: &str) -> Result<String, CryptoError> {
+        let salt = "c2FsdHNhbHRzYWx0c2FsdA==";
+        let envelope = Envelope {
+            encrypted: true,
+            password_protected: true,
+            salt: Some(salt.to_string()),
+            kdf_type: Some(KDF_PBKDF2),
+            kdf_iterations: Some(1000),
+            kdf_memory: None,
+            kdf_parallelism: None,
+            enc_key_validation: None,
+            data: None,
+        };
+        let keys = export_keys(&envelope, password)?;
+        let validation = enc_string_encrypt_bytes(&keys.enc_key, &keys.mac_key, b"validation")?;
+        let data = enc_string_encrypt_bytes(&keys.enc_key, &keys.mac_key, inner.as_bytes())?;
+        Ok(format!(
+            r#"{{"encrypted": true, "passwordProtected": true, "salt": "{salt}", "kdfType": 0,
+                "kdfIterations": 1000, "encKeyValidation_DO_NOT_EDIT": "{validation}", "data": "{data}"}}"#
+        ))
+    }
+
+    #[test]
+    fn plain_export_maps_items() -> Result<(), CryptoError> {
+        let items = import_bitwarden_bytes(PLAIN.as_bytes(), b"")?;
+        assert_eq!(items.len(), 3);
+        let login = &items[0];
+        assert_eq!(login.kind, ItemType::Login);
+        assert_eq!((login.folder.as_deref(), login.notes.as_deref()), (Some("Work"), None));
+        let data = login.login.clone().unwrap_or_default();
+        assert_eq!(data.uris, vec!["https://github.com".to_string()]);
+        let pin = CustomField { name: "pin".to_string(), value: "1234".to_string(), hidden: true };
+        assert_eq!(login.fields, vec![pin]);
+        assert_eq!(items[1].kind, ItemType::Card);
+        assert_eq!(items[1].card.as_ref().and_then(|c| c.code.as_deref()), Some("123"));
+        assert_eq!((items[2].kind, items[2].notes.as_deref()), (ItemType::SecureNote, Some("text")));
+        Ok(())
+    }
+
+    #[test]
+    fn password_protected_export_opens_with_the_file_password() -> Result<(), CryptoError> {
+        let export = protected_export(b"file password", PLAIN)?;
+        let items = import_bitwarden_bytes(export.as_bytes(), b"file password")?;
+        assert_eq!(items, import_bitwarden_bytes(PLAIN.as_bytes(), b"")?);
+        assert!(matches!(import_bitwarden_bytes(export.as_bytes(), b"wrong"), Err(CryptoError::WrongPassword(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_and_malformed_exports_are_rejected() {
+        let account = r#"{"encrypted": true, "passwordProtected": false, "data": "2.x|y|z"}"#;
+        assert!(matches!(import_bitwarden_bytes(account.as_bytes(), b""), Err(CryptoError::UnsupportedAlgorithm(_))));
+        let no_salt = r#"{"encrypted": true, "passwordProtected": true, "kdfIterations": 1000}"#;
+        assert!(matches!(import_bitwarden_bytes(no_salt.as_bytes(), b"pw"), Err(CryptoError::CorruptHeader(_))));
+        let bad_kdf =
+            r#"{"encrypted": true, "passwordProtected": true, "salt": "s", "kdfType": 9, "kdfIterations": 1}"#;
+        assert!(matches!(import_bitwarden_bytes(bad_kdf.as_bytes(), b"pw"), Err(CryptoError::UnsupportedAlgorithm(_))));
+        assert!(matches!(import_bitwarden_bytes(b"{\"items\": 5}", b""), Err(CryptoError::InvalidInput(_))));
+    }
+}
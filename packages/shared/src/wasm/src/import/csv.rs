@@ -0,0 +1,319 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::encoding::bytes_to_hex;
+use crate::error::CryptoError;
+use crate::import::{ImportResult, ImportedItem, ItemType, LoginData, RowError, non_empty};
+use crate::rng::random_array;
+use crate::url::canonicalize_url;
+
+// Import CSV z menedzerow i przegladarek. Parser wg RFC 4180: pola w cudzyslowach moga
+// zawierac przecinki, cudzyslowy ("") i konce linii; CRLF i LF sa rownowazne. Kolumny
+// wyszukiwane po naglowku (bez wielkosci liter), bo kolejnosc rozni sie miedzy wersjami
+// eksporterow. Zly wiersz nie przerywa importu - trafia do errors z numerem linii.
+const LASTPASS_NOTE_URL: &str = "http://sn";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvSource {
+    LastPass,
+    Chrome,
+    Firefox,
+    Safari,
+}
+
+// Nazwy kolumn w naglowku; puste - zrodlo nie ma takiej kolumny.
+struct ColumnMap {
+    name: &'static str,
+    url: &'static str,
+    username: &'static str,
+    password: &'static str,
+    notes: &'static str,
+    totp: &'static str,
+    folder: &'static str,
+    id: &'static str,
+}
+
+impl CsvSource {
+    pub fn from_name(name: &str) -> Result<CsvSource, CryptoError> {
+        match name {
+            "lastpass" => Ok(CsvSource::LastPass),
+            "chrome" | "edge" => Ok(CsvSource::Chrome),
+            "firefox" => Ok(CsvSource::Firefox),
+            "safari" => Ok(CsvSource::Safari),
+            other => Err(CryptoError::UnsupportedAlgorithm(format!("csv source: {other}"))),
+        }
+    }
+
+    fn columns(self) -> ColumnMap {
+        match self {
+            CsvSource::LastPass => ColumnMap {
+                name: "name",
+                url: "url",
+                username: "username",
+                password: "password",
+                notes: "extra",
+                totp: "totp",
+                folder: "grouping",
+                id: "",
+            },
+            CsvSource::Chrome => ColumnMap {
+                name: "name",
+                url: "url",
+                username: "username",
+                password: "password",
+                notes: "note",
+                totp: "",
+                folder: "",
+                id: "",
+            },
+            CsvSource::Firefox => ColumnMap {
+                name: "",
+                url: "url",
+                username: "username",
+                password: "password",
+                notes: "",
+                totp: "",
+                folder: "",
+                id: "guid",
+            },
+            CsvSource::Safari => ColumnMap {
+                name: "title",
+                url: "url",
+                username: "username",
+                password: "password",
+                notes: "notes",
+                totp: "otpauth",
+                folder: "",
+                id: "",
+            },
+        }
+    }
+}
+
+pub struct CsvRecord {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+fn malformed(line: usize, what: &str) -> CryptoError {
+    CryptoError::InvalidInput(format!("malformed CSV at line {line}: {what}"))
+}
+
+// Pomija reszte linii z blednym cudzyslowem.
+fn misplaced_quote(chars: &mut Peekable<Chars>, row: usize) -> RowError {
+    chars.find(|&c| c == '\n');
+    RowError { row, message: "misplaced quote".to_string() }
+}
+
+// Rekordy z numerem linii, od ktorej sie zaczynaja (pole w cudzyslowie moze miec wiele linii).
+// Cudzyslow w zlym miejscu odrzuca tylko swoj wiersz; niezamkniety pochlania reszte pliku,
+// wiec jest bledem calego importu.
+pub fn parse_csv(text: &str) -> Result<(Vec<CsvRecord>, Vec<RowError>), CryptoError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let (mut records, mut errors) = (Vec::new(), Vec::new());
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    'records: while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        loop {
+            let mut field = String::new();
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            line += (c == '\n') as usize;
+                            field.push(c);
+                        }
+                        None => return Err(malformed(start, "unterminated quoted field")),
+                    }
+                }
+                if !matches!(chars.peek(), None | Some(',' | '\r' | '\n')) {
+                    errors.push(misplaced_quote(&mut chars, start));
+                    line += 1;
+                    continue 'records;
+                }
+            } else {
+                while let Some(&c) = chars.peek() {
+                    if matches!(c, ',' | '\r' | '\n') {
+                        break;
+                    }
+                    if c == '"' {
+                        errors.push(misplaced_quote(&mut chars, start));
+                        line += 1;
+                        continue 'records;
+                    }
+                    field.push(c);
+                    chars.next();
+                }
+            }
+            fields.push(field);
+            match chars.next() {
+                Some(',') => continue,
+                Some('\r') => {
+                    chars.next_if_eq(&'\n');
+                }
+                _ => {}
+            }
+            line += 1;
+            break;
+        }
+        records.push(CsvRecord { line: start, fields });
+    }
+    Ok((records, errors))
+}
+
+fn random_id() -> Result<String, CryptoError> {
+    Ok(bytes_to_hex(&random_array::<16>()?))
+}
+
+pub fn import_csv_bytes(source: CsvSource, text: &str) -> Result<ImportResult, CryptoError> {
+    let (records, errors) = parse_csv(text)?;
+    let mut records = records.into_iter();
+    let header = records.next().ok_or_else(|| CryptoError::InvalidInput("CSV has no header row".to_string()))?;
+    let header: Vec<String> = header.fields.iter().map(|h| h.trim().to_lowercase()).collect();
+    let map = source.columns();
+    let index = |column: &str| (!column.is_empty()).then(|| header.iter().position(|h| h == column)).flatten();
+    let (Some(url_at), Some(password_at)) = (index(map.url), index(map.password)) else {
+        return Err(CryptoError::InvalidInput(format!(
+            "CSV header is missing the {} or {} column for {source:?}",
+            map.url, map.password
+        )));
+    };
+    let columns = [index(map.name), index(map.username), index(map.notes), index(map.totp), index(map.folder)];
+    let [name_at, username_at, notes_at, totp_at, folder_at] = columns;
+    let id_at = index(map.id);
+
+    let mut result = ImportResult { errors, ..ImportResult::default() };
+    for record in records {
+        // Pusta linia (np. na koncu pliku) to jeden pusty rekord
+        if record.fields.len() == 1 && record.fields[0].is_empty() {
+            continue;
+        }
+        if record.fields.len() != header.len() {
+            result.errors.push(RowError {
+                row: record.line,
+                message: format!("expected {} fields, found {}", header.len(), record.fields.len()),
+            });
+            continue;
+        }
+        let cell = |at: Option<usize>| at.map_or("", |i| record.fields[i].as_str());
+        let url = cell(Some(url_at));
+        let login = LoginData {
+            username: non_empty(cell(username_at)),
+            password: non_empty(cell(Some(password_at))),
+            uris: non_empty(url).into_iter().collect(),
+            totp: non_empty(cell(totp_at)),
+        };
+        let notes = non_empty(cell(notes_at));
+        if login == LoginData::default() && notes.is_none() && cell(name_at).is_empty() {
+            result
+                .errors
+                .push(RowError { row: record.line, message: "row has no name, url, credentials or notes".to_string() });
+            continue;
+        }
+        // LastPass: podfoldery "a\b", notatki bezpieczne z adresem http://sn
+        let folder = non_empty(&cell(folder_at).replace('\\', "/"));
+        let is_note = source == CsvSource::LastPass && url == LASTPASS_NOTE_URL;
+        let name = non_empty(cell(name_at))
+            .or_else(|| canonicalize_url(url).ok())
+            .or_else(|| login.username.clone())
+            .unwrap_or_default();
+        result.items.push(ImportedItem {
+            id: non_empty(cell(id_at)).map_or_else(random_id, Ok)?,
+            kind: if is_note { ItemType::SecureNote } else { ItemType::Login },
+            name,
+            folder,
+            notes,
+            login: (!is_note).then_some(login),
+            ..ImportedItem::default()
+        });
+    }
+    result.errors.sort_by_key(|e| e.row);
+    Ok(result)
+}
+
+// source: "lastpass", "chrome" / "edge", "firefox", "safari". Wynik: JSON ImportResult.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_csv(source: &str, text: &str) -> Result<String, CryptoError> {
+    let result = import_csv_bytes(CsvSource::from_name(source)?, text)?;
+    serde_json::to_string(&result).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_fields_keep_commas_quotes_and_newlines() -> Result<(), CryptoError> {
+        let text = "\u{feff}a,b\r\n\"x, \"\"y\"\"\",\"two\nlines\"\n";
+        let (records, errors) = parse_csv(text)?;
+        assert!(errors.is_empty());
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].fields, vec!["x, \"y\"".to_string(), "two\nlines".to_string()]);
+        assert_eq!(records[1].line, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_quotes_reject_a_row_or_the_file() -> Result<(), CryptoError> {
+        let (records, errors) = parse_csv("a,b\nx\"y,z\nok,1\n")?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(errors, vec![RowError { row: 2, message: "misplaced quote".to_string() }]);
+        assert!(matches!(parse_csv("a,b\n\"open,1\n"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn lastpass_export_maps_folders_and_notes() -> Result<(), CryptoError> {
+        let text = "url,username,password,totp,extra,name,grouping,fav\n\
+            https://github.com,octo,pw,JBSWY3DP,,GitHub,Work\\Dev,0\n\
+            http://sn,,,,secret note,Note,,0\n";
+        let result = import_csv_bytes(CsvSource::LastPass, text)?;
+        assert!(result.errors.is_empty());
+        let [login, note] = result.items.as_slice() else {
+            return Err(CryptoError::InvalidInput("expected two items".to_string()));
+        };
+        assert_eq!(login.kind, ItemType::Login);
+        assert_eq!(login.folder.as_deref(), Some("Work/Dev"));
+        let data = login.login.clone().unwrap_or_default();
+        assert_eq!(data.username.as_deref(), Some("octo"));
+        assert_eq!(data.totp.as_deref(), Some("JBSWY3DP"));
+        assert_eq!(note.kind, ItemType::SecureNote);
+        assert_eq!(note.notes.as_deref(), Some("secret note"));
+        assert!(note.login.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn firefox_export_uses_guid_and_url_as_name() -> Result<(), CryptoError> {
+        let text = "url,username,password,httpRealm,formActionOrigin,guid\n\
+            https://www.example.com/login,me,pw,,,{guid-1}\n";
+        let result = import_csv("firefox", text)?;
+        let result: ImportResult = serde_json::from_str(&result).map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        assert_eq!(result.items[0].id, "{guid-1}");
+        assert!(!result.items[0].name.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn bad_rows_and_headers_are_reported() -> Result<(), CryptoError> {
+        let text = "name,url,username,password\nA,https://a.io,u,p\nonly,three,fields\n,,,\n";
+        let result = import_csv_bytes(CsvSource::Chrome, text)?;
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.errors.iter().map(|e| e.row).collect::<Vec<_>>(), vec![3, 4]);
+        assert!(matches!(import_csv_bytes(CsvSource::Chrome, "name,login\nA,b\n"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(import_csv_bytes(CsvSource::Chrome, ""), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(CsvSource::from_name("opera"), Err(CryptoError::UnsupportedAlgorithm(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::calibrate::KdfParams;
+use crate::encoding::{base64_to_bytes, bytes_to_base64};
+use crate::error::CryptoError;
+use crate::import::{ImportedItem, ImportedVault, parse_items, to_json};
+use crate::vault::{CURRENT_ENVELOPE_VERSION, VaultKdf, vault_open_bytes, vault_seal_bytes};
+
+// Szyfrowany eksport z osobnym haslem eksportu (innym niz haslo glowne - odbiorca pliku
+// nie dostaje dostepu do konta). JSON z koperta vaulta w base64:
+//   {"format": "pm-encrypted-export", "version": 1, "envelope": "<PMVE ...>"}
+// Koperta (vault_seal) robi KDF z hasla eksportu (Argon2id, w profilu FIPS PBKDF2), AEAD
+// i MAC; w srodku JSON {"items": [...]} w modelu importu. Pola opakowania sluza tylko do
+// rozpoznania pliku - wersja koperty i parametry KDF sa w jej uwierzytelnionym naglowku.
+const EXPORT_FORMAT: &str = "pm-encrypted-export";
+const EXPORT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedExport {
+    pub format: String,
+    pub version: u32,
+    pub envelope: String,
+}
+
+pub fn export_encrypted_bytes(
+    items: &[ImportedItem],
+    export_password: &[u8],
+    kdf: VaultKdf,
+) -> Result<String, CryptoError> {
+    if export_password.is_empty() {
+        return Err(CryptoError::InvalidInput("export password must not be empty".to_string()));
+    }
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(&ImportedVault { items: items.to_vec() })
+            .map_err(|e| CryptoError::InvalidInput(e.to_string()))?,
+    );
+    let envelope = vault_seal_bytes(export_password, &plaintext, CURRENT_ENVELOPE_VERSION, kdf)?;
+    let archive = EncryptedExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        envelope: bytes_to_base64(&envelope),
+    };
+    serde_json::to_string(&archive).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+pub fn import_encrypted_bytes(archive: &str, export_password: &[u8]) -> Result<Vec<ImportedItem>, CryptoError> {
+    let archive: EncryptedExport = serde_json::from_str(archive)
+        .map_err(|e| CryptoError::InvalidInput(format!("malformed encrypted export: {e}")))?;
+    if archive.format != EXPORT_FORMAT {
+        return Err(CryptoError::InvalidInput(format!("not an encrypted export: {}", archive.format)));
+    }
+    if archive.version != EXPORT_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("encrypted export version {}", archive.version)));
+    }
+    let plaintext = vault_open_bytes(export_password, &base64_to_bytes(&archive.envelope)?).map_err(|e| match e {
+        CryptoError::WrongPassword(_) => {
+            CryptoError::WrongPassword("wrong export password or modified export".to_string())
+        }
+        other => other,
+    })?;
+    let text = std::str::from_utf8(&plaintext)
+        .map_err(|_| CryptoError::InvalidInput("encrypted export payload is not UTF-8".to_string()))?;
+    parse_items(text)
+}
+
+// items - JSON w modelu importu; domyslne parametry KDF koperty vaulta.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn export_encrypted(items: &str, export_password: &str) -> Result<String, CryptoError> {
+    export_encrypted_raw(items, export_password.as_bytes())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn export_encrypted_raw(items: &str, export_password: &[u8]) -> Result<String, CryptoError> {
+    let kdf = VaultKdf::for_version(CURRENT_ENVELOPE_VERSION);
+    export_encrypted_bytes(&parse_items(items)?, export_password, kdf)
+}
+
+// Parametry z calibrate_kdf zamiast domyslnych.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn export_encrypted_with(items: &str, export_password: &str, params: &KdfParams) -> Result<String, CryptoError> {
+    export_encrypted_with_raw(items, export_password.as_bytes(), params)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn export_encrypted_with_raw(items: &str, export_password: &[u8], params: &KdfParams) -> Result<String, CryptoError> {
+    let kdf = params.vault_kdf(CURRENT_ENVELOPE_VERSION);
+    export_encrypted_bytes(&parse_items(items)?, export_password, kdf)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_encrypted(archive: &str, export_password: &str) -> Result<String, CryptoError> {
+    import_encrypted_raw(archive, export_password.as_bytes())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_encrypted_raw(archive: &str, export_password: &[u8]) -> Result<String, CryptoError> {
+    to_json(import_encrypted_bytes(archive, export_password)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: KdfParams =
+        KdfParams { pbkdf2_iterations: 1000, argon2_m_cost: 64, argon2_t_cost: 1, argon2_parallelism: 1 };
+    const ITEMS: &str = r#"{"items": [{"id": "a", "kind": "login", "name": "Mail",
+        "login": {"username": "me", "password": "pw"}}]}"#;
+
+    #[test]
+    fn export_opens_with_the_export_password() -> Result<(), CryptoError> {
+        let archive = export_encrypted_with(ITEMS, "export password", &PARAMS)?;
+        assert!(!archive.contains("Mail"));
+        let items = import_encrypted(&archive, "export password")?;
+        assert_eq!(parse_items(&items)?, parse_items(ITEMS)?);
+        Ok(())
+    }
+
+    #[test]
+    fn raw_and_text_passwords_are_interchangeable() -> Result<(), CryptoError> {
+        let archive = export_encrypted_with_raw(ITEMS, "hasło".as_bytes(), &PARAMS)?;
+        assert_eq!(import_encrypted(&archive, "hasło")?, import_encrypted_raw(&archive, "hasło".as_bytes())?);
+        // Haslo spoza UTF-8 dziala tylko przez wariant _raw
+        let archive = export_encrypted_with_raw(ITEMS, &[0xff, 0x00, 0x80], &PARAMS)?;
+        assert_eq!(parse_items(&import_encrypted_raw(&archive, &[0xff, 0x00, 0x80])?)?, parse_items(ITEMS)?);
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_password_and_foreign_files_are_rejected() -> Result<(), CryptoError> {
+        let archive = export_encrypted_with(ITEMS, "export password", &PARAMS)?;
+        assert!(matches!(import_encrypted(&archive, "wrong"), Err(CryptoError::WrongPassword(_))));
+        assert!(matches!(export_encrypted_with(ITEMS, "", &PARAMS), Err(CryptoError::InvalidInput(_))));
+        let other = archive.replace("pm-encrypted-export", "other-format");
+        assert!(matches!(import_encrypted(&other, "export password"), Err(CryptoError::InvalidInput(_))));
+        let newer = archive.replace("\"version\":1", "\"version\":2");
+        assert!(matches!(import_encrypted(&newer, "export password"), Err(CryptoError::UnsupportedVersion(_))));
+        assert!(matches!(import_encrypted("{}", "export password"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+}
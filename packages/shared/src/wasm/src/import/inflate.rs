@@ -0,0 +1,266 @@
+use zeroize::Zeroizing;
+
+use crate::crc::crc32_bytes;
+use crate::error::CryptoError;
+
+// Dekompresja gzip (RFC 1952) / DEFLATE (RFC 1951) dla importu - payload KDBX jest zwykle
+// spakowany gzipem. Dekoder Huffmana kanoniczny jak w puff.c (zlib/contrib): tablice
+// liczby kodow na dlugosc i symboli w kolejnosci kodow, bez tablic przegladowych.
+// Wyjscie ograniczone (MAX_OUTPUT), zeby plik-bomba nie zajal calej pamieci wasm.
+pub const MAX_OUTPUT: usize = 256 * 1024 * 1024;
+const MAX_BITS: usize = 15;
+
+pub const LENGTH_BASE: [u16; 29] =
+    [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+pub const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+pub const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+pub const DIST_EXTRA: [u8; 30] =
+    [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+// Kolejnosc dlugosci kodow dla alfabetu dlugosci kodow (blok dynamiczny)
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+fn corrupt(what: &str) -> CryptoError {
+    CryptoError::InvalidInput(format!("corrupt deflate stream: {what}"))
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buf: u32,
+    bit_count: u32,
+}
+
+impl BitReader<'_> {
+    fn bits(&mut self, n: u32) -> Result<u32, CryptoError> {
+        while self.bit_count < n {
+            let byte = *self.data.get(self.pos).ok_or_else(|| corrupt("unexpected end of data"))?;
+            self.pos += 1;
+            self.bit_buf |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+        let value = self.bit_buf & ((1u32 << n) - 1);
+        self.bit_buf >>= n;
+        self.bit_count -= n;
+        Ok(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        self.bit_buf = 0;
+        self.bit_count = 0;
+    }
+}
+
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Huffman, CryptoError> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        // Nadmiarowy zestaw dlugosci (wiecej kodow niz miejsca) to blad; niepelny jest dozwolony
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(corrupt("over-subscribed huffman code"));
+            }
+        }
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for len in 1..=MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Ok(Huffman { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, CryptoError> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+            let count = count as i32;
+            if code - count < first {
+                return Ok(self.symbols[(index + (code - first)) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(corrupt("invalid huffman code"))
+    }
+}
+
+fn fixed_tables() -> Result<(Huffman, Huffman), CryptoError> {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5u8; 30])?))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman), CryptoError> {
+    let nlen = reader.bits(5)? as usize + 257;
+    let ndist = reader.bits(5)? as usize + 1;
+    let ncode = reader.bits(4)? as usize + 4;
+    if nlen > 286 || ndist > 30 {
+        return Err(corrupt("bad dynamic block counts"));
+    }
+    let mut code_lengths = [0u8; 19];
+    for &i in &CODE_LENGTH_ORDER[..ncode] {
+        code_lengths[i] = reader.bits(3)? as u8;
+    }
+    let code_table = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; nlen + ndist];
+    let mut i = 0;
+    while i < lengths.len() {
+        let symbol = code_table.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths[..i].last().ok_or_else(|| corrupt("repeat with no previous length"))?;
+                (previous, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+        if i + repeat > lengths.len() {
+            return Err(corrupt("too many code lengths"));
+        }
+        lengths[i..i + repeat].fill(value);
+        i += repeat;
+    }
+    if lengths[256] == 0 {
+        return Err(corrupt("missing end-of-block code"));
+    }
+    Ok((Huffman::new(&lengths[..nlen])?, Huffman::new(&lengths[nlen..])?))
+}
+
+fn inflate_block(
+    reader: &mut BitReader,
+    out: &mut Vec<u8>,
+    lengths: &Huffman,
+    distances: &Huffman,
+    max_output: usize,
+) -> Result<(), CryptoError> {
+    loop {
+        let symbol = lengths.decode(reader)? as usize;
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    return Err(corrupt("bad length symbol"));
+                }
+                let len = LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index] as u32)? as usize;
+                let dist_symbol = distances.decode(reader)? as usize;
+                if dist_symbol >= DIST_BASE.len() {
+                    return Err(corrupt("bad distance symbol"));
+                }
+                let dist = DIST_BASE[dist_symbol] as usize + reader.bits(DIST_EXTRA[dist_symbol] as u32)? as usize;
+                if dist > out.len() {
+                    return Err(corrupt("distance too far back"));
+                }
+                // Kopiowanie bajt po bajcie - zakresy moga sie nakladac (dist < len)
+                let start = out.len() - dist;
+                for k in 0..len {
+                    out.push(out[start + k]);
+                }
+            }
+        }
+        if out.len() > max_output {
+            return Err(CryptoError::OutOfRange(format!("inflated data exceeds {max_output} bytes")));
+        }
+    }
+}
+
+// Surowy strumien DEFLATE; zwraca dane i liczbe zuzytych bajtow wejscia.
+pub fn inflate_bytes(data: &[u8]) -> Result<(Zeroizing<Vec<u8>>, usize), CryptoError> {
+    inflate_bytes_limited(data, MAX_OUTPUT)
+}
+
+// Jak inflate_bytes, ale z wlasnym limitem wyjscia (przekroczenie to OutOfRange).
+pub fn inflate_bytes_limited(data: &[u8], max_output: usize) -> Result<(Zeroizing<Vec<u8>>, usize), CryptoError> {
+    let mut reader = BitReader { data, pos: 0, bit_buf: 0, bit_count: 0 };
+    let mut out = Zeroizing::new(Vec::new());
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => {
+                reader.align_to_byte();
+                let header = data.get(reader.pos..reader.pos + 4).ok_or_else(|| corrupt("truncated stored block"))?;
+                let len = u16::from_le_bytes([header[0], header[1]]) as usize;
+                if len != !u16::from_le_bytes([header[2], header[3]]) as usize {
+                    return Err(corrupt("stored block length mismatch"));
+                }
+                let start = reader.pos + 4;
+                let block = data.get(start..start + len).ok_or_else(|| corrupt("truncated stored block"))?;
+                out.extend_from_slice(block);
+                reader.pos = start + len;
+                if out.len() > max_output {
+                    return Err(CryptoError::OutOfRange(format!("inflated data exceeds {max_output} bytes")));
+                }
+            }
+            1 => {
+                let (lengths, distances) = fixed_tables()?;
+                inflate_block(&mut reader, &mut out, &lengths, &distances, max_output)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic_tables(&mut reader)?;
+                inflate_block(&mut reader, &mut out, &lengths, &distances, max_output)?;
+            }
+            _ => return Err(corrupt("reserved block type")),
+        }
+        if last {
+            return Ok((out, reader.pos));
+        }
+    }
+}
+
+pub fn gunzip_bytes(data: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+    let bad_header = || CryptoError::CorruptHeader("not a gzip stream".to_string());
+    if data.len() < 18 || data[0] != 0x1f || data[1] != 0x8b || data[2] != 8 {
+        return Err(bad_header());
+    }
+    let flags = data[3];
+    let mut pos = 10;
+    if flags & FEXTRA != 0 {
+        let len = data.get(pos..pos + 2).ok_or_else(bad_header)?;
+        pos += 2 + u16::from_le_bytes([len[0], len[1]]) as usize;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            pos += data.get(pos..).and_then(|rest| rest.iter().position(|&b| b == 0)).ok_or_else(bad_header)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        pos += 2;
+    }
+    let body = data.get(pos..).ok_or_else(bad_header)?;
+    let (out, used) = inflate_bytes(body)?;
+    let trailer = body.get(used..used + 8).ok_or_else(|| corrupt("missing gzip trailer"))?;
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if crc != crc32_bytes(&out) || size != out.len() as u32 {
+        return Err(corrupt("gzip checksum mismatch"));
+    }
+    Ok(out)
+}
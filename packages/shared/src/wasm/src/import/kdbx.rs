@@ -0,0 +1,1019 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::aes::{Aes, BLOCK_LEN, aes_256_cbc_decrypt, aes_256_cbc_encrypt};
+use crate::argon2::{Argon2Params, Argon2Variant, Argon2Version, argon2_bytes};
+use crate::calibrate::KdfParams;
+use crate::chacha20::{KEY_LEN, NONCE_LEN, chacha20_block, chacha20_xor};
+use crate::ct::ct_eq;
+use crate::encoding::{base64_to_bytes, bytes_to_base64, bytes_to_hex, hex_to_bytes};
+use crate::error::CryptoError;
+use crate::fips::algorithm_allowed;
+use crate::hmac::hmac_sha256_bytes;
+use crate::import::inflate::gunzip_bytes;
+use crate::import::xml::{Element, escape_xml, parse_xml};
+use crate::import::{
+    CustomField, ImportedAttachment, ImportedItem, ItemType, LoginData, non_empty, parse_items, to_json,
+};
+use crate::limits::check_iterations;
+use crate::rng::random_array;
+use crate::sha2::{sha256_bytes, sha512_bytes};
+
+// Import baz KeePass KDBX4 (KeePass 2.35+, KeePassXC). Uklad pliku:
+//   sygnatury, wersja, pola naglowka (id u8, dlugosc u32 LE, dane) do pola 0,
+//   SHA-256(naglowek), HMAC-SHA256(naglowek), bloki HMAC (mac, dlugosc u32, dane) do bloku 0.
+// Klucz zlozony = SHA-256(SHA-256(haslo)) (bez pliku klucza), przeksztalcony AES-KDF albo
+// Argon2d/id z parametrow naglowka. Klucz szyfru = SHA-256(master seed || klucz),
+// klucze HMAC blokow = SHA-512(indeks u64 || SHA-512(master seed || klucz || 0x01)),
+// naglowek z indeksem u64::MAX. Zly HMAC naglowka = zle haslo (WrongPassword), zly blok =
+// uszkodzony plik (BadMac). Payload: AES-256-CBC albo ChaCha20, opcjonalnie gzip, potem
+// naglowek wewnetrzny (strumien ochrony, zalaczniki) i XML.
+// Eksport pisze ten sam format: AES-256-CBC, Argon2id z parametrow kalibracji (AES-KDF
+// z pbkdf2_iterations rundami, gdy Argon2 jest niedozwolony - profil FIPS), bez kompresji
+// (flaga 0 jest czytana przez KeePass i KeePassXC), strumien ochrony ChaCha20.
+//
+// Chronione wartosci wartosci (hasla wpisow) sa w XML-u zaszyfrowane
+// jednym ciaglym strumieniem klucza ("inner random stream") w kolejnosci wystapienia
+// w dokumencie, wiec stan strumienia przechodzi miedzy kolejnymi wartosciami.
+// KDBX4 (id 3): ChaCha20, SHA-512(klucz strumienia) -> klucz [0..32], nonce [32..44].
+// To sam szyfr strumieniowy bez uwierzytelnienia - integralnosc pliku zapewniaja bloki
+// HMAC KDBX4. Tylko do odczytu cudzego formatu, nie do ochrony naszych danych.
+// KDBX3 (id 2): Salsa20/20, klucz SHA-256(klucz strumienia), staly nonce z KeePass.
+// Salsa20 jest tylko tutaj - poza importem starych baz nie ma zastosowania.
+pub const INNER_STREAM_SALSA20: u32 = 2;
+pub const INNER_STREAM_CHACHA20: u32 = 3;
+const SALSA20_NONCE: [u8; 8] = [0xe8, 0x30, 0x09, 0x4b, 0x97, 0x20, 0x5d, 0x2a];
+
+pub struct ChaCha20Stream {
+    key: [u8; KEY_LEN],
+    nonce: [u8; NONCE_LEN],
+    counter: u32,
+    block: [u8; 64],
+    used: usize,
+}
+
+impl Drop for ChaCha20Stream {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.block.zeroize();
+    }
+}
+
+impl ChaCha20Stream {
+    pub fn new(key: [u8; KEY_LEN], nonce: [u8; NONCE_LEN]) -> ChaCha20Stream {
+        ChaCha20Stream { key, nonce, counter: 0, block: [0; 64], used: 64 }
+    }
+
+    // XOR z kolejnymi bajtami strumienia klucza (szyfrowanie = deszyfrowanie).
+    pub fn apply(&mut self, data: &mut [u8]) -> Result<(), CryptoError> {
+        for b in data.iter_mut() {
+            if self.used == 64 {
+                self.block = chacha20_block(&self.key, self.counter, &self.nonce);
+                self.counter = self
+                    .counter
+                    .checked_add(1)
+                    .ok_or_else(|| CryptoError::OutOfRange("chacha20 keystream exhausted".to_string()))?;
+                self.used = 0;
+            }
+            *b ^= self.block[self.used];
+            self.used += 1;
+        }
+        Ok(())
+    }
+}
+
+fn le32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn salsa20_block(key: &[u8; 32], nonce: &[u8; 8], counter: u64) -> [u8; 64] {
+    // "expand 32-byte k" na przekatnej
+    let mut state = [
+        0x61707865,
+        le32(&key[0..4]),
+        le32(&key[4..8]),
+        le32(&key[8..12]),
+        le32(&key[12..16]),
+        0x3320646e,
+        le32(&nonce[0..4]),
+        le32(&nonce[4..8]),
+        counter as u32,
+        (counter >> 32) as u32,
+        0x79622d32,
+        le32(&key[16..20]),
+        le32(&key[20..24]),
+        le32(&key[24..28]),
+        le32(&key[28..32]),
+        0x6b206574,
+    ];
+    let mut x = state;
+    for _ in 0..10 {
+        // kolumny, potem wiersze
+        for [a, b, c, d] in [[0, 4, 8, 12], [5, 9, 13, 1], [10, 14, 2, 6], [15, 3, 7, 11]] {
+            x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+            x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+            x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+            x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+        }
+        for [a, b, c, d] in [[0, 1, 2, 3], [5, 6, 7, 4], [10, 11, 8, 9], [15, 12, 13, 14]] {
+            x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+            x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+            x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+            x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+        }
+    }
+    let mut out = [0u8; 64];
+    for (i, (w, s)) in x.iter().zip(&state).enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&w.wrapping_add(*s).to_le_bytes());
+    }
+    x.zeroize();
+    state.zeroize();
+    out
+}
+
+pub struct Salsa20Stream {
+    key: [u8; 32],
+    nonce: [u8; 8],
+    counter: u64,
+    block: [u8; 64],
+    used: usize,
+}
+
+impl Drop for Salsa20Stream {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.block.zeroize();
+    }
+}
+
+impl Salsa20Stream {
+    pub fn new(key: [u8; 32], nonce: [u8; 8]) -> Salsa20Stream {
+        Salsa20Stream { key, nonce, counter: 0, block: [0; 64], used: 64 }
+    }
+
+    pub fn apply(&mut self, data: &mut [u8]) {
+        for b in data.iter_mut() {
+            if self.used == 64 {
+                self.block = salsa20_block(&self.key, &self.nonce, self.counter);
+                self.counter = self.counter.wrapping_add(1);
+                self.used = 0;
+            }
+            *b ^= self.block[self.used];
+            self.used += 1;
+        }
+    }
+}
+
+enum InnerStream {
+    Salsa20(Salsa20Stream),
+    ChaCha20(ChaCha20Stream),
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct KdbxInnerStream {
+    stream: InnerStream,
+}
+
+impl KdbxInnerStream {
+    pub fn from_key(stream_id: u32, stream_key: &[u8]) -> Result<KdbxInnerStream, CryptoError> {
+        match stream_id {
+            INNER_STREAM_SALSA20 => {
+                let key = sha256_bytes(stream_key);
+                Ok(KdbxInnerStream { stream: InnerStream::Salsa20(Salsa20Stream::new(key, SALSA20_NONCE)) })
+            }
+            INNER_STREAM_CHACHA20 => {
+                let mut hash = sha512_bytes(stream_key);
+                let mut key = [0u8; KEY_LEN];
+                let mut nonce = [0u8; NONCE_LEN];
+                key.copy_from_slice(&hash[..KEY_LEN]);
+                nonce.copy_from_slice(&hash[KEY_LEN..KEY_LEN + NONCE_LEN]);
+                hash.zeroize();
+                Ok(KdbxInnerStream { stream: InnerStream::ChaCha20(ChaCha20Stream::new(key, nonce)) })
+            }
+            _ => Err(CryptoError::UnsupportedAlgorithm(format!("kdbx inner stream id {stream_id}"))),
+        }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl KdbxInnerStream {
+    // stream_id i stream_key z naglowka wewnetrznego KDBX4 (pola 0x01 i 0x02) albo
+    // z naglowka KDBX3 (InnerRandomStreamID, ProtectedStreamKey).
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(stream_id: u32, stream_key: &[u8]) -> Result<KdbxInnerStream, CryptoError> {
+        KdbxInnerStream::from_key(stream_id, stream_key)
+    }
+
+    // Wartosc z atrybutem Protected="True" po zdekodowaniu base64; wywolywac po kolei
+    // dla wszystkich chronionych wartosci w dokumencie.
+    pub fn unprotect(&mut self, value: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let mut out = value.to_vec();
+        match &mut self.stream {
+            InnerStream::Salsa20(stream) => stream.apply(&mut out),
+            InnerStream::ChaCha20(stream) => stream.apply(&mut out)?,
+        }
+        Ok(out)
+    }
+}
+
+const SIGNATURE_1: u32 = 0x9aa2_d903;
+const SIGNATURE_2: u32 = 0xb54b_fb67;
+const VERSION_MAJOR: u16 = 4;
+
+const CIPHER_AES256: [u8; 16] =
+    [0x31, 0xc1, 0xf2, 0xe6, 0xbf, 0x71, 0x43, 0x50, 0xbe, 0x58, 0x05, 0x21, 0x6a, 0xfc, 0x5a, 0xff];
+const CIPHER_CHACHA20: [u8; 16] =
+    [0xd6, 0x03, 0x8a, 0x2b, 0x8b, 0x6f, 0x4c, 0xb5, 0xa5, 0x24, 0x33, 0x9a, 0x31, 0xdb, 0xb5, 0x9a];
+const KDF_AES: [u8; 16] =
+    [0xc9, 0xd9, 0xf3, 0x9a, 0x62, 0x8a, 0x44, 0x60, 0xbf, 0x74, 0x0d, 0x08, 0xc1, 0x8a, 0x4f, 0xea];
+const KDF_ARGON2D: [u8; 16] =
+    [0xef, 0x63, 0x6d, 0xdf, 0x8c, 0x29, 0x44, 0x4b, 0x91, 0xf7, 0xa9, 0xa4, 0x03, 0xe3, 0x0a, 0x0c];
+const KDF_ARGON2ID: [u8; 16] =
+    [0x9e, 0x29, 0x8b, 0x19, 0x56, 0xdb, 0x47, 0x73, 0xb2, 0x3d, 0xfc, 0x3e, 0xc6, 0xf0, 0xa1, 0xe6];
+
+const FIELD_END: u8 = 0;
+const FIELD_CIPHER_ID: u8 = 2;
+const FIELD_COMPRESSION: u8 = 3;
+const FIELD_MASTER_SEED: u8 = 4;
+const FIELD_ENCRYPTION_IV: u8 = 7;
+const FIELD_KDF_PARAMETERS: u8 = 11;
+
+const INNER_FIELD_END: u8 = 0;
+const INNER_FIELD_STREAM_ID: u8 = 1;
+const INNER_FIELD_STREAM_KEY: u8 = 2;
+const INNER_FIELD_BINARY: u8 = 3;
+
+const EXPORT_VERSION_MINOR: u16 = 1;
+const EXPORT_BLOCK_LEN: usize = 1024 * 1024;
+const EXPORT_SALT_LEN: usize = 32;
+const EXPORT_STREAM_KEY_LEN: usize = 64;
+
+const VARIANT_UINT32: u8 = 0x04;
+const VARIANT_UINT64: u8 = 0x05;
+const VARIANT_BOOL: u8 = 0x08;
+const VARIANT_INT32: u8 = 0x0c;
+const VARIANT_INT64: u8 = 0x0d;
+const VARIANT_STRING: u8 = 0x18;
+const VARIANT_BYTES: u8 = 0x42;
+
+fn truncated() -> CryptoError {
+    CryptoError::CorruptHeader("kdbx file is truncated".to_string())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CryptoError> {
+        let out = self.data.get(self.pos..self.pos.checked_add(n).ok_or_else(truncated)?).ok_or_else(truncated)?;
+        self.pos += n;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, CryptoError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, CryptoError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, CryptoError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn field(&mut self) -> Result<(u8, &'a [u8]), CryptoError> {
+        let id = self.u8()?;
+        let len = self.u32()? as usize;
+        Ok((id, self.take(len)?))
+    }
+}
+
+// Wartosc ze slownika VariantDictionary (parametry KDF).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Variant {
+    UInt32(u32),
+    UInt64(u64),
+    Bool(bool),
+    Int32(i32),
+    Int64(i64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+pub fn parse_variant_dictionary(data: &[u8]) -> Result<BTreeMap<String, Variant>, CryptoError> {
+    let bad = |what: &str| CryptoError::CorruptHeader(format!("kdbx variant dictionary: {what}"));
+    let mut reader = Reader { data, pos: 0 };
+    if reader.u16()? >> 8 != 1 {
+        return Err(CryptoError::UnsupportedVersion("kdbx variant dictionary version".to_string()));
+    }
+    let mut out = BTreeMap::new();
+    loop {
+        let kind = reader.u8()?;
+        if kind == 0 {
+            return Ok(out);
+        }
+        let key_len = reader.u32()? as usize;
+        let key = String::from_utf8(reader.take(key_len)?.to_vec()).map_err(|_| bad("key is not UTF-8"))?;
+        let value_len = reader.u32()? as usize;
+        let value = reader.take(value_len)?;
+        let fixed = |n: usize| if value.len() == n { Ok(value) } else { Err(bad("wrong value length")) };
+        let variant = match kind {
+            VARIANT_UINT32 => Variant::UInt32(u32::from_le_bytes(fixed(4)?.try_into().map_err(|_| truncated())?)),
+            VARIANT_UINT64 => Variant::UInt64(u64::from_le_bytes(fixed(8)?.try_into().map_err(|_| truncated())?)),
+            VARIANT_BOOL => Variant::Bool(fixed(1)?[0] != 0),
+            VARIANT_INT32 => Variant::Int32(i32::from_le_bytes(fixed(4)?.try_into().map_err(|_| truncated())?)),
+            VARIANT_INT64 => Variant::Int64(i64::from_le_bytes(fixed(8)?.try_into().map_err(|_| truncated())?)),
+            VARIANT_STRING => {
+                Variant::String(String::from_utf8(value.to_vec()).map_err(|_| bad("string is not UTF-8"))?)
+            }
+            VARIANT_BYTES => Variant::Bytes(value.to_vec()),
+            other => return Err(bad(&format!("unknown value type {other:#04x}"))),
+        };
+        out.insert(key, variant);
+    }
+}
+
+pub fn write_variant_dictionary(entries: &[(&str, Variant)]) -> Vec<u8> {
+    let mut out = 0x0100u16.to_le_bytes().to_vec();
+    for (key, value) in entries {
+        let (kind, bytes) = match value {
+            Variant::UInt32(v) => (VARIANT_UINT32, v.to_le_bytes().to_vec()),
+            Variant::UInt64(v) => (VARIANT_UINT64, v.to_le_bytes().to_vec()),
+            Variant::Bool(v) => (VARIANT_BOOL, vec![*v as u8]),
+            Variant::Int32(v) => (VARIANT_INT32, v.to_le_bytes().to_vec()),
+            Variant::Int64(v) => (VARIANT_INT64, v.to_le_bytes().to_vec()),
+            Variant::String(v) => (VARIANT_STRING, v.as_bytes().to_vec()),
+            Variant::Bytes(v) => (VARIANT_BYTES, v.clone()),
+        };
+        out.push(kind);
+        out.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        out.extend_from_slice(key.as_bytes());
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+    }
+    out.push(0);
+    out
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KdbxKdf {
+    Aes { rounds: u64, seed: [u8; 32] },
+    Argon2 { params: Argon2Params, salt: Vec<u8> },
+}
+
+impl KdbxKdf {
+    fn from_dictionary(dict: &BTreeMap<String, Variant>) -> Result<KdbxKdf, CryptoError> {
+        let missing = |key: &str| CryptoError::CorruptHeader(format!("kdbx kdf parameter {key} is missing"));
+        let bytes = |key: &str| match dict.get(key) {
+            Some(Variant::Bytes(b)) => Ok(b.as_slice()),
+            _ => Err(missing(key)),
+        };
+        let uint = |key: &str| match dict.get(key) {
+            Some(Variant::UInt32(v)) => Ok(*v as u64),
+            Some(Variant::UInt64(v)) => Ok(*v),
+            _ => Err(missing(key)),
+        };
+        let to_u32 = |key: &str, v: u64| {
+            u32::try_from(v).map_err(|_| CryptoError::OutOfRange(format!("kdbx kdf parameter {key} is too large")))
+        };
+        let uuid = bytes("$UUID")?;
+        if uuid == KDF_AES {
+            let seed =
+                bytes("S")?.try_into().map_err(|_| CryptoError::CorruptHeader("kdbx aes-kdf seed".to_string()))?;
+            return Ok(KdbxKdf::Aes { rounds: uint("R")?, seed });
+        }
+        let variant = if uuid == KDF_ARGON2D {
+            Argon2Variant::Argon2d
+        } else if uuid == KDF_ARGON2ID {
+            Argon2Variant::Argon2id
+        } else {
+            return Err(CryptoError::UnsupportedAlgorithm(format!("kdbx kdf {}", bytes_to_hex(uuid))));
+        };
+        // Pamiec w naglowku jest w bajtach
+        let params = Argon2Params {
+            variant,
+            version: Argon2Version::from_number(to_u32("V", uint("V")?)?)?,
+            memory_kib: to_u32("M", uint("M")? / 1024)?,
+            iterations: to_u32("I", uint("I")?)?,
+            parallelism: to_u32("P", uint("P")?)?,
+        };
+        Ok(KdbxKdf::Argon2 { params, salt: bytes("S")?.to_vec() })
+    }
+
+    pub fn from_calibrated(params: &KdfParams) -> Result<KdbxKdf, CryptoError> {
+        if algorithm_allowed("argon2id") {
+            let params = Argon2Params {
+                variant: Argon2Variant::Argon2id,
+                version: Argon2Version::V0x13,
+                memory_kib: params.argon2_m_cost,
+                iterations: params.argon2_t_cost,
+                parallelism: params.argon2_parallelism,
+            };
+            Ok(KdbxKdf::Argon2 { params, salt: random_array::<EXPORT_SALT_LEN>()?.to_vec() })
+        } else {
+            Ok(KdbxKdf::Aes { rounds: params.pbkdf2_iterations as u64, seed: random_array()? })
+        }
+    }
+
+    fn to_dictionary(&self) -> Vec<u8> {
+        match self {
+            KdbxKdf::Aes { rounds, seed } => write_variant_dictionary(&[
+                ("$UUID", Variant::Bytes(KDF_AES.to_vec())),
+                ("R", Variant::UInt64(*rounds)),
+                ("S", Variant::Bytes(seed.to_vec())),
+            ]),
+            KdbxKdf::Argon2 { params, salt } => {
+                let uuid = if params.variant == Argon2Variant::Argon2d { KDF_ARGON2D } else { KDF_ARGON2ID };
+                write_variant_dictionary(&[
+                    ("$UUID", Variant::Bytes(uuid.to_vec())),
+                    ("S", Variant::Bytes(salt.clone())),
+                    ("P", Variant::UInt32(params.parallelism)),
+                    ("M", Variant::UInt64(params.memory_kib as u64 * 1024)),
+                    ("I", Variant::UInt64(params.iterations as u64)),
+                    ("V", Variant::UInt32(params.version as u32)),
+                ])
+            }
+        }
+    }
+
+    // Klucz przeksztalcony z klucza zlozonego.
+    fn transform(&self, composite: &[u8; 32]) -> Result<Zeroizing<[u8; 32]>, CryptoError> {
+        match self {
+            KdbxKdf::Aes { rounds, seed } => {
+                let rounds = u32::try_from(*rounds).map_err(|_| {
+                    CryptoError::InvalidIterations(format!("kdbx aes-kdf rounds {rounds} exceed limit"))
+                })?;
+                check_iterations(rounds)?;
+                let aes = Aes::new(seed)?;
+                let mut key = Zeroizing::new(*composite);
+                for half in key.chunks_exact_mut(16) {
+                    let mut block = [0u8; 16];
+                    block.copy_from_slice(half);
+                    for _ in 0..rounds {
+                        aes.encrypt_block(&mut block);
+                    }
+                    half.copy_from_slice(&block);
+                    block.zeroize();
+                }
+                Ok(Zeroizing::new(sha256_bytes(key.as_ref())))
+            }
+            KdbxKdf::Argon2 { params, salt } => {
+                let out = Zeroizing::new(argon2_bytes(params, composite, salt, &[], &[], 32)?);
+                let mut key = Zeroizing::new([0u8; 32]);
+                key.copy_from_slice(&out);
+                Ok(key)
+            }
+        }
+    }
+}
+
+struct OuterHeader {
+    cipher: [u8; 16],
+    compressed: bool,
+    master_seed: [u8; 32],
+    iv: Vec<u8>,
+    kdf: KdbxKdf,
+}
+
+fn parse_outer_header(reader: &mut Reader) -> Result<OuterHeader, CryptoError> {
+    if reader.u32()? != SIGNATURE_1 || reader.u32()? != SIGNATURE_2 {
+        return Err(CryptoError::CorruptHeader("not a KeePass database".to_string()));
+    }
+    let _minor = reader.u16()?;
+    let major = reader.u16()?;
+    if major != VERSION_MAJOR {
+        return Err(CryptoError::UnsupportedVersion(format!("kdbx {major}.x (only KDBX4 is supported)")));
+    }
+    let (mut cipher, mut compressed, mut master_seed, mut iv, mut kdf) = (None, false, None, None, None);
+    loop {
+        let (id, data) = reader.field()?;
+        match id {
+            FIELD_END => break,
+            FIELD_CIPHER_ID => cipher = data.try_into().ok(),
+            FIELD_COMPRESSION => compressed = data.first().is_some_and(|&b| b != 0),
+            FIELD_MASTER_SEED => master_seed = data.try_into().ok(),
+            FIELD_ENCRYPTION_IV => iv = Some(data.to_vec()),
+            FIELD_KDF_PARAMETERS => kdf = Some(KdbxKdf::from_dictionary(&parse_variant_dictionary(data)?)?),
+            _ => {}
+        }
+    }
+    let missing = |what: &str| CryptoError::CorruptHeader(format!("kdbx header has no valid {what}"));
+    Ok(OuterHeader {
+        cipher: cipher.ok_or_else(|| missing("cipher id"))?,
+        compressed,
+        master_seed: master_seed.ok_or_else(|| missing("master seed"))?,
+        iv: iv.ok_or_else(|| missing("encryption iv"))?,
+        kdf: kdf.ok_or_else(|| missing("kdf parameters"))?,
+    })
+}
+
+fn composite_key(password: &[u8]) -> Zeroizing<[u8; 32]> {
+    let mut inner = sha256_bytes(password);
+    let composite = Zeroizing::new(sha256_bytes(&inner));
+    inner.zeroize();
+    composite
+}
+
+// Klucz szyfru i baza kluczy HMAC blokow.
+struct DatabaseKeys {
+    cipher_key: Zeroizing<[u8; 32]>,
+    hmac_base: Zeroizing<[u8; 64]>,
+}
+
+fn derive_keys(kdf: &KdbxKdf, master_seed: &[u8; 32], password: &[u8]) -> Result<DatabaseKeys, CryptoError> {
+    let transformed = kdf.transform(&composite_key(password))?;
+    let seeded = Zeroizing::new([&master_seed[..], transformed.as_ref()].concat());
+    let cipher_key = Zeroizing::new(sha256_bytes(&seeded));
+    let hmac_base = Zeroizing::new(sha512_bytes(&[&seeded[..], &[1u8]].concat()));
+    Ok(DatabaseKeys { cipher_key, hmac_base })
+}
+
+fn block_hmac_key(hmac_base: &[u8; 64], index: u64) -> Zeroizing<[u8; 64]> {
+    Zeroizing::new(sha512_bytes(&[&index.to_le_bytes()[..], hmac_base].concat()))
+}
+
+fn block_hmac(hmac_base: &[u8; 64], index: u64, data: &[u8]) -> [u8; 32] {
+    let key = block_hmac_key(hmac_base, index);
+    let len = (data.len() as u32).to_le_bytes();
+    hmac_sha256_bytes(key.as_ref(), &[&index.to_le_bytes()[..], &len, data].concat())
+}
+
+// Zawartosc zaszyfrowanej bazy po weryfikacji i odszyfrowaniu.
+pub struct KdbxPayload {
+    pub xml: Zeroizing<Vec<u8>>,
+    pub inner_stream: KdbxInnerStream,
+    pub binaries: Vec<Zeroizing<Vec<u8>>>,
+}
+
+pub fn open_kdbx_bytes(data: &[u8], password: &[u8]) -> Result<KdbxPayload, CryptoError> {
+    let mut reader = Reader { data, pos: 0 };
+    let header = parse_outer_header(&mut reader)?;
+    let header_bytes = &data[..reader.pos];
+    if !ct_eq(&sha256_bytes(header_bytes), reader.take(32)?) {
+        return Err(CryptoError::CorruptHeader("kdbx header checksum mismatch".to_string()));
+    }
+
+    let DatabaseKeys { cipher_key, hmac_base } = derive_keys(&header.kdf, &header.master_seed, password)?;
+    let header_key = block_hmac_key(&hmac_base, u64::MAX);
+    if !ct_eq(&hmac_sha256_bytes(header_key.as_ref(), header_bytes), reader.take(32)?) {
+        return Err(CryptoError::WrongPassword("wrong master password or modified kdbx header".to_string()));
+    }
+
+    let mut ciphertext = Zeroizing::new(Vec::new());
+    for index in 0u64.. {
+        let mac = reader.take(32)?;
+        let len = reader.u32()? as usize;
+        let block = reader.take(len)?;
+        if !ct_eq(&block_hmac(&hmac_base, index, block), mac) {
+            return Err(CryptoError::BadMac(format!("kdbx block {index} is corrupted")));
+        }
+        if block.is_empty() {
+            break;
+        }
+        ciphertext.extend_from_slice(block);
+    }
+
+    let mut plaintext = if header.cipher == CIPHER_AES256 {
+        aes_256_cbc_decrypt(cipher_key.as_ref(), &header.iv, &ciphertext)?
+    } else if header.cipher == CIPHER_CHACHA20 {
+        let nonce: &[u8; NONCE_LEN] = header
+            .iv
+            .as_slice()
+            .try_into()
+            .map_err(|_| CryptoError::CorruptHeader("kdbx chacha20 iv must be 12 bytes".to_string()))?;
+        let mut out = Zeroizing::new(std::mem::take(&mut *ciphertext));
+        chacha20_xor(&cipher_key, 0, nonce, &mut out)?;
+        out
+    } else {
+        return Err(CryptoError::UnsupportedAlgorithm(format!("kdbx cipher {}", bytes_to_hex(&header.cipher))));
+    };
+    if header.compressed {
+        plaintext = gunzip_bytes(&plaintext)?;
+    }
+
+    let mut inner = Reader { data: &plaintext, pos: 0 };
+    let (mut stream_id, mut stream_key, mut binaries) = (None, None, Vec::new());
+    loop {
+        let (id, field) = inner.field()?;
+        match id {
+            INNER_FIELD_END => break,
+            INNER_FIELD_STREAM_ID => {
+                stream_id = field.try_into().ok().map(u32::from_le_bytes);
+            }
+            INNER_FIELD_STREAM_KEY => stream_key = Some(Zeroizing::new(field.to_vec())),
+            // Pierwszy bajt to flagi (0x01 - chroniony w pamieci), reszta to zawartosc
+            INNER_FIELD_BINARY => binaries.push(Zeroizing::new(field.get(1..).unwrap_or_default().to_vec())),
+            _ => {}
+        }
+    }
+    let missing = || CryptoError::CorruptHeader("kdbx inner header has no protected stream".to_string());
+    let inner_stream = KdbxInnerStream::from_key(stream_id.ok_or_else(missing)?, &stream_key.ok_or_else(missing)?)?;
+    let xml = Zeroizing::new(plaintext[inner.pos..].to_vec());
+    Ok(KdbxPayload { xml, inner_stream, binaries })
+}
+
+// Odszyfrowuje wartosci Protected="True" w kolejnosci dokumentu (przejscie pre-order).
+fn unprotect_values(element: &mut Element, stream: &mut KdbxInnerStream) -> Result<(), CryptoError> {
+    if element.attr("Protected").is_some_and(|v| v.eq_ignore_ascii_case("true")) {
+        let sealed = base64_to_bytes(element.text.trim())?;
+        let plain = Zeroizing::new(stream.unprotect(&sealed)?);
+        let text = String::from_utf8(plain.to_vec())
+            .map_err(|_| CryptoError::InvalidInput("kdbx protected value is not UTF-8".to_string()))?;
+        element.text.zeroize();
+        element.text = text;
+    }
+    for child in &mut element.children {
+        unprotect_values(child, stream)?;
+    }
+    Ok(())
+}
+
+fn uuid_hex(element: &Element) -> Result<String, CryptoError> {
+    Ok(bytes_to_hex(&base64_to_bytes(element.child_text("UUID").trim())?))
+}
+
+fn entry_to_item(
+    entry: &Element,
+    folder: Option<String>,
+    binaries: &[Zeroizing<Vec<u8>>],
+) -> Result<ImportedItem, CryptoError> {
+    let mut item = ImportedItem { id: uuid_hex(entry)?, folder, ..ImportedItem::default() };
+    let mut login = LoginData::default();
+    for string in entry.children_named("String") {
+        let key = string.child_text("Key");
+        let value = string.child("Value");
+        let text = value.map_or("", |v| v.text.as_str());
+        match key {
+            "Title" => item.name = text.to_string(),
+            "UserName" => login.username = non_empty(text),
+            "Password" => login.password = non_empty(text),
+            "URL" => login.uris.extend(non_empty(text)),
+            // Dodatkowe adresy (KeePass2Android, KeePassXC, nasz eksport)
+            _ if key.starts_with("KP2A_URL") => login.uris.extend(non_empty(text)),
+            "Notes" => item.notes = non_empty(text),
+            "otp" => login.totp = non_empty(text),
+            _ => item.fields.push(CustomField {
+                name: key.to_string(),
+                value: text.to_string(),
+                hidden: value
+                    .and_then(|v| v.attr("ProtectInMemory").or(v.attr("Protected")))
+                    .is_some_and(|p| p.eq_ignore_ascii_case("true")),
+            }),
+        }
+    }
+    for binary in entry.children_named("Binary") {
+        let reference = binary.child("Value").and_then(|v| v.attr("Ref")).and_then(|r| r.parse::<usize>().ok());
+        let data = reference
+            .and_then(|r| binaries.get(r))
+            .ok_or_else(|| CryptoError::InvalidInput("kdbx entry references a missing attachment".to_string()))?;
+        item.attachments.push(ImportedAttachment {
+            name: binary.child_text("Key").to_string(),
+            size: data.len(),
+            data: bytes_to_base64(data),
+        });
+    }
+    if login == LoginData::default() {
+        item.kind = ItemType::SecureNote;
+    } else {
+        item.login = Some(login);
+    }
+    Ok(item)
+}
+
+fn collect_group(
+    group: &Element,
+    path: Option<String>,
+    recycle_bin: Option<&str>,
+    binaries: &[Zeroizing<Vec<u8>>],
+    items: &mut Vec<ImportedItem>,
+) -> Result<(), CryptoError> {
+    for entry in group.children_named("Entry") {
+        items.push(entry_to_item(entry, path.clone(), binaries)?);
+    }
+    for child in group.children_named("Group") {
+        if recycle_bin.is_some_and(|bin| child.child_text("UUID").trim() == bin) {
+            continue;
+        }
+        let name = child.child_text("Name");
+        let child_path = match &path {
+            Some(parent) => format!("{parent}/{name}"),
+            None => name.to_string(),
+        };
+        collect_group(child, Some(child_path), recycle_bin, binaries, items)?;
+    }
+    Ok(())
+}
+
+// Wpisy z bazy, bez historii i kosza. Grupa glowna (nazwa bazy) nie trafia do sciezki folderu.
+pub fn import_kdbx_bytes(data: &[u8], password: &[u8]) -> Result<Vec<ImportedItem>, CryptoError> {
+    let mut payload = open_kdbx_bytes(data, password)?;
+    let text = std::str::from_utf8(&payload.xml)
+        .map_err(|_| CryptoError::InvalidInput("kdbx xml is not UTF-8".to_string()))?;
+    let mut document = parse_xml(text)?;
+    unprotect_values(&mut document, &mut payload.inner_stream)?;
+
+    let meta = document.child("Meta");
+    let recycle_bin = meta
+        .filter(|m| !m.child_text("RecycleBinEnabled").eq_ignore_ascii_case("false"))
+        .map(|m| m.child_text("RecycleBinUUID").trim())
+        .filter(|uuid| !uuid.is_empty());
+    let root_group = document
+        .child("Root")
+        .and_then(|r| r.child("Group"))
+        .ok_or_else(|| CryptoError::InvalidInput("kdbx xml has no root group".to_string()))?;
+    let mut items = Vec::new();
+    collect_group(root_group, None, recycle_bin, &payload.binaries, &mut items)?;
+    Ok(items)
+}
+
+fn write_field(out: &mut Vec<u8>, id: u8, data: &[u8]) {
+    out.push(id);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+}
+
+#[derive(Default)]
+struct GroupNode<'a> {
+    items: Vec<&'a ImportedItem>,
+    children: BTreeMap<String, GroupNode<'a>>,
+}
+
+struct XmlWriter {
+    xml: Zeroizing<String>,
+    stream: KdbxInnerStream,
+    binaries: Vec<Zeroizing<Vec<u8>>>,
+}
+
+impl XmlWriter {
+    fn uuid(&mut self, id: Option<&str>) -> Result<(), CryptoError> {
+        // Identyfikator 16-bajtowy w hex (np. z importu KDBX) zostaje, inne dostaja nowy UUID
+        let uuid = match id.map(hex_to_bytes) {
+            Some(Ok(bytes)) if bytes.len() == 16 => bytes,
+            _ => random_array::<16>()?.to_vec(),
+        };
+        self.xml.push_str(&format!("<UUID>{}</UUID>", bytes_to_base64(&uuid)));
+        Ok(())
+    }
+
+    // Wartosci chronione sa XOR-owane strumieniem w kolejnosci zapisu - tak samo czyta je import.
+    fn string(&mut self, key: &str, value: &str, protected: bool) -> Result<(), CryptoError> {
+        self.xml.push_str(&format!("<String><Key>{}</Key>", escape_xml(key)));
+        if protected {
+            let sealed = self.stream.unprotect(value.as_bytes())?;
+            self.xml.push_str(&format!("<Value Protected=\"True\">{}</Value></String>", bytes_to_base64(&sealed)));
+        } else {
+            self.xml.push_str(&format!("<Value>{}</Value></String>", escape_xml(value)));
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, item: &ImportedItem) -> Result<(), CryptoError> {
+        self.xml.push_str("<Entry>");
+        self.uuid(Some(&item.id))?;
+        self.string("Title", &item.name, false)?;
+        let login = item.login.clone().unwrap_or_default();
+        self.string("UserName", login.username.as_deref().unwrap_or_default(), false)?;
+        self.string("Password", login.password.as_deref().unwrap_or_default(), true)?;
+        self.string("URL", login.uris.first().map_or("", String::as_str), false)?;
+        self.string("Notes", item.notes.as_deref().unwrap_or_default(), false)?;
+        if let Some(totp) = &login.totp {
+            self.string("otp", totp, true)?;
+        }
+        // Dodatkowe adresy w konwencji KeePass2Android/KeePassXC
+        for (i, uri) in login.uris.iter().enumerate().skip(1) {
+            self.string(&format!("KP2A_URL_{i}"), uri, false)?;
+        }
+        for field in item.detail_fields().iter().chain(&item.fields) {
+            self.string(&field.name, &field.value, field.hidden)?;
+        }
+        for attachment in &item.attachments {
+            self.xml.push_str(&format!(
+                "<Binary><Key>{}</Key><Value Ref=\"{}\"/></Binary>",
+                escape_xml(&attachment.name),
+                self.binaries.len()
+            ));
+            self.binaries.push(Zeroizing::new(base64_to_bytes(&attachment.data)?));
+        }
+        self.xml.push_str("</Entry>");
+        Ok(())
+    }
+
+    fn group(&mut self, name: &str, node: &GroupNode) -> Result<(), CryptoError> {
+        self.xml.push_str("<Group>");
+        self.uuid(None)?;
+        self.xml.push_str(&format!("<Name>{}</Name>", escape_xml(name)));
+        for item in &node.items {
+            self.entry(item)?;
+        }
+        for (child_name, child) in &node.children {
+            self.group(child_name, child)?;
+        }
+        self.xml.push_str("</Group>");
+        Ok(())
+    }
+}
+
+pub fn export_kdbx_bytes(items: &[ImportedItem], password: &[u8], kdf: &KdbxKdf) -> Result<Vec<u8>, CryptoError> {
+    // Foldery "a/b" -> zagniezdzone grupy pod grupa glowna
+    let mut root = GroupNode::default();
+    for item in items {
+        let mut node = &mut root;
+        for part in item.folder.iter().flat_map(|f| f.split('/')).filter(|p| !p.is_empty()) {
+            node = node.children.entry(part.to_string()).or_default();
+        }
+        node.items.push(item);
+    }
+
+    let stream_key = Zeroizing::new(random_array::<EXPORT_STREAM_KEY_LEN>()?);
+    let mut writer = XmlWriter {
+        xml: Zeroizing::new(String::new()),
+        stream: KdbxInnerStream::from_key(INNER_STREAM_CHACHA20, stream_key.as_ref())?,
+        binaries: Vec::new(),
+    };
+    writer.xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>\n<KeePassFile><Meta>");
+    writer.xml.push_str("<Generator>Password Manager</Generator><DatabaseName>Export</DatabaseName>");
+    writer.xml.push_str("<RecycleBinEnabled>False</RecycleBinEnabled></Meta><Root>");
+    writer.group("Export", &root)?;
+    writer.xml.push_str("</Root></KeePassFile>");
+
+    let mut payload = Zeroizing::new(Vec::new());
+    write_field(&mut payload, INNER_FIELD_STREAM_ID, &INNER_STREAM_CHACHA20.to_le_bytes());
+    write_field(&mut payload, INNER_FIELD_STREAM_KEY, stream_key.as_ref());
+    for binary in &writer.binaries {
+        write_field(&mut payload, INNER_FIELD_BINARY, &[&[0u8][..], binary].concat());
+    }
+    write_field(&mut payload, INNER_FIELD_END, &[]);
+    payload.extend_from_slice(writer.xml.as_bytes());
+
+    let master_seed = random_array::<32>()?;
+    let iv = random_array::<BLOCK_LEN>()?;
+    let mut header = Vec::new();
+    header.extend_from_slice(&SIGNATURE_1.to_le_bytes());
+    header.extend_from_slice(&SIGNATURE_2.to_le_bytes());
+    header.extend_from_slice(&EXPORT_VERSION_MINOR.to_le_bytes());
+    header.extend_from_slice(&VERSION_MAJOR.to_le_bytes());
+    write_field(&mut header, FIELD_CIPHER_ID, &CIPHER_AES256);
+    write_field(&mut header, FIELD_COMPRESSION, &0u32.to_le_bytes());
+    write_field(&mut header, FIELD_MASTER_SEED, &master_seed);
+    write_field(&mut header, FIELD_ENCRYPTION_IV, &iv);
+    write_field(&mut header, FIELD_KDF_PARAMETERS, &kdf.to_dictionary());
+    write_field(&mut header, FIELD_END, b"\r\n\r\n");
+
+    let DatabaseKeys { cipher_key, hmac_base } = derive_keys(kdf, &master_seed, password)?;
+    let ciphertext = aes_256_cbc_encrypt(cipher_key.as_ref(), &iv, &payload)?;
+    let header_key = block_hmac_key(&hmac_base, u64::MAX);
+    let mut out = header.clone();
+    out.extend_from_slice(&sha256_bytes(&header));
+    out.extend_from_slice(&hmac_sha256_bytes(header_key.as_ref(), &header));
+    for (index, block) in ciphertext.chunks(EXPORT_BLOCK_LEN).chain([&[][..]]).enumerate() {
+        out.extend_from_slice(&block_hmac(&hmac_base, index as u64, block));
+        out.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    Ok(out)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_kdbx(data: &[u8], password: &str) -> Result<String, CryptoError> {
+    import_kdbx_raw(data, password.as_bytes())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_kdbx_raw(data: &[u8], password: &[u8]) -> Result<String, CryptoError> {
+    to_json(import_kdbx_bytes(data, password)?)
+}
+
+// items - JSON w modelu importu ({"items": [...]} albo tablica), jak zwraca import_kdbx.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn export_kdbx(items: &str, password: &str, kdf_params: &KdfParams) -> Result<Vec<u8>, CryptoError> {
+    export_kdbx_raw(items, password.as_bytes(), kdf_params)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn export_kdbx_raw(items: &str, password: &[u8], kdf_params: &KdfParams) -> Result<Vec<u8>, CryptoError> {
+    let items = parse_items(items)?;
+    export_kdbx_bytes(&items, password, &KdbxKdf::from_calibrated(kdf_params)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+    use crate::import::{CardData, CustomField, ImportedAttachment, ItemType, LoginData};
+
+    const STREAM_KEY: [u8; 64] = [1u8; 64];
+    const VALUES: &[u8] = b"correct horse battery staple, part one and part two of the protected values";
+
+    // Szyfrogramy z niezaleznej implementacji (ChaCha20 z pakietu cryptography, Salsa20 wg
+    // specyfikacji) dla klucza strumienia 01 * 64 - tak jak liczy je KeePass.
+    const CHACHA20: &str = "1abe6ae155742a2acbe04c39808d537374855cfa72c6ecf741c6557986e4b065798c9b5d37afa56834ca14d5bca34b72\
+                            d33ef7b019c1f2ff45d0ade3b077d2d3331ceafdc65dc6ffae488e";
+    const SALSA20: &str = "2b231d6c1d8bfc93a5abd5c16ecb1b4b84c2c5d685a0cb93a0211536b5d578c59e0759dcfa900e6afd84dd19d2ca7830\
+                           67b8fe0577dac51ad94ea4fafb24186c2d7d8424a12844ae4798b6";
+
+    #[test]
+    fn inner_streams_match_keepass() -> Result<(), CryptoError> {
+        for (id, expected) in [(INNER_STREAM_CHACHA20, CHACHA20), (INNER_STREAM_SALSA20, SALSA20)] {
+            let protected = hex_to_bytes(expected)?;
+            // Jeden strumien na dokument: kolejne wartosci kontynuuja keystream
+            let mut stream = crate::KdbxInnerStream::new(id, &STREAM_KEY)?;
+            let mut plain = stream.unprotect(&protected[..30])?;
+            plain.extend(stream.unprotect(&protected[30..])?);
+            assert_eq!(plain, VALUES);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_inner_stream_is_rejected() {
+        assert!(matches!(KdbxInnerStream::new(1, &STREAM_KEY), Err(CryptoError::UnsupportedAlgorithm(_))));
+        assert!(matches!(KdbxInnerStream::new(4, &STREAM_KEY), Err(CryptoError::UnsupportedAlgorithm(_))));
+    }
+
+    fn cheap_kdf() -> Result<KdbxKdf, CryptoError> {
+        KdbxKdf::from_calibrated(&KdfParams {
+            pbkdf2_iterations: 1000,
+            argon2_m_cost: 64,
+            argon2_t_cost: 1,
+            argon2_parallelism: 1,
+        })
+    }
+
+    fn named<'a>(items: &'a [ImportedItem], name: &str) -> Result<&'a ImportedItem, CryptoError> {
+        items.iter().find(|i| i.name == name).ok_or_else(|| CryptoError::InvalidInput(format!("no item {name}")))
+    }
+
+    fn sample_items() -> Vec<ImportedItem> {
+        let login = ImportedItem {
+            id: "00112233445566778899aabbccddeeff".to_string(),
+            kind: ItemType::Login,
+            name: "Bank <&>".to_string(),
+            folder: Some("Finance/Personal".to_string()),
+            notes: Some("line one\nline two".to_string()),
+            login: Some(LoginData {
+                username: Some("jan".to_string()),
+                password: Some("s3cret \"quoted\"".to_string()),
+                uris: vec!["https://bank.example".to_string(), "https://m.bank.example".to_string()],
+                totp: Some("otpauth://totp/bank?secret=JBSWY3DP".to_string()),
+            }),
+            fields: vec![CustomField { name: "PIN".to_string(), value: "4321".to_string(), hidden: true }],
+            attachments: vec![ImportedAttachment {
+                name: "key.txt".to_string(),
+                size: 5,
+                data: "aGVsbG8=".to_string(),
+            }],
+            ..ImportedItem::default()
+        };
+        let card = ImportedItem {
+            id: "not-a-uuid".to_string(),
+            kind: ItemType::Card,
+            name: "Visa".to_string(),
+            card: Some(CardData { number: Some("4111111111111111".to_string()), ..CardData::default() }),
+            ..ImportedItem::default()
+        };
+        vec![login, card]
+    }
+
+    #[test]
+    fn export_round_trips_through_import() -> Result<(), CryptoError> {
+        let items = sample_items();
+        let blob = export_kdbx_bytes(&items, b"file password", &cheap_kdf()?)?;
+        let imported = import_kdbx_bytes(&blob, b"file password")?;
+        assert_eq!(imported.len(), 2);
+        let login = named(&imported, "Bank <&>")?;
+        assert_eq!(login.id, items[0].id);
+        assert_eq!(login.folder, items[0].folder);
+        assert_eq!(login.notes, items[0].notes);
+        assert_eq!(login.login, items[0].login);
+        assert_eq!(login.fields, items[0].fields);
+        assert_eq!(login.attachments, items[0].attachments);
+        // Karta bez odpowiednika w KDBX wraca jako pola wlasne, a id spoza UUID dostaje nowy UUID
+        let card = named(&imported, "Visa")?;
+        assert_ne!(card.id, "not-a-uuid");
+        assert!(card.fields.iter().any(|f| f.name == "CardNumber" && f.value == "4111111111111111" && f.hidden));
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_password_and_tampering_are_rejected() -> Result<(), CryptoError> {
+        let blob = export_kdbx_bytes(&sample_items(), b"file password", &cheap_kdf()?)?;
+        assert!(matches!(import_kdbx_bytes(&blob, b"wrong"), Err(CryptoError::WrongPassword(_))));
+        let mut tampered = blob.clone();
+        let last = tampered.len() - 40;
+        tampered[last] ^= 1;
+        assert!(matches!(import_kdbx_bytes(&tampered, b"file password"), Err(CryptoError::BadMac(_))));
+        let mut header = blob.clone();
+        header[12] ^= 1;
+        assert!(import_kdbx_bytes(&header, b"file password").is_err());
+        assert!(import_kdbx_bytes(b"not a keepass file", b"file password").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+use serde::Deserialize;
+use serde_json::Value;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::encoding::bytes_to_base64;
+use crate::error::CryptoError;
+use crate::import::zip::ZipArchive;
+use crate::import::{
+    CardData, CustomField, IdentityData, ImportedAttachment, ImportedItem, ItemType, LoginData, non_empty, to_json,
+};
+
+// Import archiwum 1PUX (1Password 8): ZIP z export.data (JSON konta -> sejfy -> wpisy)
+// i zalacznikami w files/<documentId>__<nazwa>. Nazwa sejfu staje sie folderem. Wartosci
+// pol sekcji sa obiektem z jednym kluczem typu ({"concealed": ..}, {"totp": ..}, ...);
+// znane pola kart i tozsamosci trafiaja do modelu, reszta do pol wlasnych.
+const DATA_ENTRY: &str = "export.data";
+const FILES_PREFIX: &str = "files/";
+
+const CATEGORY_LOGIN: &str = "001";
+const CATEGORY_CARD: &str = "002";
+const CATEGORY_IDENTITY: &str = "004";
+const CATEGORY_PASSWORD: &str = "005";
+
+#[derive(Deserialize)]
+struct Export {
+    #[serde(default)]
+    accounts: Vec<Account>,
+}
+
+#[derive(Deserialize)]
+struct Account {
+    #[serde(default)]
+    vaults: Vec<Vault>,
+}
+
+#[derive(Deserialize, Default)]
+struct VaultAttrs {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Vault {
+    #[serde(default)]
+    attrs: VaultAttrs,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    #[serde(default)]
+    uuid: String,
+    #[serde(default)]
+    category_uuid: String,
+    #[serde(default)]
+    details: Details,
+    #[serde(default)]
+    overview: Overview,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Details {
+    #[serde(default)]
+    login_fields: Vec<LoginField>,
+    notes_plain: Option<String>,
+    password: Option<String>,
+    #[serde(default)]
+    sections: Vec<Section>,
+    document_attributes: Option<Document>,
+}
+
+#[derive(Deserialize)]
+struct LoginField {
+    #[serde(default)]
+    value: String,
+    #[serde(default)]
+    name: String,
+    designation: Option<String>,
+    #[serde(rename = "fieldType", default)]
+    field_type: String,
+}
+
+#[derive(Deserialize)]
+struct Section {
+    #[serde(default)]
+    fields: Vec<SectionField>,
+}
+
+#[derive(Deserialize)]
+struct SectionField {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    value: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    #[serde(default)]
+    file_name: String,
+    #[serde(default)]
+    document_id: String,
+}
+
+#[derive(Deserialize)]
+struct OverviewUrl {
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Deserialize, Default)]
+struct Overview {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    urls: Vec<OverviewUrl>,
+}
+
+// Dzien kalendarza z dni od 1970-01-01 (algorytm civil_from_days, H. Hinnant).
+fn civil_date(timestamp: i64) -> String {
+    let z = timestamp.div_euclid(86_400) + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+enum FieldValue {
+    Text(String),
+    Concealed(String),
+    Totp(String),
+    File(Document),
+}
+
+fn field_value(value: &Value) -> Option<FieldValue> {
+    let (kind, inner) = value.as_object()?.iter().next()?;
+    let text = match (kind.as_str(), inner) {
+        ("concealed" | "creditCardNumber", Value::String(s)) => return non_empty(s).map(FieldValue::Concealed),
+        ("totp", Value::String(s)) => return non_empty(s).map(FieldValue::Totp),
+        ("file", file) => return serde_json::from_value(file.clone()).ok().map(FieldValue::File),
+        ("sshKey", key) => return key.get("privateKey")?.as_str().and_then(non_empty).map(FieldValue::Concealed),
+        ("date", Value::Number(n)) => civil_date(n.as_i64()?),
+        // 202512 -> 12/2025
+        ("monthYear", Value::Number(n)) => {
+            let n = n.as_u64()?;
+            format!("{:02}/{}", n % 100, n / 100)
+        }
+        ("email", Value::Object(email)) => email.get("email_address")?.as_str()?.to_string(),
+        ("address", Value::Object(address)) => ["street", "city", "state", "zip", "country"]
+            .iter()
+            .filter_map(|key| address.get(*key).and_then(Value::as_str).filter(|s| !s.is_empty()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (_, Value::String(s)) => s.clone(),
+        (_, Value::Number(n)) => n.to_string(),
+        _ => return None,
+    };
+    non_empty(&text).map(FieldValue::Text)
+}
+
+fn attachment(archive: &ZipArchive, document: &Document) -> Result<Option<ImportedAttachment>, CryptoError> {
+    let prefix = format!("{FILES_PREFIX}{}", document.document_id);
+    let Some(entry) = archive.entries.iter().find(|e| e.name.starts_with(&prefix)) else {
+        return Ok(None);
+    };
+    let data = archive.read(entry)?;
+    Ok(Some(ImportedAttachment { name: document.file_name.clone(), size: data.len(), data: bytes_to_base64(&data) }))
+}
+
+fn convert_item(archive: &ZipArchive, item: Item, vault: &str) -> Result<ImportedItem, CryptoError> {
+    let mut imported = ImportedItem {
+        id: item.uuid,
+        kind: ItemType::SecureNote,
+        name: item.overview.title,
+        folder: non_empty(vault),
+        notes: item.details.notes_plain.as_deref().and_then(non_empty),
+        ..ImportedItem::default()
+    };
+    let mut login = LoginData::default();
+    let mut card = CardData::default();
+    let mut identity = IdentityData::default();
+    let category = item.category_uuid.as_str();
+
+    // Pola formularza logowania; pozostale (np. "remember me") jako pola wlasne
+    for field in item.details.login_fields {
+        match field.designation.as_deref() {
+            Some("username") => login.username = non_empty(&field.value),
+            Some("password") => login.password = non_empty(&field.value),
+            _ if !field.value.is_empty() => imported.fields.push(CustomField {
+                name: field.name,
+                value: field.value,
+                hidden: field.field_type == "P",
+            }),
+            _ => {}
+        }
+    }
+    if let Some(password) = item.details.password.as_deref().and_then(non_empty) {
+        login.password.get_or_insert(password);
+    }
+    for url in std::iter::once(item.overview.url).chain(item.overview.urls.into_iter().map(|u| u.url)) {
+        if !url.is_empty() && !login.uris.contains(&url) {
+            login.uris.push(url);
+        }
+    }
+
+    for field in item.details.sections.into_iter().flat_map(|s| s.fields) {
+        if category == CATEGORY_IDENTITY
+            && let Some(address) = field.value.get("address")
+        {
+            let part = |key: &str| address.get(key).and_then(Value::as_str).and_then(non_empty);
+            identity.address1 = part("street");
+            identity.city = part("city");
+            identity.state = part("state");
+            identity.postal_code = part("zip");
+            identity.country = part("country");
+            continue;
+        }
+        let Some(value) = field_value(&field.value) else { continue };
+        let name = if field.title.is_empty() { field.id.clone() } else { field.title };
+        let (text, hidden) = match value {
+            FieldValue::File(document) => {
+                imported.attachments.extend(attachment(archive, &document)?);
+                continue;
+            }
+            FieldValue::Totp(seed) if login.totp.is_none() => {
+                login.totp = Some(seed);
+                continue;
+            }
+            FieldValue::Totp(seed) | FieldValue::Concealed(seed) => (seed, true),
+            FieldValue::Text(text) => (text, false),
+        };
+        let slot = match (category, field.id.as_str()) {
+            (CATEGORY_CARD, "cardholder") => &mut card.cardholder_name,
+            (CATEGORY_CARD, "type") => &mut card.brand,
+            (CATEGORY_CARD, "ccnum") => &mut card.number,
+            (CATEGORY_CARD, "cvv") => &mut card.code,
+            (CATEGORY_CARD, "expiry") => &mut card.exp_month,
+            (CATEGORY_IDENTITY, "firstname") => &mut identity.first_name,
+            (CATEGORY_IDENTITY, "initial") => &mut identity.middle_name,
+            (CATEGORY_IDENTITY, "lastname") => &mut identity.last_name,
+            (CATEGORY_IDENTITY, "company") => &mut identity.company,
+            (CATEGORY_IDENTITY, "email") => &mut identity.email,
+            (CATEGORY_IDENTITY, "defphone") => &mut identity.phone,
+            (CATEGORY_IDENTITY, "username") => &mut identity.username,
+            _ => {
+                imported.fields.push(CustomField { name, value: text, hidden });
+                continue;
+            }
+        };
+        *slot = Some(text);
+    }
+    if let Some(document) = &item.details.document_attributes {
+        imported.attachments.extend(attachment(archive, document)?);
+    }
+
+    match category {
+        CATEGORY_LOGIN | CATEGORY_PASSWORD => imported.kind = ItemType::Login,
+        CATEGORY_CARD => {
+            // "12/2025" -> miesiac i rok
+            if let Some(expiry) = card.exp_month.take()
+                && let Some((month, year)) = expiry.split_once('/')
+            {
+                card.exp_month = non_empty(month);
+                card.exp_year = non_empty(year);
+            }
+            imported.kind = ItemType::Card;
+            imported.card = Some(card);
+        }
+        CATEGORY_IDENTITY => {
+            imported.kind = ItemType::Identity;
+            imported.identity = Some(identity);
+        }
+        _ => {}
+    }
+    // Inne kategorie (np. serwer, baza danych) moga miec dane logowania
+    if imported.kind == ItemType::Login || login != LoginData::default() {
+        imported.login = Some(login);
+    }
+    Ok(imported)
+}
+
+pub fn import_1pux_bytes(data: &[u8]) -> Result<Vec<ImportedItem>, CryptoError> {
+    let archive = ZipArchive::parse(data)?;
+    let entry = archive
+        .entry(DATA_ENTRY)
+        .ok_or_else(|| CryptoError::InvalidInput(format!("1pux archive has no {DATA_ENTRY}")))?;
+    let export: Export = serde_json::from_slice(&archive.read(entry)?)
+        .map_err(|e| CryptoError::InvalidInput(format!("malformed 1pux export data: {e}")))?;
+    let mut items = Vec::new();
+    for vault in export.accounts.into_iter().flat_map(|a| a.vaults) {
+        for item in vault.items {
+            items.push(convert_item(&archive, item, &vault.attrs.name)?);
+        }
+    }
+    Ok(items)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn import_1pux(data: &[u8]) -> Result<String, CryptoError> {
+    to_json(import_1pux_bytes(data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::crc32_bytes;
+    use crate::deflate::deflate_bytes;
+
+    // Minimalny ZIP: naglowki lokalne, katalog centralny, EOCD; deflate albo stored.
+    fn zip(entries: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let (mut out, mut directory) = (Vec::new(), Vec::new());
+        for (name, data, deflate) in entries {
+            let stored = if *deflate { deflate_bytes(data).to_vec() } else { data.to_vec() };
+            let method: u16 = if *deflate { 8 } else { 0 };
+            let mut common = Vec::new();
+            common.extend_from_slice(&20u16.to_le_bytes());
+            common.extend_from_slice(&0u16.to_le_bytes());
+            common.extend_from_slice(&method.to_le_bytes());
+            common.extend_from_slice(&[0; 4]);
+            common.extend_from_slice(&crc32_bytes(data).to_le_bytes());
+            common.extend_from_slice(&(stored.len() as u32).to_le_bytes());
+            common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            common.extend_from_slice(&0u16.to_le_bytes());
+            directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes());
+            directory.extend_from_slice(&common);
+            directory.extend_from_slice(&[0; 10]);
+            directory.extend_from_slice(&(out.len() as u32).to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+            out.extend_from_slice(&common);
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(&stored);
+        }
+        let offset = out.len() as u32;
+        out.extend_from_slice(&directory);
+        out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out
+    }
+
+    const EXPORT: &str = r#"{"accounts": [{"vaults": [{"attrs": {"name": "Private"}, "items": [
+        {"uuid": "l1", "categoryUuid": "001",
+         "overview": {"title": "GitHub", "url": "https://github.com", "urls": [{"url": "https://github.com"}]},
+         "details": {"notesPlain": "note",
+            "loginFields": [
+                {"value": "octo", "name": "login", "designation": "username", "fieldType": "T"},
+                {"value": "pw", "name": "password", "designation": "password", "fieldType": "P"},
+                {"value": "✓", "name": "remember", "fieldType": "C"}],
+            "sections": [{"fields": [
+                {"title": "one-time password", "id": "otp", "value": {"totp": "otpauth://totp/gh?secret=ABC"}},
+                {"title": "recovery", "id": "rc", "value": {"file": {"fileName": "codes.txt", "documentId": "d1"}}},
+                {"title": "since", "id": "s", "value": {"date": 951782400}}]}]}},
+        {"uuid": "c1", "categoryUuid": "002", "overview": {"title": "Visa"},
+         "details": {"sections": [{"fields": [
+            {"title": "number", "id": "ccnum", "value": {"creditCardNumber": "4111111111111111"}},
+            {"title": "expiry", "id": "expiry", "value": {"monthYear": 202512}},
+            {"title": "cvv", "id": "cvv", "value": {"concealed": "123"}}]}]}},
+        {"uuid": "i1", "categoryUuid": "004", "overview": {"title": "Me"},
+         "details": {"sections": [{"fields": [
+            {"title": "first", "id": "firstname", "value": {"string": "Jan"}},
+            {"title": "address", "id": "address", "value": {"address": {"street": "Main 1", "city": "Gdansk"}}}]}]}}
+    ]}]}]}"#;
+
+    fn archive() -> Vec<u8> {
+        zip(&[("export.data", EXPORT.as_bytes(), true), ("files/d1__codes.txt", b"1111 2222", false)])
+    }
+
+    #[test]
+    fn logins_keep_credentials_totp_and_files() -> Result<(), CryptoError> {
+        let items = import_1pux_bytes(&archive())?;
+        assert_eq!(items.len(), 3);
+        let login = &items[0];
+        assert_eq!(login.kind, ItemType::Login);
+        assert_eq!((login.folder.as_deref(), login.notes.as_deref()), (Some("Private"), Some("note")));
+        let data = login.login.clone().unwrap_or_default();
+        assert_eq!((data.username.as_deref(), data.password.as_deref()), (Some("octo"), Some("pw")));
+        assert_eq!(data.uris, vec!["https://github.com".to_string()]);
+        assert_eq!(data.totp.as_deref(), Some("otpauth://totp/gh?secret=ABC"));
+        assert_eq!(login.attachments.len(), 1);
+        assert_eq!((login.attachments[0].name.as_str(), login.attachments[0].size), ("codes.txt", 9));
+        assert!(login.fields.iter().any(|f| f.name == "since" && f.value == "2000-02-29"));
+        assert!(login.fields.iter().any(|f| f.name == "remember" && !f.hidden));
+        Ok(())
+    }
+
+    #[test]
+    fn cards_and_identities_fill_the_model() -> Result<(), CryptoError> {
+        let items = import_1pux_bytes(&archive())?;
+        let card = items[1].card.clone().unwrap_or_default();
+        assert_eq!(items[1].kind, ItemType::Card);
+        assert_eq!(card.number.as_deref(), Some("4111111111111111"));
+        assert_eq!((card.exp_month.as_deref(), card.exp_year.as_deref()), (Some("12"), Some("2025")));
+        assert_eq!(card.code.as_deref(), Some("123"));
+        let identity = items[2].identity.clone().unwrap_or_default();
+        assert_eq!(items[2].kind, ItemType::Identity);
+        assert_eq!(identity.first_name.as_deref(), Some("Jan"));
+        assert_eq!((identity.address1.as_deref(), identity.city.as_deref()), (Some("Main 1"), Some("Gdansk")));
+        assert!(items[2].login.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn broken_archives_are_rejected() {
+        assert!(matches!(import_1pux_bytes(b"not a zip"), Err(CryptoError::CorruptHeader(_))));
+        let no_data = zip(&[("files/x", b"x", false)]);
+        assert!(matches!(import_1pux_bytes(&no_data), Err(CryptoError::InvalidInput(_))));
+        let bad_json = zip(&[("export.data", b"{\"accounts\": 1}", false)]);
+        assert!(matches!(import_1pux_bytes(&bad_json), Err(CryptoError::InvalidInput(_))));
+        // Uszkodzony bajt danych - CRC z katalogu centralnego sie nie zgadza
+        let mut corrupted = zip(&[("export.data", EXPORT.as_bytes(), false)]);
+        corrupted[30 + "export.data".len() + 5] ^= 1;
+        assert!(matches!(import_1pux_bytes(&corrupted), Err(CryptoError::CorruptHeader(_))));
+    }
+
+    #[test]
+    fn dates_are_calendar_days() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(951_782_400), "2000-02-29");
+        assert_eq!(civil_date(-86_400), "1969-12-31");
+    }
+}
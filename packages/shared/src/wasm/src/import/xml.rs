@@ -0,0 +1,178 @@
+use crate::error::CryptoError;
+
+// Minimalny parser XML dla formatow importu (KeePass): elementy, atrybuty, tekst, CDATA
+// i encje standardowe + numeryczne. Bez DTD i encji zewnetrznych (nic do rozwiniecia,
+// wiec "billion laughs" nie jest mozliwe). Tekst mieszany z elementami jest sklejany.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Element {
+    pub name: String,
+    pub attrs: Vec<(String, String)>,
+    pub children: Vec<Element>,
+    pub text: String,
+}
+
+impl Element {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    // Tekst dziecka albo "" (KeePass pomija puste pola albo zapisuje je jako <X/>).
+    pub fn child_text(&self, name: &str) -> &str {
+        self.child(name).map_or("", |c| c.text.as_str())
+    }
+}
+
+fn malformed(what: &str) -> CryptoError {
+    CryptoError::InvalidInput(format!("malformed XML: {what}"))
+}
+
+pub fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn decode_entities(raw: &str) -> Result<String, CryptoError> {
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let end = rest[amp..].find(';').ok_or_else(|| malformed("unterminated entity"))? + amp;
+        let entity = &rest[amp + 1..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok()
+                } else {
+                    entity.strip_prefix('#').and_then(|dec| dec.parse().ok())
+                };
+                code.and_then(char::from_u32).ok_or_else(|| malformed("unknown entity"))?
+            }
+        };
+        out.push(c);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+}
+
+fn parse_tag(tag: &str) -> Result<Element, CryptoError> {
+    let name_end = tag.find(|c: char| !is_name_char(c)).unwrap_or(tag.len());
+    if name_end == 0 {
+        return Err(malformed("empty element name"));
+    }
+    let mut element = Element { name: tag[..name_end].to_string(), ..Element::default() };
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest.find('=').ok_or_else(|| malformed("attribute without value"))?;
+        let key = rest[..eq].trim();
+        let after = rest[eq + 1..].trim_start();
+        let quote =
+            after.chars().next().filter(|q| *q == '"' || *q == '\'').ok_or_else(|| malformed("unquoted attribute"))?;
+        let close = after[1..].find(quote).ok_or_else(|| malformed("unterminated attribute"))? + 1;
+        element.attrs.push((key.to_string(), decode_entities(&after[1..close])?));
+        rest = after[close + 1..].trim_start();
+    }
+    Ok(element)
+}
+
+pub fn parse_xml(text: &str) -> Result<Element, CryptoError> {
+    let mut rest = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            if !rest.trim().is_empty() {
+                return Err(malformed("text outside the root element"));
+            }
+            break;
+        };
+        if lt > 0 {
+            match stack.last_mut() {
+                Some(top) => top.text.push_str(&decode_entities(&rest[..lt])?),
+                None if rest[..lt].trim().is_empty() => {}
+                None => return Err(malformed("text outside the root element")),
+            }
+        }
+        rest = &rest[lt..];
+        let skip_to = |rest: &str, end: &str| {
+            rest.find(end).map(|i| i + end.len()).ok_or_else(|| malformed("unterminated markup"))
+        };
+        if rest.starts_with("<?") {
+            rest = &rest[skip_to(rest, "?>")?..];
+        } else if rest.starts_with("<!--") {
+            rest = &rest[skip_to(rest, "-->")?..];
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").ok_or_else(|| malformed("unterminated CDATA"))?;
+            stack.last_mut().ok_or_else(|| malformed("CDATA outside the root element"))?.text.push_str(&cdata[..end]);
+            rest = &cdata[end + 3..];
+        } else if rest.starts_with("<!") {
+            return Err(malformed("DTD declarations are not supported"));
+        } else if let Some(close) = rest.strip_prefix("</") {
+            let end = close.find('>').ok_or_else(|| malformed("unterminated closing tag"))?;
+            let element = stack.pop().ok_or_else(|| malformed("unexpected closing tag"))?;
+            if close[..end].trim() != element.name {
+                return Err(malformed("mismatched closing tag"));
+            }
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => root = Some(element),
+            }
+            rest = &close[end + 1..];
+        } else {
+            let end = rest.find('>').ok_or_else(|| malformed("unterminated tag"))?;
+            let (tag, self_closing) = match rest[1..end].strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (&rest[1..end], false),
+            };
+            if root.is_some() {
+                return Err(malformed("more than one root element"));
+            }
+            let element = parse_tag(tag)?;
+            if self_closing {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            } else {
+                if stack.len() >= MAX_DEPTH {
+                    return Err(malformed("nesting too deep"));
+                }
+                stack.push(element);
+            }
+            rest = &rest[end + 1..];
+        }
+    }
+    if !stack.is_empty() {
+        return Err(malformed("unclosed element"));
+    }
+    root.ok_or_else(|| malformed("no root element"))
+}
@@ -0,0 +1,131 @@
+use zeroize::Zeroizing;
+
+use crate::crc::crc32_bytes;
+use crate::error::CryptoError;
+use crate::import::inflate::{MAX_OUTPUT, inflate_bytes};
+
+// Odczyt archiwow ZIP dla importu (1PUX). Wpisy z katalogu centralnego - rozmiary i CRC
+// sa tam zawsze, takze gdy naglowek lokalny uzywa deskryptora danych (bit 3). Metody 0
+// (stored) i 8 (deflate); ZIP64, archiwa wieloczesciowe i szyfrowanie ZIP sa odrzucane.
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const CENTRAL_SIGNATURE: u32 = 0x0201_4b50;
+const LOCAL_SIGNATURE: u32 = 0x0403_4b50;
+const EOCD_LEN: usize = 22;
+const CENTRAL_LEN: usize = 46;
+const LOCAL_LEN: usize = 30;
+const MAX_COMMENT: usize = 0xffff;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+const FLAG_ENCRYPTED: u16 = 0x0001;
+
+fn corrupt(what: &str) -> CryptoError {
+    CryptoError::CorruptHeader(format!("corrupt zip archive: {what}"))
+}
+
+fn u16_at(data: &[u8], pos: usize) -> Result<u16, CryptoError> {
+    let bytes = data.get(pos..pos + 2).ok_or_else(|| corrupt("truncated record"))?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], pos: usize) -> Result<u32, CryptoError> {
+    let bytes = data.get(pos..pos + 4).ok_or_else(|| corrupt("truncated record"))?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZipEntry {
+    pub name: String,
+    pub size: usize,
+    method: u16,
+    flags: u16,
+    crc: u32,
+    compressed_size: usize,
+    local_offset: usize,
+}
+
+pub struct ZipArchive<'a> {
+    data: &'a [u8],
+    pub entries: Vec<ZipEntry>,
+}
+
+impl<'a> ZipArchive<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<ZipArchive<'a>, CryptoError> {
+        // EOCD na koncu, przed nim najwyzej 64 KiB komentarza
+        let lowest = data.len().saturating_sub(EOCD_LEN + MAX_COMMENT);
+        let eocd = (lowest..=data.len().saturating_sub(EOCD_LEN))
+            .rev()
+            .find(|&pos| u32_at(data, pos).is_ok_and(|sig| sig == EOCD_SIGNATURE))
+            .ok_or_else(|| CryptoError::CorruptHeader("not a zip archive".to_string()))?;
+        if u16_at(data, eocd + 4)? != 0 || u16_at(data, eocd + 6)? != 0 {
+            return Err(CryptoError::UnsupportedVersion("multi-part zip archives are not supported".to_string()));
+        }
+        let count = u16_at(data, eocd + 10)? as usize;
+        let directory_offset = u32_at(data, eocd + 16)?;
+        if directory_offset == u32::MAX {
+            return Err(CryptoError::UnsupportedVersion("zip64 archives are not supported".to_string()));
+        }
+
+        let mut entries = Vec::with_capacity(count);
+        let mut pos = directory_offset as usize;
+        for _ in 0..count {
+            if u32_at(data, pos)? != CENTRAL_SIGNATURE {
+                return Err(corrupt("bad central directory signature"));
+            }
+            let name_len = u16_at(data, pos + 28)? as usize;
+            let extra_len = u16_at(data, pos + 30)? as usize;
+            let comment_len = u16_at(data, pos + 32)? as usize;
+            let name =
+                data.get(pos + CENTRAL_LEN..pos + CENTRAL_LEN + name_len).ok_or_else(|| corrupt("truncated name"))?;
+            let (compressed_size, size, local_offset) =
+                (u32_at(data, pos + 20)?, u32_at(data, pos + 24)?, u32_at(data, pos + 42)?);
+            if [compressed_size, size, local_offset].contains(&u32::MAX) {
+                return Err(CryptoError::UnsupportedVersion("zip64 archives are not supported".to_string()));
+            }
+            entries.push(ZipEntry {
+                name: String::from_utf8_lossy(name).into_owned(),
+                size: size as usize,
+                method: u16_at(data, pos + 10)?,
+                flags: u16_at(data, pos + 8)?,
+                crc: u32_at(data, pos + 16)?,
+                compressed_size: compressed_size as usize,
+                local_offset: local_offset as usize,
+            });
+            pos += CENTRAL_LEN + name_len + extra_len + comment_len;
+        }
+        Ok(ZipArchive { data, entries })
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&ZipEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    pub fn read(&self, entry: &ZipEntry) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+        if entry.flags & FLAG_ENCRYPTED != 0 {
+            return Err(CryptoError::UnsupportedAlgorithm(format!("zip entry {} is encrypted", entry.name)));
+        }
+        if entry.size > MAX_OUTPUT {
+            return Err(CryptoError::OutOfRange(format!("zip entry exceeds {MAX_OUTPUT} bytes")));
+        }
+        let local = entry.local_offset;
+        if u32_at(self.data, local)? != LOCAL_SIGNATURE {
+            return Err(corrupt("bad local header signature"));
+        }
+        // Dlugosci nazwy i pola extra w naglowku lokalnym moga sie roznic od katalogu
+        let start =
+            local + LOCAL_LEN + u16_at(self.data, local + 26)? as usize + u16_at(self.data, local + 28)? as usize;
+        let stored = self
+            .data
+            .get(start..start + entry.compressed_size)
+            .ok_or_else(|| corrupt("entry data past end of archive"))?;
+        let out = match entry.method {
+            METHOD_STORED => Zeroizing::new(stored.to_vec()),
+            METHOD_DEFLATE => inflate_bytes(stored)?.0,
+            other => return Err(CryptoError::UnsupportedAlgorithm(format!("zip compression method {other}"))),
+        };
+        if out.len() != entry.size || crc32_bytes(&out) != entry.crc {
+            return Err(corrupt(&format!("checksum mismatch in {}", entry.name)));
+        }
+        Ok(out)
+    }
+}
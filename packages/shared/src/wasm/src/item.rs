@@ -0,0 +1,598 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::cbor::Cbor;
+use crate::encoding::{base64_to_bytes, bytes_to_base64};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::history::{HistoryUpdate, history_record_change_bytes};
+use crate::import::ImportedItem;
+use crate::rng::random_array;
+use crate::secret::{EntryKey, MasterKey};
+
+// Model wpisu vaulta wspolny dla wszystkich klientow (zapisywany jako JSON w Vault::put_entry).
+// Typ wpisu wyznacza, ktora sekcja danych (login / card / identity) moze byc ustawiona;
+// notatka bezpieczna ma tylko notes i pola wlasne. Folder to sciezka "a/b", tagi bez
+// duplikatow w kolejnosci dodania. Czasy w sekundach podaje wywolujacy, jak w reszcie API.
+//
+// Ukryte pola wlasne sa szyfrowane osobno (AES-256-GCM, base64 nonce || ct || tag) kluczem pol
+// wyprowadzonym z klucza vaulta, AAD = etykieta || id wpisu || 0 || nazwa pola. Lista wpisow
+// i ich JSON w pamieci JS nie zawieraja wiec sekretow pol, dopoki UI ich nie odslonie, a pola
+// nie da sie przeniesc do innego wpisu ani pod inna nazwe.
+const FIELD_KEY_INFO: &[u8] = b"pm-item-field";
+const FIELD_LABEL: &[u8] = b"pm-item-field-v1\0";
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemType {
+    #[default]
+    Login,
+    SecureNote,
+    Card,
+    Identity,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    #[default]
+    Text,
+    Hidden,
+    Boolean,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoginData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uris: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub totp: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CardData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cardholder_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp_month: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp_year: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdentityData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub middle_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address2: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address3: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssn: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passport_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_number: Option<String>,
+}
+
+// Dla Hidden value to zaszyfrowana wartosc (base64), dla pozostalych jawny tekst.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemField {
+    pub name: String,
+    #[serde(default)]
+    pub kind: FieldKind,
+    pub value: String,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VaultItem {
+    id: String,
+    item_type: ItemType,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default)]
+    favorite: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    login: Option<LoginData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    card: Option<CardData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    identity: Option<IdentityData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<ItemField>,
+    created_at: u64,
+    updated_at: u64,
+}
+
+// Osobny klucz pol ukrytych wyprowadzany z klucza vaulta.
+pub fn derive_field_key(vault_key: &MasterKey) -> Result<EntryKey, CryptoError> {
+    Ok(EntryKey::from_vec(hkdf_sha256_bytes(vault_key.as_bytes(), &[], FIELD_KEY_INFO, 32)?))
+}
+
+fn field_cipher(field_key: &EntryKey) -> Result<AesGcm, CryptoError> {
+    if field_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("field key must be 32 bytes".to_string()));
+    }
+    AesGcm::new(field_key.as_bytes())
+}
+
+fn field_aad(item_id: &str, name: &str) -> Vec<u8> {
+    [FIELD_LABEL, item_id.as_bytes(), &[0], name.as_bytes()].concat()
+}
+
+impl VaultItem {
+    pub fn create(id: &str, item_type: ItemType, name: &str, now: u64) -> Result<VaultItem, CryptoError> {
+        let mut item = VaultItem {
+            id: id.to_string(),
+            item_type,
+            name: name.to_string(),
+            created_at: now,
+            updated_at: now,
+            ..VaultItem::default()
+        };
+        match item_type {
+            ItemType::Login => item.login = Some(LoginData::default()),
+            ItemType::Card => item.card = Some(CardData::default()),
+            ItemType::Identity => item.identity = Some(IdentityData::default()),
+            ItemType::SecureNote => {}
+        }
+        item.validate()?;
+        Ok(item)
+    }
+
+    // Ukryte pola wlasne z importu sa szyfrowane; zalaczniki importu nie naleza do wpisu.
+    pub fn from_imported(item: &ImportedItem, field_key: &EntryKey, now: u64) -> Result<VaultItem, CryptoError> {
+        let mut converted = VaultItem {
+            id: item.id.clone(),
+            item_type: item.kind,
+            name: item.name.clone(),
+            folder: item.folder.clone(),
+            notes: item.notes.clone(),
+            login: item.login.clone(),
+            card: item.card.clone(),
+            identity: item.identity.clone(),
+            created_at: now,
+            updated_at: now,
+            ..VaultItem::default()
+        };
+        for field in &item.fields {
+            let kind = if field.hidden { FieldKind::Hidden } else { FieldKind::Text };
+            converted.set_field_with(field_key, &field.name, kind, &field.value, now)?;
+        }
+        converted.validate()?;
+        Ok(converted)
+    }
+
+    pub fn validate(&self) -> Result<(), CryptoError> {
+        if self.id.is_empty() {
+            return Err(CryptoError::InvalidInput("item id must not be empty".to_string()));
+        }
+        let sections = [
+            (ItemType::Login, self.login.is_some()),
+            (ItemType::Card, self.card.is_some()),
+            (ItemType::Identity, self.identity.is_some()),
+        ];
+        // Sekcja innego typu jest bledem; wyjatkiem login - importy zapisuja dane logowania
+        // np. serwera albo dokumentu przy notatce
+        for (section_type, present) in sections {
+            if present && section_type != self.item_type && section_type != ItemType::Login {
+                return Err(CryptoError::InvalidInput(format!(
+                    "{:?} item must not carry {section_type:?} data",
+                    self.item_type
+                )));
+            }
+        }
+        if self.updated_at < self.created_at {
+            return Err(CryptoError::InvalidInput("item updated_at is before created_at".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn login(&self) -> Option<&LoginData> {
+        self.login.as_ref()
+    }
+
+    pub fn card(&self) -> Option<&CardData> {
+        self.card.as_ref()
+    }
+
+    pub fn identity(&self) -> Option<&IdentityData> {
+        self.identity.as_ref()
+    }
+
+    pub fn fields(&self) -> &[ItemField] {
+        &self.fields
+    }
+
+    fn touch(&mut self, now: u64) {
+        self.updated_at = self.updated_at.max(now);
+    }
+
+    // Nadpisuje pole o tej samej nazwie albo dodaje nowe na koncu.
+    pub fn set_field_with(
+        &mut self,
+        field_key: &EntryKey,
+        name: &str,
+        kind: FieldKind,
+        value: &str,
+        now: u64,
+    ) -> Result<(), CryptoError> {
+        if name.is_empty() {
+            return Err(CryptoError::InvalidInput("field name must not be empty".to_string()));
+        }
+        let value = match kind {
+            FieldKind::Hidden => {
+                let nonce = random_array::<NONCE_LEN>()?;
+                let sealed = field_cipher(field_key)?.encrypt(&nonce, &field_aad(&self.id, name), value.as_bytes())?;
+                bytes_to_base64(&[&nonce[..], &sealed].concat())
+            }
+            FieldKind::Boolean if value != "true" && value != "false" => {
+                return Err(CryptoError::InvalidInput("boolean field value must be \"true\" or \"false\"".to_string()));
+            }
+            _ => value.to_string(),
+        };
+        let field = ItemField { name: name.to_string(), kind, value };
+        match self.fields.iter_mut().find(|f| f.name == name) {
+            Some(existing) => *existing = field,
+            None => self.fields.push(field),
+        }
+        self.touch(now);
+        Ok(())
+    }
+
+    // set_data z historia hasel: przy zmianie hasla logowania sprawdza nowe wzgledem ledgera
+    // i dopisuje stare. Zwraca (czy nowe haslo bylo juz uzyte, ledger do zapisania) - bez
+    // zmiany hasla ledger wraca bez zmian. Przy bledzie wpis zostaje nietkniety.
+    pub fn set_data_with(
+        &mut self,
+        history_key: &EntryKey,
+        ledger: &[u8],
+        data: &str,
+        now: u64,
+    ) -> Result<(bool, Vec<u8>), CryptoError> {
+        let mut updated = self.clone();
+        updated.set_data(data, now)?;
+        let password = |item: &VaultItem| item.login.as_ref().and_then(|l| l.password.clone()).map(Zeroizing::new);
+        let (old, new) = (password(self), password(&updated));
+        let result = match new.as_deref() {
+            Some(new) if !new.trim().is_empty() && old.as_deref() != Some(new) => {
+                history_record_change_bytes(history_key, ledger, old.as_ref().map(|p| p.as_str()), new, now)?
+            }
+            _ => (false, ledger.to_vec()),
+        };
+        *self = updated;
+        Ok(result)
+    }
+
+    pub fn reveal_field_with(&self, field_key: &EntryKey, name: &str) -> Result<Zeroizing<String>, CryptoError> {
+        let field = self
+            .fields
+            .iter()
+            .find(|f| f.name == name)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("item has no field {name}")))?;
+        if field.kind != FieldKind::Hidden {
+            return Ok(Zeroizing::new(field.value.clone()));
+        }
+        let blob = base64_to_bytes(&field.value)?;
+        if blob.len() < NONCE_LEN + TAG_LEN {
+            return Err(CryptoError::CorruptHeader("sealed field value truncated".to_string()));
+        }
+        let (nonce, sealed) = blob.split_at(NONCE_LEN);
+        let plaintext = Zeroizing::new(field_cipher(field_key)?.decrypt(nonce, &field_aad(&self.id, name), sealed)?);
+        String::from_utf8(plaintext.to_vec())
+            .map(Zeroizing::new)
+            .map_err(|_| CryptoError::CorruptHeader("field value is not UTF-8".to_string()))
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl VaultItem {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(id: &str, item_type: ItemType, name: &str, now: u64) -> Result<VaultItem, CryptoError> {
+        VaultItem::create(id, item_type, name, now)
+    }
+
+    pub fn from_json(json: &str) -> Result<VaultItem, CryptoError> {
+        let item: VaultItem =
+            serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(format!("malformed item: {e}")))?;
+        item.validate()?;
+        Ok(item)
+    }
+
+    pub fn to_json(&self) -> Result<String, CryptoError> {
+        serde_json::to_string(self).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+    }
+
+    pub fn from_cbor(data: &[u8]) -> Result<VaultItem, CryptoError> {
+        let item: VaultItem = serde_json::from_value(Cbor::decode(data)?.to_json()?)
+            .map_err(|e| CryptoError::InvalidInput(format!("malformed item: {e}")))?;
+        item.validate()?;
+        Ok(item)
+    }
+
+    // Te same pola co to_json, w kanonicznym CBOR.
+    pub fn to_cbor(&self) -> Result<Vec<u8>, CryptoError> {
+        let value = serde_json::to_value(self).map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        Ok(Cbor::from_json(&value).encode())
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn item_type(&self) -> ItemType {
+        self.item_type
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn folder(&self) -> Option<String> {
+        self.folder.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn favorite(&self) -> bool {
+        self.favorite
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn notes(&self) -> Option<String> {
+        self.notes.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn created_at(&self) -> u64 {
+        self.created_at
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn updated_at(&self) -> u64 {
+        self.updated_at
+    }
+
+    pub fn set_name(&mut self, name: &str, now: u64) {
+        self.name = name.to_string();
+        self.touch(now);
+    }
+
+    pub fn set_folder(&mut self, folder: Option<String>, now: u64) {
+        self.folder = folder.filter(|f| !f.is_empty());
+        self.touch(now);
+    }
+
+    pub fn set_notes(&mut self, notes: Option<String>, now: u64) {
+        self.notes = notes.filter(|n| !n.is_empty());
+        self.touch(now);
+    }
+
+    pub fn set_favorite(&mut self, favorite: bool, now: u64) {
+        self.favorite = favorite;
+        self.touch(now);
+    }
+
+    // Zwraca false, gdy tag juz byl (albo jest pusty).
+    pub fn add_tag(&mut self, tag: &str, now: u64) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() || self.tags.iter().any(|t| t == tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        self.touch(now);
+        true
+    }
+
+    pub fn remove_tag(&mut self, tag: &str, now: u64) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|t| t != tag.trim());
+        let removed = self.tags.len() != before;
+        if removed {
+            self.touch(now);
+        }
+        removed
+    }
+
+    // Dane sekcji typu (login / card / identity) jako JSON; sekcja musi pasowac do typu wpisu.
+    pub fn set_data(&mut self, data: &str, now: u64) -> Result<(), CryptoError> {
+        let malformed = |e: serde_json::Error| CryptoError::InvalidInput(format!("malformed item data: {e}"));
+        match self.item_type {
+            ItemType::Login => self.login = Some(serde_json::from_str(data).map_err(malformed)?),
+            ItemType::Card => self.card = Some(serde_json::from_str(data).map_err(malformed)?),
+            ItemType::Identity => self.identity = Some(serde_json::from_str(data).map_err(malformed)?),
+            ItemType::SecureNote => {
+                return Err(CryptoError::InvalidInput("secure notes have no typed data".to_string()));
+            }
+        }
+        self.touch(now);
+        Ok(())
+    }
+
+    // Jak set_data, ale zapisuje zmiane hasla w historii (zob. set_data_with).
+    pub fn set_data_with_history(
+        &mut self,
+        history_key: &[u8],
+        ledger: &[u8],
+        data: &str,
+        now: u64,
+    ) -> Result<HistoryUpdate, CryptoError> {
+        let (reused, ledger) = self.set_data_with(&EntryKey::from_slice(history_key), ledger, data, now)?;
+        Ok(HistoryUpdate::new(reused, ledger))
+    }
+
+    pub fn data(&self) -> Result<String, CryptoError> {
+        let json = match self.item_type {
+            ItemType::Login => serde_json::to_string(&self.login),
+            ItemType::Card => serde_json::to_string(&self.card),
+            ItemType::Identity => serde_json::to_string(&self.identity),
+            ItemType::SecureNote => Ok("null".to_string()),
+        };
+        json.map_err(|e| CryptoError::InvalidInput(e.to_string()))
+    }
+
+    pub fn set_field(
+        &mut self,
+        field_key: &[u8],
+        name: &str,
+        kind: FieldKind,
+        value: &str,
+        now: u64,
+    ) -> Result<(), CryptoError> {
+        self.set_field_with(&EntryKey::from_slice(field_key), name, kind, value, now)
+    }
+
+    pub fn remove_field(&mut self, name: &str, now: u64) -> bool {
+        let before = self.fields.len();
+        self.fields.retain(|f| f.name != name);
+        let removed = self.fields.len() != before;
+        if removed {
+            self.touch(now);
+        }
+        removed
+    }
+
+    pub fn field_names(&self) -> Vec<String> {
+        self.fields.iter().map(|f| f.name.clone()).collect()
+    }
+
+    pub fn reveal_field(&self, field_key: &[u8], name: &str) -> Result<String, CryptoError> {
+        let mut value = self.reveal_field_with(&EntryKey::from_slice(field_key), name)?;
+        Ok(std::mem::take(&mut *value))
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn item_field_key(vault_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(derive_field_key(&MasterKey::from_slice(vault_key))?.as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELD_KEY: [u8; 32] = [0x24; 32];
+
+    fn sample() -> Result<VaultItem, CryptoError> {
+        let mut item = VaultItem::create("item-1", ItemType::Login, "Mail", 100)?;
+        item.set_data(r#"{"username":"alice","password":"hunter2","uris":["https://mail.example"]}"#, 110)?;
+        item.set_folder(Some("work/mail".to_string()), 120);
+        item.add_tag("work", 120);
+        item.set_favorite(true, 120);
+        item.set_field(&FIELD_KEY, "pin", FieldKind::Hidden, "s3cret-pin", 130)?;
+        item.set_field(&FIELD_KEY, "2fa", FieldKind::Boolean, "true", 130)?;
+        Ok(item)
+    }
+
+    #[test]
+    fn cbor_and_json_round_trip() -> Result<(), CryptoError> {
+        let item = sample()?;
+        let cbor = item.to_cbor()?;
+        let decoded = VaultItem::from_cbor(&cbor)?;
+        assert_eq!(decoded, item);
+        assert_eq!(decoded.to_cbor()?, cbor);
+        assert_eq!(VaultItem::from_json(&item.to_json()?)?, item);
+        assert_eq!((decoded.created_at(), decoded.updated_at()), (100, 130));
+        assert_eq!(decoded.login().and_then(|l| l.password.as_deref()), Some("hunter2"));
+        // Ukryte pole zostaje zaszyfrowane w obu formatach.
+        assert!(!cbor.windows(10).any(|w| w == b"s3cret-pin"));
+        assert_eq!(decoded.reveal_field(&FIELD_KEY, "pin")?, "s3cret-pin");
+        Ok(())
+    }
+
+    #[test]
+    fn schema_violations_are_rejected() -> Result<(), CryptoError> {
+        let invalid = [
+            r#"{"id":"","item_type":"login","name":"x","created_at":1,"updated_at":1}"#,
+            r#"{"id":"a","item_type":"card","name":"x","identity":{},"created_at":1,"updated_at":1}"#,
+            r#"{"id":"a","item_type":"login","name":"x","created_at":2,"updated_at":1}"#,
+            r#"{"id":"a","item_type":"wallet","name":"x","created_at":1,"updated_at":1}"#,
+            r#"{"id":"a","item_type":"login","name":"x","created_at":1}"#,
+            r#"{"id":"a","item_type":"login","name":"x","tags":"work","created_at":1,"updated_at":1}"#,
+        ];
+        for json in invalid {
+            let value: serde_json::Value =
+                serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+            assert!(matches!(VaultItem::from_json(json), Err(CryptoError::InvalidInput(_))), "{json}");
+            assert!(VaultItem::from_cbor(&Cbor::from_json(&value).encode()).is_err(), "{json}");
+        }
+        // Login przy notatce jest dozwolony (importy), karta nie.
+        let note = r#"{"id":"a","item_type":"secure_note","name":"x","login":{},"created_at":1,"updated_at":1}"#;
+        assert!(VaultItem::from_json(note).is_ok());
+        assert!(VaultItem::from_cbor(b"\xff").is_err());
+
+        let mut item = sample()?;
+        assert!(item.set_field(&FIELD_KEY, "flag", FieldKind::Boolean, "yes", 140).is_err());
+        item.set_data("{}", 140)?;
+        let mut note = VaultItem::create("n", ItemType::SecureNote, "note", 1)?;
+        assert!(matches!(note.set_data("{}", 2), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_field_is_bound_to_its_item_and_name() -> Result<(), CryptoError> {
+        let item = sample()?;
+        let sealed = item.fields().iter().find(|f| f.name == "pin").map(|f| f.value.clone()).unwrap_or_default();
+        let mut moved = VaultItem::create("item-2", ItemType::Login, "Other", 100)?;
+        moved.fields.push(ItemField { name: "pin".to_string(), kind: FieldKind::Hidden, value: sealed.clone() });
+        assert!(matches!(moved.reveal_field(&FIELD_KEY, "pin"), Err(CryptoError::DecryptFailed(_))));
+        let mut renamed = item.clone();
+        renamed.fields.push(ItemField { name: "pin2".to_string(), kind: FieldKind::Hidden, value: sealed });
+        assert!(matches!(renamed.reveal_field(&FIELD_KEY, "pin2"), Err(CryptoError::DecryptFailed(_))));
+        assert!(matches!(item.reveal_field(&[0x25; 32], "pin"), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::hmac::hmac_sha256_bytes;
+
+// Kanonizacja JSON (RFC 8785, JCS) dla danych, ktore musza zostac JSON-em (API serwera):
+// HMAC i podpis licza sie nad postacia kanoniczna, wiec nie zaleza od kolejnosci kluczy
+// ani bialych znakow, jakie wyprodukowal JS.
+//   - klucze obiektow sortowane po jednostkach UTF-16 (jak w JS), bez duplikatow (I-JSON)
+//   - liczby jako double w zapisie Number.prototype.toString z ES6 (najkrotszy dokladny)
+//   - w stringach escapowane tylko ", \ i znaki < 0x20; reszta UTF-8 bez zmian
+// Liczby calkowite powyzej 2^53 traca dokladnosc tak samo jak w JS - duze identyfikatory
+// powinny byc stringami.
+
+// Wartosc JSON odrzucajaca powtorzone klucze (serde_json po cichu bierze ostatni).
+struct StrictValue(Value);
+
+struct StrictVisitor;
+
+impl<'de> Visitor<'de> for StrictVisitor {
+    type Value = StrictValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::Bool(v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::from(v)))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::from(v)))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::from(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::String(v.to_string())))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::String(v)))
+    }
+
+    fn visit_unit<E>(self) -> Result<StrictValue, E> {
+        Ok(StrictValue(Value::Null))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<StrictValue, A::Error> {
+        let mut items = Vec::new();
+        while let Some(StrictValue(item)) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(StrictValue(Value::Array(items)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<StrictValue, A::Error> {
+        let mut map = Map::new();
+        while let Some(key) = access.next_key::<String>()? {
+            let StrictValue(value) = access.next_value()?;
+            if map.insert(key.clone(), value).is_some() {
+                return Err(de::Error::custom(format!("duplicate key {key:?}")));
+            }
+        }
+        Ok(StrictValue(Value::Object(map)))
+    }
+}
+
+impl<'de> Deserialize<'de> for StrictValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<StrictValue, D::Error> {
+        deserializer.deserialize_any(StrictVisitor)
+    }
+}
+
+// ES6 Number::toString: cyfry najkrotszej reprezentacji (z formatu {:e}) i wykladnik n,
+// gdzie wartosc = 0.cyfry * 10^n.
+fn write_number(out: &mut String, x: f64) -> Result<(), CryptoError> {
+    if !x.is_finite() {
+        return Err(CryptoError::InvalidInput("json number is not finite".to_string()));
+    }
+    if x == 0.0 {
+        out.push('0');
+        return Ok(());
+    }
+    if x < 0.0 {
+        out.push('-');
+    }
+    // {:e} daje najkrotsza liczbe cyfr k, ale przy remisie zaokragla w gore; ES6 bierze
+    // k cyfr najblizszych dokladnej wartosci (remis do parzystej) - tak formatuje {:.(k-1)e}.
+    let shortest = format!("{:e}", x.abs());
+    let k = shortest.split('e').next().unwrap_or("").chars().filter(char::is_ascii_digit).count();
+    let formatted = format!("{:.*e}", k.saturating_sub(1), x.abs());
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let digits = digits.trim_end_matches('0');
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().map_err(|e| CryptoError::InvalidInput(e.to_string()))? + 1;
+    if k <= n && n <= 21 {
+        out.push_str(digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', -n as usize));
+        out.push_str(digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push_str(&format!("e{}{}", if n > 0 { "+" } else { "-" }, (n - 1).abs()));
+    }
+    Ok(())
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_value(out: &mut String, value: &Value) -> Result<(), CryptoError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            let x = n.as_f64().ok_or_else(|| CryptoError::InvalidInput("json number out of range".to_string()))?;
+            write_number(out, x)?;
+        }
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(Vec<u16>, &String, &Value)> =
+                map.iter().map(|(k, v)| (k.encode_utf16().collect(), k, v)).collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            out.push('{');
+            for (i, (_, key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, value)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+pub fn canonicalize_value(value: &Value) -> Result<String, CryptoError> {
+    let mut out = String::new();
+    write_value(&mut out, value)?;
+    Ok(out)
+}
+
+pub fn canonicalize_json_str(json: &str) -> Result<String, CryptoError> {
+    let StrictValue(value) =
+        serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(format!("malformed json: {e}")))?;
+    canonicalize_value(&value)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn canonicalize_json(json: &str) -> Result<String, CryptoError> {
+    canonicalize_json_str(json)
+}
+
+// HMAC-SHA256 nad postacia kanoniczna (UTF-8).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn json_hmac_sha256(key: &[u8], json: &str) -> Result<Vec<u8>, CryptoError> {
+    Ok(hmac_sha256_bytes(key, canonicalize_json_str(json)?.as_bytes()).to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_json_hmac_sha256(key: &[u8], json: &str, mac: &[u8]) -> Result<bool, CryptoError> {
+    Ok(ct_eq(&json_hmac_sha256(key, json)?, mac))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8785 dodatek B: bity IEEE 754 -> zapis ES6
+    const NUMBERS: [(u64, &str); 23] = [
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+    ];
+
+    #[test]
+    fn rfc8785_numbers() -> Result<(), CryptoError> {
+        for (bits, expected) in NUMBERS {
+            let mut out = String::new();
+            write_number(&mut out, f64::from_bits(bits))?;
+            assert_eq!(out, expected, "{bits:016x}");
+        }
+        assert!(matches!(write_number(&mut String::new(), f64::NAN), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rfc8785_example() -> Result<(), CryptoError> {
+        // RFC 8785 3.2.2
+        let input = r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+            "literals": [null, true, false]
+        }"#;
+        let expected = r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"#
+            .to_string()
+            + r#""string":"€$\u000f\nA'B\"\\\\\"/"}"#;
+        assert_eq!(canonicalize_json_str(input)?, expected);
+        assert!(matches!(canonicalize_json_str(r#"{"a":1,"a":2}"#), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn sorts_keys_by_utf16_code_units() -> Result<(), CryptoError> {
+        // RFC 8785 3.2.3: emoji (para zastepcza 0xd83d...) przed U+FB33, choc w kodach punktow jest dalej
+        let input = r#"{"€":"Euro Sign","\r":"Carriage Return","דּ":"Hebrew Letter Dalet With Dagesh",
+            "1":"One","😀":"Emoji: Grinning Face","\u0080":"Control",
+            "ö":"Latin Small Letter O With Diaeresis"}"#;
+        let keys: Vec<String> = ["\r", "1", "\u{80}", "\u{f6}", "\u{20ac}", "\u{1f600}", "\u{fb33}"]
+            .iter()
+            .map(|k| {
+                let mut out = String::new();
+                write_string(&mut out, k);
+                out
+            })
+            .collect();
+        let canonical = canonicalize_json_str(input)?;
+        let positions: Vec<usize> = keys.iter().map(|k| canonical.find(k.as_str()).unwrap_or(usize::MAX)).collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]), "{canonical}");
+        Ok(())
+    }
+
+    #[test]
+    fn hmac_ignores_formatting_but_not_content() -> Result<(), CryptoError> {
+        let key = b"jcs hmac key";
+        let mac = json_hmac_sha256(key, r#"{"b":[1,2],"a":"x"}"#)?;
+        assert!(verify_json_hmac_sha256(key, "{ \"a\": \"x\",\n \"b\": [1.0, 2e0] }", &mac)?);
+        assert!(!verify_json_hmac_sha256(key, r#"{"a":"y","b":[1,2]}"#, &mac)?);
+        assert!(!verify_json_hmac_sha256(b"other key", r#"{"a":"x","b":[1,2]}"#, &mac)?);
+        assert!(!verify_json_hmac_sha256(key, r#"{"a":"x","b":[1,2]}"#, &mac[..31])?);
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::aes::Aes;
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+
+// AES Key Wrap (RFC 3394, NIST SP 800-38F KW) - opakowanie kluczy symetrycznych do zapisu
+// obok vaulta, zgodne z WebCrypto wrapKey("raw", ..., "AES-KW"). KEK 128/192/256 bitow,
+// klucz opakowywany: wielokrotnosc 8 bajtow, co najmniej 16. Wynik o 8 bajtow dluzszy;
+// integralnosc sprawdza staly IV A6A6A6A6A6A6A6A6 (porownanie w stalym czasie).
+const DEFAULT_IV: [u8; 8] = [0xa6; 8];
+const SEMIBLOCK: usize = 8;
+
+fn check_key_len(len: usize, what: &str) -> Result<(), CryptoError> {
+    if len < 2 * SEMIBLOCK || !len.is_multiple_of(SEMIBLOCK) {
+        return Err(CryptoError::InvalidLength(format!("{what} must be a multiple of 8 bytes and at least 16")));
+    }
+    Ok(())
+}
+
+pub fn aes_kw_wrap_bytes(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    require_approved("aes-kw")?;
+    check_key_len(key.len(), "wrapped key")?;
+    let aes = Aes::new(kek)?;
+    let n = key.len() / SEMIBLOCK;
+    let mut a = DEFAULT_IV;
+    let mut r = Zeroizing::new(key.to_vec());
+    let mut block = [0u8; 16];
+    for j in 0..6 {
+        for (i, ri) in r.chunks_exact_mut(SEMIBLOCK).enumerate() {
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(ri);
+            aes.encrypt_block(&mut block);
+            let t = (n * j + i + 1) as u64;
+            for (a, (b, t)) in a.iter_mut().zip(block[..8].iter().zip(t.to_be_bytes())) {
+                *a = b ^ t;
+            }
+            ri.copy_from_slice(&block[8..]);
+        }
+    }
+    block.zeroize();
+    let mut out = a.to_vec();
+    out.extend_from_slice(&r);
+    Ok(out)
+}
+
+pub fn aes_kw_unwrap_bytes(kek: &[u8], wrapped: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    require_approved("aes-kw")?;
+    check_key_len(wrapped.len().saturating_sub(SEMIBLOCK), "unwrapped key")?;
+    let aes = Aes::new(kek)?;
+    let n = wrapped.len() / SEMIBLOCK - 1;
+    let mut a = [0u8; SEMIBLOCK];
+    a.copy_from_slice(&wrapped[..SEMIBLOCK]);
+    let mut r = Zeroizing::new(wrapped[SEMIBLOCK..].to_vec());
+    let mut block = [0u8; 16];
+    for j in (0..6).rev() {
+        for (i, ri) in r.chunks_exact_mut(SEMIBLOCK).enumerate().rev() {
+            let t = (n * j + i + 1) as u64;
+            for (b, (a, t)) in block[..8].iter_mut().zip(a.iter().zip(t.to_be_bytes())) {
+                *b = a ^ t;
+            }
+            block[8..].copy_from_slice(ri);
+            aes.decrypt_block(&mut block);
+            a.copy_from_slice(&block[..8]);
+            ri.copy_from_slice(&block[8..]);
+        }
+    }
+    block.zeroize();
+    if !ct_eq(&a, &DEFAULT_IV) {
+        return Err(CryptoError::DecryptFailed("aes key wrap integrity check failed".to_string()));
+    }
+    Ok(r)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn aes_kw_wrap(kek: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    aes_kw_wrap_bytes(kek, key)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn aes_kw_unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut key = aes_kw_unwrap_bytes(kek, wrapped)?;
+    Ok(std::mem::take(&mut *key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+
+    const KEY_DATA: &str = "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f";
+
+    // RFC 3394 4.1-4.6: (dlugosc KEK, dlugosc klucza, wynik)
+    const VECTORS: [(usize, usize, &str); 6] = [
+        (16, 16, "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5"),
+        (24, 16, "96778b25ae6ca435f92b5b97c050aed2468ab8a17ad84e5d"),
+        (32, 16, "64e8c3f9ce0f5ba263e9777905818a2a93c8191e7d6e8ae7"),
+        (24, 24, "031d33264e15d33268f24ec260743edce1c6c7ddee725a936ba814915c6762d2"),
+        (32, 24, "a8f9bc1612c68b3ff6e6f4fbe30e71e4769c8b80a32cb8958cd5d17d6b254da1"),
+        (32, 32, "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21"),
+    ];
+
+    fn kek(len: usize) -> Vec<u8> {
+        (0..len as u8).collect()
+    }
+
+    #[test]
+    fn rfc3394_vectors() -> Result<(), CryptoError> {
+        let key_data = hex_to_bytes(KEY_DATA)?;
+        for (kek_len, key_len, expected) in VECTORS {
+            let wrapped = aes_kw_wrap_bytes(&kek(kek_len), &key_data[..key_len])?;
+            assert_eq!(wrapped, hex_to_bytes(expected)?);
+            assert_eq!(*aes_kw_unwrap_bytes(&kek(kek_len), &wrapped)?, &key_data[..key_len]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_tampered_wrap() -> Result<(), CryptoError> {
+        let mut wrapped = hex_to_bytes(VECTORS[0].2)?;
+        wrapped[10] ^= 1;
+        assert!(matches!(aes_kw_unwrap_bytes(&kek(16), &wrapped), Err(CryptoError::DecryptFailed(_))));
+        let wrapped = hex_to_bytes(VECTORS[0].2)?;
+        assert!(matches!(aes_kw_unwrap_bytes(&kek(24), &wrapped), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_key_lengths() -> Result<(), CryptoError> {
+        let key_data = hex_to_bytes(KEY_DATA)?;
+        for len in [0, 8, 15, 17, 20] {
+            assert!(matches!(aes_kw_wrap_bytes(&kek(16), &key_data[..len]), Err(CryptoError::InvalidLength(_))));
+        }
+        let wrapped = hex_to_bytes(VECTORS[0].2)?;
+        for len in [0, 16, 23] {
+            assert!(matches!(aes_kw_unwrap_bytes(&kek(16), &wrapped[..len]), Err(CryptoError::InvalidLength(_))));
+        }
+        assert!(matches!(aes_kw_wrap_bytes(&kek(20), &key_data[..16]), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use serde::Deserialize;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::aead::AeadAlgorithm;
+use crate::encoding::{base64_to_bytes, bytes_to_hex};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hmac::{hmac_sha256_bytes, hmac_sha512_bytes};
+use crate::migrate::seal_envelope_bytes;
+use crate::pbkdf2::{Pbkdf2Layout, pbkdf2_hmac_sha256_layout_bytes, pbkdf2_hmac_sha512_layout_bytes};
+use crate::secret::{EntryKey, MasterKey};
+use crate::sha2::{sha256_bytes, sha512_bytes};
+
+// Zgodnosc ze starym API string -> hex (sha256, hmac_sha256, pbkdf2_hmac_sha256, ...).
+// Wejscie to zawsze bajty UTF-8 stringa, wyjscie - maly hex. Tych funkcji nie zmieniamy,
+// nawet gdy nowe API sie rozjedzie - zapisane dane zaleza od dokladnie tego zachowania.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn legacy_sha256(input: &str) -> String {
+    bytes_to_hex(&sha256_bytes(input.as_bytes()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn legacy_sha512(input: &str) -> String {
+    bytes_to_hex(&sha512_bytes(input.as_bytes()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn legacy_hmac_sha256(key: &str, input: &str) -> String {
+    bytes_to_hex(&hmac_sha256_bytes(key.as_bytes(), input.as_bytes()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn legacy_hmac_sha512(key: &str, input: &str) -> String {
+    bytes_to_hex(&hmac_sha512_bytes(key.as_bytes(), input.as_bytes()))
+}
+
+// Stary kod obcinal pierwszy blok T_1 zamiast ostatniego (zob. Pbkdf2Layout::V0).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn legacy_pbkdf2_hmac_sha256(password: &str, salt: &str, iterations: u32, dk_len: usize) -> Result<String, CryptoError> {
+    let dk = Zeroizing::new(pbkdf2_hmac_sha256_layout_bytes(
+        password.as_bytes(),
+        salt.as_bytes(),
+        iterations,
+        dk_len,
+        Pbkdf2Layout::V0,
+    )?);
+    Ok(bytes_to_hex(&dk))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn legacy_pbkdf2_hmac_sha512(password: &str, salt: &str, iterations: u32, dk_len: usize) -> Result<String, CryptoError> {
+    let dk = Zeroizing::new(pbkdf2_hmac_sha512_layout_bytes(
+        password.as_bytes(),
+        salt.as_bytes(),
+        iterations,
+        dk_len,
+        Pbkdf2Layout::V0,
+    )?);
+    Ok(bytes_to_hex(&dk))
+}
+
+// Klucz vaulta tak jak liczy go klient: PBKDF2-SHA256(haslo || secret key, sol jako
+// tekst base64 - nie dekodowana!), 32 bajty, hex -> bajty.
+pub fn legacy_vault_key_bytes(master_password: &str, secret_key: &str, salt: &str, iterations: u32) -> Result<MasterKey, CryptoError> {
+    let password = Zeroizing::new(format!("{master_password}{secret_key}"));
+    let key = pbkdf2_hmac_sha256_layout_bytes(password.as_bytes(), salt.as_bytes(), iterations, 32, Pbkdf2Layout::V0)?;
+    Ok(MasterKey::from_vec(key))
+}
+
+// Stary rekord: trzy pola base64 z AES-256-GCM bez AAD. Nazwy jak w pliku vaulta.
+#[derive(Deserialize)]
+struct LegacyRecord {
+    #[serde(alias = "vault_iv")]
+    iv: String,
+    #[serde(alias = "vault_ciphertext")]
+    ciphertext: String,
+    #[serde(alias = "vault_tag")]
+    tag: String,
+}
+
+pub fn open_legacy_record(key: &MasterKey, record: &str) -> Result<Vec<u8>, CryptoError> {
+    let record: LegacyRecord =
+        serde_json::from_str(record).map_err(|e| CryptoError::InvalidInput(format!("malformed legacy record: {e}")))?;
+    let iv = base64_to_bytes(&record.iv)?;
+    let mut sealed = base64_to_bytes(&record.ciphertext)?;
+    let tag = base64_to_bytes(&record.tag)?;
+    if iv.len() != NONCE_LEN || tag.len() != TAG_LEN {
+        return Err(CryptoError::CorruptHeader("legacy record has wrong iv or tag length".to_string()));
+    }
+    sealed.extend_from_slice(&tag);
+    AesGcm::new(key.as_bytes())?.decrypt(&iv, &[], &sealed)
+}
+
+// Rekord -> koperta PMCT (zob. migrate.rs) pod tym samym kluczem.
+pub fn migrate_legacy_record_bytes(key: &MasterKey, record: &str) -> Result<Vec<u8>, CryptoError> {
+    let plaintext = Zeroizing::new(open_legacy_record(key, record)?);
+    // stary rekord byl szyfrowany wprost kluczem vaulta - ten sam klucz zamyka koperte
+    seal_envelope_bytes(AeadAlgorithm::Aes256Gcm, &EntryKey::from_slice(key.as_bytes()), &[], &plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn legacy_vault_key(master_password: &str, secret_key: &str, salt: &str, iterations: u32) -> Result<Vec<u8>, CryptoError> {
+    Ok(legacy_vault_key_bytes(master_password, secret_key, salt, iterations)?.as_bytes().to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn migrate_legacy_record(key: &[u8], record: &str) -> Result<Vec<u8>, CryptoError> {
+    migrate_legacy_record_bytes(&MasterKey::from_slice(key), record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_base64;
+    use crate::migrate::open_ciphertext_bytes;
+
+    // Wektory policzone kodem z commita bazowego (559a6178).
+    #[test]
+    fn pbkdf2_matches_baseline_vectors() -> Result<(), CryptoError> {
+        assert_eq!(
+            legacy_pbkdf2_hmac_sha256("password", "saltSALTsaltSALT", 1000, 32)?,
+            "46f895baaa185f0265c1da708d799953de592f576c4538b7eba99ced700ee75b"
+        );
+        assert_eq!(
+            legacy_pbkdf2_hmac_sha256("correct horse", "legacy-salt-0001", 1000, 50)?,
+            "28211ade37672fca9af071a936825a847055c4e624c7ddc6e59510d60a4608afb68859ec3976447b87aef9b8da76602ed774"
+        );
+        assert_eq!(
+            legacy_pbkdf2_hmac_sha512("password", "saltSALTsaltSALT", 1000, 80)?,
+            "2febe385f6399aa2a20a9926d1822e2f4f5beb92667533312cad0824889d591dd2b306081990f5b010ad427e6be2c1e3\
+             26307f2f4e799602f864dc9ce7684bfee25d22f2c5e9310ecc7b385327b8c7e2"
+        );
+        assert_eq!(
+            legacy_pbkdf2_hmac_sha512("correct horse", "legacy-salt-0001", 1000, 100)?,
+            "a54501ec4123a8271235cb8038fc7bbc4efe76975b8372bd12db135318ad167b220405f5758d811df9f91882f469d4f9\
+             b2d19ddb912f5e12815a7b953c5a48fc373c06f0afcc20998f6a54464ed9c6437966e113a453af7fed75b95cfa3bc2\
+             50d50ee758"
+        );
+        assert!(matches!(legacy_pbkdf2_hmac_sha256("password", "saltSALTsaltSALT", 1000, 0), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+
+    fn record(key: &MasterKey, plaintext: &[u8]) -> Result<String, CryptoError> {
+        let iv = [9u8; NONCE_LEN];
+        let sealed = AesGcm::new(key.as_bytes())?.encrypt(&iv, &[], plaintext)?;
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - TAG_LEN);
+        Ok(format!(
+            r#"{{"vault_iv":"{}","vault_ciphertext":"{}","vault_tag":"{}"}}"#,
+            bytes_to_base64(&iv),
+            bytes_to_base64(ciphertext),
+            bytes_to_base64(tag)
+        ))
+    }
+
+    #[test]
+    fn legacy_record_migrates() -> Result<(), CryptoError> {
+        let key = legacy_vault_key_bytes("master", "A3-SECRET", "saltSALTsaltSALT", 1000)?;
+        let record = record(&key, b"{\"items\":[]}")?;
+        assert_eq!(open_legacy_record(&key, &record)?, b"{\"items\":[]}");
+        let envelope = migrate_legacy_record_bytes(&key, &record)?;
+        let envelope_key = EntryKey::from_slice(key.as_bytes());
+        assert_eq!(open_ciphertext_bytes(&envelope_key, &[], &envelope)?, b"{\"items\":[]}");
+        Ok(())
+    }
+
+    #[test]
+    fn bad_legacy_record_is_rejected() -> Result<(), CryptoError> {
+        let key = MasterKey::from_slice(&[1u8; 32]);
+        let record = record(&key, b"secret")?;
+        assert!(open_legacy_record(&MasterKey::from_slice(&[2u8; 32]), &record).is_err());
+        assert!(matches!(open_legacy_record(&key, "{\"iv\":\"AAAA\"}"), Err(CryptoError::InvalidInput(_))));
+        let short_iv = record.replace(&bytes_to_base64(&[9u8; NONCE_LEN]), &bytes_to_base64(&[9u8; 8]));
+        assert!(matches!(open_legacy_record(&key, &short_iv), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+}
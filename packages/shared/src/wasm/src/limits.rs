@@ -0,0 +1,132 @@
+use std::sync::RwLock;
+
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+
+// Gorne limity parametrow KDF sprawdzane przed rozpoczeciem obliczen, zeby
+// zlosliwy naglowek vaulta nie mogl zawiesic klienta (np. 2^31 iteracji PBKDF2).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfLimits {
+    pub max_iterations: u32,
+    // Przebiegi Argon2 i koszt bcrypt maja osobne limity - kazdy przebieg Argon2 przechodzi
+    // przez cala pamiec, wiec limit iteracji PBKDF2 pozwalalby na tygodnie obliczen.
+    pub max_argon2_iterations: u32,
+    pub max_bcrypt_cost: u32,
+    pub max_memory_kib: u32,
+    pub max_dk_len: u32,
+    pub max_salt_len: u32,
+}
+
+pub const DEFAULT_KDF_LIMITS: KdfLimits = KdfLimits {
+    max_iterations: 10_000_000,
+    max_argon2_iterations: 16,
+    max_bcrypt_cost: 16,
+    max_memory_kib: 256 * 1024,
+    max_dk_len: 1024,
+    max_salt_len: 1024,
+};
+
+impl Default for KdfLimits {
+    fn default() -> Self {
+        DEFAULT_KDF_LIMITS
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl KdfLimits {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new() -> KdfLimits {
+        DEFAULT_KDF_LIMITS
+    }
+}
+
+static LIMITS: RwLock<KdfLimits> = RwLock::new(DEFAULT_KDF_LIMITS);
+
+pub fn kdf_limits() -> KdfLimits {
+    *LIMITS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn get_kdf_limits() -> KdfLimits {
+    kdf_limits()
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn set_kdf_limits(limits: &KdfLimits) -> Result<(), CryptoError> {
+    if limits.max_iterations == 0
+        || limits.max_argon2_iterations == 0
+        || limits.max_bcrypt_cost == 0
+        || limits.max_memory_kib == 0
+        || limits.max_dk_len == 0
+        || limits.max_salt_len == 0
+    {
+        return Err(CryptoError::OutOfRange("kdf limits must be non-zero".to_string()));
+    }
+    *LIMITS.write().unwrap_or_else(|e| e.into_inner()) = *limits;
+    Ok(())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn reset_kdf_limits() {
+    *LIMITS.write().unwrap_or_else(|e| e.into_inner()) = DEFAULT_KDF_LIMITS;
+}
+
+pub fn check_iterations(iterations: u32) -> Result<(), CryptoError> {
+    let max = kdf_limits().max_iterations;
+    if iterations == 0 {
+        return Err(CryptoError::InvalidIterations("iterations must be at least 1".to_string()));
+    }
+    if iterations > max {
+        return Err(CryptoError::InvalidIterations(format!("iterations {iterations} exceed limit {max}")));
+    }
+    Ok(())
+}
+
+pub fn check_argon2_iterations(iterations: u32) -> Result<(), CryptoError> {
+    let max = kdf_limits().max_argon2_iterations;
+    if iterations == 0 {
+        return Err(CryptoError::InvalidIterations("argon2 iterations must be at least 1".to_string()));
+    }
+    if iterations > max {
+        return Err(CryptoError::InvalidIterations(format!("argon2 iterations {iterations} exceed limit {max}")));
+    }
+    Ok(())
+}
+
+pub fn check_bcrypt_cost(cost: u32) -> Result<(), CryptoError> {
+    let max = kdf_limits().max_bcrypt_cost;
+    if cost > max {
+        return Err(CryptoError::InvalidIterations(format!("bcrypt cost {cost} exceeds limit {max}")));
+    }
+    Ok(())
+}
+
+pub fn check_memory_kib(memory_kib: u64) -> Result<(), CryptoError> {
+    let max = kdf_limits().max_memory_kib;
+    if memory_kib > max as u64 {
+        return Err(CryptoError::OutOfRange(format!("memory {memory_kib} KiB exceeds limit {max} KiB")));
+    }
+    Ok(())
+}
+
+pub fn check_dk_len(dk_len: usize) -> Result<(), CryptoError> {
+    let max = kdf_limits().max_dk_len;
+    if dk_len == 0 {
+        return Err(CryptoError::OutOfRange("derived key length must be positive".to_string()));
+    }
+    if dk_len > max as usize {
+        return Err(CryptoError::OutOfRange(format!("derived key length {dk_len} exceeds limit {max}")));
+    }
+    Ok(())
+}
+
+pub fn check_salt_len(salt_len: usize) -> Result<(), CryptoError> {
+    let max = kdf_limits().max_salt_len;
+    if salt_len > max as usize {
+        return Err(CryptoError::OutOfRange(format!("salt length {salt_len} exceeds limit {max}")));
+    }
+    Ok(())
+}
@@ -0,0 +1,274 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+use crate::secret::MasterKey;
+use crate::vault::Vault;
+
+// Kilka vaultow (np. prywatny i sluzbowy) w jednej sesji aplikacji. Kazdy ma wlasny klucz
+// i hierarchie kluczy, blokuje sie niezaleznie (wlasny limit bezczynnosci), a przeniesienie
+// wpisu odszyfrowuje go kluczem zrodla i szyfruje kluczami celu - oba musza byc odblokowane.
+// JS nie dostaje referencji do obiektow Vault, wszystkie operacje ida przez identyfikator.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Default)]
+pub struct VaultManager {
+    vaults: Vec<Vault>,
+}
+
+impl VaultManager {
+    pub fn add(&mut self, vault: Vault) -> Result<(), CryptoError> {
+        if self.vaults.iter().any(|v| v.id() == vault.id()) {
+            return Err(CryptoError::InvalidInput(format!("vault {} is already open", vault.id())));
+        }
+        self.vaults.push(vault);
+        Ok(())
+    }
+
+    pub fn vault(&self, vault_id: &str) -> Result<&Vault, CryptoError> {
+        self.vaults
+            .iter()
+            .find(|v| v.id() == vault_id)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("unknown vault {vault_id}")))
+    }
+
+    pub fn vault_mut(&mut self, vault_id: &str) -> Result<&mut Vault, CryptoError> {
+        self.vaults
+            .iter_mut()
+            .find(|v| v.id() == vault_id)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("unknown vault {vault_id}")))
+    }
+
+    // Zapis do celu przed usunieciem ze zrodla - przy bledzie zrodlo zostaje nietkniete.
+    pub fn move_entry_between(&mut self, from: &str, to: &str, entry_id: &str) -> Result<(), CryptoError> {
+        if from == to {
+            return Err(CryptoError::InvalidInput("source and destination vault are the same".to_string()));
+        }
+        let plaintext = self.vault(from)?.open_entry(entry_id)?;
+        let target = self.vault_mut(to)?;
+        if target.contains_entry(entry_id) {
+            return Err(CryptoError::InvalidInput(format!("entry {entry_id} already exists in vault {to}")));
+        }
+        let blob = target.seal_entry(entry_id, &plaintext)?;
+        target.insert_sealed(entry_id, blob);
+        self.vault_mut(from)?.remove_entry(entry_id)?;
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl VaultManager {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new() -> VaultManager {
+        VaultManager::default()
+    }
+
+    // lock_timeout w sekundach, 0 = bez automatycznej blokady. now - jak w tick.
+    pub fn create_vault(
+        &mut self,
+        vault_id: &str,
+        vault_key: &[u8],
+        lock_timeout: u32,
+        now: u64,
+    ) -> Result<(), CryptoError> {
+        let mut vault = Vault::create(vault_id, &MasterKey::from_slice(vault_key), now)?;
+        vault.set_lock_timeout(lock_timeout);
+        self.add(vault)
+    }
+
+    // Dodaje zapisany vault (zablokowany). Zwraca jego identyfikator.
+    pub fn load_vault(&mut self, sealed: &str) -> Result<String, CryptoError> {
+        let vault = Vault::load(sealed)?;
+        let id = vault.id();
+        self.add(vault)?;
+        Ok(id)
+    }
+
+    pub fn export_vault(&self, vault_id: &str) -> Result<String, CryptoError> {
+        self.vault(vault_id)?.export_sealed()
+    }
+
+    // Zamyka vault w tej sesji (klucze i wpisy znikaja z pamieci).
+    pub fn close_vault(&mut self, vault_id: &str) -> bool {
+        let before = self.vaults.len();
+        self.vaults.retain(|v| v.id() != vault_id);
+        self.vaults.len() != before
+    }
+
+    pub fn vault_ids(&self) -> Vec<String> {
+        self.vaults.iter().map(Vault::id).collect()
+    }
+
+    pub fn unlock(&mut self, vault_id: &str, vault_key: &[u8], now: u64) -> Result<(), CryptoError> {
+        self.vault_mut(vault_id)?.unlock(vault_key, now)
+    }
+
+    pub fn lock(&mut self, vault_id: &str) -> Result<(), CryptoError> {
+        self.vault_mut(vault_id)?.lock();
+        Ok(())
+    }
+
+    pub fn lock_all(&mut self) {
+        for vault in self.vaults.iter_mut() {
+            vault.lock();
+        }
+    }
+
+    pub fn is_locked(&self, vault_id: &str) -> Result<bool, CryptoError> {
+        Ok(self.vault(vault_id)?.locked())
+    }
+
+    pub fn set_lock_timeout(&mut self, vault_id: &str, seconds: u32) -> Result<(), CryptoError> {
+        self.vault_mut(vault_id)?.set_lock_timeout(seconds);
+        Ok(())
+    }
+
+    pub fn touch(&mut self, vault_id: &str, now: u64) -> Result<(), CryptoError> {
+        self.vault_mut(vault_id)?.touch(now);
+        Ok(())
+    }
+
+    // Wywolywane z timera aplikacji. Zwraca identyfikatory vaultow zablokowanych w tym kroku.
+    pub fn tick(&mut self, now: u64) -> Vec<String> {
+        self.vaults.iter_mut().filter_map(|v| v.lock_if_idle(now).then(|| v.id())).collect()
+    }
+
+    pub fn put_entry(&mut self, vault_id: &str, entry_id: &str, entry: &str) -> Result<(), CryptoError> {
+        self.vault_mut(vault_id)?.put_entry(entry_id, entry)
+    }
+
+    pub fn get_entry(&self, vault_id: &str, entry_id: &str) -> Result<String, CryptoError> {
+        self.vault(vault_id)?.get_entry(entry_id)
+    }
+
+    pub fn remove_entry(&mut self, vault_id: &str, entry_id: &str) -> Result<bool, CryptoError> {
+        self.vault_mut(vault_id)?.remove_entry(entry_id)
+    }
+
+    pub fn entry_ids(&self, vault_id: &str) -> Result<Vec<String>, CryptoError> {
+        Ok(self.vault(vault_id)?.entry_ids())
+    }
+
+    // Zdarzenia zmian jednego vaulta, zob. Vault::subscribe.
+    #[cfg(feature = "js")]
+    pub fn subscribe(&mut self, vault_id: &str, callback: js_sys::Function) -> Result<u32, CryptoError> {
+        Ok(self.vault_mut(vault_id)?.subscribe(callback))
+    }
+
+    pub fn unsubscribe(&mut self, vault_id: &str, subscription: u32) -> Result<bool, CryptoError> {
+        Ok(self.vault_mut(vault_id)?.unsubscribe(subscription))
+    }
+
+    pub fn move_entry(&mut self, from_vault: &str, to_vault: &str, entry_id: &str) -> Result<(), CryptoError> {
+        self.move_entry_between(from_vault, to_vault, entry_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERSONAL_KEY: [u8; 32] = [0x11; 32];
+    const WORK_KEY: [u8; 32] = [0x22; 32];
+
+    fn manager() -> Result<VaultManager, CryptoError> {
+        let mut manager = VaultManager::new();
+        manager.create_vault("personal", &PERSONAL_KEY, 0, 1000)?;
+        manager.create_vault("work", &WORK_KEY, 300, 1000)?;
+        Ok(manager)
+    }
+
+    #[test]
+    fn vault_ids_must_be_unique() -> Result<(), CryptoError> {
+        let mut manager = manager()?;
+        assert!(matches!(manager.create_vault("work", &PERSONAL_KEY, 0, 1000), Err(CryptoError::InvalidInput(_))));
+        let sealed = manager.export_vault("personal")?;
+        assert!(matches!(manager.load_vault(&sealed), Err(CryptoError::InvalidInput(_))));
+        assert_eq!(manager.vault_ids(), ["personal", "work"]);
+        assert!(matches!(manager.vault("missing"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn timers_start_at_creation_and_unlock() -> Result<(), CryptoError> {
+        let mut manager = manager()?;
+        assert!(manager.tick(1001).is_empty());
+        assert!(manager.tick(1299).is_empty());
+        manager.touch("work", 1200)?;
+        assert!(manager.tick(1499).is_empty());
+        assert_eq!(manager.tick(1500), ["work"]);
+        assert!(manager.is_locked("work")?);
+        assert!(!manager.is_locked("personal")?);
+        assert!(manager.tick(1_000_000).is_empty());
+
+        assert!(matches!(manager.unlock("work", &PERSONAL_KEY, 2000), Err(CryptoError::WrongPassword(_))));
+        manager.unlock("work", &WORK_KEY, 2000)?;
+        assert!(manager.tick(2001).is_empty());
+        assert_eq!(manager.tick(2300), ["work"]);
+        Ok(())
+    }
+
+    #[test]
+    fn loaded_vault_is_locked_until_unlocked() -> Result<(), CryptoError> {
+        let mut manager = manager()?;
+        manager.put_entry("work", "login", "secret")?;
+        let sealed = manager.export_vault("work")?;
+        assert!(manager.close_vault("work"));
+        assert!(!manager.close_vault("work"));
+        assert_eq!(manager.load_vault(&sealed)?, "work");
+        assert!(manager.is_locked("work")?);
+        assert!(manager.get_entry("work", "login").is_err());
+        manager.unlock("work", &WORK_KEY, 5000)?;
+        assert!(manager.tick(5001).is_empty());
+        assert_eq!(manager.get_entry("work", "login")?, "secret");
+        Ok(())
+    }
+
+    #[test]
+    fn move_entry_reencrypts_under_the_target_vault() -> Result<(), CryptoError> {
+        let mut manager = manager()?;
+        manager.put_entry("personal", "login", "secret")?;
+        manager.move_entry("personal", "work", "login")?;
+        assert!(manager.entry_ids("personal")?.is_empty());
+        assert_eq!(manager.get_entry("work", "login")?, "secret");
+
+        // Zaszyfrowany kluczem celu - otwiera sie po eksporcie i ponownym wczytaniu tylko nim
+        let sealed = manager.export_vault("work")?;
+        let mut reloaded = Vault::load(&sealed)?;
+        reloaded.unlock(&WORK_KEY, 0)?;
+        assert_eq!(reloaded.get_entry("login")?, "secret");
+        Ok(())
+    }
+
+    #[test]
+    fn move_entry_rejects_duplicates_and_locked_vaults() -> Result<(), CryptoError> {
+        let mut manager = manager()?;
+        manager.put_entry("personal", "login", "personal secret")?;
+        manager.put_entry("work", "login", "work secret")?;
+        assert!(matches!(manager.move_entry("personal", "work", "login"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(manager.move_entry("personal", "personal", "login"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(manager.move_entry("personal", "missing", "login"), Err(CryptoError::InvalidInput(_))));
+        assert_eq!(manager.get_entry("work", "login")?, "work secret");
+
+        manager.remove_entry("work", "login")?;
+        manager.lock("work")?;
+        assert!(matches!(manager.move_entry("personal", "work", "login"), Err(CryptoError::InvalidInput(_))));
+        manager.unlock("work", &WORK_KEY, 1000)?;
+        manager.lock("personal")?;
+        assert!(matches!(manager.move_entry("personal", "work", "login"), Err(CryptoError::InvalidInput(_))));
+
+        // Nieudane przeniesienia zostawiaja zrodlo nietkniete
+        manager.unlock("personal", &PERSONAL_KEY, 1000)?;
+        assert_eq!(manager.get_entry("personal", "login")?, "personal secret");
+        assert!(manager.entry_ids("work")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn lock_all_locks_every_vault() -> Result<(), CryptoError> {
+        let mut manager = manager()?;
+        manager.lock_all();
+        assert!(manager.is_locked("personal")? && manager.is_locked("work")?);
+        assert!(manager.put_entry("personal", "login", "secret").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::ed25519::{sign_bytes, verify_strict};
+use crate::error::CryptoError;
+use crate::jcs::{canonicalize_json_str, canonicalize_value};
+
+// Manifest vaulta podpisany Ed25519 przez wlasciciela: lista id wpisow z licznikami wersji
+// i rewizja calego manifestu. Serwer synchronizacji nie zna klucza podpisu, wiec nie moze
+// po cichu usunac wpisu ani podac starszej wersji - klient porownuje to, co dostal, z
+// manifestem, a manifest z poprzednio zweryfikowanym (rewizja i liczniki nie maleja).
+// Podpis nad postacia kanoniczna JSON (RFC 8785), wiec kolejnosc kluczy i biale znaki
+// z serwera nie maja znaczenia. Liczniki ograniczone do 2^53 - 1, zeby JS ich nie zaokraglil.
+const MANIFEST_SIGNATURE_LABEL: &[u8] = b"pm-vault-manifest-v1\0";
+pub const MANIFEST_VERSION: u32 = 1;
+const MAX_COUNTER: u64 = (1 << 53) - 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultManifest {
+    pub version: u32,
+    pub vault_id: String,
+    pub revision: u64,
+    pub items: BTreeMap<String, u64>,
+}
+
+impl VaultManifest {
+    pub fn canonical(&self) -> Result<String, CryptoError> {
+        let value = serde_json::to_value(self).map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        canonicalize_value(&value)
+    }
+
+    // Poprzedni manifest musi byc tego samego vaulta, a nowy nie moze cofac rewizji ani
+    // licznikow wpisow, ktore nadal istnieja (usuniecie wpisu jest podpisane, wiec dozwolone).
+    pub fn check_successor(&self, previous: &VaultManifest) -> Result<(), CryptoError> {
+        if self.vault_id != previous.vault_id {
+            return Err(CryptoError::VerificationFailed("manifest belongs to a different vault".to_string()));
+        }
+        if self.revision < previous.revision {
+            return Err(CryptoError::VerificationFailed(format!(
+                "manifest rolled back from revision {} to {}",
+                previous.revision, self.revision
+            )));
+        }
+        for (id, counter) in &self.items {
+            if let Some(&known) = previous.items.get(id)
+                && *counter < known
+            {
+                return Err(CryptoError::VerificationFailed(format!(
+                    "item {id} rolled back from {known} to {counter}"
+                )));
+            }
+        }
+        if self.revision == previous.revision && self != previous {
+            return Err(CryptoError::VerificationFailed(format!("two manifests with revision {}", self.revision)));
+        }
+        Ok(())
+    }
+
+    // Id wpisow, ktore nie zgadzaja sie z manifestem: brakujace, w innej wersji albo nadmiarowe.
+    pub fn mismatched_items(&self, items: &BTreeMap<String, u64>) -> Vec<String> {
+        let mut out: Vec<String> = self
+            .items
+            .iter()
+            .filter(|(id, counter)| items.get(*id) != Some(counter))
+            .map(|(id, _)| id.clone())
+            .collect();
+        out.extend(items.keys().filter(|id| !self.items.contains_key(*id)).cloned());
+        out.sort();
+        out
+    }
+}
+
+fn signed_message(canonical: &str) -> Vec<u8> {
+    let mut msg = MANIFEST_SIGNATURE_LABEL.to_vec();
+    msg.extend_from_slice(canonical.as_bytes());
+    msg
+}
+
+fn malformed(e: impl std::fmt::Display) -> CryptoError {
+    CryptoError::InvalidInput(format!("malformed manifest: {e}"))
+}
+
+// Zwraca manifest i jego postac kanoniczna; powtorzone klucze sa odrzucane przez JCS.
+pub fn parse_manifest(payload: &str) -> Result<(VaultManifest, String), CryptoError> {
+    let canonical = canonicalize_json_str(payload)?;
+    let manifest: VaultManifest = serde_json::from_str(&canonical).map_err(malformed)?;
+    if manifest.version != MANIFEST_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("manifest version {}", manifest.version)));
+    }
+    if manifest.revision > MAX_COUNTER || manifest.items.values().any(|&c| c > MAX_COUNTER) {
+        return Err(CryptoError::OutOfRange("manifest counters must be below 2^53".to_string()));
+    }
+    Ok((manifest, canonical))
+}
+
+pub fn sign_manifest_bytes(manifest: &VaultManifest, signing_key: &[u8; 32]) -> Result<[u8; 64], CryptoError> {
+    let (_, canonical) = parse_manifest(&manifest.canonical()?)?;
+    Ok(sign_bytes(signing_key, &signed_message(&canonical)))
+}
+
+pub fn verify_manifest_bytes(
+    payload: &str,
+    signature: &[u8],
+    public_key: &[u8],
+    previous: Option<&VaultManifest>,
+) -> Result<VaultManifest, CryptoError> {
+    let (manifest, canonical) = parse_manifest(payload)?;
+    verify_strict(public_key, &signed_message(&canonical), signature)?;
+    if let Some(previous) = previous {
+        manifest.check_successor(previous)?;
+    }
+    Ok(manifest)
+}
+
+fn parse_items(items: &str) -> Result<BTreeMap<String, u64>, CryptoError> {
+    serde_json::from_str(items).map_err(|e| CryptoError::InvalidInput(format!("malformed item versions: {e}")))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn sign_manifest(signing_key: &[u8], manifest: &str) -> Result<Vec<u8>, CryptoError> {
+    let (manifest, _) = parse_manifest(manifest)?;
+    let mut key: [u8; 32] = signing_key
+        .try_into()
+        .map_err(|_| CryptoError::InvalidLength("ed25519 secret key must be 32 bytes".to_string()))?;
+    let signature = sign_manifest_bytes(&manifest, &key);
+    key.zeroize();
+    Ok(signature?.to_vec())
+}
+
+// previous - ostatni zweryfikowany manifest tego vaulta (JSON), jesli klient go ma.
+// Zwraca zweryfikowany manifest w postaci kanonicznej.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_manifest(
+    public_key: &[u8],
+    manifest: &str,
+    signature: &[u8],
+    previous: Option<String>,
+) -> Result<String, CryptoError> {
+    let previous = previous.as_deref().map(parse_manifest).transpose()?.map(|(previous, _)| previous);
+    verify_manifest_bytes(manifest, signature, public_key, previous.as_ref())?.canonical()
+}
+
+// items - obiekt JSON { id wpisu: licznik wersji } z tego, co zwrocil serwer.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn manifest_mismatches(manifest: &str, items: &str) -> Result<Vec<String>, CryptoError> {
+    let (manifest, _) = parse_manifest(manifest)?;
+    Ok(manifest.mismatched_items(&parse_items(items)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ed25519::public_key_bytes;
+
+    const SECRET: [u8; 32] = [9u8; 32];
+
+    fn manifest(revision: u64, items: &[(&str, u64)]) -> VaultManifest {
+        VaultManifest {
+            version: MANIFEST_VERSION,
+            vault_id: "vault-1".to_string(),
+            revision,
+            items: items.iter().map(|(id, counter)| (id.to_string(), *counter)).collect(),
+        }
+    }
+
+    fn signed(manifest: &VaultManifest) -> Result<(String, [u8; 64]), CryptoError> {
+        Ok((manifest.canonical()?, sign_manifest_bytes(manifest, &SECRET)?))
+    }
+
+    #[test]
+    fn accepts_signed_successor() -> Result<(), CryptoError> {
+        let public = public_key_bytes(&SECRET);
+        let previous = manifest(1, &[("a", 1), ("b", 1)]);
+        // Nowa rewizja: wyzszy licznik, podpisane usuniecie "b" i nowy wpis "c"
+        let next = manifest(2, &[("a", 2), ("c", 1)]);
+        let (payload, signature) = signed(&next)?;
+        assert_eq!(verify_manifest_bytes(&payload, &signature, &public, Some(&previous))?, next);
+        assert_eq!(verify_manifest_bytes(&payload, &signature, &public, Some(&next))?, next);
+
+        // Kolejnosc kluczy i biale znaki nie wplywaja na podpis
+        let reordered = r#"{ "items": {"c": 1, "a": 2}, "revision": 2, "vault_id": "vault-1", "version": 1 }"#;
+        assert_eq!(verify_manifest_bytes(reordered, &signature, &public, Some(&previous))?, next);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_rollback_and_fork() -> Result<(), CryptoError> {
+        let previous = manifest(5, &[("a", 3)]);
+        let rolled_back = manifest(4, &[("a", 3)]).check_successor(&previous);
+        assert!(matches!(rolled_back, Err(CryptoError::VerificationFailed(_))));
+        let item_rolled_back = manifest(6, &[("a", 2)]).check_successor(&previous);
+        assert!(matches!(item_rolled_back, Err(CryptoError::VerificationFailed(_))));
+        let fork = manifest(5, &[("a", 4)]).check_successor(&previous);
+        assert!(matches!(fork, Err(CryptoError::VerificationFailed(_))));
+        // Wpis usuniety bez nowej rewizji to tez rozwidlenie
+        let dropped = manifest(5, &[]).check_successor(&previous);
+        assert!(matches!(dropped, Err(CryptoError::VerificationFailed(_))));
+        let mut other_vault = manifest(6, &[("a", 3)]);
+        other_vault.vault_id = "vault-2".to_string();
+        assert!(matches!(other_vault.check_successor(&previous), Err(CryptoError::VerificationFailed(_))));
+
+        let public = public_key_bytes(&SECRET);
+        let (payload, signature) = signed(&manifest(4, &[("a", 3)]))?;
+        let verified = verify_manifest_bytes(&payload, &signature, &public, Some(&previous));
+        assert!(matches!(verified, Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_dropped_item_and_wrong_key() -> Result<(), CryptoError> {
+        let public = public_key_bytes(&SECRET);
+        let (payload, signature) = signed(&manifest(3, &[("a", 1), ("b", 2)]))?;
+        // Serwer usuwa wpis z podpisanego manifestu
+        let dropped = manifest(3, &[("a", 1)]).canonical()?;
+        let verified = verify_manifest_bytes(&dropped, &signature, &public, None);
+        assert!(matches!(verified, Err(CryptoError::VerificationFailed(_))));
+        // albo tylko z listy wpisow, ktora zwraca klientowi
+        let (manifest, _) = parse_manifest(&payload)?;
+        assert_eq!(manifest.mismatched_items(&parse_items(r#"{"a":1}"#)?), vec!["b".to_string()]);
+        assert_eq!(manifest.mismatched_items(&parse_items(r#"{"a":1,"b":2}"#)?), Vec::<String>::new());
+
+        let other = public_key_bytes(&[8u8; 32]);
+        let verified = verify_manifest_bytes(&payload, &signature, &other, None);
+        assert!(matches!(verified, Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_non_canonical_payload() -> Result<(), CryptoError> {
+        let public = public_key_bytes(&SECRET);
+        let (_, signature) = signed(&manifest(1, &[("a", 1)]))?;
+        // Powtorzony klucz nie ma postaci kanonicznej JCS
+        let duplicated = r#"{"items":{"a":1,"a":2},"revision":1,"vault_id":"vault-1","version":1}"#;
+        let verified = verify_manifest_bytes(duplicated, &signature, &public, None);
+        assert!(matches!(verified, Err(CryptoError::InvalidInput(_))));
+        let too_large = r#"{"items":{"a":9007199254740992},"revision":1,"vault_id":"vault-1","version":1}"#;
+        assert!(matches!(parse_manifest(too_large), Err(CryptoError::OutOfRange(_))));
+        let version = r#"{"items":{},"revision":1,"vault_id":"vault-1","version":2}"#;
+        assert!(matches!(parse_manifest(version), Err(CryptoError::UnsupportedVersion(_))));
+        Ok(())
+    }
+}
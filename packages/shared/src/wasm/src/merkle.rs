@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::ct::ct_eq;
+use crate::encoding::length_prefixed;
+use crate::error::CryptoError;
+use crate::sha2::sha256_bytes;
+
+// Drzewo Merkle nad zaszyfrowanymi wpisami vaulta: klient liczy korzen z tego, co dostal od
+// serwera, i porownuje z zapamietanym (albo podpisanym), wiec brakujacy lub podmieniony
+// wpis jest wykrywany. Skroty jak w RFC 6962:
+//   lisc  = SHA-256(0x00 || dlugosc id (u32 BE) || id || dlugosc (32) || SHA-256(szyfrogram))
+//   wezel = SHA-256(0x01 || lewy || prawy),  puste poddrzewo = SHA-256("")
+// Wpisy dzielone na 256 kubelkow wg pierwszego bajtu SHA-256(id); kubelek to drzewo RFC 6962
+// nad liscmi posortowanymi po id, nad kubelkami pelne drzewo binarne o glebokosci 8.
+// Ksztalt nie zalezy od liczby wpisow, wiec zmiana jednego wpisu zmienia tylko sciezke jego
+// kubelka - synchronizacja porownuje poziomy (level) od korzenia i pobiera tylko kubelki,
+// ktore sie roznia.
+// Dowod wlaczenia: indeks w kubelku (u32 BE) || rozmiar kubelka (u32 BE) || sciezka w
+// kubelku (od liscia) || 8 skrotow rodzenstwa kubelka (od dolu).
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+pub const BUCKET_BITS: u32 = 8;
+const BUCKETS: usize = 1 << BUCKET_BITS;
+const HASH_LEN: usize = 32;
+
+type Hash = [u8; HASH_LEN];
+
+pub fn leaf_hash(entry_id: &str, blob: &[u8]) -> Hash {
+    let mut data = vec![LEAF_PREFIX];
+    data.extend_from_slice(&length_prefixed(&[entry_id.as_bytes(), &sha256_bytes(blob)]));
+    sha256_bytes(&data)
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut data = Vec::with_capacity(1 + 2 * HASH_LEN);
+    data.push(NODE_PREFIX);
+    data.extend_from_slice(left);
+    data.extend_from_slice(right);
+    sha256_bytes(&data)
+}
+
+pub fn bucket_of(entry_id: &str) -> usize {
+    sha256_bytes(entry_id.as_bytes())[0] as usize >> (8 - BUCKET_BITS)
+}
+
+// Najwieksza potega dwojki mniejsza od n (n > 1).
+fn split(n: usize) -> usize {
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+fn subtree_root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => sha256_bytes(&[]),
+        1 => leaves[0],
+        n => {
+            let k = split(n);
+            node_hash(&subtree_root(&leaves[..k]), &subtree_root(&leaves[k..]))
+        }
+    }
+}
+
+// PATH(m, D[n]) z RFC 6962 - od liscia do korzenia.
+fn subtree_path(index: usize, leaves: &[Hash], out: &mut Vec<Hash>) {
+    if leaves.len() <= 1 {
+        return;
+    }
+    let k = split(leaves.len());
+    if index < k {
+        subtree_path(index, &leaves[..k], out);
+        out.push(subtree_root(&leaves[k..]));
+    } else {
+        subtree_path(index - k, &leaves[k..], out);
+        out.push(subtree_root(&leaves[..k]));
+    }
+}
+
+// Weryfikacja sciezki z RFC 9162 (2.1.3.2); zwraca korzen kubelka.
+fn root_from_path(index: u64, size: u64, leaf: Hash, path: &[Hash]) -> Option<Hash> {
+    if index >= size {
+        return None;
+    }
+    let (mut f, mut s, mut r) = (index, size - 1, leaf);
+    for p in path {
+        if s == 0 {
+            return None;
+        }
+        if f & 1 == 1 || f == s {
+            r = node_hash(p, &r);
+            while f & 1 == 0 && f != 0 {
+                f >>= 1;
+                s >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+        f >>= 1;
+        s >>= 1;
+    }
+    (s == 0).then_some(r)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct MerkleTree {
+    // Liscie kazdego kubelka posortowane po id
+    buckets: Vec<Vec<(String, Hash)>>,
+    // levels[0] = [korzen], levels[BUCKET_BITS] = skroty kubelkow
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    pub fn build<'a>(entries: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> MerkleTree {
+        let mut sorted: BTreeMap<&str, Hash> = BTreeMap::new();
+        for (id, blob) in entries {
+            sorted.insert(id, leaf_hash(id, blob));
+        }
+        let mut buckets: Vec<Vec<(String, Hash)>> = vec![Vec::new(); BUCKETS];
+        for (id, leaf) in sorted {
+            buckets[bucket_of(id)].push((id.to_string(), leaf));
+        }
+        let mut level: Vec<Hash> = buckets
+            .iter()
+            .map(|bucket| subtree_root(&bucket.iter().map(|(_, leaf)| *leaf).collect::<Vec<_>>()))
+            .collect();
+        let mut levels = vec![level.clone()];
+        while level.len() > 1 {
+            level = level.chunks(2).map(|pair| node_hash(&pair[0], &pair[1])).collect();
+            levels.push(level.clone());
+        }
+        levels.reverse();
+        MerkleTree { buckets, levels }
+    }
+
+    pub fn root_hash(&self) -> Hash {
+        self.levels[0][0]
+    }
+
+    pub fn proof_bytes(&self, entry_id: &str) -> Result<Vec<u8>, CryptoError> {
+        let bucket_index = bucket_of(entry_id);
+        let bucket = &self.buckets[bucket_index];
+        let index = bucket
+            .binary_search_by(|(id, _)| id.as_str().cmp(entry_id))
+            .map_err(|_| CryptoError::InvalidInput(format!("no entry {entry_id} in merkle tree")))?;
+        let leaves: Vec<Hash> = bucket.iter().map(|(_, leaf)| *leaf).collect();
+        let mut path = Vec::new();
+        subtree_path(index, &leaves, &mut path);
+        let mut node = bucket_index;
+        for depth in (1..=BUCKET_BITS as usize).rev() {
+            path.push(self.levels[depth][node ^ 1]);
+            node >>= 1;
+        }
+        let mut out = (index as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(&(leaves.len() as u32).to_be_bytes());
+        for hash in path {
+            out.extend_from_slice(&hash);
+        }
+        Ok(out)
+    }
+}
+
+pub fn verify_proof_bytes(root: &[u8], entry_id: &str, blob: &[u8], proof: &[u8]) -> bool {
+    if proof.len() < 8 || !(proof.len() - 8).is_multiple_of(HASH_LEN) {
+        return false;
+    }
+    let index = u32::from_be_bytes([proof[0], proof[1], proof[2], proof[3]]) as u64;
+    let size = u32::from_be_bytes([proof[4], proof[5], proof[6], proof[7]]) as u64;
+    let hashes: Vec<Hash> = proof[8..].chunks_exact(HASH_LEN).filter_map(|h| h.try_into().ok()).collect();
+    let Some(split_at) = hashes.len().checked_sub(BUCKET_BITS as usize) else { return false };
+    let (bucket_path, upper) = hashes.split_at(split_at);
+    let Some(mut r) = root_from_path(index, size, leaf_hash(entry_id, blob), bucket_path) else { return false };
+    let mut node = bucket_of(entry_id);
+    for sibling in upper {
+        r = if node & 1 == 1 { node_hash(sibling, &r) } else { node_hash(&r, sibling) };
+        node >>= 1;
+    }
+    ct_eq(&r, root)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl MerkleTree {
+    // entries - obiekt JSON { id wpisu: szyfrogram base64 } (pole entries eksportu vaulta).
+    pub fn from_entries(entries: &str) -> Result<MerkleTree, CryptoError> {
+        let entries: BTreeMap<String, String> =
+            serde_json::from_str(entries).map_err(|e| CryptoError::InvalidInput(format!("malformed entries: {e}")))?;
+        let blobs = entries
+            .into_iter()
+            .map(|(id, blob)| Ok((id, crate::encoding::base64_to_bytes(&blob)?)))
+            .collect::<Result<Vec<_>, CryptoError>>()?;
+        Ok(MerkleTree::build(blobs.iter().map(|(id, blob)| (id.as_str(), blob.as_slice()))))
+    }
+
+    pub fn root(&self) -> Vec<u8> {
+        self.root_hash().to_vec()
+    }
+
+    pub fn proof(&self, entry_id: &str) -> Result<Vec<u8>, CryptoError> {
+        self.proof_bytes(entry_id)
+    }
+
+    // Skroty wezlow na glebokosci depth (0 = korzen, 8 = kubelki) sklejone po 32 B.
+    pub fn level(&self, depth: u32) -> Result<Vec<u8>, CryptoError> {
+        let level = self
+            .levels
+            .get(depth as usize)
+            .ok_or_else(|| CryptoError::OutOfRange(format!("merkle depth must be at most {BUCKET_BITS}")))?;
+        Ok(level.concat())
+    }
+
+    // Indeksy wezlow na glebokosci depth, ktorych skroty roznia sie od podanego poziomu drugiej strony.
+    pub fn differing_nodes(&self, depth: u32, other_level: &[u8]) -> Result<Vec<u32>, CryptoError> {
+        let ours = self.level(depth)?;
+        if other_level.len() != ours.len() {
+            return Err(CryptoError::InvalidLength(format!("merkle level {depth} must be {} bytes", ours.len())));
+        }
+        Ok(ours
+            .chunks(HASH_LEN)
+            .zip(other_level.chunks(HASH_LEN))
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, _)| i as u32)
+            .collect())
+    }
+
+    pub fn bucket_entry_ids(&self, bucket: u32) -> Result<Vec<String>, CryptoError> {
+        let bucket = self
+            .buckets
+            .get(bucket as usize)
+            .ok_or_else(|| CryptoError::OutOfRange(format!("merkle bucket {bucket}")))?;
+        Ok(bucket.iter().map(|(id, _)| id.clone()).collect())
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn merkle_bucket(entry_id: &str) -> u32 {
+    bucket_of(entry_id) as u32
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_merkle_proof(root: &[u8], entry_id: &str, blob: &[u8], proof: &[u8]) -> bool {
+    verify_proof_bytes(root, entry_id, blob, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(n: usize) -> Vec<(String, Vec<u8>)> {
+        (0..n).map(|i| (format!("entry-{i}"), format!("blob {i}").into_bytes())).collect()
+    }
+
+    fn tree(entries: &[(String, Vec<u8>)]) -> MerkleTree {
+        MerkleTree::build(entries.iter().map(|(id, blob)| (id.as_str(), blob.as_slice())))
+    }
+
+    #[test]
+    fn proofs_round_trip() -> Result<(), CryptoError> {
+        for n in [1, 2, 3, 7, 1000] {
+            let entries = entries(n);
+            let tree = tree(&entries);
+            let root = tree.root_hash();
+            for (id, blob) in &entries {
+                assert!(verify_proof_bytes(&root, id, blob, &tree.proof_bytes(id)?), "n = {n}, {id}");
+            }
+        }
+        Ok(())
+    }
+
+    // Sciezki RFC 6962 w jednym kubelku, takze o rozmiarze niebedacym potega dwojki
+    #[test]
+    fn bucket_paths_round_trip() {
+        for n in [1usize, 2, 3, 7, 300] {
+            let leaves: Vec<Hash> = (0..n).map(|i| leaf_hash(&i.to_string(), b"x")).collect();
+            let root = subtree_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let mut path = Vec::new();
+                subtree_path(index, &leaves, &mut path);
+                assert_eq!(root_from_path(index as u64, n as u64, *leaf, &path), Some(root), "n = {n}, {index}");
+                assert_eq!(root_from_path(index as u64 + n as u64, n as u64, *leaf, &path), None);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_or_misused_proofs() -> Result<(), CryptoError> {
+        let entries = entries(300);
+        let original = tree(&entries);
+        let root = original.root_hash();
+        let (id, blob) = (&entries[5].0, &entries[5].1);
+        let proof = original.proof_bytes(id)?;
+        assert!(verify_proof_bytes(&root, id, blob, &proof));
+
+        assert!(!verify_proof_bytes(&root, id, b"tampered", &proof));
+        assert!(!verify_proof_bytes(&root, &entries[6].0, blob, &proof));
+        assert!(!verify_proof_bytes(&root, id, blob, &proof[..proof.len() - HASH_LEN]));
+        assert!(!verify_proof_bytes(&root, id, blob, &proof[..proof.len() - 1]));
+        assert!(!verify_proof_bytes(&root, id, blob, &proof[..4]));
+        assert!(!verify_proof_bytes(&root, id, blob, &[proof.as_slice(), &[0u8; HASH_LEN]].concat()));
+        let mut bad_index = proof.clone();
+        bad_index[3] ^= 1;
+        assert!(!verify_proof_bytes(&root, id, blob, &bad_index));
+
+        // Dowod ze starego drzewa nie pasuje do korzenia po zmianie innego wpisu
+        let mut changed = entries.clone();
+        changed[6].1 = b"new blob".to_vec();
+        assert!(!verify_proof_bytes(&tree(&changed).root_hash(), id, blob, &proof));
+        assert!(matches!(original.proof_bytes("missing"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn levels_locate_changed_bucket() -> Result<(), CryptoError> {
+        let entries = entries(50);
+        let mut changed = entries.clone();
+        changed[7].1 = b"new blob".to_vec();
+        let (a, b) = (tree(&entries), tree(&changed));
+        let bucket = bucket_of(&entries[7].0) as u32;
+        assert_eq!(a.differing_nodes(BUCKET_BITS, &b.level(BUCKET_BITS)?)?, vec![bucket]);
+        assert_eq!(a.differing_nodes(0, &b.level(0)?)?, vec![0]);
+        assert!(matches!(a.level(BUCKET_BITS + 1), Err(CryptoError::OutOfRange(_))));
+        assert!(matches!(a.differing_nodes(0, &[0u8; 31]), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::aead::{AeadAlgorithm, AeadCipher};
+use crate::encoding::hex_to_bytes;
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::rng::random_array;
+use crate::secret::EntryKey;
+
+// Migracja szyfrogramow miedzy formatami. Kazdy stary format ma wlasna sciezke
+// odszyfrowania; migracja odszyfrowuje i zapisuje ponownie w najnowszym formacie.
+//
+// Rozpoznawane formaty:
+//   LegacyRaw   - nonce || ciphertext || tag, AES-256-GCM (pm-crypto encrypt, klucze kolekcji)
+//   LegacyHex   - to samo zapisane jako hex (dotychczasowe wyjscia tekstowe)
+//   Envelope1   - "PMCT" || 0x01 || id algorytmu AEAD || nonce || ciphertext || tag,
+//                 naglowek (6 bajtow) jako prefiks AAD
+//
+// Nowy format dopisujemy jako kolejny wariant, rejestrujemy kroki w MIGRATION_STEPS
+// (kazdy krok ma fixture w testach) i przestawiamy CURRENT_FORMAT.
+const ENVELOPE_MAGIC: &[u8; 4] = b"PMCT";
+const ENVELOPE_HEADER_LEN: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CiphertextFormat {
+    LegacyRaw,
+    LegacyHex,
+    Envelope1,
+}
+
+pub const CURRENT_FORMAT: CiphertextFormat = CiphertextFormat::Envelope1;
+
+// Zarejestrowane kroki migracji: format zrodlowy -> format docelowy.
+pub const MIGRATION_STEPS: &[(CiphertextFormat, CiphertextFormat)] = &[
+    (CiphertextFormat::LegacyRaw, CiphertextFormat::Envelope1),
+    (CiphertextFormat::LegacyHex, CiphertextFormat::Envelope1),
+];
+
+impl CiphertextFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            CiphertextFormat::LegacyRaw => "legacy-raw",
+            CiphertextFormat::LegacyHex => "legacy-hex",
+            CiphertextFormat::Envelope1 => "envelope-v1",
+        }
+    }
+
+    // Kolejnosc sprawdzania: jawny naglowek, potem hex, na koncu surowe bajty.
+    pub fn detect(blob: &[u8]) -> Result<CiphertextFormat, CryptoError> {
+        if blob.starts_with(ENVELOPE_MAGIC) {
+            return match blob.get(4) {
+                Some(1) => Ok(CiphertextFormat::Envelope1),
+                Some(v) => Err(CryptoError::UnsupportedVersion(format!("ciphertext envelope version {v}"))),
+                None => Err(CryptoError::CorruptHeader("ciphertext envelope truncated".to_string())),
+            };
+        }
+        if decode_hex(blob).is_some_and(|raw| raw.len() >= NONCE_LEN + TAG_LEN) {
+            return Ok(CiphertextFormat::LegacyHex);
+        }
+        if blob.len() >= NONCE_LEN + TAG_LEN {
+            return Ok(CiphertextFormat::LegacyRaw);
+        }
+        Err(CryptoError::CorruptHeader("unrecognized ciphertext format".to_string()))
+    }
+
+    pub fn decrypt(self, key: &EntryKey, aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match self {
+            CiphertextFormat::LegacyRaw => open_legacy(key, aad, blob),
+            CiphertextFormat::LegacyHex => {
+                let raw = decode_hex(blob)
+                    .ok_or_else(|| CryptoError::CorruptHeader("legacy ciphertext is not valid hex".to_string()))?;
+                open_legacy(key, aad, &raw)
+            }
+            CiphertextFormat::Envelope1 => open_envelope(key, aad, blob),
+        }
+    }
+}
+
+fn decode_hex(text: &[u8]) -> Option<Vec<u8>> {
+    if text.is_empty() {
+        return None;
+    }
+    hex_to_bytes(std::str::from_utf8(text).ok()?).ok()
+}
+
+fn open_legacy(key: &EntryKey, aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if key.len() != 32 {
+        return Err(CryptoError::InvalidLength("legacy ciphertext key must be 32 bytes".to_string()));
+    }
+    if blob.len() < NONCE_LEN + TAG_LEN {
+        return Err(CryptoError::InvalidLength("legacy ciphertext too short".to_string()));
+    }
+    let (nonce, sealed) = blob.split_at(NONCE_LEN);
+    AesGcm::new(key.as_bytes())?.decrypt(nonce, aad, sealed)
+}
+
+fn envelope_aad(header: &[u8], aad: &[u8]) -> Vec<u8> {
+    [header, aad].concat()
+}
+
+pub fn seal_envelope_with_nonce(
+    algorithm: AeadAlgorithm,
+    key: &EntryKey,
+    nonce: &[u8; NONCE_LEN],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let cipher = AeadCipher::new(algorithm, key.as_bytes())?;
+    let mut out = ENVELOPE_MAGIC.to_vec();
+    out.push(1);
+    out.push(algorithm.id());
+    let sealed = cipher.encrypt(nonce, &envelope_aad(&out, aad), plaintext)?;
+    out.extend_from_slice(nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+pub fn seal_envelope_bytes(
+    algorithm: AeadAlgorithm,
+    key: &EntryKey,
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    seal_envelope_with_nonce(algorithm, key, &random_array::<NONCE_LEN>()?, aad, plaintext)
+}
+
+fn open_envelope(key: &EntryKey, aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if blob.len() < ENVELOPE_HEADER_LEN + NONCE_LEN + TAG_LEN {
+        return Err(CryptoError::CorruptHeader("ciphertext envelope truncated".to_string()));
+    }
+    let (header, rest) = blob.split_at(ENVELOPE_HEADER_LEN);
+    let algorithm = AeadAlgorithm::from_id(header[5])?;
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    AeadCipher::new(algorithm, key.as_bytes())?.decrypt(nonce, &envelope_aad(header, aad), sealed)
+}
+
+// Odszyfrowuje blob w dowolnym rozpoznawanym formacie.
+pub fn open_ciphertext_bytes(key: &EntryKey, aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    CiphertextFormat::detect(blob)?.decrypt(key, aad, blob)
+}
+
+// Krok z rejestru z podanym nonce nowego formatu (fixture'y i wektory testowe).
+// Stare formaty sa zawsze AES-256-GCM, wiec migrowane bloby tez.
+pub fn migrate_ciphertext_with_nonce(
+    key: &EntryKey,
+    aad: &[u8],
+    blob: &[u8],
+    nonce: &[u8; NONCE_LEN],
+) -> Result<Option<Vec<u8>>, CryptoError> {
+    let format = CiphertextFormat::detect(blob)?;
+    if format == CURRENT_FORMAT {
+        format.decrypt(key, aad, blob)?;
+        return Ok(None);
+    }
+    let Some(&(_, target)) = MIGRATION_STEPS.iter().find(|(from, _)| *from == format) else {
+        return Err(CryptoError::UnsupportedVersion(format!("no migration from {}", format.name())));
+    };
+    let plaintext = Zeroizing::new(format.decrypt(key, aad, blob)?);
+    match target {
+        CiphertextFormat::Envelope1 => {
+            seal_envelope_with_nonce(AeadAlgorithm::Aes256Gcm, key, nonce, aad, &plaintext).map(Some)
+        }
+        other => Err(CryptoError::UnsupportedVersion(format!("cannot write {}", other.name()))),
+    }
+}
+
+// Zwraca None, gdy blob jest juz w najnowszym formacie (bez ponownego szyfrowania).
+pub fn migrate_ciphertext_bytes(key: &EntryKey, aad: &[u8], blob: &[u8]) -> Result<Option<Vec<u8>>, CryptoError> {
+    migrate_ciphertext_with_nonce(key, aad, blob, &random_array::<NONCE_LEN>()?)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn ciphertext_format(blob: &[u8]) -> Result<String, CryptoError> {
+    Ok(CiphertextFormat::detect(blob)?.name().to_string())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn open_ciphertext(key: &[u8], aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    open_ciphertext_bytes(&EntryKey::from_slice(key), aad, blob)
+}
+
+// Zawsze zwraca blob w najnowszym formacie (kopie wejscia, jesli migracja nie byla potrzebna).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn migrate_ciphertext(key: &[u8], aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(migrate_ciphertext_bytes(&EntryKey::from_slice(key), aad, blob)?.unwrap_or_else(|| blob.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    const KEY: [u8; 32] = [0x42; 32];
+
+    fn legacy_blob() -> Result<Vec<u8>, CryptoError> {
+        let nonce = [7u8; NONCE_LEN];
+        let mut blob = nonce.to_vec();
+        blob.extend_from_slice(&AesGcm::new(&KEY)?.encrypt(&nonce, b"aad", b"old secret")?);
+        Ok(blob)
+    }
+
+    #[test]
+    fn legacy_formats_migrate_to_the_envelope() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let raw = legacy_blob()?;
+        let hex = bytes_to_hex(&raw).into_bytes();
+        assert_eq!(ciphertext_format(&raw)?, "legacy-raw");
+        assert_eq!(ciphertext_format(&hex)?, "legacy-hex");
+        for blob in [raw, hex] {
+            let migrated = migrate_ciphertext(&KEY, b"aad", &blob)?;
+            assert_eq!(ciphertext_format(&migrated)?, "envelope-v1");
+            assert_eq!(open_ciphertext_bytes(&key, b"aad", &migrated)?, b"old secret");
+            assert_eq!(migrate_ciphertext_bytes(&key, b"aad", &migrated)?, None);
+        }
+        Ok(())
+    }
+
+    // Jeden fixture na kazdy zarejestrowany krok: stary blob -> oczekiwany nowy blob
+    // (nonce nowego formatu 09 * 12). Wartosci sprawdzone niezaleznie (AES-GCM z Pythona).
+    const NEW_NONCE: [u8; NONCE_LEN] = [9; NONCE_LEN];
+    const LEGACY_RAW: &str = "070707070707070707070707178e398cc69411894620b1268df5b30e9474f17b9bce17601c93";
+    const ENVELOPE_V1: &str =
+        "504d4354010109090909090909090909090952a5cd4a22fc243b9af31739cb430a81917f2635f5273a1ed767";
+
+    fn fixture(step: (CiphertextFormat, CiphertextFormat)) -> Option<(Vec<u8>, &'static str)> {
+        match step {
+            (CiphertextFormat::LegacyRaw, CiphertextFormat::Envelope1) => {
+                Some((hex_to_bytes(LEGACY_RAW).ok()?, ENVELOPE_V1))
+            }
+            (CiphertextFormat::LegacyHex, CiphertextFormat::Envelope1) => {
+                Some((LEGACY_RAW.as_bytes().to_vec(), ENVELOPE_V1))
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn every_migration_step_matches_its_fixture() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        assert_eq!(bytes_to_hex(&legacy_blob()?), LEGACY_RAW);
+        for &step in MIGRATION_STEPS {
+            let Some((old, expected)) = fixture(step) else {
+                return Err(CryptoError::InvalidInput(format!("no fixture for {}", step.0.name())));
+            };
+            assert_eq!(CiphertextFormat::detect(&old)?, step.0);
+            let migrated = migrate_ciphertext_with_nonce(&key, b"aad", &old, &NEW_NONCE)?.unwrap_or_default();
+            assert_eq!(bytes_to_hex(&migrated), expected, "{}", step.0.name());
+            assert_eq!(CiphertextFormat::detect(&migrated)?, step.1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn migration_steps_reject_a_wrong_key() -> Result<(), CryptoError> {
+        let wrong = EntryKey::from_slice(&[0x24; 32]);
+        for &step in MIGRATION_STEPS {
+            let (old, _) = fixture(step).unwrap_or_default();
+            assert!(migrate_ciphertext_with_nonce(&wrong, b"aad", &old, &NEW_NONCE).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bad_ciphertexts_are_rejected() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&KEY);
+        let envelope = seal_envelope_bytes(AeadAlgorithm::Aes256Gcm, &key, b"aad", b"secret")?;
+        assert!(open_ciphertext_bytes(&key, b"other aad", &envelope).is_err());
+        assert!(open_ciphertext(&[0u8; 32], b"aad", &envelope).is_err());
+        assert!(matches!(ciphertext_format(b"PMCT\x02"), Err(CryptoError::UnsupportedVersion(_))));
+        assert!(matches!(ciphertext_format(b"PMCT"), Err(CryptoError::CorruptHeader(_))));
+        assert!(matches!(ciphertext_format(b"short"), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::digest::{DigestState, HashAlgorithm};
+use crate::error::CryptoError;
+#[cfg(feature = "js")]
+use crate::encoding::{OutputEncoding, encode_output};
+
+// Liczy kilka skrotow w jednym przebiegu po danych (np. SHA-256 + SHA-1 + BLAKE3 przy imporcie).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct MultiHasher {
+    states: Vec<DigestState>,
+}
+
+impl MultiHasher {
+    pub fn with_algorithms(algorithms: &[HashAlgorithm]) -> Result<MultiHasher, CryptoError> {
+        if algorithms.is_empty() {
+            return Err(CryptoError::InvalidParameter("at least one hash algorithm is required".to_string()));
+        }
+        let mut states: Vec<DigestState> = Vec::with_capacity(algorithms.len());
+        for &alg in algorithms {
+            if states.iter().any(|s| s.algorithm() == alg) {
+                return Err(CryptoError::InvalidParameter(format!("duplicate hash algorithm: {}", alg.name())));
+            }
+            states.push(DigestState::new(alg));
+        }
+        Ok(MultiHasher { states })
+    }
+
+    pub fn finalize_bytes(self) -> Vec<(HashAlgorithm, Vec<u8>)> {
+        self.states
+            .into_iter()
+            .map(|s| (s.algorithm(), s.finalize()))
+            .collect()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl MultiHasher {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(algorithms: Vec<String>) -> Result<MultiHasher, CryptoError> {
+        let algorithms = algorithms
+            .iter()
+            .map(|name| HashAlgorithm::from_name(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        MultiHasher::with_algorithms(&algorithms)
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        for state in &mut self.states {
+            state.update(chunk);
+        }
+    }
+
+    // Zwraca obiekt { sha256: ..., sha512: ... } w wybranym formacie.
+    #[cfg(feature = "js")]
+    pub fn finalize(self, encoding: Option<OutputEncoding>) -> Result<js_sys::Object, JsValue> {
+        let out = js_sys::Object::new();
+        for (alg, digest) in self.finalize_bytes() {
+            js_sys::Reflect::set(&out, &JsValue::from_str(alg.name()), &encode_output(&digest, encoding))?;
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::digest_bytes;
+
+    #[test]
+    fn one_pass_matches_separate_digests() -> Result<(), CryptoError> {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 7) as u8).collect();
+        let algorithms = [HashAlgorithm::Sha256, HashAlgorithm::Sha512, HashAlgorithm::Sha3_256];
+        let mut hasher = MultiHasher::with_algorithms(&algorithms)?;
+        for chunk in data.chunks(4096) {
+            hasher.update(chunk);
+        }
+        let digests = hasher.finalize_bytes();
+        assert_eq!(digests.len(), algorithms.len());
+        for (alg, digest) in digests {
+            assert_eq!(digest, digest_bytes(alg, &data), "{}", alg.name());
+        }
+        Ok(())
+    }
+
+    // Przypadek z importu: SHA-256, SHA-1 i BLAKE3 tego samego pliku w jednym przebiegu.
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn import_verification_set() -> Result<(), CryptoError> {
+        let mut hasher = MultiHasher::new(vec!["sha256".into(), "sha1".into(), "blake3".into()])?;
+        hasher.update(b"attachment ");
+        hasher.update(b"bytes");
+        let names: Vec<&str> = hasher.finalize_bytes().iter().map(|(alg, _)| alg.name()).collect();
+        assert_eq!(names, ["sha256", "sha1", "blake3"]);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_empty_and_duplicate_lists() {
+        assert!(matches!(MultiHasher::with_algorithms(&[]), Err(CryptoError::InvalidParameter(_))));
+        let twice = [HashAlgorithm::Sha256, HashAlgorithm::Sha256];
+        assert!(matches!(MultiHasher::with_algorithms(&twice), Err(CryptoError::InvalidParameter(_))));
+        assert!(matches!(MultiHasher::new(vec!["md5".into()]), Err(CryptoError::UnsupportedAlgorithm(_))));
+    }
+}
@@ -0,0 +1,288 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::ct::ct_eq;
+use crate::encoding::base32_to_bytes;
+use crate::error::CryptoError;
+use crate::hmac::{hmac_sha1_bytes, hmac_sha256_bytes, hmac_sha512_bytes};
+
+// Kody jednorazowe: HOTP (RFC 4226, licznik przechowuje wywolujacy) i TOTP (RFC 6238, licznik = czas / okres).
+// Sekret w wasm API jako base32 (tak jak w otpauth://), czas w sekundach od wywolujacego.
+// Weryfikacja sprawdza cale okno +-skew bez wczesnego wyjscia i porownuje w stalym czasie.
+pub const DEFAULT_DIGITS: u32 = 6;
+pub const DEFAULT_PERIOD: u32 = 30;
+const MIN_DIGITS: u32 = 6;
+const MAX_DIGITS: u32 = 10;
+const MAX_SKEW: u32 = 10;
+const MAX_LOOK_AHEAD: u32 = 100;
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OtpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl OtpAlgorithm {
+    // Nazwy jak w parametrze algorithm= URI otpauth://
+    pub fn from_name(name: &str) -> Result<OtpAlgorithm, CryptoError> {
+        match name.to_ascii_uppercase().as_str() {
+            "SHA1" | "SHA-1" => Ok(OtpAlgorithm::Sha1),
+            "SHA256" | "SHA-256" => Ok(OtpAlgorithm::Sha256),
+            "SHA512" | "SHA-512" => Ok(OtpAlgorithm::Sha512),
+            _ => Err(CryptoError::UnsupportedAlgorithm(format!("otp algorithm {name}"))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OtpAlgorithm::Sha1 => "SHA1",
+            OtpAlgorithm::Sha256 => "SHA256",
+            OtpAlgorithm::Sha512 => "SHA512",
+        }
+    }
+
+    fn mac(self, key: &[u8], data: &[u8]) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            OtpAlgorithm::Sha1 => hmac_sha1_bytes(key, data).to_vec(),
+            OtpAlgorithm::Sha256 => hmac_sha256_bytes(key, data).to_vec(),
+            OtpAlgorithm::Sha512 => hmac_sha512_bytes(key, data).to_vec(),
+        })
+    }
+}
+
+fn check_digits(digits: u32) -> Result<u32, CryptoError> {
+    let digits = if digits == 0 { DEFAULT_DIGITS } else { digits };
+    if !(MIN_DIGITS..=MAX_DIGITS).contains(&digits) {
+        return Err(CryptoError::OutOfRange(format!("otp digits must be in {MIN_DIGITS}..={MAX_DIGITS}")));
+    }
+    Ok(digits)
+}
+
+// Dynamiczne obcinanie (RFC 4226 5.3): 4 bajty od przesuniecia z ostatniego polbajtu MAC-a.
+pub fn hotp_bytes(secret: &[u8], counter: u64, digits: u32, algorithm: OtpAlgorithm) -> Result<String, CryptoError> {
+    let digits = check_digits(digits)?;
+    if secret.is_empty() {
+        return Err(CryptoError::InvalidLength("otp secret must not be empty".to_string()));
+    }
+    let mac = algorithm.mac(secret, &counter.to_be_bytes());
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([mac[offset] & 0x7f, mac[offset + 1], mac[offset + 2], mac[offset + 3]]);
+    let code = binary as u64 % 10u64.pow(digits);
+    Ok(format!("{code:0width$}", width = digits as usize))
+}
+
+pub fn totp_counter(time: u64, period: u32) -> u64 {
+    let period = if period == 0 { DEFAULT_PERIOD } else { period };
+    time / period as u64
+}
+
+pub fn totp_bytes(
+    secret: &[u8],
+    time: u64,
+    period: u32,
+    digits: u32,
+    algorithm: OtpAlgorithm,
+) -> Result<String, CryptoError> {
+    hotp_bytes(secret, totp_counter(time, period), digits, algorithm)
+}
+
+// Zwraca przesuniecie okna (-skew..=skew), w ktorym kod pasuje, albo None.
+pub fn totp_verify_bytes(
+    secret: &[u8],
+    code: &str,
+    time: u64,
+    period: u32,
+    digits: u32,
+    algorithm: OtpAlgorithm,
+    skew: u32,
+) -> Result<Option<i64>, CryptoError> {
+    if skew > MAX_SKEW {
+        return Err(CryptoError::OutOfRange(format!("otp skew must be at most {MAX_SKEW}")));
+    }
+    let digits = check_digits(digits)?;
+    let counter = totp_counter(time, period);
+    let mut matched = None;
+    for offset in -(skew as i64)..=skew as i64 {
+        let Some(step) = counter.checked_add_signed(offset) else {
+            continue;
+        };
+        let expected = hotp_bytes(secret, step, digits, algorithm)?;
+        if ct_eq(expected.as_bytes(), code.trim().as_bytes()) && matched.is_none() {
+            matched = Some(offset);
+        }
+    }
+    Ok(matched)
+}
+
+// Okno wyprzedzenia licznika (RFC 4226 7.4): klient mogl wygenerowac kody, ktorych nie uzyl.
+// Zwraca licznik, przy ktorym kod pasuje, albo None.
+pub fn hotp_verify_bytes(
+    secret: &[u8],
+    code: &str,
+    counter: u64,
+    digits: u32,
+    algorithm: OtpAlgorithm,
+    look_ahead: u32,
+) -> Result<Option<u64>, CryptoError> {
+    if look_ahead > MAX_LOOK_AHEAD {
+        return Err(CryptoError::OutOfRange(format!("hotp look-ahead must be at most {MAX_LOOK_AHEAD}")));
+    }
+    let mut matched = None;
+    for step in 0..=look_ahead as u64 {
+        let Some(candidate) = counter.checked_add(step) else {
+            break;
+        };
+        let expected = hotp_bytes(secret, candidate, digits, algorithm)?;
+        if ct_eq(expected.as_bytes(), code.trim().as_bytes()) && matched.is_none() {
+            matched = Some(candidate);
+        }
+    }
+    Ok(matched)
+}
+
+fn decode_secret(secret_base32: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    Ok(Zeroizing::new(base32_to_bytes(secret_base32)?))
+}
+
+// digits 0 = domyslne (6), algorithm domyslnie SHA1.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hotp(
+    secret_base32: &str,
+    counter: u64,
+    digits: u32,
+    algorithm: Option<OtpAlgorithm>,
+) -> Result<String, CryptoError> {
+    hotp_bytes(&decode_secret(secret_base32)?, counter, digits, algorithm.unwrap_or_default())
+}
+
+// Zwraca nastepna wartosc licznika do zapisania (licznik pasujacego kodu + 1) albo undefined.
+// Kod pasujacy przy liczniku u64::MAX to blad - nie ma nastepnej wartosci do zapisania.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn hotp_verify(
+    secret_base32: &str,
+    code: &str,
+    counter: u64,
+    digits: u32,
+    algorithm: Option<OtpAlgorithm>,
+    look_ahead: u32,
+) -> Result<Option<u64>, CryptoError> {
+    let secret = decode_secret(secret_base32)?;
+    let matched = hotp_verify_bytes(&secret, code, counter, digits, algorithm.unwrap_or_default(), look_ahead)?;
+    matched
+        .map(|c| c.checked_add(1).ok_or_else(|| CryptoError::OutOfRange("hotp counter exhausted".to_string())))
+        .transpose()
+}
+
+// period/digits 0 = domyslne (30 s, 6 cyfr), algorithm domyslnie SHA1.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn totp_generate(
+    secret_base32: &str,
+    time: u64,
+    period: u32,
+    digits: u32,
+    algorithm: Option<OtpAlgorithm>,
+) -> Result<String, CryptoError> {
+    totp_bytes(&decode_secret(secret_base32)?, time, period, digits, algorithm.unwrap_or_default())
+}
+
+// skew - liczba sasiednich okresow akceptowanych w kazda strone (zwykle 1).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn totp_verify(
+    secret_base32: &str,
+    code: &str,
+    time: u64,
+    period: u32,
+    digits: u32,
+    algorithm: Option<OtpAlgorithm>,
+    skew: u32,
+) -> Result<bool, CryptoError> {
+    let secret = decode_secret(secret_base32)?;
+    Ok(totp_verify_bytes(&secret, code, time, period, digits, algorithm.unwrap_or_default(), skew)?.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4226 dodatek D: sekret "12345678901234567890"
+    const SECRET: &[u8] = b"12345678901234567890";
+    const SECRET_BASE32: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    const RFC4226_CODES: [&str; 10] =
+        ["755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871", "520489"];
+
+    #[test]
+    fn rfc4226_vectors() -> Result<(), CryptoError> {
+        for (counter, code) in RFC4226_CODES.iter().enumerate() {
+            assert_eq!(hotp_bytes(SECRET, counter as u64, 6, OtpAlgorithm::Sha1)?, *code);
+            assert_eq!(hotp(SECRET_BASE32, counter as u64, 0, None)?, *code);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn look_ahead_window() -> Result<(), CryptoError> {
+        let code = RFC4226_CODES[5];
+        assert_eq!(hotp_verify(SECRET_BASE32, code, 0, 6, None, 5)?, Some(6));
+        assert_eq!(hotp_verify(SECRET_BASE32, code, 0, 6, None, 4)?, None);
+        assert_eq!(hotp_verify(SECRET_BASE32, code, 6, 6, None, 10)?, None);
+        assert!(matches!(hotp_verify(SECRET_BASE32, code, 0, 6, None, 101), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_match_at_last_counter() -> Result<(), CryptoError> {
+        let code = hotp_bytes(SECRET, u64::MAX, 6, OtpAlgorithm::Sha1)?;
+        assert_eq!(hotp_verify_bytes(SECRET, &code, u64::MAX, 6, OtpAlgorithm::Sha1, 0)?, Some(u64::MAX));
+        assert!(matches!(hotp_verify(SECRET_BASE32, &code, u64::MAX, 6, None, 0), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+
+    // RFC 6238 dodatek B: sekret ASCII "1234567890" powtarzany do dlugosci skrotu, 8 cyfr
+    const TOTP_SECRETS: [(OtpAlgorithm, &str); 3] = [
+        (OtpAlgorithm::Sha1, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"),
+        (OtpAlgorithm::Sha256, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZA===="),
+        (
+            OtpAlgorithm::Sha512,
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNA=",
+        ),
+    ];
+    const RFC6238_CODES: [(u64, [&str; 3]); 6] = [
+        (59, ["94287082", "46119246", "90693936"]),
+        (1_111_111_109, ["07081804", "68084774", "25091201"]),
+        (1_111_111_111, ["14050471", "67062674", "99943326"]),
+        (1_234_567_890, ["89005924", "91819424", "93441116"]),
+        (2_000_000_000, ["69279037", "90698825", "38618901"]),
+        (20_000_000_000, ["65353130", "77737706", "47863826"]),
+    ];
+
+    #[test]
+    fn rfc6238_vectors() -> Result<(), CryptoError> {
+        for (time, codes) in RFC6238_CODES {
+            for ((algorithm, secret), code) in TOTP_SECRETS.iter().zip(codes) {
+                assert_eq!(totp_generate(secret, time, 0, 8, Some(*algorithm))?, code);
+                assert!(totp_verify(secret, code, time, 30, 8, Some(*algorithm), 0)?);
+            }
+        }
+        assert_eq!(totp_generate(TOTP_SECRETS[0].1, 59, 0, 8, None)?, "94287082");
+        Ok(())
+    }
+
+    #[test]
+    fn skew_window_boundaries() -> Result<(), CryptoError> {
+        // Czas 89 to licznik 2; kody z licznikow 0..=4 wokol okna +-1
+        let code = |counter: u64| hotp_bytes(SECRET, counter, 6, OtpAlgorithm::Sha1);
+        let verify = |code: &str, skew| totp_verify_bytes(SECRET, code, 89, 30, 6, OtpAlgorithm::Sha1, skew);
+        assert_eq!(verify(&code(1)?, 1)?, Some(-1));
+        assert_eq!(verify(&code(3)?, 1)?, Some(1));
+        assert_eq!(verify(&code(0)?, 1)?, None);
+        assert_eq!(verify(&code(4)?, 1)?, None);
+        assert_eq!(verify(&code(3)?, 0)?, None);
+        assert!(matches!(verify(&code(2)?, MAX_SKEW + 1), Err(CryptoError::OutOfRange(_))));
+        assert!(!totp_verify(SECRET_BASE32, &code(3)?, 89, 30, 6, None, 0)?);
+        Ok(())
+    }
+}
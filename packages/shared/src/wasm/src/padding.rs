@@ -0,0 +1,107 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::error::CryptoError;
+
+// Dopelnienie dlugosci przed szyfrowaniem AEAD, zeby dlugosc szyfrogramu nie zdradzala
+// dlugosci hasla czy notatki (ani stopnia kompresji payloadu). Padme (PURBs, Nikitin i in.):
+// dlugosc L zaokraglana w gore tak, ze zostaje tylko O(log log L) znaczacych bitow -
+// narzut najwyzej ~12%, a dlugosci wpadaja do malej liczby koszykow.
+// Ponizej MIN_PADDED_LEN jeden wspolny koszyk (krotkie sekrety sa najbardziej wrazliwe).
+// Kodowanie jak ISO/IEC 7816-4: dane || 0x80 || 0x00..., wiec zdjecie paddingu nie wymaga
+// zapisanej dlugosci.
+const MIN_PADDED_LEN: u64 = 32;
+const PAD_MARKER: u8 = 0x80;
+
+pub fn padme_len(len: u64) -> u64 {
+    if len < 2 {
+        return len;
+    }
+    let e = 63 - len.leading_zeros() as u64;
+    let s = 64 - e.leading_zeros() as u64;
+    let mask = (1u64 << (e - s)) - 1;
+    (len + mask) & !mask
+}
+
+// Dlugosc po pad_bytes dla danych o dlugosci len.
+pub fn padded_len(len: u64) -> u64 {
+    padme_len((len + 1).max(MIN_PADDED_LEN))
+}
+
+pub fn pad_bytes(data: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut out = Zeroizing::new(Vec::with_capacity(padded_len(data.len() as u64) as usize));
+    out.extend_from_slice(data);
+    out.push(PAD_MARKER);
+    out.resize(padded_len(data.len() as u64) as usize, 0);
+    out
+}
+
+// Wolane tylko na danych juz uwierzytelnionych (po AEAD), wiec blad nie jest wyrocznia.
+pub fn unpad_bytes(padded: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let end = padded.iter().rposition(|&b| b != 0);
+    match end {
+        Some(end) if padded[end] == PAD_MARKER => Ok(Zeroizing::new(padded[..end].to_vec())),
+        _ => Err(CryptoError::DecryptFailed("invalid length padding".to_string())),
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn padme_length(len: u32) -> u32 {
+    padded_len(len as u64).min(u32::MAX as u64) as u32
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn pad(data: &[u8]) -> Vec<u8> {
+    let mut out = pad_bytes(data);
+    std::mem::take(&mut *out)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn unpad(padded: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut out = unpad_bytes(padded)?;
+    Ok(std::mem::take(&mut *out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padme_buckets() {
+        let cases = [(0, 0), (1, 1), (9, 10), (32, 32), (33, 36), (37, 40), (100, 104), (255, 256), (1000, 1024)];
+        for (len, bucket) in cases {
+            assert_eq!(padme_len(len), bucket, "len {len}");
+        }
+        // Ponizej MIN_PADDED_LEN (z markerem) wszystko trafia do jednego koszyka.
+        for len in 0..32 {
+            assert_eq!(padded_len(len), 32);
+        }
+        assert_eq!(padded_len(32), 36);
+        assert_eq!(padded_len(35), 36);
+        assert_eq!(padded_len(36), 40);
+        assert_eq!(padme_length(u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn round_trip_at_bucket_boundaries() -> Result<(), CryptoError> {
+        for len in [0, 1, 30, 31, 32, 35, 36, 103, 104, 1023, 1024] {
+            let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8 + 1).collect();
+            let padded = pad_bytes(&data);
+            assert_eq!(padded.len() as u64, padded_len(len as u64));
+            assert_eq!(&*unpad_bytes(&padded)?, data.as_slice());
+        }
+        // Zera i 0x80 w samych danych nie myla unpad.
+        let data = [0x00, 0x80, 0x00];
+        assert_eq!(&*unpad_bytes(&pad_bytes(&data))?, &data);
+        Ok(())
+    }
+
+    #[test]
+    fn unpad_rejects_bad_padding() {
+        let bad: [&[u8]; 4] = [b"", &[0; 32], b"data\x00\x00", b"data\x80\x00\x01\x00"];
+        for padded in bad {
+            assert!(matches!(unpad_bytes(padded), Err(CryptoError::DecryptFailed(_))));
+        }
+    }
+}
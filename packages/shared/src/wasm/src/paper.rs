@@ -0,0 +1,252 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::encoding::{CROCKFORD, bytes_to_crockford, crockford_to_bytes, crockford_value};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::pbkdf2::pbkdf2_hmac_sha256_bytes;
+use crate::rng::random_array;
+
+// "Emergency kit" do wydruku: sekret odzyskiwania szyfrowany haslem kitu
+// (PBKDF2-HMAC-SHA256 + AES-256-GCM), zakodowany Crockford base32 w liniach:
+//
+//   01/04 XXXXX-XXXXX-XXXXX-XXXXX C
+//
+// Kazda linia ma numer, liczbe linii i symbol kontrolny (mod 37, alfabet Crockforda)
+// liczony takze z numeru linii - przy przepisywaniu blad wskazuje konkretna linie,
+// a zamiana kolejnosci linii tez jest wykrywana.
+const KIT_VERSION: u8 = 1;
+const KIT_HEADER: &str = "# pm emergency kit v1";
+pub const KIT_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = 1 + 4 + SALT_LEN;
+const GROUP_LEN: usize = 5;
+const GROUPS_PER_LINE: usize = 4;
+const LINE_SYMBOLS: usize = GROUP_LEN * GROUPS_PER_LINE;
+const CHECK_SYMBOLS: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+fn line_check(line_no: usize, total: usize, symbols: &[u8]) -> u8 {
+    let mut acc = 0u32;
+    for b in [line_no as u8, total as u8] {
+        acc = (acc * 256 + b as u32) % 37;
+    }
+    for &v in symbols {
+        acc = (acc * 32 + v as u32) % 37;
+    }
+    CHECK_SYMBOLS[acc as usize]
+}
+
+fn kit_key(password: &[u8], salt: &[u8], iterations: u32) -> Result<Vec<u8>, CryptoError> {
+    pbkdf2_hmac_sha256_bytes(password, salt, iterations, 32)
+}
+
+pub fn seal_kit_bytes(recovery_secret: &[u8], kit_password: &[u8], iterations: u32) -> Result<Vec<u8>, CryptoError> {
+    if recovery_secret.is_empty() {
+        return Err(CryptoError::InvalidLength("recovery secret must not be empty".to_string()));
+    }
+    let salt = random_array::<SALT_LEN>()?;
+    let nonce = random_array::<NONCE_LEN>()?;
+    let mut header = vec![KIT_VERSION];
+    header.extend_from_slice(&iterations.to_be_bytes());
+    header.extend_from_slice(&salt);
+
+    let mut key = kit_key(kit_password, &salt, iterations)?;
+    let cipher = AesGcm::new(&key);
+    key.zeroize();
+    let sealed = cipher?.encrypt(&nonce, &header, recovery_secret)?;
+
+    let mut out = header;
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+pub fn open_kit_bytes(blob: &[u8], kit_password: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if blob.len() < HEADER_LEN + NONCE_LEN + TAG_LEN {
+        return Err(CryptoError::CorruptHeader("emergency kit too short".to_string()));
+    }
+    if blob[0] != KIT_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("emergency kit version {}", blob[0])));
+    }
+    let (header, rest) = blob.split_at(HEADER_LEN);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let iterations = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+    let mut key = kit_key(kit_password, &header[5..], iterations)?;
+    let cipher = AesGcm::new(&key);
+    key.zeroize();
+    // Naglowek jest w AAD, wiec blad tagu przy poprawnym naglowku to prawie zawsze zle haslo
+    cipher?.decrypt(nonce, header, sealed).map_err(|e| match e {
+        CryptoError::DecryptFailed(_) => CryptoError::WrongPassword("emergency kit password is incorrect".to_string()),
+        other => other,
+    })
+}
+
+pub fn format_kit(blob: &[u8]) -> String {
+    let symbols = bytes_to_crockford(blob);
+    let chunks: Vec<&[u8]> = symbols.as_bytes().chunks(LINE_SYMBOLS).collect();
+    let total = chunks.len();
+    let mut out = String::from(KIT_HEADER);
+    out.push('\n');
+    for (i, chunk) in chunks.iter().enumerate() {
+        let values: Vec<u8> = chunk.iter().filter_map(|&c| crockford_value(c as char)).collect();
+        let groups: Vec<&str> = chunk
+            .chunks(GROUP_LEN)
+            .map(|g| std::str::from_utf8(g).unwrap_or_default())
+            .collect();
+        out.push_str(&format!(
+            "{:02}/{:02} {} {}\n",
+            i + 1,
+            total,
+            groups.join("-"),
+            line_check(i + 1, total, &values) as char
+        ));
+    }
+    out
+}
+
+struct KitLine {
+    line_no: usize,
+    total: usize,
+    data: String,
+}
+
+// Pojedyncza linia w postaci wpisanej przez uzytkownika, np. "03/05 abcde-fghjk-... Q".
+fn parse_line(line: &str) -> Result<KitLine, CryptoError> {
+    let line = line.trim();
+    let (label, rest) = line
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| CryptoError::InvalidInput("line is missing its number".to_string()))?;
+    let (no, total) = label
+        .split_once('/')
+        .and_then(|(n, t)| Some((n.parse::<usize>().ok()?, t.parse::<usize>().ok()?)))
+        .ok_or_else(|| CryptoError::InvalidInput(format!("malformed line number '{label}'")))?;
+    if no == 0 || total == 0 || no > total || total > 255 {
+        return Err(CryptoError::InvalidInput(format!("line number {label} out of range")));
+    }
+    let err = |msg: &str| CryptoError::InvalidInput(format!("line {no:02}: {msg}"));
+
+    let compact: Vec<char> = rest
+        .chars()
+        .filter(|&c| c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let Some((&check, data)) = compact.split_last() else {
+        return Err(err("missing data"));
+    };
+    if data.is_empty() || data.len() > LINE_SYMBOLS {
+        return Err(err("wrong number of characters"));
+    }
+    let values = data
+        .iter()
+        .map(|&c| crockford_value(c).ok_or_else(|| err(&format!("invalid character '{c}'"))))
+        .collect::<Result<Vec<u8>, CryptoError>>()?;
+    // Symbol kontrolny porownywany po normalizacji (I/L/O) jak dane
+    let check = match check {
+        'I' | 'L' => '1',
+        'O' => '0',
+        c => c,
+    };
+    if check != line_check(no, total, &values) as char {
+        return Err(err("checksum mismatch, please re-enter this line"));
+    }
+    let data = values.iter().map(|&v| CROCKFORD[v as usize] as char).collect();
+    Ok(KitLine { line_no: no, total, data })
+}
+
+pub fn parse_kit(text: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut lines: Vec<Option<String>> = Vec::new();
+    let mut total = 0;
+    for raw in text.lines() {
+        let raw = raw.trim();
+        if raw.is_empty() || raw.starts_with('#') {
+            continue;
+        }
+        let line = parse_line(raw)?;
+        if total == 0 {
+            total = line.total;
+            lines = vec![None; total];
+        } else if line.total != total {
+            return Err(CryptoError::InvalidInput(format!(
+                "line {:02}: line count {} does not match {}",
+                line.line_no, line.total, total
+            )));
+        }
+        if lines[line.line_no - 1].is_some() {
+            return Err(CryptoError::InvalidInput(format!("line {:02}: entered twice", line.line_no)));
+        }
+        if line.line_no < total && line.data.len() != LINE_SYMBOLS {
+            return Err(CryptoError::InvalidInput(format!("line {:02}: wrong number of characters", line.line_no)));
+        }
+        lines[line.line_no - 1] = Some(line.data);
+    }
+    if total == 0 {
+        return Err(CryptoError::InvalidInput("emergency kit is empty".to_string()));
+    }
+    let mut symbols = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        match line {
+            Some(data) => symbols.push_str(data),
+            None => return Err(CryptoError::InvalidInput(format!("line {:02}: missing", i + 1))),
+        }
+    }
+    crockford_to_bytes(&symbols)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn create_emergency_kit(recovery_secret: &[u8], kit_password: &str) -> Result<String, CryptoError> {
+    create_emergency_kit_raw(recovery_secret, kit_password.as_bytes())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn create_emergency_kit_raw(recovery_secret: &[u8], kit_password: &[u8]) -> Result<String, CryptoError> {
+    Ok(format_kit(&seal_kit_bytes(recovery_secret, kit_password, KIT_ITERATIONS)?))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn open_emergency_kit(kit_text: &str, kit_password: &str) -> Result<Vec<u8>, CryptoError> {
+    open_emergency_kit_raw(kit_text, kit_password.as_bytes())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn open_emergency_kit_raw(kit_text: &str, kit_password: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    open_kit_bytes(&parse_kit(kit_text)?, kit_password)
+}
+
+// Walidacja pojedynczej linii w trakcie wpisywania (bez hasla).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn check_emergency_kit_line(line: &str) -> Result<(), CryptoError> {
+    parse_line(line).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"recovery secret bytes";
+
+    #[test]
+    fn kit_round_trips_through_text() -> Result<(), CryptoError> {
+        let kit = format_kit(&seal_kit_bytes(SECRET, "kit password".as_bytes(), 1000)?);
+        assert_eq!(open_emergency_kit(&kit, "kit password")?, SECRET);
+        assert_eq!(open_emergency_kit_raw(&kit, b"kit password")?, SECRET);
+        for line in kit.lines().skip(1) {
+            check_emergency_kit_line(line)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn damaged_kit_is_rejected() -> Result<(), CryptoError> {
+        let kit = format_kit(&seal_kit_bytes(SECRET, b"kit password", 1000)?);
+        assert!(matches!(open_emergency_kit_raw(&kit, b"wrong"), Err(CryptoError::WrongPassword(_))));
+        let lines: Vec<&str> = kit.lines().collect();
+        let missing = [lines[0], lines[1]].join("\n");
+        assert!(matches!(open_emergency_kit(&missing, "kit password"), Err(CryptoError::InvalidInput(_))));
+        let doubled = [lines[0], lines[1], lines[1]].join("\n");
+        assert!(matches!(open_emergency_kit(&doubled, "kit password"), Err(CryptoError::InvalidInput(_))));
+        assert!(check_emergency_kit_line("01/02 ZZZZZ").is_err());
+        assert!(matches!(seal_kit_bytes(&[], b"kit password", 1000), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::OnceLock;
+
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::error::CryptoError;
+use crate::rng::random_below;
+
+// Hasla-frazy w stylu diceware z listy EFF (eff_large_wordlist.txt, 7776 slow = 5 rzutow
+// kostka, ~12.9 bita na slowo). Lista w formacie EFF: "11111<TAB>abacus", po jednej w linii;
+// parser sprawdza kolejnosc numerow kostek i unikalnosc slow, zeby uszkodzony plik nie
+// obnizal entropii po cichu. Lista jest wbudowana (generate_passphrase); PassphraseGenerator
+// przyjmuje tez wlasna liste w tym samym formacie.
+// Slowa losowane przez random_below (bez biasu modulo); include_number dokleja cyfre 0-9
+// do losowego slowa.
+pub const EFF_LARGE_WORDS: usize = 7776;
+pub const MIN_WORDS: u32 = 3;
+pub const MAX_WORDS: u32 = 20;
+const DICE_PER_WORD: usize = 5;
+const EFF_LARGE_WORDLIST: &str = include_str!("wordlists/eff_large_wordlist.txt");
+
+static EFF_LARGE: OnceLock<Option<PassphraseGenerator>> = OnceLock::new();
+
+pub fn eff_large() -> Result<&'static PassphraseGenerator, CryptoError> {
+    EFF_LARGE
+        .get_or_init(|| PassphraseGenerator::from_eff_list(EFF_LARGE_WORDLIST).ok())
+        .as_ref()
+        .ok_or_else(|| CryptoError::InvalidInput("built-in wordlist is corrupt".to_string()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct PassphraseGenerator {
+    words: Vec<String>,
+}
+
+fn dice_index(dice: &str) -> Option<usize> {
+    if dice.len() != DICE_PER_WORD {
+        return None;
+    }
+    dice.bytes().try_fold(0usize, |acc, d| match d {
+        b'1'..=b'6' => Some(acc * 6 + (d - b'1') as usize),
+        _ => None,
+    })
+}
+
+impl PassphraseGenerator {
+    pub fn from_eff_list(text: &str) -> Result<PassphraseGenerator, CryptoError> {
+        let mut words = Vec::with_capacity(EFF_LARGE_WORDS);
+        for (line_no, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let malformed = || CryptoError::InvalidInput(format!("wordlist line {} is malformed", line_no + 1));
+            let (dice, word) = line.trim().split_once(char::is_whitespace).ok_or_else(malformed)?;
+            let word = word.trim();
+            if dice_index(dice) != Some(words.len())
+                || word.is_empty()
+                || !word.bytes().all(|b| b.is_ascii_lowercase() || b == b'-')
+            {
+                return Err(malformed());
+            }
+            words.push(word.to_string());
+        }
+        if words.len() != EFF_LARGE_WORDS {
+            return Err(CryptoError::InvalidLength(format!(
+                "wordlist must have {EFF_LARGE_WORDS} words, got {}",
+                words.len()
+            )));
+        }
+        let mut sorted: Vec<&str> = words.iter().map(String::as_str).collect();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|w| w[0] == w[1]) {
+            return Err(CryptoError::InvalidInput("wordlist contains duplicate words".to_string()));
+        }
+        Ok(PassphraseGenerator { words })
+    }
+
+    pub fn generate_with(
+        &self,
+        word_count: u32,
+        separator: &str,
+        capitalize: bool,
+        include_number: bool,
+    ) -> Result<Zeroizing<String>, CryptoError> {
+        if !(MIN_WORDS..=MAX_WORDS).contains(&word_count) {
+            return Err(CryptoError::OutOfRange(format!("passphrase word count must be in {MIN_WORDS}..={MAX_WORDS}")));
+        }
+        let number_at = if include_number { Some(random_below(word_count)?) } else { None };
+        // Pojemnosc z gory - bez realokacji, ktore zostawilyby kopie frazy w pamieci
+        let longest = self.words.iter().map(String::len).max().unwrap_or(0);
+        let mut out = Zeroizing::new(String::with_capacity(word_count as usize * (longest + separator.len() + 1)));
+        for i in 0..word_count {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            let word = &self.words[random_below(self.words.len() as u32)? as usize];
+            let mut chars = word.chars();
+            if capitalize && let Some(first) = chars.next() {
+                out.push(first.to_ascii_uppercase());
+                out.push_str(chars.as_str());
+            } else {
+                out.push_str(word);
+            }
+            if number_at == Some(i) {
+                out.push(char::from(b'0' + random_below(10)? as u8));
+            }
+        }
+        Ok(out)
+    }
+
+    // Entropia w bitach (tylko wybor slow; cyfra dodaje log2(10 * word_count)).
+    pub fn entropy_bits(&self, word_count: u32, include_number: bool) -> f64 {
+        let words = word_count as f64 * (self.words.len() as f64).log2();
+        if include_number { words + (10.0 * word_count as f64).log2() } else { words }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl PassphraseGenerator {
+    // wordlist - lista w formacie EFF; dla wbudowanej listy wystarczy generate_passphrase.
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(wordlist: &str) -> Result<PassphraseGenerator, CryptoError> {
+        PassphraseGenerator::from_eff_list(wordlist)
+    }
+
+    pub fn generate_passphrase(
+        &self,
+        word_count: u32,
+        separator: &str,
+        capitalize: bool,
+        include_number: bool,
+    ) -> Result<String, CryptoError> {
+        let mut passphrase = self.generate_with(word_count, separator, capitalize, include_number)?;
+        Ok(std::mem::take(&mut *passphrase))
+    }
+
+    pub fn passphrase_entropy(&self, word_count: u32, include_number: bool) -> f64 {
+        self.entropy_bits(word_count, include_number)
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn generate_passphrase(
+    word_count: u32,
+    separator: &str,
+    capitalize: bool,
+    include_number: bool,
+) -> Result<String, CryptoError> {
+    eff_large()?.generate_passphrase(word_count, separator, capitalize, include_number)
+}
+
+// Dla wbudowanej listy: word_count * log2(7776) (+ cyfra).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn passphrase_entropy(word_count: u32, include_number: bool) -> Result<f64, CryptoError> {
+    Ok(eff_large()?.entropy_bits(word_count, include_number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(passphrase: &str, separator: &str) -> Vec<String> {
+        passphrase.split(separator).map(str::to_string).collect()
+    }
+
+    #[test]
+    fn builtin_list_is_the_full_eff_list() -> Result<(), CryptoError> {
+        let list = eff_large()?;
+        assert_eq!(list.words.len(), EFF_LARGE_WORDS);
+        assert_eq!((list.words[0].as_str(), list.words[EFF_LARGE_WORDS - 1].as_str()), ("abacus", "zoom"));
+        let bits = passphrase_entropy(6, false)?;
+        assert!((bits - 6.0 * 7776f64.log2()).abs() < 1e-9);
+        assert!((passphrase_entropy(6, true)? - bits - 60f64.log2()).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn word_count_and_separator() -> Result<(), CryptoError> {
+        let list = eff_large()?;
+        for count in [MIN_WORDS, 6, MAX_WORDS] {
+            let parts = words(&generate_passphrase(count, " / ", false, false)?, " / ");
+            assert_eq!(parts.len(), count as usize);
+            assert!(parts.iter().all(|w| list.words.contains(w)));
+        }
+        for count in [0, MIN_WORDS - 1, MAX_WORDS + 1] {
+            assert!(matches!(generate_passphrase(count, "-", false, false), Err(CryptoError::OutOfRange(_))));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn capitalization_and_number() -> Result<(), CryptoError> {
+        let list = eff_large()?;
+        let parts = words(&generate_passphrase(8, "_", true, true)?, "_");
+        assert_eq!(parts.len(), 8);
+        let with_digit: Vec<&String> = parts.iter().filter(|w| w.ends_with(|c: char| c.is_ascii_digit())).collect();
+        assert_eq!(with_digit.len(), 1);
+        for part in &parts {
+            let word = part.trim_end_matches(|c: char| c.is_ascii_digit());
+            assert!(word.starts_with(|c: char| c.is_ascii_uppercase()));
+            assert!(list.words.iter().any(|w| w.eq_ignore_ascii_case(word)));
+        }
+        let plain = generate_passphrase(5, "", false, false)?;
+        assert!(plain.bytes().all(|b| b.is_ascii_lowercase() || b == b'-'));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_lists_are_rejected() -> Result<(), CryptoError> {
+        let mut lines: Vec<&str> = EFF_LARGE_WORDLIST.lines().collect();
+        lines.pop();
+        assert!(matches!(PassphraseGenerator::new(&lines.join("\n")), Err(CryptoError::InvalidLength(_))));
+        lines.push("66666\tabacus");
+        assert!(matches!(PassphraseGenerator::new(&lines.join("\n")), Err(CryptoError::InvalidInput(_))));
+        lines.swap(0, 1);
+        assert!(matches!(PassphraseGenerator::new(&lines.join("\n")), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+}
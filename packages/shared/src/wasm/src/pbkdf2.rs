@@ -0,0 +1,223 @@
+use zeroize::Zeroize;
+
+use crate::error::CryptoError;
+use crate::fips::check_pbkdf2;
+use crate::hmac::{HmacSha256, HmacSha512};
+use crate::limits::{check_dk_len, check_iterations, check_salt_len};
+
+fn check_pbkdf2_params(salt_len: usize, c: u32, dk_len: usize) -> Result<(), CryptoError> {
+    check_iterations(c)?;
+    check_dk_len(dk_len)?;
+    check_salt_len(salt_len)?;
+    check_pbkdf2(salt_len, c, dk_len)
+}
+
+// Uklad blokow T_i w kluczu wyjsciowym. Kod bazowy obcina pierwszy blok do reszty dlugosci,
+// a kolejne kopiuje w calosci (V0) - tak licza eksporty pbkdf2_hmac_sha256/512 (i _raw), zeby
+// zapisane klucze dalej sie zgadzaly. RFC 8018 (obcinany ostatni blok) licza funkcje *_bytes
+// i eksporty *_rfc8018. Wynik rozni sie tylko gdy dk_len > hLen i nie jest wielokrotnoscia hLen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pbkdf2Layout {
+    // T_1 || T_2 || ... || T_l[..r]
+    Rfc8018,
+    // T_1[..r] || T_2 || ... || T_l
+    V0,
+}
+
+// Dzieli dk na kawalki odpowiadajace kolejnym blokom T_i (indeksowanym od 1).
+fn block_chunks(dk: &mut [u8], h_len: usize, layout: Pbkdf2Layout) -> impl Iterator<Item = (u32, &mut [u8])> {
+    let first = match layout {
+        Pbkdf2Layout::Rfc8018 => dk.len().min(h_len),
+        Pbkdf2Layout::V0 => match dk.len() % h_len {
+            0 => dk.len().min(h_len),
+            r => r,
+        },
+    };
+    let (head, tail) = dk.split_at_mut(first);
+    std::iter::once(head).chain(tail.chunks_mut(h_len)).zip(1u32..).map(|(chunk, i)| (i, chunk))
+}
+
+pub fn pbkdf2_hmac_sha512_bytes(password: &[u8], salt: &[u8], c: u32, dk_len: usize) -> Result<Vec<u8>, CryptoError> {
+    pbkdf2_hmac_sha512_layout_bytes(password, salt, c, dk_len, Pbkdf2Layout::Rfc8018)
+}
+
+pub fn pbkdf2_hmac_sha512_layout_bytes(
+    password: &[u8],
+    salt: &[u8],
+    c: u32,
+    dk_len: usize,
+    layout: Pbkdf2Layout,
+) -> Result<Vec<u8>, CryptoError> {
+    const H_LEN: usize = 64;
+    check_pbkdf2_params(salt.len(), c, dk_len)?;
+    if dk_len > (u32::MAX as usize).saturating_mul(H_LEN) {
+        return Err(CryptoError::OutOfRange("derived key too long".to_string()));
+    }
+
+    let mut dk = vec![0u8; dk_len];
+    let prf = HmacSha512::new(password);
+
+    for (i, chunk) in block_chunks(&mut dk, H_LEN, layout) {
+        let mut block = pbkdf2_f(&prf, salt, c, i);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        block.zeroize();
+    }
+
+    Ok(dk)
+}
+
+fn pbkdf2_f(prf: &HmacSha512, salt: &[u8], c: u32, i: u32) -> [u8; 64] {
+    let mut u = prf.mac(&[salt, &i.to_be_bytes()].concat());
+    let mut t = u;
+
+    for _ in 1..c {
+        u = prf.mac(&u);
+        for j in 0..64 {
+            t[j] ^= u[j];
+        }
+    }
+    u.zeroize();
+    t
+}
+
+pub fn pbkdf2_hmac_sha256_bytes(password: &[u8], salt: &[u8], c: u32, dk_len: usize) -> Result<Vec<u8>, CryptoError> {
+    pbkdf2_hmac_sha256_layout_bytes(password, salt, c, dk_len, Pbkdf2Layout::Rfc8018)
+}
+
+pub fn pbkdf2_hmac_sha256_layout_bytes(
+    password: &[u8],
+    salt: &[u8],
+    c: u32,
+    dk_len: usize,
+    layout: Pbkdf2Layout,
+) -> Result<Vec<u8>, CryptoError> {
+    const H_LEN: usize = 32;
+    check_pbkdf2_params(salt.len(), c, dk_len)?;
+    if dk_len > (u32::MAX as usize).saturating_mul(H_LEN) {
+        return Err(CryptoError::OutOfRange("derived key too long".to_string()));
+    }
+
+    let mut dk = vec![0u8; dk_len];
+    let prf = HmacSha256::new(password);
+
+    for (i, chunk) in block_chunks(&mut dk, H_LEN, layout) {
+        let mut block = pbkdf2_f_sha256(&prf, salt, c, i);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        block.zeroize();
+    }
+
+    Ok(dk)
+}
+
+fn pbkdf2_f_sha256(prf: &HmacSha256, salt: &[u8], c: u32, i: u32) -> [u8; 32] {
+    let mut u = prf.mac(&[salt, &i.to_be_bytes()].concat());
+    let mut t = u;
+
+    for _ in 1..c {
+        u = prf.mac(&u);
+        for j in 0..32 {
+            t[j] ^= u[j];
+        }
+    }
+    u.zeroize();
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    const SALT: &[u8] = b"saltSALTsaltSALT";
+
+    // Wektory RFC 8018, zgodne z hashlib.pbkdf2_hmac.
+    #[test]
+    fn rfc8018_layout_fixtures() -> Result<(), CryptoError> {
+        assert_eq!(
+            bytes_to_hex(&pbkdf2_hmac_sha256_bytes(b"password", SALT, 1000, 40)?),
+            "46f895baaa185f0265c1da708d799953de592f576c4538b7eba99ced700ee75be7853914a7787341"
+        );
+        assert_eq!(
+            bytes_to_hex(&pbkdf2_hmac_sha512_bytes(b"password", SALT, 1000, 80)?),
+            "2febe385f6399aa2a20a9926d1822e2f499daa76ce5e1630fefef849424413077848f761eecadbf2fe6e5da1a48cc73f\
+             2f0cd7588e150a3f6d45f20566a965974f5beb92667533312cad0824889d591d"
+        );
+        Ok(())
+    }
+
+    // Wyniki kodu z commita bazowego (559a6178) - pierwszy blok obciety.
+    #[test]
+    fn v0_layout_fixtures() -> Result<(), CryptoError> {
+        assert_eq!(
+            bytes_to_hex(&pbkdf2_hmac_sha256_layout_bytes(b"password", SALT, 1000, 40, Pbkdf2Layout::V0)?),
+            "46f895baaa185f02e7853914a7787341b540cf14ab6d023633fa220f293625b85af3baed95f654a4"
+        );
+        assert_eq!(
+            bytes_to_hex(&pbkdf2_hmac_sha512_layout_bytes(b"password", SALT, 1000, 80, Pbkdf2Layout::V0)?),
+            "2febe385f6399aa2a20a9926d1822e2f4f5beb92667533312cad0824889d591dd2b306081990f5b010ad427e6be2c1e3\
+             26307f2f4e799602f864dc9ce7684bfee25d22f2c5e9310ecc7b385327b8c7e2"
+        );
+        Ok(())
+    }
+
+    // Eksporty o dotychczasowych nazwach licza jak kod bazowy, RFC 8018 pod *_rfc8018.
+    #[test]
+    fn exports_keep_the_v0_layout() -> Result<(), CryptoError> {
+        let v0 = pbkdf2_hmac_sha256_layout_bytes(b"password", SALT, 1000, 40, Pbkdf2Layout::V0)?;
+        assert_eq!(crate::pbkdf2_hmac_sha256_raw(b"password", SALT, 1000, 40)?, v0);
+        let rfc = pbkdf2_hmac_sha256_bytes(b"password", SALT, 1000, 40)?;
+        assert_eq!(crate::pbkdf2_hmac_sha256_rfc8018_raw(b"password", SALT, 1000, 40)?, rfc);
+        let v0 = pbkdf2_hmac_sha512_layout_bytes(b"password", SALT, 1000, 80, Pbkdf2Layout::V0)?;
+        assert_eq!(crate::pbkdf2_hmac_sha512_raw(b"password", SALT, 1000, 80)?, v0);
+        let rfc = pbkdf2_hmac_sha512_bytes(b"password", SALT, 1000, 80)?;
+        assert_eq!(crate::pbkdf2_hmac_sha512_rfc8018_raw(b"password", SALT, 1000, 80)?, rfc);
+        Ok(())
+    }
+
+    #[test]
+    fn layouts_agree_on_whole_blocks() -> Result<(), CryptoError> {
+        for dk_len in [16, 32, 64] {
+            assert_eq!(
+                pbkdf2_hmac_sha256_bytes(b"password", SALT, 1000, dk_len)?,
+                pbkdf2_hmac_sha256_layout_bytes(b"password", SALT, 1000, dk_len, Pbkdf2Layout::V0)?
+            );
+            assert_eq!(
+                pbkdf2_hmac_sha512_bytes(b"password", SALT, 1000, dk_len)?,
+                pbkdf2_hmac_sha512_layout_bytes(b"password", SALT, 1000, dk_len, Pbkdf2Layout::V0)?
+            );
+        }
+        Ok(())
+    }
+
+    // Przypadki, ktore przed synth-215 konczyly sie panikiem (przy panic=abort - zabiciem
+    // instancji wasm): dk_len 0 (niedomiar w r), 0 iteracji, parametry ponad limity.
+    #[test]
+    fn former_panics_are_errors() {
+        for layout in [Pbkdf2Layout::Rfc8018, Pbkdf2Layout::V0] {
+            assert!(matches!(
+                pbkdf2_hmac_sha256_layout_bytes(b"password", SALT, 1000, 0, layout),
+                Err(CryptoError::OutOfRange(_))
+            ));
+            assert!(matches!(
+                pbkdf2_hmac_sha512_layout_bytes(b"password", SALT, 1000, 0, layout),
+                Err(CryptoError::OutOfRange(_))
+            ));
+            assert!(matches!(
+                pbkdf2_hmac_sha256_layout_bytes(b"password", SALT, 0, 32, layout),
+                Err(CryptoError::InvalidIterations(_))
+            ));
+            assert!(matches!(
+                pbkdf2_hmac_sha512_layout_bytes(b"password", SALT, u32::MAX, 64, layout),
+                Err(CryptoError::InvalidIterations(_))
+            ));
+            assert!(matches!(
+                pbkdf2_hmac_sha256_layout_bytes(b"password", SALT, 1000, usize::MAX, layout),
+                Err(CryptoError::OutOfRange(_))
+            ));
+            assert!(matches!(
+                pbkdf2_hmac_sha512_layout_bytes(b"password", &[0u8; 4096], 1000, 64, layout),
+                Err(CryptoError::OutOfRange(_))
+            ));
+        }
+    }
+}
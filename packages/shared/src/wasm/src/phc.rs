@@ -0,0 +1,172 @@
+use crate::encoding::{base64_to_bytes, bytes_to_base64};
+use crate::error::CryptoError;
+
+// Format PHC: $<id>[$v=<wersja>][$<param>=<wartosc>(,<param>=<wartosc>)*][$<sol>[$<hash>]]
+// Sol i hash w base64 (alfabet standardowy) bez paddingu. Kolejnosc parametrow jest
+// zachowywana, bo jest czescia kanonicznej postaci stringa.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhcString {
+    pub algorithm: String,
+    pub version: Option<u32>,
+    pub params: Vec<(String, String)>,
+    pub salt: Option<Vec<u8>>,
+    pub hash: Option<Vec<u8>>,
+}
+
+pub fn phc_b64_encode(data: &[u8]) -> String {
+    bytes_to_base64(data).trim_end_matches('=').to_string()
+}
+
+pub fn phc_b64_decode(text: &str) -> Result<Vec<u8>, CryptoError> {
+    if text.contains('=') {
+        return Err(CryptoError::InvalidInput("PHC base64 must not be padded".to_string()));
+    }
+    base64_to_bytes(text)
+}
+
+fn is_symbol(text: &str, extra: &[u8]) -> bool {
+    !text.is_empty() && text.bytes().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || extra.contains(&c))
+}
+
+impl PhcString {
+    pub fn parse(text: &str) -> Result<PhcString, CryptoError> {
+        let mut fields = text
+            .strip_prefix('$')
+            .ok_or_else(|| CryptoError::InvalidInput("PHC string must start with '$'".to_string()))?
+            .split('$')
+            .peekable();
+        let algorithm = fields.next().unwrap_or_default();
+        if !is_symbol(algorithm, b"-") || algorithm.len() > 32 {
+            return Err(CryptoError::InvalidInput(format!("invalid PHC algorithm identifier '{algorithm}'")));
+        }
+
+        let mut version = None;
+        if let Some(v) = fields.peek().and_then(|f| f.strip_prefix("v=")) {
+            version = Some(
+                v.parse::<u32>()
+                    .map_err(|_| CryptoError::InvalidInput(format!("invalid PHC version '{v}'")))?,
+            );
+            fields.next();
+        }
+
+        let mut params = Vec::new();
+        if fields.peek().is_some_and(|f| f.contains('=')) {
+            for pair in fields.next().unwrap_or_default().split(',') {
+                let (name, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| CryptoError::InvalidInput(format!("invalid PHC parameter '{pair}'")))?;
+                if !is_symbol(name, b"-") || value.is_empty() || params.iter().any(|(n, _)| n == name) {
+                    return Err(CryptoError::InvalidInput(format!("invalid PHC parameter '{pair}'")));
+                }
+                params.push((name.to_string(), value.to_string()));
+            }
+        }
+
+        let salt = fields.next().map(phc_b64_decode).transpose()?;
+        let hash = fields.next().map(phc_b64_decode).transpose()?;
+        if fields.next().is_some() {
+            return Err(CryptoError::InvalidInput("too many fields in PHC string".to_string()));
+        }
+        Ok(PhcString { algorithm: algorithm.to_string(), version, params, salt, hash })
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    // Parametr liczbowy (dziesietny, bez znaku i zer wiodacych - jak w specyfikacji PHC).
+    pub fn decimal_param(&self, name: &str) -> Result<Option<u32>, CryptoError> {
+        let Some(value) = self.param(name) else {
+            return Ok(None);
+        };
+        if !value.bytes().all(|c| c.is_ascii_digit()) || (value.len() > 1 && value.starts_with('0')) {
+            return Err(CryptoError::InvalidInput(format!("PHC parameter {name} is not a decimal number")));
+        }
+        value
+            .parse::<u32>()
+            .map(Some)
+            .map_err(|_| CryptoError::OutOfRange(format!("PHC parameter {name} too large")))
+    }
+
+    // Tylko znane parametry - nieznany zwykle oznacza inny wariant algorytmu.
+    pub fn require_known_params(&self, known: &[&str]) -> Result<(), CryptoError> {
+        match self.params.iter().find(|(n, _)| !known.contains(&n.as_str())) {
+            Some((name, _)) => Err(CryptoError::UnsupportedAlgorithm(format!(
+                "{} parameter '{name}' is not supported",
+                self.algorithm
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut out = format!("${}", self.algorithm);
+        if let Some(v) = self.version {
+            out.push_str(&format!("$v={v}"));
+        }
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(|(n, v)| format!("{n}={v}")).collect();
+            out.push('$');
+            out.push_str(&params.join(","));
+        }
+        if let Some(salt) = &self.salt {
+            out.push('$');
+            out.push_str(&phc_b64_encode(salt));
+            if let Some(hash) = &self.hash {
+                out.push('$');
+                out.push_str(&phc_b64_encode(hash));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_reencodes() -> Result<(), CryptoError> {
+        let text = "$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$Gpj7qOY5RCXJvcMzqcdQqvgR3wcPX7SleI4c9NtXk6E";
+        let phc = PhcString::parse(text)?;
+        assert_eq!(phc.algorithm, "argon2id");
+        assert_eq!(phc.version, Some(19));
+        assert_eq!(phc.decimal_param("m")?, Some(64));
+        assert_eq!(phc.salt.as_deref(), Some(b"somesaltsomesalt".as_slice()));
+        assert_eq!(phc.encode(), text);
+        assert!(PhcString::parse("$scrypt$ln=4,r=1,p=1")?.version.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn b64_rejects_padding_and_bad_alphabet() -> Result<(), CryptoError> {
+        assert_eq!(phc_b64_decode("c29tZXNhbHQ")?, b"somesalt");
+        for bad in ["c29tZXNhbHQ=", "c29tZXNhbHQ==", "c29tZXNhbHRz-w", "c29tZXNh_HQ", "c29tZXNhbHR"] {
+            assert!(matches!(phc_b64_decode(bad), Err(CryptoError::InvalidInput(_))), "{bad}");
+        }
+        assert!(matches!(
+            PhcString::parse("$argon2id$v=19$m=64,t=2,p=1$c29tZXNhbHQ=$aGFzaA"),
+            Err(CryptoError::InvalidInput(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_strings() -> Result<(), CryptoError> {
+        for bad in [
+            "argon2id$v=19",
+            "$Argon2id$v=19",
+            "$argon2id$v=x$m=64",
+            "$argon2id$v=19$m=64,m=65$c29tZXNhbHQ",
+            "$argon2id$v=19$m=,t=2$c29tZXNhbHQ",
+            "$argon2id$v=19$m=64$c29tZXNhbHQ$aGFzaA$extra",
+        ] {
+            assert!(matches!(PhcString::parse(bad), Err(CryptoError::InvalidInput(_))), "{bad}");
+        }
+        let phc = PhcString::parse("$argon2id$v=19$m=064,t=99999999999")?;
+        assert!(matches!(phc.decimal_param("m"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(phc.decimal_param("t"), Err(CryptoError::OutOfRange(_))));
+        assert!(matches!(phc.require_known_params(&["m"]), Err(CryptoError::UnsupportedAlgorithm(_))));
+        Ok(())
+    }
+}
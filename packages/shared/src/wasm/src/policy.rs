@@ -0,0 +1,366 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::ed25519::{sign_bytes, verify_strict};
+use crate::error::CryptoError;
+
+// Polityki organizacji wypychane przez administratora. Podpis Ed25519 obejmuje dokladnie
+// przeslany tekst JSON (z etykieta domeny), wiec nie zalezy od kolejnosci kluczy.
+const POLICY_SIGNATURE_LABEL: &[u8] = b"pm-org-policy-v1\0";
+pub const POLICY_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OrgPolicy {
+    pub version: u32,
+    pub org_id: String,
+    pub issued_at: u64,
+    #[serde(default)]
+    pub master_password: Option<MasterPasswordPolicy>,
+    #[serde(default)]
+    pub kdf_minimums: Option<KdfMinimums>,
+    #[serde(default)]
+    pub disallow_export: bool,
+    #[serde(default)]
+    pub vault_timeout: Option<VaultTimeoutPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct MasterPasswordPolicy {
+    #[serde(default)]
+    pub min_length: u32,
+    #[serde(default)]
+    pub require_lower: bool,
+    #[serde(default)]
+    pub require_upper: bool,
+    #[serde(default)]
+    pub require_digit: bool,
+    #[serde(default)]
+    pub require_symbol: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct KdfMinimums {
+    #[serde(default)]
+    pub pbkdf2_iterations: u32,
+    #[serde(default)]
+    pub argon2_memory_kib: u32,
+    #[serde(default)]
+    pub argon2_iterations: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultTimeoutPolicy {
+    pub max_minutes: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KdfAlgorithm {
+    Pbkdf2Sha256,
+    Pbkdf2Sha512,
+    Argon2id,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KdfSettings {
+    pub algorithm: KdfAlgorithm,
+    pub iterations: u32,
+    #[serde(default)]
+    pub memory_kib: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyAction {
+    SetMasterPassword { password: String },
+    ChangeKdf { kdf: KdfSettings },
+    Export,
+    // None = blokada wylaczona
+    SetVaultTimeout { minutes: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PolicyViolation {
+    pub rule: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PolicyDecision {
+    pub allowed: bool,
+    pub violations: Vec<PolicyViolation>,
+}
+
+fn violation(rule: &str, message: String) -> PolicyViolation {
+    PolicyViolation { rule: rule.to_string(), message }
+}
+
+fn check_master_password(rules: &MasterPasswordPolicy, password: &str, out: &mut Vec<PolicyViolation>) {
+    let len = password.chars().count() as u32;
+    if len < rules.min_length {
+        out.push(violation(
+            "master_password.min_length",
+            format!("master password must be at least {} characters", rules.min_length),
+        ));
+    }
+    let checks = [
+        (rules.require_lower, password.chars().any(|c| c.is_lowercase()), "require_lower", "a lowercase letter"),
+        (rules.require_upper, password.chars().any(|c| c.is_uppercase()), "require_upper", "an uppercase letter"),
+        (rules.require_digit, password.chars().any(|c| c.is_ascii_digit()), "require_digit", "a digit"),
+        (
+            rules.require_symbol,
+            password.chars().any(|c| !c.is_alphanumeric() && !c.is_whitespace()),
+            "require_symbol",
+            "a symbol",
+        ),
+    ];
+    for (required, present, rule, what) in checks {
+        if required && !present {
+            out.push(violation(&format!("master_password.{rule}"), format!("master password must contain {what}")));
+        }
+    }
+}
+
+// Algorytm bez skonfigurowanego minimum jest odrzucany - inaczej minima Argon2 dalo sie
+// obejsc, przechodzac na PBKDF2 z jedna iteracja.
+fn check_kdf(mins: &KdfMinimums, kdf: &KdfSettings, out: &mut Vec<PolicyViolation>) {
+    match kdf.algorithm {
+        KdfAlgorithm::Pbkdf2Sha256 | KdfAlgorithm::Pbkdf2Sha512 => {
+            if mins.pbkdf2_iterations == 0 {
+                out.push(violation("kdf_minimums.algorithm", "PBKDF2 is not allowed by your organization".to_string()));
+            } else if kdf.iterations < mins.pbkdf2_iterations {
+                out.push(violation(
+                    "kdf_minimums.pbkdf2_iterations",
+                    format!("PBKDF2 requires at least {} iterations", mins.pbkdf2_iterations),
+                ));
+            }
+        }
+        KdfAlgorithm::Argon2id => {
+            if mins.argon2_memory_kib == 0 && mins.argon2_iterations == 0 {
+                out.push(violation(
+                    "kdf_minimums.algorithm",
+                    "Argon2id is not allowed by your organization".to_string(),
+                ));
+                return;
+            }
+            if kdf.memory_kib < mins.argon2_memory_kib {
+                out.push(violation(
+                    "kdf_minimums.argon2_memory_kib",
+                    format!("Argon2id requires at least {} KiB of memory", mins.argon2_memory_kib),
+                ));
+            }
+            if kdf.iterations < mins.argon2_iterations {
+                out.push(violation(
+                    "kdf_minimums.argon2_iterations",
+                    format!("Argon2id requires at least {} iterations", mins.argon2_iterations),
+                ));
+            }
+        }
+    }
+}
+
+pub fn enforce(policy: &OrgPolicy, action: &PolicyAction) -> PolicyDecision {
+    let mut violations = Vec::new();
+    match action {
+        PolicyAction::SetMasterPassword { password } => {
+            if let Some(rules) = &policy.master_password {
+                check_master_password(rules, password, &mut violations);
+            }
+        }
+        PolicyAction::ChangeKdf { kdf } => {
+            if let Some(mins) = &policy.kdf_minimums {
+                check_kdf(mins, kdf, &mut violations);
+            }
+        }
+        PolicyAction::Export => {
+            if policy.disallow_export {
+                violations.push(violation("disallow_export", "vault export is disabled by your organization".to_string()));
+            }
+        }
+        PolicyAction::SetVaultTimeout { minutes } => {
+            if let Some(timeout) = &policy.vault_timeout {
+                match minutes {
+                    Some(m) if *m <= timeout.max_minutes => {}
+                    _ => violations.push(violation(
+                        "vault_timeout.max_minutes",
+                        format!("vault timeout must be at most {} minutes", timeout.max_minutes),
+                    )),
+                }
+            }
+        }
+    }
+    PolicyDecision { allowed: violations.is_empty(), violations }
+}
+
+fn signed_message(payload: &str) -> Vec<u8> {
+    let mut msg = POLICY_SIGNATURE_LABEL.to_vec();
+    msg.extend_from_slice(payload.as_bytes());
+    msg
+}
+
+pub fn parse_policy(payload: &str) -> Result<OrgPolicy, CryptoError> {
+    let policy: OrgPolicy =
+        serde_json::from_str(payload).map_err(|e| CryptoError::InvalidInput(format!("malformed policy: {e}")))?;
+    if policy.version != POLICY_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("policy version {}", policy.version)));
+    }
+    Ok(policy)
+}
+
+// min_issued_at - issued_at ostatnio przyjetej polityki. Starsza (nawet poprawnie podpisana)
+// polityka jest odrzucana, zeby nie dalo sie podsunac wczesniejszych, slabszych regul.
+pub fn verify_signed_policy(
+    payload: &str,
+    signature: &[u8],
+    org_public_key: &[u8],
+    org_id: &str,
+    min_issued_at: u64,
+) -> Result<OrgPolicy, CryptoError> {
+    verify_strict(org_public_key, &signed_message(payload), signature)?;
+    let policy = parse_policy(payload)?;
+    if policy.org_id != org_id {
+        return Err(CryptoError::VerificationFailed(format!("policy belongs to organization {}", policy.org_id)));
+    }
+    if policy.issued_at < min_issued_at {
+        return Err(CryptoError::VerificationFailed(format!(
+            "policy issued at {} is older than {min_issued_at}",
+            policy.issued_at
+        )));
+    }
+    Ok(policy)
+}
+
+pub fn sign_policy_bytes(payload: &str, admin_secret_key: &[u8; 32]) -> Result<[u8; 64], CryptoError> {
+    parse_policy(payload)?;
+    Ok(sign_bytes(admin_secret_key, &signed_message(payload)))
+}
+
+fn parse_action(action: &str) -> Result<PolicyAction, CryptoError> {
+    serde_json::from_str(action).map_err(|e| CryptoError::InvalidInput(format!("malformed policy action: {e}")))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, CryptoError> {
+    serde_json::to_string(value).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn sign_policy(payload: &str, admin_secret_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let key: Zeroizing<[u8; 32]> = Zeroizing::new(
+        admin_secret_key
+            .try_into()
+            .map_err(|_| CryptoError::InvalidLength("ed25519 secret key must be 32 bytes".to_string()))?,
+    );
+    Ok(sign_policy_bytes(payload, &key)?.to_vec())
+}
+
+// Zwraca zweryfikowana polityke jako JSON (po normalizacji).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_policy(
+    payload: &str,
+    signature: &[u8],
+    org_public_key: &[u8],
+    org_id: &str,
+    min_issued_at: u64,
+) -> Result<String, CryptoError> {
+    to_json(&verify_signed_policy(payload, signature, org_public_key, org_id, min_issued_at)?)
+}
+
+// Polityka musi byc podpisana - klient nie egzekwuje niezweryfikowanych regul.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn enforce_policy(
+    payload: &str,
+    signature: &[u8],
+    org_public_key: &[u8],
+    org_id: &str,
+    min_issued_at: u64,
+    action: &str,
+) -> Result<String, CryptoError> {
+    let policy = verify_signed_policy(payload, signature, org_public_key, org_id, min_issued_at)?;
+    to_json(&enforce(&policy, &parse_action(action)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ed25519::public_key_bytes;
+
+    const SECRET: [u8; 32] = [7u8; 32];
+    const POLICY: &str = r#"{"version":1,"org_id":"org-1","issued_at":1700000000,
+        "master_password":{"min_length":12,"require_digit":true},
+        "kdf_minimums":{"pbkdf2_iterations":600000},"disallow_export":true}"#;
+
+    #[test]
+    fn signed_policy_is_enforced() -> Result<(), CryptoError> {
+        let signature = sign_policy(POLICY, &SECRET)?;
+        let public = public_key_bytes(&SECRET);
+        let decision: PolicyDecision = serde_json::from_str(&enforce_policy(
+            POLICY,
+            &signature,
+            &public,
+            "org-1",
+            1700000000,
+            r#"{"type":"set_master_password","password":"correct horse battery 9"}"#,
+        )?)
+        .map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        assert!(decision.allowed);
+
+        let policy = verify_signed_policy(POLICY, &signature, &public, "org-1", 0)?;
+        let weak = enforce(&policy, &PolicyAction::SetMasterPassword { password: "short".to_string() });
+        let rules: Vec<&str> = weak.violations.iter().map(|v| v.rule.as_str()).collect();
+        assert_eq!(rules, ["master_password.min_length", "master_password.require_digit"]);
+        assert!(!enforce(&policy, &PolicyAction::Export).allowed);
+        let kdf = KdfSettings { algorithm: KdfAlgorithm::Pbkdf2Sha256, iterations: 100_000, memory_kib: 0 };
+        assert!(!enforce(&policy, &PolicyAction::ChangeKdf { kdf }).allowed);
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_policy_is_rejected() -> Result<(), CryptoError> {
+        let signature = sign_policy(POLICY, &SECRET)?;
+        let public = public_key_bytes(&SECRET);
+        let tampered = POLICY.replace("\"disallow_export\":true", "\"disallow_export\":false");
+        assert!(verify_signed_policy(&tampered, &signature, &public, "org-1", 0).is_err());
+        assert!(verify_signed_policy(POLICY, &signature, &public_key_bytes(&[8u8; 32]), "org-1", 0).is_err());
+        assert!(matches!(sign_policy(POLICY, &SECRET[..31]), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn older_or_foreign_policy_is_rejected() -> Result<(), CryptoError> {
+        let signature = sign_policy(POLICY, &SECRET)?;
+        let public = public_key_bytes(&SECRET);
+        assert!(verify_signed_policy(POLICY, &signature, &public, "org-1", 1700000000).is_ok());
+        let replayed = verify_signed_policy(POLICY, &signature, &public, "org-1", 1700000001);
+        assert!(matches!(replayed, Err(CryptoError::VerificationFailed(_))));
+        let foreign = verify_signed_policy(POLICY, &signature, &public, "org-2", 0);
+        assert!(matches!(foreign, Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn kdf_without_a_minimum_is_not_allowed() -> Result<(), CryptoError> {
+        let policy = parse_policy(
+            r#"{"version":1,"org_id":"org-1","issued_at":1,
+            "kdf_minimums":{"argon2_memory_kib":65536,"argon2_iterations":3}}"#,
+        )?;
+        let change = |algorithm, iterations, memory_kib| PolicyAction::ChangeKdf {
+            kdf: KdfSettings { algorithm, iterations, memory_kib },
+        };
+        let pbkdf2 = enforce(&policy, &change(KdfAlgorithm::Pbkdf2Sha256, 1, 0));
+        assert!(!pbkdf2.allowed);
+        assert_eq!(pbkdf2.violations[0].rule, "kdf_minimums.algorithm");
+        assert!(!enforce(&policy, &change(KdfAlgorithm::Pbkdf2Sha512, 10_000_000, 0)).allowed);
+        assert!(enforce(&policy, &change(KdfAlgorithm::Argon2id, 3, 65536)).allowed);
+        assert!(!enforce(&policy, &change(KdfAlgorithm::Argon2id, 2, 65536)).allowed);
+
+        // Same minima PBKDF2 - Argon2id niedozwolony
+        let policy = parse_policy(
+            r#"{"version":1,"org_id":"org-1","issued_at":1,"kdf_minimums":{"pbkdf2_iterations":600000}}"#,
+        )?;
+        assert!(!enforce(&policy, &change(KdfAlgorithm::Argon2id, 3, 65536)).allowed);
+        assert!(enforce(&policy, &change(KdfAlgorithm::Pbkdf2Sha512, 600_000, 0)).allowed);
+        Ok(())
+    }
+}
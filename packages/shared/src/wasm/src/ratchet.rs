@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::{hkdf_expand_sha256, hkdf_extract_sha256};
+use crate::hmac::hmac_sha256_bytes;
+use crate::rng::random_array;
+use crate::x25519;
+
+// Double Ratchet (wg specyfikacji Signal) dla strumienia aktualizacji wspoldzielonego wpisu.
+// Kazda wiadomosc ma wlasny klucz z lancucha HMAC (forward secrecy), a kazda zmiana kierunku
+// rozmowy robi nowy krok DH X25519 (odzyskanie po wycieku stanu).
+//   KDF_RK: HKDF-SHA256(salt = root key, ikm = DH) -> 64 bajty: nowy root key || chain key
+//   KDF_CK: klucz wiadomosci = HMAC(ck, 0x01), nowy ck = HMAC(ck, 0x02)
+//   Szyfrowanie: HKDF(klucz wiadomosci) -> klucz AES-256-GCM || nonce (klucz jest jednorazowy)
+// Wiadomosc: "PMRM" || 1 || klucz DH nadawcy (32) || pn (u32 BE) || n (u32 BE) || ct || tag,
+// AAD = associated data sesji || naglowek. Sekret poczatkowy pochodzi z uzgodnienia kluczy
+// (X3DH albo klucz udostepnienia), associated data wiaze sesje z tozsamosciami stron.
+// Stan sesji zapisywany jest zaszyfrowany: "PMRS" || 1 || nonce || ct || tag, naglowek jako AAD.
+const MESSAGE_MAGIC: &[u8; 4] = b"PMRM";
+const STATE_MAGIC: &[u8; 4] = b"PMRS";
+const RATCHET_VERSION: u8 = 1;
+const MESSAGE_HEADER_LEN: usize = 4 + 1 + 32 + 4 + 4;
+const STATE_HEADER_LEN: usize = 5;
+const ROOT_INFO: &[u8] = b"pm-ratchet-root";
+const MESSAGE_INFO: &[u8] = b"pm-ratchet-message";
+// Limit pominietych wiadomosci w jednym lancuchu i lacznie przechowywanych kluczy
+const MAX_SKIP: u32 = 1000;
+const MAX_SKIPPED_KEYS: usize = 2000;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct SkippedKey {
+    dh: [u8; 32],
+    n: u32,
+    key: [u8; 32],
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct RatchetState {
+    dh_secret: [u8; 32],
+    dh_public: [u8; 32],
+    dh_remote: Option<[u8; 32]>,
+    root_key: [u8; 32],
+    send_chain: Option<[u8; 32]>,
+    recv_chain: Option<[u8; 32]>,
+    send_n: u32,
+    recv_n: u32,
+    prev_n: u32,
+    associated_data: Vec<u8>,
+    skipped: Vec<SkippedKey>,
+}
+
+impl Drop for RatchetState {
+    fn drop(&mut self) {
+        self.dh_secret.zeroize();
+        self.root_key.zeroize();
+        if let Some(ck) = self.send_chain.as_mut() {
+            ck.zeroize();
+        }
+        if let Some(ck) = self.recv_chain.as_mut() {
+            ck.zeroize();
+        }
+        for s in self.skipped.iter_mut() {
+            s.key.zeroize();
+        }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct RatchetSession {
+    state: RatchetState,
+}
+
+fn kdf_rk(root_key: &[u8; 32], dh_out: &[u8; 32]) -> Result<([u8; 32], [u8; 32]), CryptoError> {
+    let mut prk = hkdf_extract_sha256(root_key, dh_out);
+    let okm = hkdf_expand_sha256(&prk, ROOT_INFO, 64);
+    prk.zeroize();
+    let okm = Zeroizing::new(okm?);
+    let mut rk = [0u8; 32];
+    let mut ck = [0u8; 32];
+    rk.copy_from_slice(&okm[..32]);
+    ck.copy_from_slice(&okm[32..]);
+    Ok((rk, ck))
+}
+
+// Zwraca (nowy chain key, klucz wiadomosci).
+fn kdf_ck(chain_key: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
+    (hmac_sha256_bytes(chain_key, &[0x02]), hmac_sha256_bytes(chain_key, &[0x01]))
+}
+
+fn message_cipher(message_key: &[u8; 32]) -> Result<(AesGcm, [u8; NONCE_LEN]), CryptoError> {
+    let mut prk = hkdf_extract_sha256(&[0u8; 32], message_key);
+    let okm = hkdf_expand_sha256(&prk, MESSAGE_INFO, 32 + NONCE_LEN);
+    prk.zeroize();
+    let okm = Zeroizing::new(okm?);
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&okm[32..]);
+    Ok((AesGcm::new(&okm[..32])?, nonce))
+}
+
+fn message_header(dh: &[u8; 32], prev_n: u32, n: u32) -> Vec<u8> {
+    let mut out = MESSAGE_MAGIC.to_vec();
+    out.push(RATCHET_VERSION);
+    out.extend_from_slice(dh);
+    out.extend_from_slice(&prev_n.to_be_bytes());
+    out.extend_from_slice(&n.to_be_bytes());
+    out
+}
+
+fn seal(
+    state: &RatchetState,
+    message_key: &[u8; 32],
+    header: Vec<u8>,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let (cipher, nonce) = message_cipher(message_key)?;
+    let aad = [state.associated_data.as_slice(), &header].concat();
+    let sealed = cipher.encrypt(&nonce, &aad, plaintext)?;
+    let mut out = header;
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+fn open(state: &RatchetState, message_key: &[u8; 32], header: &[u8], sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let (cipher, nonce) = message_cipher(message_key)?;
+    let aad = [state.associated_data.as_slice(), header].concat();
+    cipher.decrypt(&nonce, &aad, sealed)
+}
+
+// Zapamietuje klucze wiadomosci biezacego lancucha odbiorczego az do numeru `until`.
+fn skip_message_keys(state: &mut RatchetState, until: u32) -> Result<(), CryptoError> {
+    let Some(mut ck) = state.recv_chain else {
+        return Ok(());
+    };
+    if until > state.recv_n.saturating_add(MAX_SKIP) {
+        return Err(CryptoError::OutOfRange(format!("too many skipped ratchet messages ({until})")));
+    }
+    let Some(dh) = state.dh_remote else {
+        return Ok(());
+    };
+    while state.recv_n < until {
+        let (next, key) = kdf_ck(&ck);
+        ck.zeroize();
+        ck = next;
+        state.skipped.push(SkippedKey { dh, n: state.recv_n, key });
+        state.recv_n += 1;
+    }
+    state.recv_chain = Some(ck);
+    ck.zeroize();
+    // Najstarsze klucze wypadaja pierwsze
+    if state.skipped.len() > MAX_SKIPPED_KEYS {
+        let excess = state.skipped.len() - MAX_SKIPPED_KEYS;
+        for s in state.skipped.iter_mut().take(excess) {
+            s.key.zeroize();
+        }
+        state.skipped.drain(..excess);
+    }
+    Ok(())
+}
+
+fn dh_ratchet(state: &mut RatchetState, remote: &[u8; 32]) -> Result<(), CryptoError> {
+    state.prev_n = state.send_n;
+    state.send_n = 0;
+    state.recv_n = 0;
+    state.dh_remote = Some(*remote);
+
+    let mut dh_out = x25519::shared_secret(&state.dh_secret, remote)?;
+    let (rk, recv_chain) = kdf_rk(&state.root_key, &dh_out)?;
+    state.root_key = rk;
+    state.recv_chain = Some(recv_chain);
+
+    state.dh_secret.zeroize();
+    state.dh_secret = x25519::generate_secret()?;
+    state.dh_public = x25519::public_key_bytes(&state.dh_secret);
+    dh_out = x25519::shared_secret(&state.dh_secret, remote)?;
+    let (rk, send_chain) = kdf_rk(&state.root_key, &dh_out)?;
+    dh_out.zeroize();
+    state.root_key = rk;
+    state.send_chain = Some(send_chain);
+    Ok(())
+}
+
+fn parse_message(message: &[u8]) -> Result<([u8; 32], u32, u32), CryptoError> {
+    if message.len() < MESSAGE_HEADER_LEN + TAG_LEN {
+        return Err(CryptoError::InvalidLength("ratchet message too short".to_string()));
+    }
+    if &message[..4] != MESSAGE_MAGIC {
+        return Err(CryptoError::CorruptHeader("not a ratchet message".to_string()));
+    }
+    if message[4] != RATCHET_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("ratchet message version {}", message[4])));
+    }
+    let mut dh = [0u8; 32];
+    dh.copy_from_slice(&message[5..37]);
+    let prev_n = u32::from_be_bytes([message[37], message[38], message[39], message[40]]);
+    let n = u32::from_be_bytes([message[41], message[42], message[43], message[44]]);
+    Ok((dh, prev_n, n))
+}
+
+impl RatchetSession {
+    // Strona rozpoczynajaca zna klucz ratchet odbiorcy (np. podpisany prekey z X3DH).
+    pub fn initiator(
+        shared_secret: &[u8; 32],
+        remote_public: &[u8; 32],
+        associated_data: &[u8],
+    ) -> Result<RatchetSession, CryptoError> {
+        let dh_secret = x25519::generate_secret()?;
+        let dh_public = x25519::public_key_bytes(&dh_secret);
+        let mut dh_out = x25519::shared_secret(&dh_secret, remote_public)?;
+        let (root_key, send_chain) = kdf_rk(shared_secret, &dh_out)?;
+        dh_out.zeroize();
+        Ok(RatchetSession {
+            state: RatchetState {
+                dh_secret,
+                dh_public,
+                dh_remote: Some(*remote_public),
+                root_key,
+                send_chain: Some(send_chain),
+                recv_chain: None,
+                send_n: 0,
+                recv_n: 0,
+                prev_n: 0,
+                associated_data: associated_data.to_vec(),
+                skipped: Vec::new(),
+            },
+        })
+    }
+
+    // Odbiorca moze wysylac dopiero po odebraniu pierwszej wiadomosci.
+    pub fn responder(shared_secret: &[u8; 32], ratchet_secret: &[u8; 32], associated_data: &[u8]) -> RatchetSession {
+        RatchetSession {
+            state: RatchetState {
+                dh_secret: *ratchet_secret,
+                dh_public: x25519::public_key_bytes(ratchet_secret),
+                dh_remote: None,
+                root_key: *shared_secret,
+                send_chain: None,
+                recv_chain: None,
+                send_n: 0,
+                recv_n: 0,
+                prev_n: 0,
+                associated_data: associated_data.to_vec(),
+                skipped: Vec::new(),
+            },
+        }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl RatchetSession {
+    pub fn initiate(
+        shared_secret: &[u8],
+        remote_public: &[u8],
+        associated_data: &[u8],
+    ) -> Result<RatchetSession, CryptoError> {
+        let secret = Zeroizing::new(x25519::to_key(shared_secret, "ratchet shared secret")?);
+        let remote = x25519::to_key(remote_public, "ratchet public key")?;
+        RatchetSession::initiator(&secret, &remote, associated_data)
+    }
+
+    pub fn respond(
+        shared_secret: &[u8],
+        ratchet_secret: &[u8],
+        associated_data: &[u8],
+    ) -> Result<RatchetSession, CryptoError> {
+        let secret = Zeroizing::new(x25519::to_key(shared_secret, "ratchet shared secret")?);
+        let ratchet = Zeroizing::new(x25519::to_key(ratchet_secret, "ratchet secret key")?);
+        Ok(RatchetSession::responder(&secret, &ratchet, associated_data))
+    }
+
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let state = &mut self.state;
+        let Some(mut ck) = state.send_chain else {
+            return Err(CryptoError::InvalidInput(
+                "ratchet session cannot send before receiving the first message".to_string(),
+            ));
+        };
+        let n = state.send_n;
+        let next_n = n
+            .checked_add(1)
+            .ok_or_else(|| CryptoError::InvalidInput("ratchet message number exhausted".to_string()))?;
+        let (next, mut key) = kdf_ck(&ck);
+        ck.zeroize();
+        state.send_chain = Some(next);
+        state.send_n = next_n;
+        let header = message_header(&state.dh_public, state.prev_n, n);
+        let out = seal(state, &key, header, plaintext);
+        key.zeroize();
+        out
+    }
+
+    // Odszyfrowanie dziala na kopii stanu - nieudana (podrobiona) wiadomosc niczego nie zmienia.
+    pub fn decrypt(&mut self, message: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let (dh, prev_n, n) = parse_message(message)?;
+        let (header, sealed) = message.split_at(MESSAGE_HEADER_LEN);
+        let mut state = self.state.clone();
+
+        if let Some(pos) = state.skipped.iter().position(|s| s.dh == dh && s.n == n) {
+            let mut skipped = state.skipped.remove(pos);
+            let plaintext = open(&state, &skipped.key, header, sealed);
+            skipped.key.zeroize();
+            let plaintext = plaintext?;
+            self.state = state;
+            return Ok(plaintext);
+        }
+
+        if state.dh_remote != Some(dh) {
+            skip_message_keys(&mut state, prev_n)?;
+            dh_ratchet(&mut state, &dh)?;
+        }
+        if n < state.recv_n {
+            return Err(CryptoError::VerificationFailed(format!("replayed ratchet message {n}")));
+        }
+        skip_message_keys(&mut state, n)?;
+        let Some(mut ck) = state.recv_chain else {
+            return Err(CryptoError::VerificationFailed("unexpected ratchet message".to_string()));
+        };
+        let (next, mut key) = kdf_ck(&ck);
+        ck.zeroize();
+        state.recv_chain = Some(next);
+        state.recv_n = n
+            .checked_add(1)
+            .ok_or_else(|| CryptoError::InvalidInput("ratchet message number exhausted".to_string()))?;
+        let plaintext = open(&state, &key, header, sealed);
+        key.zeroize();
+        let plaintext = plaintext?;
+        self.state = state;
+        Ok(plaintext)
+    }
+
+    // Biezacy klucz ratchet tej strony (zmienia sie przy kazdej zmianie kierunku).
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn ratchet_public(&self) -> Vec<u8> {
+        self.state.dh_public.to_vec()
+    }
+
+    pub fn save(&self, storage_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let json =
+            Zeroizing::new(serde_json::to_vec(&self.state).map_err(|e| CryptoError::InvalidInput(e.to_string()))?);
+        let mut blob = STATE_MAGIC.to_vec();
+        blob.push(RATCHET_VERSION);
+        let nonce = random_array::<NONCE_LEN>()?;
+        let sealed = AesGcm::new(storage_key)?.encrypt(&nonce, &blob, &json)?;
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&sealed);
+        Ok(blob)
+    }
+
+    pub fn restore(storage_key: &[u8], blob: &[u8]) -> Result<RatchetSession, CryptoError> {
+        if blob.len() < STATE_HEADER_LEN + NONCE_LEN + TAG_LEN || &blob[..4] != STATE_MAGIC {
+            return Err(CryptoError::CorruptHeader("not a ratchet session state".to_string()));
+        }
+        if blob[4] != RATCHET_VERSION {
+            return Err(CryptoError::UnsupportedVersion(format!("ratchet state version {}", blob[4])));
+        }
+        let (header, rest) = blob.split_at(STATE_HEADER_LEN);
+        let (nonce, sealed) = rest.split_at(NONCE_LEN);
+        let json = Zeroizing::new(AesGcm::new(storage_key)?.decrypt(nonce, header, sealed)?);
+        let state = serde_json::from_slice(&json)
+            .map_err(|e| CryptoError::CorruptHeader(format!("malformed ratchet state: {e}")))?;
+        Ok(RatchetSession { state })
+    }
+}
+
+// X25519 poza profilem FIPS
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+
+    const SHARED: [u8; 32] = [1u8; 32];
+    const AD: &[u8] = b"alice|bob";
+
+    fn pair() -> Result<(RatchetSession, RatchetSession), CryptoError> {
+        let bob_secret = x25519::generate_secret()?;
+        let bob_public = x25519::public_key_bytes(&bob_secret);
+        Ok((RatchetSession::initiator(&SHARED, &bob_public, AD)?, RatchetSession::responder(&SHARED, &bob_secret, AD)))
+    }
+
+    #[test]
+    fn in_order_conversation() -> Result<(), CryptoError> {
+        let (mut alice, mut bob) = pair()?;
+        assert!(matches!(bob.encrypt(b"too early"), Err(CryptoError::InvalidInput(_))));
+        for round in 0..3u8 {
+            let to_bob = alice.encrypt(&[b'a', round])?;
+            assert_eq!(bob.decrypt(&to_bob)?, [b'a', round]);
+            let ratchet = alice.ratchet_public();
+            let to_alice = bob.encrypt(&[b'b', round])?;
+            assert_eq!(alice.decrypt(&to_alice)?, [b'b', round]);
+            // Zmiana kierunku robi nowy krok DH
+            assert_ne!(alice.ratchet_public(), ratchet);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_order_and_skipped_messages() -> Result<(), CryptoError> {
+        let (mut alice, mut bob) = pair()?;
+        let messages: Vec<Vec<u8>> = (0..4u8).map(|i| alice.encrypt(&[i])).collect::<Result<_, _>>()?;
+        assert_eq!(bob.decrypt(&messages[2])?, [2]);
+        assert_eq!(bob.decrypt(&messages[0])?, [0]);
+        // Odpowiedz boba zmienia lancuch; pominiete klucze starego lancucha zostaja
+        let reply = bob.encrypt(b"reply")?;
+        assert_eq!(alice.decrypt(&reply)?, b"reply");
+        let next = alice.encrypt(b"next")?;
+        assert_eq!(bob.decrypt(&next)?, b"next");
+        assert_eq!(bob.decrypt(&messages[3])?, [3]);
+        assert_eq!(bob.decrypt(&messages[1])?, [1]);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_replayed_messages() -> Result<(), CryptoError> {
+        let (mut alice, mut bob) = pair()?;
+        let first = alice.encrypt(b"first")?;
+        let second = alice.encrypt(b"second")?;
+        let third = alice.encrypt(b"third")?;
+        bob.decrypt(&first)?;
+        assert!(matches!(bob.decrypt(&first), Err(CryptoError::VerificationFailed(_))));
+        // Klucz pominietej wiadomosci jest jednorazowy
+        bob.decrypt(&third)?;
+        bob.decrypt(&second)?;
+        assert!(matches!(bob.decrypt(&second), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_too_many_skipped_messages() -> Result<(), CryptoError> {
+        let (mut alice, mut bob) = pair()?;
+        bob.decrypt(&alice.encrypt(b"first")?)?;
+        let mut message = alice.encrypt(b"second")?;
+        message[41..45].copy_from_slice(&(MAX_SKIP + 2).to_be_bytes());
+        assert!(matches!(bob.decrypt(&message), Err(CryptoError::OutOfRange(_))));
+        // Odrzucona wiadomosc nie zmienia stanu
+        message[41..45].copy_from_slice(&1u32.to_be_bytes());
+        assert_eq!(bob.decrypt(&message)?, b"second");
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_messages() -> Result<(), CryptoError> {
+        let (mut alice, mut bob) = pair()?;
+        let message = alice.encrypt(b"hello")?;
+        assert!(matches!(bob.decrypt(&message[..MESSAGE_HEADER_LEN]), Err(CryptoError::InvalidLength(_))));
+        let mut bad = message.clone();
+        bad[0] = b'X';
+        assert!(matches!(bob.decrypt(&bad), Err(CryptoError::CorruptHeader(_))));
+        let mut bad = message.clone();
+        bad[4] = 2;
+        assert!(matches!(bob.decrypt(&bad), Err(CryptoError::UnsupportedVersion(_))));
+        // Naglowek jest w AAD
+        let mut bad = message.clone();
+        bad[40] ^= 1;
+        assert!(matches!(bob.decrypt(&bad), Err(CryptoError::DecryptFailed(_))));
+        assert_eq!(bob.decrypt(&message)?, b"hello");
+        Ok(())
+    }
+
+    #[test]
+    fn saved_state_round_trips() -> Result<(), CryptoError> {
+        let (mut alice, mut bob) = pair()?;
+        bob.decrypt(&alice.encrypt(b"first")?)?;
+        let saved = bob.save(&[3u8; 32])?;
+        assert!(matches!(RatchetSession::restore(&[4u8; 32], &saved), Err(CryptoError::DecryptFailed(_))));
+        let mut restored = RatchetSession::restore(&[3u8; 32], &saved)?;
+        assert_eq!(restored.decrypt(&alice.encrypt(b"second")?)?, b"second");
+        Ok(())
+    }
+}
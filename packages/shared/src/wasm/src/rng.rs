@@ -0,0 +1,221 @@
+use crate::error::CryptoError;
+
+// Zrodlo losowosci. W produkcji jest tylko OsRng - CSPRNG systemu (crypto.getRandomValues
+// w przegladarce). Wszystkie nonce, sole i klucze w crate ida przez random_bytes, wiec
+// podmiana zrodla (cecha test-rng) obejmuje caly crate naraz.
+pub trait RandomSource {
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), CryptoError>;
+}
+
+pub struct OsRng;
+
+impl RandomSource for OsRng {
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), CryptoError> {
+        getrandom::getrandom(buf).map_err(|e| CryptoError::Rng(e.to_string()))
+    }
+}
+
+#[cfg(not(feature = "test-rng"))]
+pub fn random_bytes(buf: &mut [u8]) -> Result<(), CryptoError> {
+    OsRng.fill(buf)
+}
+
+// Zrodlo, ktore samo losuje przez random_bytes, dostaje blad zamiast paniki RefCell.
+#[cfg(feature = "test-rng")]
+pub fn random_bytes(buf: &mut [u8]) -> Result<(), CryptoError> {
+    test_rng::OVERRIDE.with(|slot| match test_rng::borrow(slot)?.as_mut() {
+        Some(rng) => rng.fill(buf),
+        None => OsRng.fill(buf),
+    })
+}
+
+pub fn random_array<const N: usize>() -> Result<[u8; N], CryptoError> {
+    let mut out = [0u8; N];
+    random_bytes(&mut out)?;
+    Ok(out)
+}
+
+// Liczba z [0, bound) bez przesuniecia modulo: losowania z niepelnego ostatniego
+// przedzialu sa odrzucane i powtarzane.
+pub fn random_below(bound: u32) -> Result<u32, CryptoError> {
+    if bound == 0 {
+        return Err(CryptoError::InvalidParameter("random bound must be positive".to_string()));
+    }
+    let zone = u32::MAX - u32::MAX % bound;
+    loop {
+        let value = u32::from_le_bytes(random_array::<4>()?);
+        if value < zone {
+            return Ok(value % bound);
+        }
+    }
+}
+
+// Deterministyczne RNG nie moze trafic do builda wydania.
+#[cfg(all(feature = "test-rng", not(debug_assertions)))]
+compile_error!("the test-rng feature is for test and debug builds only; do not enable it in release builds");
+
+#[cfg(feature = "test-rng")]
+pub use test_rng::{ChaCha20Rng, clear_rng, reset_test_rng, seed_test_rng, set_rng, with_rng};
+
+// Deterministyczne RNG do testow integracyjnych i odtwarzania bledow. Tylko z cecha
+// test-rng - build produkcyjny nie ma tego kodu wcale, wiec nie da sie go wlaczyc w runtime.
+#[cfg(feature = "test-rng")]
+mod test_rng {
+    use std::cell::{RefCell, RefMut};
+
+    #[cfg(feature = "js")]
+    use wasm_bindgen::prelude::*;
+    use zeroize::Zeroize;
+
+    use super::*;
+    use crate::chacha20::chacha20_block;
+
+    thread_local! {
+        pub(super) static OVERRIDE: RefCell<Option<Box<dyn RandomSource>>> = RefCell::new(None);
+    }
+
+    type Slot = RefCell<Option<Box<dyn RandomSource>>>;
+
+    pub(super) fn borrow(slot: &Slot) -> Result<RefMut<'_, Option<Box<dyn RandomSource>>>, CryptoError> {
+        slot.try_borrow_mut().map_err(|_| CryptoError::Rng("test rng used re-entrantly".to_string()))
+    }
+
+    // Strumien klucza ChaCha20 (RFC 8439) z kluczem = ziarno, nonce = 0, licznik od 0.
+    pub struct ChaCha20Rng {
+        key: [u8; 32],
+        counter: u32,
+        block: [u8; 64],
+        used: usize,
+    }
+
+    impl Drop for ChaCha20Rng {
+        fn drop(&mut self) {
+            self.key.zeroize();
+            self.block.zeroize();
+        }
+    }
+
+    impl ChaCha20Rng {
+        pub fn from_seed(seed: [u8; 32]) -> ChaCha20Rng {
+            ChaCha20Rng { key: seed, counter: 0, block: [0; 64], used: 64 }
+        }
+
+        fn refill(&mut self) -> Result<(), CryptoError> {
+            self.block = chacha20_block(&self.key, self.counter, &[0; 12]);
+            self.counter = self
+                .counter
+                .checked_add(1)
+                .ok_or_else(|| CryptoError::Rng("test rng stream exhausted".to_string()))?;
+            self.used = 0;
+            Ok(())
+        }
+    }
+
+    impl RandomSource for ChaCha20Rng {
+        fn fill(&mut self, buf: &mut [u8]) -> Result<(), CryptoError> {
+            let mut filled = 0;
+            while filled < buf.len() {
+                if self.used == 64 {
+                    self.refill()?;
+                }
+                let n = (64 - self.used).min(buf.len() - filled);
+                buf[filled..filled + n].copy_from_slice(&self.block[self.used..self.used + n]);
+                self.used += n;
+                filled += n;
+            }
+            Ok(())
+        }
+    }
+
+    // Zrodlo dla biezacego watku az do clear_rng.
+    pub fn set_rng(rng: Box<dyn RandomSource>) -> Result<(), CryptoError> {
+        OVERRIDE.with(|slot| {
+            *borrow(slot)? = Some(rng);
+            Ok(())
+        })
+    }
+
+    pub fn clear_rng() -> Result<(), CryptoError> {
+        OVERRIDE.with(|slot| {
+            *borrow(slot)? = None;
+            Ok(())
+        })
+    }
+
+    // Uruchamia f z podanym zrodlem i przywraca poprzednie (takze zagniezdzone wywolania).
+    pub fn with_rng<R: RandomSource + 'static, T>(rng: R, f: impl FnOnce() -> T) -> Result<T, CryptoError> {
+        let previous = OVERRIDE.with(|slot| Ok::<_, CryptoError>(borrow(slot)?.replace(Box::new(rng))))?;
+        let result = f();
+        OVERRIDE.with(|slot| {
+            *borrow(slot)? = previous;
+            Ok(result)
+        })
+    }
+
+    // Dla testow po stronie JS: wszystkie kolejne losowania z ChaCha20(seed).
+    #[cfg_attr(feature = "js", wasm_bindgen)]
+    pub fn seed_test_rng(seed: &[u8]) -> Result<(), CryptoError> {
+        let seed: [u8; 32] = seed
+            .try_into()
+            .map_err(|_| CryptoError::InvalidLength("test rng seed must be 32 bytes".to_string()))?;
+        set_rng(Box::new(ChaCha20Rng::from_seed(seed)))
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen)]
+    pub fn reset_test_rng() -> Result<(), CryptoError> {
+        clear_rng()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_below_stays_in_range() -> Result<(), CryptoError> {
+        for bound in [1, 2, 3, 7, 1000, u32::MAX] {
+            assert!(random_below(bound)? < bound);
+        }
+        assert!(matches!(random_below(0), Err(CryptoError::InvalidParameter(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn random_arrays_differ() -> Result<(), CryptoError> {
+        assert_ne!(random_array::<32>()?, random_array::<32>()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "test-rng")]
+    #[test]
+    fn seeded_rng_is_the_chacha20_keystream() -> Result<(), CryptoError> {
+        // RFC 8439 A.1, wektor 1: klucz 0, nonce 0, licznik 0
+        let prefix = [0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1, 0x3d, 0x90];
+        let first = with_rng(ChaCha20Rng::from_seed([0; 32]), random_array::<8>)??;
+        assert_eq!(first, prefix);
+        let split = with_rng(ChaCha20Rng::from_seed([0; 32]), || {
+            Ok::<_, CryptoError>([random_array::<3>()?, random_array::<3>()?].concat())
+        })??;
+        assert_eq!(split, prefix[..6]);
+        Ok(())
+    }
+
+    #[cfg(feature = "test-rng")]
+    struct ReentrantRng;
+
+    #[cfg(feature = "test-rng")]
+    impl RandomSource for ReentrantRng {
+        fn fill(&mut self, buf: &mut [u8]) -> Result<(), CryptoError> {
+            random_bytes(buf)
+        }
+    }
+
+    #[cfg(feature = "test-rng")]
+    #[test]
+    fn reentrant_source_is_an_error_not_a_panic() -> Result<(), CryptoError> {
+        let result = with_rng(ReentrantRng, random_array::<4>)?;
+        assert!(matches!(result, Err(CryptoError::Rng(_))));
+        assert!(random_array::<4>().is_ok());
+        Ok(())
+    }
+}
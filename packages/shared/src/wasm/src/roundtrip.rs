@@ -0,0 +1,406 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::Value;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+use crate::import::ImportedItem;
+use crate::import::bitwarden::import_bitwarden_bytes;
+use crate::import::csv::{CsvSource, import_csv_bytes};
+use crate::import::kdbx::import_kdbx_bytes;
+
+// Weryfikacja importu/eksportu: ponowne sparsowanie wyniku, normalizacja obu stron
+// i raport roznic na poziomie pol. Raport zawiera tylko sciezki pol, nigdy wartosci,
+// zeby nie wyciekaly hasla do logow/UI.
+//
+// Normalizacja: CRLF -> LF, null / "" / [] / {} traktowane jak brak pola (eksportery
+// pomijaja puste pola). Elementy dopasowywane po "id", a bez niego po "name"; kolejne
+// elementy o tym samym kluczu dostaja numer wystapienia ("name#2") i sa porownywane osobno.
+// CSV i KDBX nie zachowuja identyfikatorow (nowe losowe id / UUID), wiec tam "id" jest
+// pomijane po obu stronach i dopasowanie idzie po nazwie.
+// Zalaczniki ("attachments") porownywane osobno po nazwie i skrocie/rozmiarze.
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    pub item: String,
+    pub field: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Missing,
+    Added,
+    Changed,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct AttachmentRef {
+    pub item: String,
+    pub attachment: String,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoundTripReport {
+    pub ok: bool,
+    pub source_items: usize,
+    pub artifact_items: usize,
+    pub missing_items: Vec<String>,
+    pub extra_items: Vec<String>,
+    pub altered_fields: Vec<FieldChange>,
+    pub dropped_attachments: Vec<AttachmentRef>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArtifactFormat {
+    Json,
+    Csv(CsvSource),
+    Bitwarden,
+    Kdbx,
+}
+
+impl ArtifactFormat {
+    // "json", "csv:lastpass" / "csv:chrome" / ..., "bitwarden", "kdbx".
+    pub fn from_name(name: &str) -> Result<ArtifactFormat, CryptoError> {
+        match name {
+            "json" => Ok(ArtifactFormat::Json),
+            "bitwarden" => Ok(ArtifactFormat::Bitwarden),
+            "kdbx" => Ok(ArtifactFormat::Kdbx),
+            other => match other.strip_prefix("csv:") {
+                Some(source) => Ok(ArtifactFormat::Csv(CsvSource::from_name(source)?)),
+                None => Err(CryptoError::UnsupportedAlgorithm(format!("artifact format: {other}"))),
+            },
+        }
+    }
+
+    fn keeps_ids(self) -> bool {
+        matches!(self, ArtifactFormat::Json | ArtifactFormat::Bitwarden)
+    }
+
+    // Zwraca liste elementow w postaci JSON niezaleznie od formatu artefaktu. password - haslo
+    // eksportu chronionego (Bitwarden, KDBX), dla pozostalych ignorowane.
+    fn parse_items(self, artifact: &[u8], password: &[u8]) -> Result<Vec<Value>, CryptoError> {
+        let text = || {
+            std::str::from_utf8(artifact).map_err(|_| CryptoError::InvalidInput("artifact is not UTF-8".to_string()))
+        };
+        match self {
+            ArtifactFormat::Json => parse_json_items(text()?),
+            ArtifactFormat::Csv(source) => {
+                let result = import_csv_bytes(source, text()?)?;
+                // Odrzucony wiersz to element, ktorego w artefakcie nie ma - wyjdzie jako brakujacy
+                imported_values(result.items)
+            }
+            ArtifactFormat::Bitwarden => imported_values(import_bitwarden_bytes(artifact, password)?),
+            ArtifactFormat::Kdbx => imported_values(import_kdbx_bytes(artifact, password)?),
+        }
+    }
+}
+
+fn imported_values(items: Vec<ImportedItem>) -> Result<Vec<Value>, CryptoError> {
+    items
+        .into_iter()
+        .map(|item| serde_json::to_value(item).map_err(|e| CryptoError::InvalidInput(e.to_string())))
+        .collect()
+}
+
+// Tablica elementow albo obiekt {"items": [...]}.
+pub fn parse_json_items(text: &str) -> Result<Vec<Value>, CryptoError> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|e| CryptoError::InvalidInput(format!("malformed JSON: {e}")))?;
+    match value {
+        Value::Array(items) => Ok(items),
+        Value::Object(mut obj) => match obj.remove("items") {
+            Some(Value::Array(items)) => Ok(items),
+            _ => Err(CryptoError::InvalidInput("expected an \"items\" array".to_string())),
+        },
+        _ => Err(CryptoError::InvalidInput("expected an array of items".to_string())),
+    }
+}
+
+fn normalize(value: &Value) -> Option<Value> {
+    match value {
+        Value::Null => None,
+        Value::String(s) if s.is_empty() => None,
+        Value::String(s) => Some(Value::String(s.replace("\r\n", "\n"))),
+        Value::Array(items) => {
+            let items: Vec<Value> = items.iter().filter_map(normalize).collect();
+            (!items.is_empty()).then_some(Value::Array(items))
+        }
+        Value::Object(obj) => {
+            let obj: serde_json::Map<String, Value> = obj
+                .iter()
+                .filter_map(|(k, v)| normalize(v).map(|v| (k.clone(), v)))
+                .collect();
+            (!obj.is_empty()).then_some(Value::Object(obj))
+        }
+        other => Some(other.clone()),
+    }
+}
+
+fn item_key(item: &Value, index: usize) -> String {
+    let scalar = |v: &Value| match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    item.get("id")
+        .and_then(scalar)
+        .or_else(|| item.get("name").and_then(scalar))
+        .unwrap_or_else(|| format!("#{index}"))
+}
+
+// Splaszcza obiekt do sciezek "login.username", "fields[1].value".
+pub fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(obj) => {
+            for (k, v) in obj {
+                let path = if prefix.is_empty() { k.clone() } else { format!("{prefix}.{k}") };
+                flatten(&path, v, out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(&format!("{prefix}[{i}]"), v, out);
+            }
+        }
+        other => {
+            out.insert(prefix.to_string(), other.clone());
+        }
+    }
+}
+
+fn attachments(item: &mut Value) -> BTreeMap<String, Value> {
+    let mut out = BTreeMap::new();
+    if let Some(Value::Array(list)) = item.as_object_mut().and_then(|o| o.remove("attachments")) {
+        for (i, a) in list.into_iter().enumerate() {
+            let name = a
+                .get("id")
+                .or_else(|| a.get("name"))
+                .or_else(|| a.get("file_name"))
+                .and_then(Value::as_str)
+                .map_or_else(|| format!("#{i}"), str::to_string);
+            out.insert(name, a);
+        }
+    }
+    out
+}
+
+// Klucz (id albo nazwa, numer wystapienia) - elementy o tej samej nazwie nie zlewaja sie w jeden.
+pub type ItemKey = (String, usize);
+
+pub fn key_label((key, occurrence): &ItemKey) -> String {
+    if *occurrence == 1 { key.clone() } else { format!("{key}#{occurrence}") }
+}
+
+pub fn index_items(items: &[Value]) -> BTreeMap<ItemKey, Value> {
+    let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+    let mut out = BTreeMap::new();
+    for (i, item) in items.iter().enumerate() {
+        let Some(value) = normalize(item) else { continue };
+        let key = item_key(item, i);
+        let occurrence = seen.entry(key.clone()).or_default();
+        *occurrence += 1;
+        out.insert((key, *occurrence), value);
+    }
+    out
+}
+
+pub fn compare_items(source: &[Value], artifact: &[Value]) -> RoundTripReport {
+    let source_map = index_items(source);
+    let mut artifact_map = index_items(artifact);
+    let mut report = RoundTripReport {
+        source_items: source.len(),
+        artifact_items: artifact.len(),
+        ..RoundTripReport::default()
+    };
+
+    for (item_key, mut expected) in source_map {
+        let key = key_label(&item_key);
+        let Some(mut actual) = artifact_map.remove(&item_key) else {
+            report.missing_items.push(key);
+            continue;
+        };
+        let expected_att = attachments(&mut expected);
+        let actual_att = attachments(&mut actual);
+        for (name, att) in &expected_att {
+            // Zalacznik bez skrotu/rozmiaru albo z innym - traktowany jako utracony
+            if actual_att.get(name) != Some(att) {
+                report.dropped_attachments.push(AttachmentRef { item: key.clone(), attachment: name.clone() });
+            }
+        }
+
+        let (mut want, mut got) = (BTreeMap::new(), BTreeMap::new());
+        flatten("", &expected, &mut want);
+        flatten("", &actual, &mut got);
+        for (field, value) in &want {
+            let kind = match got.remove(field) {
+                None => ChangeKind::Missing,
+                Some(v) if v != *value => ChangeKind::Changed,
+                Some(_) => continue,
+            };
+            report.altered_fields.push(FieldChange { item: key.clone(), field: field.clone(), kind });
+        }
+        for field in got.into_keys() {
+            report.altered_fields.push(FieldChange { item: key.clone(), field, kind: ChangeKind::Added });
+        }
+    }
+    report.extra_items = artifact_map.keys().map(key_label).collect();
+    report.ok = report.missing_items.is_empty()
+        && report.extra_items.is_empty()
+        && report.altered_fields.is_empty()
+        && report.dropped_attachments.is_empty()
+        && report.source_items == report.artifact_items;
+    report
+}
+
+fn without_ids(items: Vec<Value>) -> Vec<Value> {
+    items
+        .into_iter()
+        .map(|mut item| {
+            if let Some(obj) = item.as_object_mut() {
+                obj.remove("id");
+            }
+            item
+        })
+        .collect()
+}
+
+pub fn verify_round_trip_bytes(
+    format: ArtifactFormat,
+    source_items: &str,
+    artifact: &[u8],
+    password: &[u8],
+) -> Result<RoundTripReport, CryptoError> {
+    let mut source = parse_json_items(source_items)?;
+    let mut produced = format.parse_items(artifact, password)?;
+    if !format.keeps_ids() {
+        source = without_ids(source);
+        produced = without_ids(produced);
+    }
+    Ok(compare_items(&source, &produced))
+}
+
+fn report_json(report: &RoundTripReport) -> Result<String, CryptoError> {
+    serde_json::to_string(report).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+// source_items - elementy vaulta jako JSON (model importu), artifact - wynik eksportu (albo
+// zrodlo importu) w formacie tekstowym. Zwraca raport jako JSON.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_round_trip(format: &str, source_items: &str, artifact: &str) -> Result<String, CryptoError> {
+    report_json(&verify_round_trip_bytes(ArtifactFormat::from_name(format)?, source_items, artifact.as_bytes(), b"")?)
+}
+
+// Artefakt binarny albo chroniony haslem (KDBX, zaszyfrowany eksport Bitwarden).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn verify_round_trip_raw(
+    format: &str,
+    source_items: &str,
+    artifact: &[u8],
+    password: &[u8],
+) -> Result<String, CryptoError> {
+    report_json(&verify_round_trip_bytes(ArtifactFormat::from_name(format)?, source_items, artifact, password)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibrate::KdfParams;
+    use crate::import::kdbx::export_kdbx_raw;
+
+    const PARAMS: KdfParams =
+        KdfParams { pbkdf2_iterations: 1000, argon2_m_cost: 64, argon2_t_cost: 1, argon2_parallelism: 1 };
+
+    const TWO_GITHUB: &str = r#"[
+        {"kind": "login", "name": "GitHub", "login": {"username": "work", "password": "pw-1"}},
+        {"kind": "login", "name": "GitHub", "login": {"username": "home", "password": "pw-2"}}
+    ]"#;
+
+    fn json_report(source: &str, artifact: &str) -> Result<RoundTripReport, CryptoError> {
+        verify_round_trip_bytes(ArtifactFormat::Json, source, artifact.as_bytes(), b"")
+    }
+
+    #[test]
+    fn items_sharing_a_name_are_compared_one_by_one() -> Result<(), CryptoError> {
+        assert!(json_report(TWO_GITHUB, TWO_GITHUB)?.ok);
+
+        // Drugi wpis "GitHub" zgubiony - dawniej oba zlewaly sie w jeden klucz i raport byl pusty
+        let one = r#"[{"kind": "login", "name": "GitHub", "login": {"username": "work", "password": "pw-1"}}]"#;
+        let report = json_report(TWO_GITHUB, one)?;
+        assert!(!report.ok);
+        assert_eq!(report.missing_items, vec!["GitHub#2".to_string()]);
+
+        let changed = TWO_GITHUB.replace("pw-2", "pw-3");
+        let report = json_report(TWO_GITHUB, &changed)?;
+        assert_eq!(
+            report.altered_fields,
+            vec![FieldChange {
+                item: "GitHub#2".to_string(),
+                field: "login.password".to_string(),
+                kind: ChangeKind::Changed
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn csv_artifact_is_matched_by_name() -> Result<(), CryptoError> {
+        let source = r#"[{"id": "a1", "kind": "login", "name": "GitHub",
+            "login": {"username": "octo", "password": "pw", "uris": ["https://github.com"]}}]"#;
+        let csv = "name,url,username,password\nGitHub,https://github.com,octo,pw\n";
+        let format = ArtifactFormat::from_name("csv:chrome")?;
+        assert!(verify_round_trip_bytes(format, source, csv.as_bytes(), b"")?.ok);
+
+        let report = verify_round_trip_bytes(format, source, csv.replace(",pw", ",other").as_bytes(), b"")?;
+        assert_eq!(report.altered_fields.len(), 1);
+        assert_eq!(report.altered_fields[0].field, "login.password");
+        Ok(())
+    }
+
+    #[test]
+    fn bitwarden_artifact_keeps_ids() -> Result<(), CryptoError> {
+        let source = r#"[{"id": "b1", "kind": "login", "name": "Mail", "login": {"username": "me"}}]"#;
+        let export = r#"{"encrypted": false, "items": [
+            {"id": "b1", "type": 1, "name": "Mail", "login": {"username": "me"}}
+        ]}"#;
+        let format = ArtifactFormat::from_name("bitwarden")?;
+        assert!(verify_round_trip_bytes(format, source, export.as_bytes(), b"")?.ok);
+
+        let report = verify_round_trip_bytes(format, source, export.replace("\"b1\"", "\"b2\"").as_bytes(), b"")?;
+        assert_eq!(report.missing_items, vec!["b1".to_string()]);
+        assert_eq!(report.extra_items, vec!["b2".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn kdbx_export_round_trips() -> Result<(), CryptoError> {
+        let source = r#"{"items": [
+            {"id": "k1", "kind": "login", "name": "Bank", "notes": "pin\r\nelsewhere",
+             "login": {"username": "jan", "password": "s3cret", "uris": ["https://bank.example"]}},
+            {"id": "k2", "kind": "login", "name": "Bank", "login": {"username": "anna", "password": "other"}}
+        ]}"#;
+        let artifact = export_kdbx_raw(source, b"file password", &PARAMS)?;
+        let format = ArtifactFormat::from_name("kdbx")?;
+        let report = verify_round_trip_bytes(format, source, &artifact, b"file password")?;
+        assert!(report.ok, "{report:?}");
+        assert!(matches!(
+            verify_round_trip_bytes(format, source, &artifact, b"wrong password"),
+            Err(CryptoError::WrongPassword(_) | CryptoError::BadMac(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_formats_and_bad_artifacts_are_rejected() {
+        assert!(matches!(ArtifactFormat::from_name("xml"), Err(CryptoError::UnsupportedAlgorithm(_))));
+        assert!(matches!(ArtifactFormat::from_name("csv:opera"), Err(CryptoError::UnsupportedAlgorithm(_))));
+        assert!(matches!(json_report("[]", "{\"items\": 1}"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(
+            verify_round_trip_bytes(ArtifactFormat::Json, "[]", &[0xff, 0xfe], b""),
+            Err(CryptoError::InvalidInput(_))
+        ));
+    }
+}
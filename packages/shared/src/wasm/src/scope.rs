@@ -0,0 +1,254 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::rng::random_array;
+use crate::secret::{EntryKey, MasterKey};
+
+// Czesciowe odblokowanie vaulta. Kazda partycja (loginy, notatki, OTP, ...) ma wlasny klucz
+// wyprowadzony HKDF z klucza vaulta, wiec autofill moze trzymac w pamieci tylko klucz
+// loginow - reszta pozostaje zapieczetowana do jawnego odblokowania kluczem vaulta.
+// ScopedVault nie przechowuje klucza vaulta, tylko klucze odblokowanych partycji.
+// Blob partycji: "PMSP" || 1 || id partycji || nonce || ciphertext || tag, naglowek || aad
+// jako AAD - blobu nie da sie przeniesc do innej partycji.
+const PARTITION_MAGIC: &[u8; 4] = b"PMSP";
+const PARTITION_VERSION: u8 = 1;
+const PARTITION_HEADER_LEN: usize = 6;
+const SCOPE_KEY_INFO: &[u8] = b"pm-vault-scope\0";
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VaultScope {
+    Logins,
+    SecureNotes,
+    Cards,
+    Identities,
+    OtpSeeds,
+    Attachments,
+}
+
+impl VaultScope {
+    pub const ALL: [VaultScope; 6] = [
+        VaultScope::Logins,
+        VaultScope::SecureNotes,
+        VaultScope::Cards,
+        VaultScope::Identities,
+        VaultScope::OtpSeeds,
+        VaultScope::Attachments,
+    ];
+
+    pub fn from_name(name: &str) -> Result<VaultScope, CryptoError> {
+        VaultScope::ALL
+            .into_iter()
+            .find(|s| s.name() == name)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("unknown vault scope: {name}")))
+    }
+
+    // Nazwa jest czescia wyprowadzenia klucza - nie zmieniac.
+    pub fn name(self) -> &'static str {
+        match self {
+            VaultScope::Logins => "logins",
+            VaultScope::SecureNotes => "secure_notes",
+            VaultScope::Cards => "cards",
+            VaultScope::Identities => "identities",
+            VaultScope::OtpSeeds => "otp_seeds",
+            VaultScope::Attachments => "attachments",
+        }
+    }
+
+    // Identyfikator w naglowku blobu - stabilny, nie zmieniac.
+    pub fn id(self) -> u8 {
+        match self {
+            VaultScope::Logins => 1,
+            VaultScope::SecureNotes => 2,
+            VaultScope::Cards => 3,
+            VaultScope::Identities => 4,
+            VaultScope::OtpSeeds => 5,
+            VaultScope::Attachments => 6,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<VaultScope, CryptoError> {
+        VaultScope::ALL
+            .into_iter()
+            .find(|s| s.id() == id)
+            .ok_or_else(|| CryptoError::CorruptHeader(format!("unknown vault scope id {id}")))
+    }
+}
+
+pub fn derive_scope_key(vault_key: &MasterKey, scope: VaultScope) -> Result<EntryKey, CryptoError> {
+    if vault_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("vault key must be 32 bytes".to_string()));
+    }
+    let info = [SCOPE_KEY_INFO, scope.name().as_bytes()].concat();
+    Ok(EntryKey::from_vec(hkdf_sha256_bytes(vault_key.as_bytes(), &[], &info, 32)?))
+}
+
+fn partition_header(scope: VaultScope) -> Vec<u8> {
+    let mut out = PARTITION_MAGIC.to_vec();
+    out.push(PARTITION_VERSION);
+    out.push(scope.id());
+    out
+}
+
+pub fn partition_scope_bytes(blob: &[u8]) -> Result<VaultScope, CryptoError> {
+    if blob.len() < PARTITION_HEADER_LEN + NONCE_LEN + TAG_LEN || &blob[..4] != PARTITION_MAGIC {
+        return Err(CryptoError::CorruptHeader("not a vault partition blob".to_string()));
+    }
+    if blob[4] != PARTITION_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("vault partition version {}", blob[4])));
+    }
+    VaultScope::from_id(blob[5])
+}
+
+pub fn seal_partition_bytes(
+    scope_key: &EntryKey,
+    scope: VaultScope,
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let mut blob = partition_header(scope);
+    let nonce = random_array::<NONCE_LEN>()?;
+    let full_aad = [blob.as_slice(), aad].concat();
+    let sealed = AesGcm::new(scope_key.as_bytes())?.encrypt(&nonce, &full_aad, plaintext)?;
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&sealed);
+    Ok(blob)
+}
+
+pub fn open_partition_bytes(scope_key: &EntryKey, aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    partition_scope_bytes(blob)?;
+    let (header, rest) = blob.split_at(PARTITION_HEADER_LEN);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let full_aad = [header, aad].concat();
+    AesGcm::new(scope_key.as_bytes())?.decrypt(nonce, &full_aad, sealed)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct ScopedVault {
+    keys: Vec<(VaultScope, EntryKey)>,
+}
+
+impl ScopedVault {
+    pub fn with_scopes(vault_key: &MasterKey, scopes: &[VaultScope]) -> Result<ScopedVault, CryptoError> {
+        let mut vault = ScopedVault { keys: Vec::new() };
+        for &scope in scopes {
+            vault.unlock_scope(vault_key, scope)?;
+        }
+        Ok(vault)
+    }
+
+    pub fn unlock_scope(&mut self, vault_key: &MasterKey, scope: VaultScope) -> Result<(), CryptoError> {
+        if !self.is_unlocked(scope) {
+            self.keys.push((scope, derive_scope_key(vault_key, scope)?));
+        }
+        Ok(())
+    }
+
+    fn key(&self, scope: VaultScope) -> Result<&EntryKey, CryptoError> {
+        self.keys
+            .iter()
+            .find(|(s, _)| *s == scope)
+            .map(|(_, k)| k)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("vault scope {} is locked", scope.name())))
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl ScopedVault {
+    // scopes - nazwy partycji do odblokowania, np. ["logins"] dla autofill.
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(vault_key: &[u8], scopes: Vec<String>) -> Result<ScopedVault, CryptoError> {
+        let scopes = scopes.iter().map(|s| VaultScope::from_name(s)).collect::<Result<Vec<_>, _>>()?;
+        ScopedVault::with_scopes(&MasterKey::from_slice(vault_key), &scopes)
+    }
+
+    pub fn unlock(&mut self, vault_key: &[u8], scope: VaultScope) -> Result<(), CryptoError> {
+        self.unlock_scope(&MasterKey::from_slice(vault_key), scope)
+    }
+
+    // Usuwa (i czysci) klucz partycji, np. po zamknieciu notatki.
+    pub fn lock(&mut self, scope: VaultScope) {
+        self.keys.retain(|(s, _)| *s != scope);
+    }
+
+    pub fn is_unlocked(&self, scope: VaultScope) -> bool {
+        self.keys.iter().any(|(s, _)| *s == scope)
+    }
+
+    pub fn seal(&self, scope: VaultScope, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        seal_partition_bytes(self.key(scope)?, scope, aad, plaintext)
+    }
+
+    // Partycja odczytywana z naglowka blobu.
+    pub fn open(&self, aad: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let scope = partition_scope_bytes(blob)?;
+        open_partition_bytes(self.key(scope)?, aad, blob)
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_scope_key(vault_key: &[u8], scope: VaultScope) -> Result<Vec<u8>, CryptoError> {
+    Ok(derive_scope_key(&MasterKey::from_slice(vault_key), scope)?.as_bytes().to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_partition_scope(blob: &[u8]) -> Result<VaultScope, CryptoError> {
+    partition_scope_bytes(blob)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_KEY: [u8; 32] = [0x42; 32];
+
+    #[test]
+    fn blob_sealed_under_one_scope_fails_under_another() -> Result<(), CryptoError> {
+        let vault_key = MasterKey::from_slice(&VAULT_KEY);
+        let logins = derive_scope_key(&vault_key, VaultScope::Logins)?;
+        let notes = derive_scope_key(&vault_key, VaultScope::SecureNotes)?;
+        assert_ne!(logins.as_bytes(), notes.as_bytes());
+        let blob = seal_partition_bytes(&logins, VaultScope::Logins, b"entry-1", b"secret")?;
+        assert_eq!(partition_scope_bytes(&blob)?, VaultScope::Logins);
+        assert_eq!(open_partition_bytes(&logins, b"entry-1", &blob)?, b"secret");
+        assert!(matches!(open_partition_bytes(&notes, b"entry-1", &blob), Err(CryptoError::DecryptFailed(_))));
+        assert!(matches!(open_partition_bytes(&logins, b"entry-2", &blob), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_scope_id_is_rejected() -> Result<(), CryptoError> {
+        let vault = ScopedVault::with_scopes(&MasterKey::from_slice(&VAULT_KEY), &VaultScope::ALL)?;
+        let blob = vault.seal(VaultScope::Logins, b"", b"secret")?;
+        assert!(blob.starts_with(b"PMSP\x01\x01"));
+        // Naglowek jest w AAD - zmiana partycji na inna, odblokowana, nie przechodzi.
+        let mut moved = blob.clone();
+        moved[5] = VaultScope::SecureNotes.id();
+        assert!(matches!(vault.open(b"", &moved), Err(CryptoError::DecryptFailed(_))));
+        let mut unknown = blob.clone();
+        unknown[5] = 0xff;
+        assert!(matches!(vault.open(b"", &unknown), Err(CryptoError::CorruptHeader(_))));
+        let mut version = blob.clone();
+        version[4] = 2;
+        assert!(matches!(vault.open(b"", &version), Err(CryptoError::UnsupportedVersion(_))));
+        assert_eq!(vault.open(b"", &blob)?, b"secret");
+        Ok(())
+    }
+
+    #[test]
+    fn locked_scope_refuses_seal_and_open() -> Result<(), CryptoError> {
+        let mut vault = ScopedVault::new(&VAULT_KEY, vec!["logins".to_string(), "otp_seeds".to_string()])?;
+        assert!(!vault.is_unlocked(VaultScope::SecureNotes));
+        assert!(matches!(vault.seal(VaultScope::SecureNotes, b"", b"x"), Err(CryptoError::InvalidInput(_))));
+        let seed = vault.seal(VaultScope::OtpSeeds, b"", b"seed")?;
+        vault.lock(VaultScope::OtpSeeds);
+        assert!(matches!(vault.open(b"", &seed), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(vault.seal(VaultScope::OtpSeeds, b"", b"x"), Err(CryptoError::InvalidInput(_))));
+        vault.unlock(&VAULT_KEY, VaultScope::OtpSeeds)?;
+        assert_eq!(vault.open(b"", &seed)?, b"seed");
+        Ok(())
+    }
+}
@@ -0,0 +1,391 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+use crate::hmac::HmacSha256;
+use crate::limits::{check_dk_len, check_iterations, check_memory_kib, check_salt_len};
+use crate::phc::PhcString;
+use crate::rng::random_array;
+
+// scrypt (RFC 7914) i jego dwa zapisy tekstowe:
+//   PHC: $scrypt$ln=<log2 N>,r=<r>,p=<p>$<sol base64>$<hash base64>
+//   MCF: $7$<N: 1 znak><r: 5 znakow><p: 5 znakow><sol>$<hash 43 znaki>   (libxcrypt/libsodium)
+// W $7$ liczby i hash sa w base64 "crypt" (./0-9A-Za-z) little-endian, a sola sa
+// bajty tekstu soli takie, jak stoja w stringu (nie dekodowane).
+const ITOA64: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const DEFAULT_SALT_LEN: usize = 16;
+const DEFAULT_HASH_LEN: usize = 32;
+const MCF_HASH_CHARS: usize = 43;
+
+// Domyslne parametry nowych hashy (N = 2^17, r = 8, p = 1 - 128 MiB).
+pub const DEFAULT_LOG_N: u8 = 17;
+pub const DEFAULT_R: u32 = 8;
+pub const DEFAULT_P: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScryptParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl ScryptParams {
+    // Parametry zapisane jako N (np. w eksportach innych menedzerow), nie log2 N.
+    pub fn from_n(n: u64, r: u32, p: u32) -> Result<ScryptParams, CryptoError> {
+        if n < 2 || !n.is_power_of_two() {
+            return Err(CryptoError::OutOfRange("scrypt N must be a power of two greater than 1".to_string()));
+        }
+        Ok(ScryptParams { log_n: n.trailing_zeros() as u8, r, p })
+    }
+}
+
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
+
+fn salsa20_8(b: &mut [u32; 16]) {
+    let mut x = *b;
+    for _ in 0..4 {
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 5, 9, 13, 1);
+        quarter_round(&mut x, 10, 14, 2, 6);
+        quarter_round(&mut x, 15, 3, 7, 11);
+        quarter_round(&mut x, 0, 1, 2, 3);
+        quarter_round(&mut x, 5, 6, 7, 4);
+        quarter_round(&mut x, 10, 11, 8, 9);
+        quarter_round(&mut x, 15, 12, 13, 14);
+    }
+    for (w, v) in b.iter_mut().zip(x.iter()) {
+        *w = w.wrapping_add(*v);
+    }
+    x.zeroize();
+}
+
+// BlockMix: 2r blokow po 16 slow; wyjscie - najpierw bloki parzyste, potem nieparzyste.
+fn block_mix(input: &[u32], out: &mut [u32], r: usize) {
+    let mut x = [0u32; 16];
+    x.copy_from_slice(&input[(2 * r - 1) * 16..]);
+    for i in 0..2 * r {
+        for (a, b) in x.iter_mut().zip(&input[i * 16..i * 16 + 16]) {
+            *a ^= b;
+        }
+        salsa20_8(&mut x);
+        let dst = if i.is_multiple_of(2) { i / 2 } else { r + i / 2 };
+        out[dst * 16..dst * 16 + 16].copy_from_slice(&x);
+    }
+    x.zeroize();
+}
+
+fn ro_mix(block: &mut [u8], n: usize, r: usize) {
+    let words = 32 * r;
+    let mut x: Vec<u32> = block
+        .chunks_exact(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+    let mut v = vec![0u32; n * words];
+    let mut tmp = vec![0u32; words];
+    for i in 0..n {
+        v[i * words..(i + 1) * words].copy_from_slice(&x);
+        block_mix(&x, &mut tmp, r);
+        std::mem::swap(&mut x, &mut tmp);
+    }
+    for _ in 0..n {
+        // Integerify: pierwsze slowo ostatniego bloku 64-bajtowego
+        let j = x[(2 * r - 1) * 16] as usize & (n - 1);
+        for (a, b) in x.iter_mut().zip(&v[j * words..(j + 1) * words]) {
+            *a ^= b;
+        }
+        block_mix(&x, &mut tmp, r);
+        std::mem::swap(&mut x, &mut tmp);
+    }
+    for (chunk, w) in block.chunks_exact_mut(4).zip(x.iter()) {
+        chunk.copy_from_slice(&w.to_le_bytes());
+    }
+    x.zeroize();
+    v.zeroize();
+    tmp.zeroize();
+}
+
+// PBKDF2-HMAC-SHA256 z jedna iteracja. Bez limitow z pbkdf2.rs - bufor B ma p * 128 * r
+// bajtow i zwykle przekracza limit dlugosci klucza, a jego rozmiar sprawdza check_params.
+fn pbkdf2_once(password: &[u8], salt: &[u8], len: usize) -> Vec<u8> {
+    let prf = HmacSha256::new(password);
+    let mut out = Vec::with_capacity(len + 32);
+    let mut msg = salt.to_vec();
+    msg.extend_from_slice(&[0; 4]);
+    let mut i: u32 = 1;
+    while out.len() < len {
+        let at = msg.len() - 4;
+        msg[at..].copy_from_slice(&i.to_be_bytes());
+        let mut block = prf.mac(&msg);
+        out.extend_from_slice(&block);
+        block.zeroize();
+        i += 1;
+    }
+    out[len..].zeroize();
+    out.truncate(len);
+    out
+}
+
+pub fn check_params(params: &ScryptParams, salt_len: usize, dk_len: usize) -> Result<(), CryptoError> {
+    require_approved("scrypt")?;
+    check_dk_len(dk_len)?;
+    check_salt_len(salt_len)?;
+    if params.r == 0 || params.p == 0 || params.r as u64 * params.p as u64 >= 1 << 30 {
+        return Err(CryptoError::OutOfRange("scrypt r and p must be positive with r * p < 2^30".to_string()));
+    }
+    // N < 2^(16 r); r >= 4 dopuszcza kazde log2 N < 63
+    if params.log_n == 0 || params.log_n >= 63 || params.log_n as u32 >= 16 * params.r.min(4) {
+        return Err(CryptoError::OutOfRange("scrypt N must be a power of two with 1 <= log2 N < 16 * r".to_string()));
+    }
+    // V zajmuje 128 * r * N bajtow; N * p to liczba wywolan BlockMix na blok
+    let memory_kib = (128u128 * params.r as u128) << params.log_n >> 10;
+    check_memory_kib(u64::try_from(memory_kib).unwrap_or(u64::MAX))?;
+    check_iterations(u32::try_from((params.p as u64) << params.log_n).unwrap_or(u32::MAX))
+}
+
+pub fn scrypt_bytes(password: &[u8], salt: &[u8], params: &ScryptParams, dk_len: usize) -> Result<Vec<u8>, CryptoError> {
+    check_params(params, salt.len(), dk_len)?;
+    let r = params.r as usize;
+    let mut b = Zeroizing::new(pbkdf2_once(password, salt, params.p as usize * 128 * r));
+    for block in b.chunks_exact_mut(128 * r) {
+        ro_mix(block, 1 << params.log_n, r);
+    }
+    Ok(pbkdf2_once(password, &b, dk_len))
+}
+
+// ---- PHC ----
+
+pub fn parse_scrypt_phc(text: &str) -> Result<(ScryptParams, Vec<u8>, Vec<u8>), CryptoError> {
+    let phc = PhcString::parse(text)?;
+    if phc.algorithm != "scrypt" {
+        return Err(CryptoError::UnsupportedAlgorithm(phc.algorithm));
+    }
+    if phc.version.is_some() {
+        return Err(CryptoError::UnsupportedVersion("scrypt PHC strings have no version".to_string()));
+    }
+    phc.require_known_params(&["ln", "r", "p"])?;
+    let required = |name: &str| {
+        phc.decimal_param(name)?
+            .ok_or_else(|| CryptoError::InvalidInput(format!("scrypt PHC string is missing parameter {name}")))
+    };
+    let log_n = u8::try_from(required("ln")?).map_err(|_| CryptoError::OutOfRange("scrypt ln too large".to_string()))?;
+    let params = ScryptParams { log_n, r: required("r")?, p: required("p")? };
+    let salt = phc.salt.clone().ok_or_else(|| CryptoError::InvalidInput("scrypt PHC string has no salt".to_string()))?;
+    let hash = phc.hash.clone().ok_or_else(|| CryptoError::InvalidInput("scrypt PHC string has no hash".to_string()))?;
+    Ok((params, salt, hash))
+}
+
+pub fn encode_scrypt_phc(params: &ScryptParams, salt: &[u8], hash: &[u8]) -> String {
+    PhcString {
+        algorithm: "scrypt".to_string(),
+        version: None,
+        params: vec![
+            ("ln".to_string(), params.log_n.to_string()),
+            ("r".to_string(), params.r.to_string()),
+            ("p".to_string(), params.p.to_string()),
+        ],
+        salt: Some(salt.to_vec()),
+        hash: Some(hash.to_vec()),
+    }
+    .encode()
+}
+
+// ---- MCF $7$ ----
+
+fn itoa64_value(c: u8) -> Result<u32, CryptoError> {
+    ITOA64
+        .iter()
+        .position(|&x| x == c)
+        .map(|v| v as u32)
+        .ok_or_else(|| CryptoError::InvalidInput(format!("invalid $7$ character '{}'", c as char)))
+}
+
+fn encode_u30(value: u32) -> String {
+    (0..5).map(|i| ITOA64[(value >> (6 * i)) as usize & 63] as char).collect()
+}
+
+fn decode_u30(text: &[u8]) -> Result<u32, CryptoError> {
+    text.iter()
+        .enumerate()
+        .try_fold(0u32, |acc, (i, &c)| Ok(acc | itoa64_value(c)? << (6 * i)))
+}
+
+// Grupy po 3 bajty jako liczba LE, po 6 bitow od najmlodszych.
+fn encode_le64(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (8 * i));
+        for i in 0..(chunk.len() * 8).div_ceil(6) {
+            out.push(ITOA64[(value >> (6 * i)) as usize & 63] as char);
+        }
+    }
+    out
+}
+
+fn decode_le64(text: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(CryptoError::InvalidInput("invalid $7$ hash length".to_string()));
+        }
+        let value = decode_u30(chunk)?;
+        let bytes = chunk.len() * 6 / 8;
+        out.extend_from_slice(&value.to_le_bytes()[..bytes]);
+        if value >> (8 * bytes) != 0 {
+            return Err(CryptoError::InvalidInput("invalid $7$ hash padding bits".to_string()));
+        }
+    }
+    Ok(out)
+}
+
+pub struct ScryptMcf {
+    pub params: ScryptParams,
+    pub salt: String,
+    pub hash: Vec<u8>,
+}
+
+pub fn parse_scrypt_mcf(text: &str) -> Result<ScryptMcf, CryptoError> {
+    let rest = text
+        .strip_prefix("$7$")
+        .ok_or_else(|| CryptoError::InvalidInput("not a $7$ scrypt hash".to_string()))?;
+    let (setting, hash) = rest
+        .rsplit_once('$')
+        .ok_or_else(|| CryptoError::InvalidInput("$7$ hash has no '$' before the hash".to_string()))?;
+    let bytes = setting.as_bytes();
+    if bytes.len() < 11 || !setting.is_ascii() || hash.len() != MCF_HASH_CHARS {
+        return Err(CryptoError::InvalidInput("malformed $7$ scrypt hash".to_string()));
+    }
+    let params = ScryptParams { log_n: itoa64_value(bytes[0])? as u8, r: decode_u30(&bytes[1..6])?, p: decode_u30(&bytes[6..11])? };
+    Ok(ScryptMcf { params, salt: setting[11..].to_string(), hash: decode_le64(hash.as_bytes())? })
+}
+
+pub fn encode_scrypt_mcf(params: &ScryptParams, salt: &str, hash: &[u8]) -> String {
+    format!(
+        "$7${}{}{}{salt}${}",
+        ITOA64[params.log_n as usize & 63] as char,
+        encode_u30(params.r),
+        encode_u30(params.p),
+        encode_le64(hash)
+    )
+}
+
+// Rozpoznaje oba zapisy po prefiksie.
+pub fn scrypt_verify_bytes(password: &[u8], encoded: &str) -> Result<bool, CryptoError> {
+    let (params, salt, expected) = if encoded.starts_with("$7$") {
+        let mcf = parse_scrypt_mcf(encoded)?;
+        (mcf.params, mcf.salt.into_bytes(), mcf.hash)
+    } else {
+        parse_scrypt_phc(encoded)?
+    };
+    let computed = Zeroizing::new(scrypt_bytes(password, &salt, &params, expected.len())?);
+    Ok(ct_eq(&computed, &expected))
+}
+
+fn params_or_default(log_n: u8, r: u32, p: u32) -> ScryptParams {
+    ScryptParams {
+        log_n: if log_n == 0 { DEFAULT_LOG_N } else { log_n },
+        r: if r == 0 { DEFAULT_R } else { r },
+        p: if p == 0 { DEFAULT_P } else { p },
+    }
+}
+
+// Koszt jako N (potega dwojki >= 2), nie log2 N - log2 N tylko w helperach PHC/MCF (ln=).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn scrypt(password: &[u8], salt: &[u8], n: u32, r: u32, p: u32, dk_len: usize) -> Result<Vec<u8>, CryptoError> {
+    scrypt_bytes(password, salt, &ScryptParams::from_n(n as u64, r, p)?, dk_len)
+}
+
+// Nowy hash $scrypt$ (PHC) z losowa sola; parametry 0 = domyslne.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn scrypt_hash_phc(password: &str, log_n: u8, r: u32, p: u32) -> Result<String, CryptoError> {
+    scrypt_hash_phc_raw(password.as_bytes(), log_n, r, p)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn scrypt_hash_phc_raw(password: &[u8], log_n: u8, r: u32, p: u32) -> Result<String, CryptoError> {
+    let params = params_or_default(log_n, r, p);
+    let salt = random_array::<DEFAULT_SALT_LEN>()?;
+    let hash = Zeroizing::new(scrypt_bytes(password, &salt, &params, DEFAULT_HASH_LEN)?);
+    Ok(encode_scrypt_phc(&params, &salt, &hash))
+}
+
+// Nowy hash $7$ (zgodny z crypt(3) w libxcrypt); sol to 22 znaki z alfabetu crypt.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn scrypt_hash_mcf(password: &str, log_n: u8, r: u32, p: u32) -> Result<String, CryptoError> {
+    scrypt_hash_mcf_raw(password.as_bytes(), log_n, r, p)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn scrypt_hash_mcf_raw(password: &[u8], log_n: u8, r: u32, p: u32) -> Result<String, CryptoError> {
+    let params = params_or_default(log_n, r, p);
+    let salt = encode_le64(&random_array::<DEFAULT_SALT_LEN>()?);
+    let hash = Zeroizing::new(scrypt_bytes(password, salt.as_bytes(), &params, DEFAULT_HASH_LEN)?);
+    Ok(encode_scrypt_mcf(&params, &salt, &hash))
+}
+
+// Przyjmuje $scrypt$... i $7$...
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn scrypt_verify(password: &str, hash: &str) -> Result<bool, CryptoError> {
+    scrypt_verify_bytes(password.as_bytes(), hash)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn scrypt_verify_raw(password: &[u8], hash: &str) -> Result<bool, CryptoError> {
+    scrypt_verify_bytes(password, hash)
+}
+
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    // RFC 7914, sekcja 12, wektor 1 (N = 16, r = 1, p = 1).
+    const RFC7914_EMPTY: &str = "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442\
+                                 fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906";
+
+    #[test]
+    fn export_takes_n() -> Result<(), CryptoError> {
+        assert_eq!(bytes_to_hex(&scrypt(b"", b"", 16, 1, 1, 64)?), RFC7914_EMPTY);
+        let params = ScryptParams { log_n: 4, r: 1, p: 1 };
+        assert_eq!(bytes_to_hex(&scrypt_bytes(b"", b"", &params, 64)?), RFC7914_EMPTY);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_cost_is_rejected() {
+        // N, nie log2 N: potegi dwojki > 1, bez obcinania do u8
+        for n in [0, 1, 3, 1000, 16385] {
+            assert!(matches!(scrypt(b"pw", b"salt", n, 1, 1, 32), Err(CryptoError::OutOfRange(_))));
+        }
+        assert!(matches!(scrypt(b"pw", b"salt", 1 << 16, 1, 1, 32), Err(CryptoError::OutOfRange(_))));
+        assert!(matches!(scrypt(b"pw", b"salt", 16, 0, 1, 32), Err(CryptoError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn phc_and_mcf_hashes_verify() -> Result<(), CryptoError> {
+        let phc = scrypt_hash_phc("hunter2", 4, 1, 1)?;
+        assert!(phc.starts_with("$scrypt$ln=4,r=1,p=1$"));
+        assert!(scrypt_verify("hunter2", &phc)?);
+        assert!(!scrypt_verify("hunter3", &phc)?);
+        let mcf = scrypt_hash_mcf_raw(b"hunter2", 4, 1, 1)?;
+        assert!(mcf.starts_with("$7$2"));
+        assert!(scrypt_verify_raw(b"hunter2", &mcf)?);
+        assert!(!scrypt_verify_raw(b"hunter3", &mcf)?);
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_hashes_are_rejected() {
+        assert!(parse_scrypt_phc("$scrypt$r=1,p=1$c2FsdA$aGFzaA").is_err());
+        assert!(parse_scrypt_phc("$argon2id$v=19$m=64,t=1,p=1$c2FsdA$aGFzaA").is_err());
+        assert!(parse_scrypt_mcf("$7$").is_err());
+        assert!(scrypt_verify("pw", "$7$C6..../....").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::blake2b::blake2b_bytes;
+use crate::chacha20::{NONCE_LEN, TAG_LEN, XNONCE_LEN, chacha20_xor, hchacha20, poly1305_bytes};
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::x25519;
+
+// Anonimowe "sealed boxes" do udostepnienia pojedynczego wpisu komus, czyj klucz publiczny
+// X25519 znamy - bez sesji i bez ujawniania nadawcy. Zgodne z libsodium
+// crypto_box_curve25519xchacha20poly1305_seal:
+//   klucz  = HChaCha20(X25519(efemeryczny, odbiorca), 0^16)
+//   nonce  = BLAKE2b-192(klucz efemeryczny || klucz odbiorcy)
+//   wynik  = klucz efemeryczny (32 B) || tag (16 B) || ciphertext
+// Szyfrowanie jak crypto_secretbox_xchacha20poly1305: pierwsze 32 B strumienia to klucz
+// Poly1305 (MAC tylko nad ciphertextem), wiadomosc od bajtu 32 strumienia.
+const PUBLIC_KEY_LEN: usize = 32;
+pub const SEAL_OVERHEAD: usize = PUBLIC_KEY_LEN + TAG_LEN;
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct BoxKeyPair {
+    secret: [u8; 32],
+    public: [u8; 32],
+}
+
+impl Drop for BoxKeyPair {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl BoxKeyPair {
+    pub fn from_secret_bytes(secret: [u8; 32]) -> BoxKeyPair {
+        BoxKeyPair { public: x25519::public_key_bytes(&secret), secret }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl BoxKeyPair {
+    pub fn generate() -> Result<BoxKeyPair, CryptoError> {
+        Ok(BoxKeyPair::from_secret_bytes(x25519::generate_secret()?))
+    }
+
+    // Sekret zapisany w vaulcie (32 B).
+    pub fn from_secret(secret_key: &[u8]) -> Result<BoxKeyPair, CryptoError> {
+        Ok(BoxKeyPair::from_secret_bytes(x25519::to_key(secret_key, "x25519 secret key")?))
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public.to_vec()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn secret_key(&self) -> Vec<u8> {
+        self.secret.to_vec()
+    }
+}
+
+fn box_key(secret: &[u8; 32], their_public: &[u8; 32]) -> Result<Zeroizing<[u8; 32]>, CryptoError> {
+    let shared = Zeroizing::new(x25519::shared_secret(secret, their_public)?);
+    Ok(Zeroizing::new(hchacha20(&shared, &[0u8; 16])))
+}
+
+fn box_nonce(ephemeral: &[u8; 32], recipient: &[u8; 32]) -> Result<[u8; XNONCE_LEN], CryptoError> {
+    let digest = blake2b_bytes(&[ephemeral.as_slice(), recipient].concat(), XNONCE_LEN)?;
+    let mut nonce = [0u8; XNONCE_LEN];
+    nonce.copy_from_slice(&digest);
+    Ok(nonce)
+}
+
+// XOR strumieniem XChaCha20 z 32 B zer na poczatku: pierwsze 32 B wyniku to klucz Poly1305.
+fn secretbox_xor(key: &[u8; 32], nonce: &[u8; XNONCE_LEN], data: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let mut subnonce = [0u8; 16];
+    subnonce.copy_from_slice(&nonce[..16]);
+    let subkey = Zeroizing::new(hchacha20(key, &subnonce));
+    let mut stream_nonce = [0u8; NONCE_LEN];
+    stream_nonce[4..].copy_from_slice(&nonce[16..]);
+    let mut buf = Zeroizing::new(vec![0u8; 32 + data.len()]);
+    buf[32..].copy_from_slice(data);
+    chacha20_xor(&subkey, 0, &stream_nonce, &mut buf)?;
+    Ok(buf)
+}
+
+fn poly_key(buf: &[u8]) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&buf[..32]);
+    key
+}
+
+pub fn seal_to_bytes(recipient_public_key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let ephemeral = BoxKeyPair::generate()?;
+    let key = box_key(&ephemeral.secret, recipient_public_key)?;
+    let nonce = box_nonce(&ephemeral.public, recipient_public_key)?;
+    let buf = secretbox_xor(&key, &nonce, plaintext)?;
+    let tag = poly1305_bytes(&poly_key(&buf), &buf[32..]);
+    let mut out = Vec::with_capacity(SEAL_OVERHEAD + plaintext.len());
+    out.extend_from_slice(&ephemeral.public);
+    out.extend_from_slice(&tag);
+    out.extend_from_slice(&buf[32..]);
+    Ok(out)
+}
+
+pub fn unseal_bytes(keypair: &BoxKeyPair, sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    if sealed.len() < SEAL_OVERHEAD {
+        return Err(CryptoError::InvalidLength(format!("sealed box shorter than {SEAL_OVERHEAD} bytes")));
+    }
+    let (ephemeral, rest) = sealed.split_at(PUBLIC_KEY_LEN);
+    let (tag, ciphertext) = rest.split_at(TAG_LEN);
+    let ephemeral = x25519::to_key(ephemeral, "sealed box ephemeral key")?;
+    let key = box_key(&keypair.secret, &ephemeral)?;
+    let nonce = box_nonce(&ephemeral, &keypair.public)?;
+    // Klucz Poly1305 z pierwszego bloku strumienia; weryfikacja przed odszyfrowaniem
+    let stream = secretbox_xor(&key, &nonce, &[])?;
+    if !ct_eq(&poly1305_bytes(&poly_key(&stream), ciphertext), tag) {
+        return Err(CryptoError::DecryptFailed("sealed box authentication failed".to_string()));
+    }
+    let mut buf = secretbox_xor(&key, &nonce, ciphertext)?;
+    Ok(Zeroizing::new(buf.split_off(32)))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn seal_to(recipient_public_key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    seal_to_bytes(&x25519::to_key(recipient_public_key, "recipient public key")?, plaintext)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn unseal(keypair: &BoxKeyPair, sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut plaintext = unseal_bytes(keypair, sealed)?;
+    Ok(std::mem::take(&mut *plaintext))
+}
+
+// X25519 i XChaCha20 poza profilem FIPS
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+    use crate::encoding::hex_to_bytes;
+
+    // crypto_box_curve25519xchacha20poly1305_seal z libsodium, sekret odbiorcy [0x42; 32]
+    const LIBSODIUM_PUBLIC: &str = "132c442be010fbd57e72603328aa76e71fccc1503aae219327d14d9c9993f472";
+    const LIBSODIUM_SEALED: &str = "dfc77cf89da2b66c9a4f496f30c3bc6677eb0c8c90f1756aadb86f9676897d31\
+        79a38b35924084515d33d80ed45ff92032abdb3dabda5f15996594f9217271867f7922e8af";
+
+    #[test]
+    fn opens_libsodium_sealed_box() -> Result<(), CryptoError> {
+        let keypair = BoxKeyPair::from_secret_bytes([0x42; 32]);
+        assert_eq!(keypair.public_key(), hex_to_bytes(LIBSODIUM_PUBLIC)?);
+        assert_eq!(*unseal_bytes(&keypair, &hex_to_bytes(LIBSODIUM_SEALED)?)?, b"sealed with libsodium");
+        Ok(())
+    }
+
+    #[test]
+    fn seal_round_trip() -> Result<(), CryptoError> {
+        let keypair = BoxKeyPair::generate()?;
+        for plaintext in [&b""[..], b"x", &[7u8; 1000]] {
+            let sealed = seal_to(&keypair.public_key(), plaintext)?;
+            assert_eq!(sealed.len(), SEAL_OVERHEAD + plaintext.len());
+            assert_eq!(unseal(&keypair, &sealed)?, plaintext);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_wrong_keypair_and_truncated_box() -> Result<(), CryptoError> {
+        let keypair = BoxKeyPair::from_secret_bytes([0x42; 32]);
+        let sealed = hex_to_bytes(LIBSODIUM_SEALED)?;
+        let other = BoxKeyPair::generate()?;
+        assert!(matches!(unseal_bytes(&other, &sealed), Err(CryptoError::DecryptFailed(_))));
+        assert!(matches!(unseal_bytes(&keypair, &sealed[..sealed.len() - 1]), Err(CryptoError::DecryptFailed(_))));
+        assert!(matches!(unseal_bytes(&keypair, &sealed[..SEAL_OVERHEAD - 1]), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use zeroize::Zeroize;
+
+// Typy na material klucza. Debug/Display nigdy nie wypisuja bajtow - tylko nazwe typu
+// i dlugosc - wiec przypadkowe logowanie bledu nie wycieknie klucza. Czyszczone przy drop.
+// Celowo bez Clone (kazda kopia to kolejny bufor do wyczyszczenia - kopiuje sie jawnie przez
+// from_slice) i bez PartialEq (porownanie kluczy tylko w stalym czasie: ct::ct_eq na as_bytes).
+// Typy obejmuja API wewnetrzne; eksporty wasm przyjmuja &[u8] z JS i od razu je opakowuja.
+macro_rules! secret_type {
+    ($name:ident) => {
+        pub struct $name(Vec<u8>);
+
+        impl $name {
+            pub fn from_vec(bytes: Vec<u8>) -> Self {
+                $name(bytes)
+            }
+
+            pub fn from_slice(bytes: &[u8]) -> Self {
+                $name(bytes.to_vec())
+            }
+
+            pub fn as_bytes(&self) -> &[u8] {
+                &self.0
+            }
+
+            pub fn len(&self) -> usize {
+                self.0.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.0.is_empty()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}([REDACTED; {} bytes])", stringify!($name), self.0.len())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(self, f)
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                self.0.zeroize();
+            }
+        }
+    };
+}
+
+// Klucz glowny vaulta (wynik KDF z hasla glownego).
+secret_type!(MasterKey);
+// Podklucz wyprowadzony z klucza glownego (wpis, przestrzen nazw, indeks).
+secret_type!(EntryKey);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_never_prints_key_bytes() {
+        let key = MasterKey::from_slice(b"super secret key bytes");
+        assert_eq!(format!("{key:?}"), "MasterKey([REDACTED; 22 bytes])");
+        assert_eq!(format!("{}", EntryKey::from_vec(vec![0x41; 32])), "EntryKey([REDACTED; 32 bytes])");
+        assert!(!format!("{key:?} {key}").contains("secret"));
+    }
+
+    #[test]
+    fn accessors_expose_the_wrapped_bytes() {
+        let key = EntryKey::from_vec(vec![1, 2, 3]);
+        assert_eq!(key.as_bytes(), [1, 2, 3]);
+        assert_eq!(key.len(), 3);
+        assert!(!key.is_empty());
+        assert!(EntryKey::from_slice(&[]).is_empty());
+    }
+}
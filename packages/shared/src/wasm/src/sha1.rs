@@ -0,0 +1,194 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+#[cfg(feature = "js")]
+use crate::encoding::{OutputEncoding, encode_output};
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+
+// SHA-1 (FIPS 180-4) wylacznie dla cudzych formatow: HOTP/TOTP (wiekszosc serwisow wydaje
+// sekrety OTP z HMAC-SHA1), zapytania zakresowe HIBP i sumy kontrolne importow (MultiHasher,
+// stad wariant w HashAlgorithm). Nie do nowych formatow ani podpisow; poza profilem FIPS.
+const H1: [u32; 5] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0];
+
+pub fn sha1_bytes(data: &[u8]) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+#[derive(Clone)]
+pub struct Sha1 {
+    h: [u32; 5],
+    buf: [u8; 64],
+    buf_len: usize,
+    total_len: u64,
+}
+
+impl Drop for Sha1 {
+    fn drop(&mut self) {
+        self.h.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha1 {
+    pub fn new() -> Self {
+        Sha1 { h: H1, buf: [0u8; 64], buf_len: 0, total_len: 0 }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+
+        if self.buf_len > 0 {
+            let take = (64 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len < 64 {
+                return;
+            }
+            let block = self.buf;
+            compress(&mut self.h, &block);
+            self.buf_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress(&mut self.h, block);
+        }
+        let rest = blocks.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; 20] {
+        let bit_len = self.total_len.wrapping_mul(8);
+        let pad_len = if self.buf_len < 56 { 56 - self.buf_len } else { 120 - self.buf_len };
+        let mut padding = [0u8; 64];
+        padding[0] = 0x80;
+        self.update(&padding[..pad_len]);
+        self.update(&bit_len.to_be_bytes());
+
+        let mut out = [0u8; 20];
+        for (i, &val) in self.h.iter().enumerate() {
+            out[i * 4..i * 4 + 4].copy_from_slice(&val.to_be_bytes());
+        }
+        out
+    }
+}
+
+fn compress(h: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+    for (i, chunk) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *h;
+    for (i, &wi) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..20 => ((b & c) | (!b & d), 0x5a82_7999),
+            20..40 => (b ^ c ^ d, 0x6ed9_eba1),
+            40..60 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+            _ => (b ^ c ^ d, 0xca62_c1d6),
+        };
+        let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(wi);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+    w.zeroize();
+
+    h[0] = h[0].wrapping_add(a);
+    h[1] = h[1].wrapping_add(b);
+    h[2] = h[2].wrapping_add(c);
+    h[3] = h[3].wrapping_add(d);
+    h[4] = h[4].wrapping_add(e);
+}
+
+// Eksporty z "interop" w nazwie - tylko dla cudzych formatow (importy, HIBP, OTP).
+// W profilu FIPS NotApproved.
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn sha1_interop(input: &str, encoding: Option<OutputEncoding>) -> Result<JsValue, CryptoError> {
+    require_approved("sha1")?;
+    Ok(encode_output(&sha1_bytes(input.as_bytes()), encoding))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn sha1_interop_raw(input: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    require_approved("sha1")?;
+    Ok(sha1_bytes(input).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digest::{DigestState, HashAlgorithm};
+    use crate::encoding::bytes_to_hex;
+
+    #[test]
+    fn fips_180_vectors() {
+        assert_eq!(bytes_to_hex(&sha1_bytes(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(bytes_to_hex(&sha1_bytes(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        let long = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        assert_eq!(bytes_to_hex(&sha1_bytes(long)), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+    }
+
+    #[test]
+    fn incremental_updates_match_one_shot() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let mut hasher = Sha1::new();
+        for chunk in data.chunks(37) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), sha1_bytes(&data));
+        assert_ne!(sha1_bytes(&data[1..]), sha1_bytes(&data));
+    }
+
+    #[test]
+    fn registered_in_hash_algorithm() {
+        let mut state = DigestState::new(HashAlgorithm::Sha1);
+        state.update(b"abc");
+        assert_eq!(state.finalize(), sha1_bytes(b"abc"));
+        assert_eq!(HashAlgorithm::Sha1.output_len(), 20);
+        assert_eq!(HashAlgorithm::Sha1.name(), "sha1");
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn multi_hasher_accepts_sha1() -> Result<(), CryptoError> {
+        let mut hasher = crate::multihash::MultiHasher::new(vec!["sha-256".into(), "SHA-1".into()])?;
+        hasher.update(b"ab");
+        hasher.update(b"c");
+        let digests = hasher.finalize_bytes();
+        assert_eq!(digests[1], (HashAlgorithm::Sha1, sha1_bytes(b"abc").to_vec()));
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn interop_export_outside_fips_profile() -> Result<(), CryptoError> {
+        assert_eq!(sha1_interop_raw(b"abc")?, sha1_bytes(b"abc"));
+        Ok(())
+    }
+
+    #[cfg(feature = "fips-profile")]
+    #[test]
+    fn sha1_is_not_approved_in_fips_profile() {
+        assert!(matches!(HashAlgorithm::from_name("sha1"), Err(CryptoError::NotApproved(_))));
+        assert!(matches!(sha1_interop_raw(b"abc"), Err(CryptoError::NotApproved(_))));
+    }
+}
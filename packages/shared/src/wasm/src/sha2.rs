@@ -0,0 +1,562 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+const K256: [u32; 64] = [ 
+    0x428a2f98,0x71374491,0xb5c0fbcf,0xe9b5dba5
+   ,0x3956c25b,0x59f111f1,0x923f82a4,0xab1c5ed5
+   ,0xd807aa98,0x12835b01,0x243185be,0x550c7dc3
+   ,0x72be5d74,0x80deb1fe,0x9bdc06a7,0xc19bf174
+   ,0xe49b69c1,0xefbe4786,0x0fc19dc6,0x240ca1cc
+   ,0x2de92c6f,0x4a7484aa,0x5cb0a9dc,0x76f988da
+   ,0x983e5152,0xa831c66d,0xb00327c8,0xbf597fc7
+   ,0xc6e00bf3,0xd5a79147,0x06ca6351,0x14292967
+   ,0x27b70a85,0x2e1b2138,0x4d2c6dfc,0x53380d13
+   ,0x650a7354,0x766a0abb,0x81c2c92e,0x92722c85
+   ,0xa2bfe8a1,0xa81a664b,0xc24b8b70,0xc76c51a3
+   ,0xd192e819,0xd6990624,0xf40e3585,0x106aa070
+   ,0x19a4c116,0x1e376c08,0x2748774c,0x34b0bcb5
+   ,0x391c0cb3,0x4ed8aa4a,0x5b9cca4f,0x682e6ff3
+   ,0x748f82ee,0x78a5636f,0x84c87814,0x8cc70208
+   ,0x90befffa,0xa4506ceb,0xbef9a3f7,0xc67178f2
+];
+
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc, 
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817
+];
+
+const H256: [u32; 8] = [
+    0x6a09e667,
+    0xbb67ae85,
+    0x3c6ef372,
+    0xa54ff53a,
+    0x510e527f,
+    0x9b05688c,
+    0x1f83d9ab,
+    0x5be0cd19
+];
+
+const H512: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179
+];
+
+// SHA-384 i SHA-512/256 (FIPS 180-4): ta sama kompresja co SHA-512, inne IV i obciety wynik.
+const H384: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4
+];
+
+const H512_256: [u64; 8] = [
+    0x22312194fc2bf72c,
+    0x9f555fa3c84c64c2,
+    0x2393b86b6f53b151,
+    0x963877195940eabd,
+    0x96283ee2a88effe3,
+    0xbe5e1e2553863992,
+    0x2b0199fc2c85b8aa,
+    0x0eb72ddc81c52ca2
+];
+
+pub fn sha256_bytes(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn sha512_bytes(data: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+pub fn sha384_bytes(data: &[u8]) -> [u8; 48] {
+    let mut hasher = Sha512::with_iv(H384);
+    hasher.update(data);
+    let mut out = [0u8; 48];
+    out.copy_from_slice(&hasher.finalize()[..48]);
+    out
+}
+
+pub fn sha512_256_bytes(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha512::with_iv(H512_256);
+    hasher.update(data);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hasher.finalize()[..32]);
+    out
+}
+
+// Stan przyrostowy - dane podawane kawalkami, bez kopiowania calej wiadomosci.
+#[derive(Clone)]
+pub struct Sha256 {
+    h: [u32; 8],
+    buf: [u8; 64],
+    buf_len: usize,
+    total_len: u64,
+}
+
+// Stan moze zawierac material klucza (np. ipad/opad w HMAC), wiec jest czyszczony.
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        self.h.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Sha256 { h: H256, buf: [0u8; 64], buf_len: 0, total_len: 0 }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+
+        if self.buf_len > 0 {
+            let take = (64 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len < 64 {
+                return;
+            }
+            let block = self.buf;
+            compress256(&mut self.h, &block);
+            self.buf_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress256(&mut self.h, block);
+        }
+        let rest = blocks.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        //dodajemy padding
+        let bit_len = self.total_len.wrapping_mul(8);
+        let pad_len = if self.buf_len < 56 { 56 - self.buf_len } else { 120 - self.buf_len };
+        let mut padding = [0u8; 64];
+        padding[0] = 0x80;
+        self.update(&padding[..pad_len]);
+        self.update(&bit_len.to_be_bytes());
+
+        let mut out = [0u8; 32];
+        for (i, &val) in self.h.iter().enumerate() {
+            out[i*4..i*4+4].copy_from_slice(&val.to_be_bytes());
+        }
+        out
+    }
+}
+
+#[derive(Clone)]
+pub struct Sha512 {
+    h: [u64; 8],
+    buf: [u8; 128],
+    buf_len: usize,
+    total_len: u128,
+}
+
+impl Drop for Sha512 {
+    fn drop(&mut self) {
+        self.h.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha512 {
+    pub fn new() -> Self {
+        Sha512::with_iv(H512)
+    }
+
+    fn with_iv(h: [u64; 8]) -> Self {
+        Sha512 { h, buf: [0u8; 128], buf_len: 0, total_len: 0 }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u128);
+
+        if self.buf_len > 0 {
+            let take = (128 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len < 128 {
+                return;
+            }
+            let block = self.buf;
+            compress512(&mut self.h, &block);
+            self.buf_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(128);
+        for block in &mut blocks {
+            compress512(&mut self.h, block);
+        }
+        let rest = blocks.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; 64] {
+        //dodajemy padding
+        let bit_len = self.total_len.wrapping_mul(8);
+        let pad_len = if self.buf_len < 112 { 112 - self.buf_len } else { 240 - self.buf_len };
+        let mut padding = [0u8; 128];
+        padding[0] = 0x80;
+        self.update(&padding[..pad_len]);
+        self.update(&bit_len.to_be_bytes());
+
+        let mut out = [0u8; 64];
+        for (i, &val) in self.h.iter().enumerate() {
+            out[i*8..i*8+8].copy_from_slice(&val.to_be_bytes());
+        }
+        out
+    }
+}
+
+fn compress256(h: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+
+    for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)).take(16) {
+        *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+
+    for i in 16..64 {
+        let s0 = w[i-15].rotate_right(7) ^ w[i-15].rotate_right(18) ^ (w[i-15] >> 3);
+        let s1 = w[i-2].rotate_right(17) ^ w[i-2].rotate_right(19) ^ (w[i-2] >> 10);
+
+        w[i] = w[i-16].wrapping_add(s0).wrapping_add(w[i-7]).wrapping_add(s1);
+    }
+
+    let mut a: u32 = h[0];
+    let mut b: u32 = h[1];
+    let mut c: u32 = h[2];
+    let mut d: u32 = h[3];
+    let mut e: u32 = h[4];
+    let mut f: u32 = h[5];
+    let mut g: u32 = h[6];
+    let mut hh: u32 = h[7];
+
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let temp1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K256[i]).wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    h[0] = h[0].wrapping_add(a);
+    h[1] = h[1].wrapping_add(b);
+    h[2] = h[2].wrapping_add(c);
+    h[3] = h[3].wrapping_add(d);
+    h[4] = h[4].wrapping_add(e);
+    h[5] = h[5].wrapping_add(f);
+    h[6] = h[6].wrapping_add(g);
+    h[7] = h[7].wrapping_add(hh);
+}
+
+fn compress512(h: &mut [u64; 8], block: &[u8]) {
+    let mut w = [0u64; 128];
+
+    for (word, bytes) in w.iter_mut().zip(block.chunks_exact(8)).take(16) {
+        *word = u64::from_be_bytes([
+            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+        ]);
+    }
+
+    for i in 16..80 {
+        //let s0 = w[i-15].rotate_right(7) ^ w[i-15].rotate_right(18) ^ (w[i-15] >> 3);
+//            let s1 = w[i-2].rotate_right(17) ^ w[i-2].rotate_right(19) ^ (w[i-2] >> 10);
+
+        let s0 = w[i-15].rotate_right(1) ^ w[i-15].rotate_right(8) ^ (w[i-15] >> 7);
+        let s1 = w[i-2].rotate_right(19) ^ w[i-2].rotate_right(61) ^ (w[i-2] >> 6);
+        w[i] = w[i-16].wrapping_add(s0).wrapping_add(w[i-7]).wrapping_add(s1);
+    }
+
+    let mut a: u64 = h[0];
+    let mut b: u64 = h[1];
+    let mut c: u64 = h[2];
+    let mut d: u64 = h[3];
+    let mut e: u64 = h[4];
+    let mut f: u64 = h[5];
+    let mut g: u64 = h[6];
+    let mut hh: u64 = h[7];
+
+    for i in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        //let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let temp1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K512[i]).wrapping_add(w[i]);
+        //let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    h[0] = h[0].wrapping_add(a);
+    h[1] = h[1].wrapping_add(b);
+    h[2] = h[2].wrapping_add(c);
+    h[3] = h[3].wrapping_add(d);
+    h[4] = h[4].wrapping_add(e);
+    h[5] = h[5].wrapping_add(f);
+    h[6] = h[6].wrapping_add(g);
+    h[7] = h[7].wrapping_add(hh);
+}
+
+// Klasy dla JS: skrot duzego zalacznika kawalkami z ReadableStream, bez skladania calosci
+// w pamieci. finalize() zuzywa obiekt po stronie JS.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Default)]
+pub struct Sha256Hasher {
+    state: Sha256,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl Sha256Hasher {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new() -> Sha256Hasher {
+        Sha256Hasher::default()
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.state.update(chunk);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        self.state.finalize().to_vec()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+#[derive(Default)]
+pub struct Sha512Hasher {
+    state: Sha512,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl Sha512Hasher {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new() -> Sha512Hasher {
+        Sha512Hasher::default()
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.state.update(chunk);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        self.state.finalize().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    const M448: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    const M896: &[u8] = concat!(
+        "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno",
+        "ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"
+    )
+    .as_bytes();
+
+    // FIPS 180-4, przyklady z CSRC (SHA256.pdf, SHA512.pdf, ...) - kolejno "abc", pusty,
+    // 448 bitow i 896 bitow.
+    #[test]
+    fn sha256_examples() {
+        assert_eq!(
+            bytes_to_hex(&sha256_bytes(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            bytes_to_hex(&sha256_bytes(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            bytes_to_hex(&sha256_bytes(M448)),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(
+            bytes_to_hex(&sha256_bytes(M896)),
+            "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1"
+        );
+    }
+
+    #[test]
+    fn sha512_examples() {
+        assert_eq!(
+            bytes_to_hex(&sha512_bytes(b"abc")),
+            concat!(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a",
+                "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(&sha512_bytes(b"")),
+            concat!(
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce",
+                "47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(&sha512_bytes(M448)),
+            concat!(
+                "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c335",
+                "96fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445"
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(&sha512_bytes(M896)),
+            concat!(
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018",
+                "501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+            )
+        );
+    }
+
+    #[test]
+    fn sha384_examples() {
+        assert_eq!(
+            bytes_to_hex(&sha384_bytes(b"abc")),
+            concat!(
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded163",
+                "1a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(&sha384_bytes(b"")),
+            concat!(
+                "38b060a751ac96384cd9327eb1b1e36a21fdb71114be0743",
+                "4c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b"
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(&sha384_bytes(M896)),
+            concat!(
+                "09330c33f71147e83d192fc782cd1b4753111b173b3b05d2",
+                "2fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039"
+            )
+        );
+    }
+
+    #[test]
+    fn sha512_256_examples() {
+        assert_eq!(
+            bytes_to_hex(&sha512_256_bytes(b"abc")),
+            "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"
+        );
+        assert_eq!(
+            bytes_to_hex(&sha512_256_bytes(b"")),
+            "c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a"
+        );
+        assert_eq!(
+            bytes_to_hex(&sha512_256_bytes(M896)),
+            "3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a"
+        );
+    }
+
+    // Milion razy 'a', podawane kawalkami przez stan przyrostowy.
+    #[test]
+    fn million_a() {
+        let chunk = [b'a'; 1000];
+        let mut h256 = Sha256::new();
+        let mut h512 = Sha512::new();
+        for _ in 0..1000 {
+            h256.update(&chunk);
+            h512.update(&chunk);
+        }
+        assert_eq!(
+            bytes_to_hex(&h256.finalize()),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+        assert_eq!(
+            bytes_to_hex(&h512.finalize()),
+            concat!(
+                "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb",
+                "de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+            )
+        );
+    }
+
+    // Podzial na granicach bloku (64 / 128 bajtow) i tuz obok nich.
+    #[test]
+    fn incremental_updates_match_one_shot() {
+        let data: Vec<u8> = (0..300u32).map(|i| i as u8).collect();
+        for split in [0, 1, 55, 56, 63, 64, 65, 111, 112, 127, 128, 129, 300] {
+            let (a, b) = data.split_at(split);
+            let mut h256 = Sha256Hasher::new();
+            h256.update(a);
+            h256.update(b);
+            assert_eq!(h256.finalize(), sha256_bytes(&data));
+            let mut h512 = Sha512Hasher::new();
+            h512.update(a);
+            h512.update(b);
+            assert_eq!(h512.finalize(), sha512_bytes(&data));
+        }
+    }
+}
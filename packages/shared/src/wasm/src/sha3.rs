@@ -0,0 +1,236 @@
+use zeroize::Zeroize;
+
+// SHA-3 (FIPS 202): gabka Keccak-f[1600], padding 0x06 .. 0x80.
+// rate = 200 - 2 * dlugosc skrotu: 136 B dla SHA3-256, 72 B dla SHA3-512.
+const RC: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+const RHO: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+const PI: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+pub fn keccak_f1600(a: &mut [u64; 25]) {
+    for rc in RC {
+        // theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        // rho + pi
+        let mut last = a[1];
+        for i in 0..24 {
+            let j = PI[i];
+            let t = a[j];
+            a[j] = last.rotate_left(RHO[i]);
+            last = t;
+        }
+        // chi
+        for y in 0..5 {
+            let row = [a[5 * y], a[5 * y + 1], a[5 * y + 2], a[5 * y + 3], a[5 * y + 4]];
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+        // iota
+        a[0] ^= rc;
+    }
+}
+
+#[derive(Clone)]
+pub struct Keccak {
+    state: [u64; 25],
+    buf: [u8; 200],
+    buf_len: usize,
+    rate: usize,
+    pad: u8,
+}
+
+impl Drop for Keccak {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+impl Keccak {
+    pub fn new(rate: usize, pad: u8) -> Self {
+        Keccak { state: [0; 25], buf: [0; 200], buf_len: 0, rate, pad }
+    }
+
+    fn absorb_block(&mut self) {
+        for (lane, chunk) in self.state.iter_mut().zip(self.buf[..self.rate].chunks_exact(8)) {
+            let mut w = [0u8; 8];
+            w.copy_from_slice(chunk);
+            *lane ^= u64::from_le_bytes(w);
+        }
+        keccak_f1600(&mut self.state);
+        self.buf_len = 0;
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = (self.rate - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len == self.rate {
+                self.absorb_block();
+            }
+        }
+    }
+
+    // Wyciska dowolna liczbe bajtow (SHA-3 bierze tylko pierwsze out.len() < rate).
+    pub fn finalize_into(mut self, out: &mut [u8]) {
+        self.buf[self.buf_len..self.rate].fill(0);
+        self.buf[self.buf_len] ^= self.pad;
+        self.buf[self.rate - 1] ^= 0x80;
+        self.absorb_block();
+
+        for chunk in out.chunks_mut(self.rate) {
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (self.state[i / 8] >> (8 * (i % 8))) as u8;
+            }
+            if chunk.len() == self.rate {
+                keccak_f1600(&mut self.state);
+            }
+        }
+    }
+}
+
+macro_rules! sha3_type {
+    ($name:ident, $out:expr) => {
+        #[derive(Clone)]
+        pub struct $name(Keccak);
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $name {
+            pub fn new() -> Self {
+                $name(Keccak::new(200 - 2 * $out, 0x06))
+            }
+
+            pub fn update(&mut self, data: &[u8]) {
+                self.0.update(data);
+            }
+
+            pub fn finalize(self) -> [u8; $out] {
+                let mut out = [0u8; $out];
+                self.0.finalize_into(&mut out);
+                out
+            }
+        }
+    };
+}
+
+sha3_type!(Sha3_256, 32);
+sha3_type!(Sha3_512, 64);
+
+pub fn sha3_256_bytes(data: &[u8]) -> [u8; 32] {
+    let mut h = Sha3_256::new();
+    h.update(data);
+    h.finalize()
+}
+
+pub fn sha3_512_bytes(data: &[u8]) -> [u8; 64] {
+    let mut h = Sha3_512::new();
+    h.update(data);
+    h.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    const ABC_448: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+    // Przyklady NIST dla FIPS 202 (SHA3-256 / SHA3-512): wiadomosc pusta, "abc", 448 bitow
+    // i 1600 bitow 0xa3.
+    #[test]
+    fn fips202_examples() {
+        let a3 = [0xa3u8; 200];
+        let cases: [(&[u8], &str, &str); 4] = [
+            (
+                b"",
+                "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+                concat!(
+                    "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6",
+                    "15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26"
+                ),
+            ),
+            (
+                b"abc",
+                "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+                concat!(
+                    "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e",
+                    "10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+                ),
+            ),
+            (
+                ABC_448,
+                "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+                concat!(
+                    "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636d",
+                    "ee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e"
+                ),
+            ),
+            (
+                &a3,
+                "79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787",
+                concat!(
+                    "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca8",
+                    "1b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00"
+                ),
+            ),
+        ];
+        for (message, sha3_256, sha3_512) in cases {
+            assert_eq!(bytes_to_hex(&sha3_256_bytes(message)), sha3_256);
+            assert_eq!(bytes_to_hex(&sha3_512_bytes(message)), sha3_512);
+        }
+    }
+
+    // Dlugosci wokol stawki SHA3-256 (136 B) - padding w tym samym albo w nowym bloku.
+    // Wartosci z hashlib (Python).
+    #[test]
+    fn rate_boundaries() {
+        assert_eq!(
+            bytes_to_hex(&sha3_256_bytes(&[b'a'; 135])),
+            "8094bb53c44cfb1e67b7c30447f9a1c33696d2463ecc1d9c92538913392843c9"
+        );
+        assert_eq!(
+            bytes_to_hex(&sha3_256_bytes(&[b'a'; 136])),
+            "3fc5559f14db8e453a0a3091edbd2bc25e11528d81c66fa570a4efdcc2695ee1"
+        );
+    }
+
+    #[test]
+    fn incremental_updates_match_one_shot() {
+        let data = [0xa3u8; 200];
+        for split in [0, 1, 71, 72, 135, 136, 137, 200] {
+            let (a, b) = data.split_at(split);
+            let mut h256 = Sha3_256::new();
+            h256.update(a);
+            h256.update(b);
+            assert_eq!(h256.finalize(), sha3_256_bytes(&data));
+            let mut h512 = Sha3_512::new();
+            h512.update(a);
+            h512.update(b);
+            assert_eq!(h512.finalize(), sha3_512_bytes(&data));
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+use crate::rng::random_array;
+
+// SipHash-2-4: szybki skrot z kluczem, odporny na kolizje wymuszane przez atakujacego
+// (URL-e, nazwy uzytkownikow w indeksach). Nie zastepuje HMAC tam, gdzie potrzebny jest MAC.
+#[derive(Clone)]
+pub struct SipHasher24 {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+impl SipHasher24 {
+    pub fn new_with_key(key: &[u8; 16]) -> Self {
+        let k0 = u64::from_le_bytes([key[0], key[1], key[2], key[3], key[4], key[5], key[6], key[7]]);
+        let k1 = u64::from_le_bytes([key[8], key[9], key[10], key[11], key[12], key[13], key[14], key[15]]);
+        SipHasher24 {
+            v0: k0 ^ 0x736f6d6570736575,
+            v1: k1 ^ 0x646f72616e646f6d,
+            v2: k0 ^ 0x6c7967656e657261,
+            v3: k1 ^ 0x7465646279746573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    fn process_word(&mut self, m: u64) {
+        self.v3 ^= m;
+        self.round();
+        self.round();
+        self.v0 ^= m;
+    }
+}
+
+impl Drop for SipHasher24 {
+    fn drop(&mut self) {
+        self.v0.zeroize();
+        self.v1.zeroize();
+        self.v2.zeroize();
+        self.v3.zeroize();
+        self.tail.zeroize();
+    }
+}
+
+impl Hasher for SipHasher24 {
+    fn write(&mut self, bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len());
+        for &b in bytes {
+            self.tail |= (b as u64) << (8 * self.ntail);
+            self.ntail += 1;
+            if self.ntail == 8 {
+                let m = self.tail;
+                self.process_word(m);
+                self.tail = 0;
+                self.ntail = 0;
+            }
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut s = self.clone();
+        let b = ((s.length as u64 & 0xff) << 56) | s.tail;
+        s.process_word(b);
+        s.v2 ^= 0xff;
+        s.round();
+        s.round();
+        s.round();
+        s.round();
+        s.v0 ^ s.v1 ^ s.v2 ^ s.v3
+    }
+}
+
+pub fn siphash24_bytes(key: &[u8; 16], data: &[u8]) -> u64 {
+    let mut h = SipHasher24::new_with_key(key);
+    h.write(data);
+    h.finish()
+}
+
+static SESSION_KEY: OnceLock<[u8; 16]> = OnceLock::new();
+
+// Klucz losowany raz na sesje (instancje wasm); nigdy nie opuszcza modulu.
+fn session_key() -> Result<&'static [u8; 16], CryptoError> {
+    if let Some(key) = SESSION_KEY.get() {
+        return Ok(key);
+    }
+    let key = random_array::<16>()?;
+    Ok(SESSION_KEY.get_or_init(|| key))
+}
+
+// BuildHasher dla HashMap/HashSet z kluczami od uzytkownika (np. klastry w dedupe.rs).
+#[derive(Clone)]
+pub struct SessionSipHash {
+    key: [u8; 16],
+}
+
+impl SessionSipHash {
+    pub fn new() -> Result<Self, CryptoError> {
+        Ok(SessionSipHash { key: *session_key()? })
+    }
+}
+
+impl Drop for SessionSipHash {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl BuildHasher for SessionSipHash {
+    type Hasher = SipHasher24;
+
+    fn build_hasher(&self) -> SipHasher24 {
+        SipHasher24::new_with_key(&self.key)
+    }
+}
+
+// Klucz podaje wywolujacy, wiec to juz PRF do uzycia poza modulem - w profilu FIPS NotApproved.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn siphash24(key: &[u8], data: &[u8]) -> Result<u64, CryptoError> {
+    require_approved("siphash24")?;
+    let key: &[u8; 16] = key.try_into().map_err(|_| CryptoError::InvalidLength("siphash key must be 16 bytes".to_string()))?;
+    Ok(siphash24_bytes(key, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // Wektory z referencyjnej implementacji SipHash-2-4: klucz 00..0f, wejscie 00..(n-1).
+    #[test]
+    fn reference_vectors() {
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let input: Vec<u8> = (0..15).collect();
+        assert_eq!(siphash24_bytes(&key, &[]), 0x726fdb47dd0e0e31);
+        assert_eq!(siphash24_bytes(&key, &input), 0xa129ca6149be45e5);
+        let mut split = SipHasher24::new_with_key(&key);
+        split.write(&input[..3]);
+        split.write(&input[3..]);
+        assert_eq!(split.finish(), 0xa129ca6149be45e5);
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn export_checks_key_length() -> Result<(), CryptoError> {
+        assert_eq!(siphash24(&[0u8; 16], b"abc")?, siphash24_bytes(&[0u8; 16], b"abc"));
+        assert!(matches!(siphash24(&[0u8; 15], b"abc"), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn session_hasher_backs_hash_sets() -> Result<(), CryptoError> {
+        let mut set = HashSet::with_hasher(SessionSipHash::new()?);
+        assert!(set.insert("example.com"));
+        assert!(!set.insert("example.com"));
+        assert_eq!(SessionSipHash::new()?.hash_one("x"), SessionSipHash::new()?.hash_one("x"));
+        Ok(())
+    }
+}
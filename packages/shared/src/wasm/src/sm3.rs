@@ -0,0 +1,157 @@
+use zeroize::Zeroize;
+
+// SM3 (GB/T 32905-2016) - 256-bitowy skrot o budowie Merkle-Damgard jak SHA-256.
+const IV: [u32; 8] = [
+    0x7380166f, 0x4914b2b9, 0x172442d7, 0xda8a0600, 0xa96f30bc, 0x163138aa, 0xe38dee4d, 0xb0fb0e4e,
+];
+
+fn p0(x: u32) -> u32 {
+    x ^ x.rotate_left(9) ^ x.rotate_left(17)
+}
+
+fn p1(x: u32) -> u32 {
+    x ^ x.rotate_left(15) ^ x.rotate_left(23)
+}
+
+fn compress(v: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 68];
+    for (i, chunk) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for j in 16..68 {
+        w[j] = p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15)) ^ w[j - 13].rotate_left(7) ^ w[j - 6];
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *v;
+    for j in 0..64 {
+        let t: u32 = if j < 16 { 0x79cc4519 } else { 0x7a879d8a };
+        let ss1 = a
+            .rotate_left(12)
+            .wrapping_add(e)
+            .wrapping_add(t.rotate_left(j as u32 % 32))
+            .rotate_left(7);
+        let ss2 = ss1 ^ a.rotate_left(12);
+        let (ff, gg) = if j < 16 {
+            (a ^ b ^ c, e ^ f ^ g)
+        } else {
+            ((a & b) | (a & c) | (b & c), (e & f) | (!e & g))
+        };
+        let tt1 = ff.wrapping_add(d).wrapping_add(ss2).wrapping_add(w[j] ^ w[j + 4]);
+        let tt2 = gg.wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j]);
+        d = c;
+        c = b.rotate_left(9);
+        b = a;
+        a = tt1;
+        h = g;
+        g = f.rotate_left(19);
+        f = e;
+        e = p0(tt2);
+    }
+    for (x, y) in v.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *x ^= y;
+    }
+    w.zeroize();
+}
+
+#[derive(Clone)]
+pub struct Sm3 {
+    v: [u32; 8],
+    buf: [u8; 64],
+    buf_len: usize,
+    total_len: u64,
+}
+
+impl Drop for Sm3 {
+    fn drop(&mut self) {
+        self.v.zeroize();
+        self.buf.zeroize();
+    }
+}
+
+impl Default for Sm3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sm3 {
+    pub fn new() -> Self {
+        Sm3 { v: IV, buf: [0; 64], buf_len: 0, total_len: 0 }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+        if self.buf_len > 0 {
+            let take = (64 - self.buf_len).min(data.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&data[..take]);
+            self.buf_len += take;
+            data = &data[take..];
+            if self.buf_len < 64 {
+                return;
+            }
+            let block = self.buf;
+            compress(&mut self.v, &block);
+            self.buf_len = 0;
+        }
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress(&mut self.v, block);
+        }
+        let rest = blocks.remainder();
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let bit_len = self.total_len.wrapping_mul(8);
+        let mut pad = vec![0x80u8];
+        pad.resize(if self.buf_len < 56 { 56 - self.buf_len } else { 120 - self.buf_len }, 0);
+        pad.extend_from_slice(&bit_len.to_be_bytes());
+        let total = self.total_len;
+        self.update(&pad);
+        self.total_len = total;
+
+        let mut out = [0u8; 32];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.v.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+}
+
+pub fn sm3_bytes(data: &[u8]) -> [u8; 32] {
+    let mut h = Sm3::new();
+    h.update(data);
+    h.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::bytes_to_hex;
+
+    // GB/T 32905-2016, dodatek A: przyklad 1 ("abc") i przyklad 2 ("abcd" x 16).
+    #[test]
+    fn gbt32905_examples() {
+        assert_eq!(
+            bytes_to_hex(&sm3_bytes(b"abc")),
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+        );
+        assert_eq!(
+            bytes_to_hex(&sm3_bytes(&b"abcd".repeat(16))),
+            "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732"
+        );
+    }
+
+    #[test]
+    fn incremental_updates_match_one_shot() {
+        let data = b"abcd".repeat(40);
+        for split in [0, 1, 55, 56, 63, 64, 65, 160] {
+            let (a, b) = data.split_at(split);
+            let mut h = Sm3::new();
+            h.update(a);
+            h.update(b);
+            assert_eq!(h.finalize(), sm3_bytes(&data));
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use zeroize::Zeroize;
+
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+use crate::gcm::BlockCipher;
+
+// SM4 (GB/T 32907-2016), blok 128 bitow, klucz 128 bitow, 32 rundy.
+// S-box czytany przez maskowane przejscie po calej tablicy - bez indeksowania
+// sekretem, tak jak bitslicowane AES w aes.rs.
+const SBOX: [u8; 256] = [
+    0xd6, 0x90, 0xe9, 0xfe, 0xcc, 0xe1, 0x3d, 0xb7, 0x16, 0xb6, 0x14, 0xc2, 0x28, 0xfb, 0x2c, 0x05,
+    0x2b, 0x67, 0x9a, 0x76, 0x2a, 0xbe, 0x04, 0xc3, 0xaa, 0x44, 0x13, 0x26, 0x49, 0x86, 0x06, 0x99,
+    0x9c, 0x42, 0x50, 0xf4, 0x91, 0xef, 0x98, 0x7a, 0x33, 0x54, 0x0b, 0x43, 0xed, 0xcf, 0xac, 0x62,
+    0xe4, 0xb3, 0x1c, 0xa9, 0xc9, 0x08, 0xe8, 0x95, 0x80, 0xdf, 0x94, 0xfa, 0x75, 0x8f, 0x3f, 0xa6,
+    0x47, 0x07, 0xa7, 0xfc, 0xf3, 0x73, 0x17, 0xba, 0x83, 0x59, 0x3c, 0x19, 0xe6, 0x85, 0x4f, 0xa8,
+    0x68, 0x6b, 0x81, 0xb2, 0x71, 0x64, 0xda, 0x8b, 0xf8, 0xeb, 0x0f, 0x4b, 0x70, 0x56, 0x9d, 0x35,
+    0x1e, 0x24, 0x0e, 0x5e, 0x63, 0x58, 0xd1, 0xa2, 0x25, 0x22, 0x7c, 0x3b, 0x01, 0x21, 0x78, 0x87,
+    0xd4, 0x00, 0x46, 0x57, 0x9f, 0xd3, 0x27, 0x52, 0x4c, 0x36, 0x02, 0xe7, 0xa0, 0xc4, 0xc8, 0x9e,
+    0xea, 0xbf, 0x8a, 0xd2, 0x40, 0xc7, 0x38, 0xb5, 0xa3, 0xf7, 0xf2, 0xce, 0xf9, 0x61, 0x15, 0xa1,
+    0xe0, 0xae, 0x5d, 0xa4, 0x9b, 0x34, 0x1a, 0x55, 0xad, 0x93, 0x32, 0x30, 0xf5, 0x8c, 0xb1, 0xe3,
+    0x1d, 0xf6, 0xe2, 0x2e, 0x82, 0x66, 0xca, 0x60, 0xc0, 0x29, 0x23, 0xab, 0x0d, 0x53, 0x4e, 0x6f,
+    0xd5, 0xdb, 0x37, 0x45, 0xde, 0xfd, 0x8e, 0x2f, 0x03, 0xff, 0x6a, 0x72, 0x6d, 0x6c, 0x5b, 0x51,
+    0x8d, 0x1b, 0xaf, 0x92, 0xbb, 0xdd, 0xbc, 0x7f, 0x11, 0xd9, 0x5c, 0x41, 0x1f, 0x10, 0x5a, 0xd8,
+    0x0a, 0xc1, 0x31, 0x88, 0xa5, 0xcd, 0x7b, 0xbd, 0x2d, 0x74, 0xd0, 0x12, 0xb8, 0xe5, 0xb4, 0xb0,
+    0x89, 0x69, 0x97, 0x4a, 0x0c, 0x96, 0x77, 0x7e, 0x65, 0xb9, 0xf1, 0x09, 0xc5, 0x6e, 0xc6, 0x84,
+    0x18, 0xf0, 0x7d, 0xec, 0x3a, 0xdc, 0x4d, 0x20, 0x79, 0xee, 0x5f, 0x3e, 0xd7, 0xcb, 0x39, 0x48,
+];
+
+const FK: [u32; 4] = [0xa3b1bac6, 0x56aa3350, 0x677d9197, 0xb27022dc];
+
+const CK: [u32; 32] = {
+    let mut ck = [0u32; 32];
+    let mut i = 0;
+    while i < 32 {
+        let mut j = 0;
+        while j < 4 {
+            ck[i] = (ck[i] << 8) | (((4 * i + j) * 7) % 256) as u32;
+            j += 1;
+        }
+        i += 1;
+    }
+    ck
+};
+
+fn sbox_ct(x: u8) -> u8 {
+    let mut out = 0u8;
+    for (i, &s) in SBOX.iter().enumerate() {
+        // mask = 0xff gdy i == x, bez galezi
+        let diff = (i as u8 ^ x) as u32;
+        let mask = (diff.wrapping_sub(1) >> 8) as u8;
+        out |= s & mask;
+    }
+    out
+}
+
+fn tau(a: u32) -> u32 {
+    let b = a.to_be_bytes();
+    u32::from_be_bytes([sbox_ct(b[0]), sbox_ct(b[1]), sbox_ct(b[2]), sbox_ct(b[3])])
+}
+
+fn t_enc(x: u32) -> u32 {
+    let b = tau(x);
+    b ^ b.rotate_left(2) ^ b.rotate_left(10) ^ b.rotate_left(18) ^ b.rotate_left(24)
+}
+
+fn t_key(x: u32) -> u32 {
+    let b = tau(x);
+    b ^ b.rotate_left(13) ^ b.rotate_left(23)
+}
+
+#[derive(Clone)]
+pub struct Sm4 {
+    rk: [u32; 32],
+}
+
+impl Drop for Sm4 {
+    fn drop(&mut self) {
+        self.rk.zeroize();
+    }
+}
+
+impl Sm4 {
+    pub fn new(key: &[u8]) -> Result<Sm4, CryptoError> {
+        require_approved("sm4")?;
+        if key.len() != 16 {
+            return Err(CryptoError::InvalidLength("SM4 key must be 16 bytes".to_string()));
+        }
+        let mut k = [0u32; 4];
+        for (i, w) in key.chunks_exact(4).enumerate() {
+            k[i] = u32::from_be_bytes([w[0], w[1], w[2], w[3]]) ^ FK[i];
+        }
+        let mut rk = [0u32; 32];
+        for (i, rk) in rk.iter_mut().enumerate() {
+            let next = k[0] ^ t_key(k[1] ^ k[2] ^ k[3] ^ CK[i]);
+            *rk = next;
+            k = [k[1], k[2], k[3], next];
+        }
+        k.zeroize();
+        Ok(Sm4 { rk })
+    }
+
+    fn crypt(&self, block: &mut [u8; 16], decrypt: bool) {
+        let mut x = [0u32; 4];
+        for (i, w) in block.chunks_exact(4).enumerate() {
+            x[i] = u32::from_be_bytes([w[0], w[1], w[2], w[3]]);
+        }
+        for i in 0..32 {
+            let rk = if decrypt { self.rk[31 - i] } else { self.rk[i] };
+            let next = x[0] ^ t_enc(x[1] ^ x[2] ^ x[3] ^ rk);
+            x = [x[1], x[2], x[3], next];
+        }
+        for (i, w) in [x[3], x[2], x[1], x[0]].iter().enumerate() {
+            block[4 * i..4 * i + 4].copy_from_slice(&w.to_be_bytes());
+        }
+        x.zeroize();
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        self.crypt(block, false);
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        self.crypt(block, true);
+    }
+}
+
+impl BlockCipher for Sm4 {
+    fn encrypt_block(&self, block: &mut [u8; 16]) {
+        Sm4::encrypt_block(self, block);
+    }
+}
+
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+    use crate::encoding::{bytes_to_hex, hex_to_bytes};
+    use crate::gcm::Sm4Gcm;
+
+    const KEY: [u8; 16] = [
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54, 0x32, 0x10,
+    ];
+
+    // GB/T 32907-2016, dodatek A, przyklad 1.
+    #[test]
+    fn gbt32907_example_1() -> Result<(), CryptoError> {
+        let cipher = Sm4::new(&KEY)?;
+        let mut block = KEY;
+        cipher.encrypt_block(&mut block);
+        assert_eq!(bytes_to_hex(&block), "681edf34d206965e86b3e94f536e4246");
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block, KEY);
+        assert!(matches!(Sm4::new(&KEY[..15]), Err(CryptoError::InvalidLength(_))));
+        Ok(())
+    }
+
+    // Przyklad 2: 10^6 szyfrowan. S-box w stalym czasie przeglada cala tablice, wiec w buildzie
+    // debug to kilka minut - uruchamiac z `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore = "slow without optimizations"]
+    fn gbt32907_example_2() -> Result<(), CryptoError> {
+        let cipher = Sm4::new(&KEY)?;
+        let mut block = KEY;
+        for _ in 0..1_000_000 {
+            cipher.encrypt_block(&mut block);
+        }
+        assert_eq!(bytes_to_hex(&block), "595298c7c6fd271f0402f804c33d3f66");
+        Ok(())
+    }
+
+    // RFC 8998, dodatek A.1 (SM4-GCM).
+    #[test]
+    fn rfc8998_gcm() -> Result<(), CryptoError> {
+        let nonce = hex_to_bytes("00001234567800000000abcd")?;
+        let aad = hex_to_bytes("feedfacedeadbeeffeedfacedeadbeefabaddad2")?;
+        let plaintext = hex_to_bytes(concat!(
+            "aaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbccccccccccccccccdddddddddddddddd",
+            "eeeeeeeeeeeeeeeeffffffffffffffffeeeeeeeeeeeeeeeeaaaaaaaaaaaaaaaa"
+        ))?;
+        let cipher = Sm4Gcm::new(&KEY)?;
+        let sealed = cipher.encrypt(&nonce, &aad, &plaintext)?;
+        assert_eq!(
+            bytes_to_hex(&sealed),
+            concat!(
+                "17f399f08c67d5ee19d0dc9969c4bb7d5fd46fd3756489069157b282bb200735",
+                "d82710ca5c22f0ccfa7cbf93d496ac15a56834cbcf98c397b4024a2691233b8d",
+                "83de3541e4c2b58177e065a9bf7b62ec"
+            )
+        );
+        assert_eq!(cipher.decrypt(&nonce, &aad, &sealed)?, plaintext);
+        assert!(matches!(cipher.decrypt(&nonce, b"", &sealed), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::chacha20::{TAG_LEN, XChaCha20Poly1305, XNONCE_LEN};
+use crate::error::CryptoError;
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::rng::random_array;
+
+// Strumieniowe szyfrowanie duzych zalacznikow (konstrukcja STREAM): dane dzielone na
+// segmenty stalej dlugosci, kazdy szyfrowany XChaCha20-Poly1305 osobno, wiec ani
+// szyfrowanie, ani odszyfrowanie nie trzyma calego pliku w pamieci wasm.
+//   naglowek  = "PMAS" || wersja (1) || dlugosc segmentu (u32 BE) || sol (32 B)
+//   klucz, prefiks nonce = HKDF(klucz zalacznika, sol, "pm-attachment-stream") - 32 B + 19 B
+//   nonce segmentu = prefiks (19 B) || numer segmentu (u32 BE) || 1 dla ostatniego, inaczej 0
+//   segment   = ciphertext || tag, AAD = naglowek
+// Nowa sol = nowy klucz dla kazdego pliku. Numer segmentu wykrywa przestawienia i usuniecia,
+// flaga ostatniego - uciecie pliku na granicy segmentu. Pusty plik to jeden pusty segment.
+const MAGIC: &[u8; 4] = b"PMAS";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 32;
+pub const HEADER_LEN: usize = MAGIC.len() + 1 + 4 + SALT_LEN;
+const STREAM_INFO: &[u8] = b"pm-attachment-stream";
+const NONCE_PREFIX_LEN: usize = XNONCE_LEN - 5;
+pub const DEFAULT_SEGMENT_SIZE: u32 = 64 * 1024;
+const MIN_SEGMENT_SIZE: u32 = 1024;
+const MAX_SEGMENT_SIZE: u32 = 16 * 1024 * 1024;
+
+struct SegmentCipher {
+    cipher: XChaCha20Poly1305,
+    nonce_prefix: [u8; NONCE_PREFIX_LEN],
+    header: Vec<u8>,
+    segment_size: usize,
+    next: u32,
+}
+
+impl SegmentCipher {
+    fn new(key: &[u8], header: Vec<u8>) -> Result<SegmentCipher, CryptoError> {
+        if key.len() != 32 {
+            return Err(CryptoError::InvalidLength("attachment key must be 32 bytes".to_string()));
+        }
+        let segment_size = u32::from_be_bytes([header[5], header[6], header[7], header[8]]);
+        if !(MIN_SEGMENT_SIZE..=MAX_SEGMENT_SIZE).contains(&segment_size) {
+            return Err(CryptoError::OutOfRange(format!(
+                "segment size must be between {MIN_SEGMENT_SIZE} and {MAX_SEGMENT_SIZE} bytes"
+            )));
+        }
+        let okm = Zeroizing::new(hkdf_sha256_bytes(key, &header[9..], STREAM_INFO, 32 + NONCE_PREFIX_LEN)?);
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_LEN];
+        nonce_prefix.copy_from_slice(&okm[32..]);
+        Ok(SegmentCipher {
+            cipher: XChaCha20Poly1305::new(&okm[..32])?,
+            nonce_prefix,
+            header,
+            segment_size: segment_size as usize,
+            next: 0,
+        })
+    }
+
+    fn nonce(&mut self, last: bool) -> Result<[u8; XNONCE_LEN], CryptoError> {
+        let mut nonce = [0u8; XNONCE_LEN];
+        nonce[..NONCE_PREFIX_LEN].copy_from_slice(&self.nonce_prefix);
+        nonce[NONCE_PREFIX_LEN..XNONCE_LEN - 1].copy_from_slice(&self.next.to_be_bytes());
+        nonce[XNONCE_LEN - 1] = last as u8;
+        self.next = self
+            .next
+            .checked_add(1)
+            .ok_or_else(|| CryptoError::OutOfRange("attachment has too many segments".to_string()))?;
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, segment: &[u8], last: bool) -> Result<Vec<u8>, CryptoError> {
+        let nonce = self.nonce(last)?;
+        self.cipher.encrypt(&nonce, &self.header, segment)
+    }
+
+    fn open(&mut self, segment: &[u8], last: bool) -> Result<Vec<u8>, CryptoError> {
+        let index = self.next;
+        let nonce = self.nonce(last)?;
+        self.cipher.decrypt(&nonce, &self.header, segment).map_err(|_| {
+            CryptoError::DecryptFailed(format!("attachment segment {index} failed authentication or is out of order"))
+        })
+    }
+}
+
+fn finished() -> CryptoError {
+    CryptoError::InvalidInput("attachment stream is already finished".to_string())
+}
+
+// push zwraca bajty do dopisania do wyniku (pierwszy wynik zaczyna sie od naglowka),
+// finish - ostatni segment. Pelny segment jest wstrzymywany, dopoki nie wiadomo, czy
+// jest ostatni.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct AttachmentEncryptor {
+    cipher: SegmentCipher,
+    pending: Zeroizing<Vec<u8>>,
+    header_sent: bool,
+    done: bool,
+}
+
+impl AttachmentEncryptor {
+    fn take_header(&mut self, out: &mut Vec<u8>) {
+        if !self.header_sent {
+            out.extend_from_slice(&self.cipher.header);
+            self.header_sent = true;
+        }
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl AttachmentEncryptor {
+    // segment_size 0 = domyslne 64 KiB.
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(key: &[u8], segment_size: u32) -> Result<AttachmentEncryptor, CryptoError> {
+        let segment_size = if segment_size == 0 { DEFAULT_SEGMENT_SIZE } else { segment_size };
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        header.extend_from_slice(&segment_size.to_be_bytes());
+        header.extend_from_slice(&random_array::<SALT_LEN>()?);
+        Ok(AttachmentEncryptor {
+            cipher: SegmentCipher::new(key, header)?,
+            pending: Zeroizing::new(Vec::new()),
+            header_sent: false,
+            done: false,
+        })
+    }
+
+    pub fn push(&mut self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        let size = self.cipher.segment_size;
+        self.pending.extend_from_slice(data);
+        let mut out = Vec::new();
+        self.take_header(&mut out);
+        let mut start = 0;
+        while self.pending.len() - start > size {
+            out.extend_from_slice(&self.cipher.seal(&self.pending[start..start + size], false)?);
+            start += size;
+        }
+        self.pending.drain(..start);
+        Ok(out)
+    }
+
+    pub fn finish(&mut self) -> Result<Vec<u8>, CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        self.done = true;
+        let mut out = Vec::new();
+        self.take_header(&mut out);
+        let pending = std::mem::take(&mut *self.pending);
+        out.extend_from_slice(&self.cipher.seal(&Zeroizing::new(pending), true)?);
+        Ok(out)
+    }
+}
+
+// push przyjmuje dowolne kawalki szyfrogramu (z naglowkiem na poczatku) i zwraca
+// odszyfrowane pelne segmenty; finish sprawdza ostatni segment. Dane zwrocone przez push
+// sa uwierzytelnione, ale o kompletnosci pliku przesadza dopiero udany finish.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct AttachmentDecryptor {
+    key: Zeroizing<Vec<u8>>,
+    cipher: Option<SegmentCipher>,
+    pending: Vec<u8>,
+    done: bool,
+}
+
+impl AttachmentDecryptor {
+    fn read_header(&mut self) -> Result<(), CryptoError> {
+        if self.cipher.is_some() || self.pending.len() < HEADER_LEN {
+            return Ok(());
+        }
+        if !self.pending.starts_with(MAGIC) {
+            return Err(CryptoError::CorruptHeader("not an encrypted attachment stream".to_string()));
+        }
+        if self.pending[4] != VERSION {
+            return Err(CryptoError::UnsupportedVersion(format!("attachment stream version {}", self.pending[4])));
+        }
+        let header: Vec<u8> = self.pending.drain(..HEADER_LEN).collect();
+        self.cipher = Some(SegmentCipher::new(&self.key, header)?);
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl AttachmentDecryptor {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(key: &[u8]) -> Result<AttachmentDecryptor, CryptoError> {
+        if key.len() != 32 {
+            return Err(CryptoError::InvalidLength("attachment key must be 32 bytes".to_string()));
+        }
+        Ok(AttachmentDecryptor { key: Zeroizing::new(key.to_vec()), cipher: None, pending: Vec::new(), done: false })
+    }
+
+    pub fn push(&mut self, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        self.pending.extend_from_slice(data);
+        self.read_header()?;
+        let Some(cipher) = self.cipher.as_mut() else { return Ok(Vec::new()) };
+        let sealed_size = cipher.segment_size + TAG_LEN;
+        let mut out = Vec::new();
+        let mut start = 0;
+        while self.pending.len() - start > sealed_size {
+            let plaintext = Zeroizing::new(cipher.open(&self.pending[start..start + sealed_size], false)?);
+            out.extend_from_slice(&plaintext);
+            start += sealed_size;
+        }
+        self.pending.drain(..start);
+        Ok(out)
+    }
+
+    pub fn finish(&mut self) -> Result<Vec<u8>, CryptoError> {
+        if self.done {
+            return Err(finished());
+        }
+        self.done = true;
+        let cipher = self
+            .cipher
+            .as_mut()
+            .ok_or_else(|| CryptoError::CorruptHeader("attachment stream truncated".to_string()))?;
+        if self.pending.len() < TAG_LEN {
+            return Err(CryptoError::CorruptHeader("attachment stream truncated".to_string()));
+        }
+        cipher.open(&self.pending, true)
+    }
+}
+
+// Caly plik naraz - dla malych zalacznikow i po stronie Rusta.
+pub fn encrypt_attachment_stream_bytes(key: &[u8], data: &[u8], segment_size: u32) -> Result<Vec<u8>, CryptoError> {
+    let mut encryptor = AttachmentEncryptor::new(key, segment_size)?;
+    let mut out = encryptor.push(data)?;
+    out.extend_from_slice(&encryptor.finish()?);
+    Ok(out)
+}
+
+pub fn decrypt_attachment_stream_bytes(key: &[u8], blob: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let mut decryptor = AttachmentDecryptor::new(key)?;
+    let mut out = Zeroizing::new(decryptor.push(blob)?);
+    out.extend_from_slice(&Zeroizing::new(decryptor.finish()?));
+    Ok(out)
+}
+
+// XChaCha20-Poly1305 poza profilem FIPS
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [0x11; 32];
+    const SEGMENT: usize = MIN_SEGMENT_SIZE as usize;
+    const SEALED: usize = SEGMENT + TAG_LEN;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn round_trip_across_segment_boundaries() -> Result<(), CryptoError> {
+        for len in [0, 1, SEGMENT - 1, SEGMENT, SEGMENT + 1, 2 * SEGMENT, 3 * SEGMENT + 7] {
+            let blob = encrypt_attachment_stream_bytes(&KEY, &data(len), MIN_SEGMENT_SIZE)?;
+            let segments = len.div_ceil(SEGMENT).max(1);
+            assert_eq!(blob.len(), HEADER_LEN + segments * TAG_LEN + len, "{len}");
+            assert_eq!(*decrypt_attachment_stream_bytes(&KEY, &blob)?, data(len), "{len}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn segment_nonces_are_sequential() -> Result<(), CryptoError> {
+        let encryptor = AttachmentEncryptor::new(&KEY, 0)?;
+        let mut cipher = encryptor.cipher;
+        let first = cipher.nonce(false)?;
+        let second = cipher.nonce(true)?;
+        assert_eq!(first[..NONCE_PREFIX_LEN], second[..NONCE_PREFIX_LEN]);
+        assert_eq!(first[NONCE_PREFIX_LEN..], [0, 0, 0, 0, 0]);
+        assert_eq!(second[NONCE_PREFIX_LEN..], [0, 0, 0, 1, 1]);
+        cipher.next = u32::MAX;
+        assert!(matches!(cipher.nonce(false), Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_dropped_final_segment() -> Result<(), CryptoError> {
+        for len in [2 * SEGMENT + 100, 2 * SEGMENT] {
+            let blob = encrypt_attachment_stream_bytes(&KEY, &data(len), MIN_SEGMENT_SIZE)?;
+            let last_start = HEADER_LEN + (len.div_ceil(SEGMENT) - 1) * SEALED;
+            let truncated = &blob[..last_start];
+            assert!(matches!(decrypt_attachment_stream_bytes(&KEY, truncated), Err(CryptoError::DecryptFailed(_))));
+        }
+        let blob = encrypt_attachment_stream_bytes(&KEY, b"", 0)?;
+        let header_only = &blob[..HEADER_LEN];
+        assert!(matches!(decrypt_attachment_stream_bytes(&KEY, header_only), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_reordered_segments() -> Result<(), CryptoError> {
+        let blob = encrypt_attachment_stream_bytes(&KEY, &data(3 * SEGMENT), MIN_SEGMENT_SIZE)?;
+        let segment = |i: usize| &blob[HEADER_LEN + i * SEALED..HEADER_LEN + (i + 1) * SEALED];
+        let swapped = [&blob[..HEADER_LEN], segment(1), segment(0), segment(2)].concat();
+        assert!(matches!(decrypt_attachment_stream_bytes(&KEY, &swapped), Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_out_of_range_segment_size() -> Result<(), CryptoError> {
+        for size in [MIN_SEGMENT_SIZE - 1, MAX_SEGMENT_SIZE + 1] {
+            assert!(matches!(AttachmentEncryptor::new(&KEY, size), Err(CryptoError::OutOfRange(_))));
+            let mut blob = encrypt_attachment_stream_bytes(&KEY, b"payload", 0)?;
+            blob[5..9].copy_from_slice(&size.to_be_bytes());
+            assert!(matches!(decrypt_attachment_stream_bytes(&KEY, &blob), Err(CryptoError::OutOfRange(_))));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn push_finish_with_unaligned_chunks() -> Result<(), CryptoError> {
+        let plaintext = data(5 * SEGMENT + 333);
+        let mut encryptor = AttachmentEncryptor::new(&KEY, MIN_SEGMENT_SIZE)?;
+        let mut blob = Vec::new();
+        for chunk in plaintext.chunks(700) {
+            blob.extend_from_slice(&encryptor.push(chunk)?);
+        }
+        blob.extend_from_slice(&encryptor.finish()?);
+        assert!(matches!(encryptor.push(b"late"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(encryptor.finish(), Err(CryptoError::InvalidInput(_))));
+
+        let mut decryptor = AttachmentDecryptor::new(&KEY)?;
+        let mut out = Vec::new();
+        for chunk in blob.chunks(977) {
+            out.extend_from_slice(&decryptor.push(chunk)?);
+        }
+        out.extend_from_slice(&decryptor.finish()?);
+        assert_eq!(out, plaintext);
+        assert!(matches!(decryptor.push(b"late"), Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,599 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+
+// Ocena sily hasla w stylu zxcvbn: znajduje wszystkie dopasowania wzorcow (slowa ze slownika,
+// takze odwrocone i l33t, sekwencje, powtorzenia, daty, sciezki po klawiaturze QWERTY), a potem
+// programowaniem dynamicznym wybiera podzial hasla o najmniejszej liczbie prob. Fragmenty bez
+// wzorca licza sie jako brute force z alfabetem calego hasla. Slowniki (wordlists/) sa krotsze
+// niz w zxcvbn, wiec wynik to gorne oszacowanie - zle hasla wychodza zle, dobre moga byc
+// przeszacowane. Analiza obejmuje pierwsze MAX_ANALYZED znakow, reszta jako brute force.
+const MAX_ANALYZED: usize = 100;
+const MAX_WORD_LEN: usize = 20;
+const MIN_SUBMATCH_GUESSES_SINGLE: f64 = 10.0;
+const MIN_SUBMATCH_GUESSES_MULTI: f64 = 50.0;
+const REFERENCE_YEAR: i32 = 2026;
+const MIN_YEAR_SPACE: f64 = 20.0;
+
+// Listy frekwencyjne, jedno slowo w linii: pozycja na liscie = liczba prob przy ataku
+// slownikowym. Slowo z obu list dostaje nizsza pozycje.
+const PASSWORDS: &str = include_str!("wordlists/passwords.txt");
+const ENGLISH: &str = include_str!("wordlists/english.txt");
+// Gorna granica liczby prob - dlugie hasla dawalyby inf, a JSON zapisuje je jako null.
+const MAX_GUESSES: f64 = 1e100;
+
+static DICTIONARY: OnceLock<HashMap<&'static str, usize>> = OnceLock::new();
+
+fn dictionary() -> &'static HashMap<&'static str, usize> {
+    DICTIONARY.get_or_init(|| {
+        let mut ranks = HashMap::new();
+        for list in [PASSWORDS, ENGLISH] {
+            for (i, word) in list.lines().map(str::trim).filter(|w| !w.is_empty()).enumerate() {
+                let rank = ranks.entry(word).or_insert(i + 1);
+                *rank = (*rank).min(i + 1);
+            }
+        }
+        ranks
+    })
+}
+
+const KEYBOARD_ROWS: [&str; 4] = ["`1234567890-=", "qwertyuiop[]\\", "asdfghjkl;'", "zxcvbnm,./"];
+const KEYBOARD_SHIFTED: [&str; 4] = ["~!@#$%^&*()_+", "QWERTYUIOP{}|", "ASDFGHJKL:\"", "ZXCVBNM<>?"];
+const KEYBOARD_STARTS: f64 = 94.0;
+const KEYBOARD_AVG_DEGREE: f64 = 4.6;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Pattern {
+    Dictionary,
+    Sequence,
+    Repeat,
+    Date,
+    Keyboard,
+    Bruteforce,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PatternMatch {
+    pub pattern: Pattern,
+    pub token: String,
+    pub i: usize,
+    pub j: usize,
+    pub guesses: f64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CrackTime {
+    pub seconds: f64,
+    pub display: String,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct CrackTimes {
+    // 100 prob na godzine (limit po stronie serwera)
+    pub online_throttling: CrackTime,
+    // 10 prob na sekunde
+    pub online_no_throttling: CrackTime,
+    // 10^4 prob na sekunde (PBKDF2/Argon2 z rozsadnymi parametrami)
+    pub offline_slow_hashing: CrackTime,
+    // 10^10 prob na sekunde (szybki skrot, wiele GPU)
+    pub offline_fast_hashing: CrackTime,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StrengthReport {
+    pub guesses: f64,
+    pub guesses_log10: f64,
+    // 0 (bardzo slabe) .. 4 (silne)
+    pub score: u8,
+    pub crack_times: CrackTimes,
+    pub sequence: Vec<PatternMatch>,
+}
+
+fn token(chars: &[char], i: usize, j: usize) -> String {
+    chars[i..=j].iter().collect()
+}
+
+fn push(out: &mut Vec<PatternMatch>, chars: &[char], pattern: Pattern, i: usize, j: usize, guesses: f64) {
+    let min = if i == j { MIN_SUBMATCH_GUESSES_SINGLE } else { MIN_SUBMATCH_GUESSES_MULTI };
+    out.push(PatternMatch { pattern, token: token(chars, i, j), i, j, guesses: guesses.max(min) });
+}
+
+fn binomial(n: u64, k: u64) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (0..k).fold(1.0, |acc, r| acc * (n - r) as f64 / (r + 1) as f64)
+}
+
+// Warianty wielkosci liter: zxcvbn - pierwsza/ostatnia/wszystkie wielkie to tylko x2.
+fn uppercase_variations(word: &[char]) -> f64 {
+    let upper = word.iter().filter(|c| c.is_uppercase()).count() as u64;
+    let lower = word.iter().filter(|c| c.is_lowercase()).count() as u64;
+    if upper == 0 {
+        return 1.0;
+    }
+    let first_only = word.first().is_some_and(|c| c.is_uppercase()) && upper == 1;
+    let last_only = word.last().is_some_and(|c| c.is_uppercase()) && upper == 1;
+    if lower == 0 || first_only || last_only {
+        return 2.0;
+    }
+    (1..=upper.min(lower)).map(|k| binomial(upper + lower, k)).sum()
+}
+
+fn unleet(c: char, alt: bool) -> Option<char> {
+    Some(match c {
+        '4' | '@' => 'a',
+        '3' => 'e',
+        '1' | '!' | '|' if alt => 'l',
+        '1' | '!' | '|' => 'i',
+        '0' => 'o',
+        '$' | '5' => 's',
+        '7' | '+' => 't',
+        _ => return None,
+    })
+}
+
+fn dictionary_matches(chars: &[char], out: &mut Vec<PatternMatch>) {
+    let dict = dictionary();
+    for i in 0..chars.len() {
+        for j in i + 2..chars.len().min(i + MAX_WORD_LEN) {
+            let word = &chars[i..=j];
+            let lower: String = word.iter().flat_map(|c| c.to_lowercase()).collect();
+            let upper_vars = uppercase_variations(word);
+            if let Some(&rank) = dict.get(lower.as_str()) {
+                push(out, chars, Pattern::Dictionary, i, j, rank as f64 * upper_vars);
+            }
+            let reversed: String = lower.chars().rev().collect();
+            if reversed != lower
+                && let Some(&rank) = dict.get(reversed.as_str())
+            {
+                push(out, chars, Pattern::Dictionary, i, j, rank as f64 * upper_vars * 2.0);
+            }
+            let subs = lower.chars().filter(|&c| unleet(c, false).is_some()).count();
+            if subs == 0 {
+                continue;
+            }
+            for alt in [false, true] {
+                let plain: String = lower.chars().map(|c| unleet(c, alt).unwrap_or(c)).collect();
+                if let Some(&rank) = dict.get(plain.as_str()) {
+                    let leet_vars = 2f64.powi(subs as i32);
+                    push(out, chars, Pattern::Dictionary, i, j, rank as f64 * upper_vars * leet_vars);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn char_class(c: char) -> u8 {
+    if c.is_ascii_lowercase() {
+        1
+    } else if c.is_ascii_uppercase() {
+        2
+    } else if c.is_ascii_digit() {
+        3
+    } else {
+        0
+    }
+}
+
+// Ciagi o stalym kroku +-1 lub +-2 w obrebie jednej klasy (abc, 9753, ACEG).
+fn sequence_matches(chars: &[char], out: &mut Vec<PatternMatch>) {
+    let mut i = 0;
+    while i + 2 < chars.len() {
+        let class = char_class(chars[i]);
+        let delta = chars[i + 1] as i32 - chars[i] as i32;
+        if class == 0 || char_class(chars[i + 1]) != class || !matches!(delta.abs(), 1 | 2) {
+            i += 1;
+            continue;
+        }
+        let mut j = i + 1;
+        while j + 1 < chars.len() && char_class(chars[j + 1]) == class && chars[j + 1] as i32 - chars[j] as i32 == delta
+        {
+            j += 1;
+        }
+        if j - i >= 2 {
+            let first = chars[i];
+            let base = if matches!(first, 'a' | 'A' | 'z' | 'Z' | '0' | '1' | '9') {
+                4.0
+            } else if class == 3 {
+                10.0
+            } else {
+                26.0
+            };
+            let direction = if delta < 0 { 2.0 } else { 1.0 };
+            push(out, chars, Pattern::Sequence, i, j, base * (j - i + 1) as f64 * direction);
+            i = j;
+        } else {
+            i += 1;
+        }
+    }
+}
+
+fn cardinality(chars: &[char]) -> f64 {
+    let mut lower = false;
+    let mut upper = false;
+    let mut digit = false;
+    let mut symbol = false;
+    let mut other = false;
+    for &c in chars {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii() => symbol = true,
+            _ => other = true,
+        }
+    }
+    let classes = [(lower, 26.0), (upper, 26.0), (digit, 10.0), (symbol, 33.0), (other, 100.0)];
+    classes.iter().filter(|(present, _)| *present).map(|(_, size)| size).sum::<f64>().max(1.0)
+}
+
+// aaa (jeden znak >= 3 razy) i abcabc (jednostka >= 2 znaki powtorzona >= 2 razy).
+fn repeat_matches(chars: &[char], out: &mut Vec<PatternMatch>) {
+    for i in 0..chars.len() {
+        for unit in 1..=(chars.len() - i) / 2 {
+            let min_repeats = if unit == 1 { 3 } else { 2 };
+            let mut repeats = 1;
+            while i + (repeats + 1) * unit <= chars.len()
+                && chars[i + repeats * unit..i + (repeats + 1) * unit] == chars[i..i + unit]
+            {
+                repeats += 1;
+            }
+            if repeats >= min_repeats {
+                let base = cardinality(&chars[i..i + unit]).powi(unit as i32);
+                push(out, chars, Pattern::Repeat, i, i + repeats * unit - 1, base * repeats as f64);
+            }
+        }
+    }
+}
+
+fn year_space(year: i32) -> f64 {
+    ((year - REFERENCE_YEAR).abs() as f64).max(MIN_YEAR_SPACE)
+}
+
+fn parse_digits(chars: &[char]) -> Option<i32> {
+    chars.iter().try_fold(0i32, |acc, c| c.to_digit(10).map(|d| acc * 10 + d as i32))
+}
+
+fn two_digit_year(y: i32) -> i32 {
+    if y > 50 { 1900 + y } else { 2000 + y }
+}
+
+fn valid_date(a: i32, b: i32, year: i32) -> bool {
+    let day_month = (1..=31).contains(&a) && (1..=12).contains(&b);
+    let month_day = (1..=12).contains(&a) && (1..=31).contains(&b);
+    (1900..=2099).contains(&year) && (day_month || month_day)
+}
+
+// Rok (1900-2099) oraz daty: ddmmyyyy, yyyymmdd, ddmmyy, z separatorem lub bez.
+fn date_matches(chars: &[char], out: &mut Vec<PatternMatch>) {
+    for i in 0..chars.len() {
+        if i + 4 <= chars.len()
+            && let Some(year) = parse_digits(&chars[i..i + 4])
+            && (1900..=2099).contains(&year)
+        {
+            push(out, chars, Pattern::Date, i, i + 3, year_space(year));
+        }
+        for len in [6usize, 8, 10] {
+            if i + len > chars.len() {
+                continue;
+            }
+            let window = &chars[i..i + len];
+            let (digits, separated): (Vec<char>, bool) = match len {
+                6 | 8 if window.iter().all(char::is_ascii_digit) => (window.to_vec(), false),
+                8 | 10 => {
+                    let sep = window[2];
+                    let alt_sep = window[4];
+                    let dmy = matches!(sep, '/' | '-' | '.' | '_' | ' ') && window[5] == sep;
+                    let ymd = len == 10 && matches!(alt_sep, '/' | '-' | '.' | '_' | ' ') && window[7] == alt_sep;
+                    if !(dmy || ymd) {
+                        continue;
+                    }
+                    (window.iter().copied().filter(char::is_ascii_digit).collect(), true)
+                }
+                _ => continue,
+            };
+            if digits.len() + if separated { 2 } else { 0 } != len {
+                continue;
+            }
+            let found = match digits.len() {
+                8 => {
+                    let year_last =
+                        parse_digits(&digits[4..]).zip(parse_digits(&digits[..2]).zip(parse_digits(&digits[2..4])));
+                    let year_first =
+                        parse_digits(&digits[..4]).zip(parse_digits(&digits[4..6]).zip(parse_digits(&digits[6..])));
+                    [year_last, year_first]
+                        .into_iter()
+                        .flatten()
+                        .find(|&(y, (a, b))| valid_date(a, b, y))
+                        .map(|(y, _)| y)
+                }
+                6 => parse_digits(&digits[4..])
+                    .map(two_digit_year)
+                    .zip(parse_digits(&digits[..2]).zip(parse_digits(&digits[2..4])))
+                    .filter(|&(y, (a, b))| valid_date(a, b, y))
+                    .map(|(y, _)| y),
+                _ => None,
+            };
+            if let Some(year) = found {
+                let separator_factor = if separated { 4.0 } else { 1.0 };
+                push(out, chars, Pattern::Date, i, i + len - 1, 365.0 * year_space(year) * separator_factor);
+            }
+        }
+    }
+}
+
+fn key_position(c: char) -> Option<(usize, usize, bool)> {
+    for (row, (plain, shifted)) in KEYBOARD_ROWS.iter().zip(KEYBOARD_SHIFTED.iter()).enumerate() {
+        if let Some(col) = plain.chars().position(|k| k == c) {
+            return Some((row, col, false));
+        }
+        if let Some(col) = shifted.chars().position(|k| k == c) {
+            return Some((row, col, true));
+        }
+    }
+    None
+}
+
+// Kierunek sasiedztwa na przesunietych rzedach QWERTY albo None, gdy klawisze nie sasiaduja.
+fn key_direction(from: (usize, usize), to: (usize, usize)) -> Option<u8> {
+    let (r1, c1) = (from.0 as i32, from.1 as i32);
+    let (r2, c2) = (to.0 as i32, to.1 as i32);
+    match (r2 - r1, c2 - c1) {
+        (0, -1) => Some(0),
+        (0, 1) => Some(1),
+        (-1, 0) => Some(2),
+        (-1, 1) => Some(3),
+        (1, -1) => Some(4),
+        (1, 0) => Some(5),
+        _ => None,
+    }
+}
+
+fn keyboard_guesses(len: usize, turns: usize, shifted: usize) -> f64 {
+    let mut guesses = 0.0;
+    for i in 2..=len as u64 {
+        for j in 1..=(turns as u64).min(i - 1) {
+            guesses += binomial(i - 1, j - 1) * KEYBOARD_STARTS * KEYBOARD_AVG_DEGREE.powi(j as i32);
+        }
+    }
+    if shifted > 0 {
+        let unshifted = len - shifted;
+        guesses *= if unshifted == 0 {
+            2.0
+        } else {
+            (1..=shifted.min(unshifted) as u64).map(|k| binomial(len as u64, k)).sum()
+        };
+    }
+    guesses
+}
+
+fn keyboard_matches(chars: &[char], out: &mut Vec<PatternMatch>) {
+    let mut i = 0;
+    while i < chars.len() {
+        let Some((row, col, shift)) = key_position(chars[i]) else {
+            i += 1;
+            continue;
+        };
+        let mut j = i;
+        let mut turns = 0;
+        let mut shifted = usize::from(shift);
+        let mut last_direction = None;
+        let mut position = (row, col);
+        while j + 1 < chars.len() {
+            let Some((r, c, s)) = key_position(chars[j + 1]) else {
+                break;
+            };
+            let Some(direction) = key_direction(position, (r, c)) else {
+                break;
+            };
+            if last_direction != Some(direction) {
+                turns += 1;
+                last_direction = Some(direction);
+            }
+            shifted += usize::from(s);
+            position = (r, c);
+            j += 1;
+        }
+        if j - i >= 2 {
+            push(out, chars, Pattern::Keyboard, i, j, keyboard_guesses(j - i + 1, turns, shifted));
+            i = j;
+        } else {
+            i += 1;
+        }
+    }
+}
+
+// Najmniejsza liczba prob dla kazdego prefiksu, osobno gdy konczy sie wzorcem (matched)
+// i fragmentem brute force (brute). Dwa fragmenty brute force nigdy nie sasiaduja - jeden
+// dluzszy kosztuje tyle samo, a raport jest czytelniejszy.
+fn minimum_guesses(chars: &[char], matches: &[PatternMatch]) -> (f64, Vec<PatternMatch>) {
+    let n = chars.len();
+    let card = cardinality(chars);
+    let mut matched = vec![f64::INFINITY; n + 1];
+    let mut brute = vec![f64::INFINITY; n + 1];
+    // (poczatek, indeks dopasowania, czy prefiks przed nim konczy sie brute force)
+    let mut matched_from = vec![(0, 0, false); n + 1];
+    let mut brute_from = vec![0; n + 1];
+    matched[0] = 1.0;
+    for k in 1..=n {
+        for (start, &prefix) in matched[..k].iter().enumerate() {
+            let guesses = prefix * card.powi((k - start) as i32);
+            if guesses < brute[k] {
+                brute[k] = guesses;
+                brute_from[k] = start;
+            }
+        }
+        for (idx, m) in matches.iter().enumerate().filter(|(_, m)| m.j + 1 == k) {
+            let after_brute = brute[m.i] < matched[m.i];
+            let guesses = matched[m.i].min(brute[m.i]) * m.guesses;
+            if guesses < matched[k] {
+                matched[k] = guesses;
+                matched_from[k] = (m.i, idx, after_brute);
+            }
+        }
+    }
+    let mut sequence = Vec::new();
+    let mut k = n;
+    let mut in_brute = brute[n] < matched[n];
+    while k > 0 {
+        if in_brute {
+            let start = brute_from[k];
+            sequence.push(PatternMatch {
+                pattern: Pattern::Bruteforce,
+                token: token(chars, start, k - 1),
+                i: start,
+                j: k - 1,
+                guesses: card.powi((k - start) as i32),
+            });
+            k = start;
+            in_brute = false;
+        } else {
+            let (start, idx, after_brute) = matched_from[k];
+            sequence.push(matches[idx].clone());
+            k = start;
+            in_brute = after_brute;
+        }
+    }
+    sequence.reverse();
+    (matched[n].min(brute[n]), sequence)
+}
+
+fn display_time(seconds: f64) -> String {
+    const UNITS: [(f64, &str); 6] = [
+        (60.0, "second"),
+        (3600.0, "minute"),
+        (86_400.0, "hour"),
+        (86_400.0 * 31.0, "day"),
+        (86_400.0 * 365.0, "month"),
+        (86_400.0 * 365.0 * 100.0, "year"),
+    ];
+    if seconds < 1.0 {
+        return "less than a second".to_string();
+    }
+    let mut divisor = 1.0;
+    for (limit, unit) in UNITS {
+        if seconds < limit {
+            let value = (seconds / divisor).round() as u64;
+            return format!("{value} {unit}{}", if value == 1 { "" } else { "s" });
+        }
+        divisor = limit;
+    }
+    "centuries".to_string()
+}
+
+fn crack_time(guesses: f64, per_second: f64) -> CrackTime {
+    let seconds = guesses / per_second;
+    CrackTime { seconds, display: display_time(seconds) }
+}
+
+fn score(guesses: f64) -> u8 {
+    match guesses {
+        g if g < 1e3 => 0,
+        g if g < 1e6 => 1,
+        g if g < 1e8 => 2,
+        g if g < 1e10 => 3,
+        _ => 4,
+    }
+}
+
+pub fn estimate_strength_report(password: &str) -> StrengthReport {
+    let all: Vec<char> = password.chars().collect();
+    let chars = &all[..all.len().min(MAX_ANALYZED)];
+    let mut matches = Vec::new();
+    dictionary_matches(chars, &mut matches);
+    sequence_matches(chars, &mut matches);
+    repeat_matches(chars, &mut matches);
+    date_matches(chars, &mut matches);
+    keyboard_matches(chars, &mut matches);
+    let (mut guesses, sequence) = minimum_guesses(chars, &matches);
+    if all.len() > chars.len() {
+        guesses *= cardinality(&all).powi((all.len() - chars.len()) as i32);
+    }
+    let guesses = guesses.clamp(1.0, MAX_GUESSES);
+    StrengthReport {
+        guesses,
+        guesses_log10: guesses.log10(),
+        score: score(guesses),
+        crack_times: CrackTimes {
+            online_throttling: crack_time(guesses, 100.0 / 3600.0),
+            online_no_throttling: crack_time(guesses, 10.0),
+            offline_slow_hashing: crack_time(guesses, 1e4),
+            offline_fast_hashing: crack_time(guesses, 1e10),
+        },
+        sequence,
+    }
+}
+
+// Raport jako JSON: { guesses, guesses_log10, score, crack_times: { ...: { seconds, display } },
+// sequence: [{ pattern, token, i, j, guesses }] } - indeksy w znakach, nie w bajtach UTF-8.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn estimate_strength(password: &str) -> Result<String, CryptoError> {
+    serde_json::to_string(&estimate_strength_report(password)).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wzorzec, gdy cale haslo pokrywa jedno dopasowanie.
+    fn single_pattern(password: &str) -> Option<Pattern> {
+        match estimate_strength_report(password).sequence.as_slice() {
+            [m] if m.i == 0 && m.j + 1 == password.chars().count() => Some(m.pattern),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn each_pattern_is_found() {
+        assert_eq!(single_pattern("password"), Some(Pattern::Dictionary));
+        assert_eq!(single_pattern("Danger"), Some(Pattern::Dictionary));
+        assert_eq!(single_pattern("drowssap"), Some(Pattern::Dictionary));
+        assert_eq!(single_pattern("d4ng3r"), Some(Pattern::Dictionary));
+        assert_eq!(single_pattern("lmnopq"), Some(Pattern::Sequence));
+        assert_eq!(single_pattern("97531"), Some(Pattern::Sequence));
+        assert_eq!(single_pattern("zzzzzz"), Some(Pattern::Repeat));
+        assert_eq!(single_pattern("5tgb"), Some(Pattern::Keyboard));
+        assert_eq!(single_pattern("12051999"), Some(Pattern::Date));
+        assert_eq!(single_pattern("19990512"), Some(Pattern::Date));
+        assert_eq!(single_pattern("12/05/1999"), Some(Pattern::Date));
+        assert_eq!(single_pattern("1999-05-12"), Some(Pattern::Date));
+        assert_eq!(single_pattern("12/05/99"), Some(Pattern::Date));
+        assert_eq!(single_pattern("311287"), Some(Pattern::Date));
+    }
+
+    #[test]
+    fn word_ranks_come_from_the_lists() {
+        assert_eq!(dictionary().get("password"), Some(&2));
+        assert_eq!(dictionary().get("123456"), Some(&1));
+        assert!(dictionary().len() > 1500);
+        // Haslo z listy: ranga, nie brute force
+        assert!(estimate_strength_report("monkey").guesses < 100.0);
+    }
+
+    #[test]
+    fn score_buckets() {
+        assert_eq!([999.0, 1e3, 1e6 - 1.0, 1e6, 1e8, 1e10, 1e20].map(score), [0, 1, 1, 2, 3, 4, 4]);
+        assert_eq!(estimate_strength_report("password").score, 0);
+        assert_eq!(estimate_strength_report("zzzzzz").score, 0);
+        assert!(estimate_strength_report("Tr0ub4dour&3xK9!qZ").score >= 3);
+        assert_eq!(estimate_strength_report("v8#Lq2!mZ@r5^Wx7&nB1").score, 4);
+    }
+
+    #[test]
+    fn long_input_stays_finite() -> Result<(), CryptoError> {
+        let password = "ż9#".repeat(200);
+        let report = estimate_strength_report(&password);
+        assert_eq!(report.guesses, MAX_GUESSES);
+        assert!(report.crack_times.offline_fast_hashing.seconds.is_finite());
+        assert!(!estimate_strength(&password)?.contains("null"));
+        Ok(())
+    }
+}
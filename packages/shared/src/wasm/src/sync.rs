@@ -0,0 +1,6 @@
+pub mod delta;
+pub mod merge;
+
+// Synchronizacja vaulta miedzy urzadzeniami po stronie klienta: serwer przechowuje tylko
+// szyfrogramy, wiec laczenie zmian wykonanych offline odbywa sie na odszyfrowanych wpisach,
+// a porownanie stanow (delta) na wektorach wersji bez odszyfrowywania.
@@ -0,0 +1,198 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+
+// Wyznaczanie, ktore szyfrogramy wpisow wyslac, a ktore pobrac, bez przesylania calego vaulta.
+// Kazdy wpis ma wektor wersji { id urzadzenia: licznik }; urzadzenie zapisujac wpis zwieksza
+// swoj licznik (po polaczeniu z inna wersja - najpierw maksimum po skladowych).
+//   lokalny >= zdalny na kazdej skladowej, rozne  -> upload
+//   zdalny >= lokalny                             -> download
+//   rowne                                         -> nic
+//   wspolbiezne                                   -> conflict: pobrac, polaczyc (sync::merge), wyslac
+// Wpis tylko po jednej stronie jest wysylany albo pobierany. Usuniecia to nagrobki w stanie
+// klienta - wpis z wektorem jak kazdy inny, wiec propaguja sie tak samo.
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct VersionVector(pub BTreeMap<String, u64>);
+
+impl VersionVector {
+    fn get(&self, device: &str) -> u64 {
+        self.0.get(device).copied().unwrap_or(0)
+    }
+
+    // None dla wektorow wspolbieznych.
+    pub fn compare(&self, other: &VersionVector) -> Option<Ordering> {
+        let devices: BTreeSet<&String> = self.0.keys().chain(other.0.keys()).collect();
+        let (mut less, mut greater) = (false, false);
+        for device in devices {
+            match self.get(device).cmp(&other.get(device)) {
+                Ordering::Less => less = true,
+                Ordering::Greater => greater = true,
+                Ordering::Equal => {}
+            }
+        }
+        match (less, greater) {
+            (false, false) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (true, true) => None,
+        }
+    }
+
+    pub fn join(&self, other: &VersionVector) -> VersionVector {
+        let mut out = self.clone();
+        for (device, &counter) in &other.0 {
+            let entry = out.0.entry(device.clone()).or_insert(0);
+            *entry = (*entry).max(counter);
+        }
+        out
+    }
+
+    pub fn increment(&mut self, device: &str) -> Result<(), CryptoError> {
+        if device.is_empty() {
+            return Err(CryptoError::InvalidInput("device id must not be empty".to_string()));
+        }
+        let counter = self.0.entry(device.to_string()).or_insert(0);
+        *counter =
+            counter.checked_add(1).ok_or_else(|| CryptoError::OutOfRange("version counter overflow".to_string()))?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncDelta {
+    pub upload: Vec<String>,
+    pub download: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub unchanged: u32,
+}
+
+pub fn compute_delta_with(
+    local: &BTreeMap<String, VersionVector>,
+    remote: &BTreeMap<String, VersionVector>,
+) -> SyncDelta {
+    let mut delta = SyncDelta::default();
+    let ids: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    for id in ids {
+        let list = match (local.get(id), remote.get(id)) {
+            (Some(_), None) => &mut delta.upload,
+            (None, Some(_)) => &mut delta.download,
+            (Some(l), Some(r)) => match l.compare(r) {
+                Some(Ordering::Greater) => &mut delta.upload,
+                Some(Ordering::Less) => &mut delta.download,
+                Some(Ordering::Equal) => {
+                    delta.unchanged += 1;
+                    continue;
+                }
+                None => &mut delta.conflicts,
+            },
+            (None, None) => continue,
+        };
+        list.push(id.clone());
+    }
+    delta
+}
+
+fn parse_vectors(json: &str, what: &str) -> Result<BTreeMap<String, VersionVector>, CryptoError> {
+    serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(format!("malformed {what}: {e}")))
+}
+
+fn parse_vector(json: &str) -> Result<VersionVector, CryptoError> {
+    serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(format!("malformed version vector: {e}")))
+}
+
+// local_state i remote_manifest - obiekty JSON { id wpisu: { id urzadzenia: licznik } }.
+// Wynik: {"upload": [...], "download": [...], "conflicts": [...], "unchanged": n}.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn compute_delta(local_state: &str, remote_manifest: &str) -> Result<String, CryptoError> {
+    let delta = compute_delta_with(
+        &parse_vectors(local_state, "local state")?,
+        &parse_vectors(remote_manifest, "remote manifest")?,
+    );
+    serde_json::to_string(&delta).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+// Wektor dla nowego zapisu wpisu na tym urzadzeniu; merged_with - wektor wersji zdalnej,
+// z ktora wpis zostal wlasnie polaczony (konflikt).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn next_version_vector(device_id: &str, current: &str, merged_with: Option<String>) -> Result<String, CryptoError> {
+    let mut vector = parse_vector(current)?;
+    if let Some(other) = merged_with {
+        vector = vector.join(&parse_vector(&other)?);
+    }
+    vector.increment(device_id)?;
+    serde_json::to_string(&vector).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(entries: &[(&str, u64)]) -> VersionVector {
+        VersionVector(entries.iter().map(|(device, counter)| (device.to_string(), *counter)).collect())
+    }
+
+    #[test]
+    fn compares_version_vectors() {
+        let a = vector(&[("phone", 2), ("laptop", 1)]);
+        assert_eq!(a.compare(&vector(&[("phone", 1), ("laptop", 1)])), Some(Ordering::Greater));
+        assert_eq!(a.compare(&vector(&[("phone", 2), ("laptop", 1), ("tablet", 0)])), Some(Ordering::Equal));
+        assert_eq!(a.compare(&vector(&[("phone", 2), ("laptop", 1), ("tablet", 1)])), Some(Ordering::Less));
+        assert_eq!(a.compare(&vector(&[("phone", 1), ("laptop", 2)])), None);
+    }
+
+    #[test]
+    fn classifies_items_by_vector() {
+        let local: BTreeMap<String, VersionVector> = [
+            ("ahead", vector(&[("phone", 2)])),
+            ("behind", vector(&[("phone", 1)])),
+            ("same", vector(&[("phone", 1)])),
+            ("split", vector(&[("phone", 2), ("laptop", 1)])),
+            ("local-only", vector(&[("phone", 1)])),
+        ]
+        .into_iter()
+        .map(|(id, v)| (id.to_string(), v))
+        .collect();
+        let remote: BTreeMap<String, VersionVector> = [
+            ("ahead", vector(&[("phone", 1)])),
+            ("behind", vector(&[("phone", 1), ("laptop", 1)])),
+            ("same", vector(&[("phone", 1)])),
+            ("split", vector(&[("phone", 1), ("laptop", 2)])),
+            ("remote-only", vector(&[("laptop", 1)])),
+        ]
+        .into_iter()
+        .map(|(id, v)| (id.to_string(), v))
+        .collect();
+
+        let delta = compute_delta_with(&local, &remote);
+        assert_eq!(delta.upload, vec!["ahead".to_string(), "local-only".to_string()]);
+        assert_eq!(delta.download, vec!["behind".to_string(), "remote-only".to_string()]);
+        assert_eq!(delta.conflicts, vec!["split".to_string()]);
+        assert_eq!(delta.unchanged, 1);
+    }
+
+    #[test]
+    fn next_vector_joins_then_increments() -> Result<(), CryptoError> {
+        let next = next_version_vector("phone", r#"{"phone":2,"laptop":1}"#, Some(r#"{"laptop":3}"#.to_string()))?;
+        assert_eq!(next, r#"{"laptop":3,"phone":3}"#);
+        // Wynik dominuje obie polaczone wersje
+        let next = parse_vector(&next)?;
+        assert_eq!(next.compare(&vector(&[("laptop", 3)])), Some(Ordering::Greater));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(matches!(compute_delta("{", "{}"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(compute_delta(r#"{"a":{"phone":-1}}"#, "{}"), Err(CryptoError::InvalidInput(_))));
+        assert!(matches!(next_version_vector("", "{}", None), Err(CryptoError::InvalidInput(_))));
+        let overflow = next_version_vector("phone", &format!(r#"{{"phone":{}}}"#, u64::MAX), None);
+        assert!(matches!(overflow, Err(CryptoError::OutOfRange(_))));
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+use crate::item::{FieldKind, VaultItem};
+use crate::secret::EntryKey;
+
+// Laczenie trojstronne (base = ostatni wspolny stan, local, remote) odszyfrowanych wpisow.
+// Wpis porownywany pole po polu: pola glowne, pola sekcji ("login.username"), pola wlasne
+// po nazwie ("fields[PIN]"); tagi jako zbior (dodania i usuniecia z obu stron).
+// Pole zmienione tylko po jednej stronie bierze te zmiane; zmienione po obu roznie -
+// wygrywa wpis o pozniejszym updated_at (remis: wieksza serializacja JSON, zeby oba
+// urzadzenia doszly do tego samego wyniku). Wyjatek to haslo: przegrane haslo nie ginie,
+// trafia do ukrytego pola wlasnego "password (conflict <updated_at>)".
+// Usuniecie po jednej stronie i edycja po drugiej - edycja wygrywa.
+const SECTIONS: [&str; 3] = ["login", "card", "identity"];
+const PASSWORD_PATH: &str = "login.password";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    Local,
+    Remote,
+    BothKept,
+    EditKept,
+}
+
+// field == "item" dla konfliktow calego wpisu (usuniecie kontra edycja, niespojny wynik).
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub item: String,
+    pub field: String,
+    pub resolution: Resolution,
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct MergeResult {
+    pub items: Vec<VaultItem>,
+    pub deleted: Vec<String>,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+fn to_object(item: &VaultItem) -> Result<Map<String, Value>, CryptoError> {
+    match serde_json::to_value(item).map_err(|e| CryptoError::InvalidInput(e.to_string()))? {
+        Value::Object(map) => Ok(map),
+        _ => Err(CryptoError::InvalidInput("item must serialize to an object".to_string())),
+    }
+}
+
+// Sciezka -> wartosc; obecnosc sekcji jako osobna sciezka ("login" = true).
+fn flatten(item: &VaultItem) -> Result<BTreeMap<String, Value>, CryptoError> {
+    let mut out = BTreeMap::new();
+    for (key, value) in to_object(item)? {
+        match key.as_str() {
+            "id" | "created_at" | "updated_at" | "tags" => {}
+            "fields" => {
+                for field in value.as_array().into_iter().flatten() {
+                    if let Some(name) = field.get("name").and_then(Value::as_str) {
+                        out.insert(format!("fields[{name}]"), field.clone());
+                    }
+                }
+            }
+            section if SECTIONS.contains(&section) => {
+                for (sub, v) in value.as_object().into_iter().flatten() {
+                    out.insert(format!("{section}.{sub}"), v.clone());
+                }
+                out.insert(key, Value::Bool(true));
+            }
+            _ => {
+                out.insert(key, value);
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn unflatten(
+    flat: BTreeMap<String, Value>,
+    field_order: &[String],
+    mut object: Map<String, Value>,
+) -> Result<VaultItem, CryptoError> {
+    let mut sections: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    let mut present = BTreeSet::new();
+    let mut fields = BTreeMap::new();
+    for (path, value) in flat {
+        if let Some(name) = path.strip_prefix("fields[").and_then(|p| p.strip_suffix(']')) {
+            fields.insert(name.to_string(), value);
+        } else if let Some((section, sub)) = path.split_once('.') {
+            sections.entry(section.to_string()).or_default().insert(sub.to_string(), value);
+        } else if SECTIONS.contains(&path.as_str()) {
+            present.insert(path);
+        } else {
+            object.insert(path, value);
+        }
+    }
+    for section in SECTIONS {
+        // Pola sekcji bez samej sekcji (usunieta po drugiej stronie) przepadaja
+        let data = sections.remove(section).unwrap_or_default();
+        if present.contains(section) {
+            object.insert(section.to_string(), Value::Object(data));
+        }
+    }
+    let fields: Vec<Value> = field_order.iter().filter_map(|name| fields.remove(name)).collect();
+    object.insert("fields".to_string(), Value::Array(fields));
+    serde_json::from_value(Value::Object(object)).map_err(|e| CryptoError::InvalidInput(format!("merged item: {e}")))
+}
+
+fn merge_tags(base: Option<&VaultItem>, local: &VaultItem, remote: &VaultItem) -> Vec<String> {
+    let base: BTreeSet<String> = base.map(|b| b.tags().into_iter().collect()).unwrap_or_default();
+    let (local, remote) = (local.tags(), remote.tags());
+    let mut out: Vec<String> = local.iter().filter(|t| remote.contains(t) || !base.contains(*t)).cloned().collect();
+    out.extend(remote.iter().filter(|t| !local.contains(t) && !base.contains(*t)).cloned());
+    out
+}
+
+fn remote_is_newer(local: &VaultItem, remote: &VaultItem) -> Result<bool, CryptoError> {
+    if local.updated_at() != remote.updated_at() {
+        return Ok(remote.updated_at() > local.updated_at());
+    }
+    Ok(remote.to_json()? > local.to_json()?)
+}
+
+pub fn merge_item_with(
+    field_key: &EntryKey,
+    base: Option<&VaultItem>,
+    local: &VaultItem,
+    remote: &VaultItem,
+    now: u64,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Result<VaultItem, CryptoError> {
+    if local == remote {
+        return Ok(local.clone());
+    }
+    let remote_wins = remote_is_newer(local, remote)?;
+    let (winner, loser) = if remote_wins { (remote, local) } else { (local, remote) };
+    let base_flat = base.map(flatten).transpose()?.unwrap_or_default();
+    let (local_flat, remote_flat) = (flatten(local)?, flatten(remote)?);
+    let paths: BTreeSet<&String> = base_flat.keys().chain(local_flat.keys()).chain(remote_flat.keys()).collect();
+
+    let mut merged = BTreeMap::new();
+    let mut lost_password = None;
+    for path in paths {
+        let (b, l, r) = (base_flat.get(path), local_flat.get(path), remote_flat.get(path));
+        let value = if l == r || r == b {
+            l
+        } else if l == b {
+            r
+        } else {
+            let (won, lost) = if remote_wins { (r, l) } else { (l, r) };
+            let mut resolution = if remote_wins { Resolution::Remote } else { Resolution::Local };
+            if path == PASSWORD_PATH
+                && let (Some(Value::String(_)), Some(Value::String(lost))) = (won, lost)
+            {
+                lost_password = Some(lost.clone());
+                resolution = Resolution::BothKept;
+            }
+            conflicts.push(MergeConflict { item: local.id(), field: path.clone(), resolution });
+            won
+        };
+        if let Some(value) = value {
+            merged.insert(path.clone(), value.clone());
+        }
+    }
+
+    let local_fields = local.field_names();
+    let remote_only = remote.field_names().into_iter().filter(|name| !local_fields.contains(name));
+    let field_order: Vec<String> = local_fields.iter().cloned().chain(remote_only).collect();
+    let mut object = Map::new();
+    object.insert("id".to_string(), Value::from(local.id()));
+    object.insert("created_at".to_string(), Value::from(local.created_at().min(remote.created_at())));
+    object.insert("updated_at".to_string(), Value::from(local.updated_at().max(remote.updated_at())));
+    object.insert("tags".to_string(), Value::from(merge_tags(base, local, remote)));
+
+    // Zmiany pol z obu stron moga dac wpis niespojny (np. inny typ i sekcja) - wtedy caly nowszy
+    let mut item = match unflatten(merged, &field_order, object) {
+        Ok(item) if item.validate().is_ok() => item,
+        _ => {
+            let resolution = if remote_wins { Resolution::Remote } else { Resolution::Local };
+            conflicts.push(MergeConflict { item: local.id(), field: "item".to_string(), resolution });
+            winner.clone()
+        }
+    };
+    if let Some(password) = lost_password {
+        let name = format!("password (conflict {})", loser.updated_at());
+        item.set_field_with(field_key, &name, FieldKind::Hidden, &password, now)?;
+    }
+    Ok(item)
+}
+
+fn index<'a>(items: &'a [VaultItem], side: &str) -> Result<BTreeMap<String, &'a VaultItem>, CryptoError> {
+    let mut out = BTreeMap::new();
+    for item in items {
+        if out.insert(item.id(), item).is_some() {
+            return Err(CryptoError::InvalidInput(format!("duplicate item {} in {side} items", item.id())));
+        }
+    }
+    Ok(out)
+}
+
+pub fn merge_items_with(
+    field_key: &EntryKey,
+    base: &[VaultItem],
+    local: &[VaultItem],
+    remote: &[VaultItem],
+    now: u64,
+) -> Result<MergeResult, CryptoError> {
+    let (base, local, remote) = (index(base, "base")?, index(local, "local")?, index(remote, "remote")?);
+    let ids: BTreeSet<&String> = base.keys().chain(local.keys()).chain(remote.keys()).collect();
+    let mut result = MergeResult::default();
+    for id in ids {
+        let b = base.get(id).copied();
+        match (b, local.get(id), remote.get(id)) {
+            (_, Some(l), Some(r)) => {
+                result.items.push(merge_item_with(field_key, b, l, r, now, &mut result.conflicts)?);
+            }
+            (None, Some(only), None) | (None, None, Some(only)) => result.items.push((*only).clone()),
+            (Some(b), Some(kept), None) | (Some(b), None, Some(kept)) => {
+                if *kept == b {
+                    result.deleted.push(id.clone());
+                } else {
+                    let resolution = Resolution::EditKept;
+                    result.conflicts.push(MergeConflict { item: id.clone(), field: "item".to_string(), resolution });
+                    result.items.push((*kept).clone());
+                }
+            }
+            (_, None, None) => result.deleted.push(id.clone()),
+        }
+    }
+    result.deleted.retain(|id| base.contains_key(id));
+    Ok(result)
+}
+
+fn parse_items(json: &str, side: &str) -> Result<Vec<VaultItem>, CryptoError> {
+    let values: Vec<Value> =
+        serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(format!("malformed {side} items: {e}")))?;
+    values.iter().map(|value| VaultItem::from_json(&value.to_string())).collect()
+}
+
+// base / local / remote - tablice JSON wpisow (VaultItem.to_json); klucz pol jak item_field_key.
+// Wynik: {"items": [...], "deleted": [id...], "conflicts": [{item, field, resolution}...]}.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn merge_vault_items(
+    field_key: &[u8],
+    base: &str,
+    local: &str,
+    remote: &str,
+    now: u64,
+) -> Result<String, CryptoError> {
+    let result = merge_items_with(
+        &EntryKey::from_slice(field_key),
+        &parse_items(base, "base")?,
+        &parse_items(local, "local")?,
+        &parse_items(remote, "remote")?,
+        now,
+    )?;
+    serde_json::to_string(&result).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn login(id: &str, data: &str, now: u64) -> Result<VaultItem, CryptoError> {
+        let mut item = VaultItem::create(id, crate::item::ItemType::Login, "Example", 100)?;
+        item.set_data(data, now)?;
+        Ok(item)
+    }
+
+    #[test]
+    fn merges_disjoint_field_edits_and_tags() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&[7u8; 32]);
+        let mut base = login("a", r#"{"username":"alice","password":"pw-1"}"#, 100)?;
+        base.add_tag("work", 100);
+        let mut local = base.clone();
+        local.set_data(r#"{"username":"alice@example.com","password":"pw-1"}"#, 200)?;
+        local.remove_tag("work", 200);
+        let mut remote = base.clone();
+        remote.set_notes(Some("shared".to_string()), 300);
+        remote.add_tag("home", 300);
+
+        let result = merge_items_with(&key, &[base], &[local], &[remote], 400)?;
+        assert!(result.conflicts.is_empty());
+        assert!(result.deleted.is_empty());
+        let merged = result.items.first().ok_or_else(|| CryptoError::InvalidInput("no item".to_string()))?;
+        assert_eq!(merged.login().and_then(|l| l.username.as_deref()), Some("alice@example.com"));
+        assert_eq!(merged.notes().as_deref(), Some("shared"));
+        assert_eq!(merged.tags(), vec!["home".to_string()]);
+        assert_eq!(merged.updated_at(), 300);
+        Ok(())
+    }
+
+    #[test]
+    fn password_conflict_keeps_losing_password() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&[7u8; 32]);
+        let base = login("a", r#"{"password":"old"}"#, 100)?;
+        let local = login("a", r#"{"password":"from-local"}"#, 200)?;
+        let remote = login("a", r#"{"password":"from-remote"}"#, 300)?;
+
+        let result = merge_items_with(&key, &[base], &[local], &[remote], 400)?;
+        let expected = MergeConflict {
+            item: "a".to_string(),
+            field: PASSWORD_PATH.to_string(),
+            resolution: Resolution::BothKept,
+        };
+        assert_eq!(result.conflicts, vec![expected]);
+        let merged = result.items.first().ok_or_else(|| CryptoError::InvalidInput("no item".to_string()))?;
+        assert_eq!(merged.login().and_then(|l| l.password.as_deref()), Some("from-remote"));
+        let kept = merged.reveal_field_with(&key, "password (conflict 200)")?;
+        assert_eq!(kept.as_str(), "from-local");
+        Ok(())
+    }
+
+    #[test]
+    fn edit_beats_delete_and_untouched_delete_propagates() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&[7u8; 32]);
+        let a = login("a", r#"{"password":"a"}"#, 100)?;
+        let b = login("b", r#"{"password":"b"}"#, 100)?;
+        let mut edited = b.clone();
+        edited.set_name("Renamed", 200);
+
+        // Lokalnie usuniete oba; zdalnie "a" bez zmian, "b" edytowany
+        let result = merge_items_with(&key, &[a.clone(), b], &[], &[a, edited.clone()], 300)?;
+        assert_eq!(result.deleted, vec!["a".to_string()]);
+        assert_eq!(result.items, vec![edited]);
+        let resolution = Resolution::EditKept;
+        let conflict = MergeConflict { item: "b".to_string(), field: "item".to_string(), resolution };
+        assert_eq!(result.conflicts, vec![conflict]);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_duplicate_ids_and_malformed_json() -> Result<(), CryptoError> {
+        let key = EntryKey::from_slice(&[7u8; 32]);
+        let a = login("a", r#"{"password":"a"}"#, 100)?;
+        let merged = merge_items_with(&key, &[], &[a.clone(), a.clone()], &[a], 200);
+        assert!(matches!(merged, Err(CryptoError::InvalidInput(_))));
+        let merged = merge_vault_items(&[7u8; 32], "[]", "{", "[]", 200);
+        assert!(matches!(merged, Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::ct::ct_eq;
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::rng::random_array;
+use crate::sha2::Sha256;
+use crate::x25519;
+
+// Sekrety "break-glass" z opoznieniem. Klucz danych K dzielony jest na dwa udzialy
+// (K = udzial klienta XOR udzial serwera, oba potrzebne). Udzial serwera jest szyfrowany
+// do klucza X25519 serwera razem z czasem odblokowania; serwer wydaje go dopiero po T.
+// Koperta zawiera zobowiazanie do udzialu serwera, wiec klient sprawdza wydany udzial
+// zanim cokolwiek odszyfruje (zly udzial = blad weryfikacji, nie smieci).
+//
+// Koperta:        "PMTL" || 1 || unlock_at (u64 BE) || lock id (16) || zobowiazanie (32) || nonce || ct || tag
+// Udzial serwera: "PMTS" || 1 || unlock_at (u64 BE) || lock id (16) || klucz efemeryczny (32) || nonce || ct || tag
+// W obu naglowek (wszystko przed nonce) jest AAD - nie da sie przestawic czasu odblokowania.
+const ENVELOPE_MAGIC: &[u8; 4] = b"PMTL";
+const SHARE_MAGIC: &[u8; 4] = b"PMTS";
+const TIMELOCK_VERSION: u8 = 1;
+const LOCK_ID_LEN: usize = 16;
+const PREFIX_LEN: usize = 4 + 1 + 8 + LOCK_ID_LEN;
+const HEADER_LEN: usize = PREFIX_LEN + 32;
+const COMMITMENT_LABEL: &[u8] = b"pm-timelock-share-v1\0";
+const DATA_KEY_INFO: &[u8] = b"pm-timelock-data";
+const SHARE_KEY_INFO: &[u8] = b"pm-timelock-share";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeLockHeader {
+    pub unlock_at: u64,
+    pub lock_id: [u8; LOCK_ID_LEN],
+}
+
+fn prefix(magic: &[u8; 4], header: &TimeLockHeader) -> Vec<u8> {
+    let mut out = magic.to_vec();
+    out.push(TIMELOCK_VERSION);
+    out.extend_from_slice(&header.unlock_at.to_be_bytes());
+    out.extend_from_slice(&header.lock_id);
+    out
+}
+
+// Zwraca naglowek i pozostale 32 bajty naglowka (zobowiazanie albo klucz efemeryczny).
+fn parse_header<'a>(magic: &[u8; 4], blob: &'a [u8]) -> Result<(TimeLockHeader, &'a [u8], &'a [u8]), CryptoError> {
+    if blob.len() < HEADER_LEN + NONCE_LEN + TAG_LEN {
+        return Err(CryptoError::CorruptHeader("time-lock blob truncated".to_string()));
+    }
+    if &blob[..4] != magic {
+        return Err(CryptoError::CorruptHeader("not a time-lock blob of this kind".to_string()));
+    }
+    if blob[4] != TIMELOCK_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("time-lock version {}", blob[4])));
+    }
+    let mut unlock_at = [0u8; 8];
+    unlock_at.copy_from_slice(&blob[5..13]);
+    let mut lock_id = [0u8; LOCK_ID_LEN];
+    lock_id.copy_from_slice(&blob[13..PREFIX_LEN]);
+    let (header, body) = blob.split_at(HEADER_LEN);
+    Ok((TimeLockHeader { unlock_at: u64::from_be_bytes(unlock_at), lock_id }, header, body))
+}
+
+fn share_commitment(lock_id: &[u8], server_share: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(COMMITMENT_LABEL);
+    hasher.update(lock_id);
+    hasher.update(server_share);
+    hasher.finalize()
+}
+
+fn combine(client_share: &[u8], server_share: &[u8]) -> Result<Zeroizing<[u8; 32]>, CryptoError> {
+    if client_share.len() != 32 || server_share.len() != 32 {
+        return Err(CryptoError::InvalidLength("time-lock shares must be 32 bytes".to_string()));
+    }
+    let mut key = Zeroizing::new([0u8; 32]);
+    for (k, (a, b)) in key.iter_mut().zip(client_share.iter().zip(server_share)) {
+        *k = a ^ b;
+    }
+    Ok(key)
+}
+
+fn data_cipher(key: &[u8; 32], lock_id: &[u8]) -> Result<AesGcm, CryptoError> {
+    let key = Zeroizing::new(hkdf_sha256_bytes(key, lock_id, DATA_KEY_INFO, 32)?);
+    AesGcm::new(&key)
+}
+
+fn share_cipher(shared: &[u8; 32], ephemeral_public: &[u8], server_public: &[u8; 32]) -> Result<AesGcm, CryptoError> {
+    let salt = [ephemeral_public, server_public].concat();
+    let key = Zeroizing::new(hkdf_sha256_bytes(shared, &salt, SHARE_KEY_INFO, 32)?);
+    AesGcm::new(&key)
+}
+
+pub struct SealedTimeLock {
+    pub envelope: Vec<u8>,
+    pub client_share: Zeroizing<[u8; 32]>,
+    pub server_share: Vec<u8>,
+}
+
+pub fn create_timelock_bytes(
+    plaintext: &[u8],
+    unlock_at: u64,
+    server_public: &[u8; 32],
+) -> Result<SealedTimeLock, CryptoError> {
+    let header = TimeLockHeader { unlock_at, lock_id: random_array::<LOCK_ID_LEN>()? };
+    let client_share = Zeroizing::new(random_array::<32>()?);
+    let mut server_share = Zeroizing::new(random_array::<32>()?);
+    let key = combine(&*client_share, &*server_share)?;
+
+    let mut envelope = prefix(ENVELOPE_MAGIC, &header);
+    envelope.extend_from_slice(&share_commitment(&header.lock_id, &*server_share));
+    let nonce = random_array::<NONCE_LEN>()?;
+    let sealed = data_cipher(&key, &header.lock_id)?.encrypt(&nonce, &envelope, plaintext)?;
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&sealed);
+
+    let mut ephemeral = x25519::generate_secret()?;
+    let ephemeral_public = x25519::public_key_bytes(&ephemeral);
+    let shared = x25519::shared_secret(&ephemeral, server_public);
+    ephemeral.zeroize();
+    let mut shared = shared?;
+    let cipher = share_cipher(&shared, &ephemeral_public, server_public);
+    shared.zeroize();
+    let mut sealed_share = prefix(SHARE_MAGIC, &header);
+    sealed_share.extend_from_slice(&ephemeral_public);
+    let nonce = random_array::<NONCE_LEN>()?;
+    let sealed = cipher?.encrypt(&nonce, &sealed_share, &*server_share)?;
+    server_share.zeroize();
+    sealed_share.extend_from_slice(&nonce);
+    sealed_share.extend_from_slice(&sealed);
+
+    Ok(SealedTimeLock { envelope, client_share, server_share: sealed_share })
+}
+
+// Strona serwera: odszyfrowuje udzial dopiero, gdy now >= unlock_at. Czas pochodzi
+// z zegara serwera - klient nie ma na niego wplywu.
+pub fn release_timelock_share_bytes(server_secret: &[u8; 32], sealed_share: &[u8], now: u64) -> Result<Vec<u8>, CryptoError> {
+    let (header, aad, body) = parse_header(SHARE_MAGIC, sealed_share)?;
+    if now < header.unlock_at {
+        return Err(CryptoError::OutOfRange(format!(
+            "time lock opens at {}, {} seconds remaining",
+            header.unlock_at,
+            header.unlock_at - now
+        )));
+    }
+    let mut ephemeral_public = [0u8; 32];
+    ephemeral_public.copy_from_slice(&aad[PREFIX_LEN..]);
+    let server_public = x25519::public_key_bytes(server_secret);
+    let mut shared = x25519::shared_secret(server_secret, &ephemeral_public)?;
+    let cipher = share_cipher(&shared, &ephemeral_public, &server_public);
+    shared.zeroize();
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    cipher?.decrypt(nonce, aad, sealed)
+}
+
+// Strona klienta: sprawdza wydany udzial wzgledem zobowiazania i odszyfrowuje.
+pub fn open_timelock_bytes(envelope: &[u8], client_share: &[u8], server_share: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let (header, aad, body) = parse_header(ENVELOPE_MAGIC, envelope)?;
+    if !ct_eq(&share_commitment(&header.lock_id, server_share), &aad[PREFIX_LEN..]) {
+        return Err(CryptoError::VerificationFailed("released share does not match the time-lock envelope".to_string()));
+    }
+    let key = combine(client_share, server_share)?;
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    data_cipher(&key, &header.lock_id)?.decrypt(nonce, aad, sealed)
+}
+
+// Naglowek koperty albo zaszyfrowanego udzialu - do parowania ich po lock id.
+pub fn timelock_header_bytes(blob: &[u8]) -> Result<TimeLockHeader, CryptoError> {
+    let magic = if blob.starts_with(SHARE_MAGIC) { SHARE_MAGIC } else { ENVELOPE_MAGIC };
+    Ok(parse_header(magic, blob)?.0)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct TimeLock {
+    envelope: Vec<u8>,
+    client_share: Zeroizing<[u8; 32]>,
+    server_share: Vec<u8>,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl TimeLock {
+    // Przechowuje klient (razem z udzialem klienta, np. w vaultcie).
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn envelope(&self) -> Vec<u8> {
+        self.envelope.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn client_share(&self) -> Vec<u8> {
+        self.client_share.to_vec()
+    }
+
+    // Wysylane na serwer; tylko serwer moze go odszyfrowac.
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn server_share(&self) -> Vec<u8> {
+        self.server_share.clone()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn create_timelock(plaintext: &[u8], unlock_at: u64, server_public_key: &[u8]) -> Result<TimeLock, CryptoError> {
+    let server_public = x25519::to_key(server_public_key, "server public key")?;
+    let sealed = create_timelock_bytes(plaintext, unlock_at, &server_public)?;
+    Ok(TimeLock { envelope: sealed.envelope, client_share: sealed.client_share, server_share: sealed.server_share })
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn release_timelock_share(server_secret_key: &[u8], sealed_share: &[u8], now: u64) -> Result<Vec<u8>, CryptoError> {
+    let mut secret = x25519::to_key(server_secret_key, "server secret key")?;
+    let result = release_timelock_share_bytes(&secret, sealed_share, now);
+    secret.zeroize();
+    result
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn open_timelock(envelope: &[u8], client_share: &[u8], server_share: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    open_timelock_bytes(envelope, client_share, server_share)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn timelock_unlock_at(blob: &[u8]) -> Result<u64, CryptoError> {
+    Ok(timelock_header_bytes(blob)?.unlock_at)
+}
+
+// X25519 poza profilem FIPS
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+
+    const UNLOCK_AT: u64 = 1_800_000_000;
+
+    fn server() -> Result<([u8; 32], [u8; 32]), CryptoError> {
+        let secret = x25519::generate_secret()?;
+        Ok((secret, x25519::public_key_bytes(&secret)))
+    }
+
+    #[test]
+    fn create_release_open_round_trip() -> Result<(), CryptoError> {
+        let (secret, public) = server()?;
+        let sealed = create_timelock_bytes(b"recovery code", UNLOCK_AT, &public)?;
+        assert_eq!(timelock_header_bytes(&sealed.envelope)?, timelock_header_bytes(&sealed.server_share)?);
+        assert_eq!(timelock_header_bytes(&sealed.envelope)?.unlock_at, UNLOCK_AT);
+        let share = release_timelock_share_bytes(&secret, &sealed.server_share, UNLOCK_AT + 10)?;
+        assert_eq!(open_timelock_bytes(&sealed.envelope, &*sealed.client_share, &share)?, b"recovery code");
+        Ok(())
+    }
+
+    #[test]
+    fn releases_only_from_unlock_time() -> Result<(), CryptoError> {
+        let (secret, public) = server()?;
+        let sealed = create_timelock_bytes(b"x", UNLOCK_AT, &public)?;
+        let early = release_timelock_share_bytes(&secret, &sealed.server_share, UNLOCK_AT - 1);
+        assert!(matches!(early, Err(CryptoError::OutOfRange(_))));
+        let share = release_timelock_share_bytes(&secret, &sealed.server_share, UNLOCK_AT)?;
+        assert_eq!(open_timelock_bytes(&sealed.envelope, &*sealed.client_share, &share)?, b"x");
+
+        // Przestawiony czas odblokowania w udziale nie przechodzi uwierzytelnienia
+        let mut moved = sealed.server_share.clone();
+        moved[5..13].copy_from_slice(&0u64.to_be_bytes());
+        assert!(matches!(release_timelock_share_bytes(&secret, &moved, 0), Err(CryptoError::DecryptFailed(_))));
+        let (other, _) = server()?;
+        let wrong_server = release_timelock_share_bytes(&other, &sealed.server_share, UNLOCK_AT);
+        assert!(matches!(wrong_server, Err(CryptoError::DecryptFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn open_rejects_tampering() -> Result<(), CryptoError> {
+        let (secret, public) = server()?;
+        let sealed = create_timelock_bytes(b"secret", UNLOCK_AT, &public)?;
+        let share = release_timelock_share_bytes(&secret, &sealed.server_share, UNLOCK_AT)?;
+        let client = &*sealed.client_share;
+
+        let mut envelope = sealed.envelope.clone();
+        envelope[12] ^= 1;
+        assert!(matches!(open_timelock_bytes(&envelope, client, &share), Err(CryptoError::DecryptFailed(_))));
+        let mut envelope = sealed.envelope.clone();
+        envelope[PREFIX_LEN] ^= 1;
+        assert!(matches!(open_timelock_bytes(&envelope, client, &share), Err(CryptoError::VerificationFailed(_))));
+        let mut envelope = sealed.envelope.clone();
+        if let Some(last) = envelope.last_mut() {
+            *last ^= 1;
+        }
+        assert!(matches!(open_timelock_bytes(&envelope, client, &share), Err(CryptoError::DecryptFailed(_))));
+
+        let mut bad_share = share.clone();
+        bad_share[0] ^= 1;
+        let opened = open_timelock_bytes(&sealed.envelope, client, &bad_share);
+        assert!(matches!(opened, Err(CryptoError::VerificationFailed(_))));
+        let opened = open_timelock_bytes(&sealed.envelope, &[0u8; 32], &share);
+        assert!(matches!(opened, Err(CryptoError::DecryptFailed(_))));
+        // Koperta i udzial nie sa zamienne
+        let opened = open_timelock_bytes(&sealed.server_share, client, &share);
+        assert!(matches!(opened, Err(CryptoError::CorruptHeader(_))));
+        let opened = open_timelock_bytes(&sealed.envelope[..HEADER_LEN], client, &share);
+        assert!(matches!(opened, Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+}
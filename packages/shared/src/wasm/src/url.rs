@@ -0,0 +1,132 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+
+use crate::error::CryptoError;
+#[cfg(feature = "js")]
+use crate::encoding::{OutputEncoding, encode_output};
+use crate::hmac::hmac_sha256_bytes;
+use crate::secret::EntryKey;
+
+const FINGERPRINT_LABEL: &[u8] = b"pm-url-fingerprint\0";
+pub const FINGERPRINT_LEN: usize = 16;
+
+// Sprowadza URL do postaci "host[:port]" na potrzeby porownywania kont miedzy urzadzeniami:
+// - schemat, dane logowania, sciezka, query i fragment sa pomijane,
+// - host malymi literami, bez koncowej kropki i bez prefiksu "www.",
+// - port pomijany gdy domyslny dla schematu (http 80, https 443; bez schematu jak https) lub brak.
+// Domeny IDN nie sa konwertowane do punycode - "bücher.de" i "xn--bcher-kva.de" daja rozne wyniki.
+pub fn canonicalize_url(url: &str) -> Result<String, CryptoError> {
+    let s = url.trim();
+    let (scheme, rest) = match s.find("://") {
+        Some(i) => (s[..i].to_ascii_lowercase(), &s[i + 3..]),
+        None => ("https".to_string(), s),
+    };
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..authority_end];
+    let host_port = match authority.rfind('@') {
+        Some(i) => &authority[i + 1..],
+        None => authority,
+    };
+
+    let (host, port) = if let Some(stripped) = host_port.strip_prefix('[') {
+        let end = stripped.find(']').ok_or_else(|| CryptoError::InvalidInput("invalid IPv6 host in url".to_string()))?;
+        let port = stripped[end + 1..].strip_prefix(':');
+        (&host_port[..end + 2], port)
+    } else {
+        match host_port.rfind(':') {
+            Some(i) => (&host_port[..i], Some(&host_port[i + 1..])),
+            None => (host_port, None),
+        }
+    };
+
+    let mut host = host.to_lowercase();
+    while host.ends_with('.') {
+        host.pop();
+    }
+    if let Some(stripped) = host.strip_prefix("www.") {
+        host = stripped.to_string();
+    }
+    if host.is_empty() {
+        return Err(CryptoError::InvalidInput("url has no host".to_string()));
+    }
+    if host.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(CryptoError::InvalidInput("url host contains invalid characters".to_string()));
+    }
+
+    let port = match port {
+        None | Some("") => return Ok(host),
+        Some(p) if p.bytes().all(|b| b.is_ascii_digit()) => p.parse::<u16>().ok().filter(|&p| p != 0),
+        Some(_) => None,
+    };
+    let port = port.ok_or_else(|| CryptoError::InvalidInput("invalid port in url".to_string()))?;
+    let default_port = match scheme.as_str() {
+        "http" | "ws" => Some(80),
+        "https" | "wss" => Some(443),
+        _ => None,
+    };
+    if default_port == Some(port) { Ok(host) } else { Ok(format!("{host}:{port}")) }
+}
+
+// HMAC-SHA256(index_key, etykieta || kanoniczny URL) obciety do 128 bitow.
+// Serwer moze porownywac odciski, ale bez index_key nie odwroci ich slownikiem domen.
+// Prawdopodobienstwo przypadkowej kolizji dla n odciskow ~ n^2 / 2^129
+// (dla miliona adresow ok. 1.5e-27), wiec kolizje mozna pominac.
+pub fn fingerprint_url_bytes(index_key: &EntryKey, url: &str) -> Result<[u8; FINGERPRINT_LEN], CryptoError> {
+    if index_key.len() < 32 {
+        return Err(CryptoError::InvalidLength("index key must be at least 32 bytes".to_string()));
+    }
+    let canonical = canonicalize_url(url)?;
+    let mut msg = FINGERPRINT_LABEL.to_vec();
+    msg.extend_from_slice(canonical.as_bytes());
+    let mac = hmac_sha256_bytes(index_key.as_bytes(), &msg);
+    let mut out = [0u8; FINGERPRINT_LEN];
+    out.copy_from_slice(&mac[..FINGERPRINT_LEN]);
+    Ok(out)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn canonical_url(url: &str) -> Result<String, CryptoError> {
+    canonicalize_url(url)
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn fingerprint_url(index_key: &[u8], url: &str, encoding: Option<OutputEncoding>) -> Result<JsValue, CryptoError> {
+    let fp = fingerprint_url_bytes(&EntryKey::from_slice(index_key), url)?;
+    Ok(encode_output(&fp, encoding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_is_normalized() -> Result<(), CryptoError> {
+        assert_eq!(canonicalize_url("https://User:pw@Example.COM/path?q=1#frag")?, "example.com");
+        assert_eq!(canonicalize_url("https://a@b@example.com")?, "example.com");
+        assert_eq!(canonicalize_url("https://www.example.com./login")?, "example.com");
+        assert_eq!(canonicalize_url("example.com..")?, "example.com");
+        assert_eq!(canonicalize_url("https://wwwexample.com")?, "wwwexample.com");
+        assert_eq!(canonicalize_url("http://[::1]:8080/")?, "[::1]:8080");
+        assert_eq!(canonicalize_url("https://[2001:DB8::1]")?, "[2001:db8::1]");
+        assert!(canonicalize_url("https://[::1/").is_err());
+        assert!(canonicalize_url("https://user@/path").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn only_the_scheme_default_port_is_dropped() -> Result<(), CryptoError> {
+        assert_eq!(canonicalize_url("https://example.com:443")?, "example.com");
+        assert_eq!(canonicalize_url("http://example.com:80")?, "example.com");
+        assert_eq!(canonicalize_url("example.com:443")?, "example.com");
+        assert_eq!(canonicalize_url("https://example.com:80")?, "example.com:80");
+        assert_eq!(canonicalize_url("http://example.com:443")?, "example.com:443");
+        assert_eq!(canonicalize_url("ftp://example.com:443")?, "example.com:443");
+        assert_eq!(canonicalize_url("https://example.com:08443")?, "example.com:8443");
+        assert_eq!(canonicalize_url("https://example.com:/")?, "example.com");
+        for url in ["https://example.com:99999", "https://example.com:0", "https://example.com:+1", "x.com:80a"] {
+            assert!(matches!(canonicalize_url(url), Err(CryptoError::InvalidInput(_))), "{url}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1374 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::aead::{AeadAlgorithm, aead_open_bytes, aead_seal_bytes};
+use crate::argon2::{self, argon2id_bytes};
+use crate::calibrate::KdfParams;
+use crate::cbor::Cbor;
+use crate::chacha20::{XChaCha20Poly1305, XNONCE_LEN};
+use crate::ct::ct_eq;
+use crate::deflate::deflate_bytes;
+use crate::encoding::{base64_to_bytes, bytes_to_base64, bytes_to_hex};
+use crate::error::CryptoError;
+use crate::fips::FIPS_PROFILE;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::hmac::hmac_sha256_bytes;
+use crate::import::inflate::inflate_bytes;
+use crate::merkle::MerkleTree;
+use crate::padding::{pad_bytes, unpad_bytes};
+use crate::pbkdf2::pbkdf2_hmac_sha256_bytes;
+use crate::rng::random_array;
+use crate::secret::{EntryKey, MasterKey};
+
+// Vault w pamieci wasm: wpisy trzymane zawsze zaszyfrowane (AES-256-GCM, nonce || ct || tag),
+// odszyfrowywane tylko przy odczycie. Klucz wpisow wyprowadzany HKDF z klucza vaulta z
+// identyfikatorem vaulta jako sola, AAD = id vaulta || 0 || id wpisu - wpisu nie da sie
+// przeniesc do innego vaulta ani pod inny identyfikator bez ponownego szyfrowania.
+// Tresc wpisu dopelniana Padme (padding.rs) przed szyfrowaniem; separator w AAD to wtedy 1
+// zamiast 0. Wpisy bez paddingu (starsze) nadal sie otwieraja, rotate_key zapisuje je na nowo.
+// Zablokowany vault nie ma zadnego klucza; odblokowanie sprawdza wartosc kontrolna (HMAC),
+// wiec zly klucz jest wykrywany od razu, a nie przy pierwszym odczycie.
+// Czas (now) w sekundach podaje wywolujacy, jak w pozostalych API.
+// Kazda mutacja (zapis, usuniecie, delta z synchronizacji, blokada) wysyla do subskrybentow
+// jedna paczke zdarzen - UI odswieza sie raz na operacje, a nie raz na wpis.
+const VAULT_FORMAT_VERSION: u32 = 1;
+const ENTRY_KEY_INFO: &[u8] = b"pm-vault-entry";
+const CHECK_KEY_INFO: &[u8] = b"pm-vault-check";
+const CHECK_LABEL: &[u8] = b"pm-vault-check-v1\0";
+
+// Koperta vaulta chronionego haslem glownym (vault_seal / vault_open):
+//   "PMVE" || wersja || id KDF || parametry KDF || dlugosc soli || sol || nonce || ciphertext || tag || MAC
+// Wersja wyznacza AEAD: v1 AES-256-GCM (nonce 12 B), v2 XChaCha20-Poly1305 (nonce 24 B).
+// KDF zapisany w naglowku: 1 = PBKDF2-HMAC-SHA256 (iteracje u32 BE), 2 = Argon2id (m, t, p u32 BE).
+// Nowe koperty: v2 + Argon2id, w profilu FIPS v1 + PBKDF2 (oba v2 sa tam niezatwierdzone).
+// Z hasla jeden klucz, z niego HKDF (sol koperty) osobne klucze AEAD i MAC. Naglowek jest AAD,
+// a HMAC-SHA256 obejmuje wszystko przed nim i jest sprawdzany przed odszyfrowaniem.
+// Najstarszy bit bajtu wersji = payload skompresowany DEFLATE przed szyfrowaniem (flaga jest
+// w AAD i pod MAC). Ustawiany tylko, gdy kompresja faktycznie zmniejsza dane.
+// Bit 0x40 = payload dopelniony Padme - zawsze razem z zadaniem kompresji, zeby dlugosc
+// koperty nie zdradzala, jak dobrze dane sie skompresowaly.
+const ENVELOPE_MAGIC: &[u8; 4] = b"PMVE";
+pub const CURRENT_ENVELOPE_VERSION: u8 = if FIPS_PROFILE { 1 } else { 2 };
+const ENVELOPE_FLAG_DEFLATE: u8 = 0x80;
+const ENVELOPE_FLAG_PADDED: u8 = 0x40;
+const ENVELOPE_SALT_LEN: usize = 16;
+const ENVELOPE_MAC_LEN: usize = 32;
+const ENVELOPE_ENC_INFO: &[u8] = b"pm-vault-envelope-enc";
+const ENVELOPE_MAC_INFO: &[u8] = b"pm-vault-envelope-mac";
+pub const ENVELOPE_PBKDF2_ITERATIONS: u32 = 600_000;
+
+// Postac zapisywana na dysk / wysylana do synchronizacji.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SealedVault {
+    pub version: u32,
+    pub vault_id: String,
+    pub check: String,
+    #[serde(default)]
+    pub lock_timeout: u32,
+    pub entries: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultChangeKind {
+    Added,
+    Updated,
+    Removed,
+    Relocked,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct VaultChange {
+    pub kind: VaultChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_id: Option<String>,
+}
+
+impl VaultChange {
+    fn entry(kind: VaultChangeKind, entry_id: &str) -> VaultChange {
+        VaultChange { kind, entry_id: Some(entry_id.to_string()) }
+    }
+}
+
+pub type VaultListener = Box<dyn Fn(&[VaultChange])>;
+
+// Rotacja kluczy: zmiana hasla (albo KDF) przepisuje tylko koperte - klucze wpisow pochodza
+// z klucza vaulta w srodku, wiec wpisy zostaja nietkniete. Ponowne szyfrowanie wpisow jest
+// potrzebne dopiero przy wymianie samego klucza vaulta (rotate_key, np. po jego wycieku);
+// rotate_vault_keys robi oba kroki naraz, z postepem per wpis.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationStage {
+    Unwrap,
+    Rewrap,
+    Reencrypt,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RotationProgress {
+    pub stage: RotationStage,
+    pub done: u32,
+    pub total: u32,
+}
+
+impl RotationProgress {
+    fn step(stage: RotationStage, done: u32, total: u32) -> RotationProgress {
+        RotationProgress { stage, done, total }
+    }
+}
+
+struct VaultKeys {
+    entry: EntryKey,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct Vault {
+    id: String,
+    check: String,
+    keys: Option<VaultKeys>,
+    entries: BTreeMap<String, Vec<u8>>,
+    lock_timeout: u32,
+    last_activity: u64,
+    listeners: Vec<(u32, VaultListener)>,
+    next_listener: u32,
+}
+
+fn derive_check(vault_id: &str, vault_key: &MasterKey) -> Result<String, CryptoError> {
+    let key = Zeroizing::new(hkdf_sha256_bytes(vault_key.as_bytes(), vault_id.as_bytes(), CHECK_KEY_INFO, 32)?);
+    let msg = [CHECK_LABEL, vault_id.as_bytes()].concat();
+    Ok(bytes_to_hex(&hmac_sha256_bytes(&key, &msg)))
+}
+
+fn derive_keys(vault_id: &str, vault_key: &MasterKey) -> Result<VaultKeys, CryptoError> {
+    if vault_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("vault key must be 32 bytes".to_string()));
+    }
+    Ok(VaultKeys {
+        entry: EntryKey::from_vec(hkdf_sha256_bytes(vault_key.as_bytes(), vault_id.as_bytes(), ENTRY_KEY_INFO, 32)?),
+    })
+}
+
+fn entry_aad(vault_id: &str, entry_id: &str, padded: bool) -> Vec<u8> {
+    let mut aad = vault_id.as_bytes().to_vec();
+    aad.push(padded as u8);
+    aad.extend_from_slice(entry_id.as_bytes());
+    aad
+}
+
+fn seal_entry_with(keys: &VaultKeys, vault_id: &str, entry_id: &str, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let aad = entry_aad(vault_id, entry_id, true);
+    aead_seal_bytes(AeadAlgorithm::Aes256Gcm, keys.entry.as_bytes(), &aad, &pad_bytes(plaintext))
+}
+
+fn open_entry_with(
+    keys: &VaultKeys,
+    vault_id: &str,
+    entry_id: &str,
+    blob: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let key = keys.entry.as_bytes();
+    match aead_open_bytes(AeadAlgorithm::Aes256Gcm, key, &entry_aad(vault_id, entry_id, true), blob) {
+        Ok(padded) => unpad_bytes(&Zeroizing::new(padded)),
+        Err(_) => {
+            let aad = entry_aad(vault_id, entry_id, false);
+            Ok(Zeroizing::new(aead_open_bytes(AeadAlgorithm::Aes256Gcm, key, &aad, blob)?))
+        }
+    }
+}
+
+impl Vault {
+    // now - czas utworzenia, od niego liczy sie limit bezczynnosci.
+    pub fn create(vault_id: &str, vault_key: &MasterKey, now: u64) -> Result<Vault, CryptoError> {
+        if vault_id.is_empty() || vault_id.contains('\0') {
+            return Err(CryptoError::InvalidInput("vault id must be non-empty and contain no NUL".to_string()));
+        }
+        Ok(Vault {
+            id: vault_id.to_string(),
+            check: derive_check(vault_id, vault_key)?,
+            keys: Some(derive_keys(vault_id, vault_key)?),
+            entries: BTreeMap::new(),
+            lock_timeout: 0,
+            last_activity: now,
+            listeners: Vec::new(),
+            next_listener: 0,
+        })
+    }
+
+    // Wczytany vault jest zablokowany.
+    pub fn from_sealed(sealed: &SealedVault) -> Result<Vault, CryptoError> {
+        let entries = sealed
+            .entries
+            .iter()
+            .map(|(id, blob)| Ok((id.clone(), base64_to_bytes(blob)?)))
+            .collect::<Result<BTreeMap<_, _>, CryptoError>>()?;
+        Vault::locked_with(sealed.version, &sealed.vault_id, &sealed.check, sealed.lock_timeout, entries)
+    }
+
+    fn locked_with(
+        version: u32,
+        vault_id: &str,
+        check: &str,
+        lock_timeout: u32,
+        entries: BTreeMap<String, Vec<u8>>,
+    ) -> Result<Vault, CryptoError> {
+        if version != VAULT_FORMAT_VERSION {
+            return Err(CryptoError::UnsupportedVersion(format!("vault format version {version}")));
+        }
+        Ok(Vault {
+            id: vault_id.to_string(),
+            check: check.to_string(),
+            keys: None,
+            entries,
+            lock_timeout,
+            last_activity: 0,
+            listeners: Vec::new(),
+            next_listener: 0,
+        })
+    }
+
+    // Te same pola co SealedVault, szyfrogramy wpisow jako ciagi bajtow zamiast base64.
+    pub fn to_cbor(&self) -> Cbor {
+        let entries = self.entries.iter().map(|(id, blob)| (Cbor::Text(id.clone()), Cbor::Bytes(blob.clone())));
+        Cbor::map(vec![
+            ("version", Cbor::Unsigned(VAULT_FORMAT_VERSION as u64)),
+            ("vault_id", Cbor::Text(self.id.clone())),
+            ("check", Cbor::Text(self.check.clone())),
+            ("lock_timeout", Cbor::Unsigned(self.lock_timeout as u64)),
+            ("entries", Cbor::Map(entries.collect())),
+        ])
+    }
+
+    pub fn from_cbor(value: &Cbor) -> Result<Vault, CryptoError> {
+        let malformed = |what: &str| CryptoError::InvalidInput(format!("malformed vault: {what}"));
+        let text = |key: &str| value.get(key).and_then(Cbor::as_text).ok_or_else(|| malformed(key));
+        let uint = |key: &str| {
+            value
+                .get(key)
+                .map_or(Some(0), Cbor::as_u64)
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| malformed(key))
+        };
+        let Some(Cbor::Map(items)) = value.get("entries") else { return Err(malformed("entries")) };
+        let entries = items
+            .iter()
+            .map(|(id, blob)| match (id, blob) {
+                (Cbor::Text(id), Cbor::Bytes(blob)) => Ok((id.clone(), blob.clone())),
+                _ => Err(malformed("entries")),
+            })
+            .collect::<Result<BTreeMap<_, _>, CryptoError>>()?;
+        if value.get("version").is_none() {
+            return Err(malformed("version"));
+        }
+        Vault::locked_with(uint("version")?, text("vault_id")?, text("check")?, uint("lock_timeout")?, entries)
+    }
+
+    pub fn to_sealed(&self) -> SealedVault {
+        SealedVault {
+            version: VAULT_FORMAT_VERSION,
+            vault_id: self.id.clone(),
+            check: self.check.clone(),
+            lock_timeout: self.lock_timeout,
+            entries: self.entries.iter().map(|(id, blob)| (id.clone(), bytes_to_base64(blob))).collect(),
+        }
+    }
+
+    fn keys(&self) -> Result<&VaultKeys, CryptoError> {
+        self.keys.as_ref().ok_or_else(|| CryptoError::InvalidInput(format!("vault {} is locked", self.id)))
+    }
+
+    // Odblokowanie liczy sie jako aktywnosc - inaczej wczytany vault zablokowalby sie
+    // przy pierwszym check_timeout.
+    pub fn unlock_with(&mut self, vault_key: &MasterKey, now: u64) -> Result<(), CryptoError> {
+        let keys = derive_keys(&self.id, vault_key)?;
+        if !ct_eq(derive_check(&self.id, vault_key)?.as_bytes(), self.check.as_bytes()) {
+            return Err(CryptoError::WrongPassword(format!("wrong key for vault {}", self.id)));
+        }
+        self.keys = Some(keys);
+        self.last_activity = now;
+        Ok(())
+    }
+
+    pub fn open_entry(&self, entry_id: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+        let keys = self.keys()?;
+        let blob = self
+            .entries
+            .get(entry_id)
+            .ok_or_else(|| CryptoError::InvalidInput(format!("no entry {entry_id} in vault {}", self.id)))?;
+        open_entry_with(keys, &self.id, entry_id, blob)
+    }
+
+    pub fn seal_entry(&self, entry_id: &str, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if entry_id.is_empty() {
+            return Err(CryptoError::InvalidInput("entry id must not be empty".to_string()));
+        }
+        seal_entry_with(self.keys()?, &self.id, entry_id, plaintext)
+    }
+
+    pub fn subscribe_with(&mut self, listener: VaultListener) -> u32 {
+        let id = self.next_listener;
+        self.next_listener = self.next_listener.wrapping_add(1);
+        self.listeners.push((id, listener));
+        id
+    }
+
+    fn emit(&self, changes: &[VaultChange]) {
+        if changes.is_empty() {
+            return;
+        }
+        for (_, listener) in &self.listeners {
+            listener(changes);
+        }
+    }
+
+    fn store(&mut self, entry_id: &str, blob: Vec<u8>) -> VaultChange {
+        let kind = match self.entries.insert(entry_id.to_string(), blob) {
+            Some(_) => VaultChangeKind::Updated,
+            None => VaultChangeKind::Added,
+        };
+        VaultChange::entry(kind, entry_id)
+    }
+
+    pub fn insert_sealed(&mut self, entry_id: &str, blob: Vec<u8>) -> bool {
+        let change = self.store(entry_id, blob);
+        self.emit(std::slice::from_ref(&change));
+        change.kind == VaultChangeKind::Updated
+    }
+
+    // Zastosowanie delty (synchronizacja, merge, czyszczenie historii) - wszystko albo nic,
+    // jedna paczka zdarzen. Wpisy szyfrowane przed jakakolwiek zmiana stanu.
+    pub fn apply_changes(&mut self, upserts: &[(String, Vec<u8>)], removals: &[String]) -> Result<(), CryptoError> {
+        self.keys()?;
+        let sealed = upserts
+            .iter()
+            .map(|(id, plaintext)| Ok((id.as_str(), self.seal_entry(id, plaintext)?)))
+            .collect::<Result<Vec<_>, CryptoError>>()?;
+        let mut changes: Vec<VaultChange> = sealed.into_iter().map(|(id, blob)| self.store(id, blob)).collect();
+        for id in removals {
+            if self.entries.remove(id).is_some() {
+                changes.push(VaultChange::entry(VaultChangeKind::Removed, id));
+            }
+        }
+        self.emit(&changes);
+        Ok(())
+    }
+
+    fn relock(&mut self) -> bool {
+        if self.keys.take().is_none() {
+            return false;
+        }
+        self.emit(&[VaultChange { kind: VaultChangeKind::Relocked, entry_id: None }]);
+        true
+    }
+
+    // Nowy klucz vaulta: wszystkie wpisy odszyfrowane starym i zaszyfrowane nowym kluczem,
+    // zanim cokolwiek zostanie podmienione - przy bledzie vault zostaje na starym kluczu.
+    pub fn rotate_key_with(
+        &mut self,
+        new_key: &MasterKey,
+        progress: &mut dyn FnMut(RotationProgress),
+    ) -> Result<(), CryptoError> {
+        let keys = self.keys()?;
+        let new_keys = derive_keys(&self.id, new_key)?;
+        let total = self.entries.len() as u32;
+        let mut entries = BTreeMap::new();
+        progress(RotationProgress::step(RotationStage::Reencrypt, 0, total));
+        for (done, (id, blob)) in self.entries.iter().enumerate() {
+            let plaintext = open_entry_with(keys, &self.id, id, blob)?;
+            entries.insert(id.clone(), seal_entry_with(&new_keys, &self.id, id, &plaintext)?);
+            progress(RotationProgress::step(RotationStage::Reencrypt, done as u32 + 1, total));
+        }
+        self.check = derive_check(&self.id, new_key)?;
+        self.keys = Some(new_keys);
+        self.entries = entries;
+        let changes: Vec<VaultChange> =
+            self.entries.keys().map(|id| VaultChange::entry(VaultChangeKind::Updated, id)).collect();
+        self.emit(&changes);
+        Ok(())
+    }
+
+    pub fn contains_entry(&self, entry_id: &str) -> bool {
+        self.entries.contains_key(entry_id)
+    }
+
+    pub fn touch_at(&mut self, now: u64) {
+        self.last_activity = now;
+    }
+
+    // Blokuje vault, jesli od ostatniej aktywnosci minal limit. Zwraca true, gdy zablokowal.
+    pub fn lock_if_idle(&mut self, now: u64) -> bool {
+        if self.keys.is_none() || self.lock_timeout == 0 {
+            return false;
+        }
+        now.saturating_sub(self.last_activity) >= self.lock_timeout as u64 && self.relock()
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl Vault {
+    #[cfg_attr(feature = "js", wasm_bindgen(constructor))]
+    pub fn new(vault_id: &str, vault_key: &[u8], now: u64) -> Result<Vault, CryptoError> {
+        Vault::create(vault_id, &MasterKey::from_slice(vault_key), now)
+    }
+
+    pub fn load(sealed: &str) -> Result<Vault, CryptoError> {
+        let sealed: SealedVault =
+            serde_json::from_str(sealed).map_err(|e| CryptoError::InvalidInput(format!("malformed vault: {e}")))?;
+        Vault::from_sealed(&sealed)
+    }
+
+    pub fn export_sealed(&self) -> Result<String, CryptoError> {
+        serde_json::to_string(&self.to_sealed()).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+    }
+
+    pub fn load_cbor(sealed: &[u8]) -> Result<Vault, CryptoError> {
+        Vault::from_cbor(&Cbor::decode(sealed)?)
+    }
+
+    // Kanoniczny CBOR - te same bajty dla tego samego stanu (MAC / podpis nad eksportem).
+    pub fn export_cbor(&self) -> Vec<u8> {
+        self.to_cbor().encode()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn locked(&self) -> bool {
+        self.keys.is_none()
+    }
+
+    pub fn unlock(&mut self, vault_key: &[u8], now: u64) -> Result<(), CryptoError> {
+        self.unlock_with(&MasterKey::from_slice(vault_key), now)
+    }
+
+    pub fn lock(&mut self) {
+        self.relock();
+    }
+
+    // 0 = bez automatycznej blokady.
+    #[cfg_attr(feature = "js", wasm_bindgen(setter))]
+    pub fn set_lock_timeout(&mut self, seconds: u32) {
+        self.lock_timeout = seconds;
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn lock_timeout(&self) -> u32 {
+        self.lock_timeout
+    }
+
+    pub fn touch(&mut self, now: u64) {
+        self.touch_at(now);
+    }
+
+    pub fn check_timeout(&mut self, now: u64) -> bool {
+        self.lock_if_idle(now)
+    }
+
+    pub fn put_entry(&mut self, entry_id: &str, entry: &str) -> Result<(), CryptoError> {
+        let blob = self.seal_entry(entry_id, entry.as_bytes())?;
+        self.insert_sealed(entry_id, blob);
+        Ok(())
+    }
+
+    pub fn get_entry(&self, entry_id: &str) -> Result<String, CryptoError> {
+        let mut plaintext = self.open_entry(entry_id)?;
+        String::from_utf8(std::mem::take(&mut *plaintext))
+            .map_err(|_| CryptoError::CorruptHeader("entry is not UTF-8".to_string()))
+    }
+
+    // Usuniecie nie wymaga klucza, ale zablokowany vault odmawia (spojnosc z zapisem).
+    pub fn remove_entry(&mut self, entry_id: &str) -> Result<bool, CryptoError> {
+        self.keys()?;
+        let removed = self.entries.remove(entry_id).is_some();
+        if removed {
+            self.emit(&[VaultChange::entry(VaultChangeKind::Removed, entry_id)]);
+        }
+        Ok(removed)
+    }
+
+    // upserts - obiekt JSON { id wpisu: tresc wpisu }, removed - identyfikatory do usuniecia.
+    pub fn apply_delta(&mut self, upserts: &str, removed: Vec<String>) -> Result<(), CryptoError> {
+        let upserts: BTreeMap<String, String> =
+            serde_json::from_str(upserts).map_err(|e| CryptoError::InvalidInput(format!("malformed delta: {e}")))?;
+        let upserts: Vec<(String, Vec<u8>)> = upserts.into_iter().map(|(id, entry)| (id, entry.into_bytes())).collect();
+        let result = self.apply_changes(&upserts, &removed);
+        for (_, mut plaintext) in upserts {
+            plaintext.zeroize();
+        }
+        result
+    }
+
+    // Callback dostaje tablice zdarzen [{ kind: "added" | "updated" | "removed" | "relocked",
+    // entry_id? }] - jedna tablica na mutacje. Zwraca identyfikator do unsubscribe.
+    #[cfg(feature = "js")]
+    pub fn subscribe(&mut self, callback: js_sys::Function) -> u32 {
+        self.subscribe_with(Box::new(move |changes| {
+            if let Ok(json) = serde_json::to_string(changes)
+                && let Ok(batch) = js_sys::JSON::parse(&json)
+            {
+                let _ = callback.call1(&JsValue::NULL, &batch);
+            }
+        }))
+    }
+
+    pub fn unsubscribe(&mut self, subscription: u32) -> bool {
+        let before = self.listeners.len();
+        self.listeners.retain(|(id, _)| *id != subscription);
+        self.listeners.len() != before
+    }
+
+    pub fn entry_ids(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+
+    // Drzewo Merkle nad zaszyfrowanymi wpisami - dziala tez na zablokowanym vaulcie.
+    pub fn merkle_tree(&self) -> MerkleTree {
+        MerkleTree::build(self.entries.iter().map(|(id, blob)| (id.as_str(), blob.as_slice())))
+    }
+
+    // Callback postepu jak w rotate_master_key; kazdy wpis dostaje zdarzenie "updated".
+    #[cfg(feature = "js")]
+    pub fn rotate_key(&mut self, new_vault_key: &[u8], progress: Option<js_sys::Function>) -> Result<(), CryptoError> {
+        self.rotate_key_with(&MasterKey::from_slice(new_vault_key), &mut js_progress(progress))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultKdf {
+    Pbkdf2Sha256 { iterations: u32 },
+    Argon2id { m_cost: u32, t_cost: u32, parallelism: u32 },
+}
+
+impl VaultKdf {
+    // Domyslny KDF dla nowych kopert danej wersji.
+    pub fn for_version(version: u8) -> VaultKdf {
+        match version {
+            1 => VaultKdf::Pbkdf2Sha256 { iterations: ENVELOPE_PBKDF2_ITERATIONS },
+            _ => VaultKdf::Argon2id {
+                m_cost: argon2::DEFAULT_MEMORY_KIB,
+                t_cost: argon2::DEFAULT_ITERATIONS,
+                parallelism: argon2::DEFAULT_PARALLELISM,
+            },
+        }
+    }
+
+    pub fn id(self) -> u8 {
+        match self {
+            VaultKdf::Pbkdf2Sha256 { .. } => 1,
+            VaultKdf::Argon2id { .. } => 2,
+        }
+    }
+
+    fn write_params(self, out: &mut Vec<u8>) {
+        match self {
+            VaultKdf::Pbkdf2Sha256 { iterations } => out.extend_from_slice(&iterations.to_be_bytes()),
+            VaultKdf::Argon2id { m_cost, t_cost, parallelism } => {
+                for value in [m_cost, t_cost, parallelism] {
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    // Zwraca KDF i liczbe bajtow parametrow.
+    fn read_params(id: u8, params: &[u8]) -> Result<(VaultKdf, usize), CryptoError> {
+        let read_u32 = |at: usize| {
+            params
+                .get(at..at + 4)
+                .map(|p| u32::from_be_bytes([p[0], p[1], p[2], p[3]]))
+                .ok_or_else(|| CryptoError::CorruptHeader("vault envelope kdf parameters truncated".to_string()))
+        };
+        match id {
+            1 => Ok((VaultKdf::Pbkdf2Sha256 { iterations: read_u32(0)? }, 4)),
+            2 => Ok((VaultKdf::Argon2id { m_cost: read_u32(0)?, t_cost: read_u32(4)?, parallelism: read_u32(8)? }, 12)),
+            _ => Err(CryptoError::UnsupportedAlgorithm(format!("vault envelope kdf id {id}"))),
+        }
+    }
+
+    fn derive(self, password: &[u8], salt: &[u8]) -> Result<MasterKey, CryptoError> {
+        let key = match self {
+            VaultKdf::Pbkdf2Sha256 { iterations } => pbkdf2_hmac_sha256_bytes(password, salt, iterations, 32)?,
+            VaultKdf::Argon2id { m_cost, t_cost, parallelism } => {
+                argon2id_bytes(password, salt, m_cost, t_cost, parallelism, 32)?
+            }
+        };
+        Ok(MasterKey::from_vec(key))
+    }
+}
+
+fn check_envelope_version(version: u8) -> Result<(), CryptoError> {
+    if !(1..=2).contains(&version) {
+        return Err(CryptoError::UnsupportedVersion(format!("vault envelope version {version}")));
+    }
+    Ok(())
+}
+
+fn envelope_nonce_len(version: u8) -> usize {
+    if version == 1 { NONCE_LEN } else { XNONCE_LEN }
+}
+
+fn envelope_encrypt(
+    version: u8,
+    key: &EntryKey,
+    nonce: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    match version {
+        1 => AesGcm::new(key.as_bytes())?.encrypt(nonce, aad, plaintext),
+        _ => XChaCha20Poly1305::new(key.as_bytes())?.encrypt(nonce, aad, plaintext),
+    }
+}
+
+fn envelope_decrypt(
+    version: u8,
+    key: &EntryKey,
+    nonce: &[u8],
+    aad: &[u8],
+    sealed: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    match version {
+        1 => AesGcm::new(key.as_bytes())?.decrypt(nonce, aad, sealed),
+        _ => XChaCha20Poly1305::new(key.as_bytes())?.decrypt(nonce, aad, sealed),
+    }
+}
+
+struct EnvelopeKeys {
+    enc: EntryKey,
+    mac: EntryKey,
+}
+
+fn envelope_keys(password: &[u8], kdf: VaultKdf, salt: &[u8]) -> Result<EnvelopeKeys, CryptoError> {
+    let key = kdf.derive(password, salt)?;
+    Ok(EnvelopeKeys {
+        enc: EntryKey::from_vec(hkdf_sha256_bytes(key.as_bytes(), salt, ENVELOPE_ENC_INFO, 32)?),
+        mac: EntryKey::from_vec(hkdf_sha256_bytes(key.as_bytes(), salt, ENVELOPE_MAC_INFO, 32)?),
+    })
+}
+
+struct EnvelopeHeader<'a> {
+    version: u8,
+    compressed: bool,
+    padded: bool,
+    kdf: VaultKdf,
+    salt: &'a [u8],
+    len: usize,
+}
+
+impl EnvelopeHeader<'_> {
+    fn to_cbor(&self) -> Cbor {
+        let kdf = match self.kdf {
+            VaultKdf::Pbkdf2Sha256 { iterations } => Cbor::map(vec![
+                ("id", Cbor::Unsigned(self.kdf.id() as u64)),
+                ("iterations", Cbor::Unsigned(iterations as u64)),
+            ]),
+            VaultKdf::Argon2id { m_cost, t_cost, parallelism } => Cbor::map(vec![
+                ("id", Cbor::Unsigned(self.kdf.id() as u64)),
+                ("m_cost", Cbor::Unsigned(m_cost as u64)),
+                ("t_cost", Cbor::Unsigned(t_cost as u64)),
+                ("parallelism", Cbor::Unsigned(parallelism as u64)),
+            ]),
+        };
+        Cbor::map(vec![
+            ("version", Cbor::Unsigned(self.version as u64)),
+            ("kdf", kdf),
+            ("salt", Cbor::Bytes(self.salt.to_vec())),
+            ("compressed", Cbor::Bool(self.compressed)),
+            ("padded", Cbor::Bool(self.padded)),
+        ])
+    }
+}
+
+fn read_envelope_header(blob: &[u8]) -> Result<EnvelopeHeader<'_>, CryptoError> {
+    let truncated = || CryptoError::CorruptHeader("vault envelope truncated".to_string());
+    if !blob.starts_with(ENVELOPE_MAGIC) {
+        return Err(CryptoError::CorruptHeader("not a vault envelope".to_string()));
+    }
+    let flags = *blob.get(4).ok_or_else(truncated)?;
+    let version = flags & !(ENVELOPE_FLAG_DEFLATE | ENVELOPE_FLAG_PADDED);
+    check_envelope_version(version)?;
+    let kdf_id = *blob.get(5).ok_or_else(truncated)?;
+    let (kdf, params_len) = VaultKdf::read_params(kdf_id, &blob[6..])?;
+    let salt_at = 6 + params_len + 1;
+    let salt_len = *blob.get(salt_at - 1).ok_or_else(truncated)? as usize;
+    if salt_len < ENVELOPE_SALT_LEN {
+        return Err(CryptoError::CorruptHeader(format!("vault envelope salt shorter than {ENVELOPE_SALT_LEN} bytes")));
+    }
+    let salt = blob.get(salt_at..salt_at + salt_len).ok_or_else(truncated)?;
+    Ok(EnvelopeHeader {
+        version,
+        compressed: flags & ENVELOPE_FLAG_DEFLATE != 0,
+        padded: flags & ENVELOPE_FLAG_PADDED != 0,
+        kdf,
+        salt,
+        len: salt_at + salt_len,
+    })
+}
+
+pub fn vault_seal_bytes(password: &[u8], plaintext: &[u8], version: u8, kdf: VaultKdf) -> Result<Vec<u8>, CryptoError> {
+    seal_envelope(password, plaintext, version, kdf, false)
+}
+
+pub fn vault_seal_compressed_bytes(
+    password: &[u8],
+    plaintext: &[u8],
+    version: u8,
+    kdf: VaultKdf,
+) -> Result<Vec<u8>, CryptoError> {
+    seal_envelope(password, plaintext, version, kdf, true)
+}
+
+fn seal_envelope(
+    password: &[u8],
+    plaintext: &[u8],
+    version: u8,
+    kdf: VaultKdf,
+    compress: bool,
+) -> Result<Vec<u8>, CryptoError> {
+    let salt = random_array::<ENVELOPE_SALT_LEN>()?;
+    let nonce = random_array::<XNONCE_LEN>()?;
+    seal_envelope_with(password, plaintext, version, kdf, compress, &salt, &nonce[..envelope_nonce_len(version)])
+}
+
+// Sol i nonce podane z zewnatrz - tylko dla wektorow testowych (bin/vectors.rs); nonce musi
+// miec dlugosc dla wersji (12 B w v1, 24 B w v2).
+pub fn vault_seal_with_nonce(
+    password: &[u8],
+    plaintext: &[u8],
+    version: u8,
+    kdf: VaultKdf,
+    salt: &[u8; ENVELOPE_SALT_LEN],
+    nonce: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    seal_envelope_with(password, plaintext, version, kdf, false, salt, nonce)
+}
+
+fn seal_envelope_with(
+    password: &[u8],
+    plaintext: &[u8],
+    version: u8,
+    kdf: VaultKdf,
+    compress: bool,
+    salt: &[u8; ENVELOPE_SALT_LEN],
+    nonce: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    check_envelope_version(version)?;
+    if password.is_empty() {
+        return Err(CryptoError::InvalidInput("master password must not be empty".to_string()));
+    }
+    if nonce.len() != envelope_nonce_len(version) {
+        return Err(CryptoError::InvalidLength(format!(
+            "vault envelope v{version} nonce must be {} bytes",
+            envelope_nonce_len(version)
+        )));
+    }
+    let packed = if compress { Some(deflate_bytes(plaintext)) } else { None };
+    let packed = packed.filter(|packed| packed.len() < plaintext.len());
+    let mut flags = version;
+    if packed.is_some() {
+        flags |= ENVELOPE_FLAG_DEFLATE;
+    }
+    let payload = packed.as_deref().map_or(plaintext, |packed| packed.as_slice());
+    let padded = if compress { Some(pad_bytes(payload)) } else { None };
+    if padded.is_some() {
+        flags |= ENVELOPE_FLAG_PADDED;
+    }
+    let payload = padded.as_deref().map_or(payload, |padded| padded.as_slice());
+    let mut out = ENVELOPE_MAGIC.to_vec();
+    out.push(flags);
+    out.push(kdf.id());
+    kdf.write_params(&mut out);
+    out.push(ENVELOPE_SALT_LEN as u8);
+    out.extend_from_slice(salt);
+
+    let keys = envelope_keys(password, kdf, salt)?;
+    let sealed = envelope_encrypt(version, &keys.enc, nonce, &out, payload)?;
+    out.extend_from_slice(nonce);
+    out.extend_from_slice(&sealed);
+    let mac = hmac_sha256_bytes(keys.mac.as_bytes(), &out);
+    out.extend_from_slice(&mac);
+    Ok(out)
+}
+
+// Parametry KDF z naglowka przechodza przez limity z limits.rs, zanim zacznie sie liczenie.
+pub fn vault_open_bytes(password: &[u8], blob: &[u8]) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    let header = read_envelope_header(blob)?;
+    let nonce_len = envelope_nonce_len(header.version);
+    if blob.len() < header.len + nonce_len + TAG_LEN + ENVELOPE_MAC_LEN {
+        return Err(CryptoError::CorruptHeader("vault envelope truncated".to_string()));
+    }
+    let (body, mac) = blob.split_at(blob.len() - ENVELOPE_MAC_LEN);
+    let keys = envelope_keys(password, header.kdf, header.salt)?;
+    if !ct_eq(&hmac_sha256_bytes(keys.mac.as_bytes(), body), mac) {
+        return Err(CryptoError::WrongPassword("wrong master password or modified vault envelope".to_string()));
+    }
+    let (aad, rest) = body.split_at(header.len);
+    let (nonce, sealed) = rest.split_at(nonce_len);
+    let mut plaintext = Zeroizing::new(envelope_decrypt(header.version, &keys.enc, nonce, aad, sealed)?);
+    if header.padded {
+        plaintext = unpad_bytes(&plaintext)?;
+    }
+    if !header.compressed {
+        return Ok(plaintext);
+    }
+    let (inflated, used) = inflate_bytes(&plaintext)?;
+    if used != plaintext.len() {
+        return Err(CryptoError::CorruptHeader("trailing data after compressed vault payload".to_string()));
+    }
+    Ok(inflated)
+}
+
+// Przepisuje koperte do wersji docelowej z domyslnym KDF tej wersji. Zwraca None, gdy
+// koperta juz jest w tej wersji - haslo i tak jest sprawdzane. Migracja tylko w gore:
+// cofniecie do starszej wersji (np. z Argon2id na PBKDF2) to UnsupportedVersion.
+pub fn migrate_vault_bytes(blob: &[u8], password: &[u8], target_version: u8) -> Result<Option<Vec<u8>>, CryptoError> {
+    check_envelope_version(target_version)?;
+    let header = read_envelope_header(blob)?;
+    if target_version < header.version {
+        return Err(CryptoError::UnsupportedVersion(format!(
+            "cannot migrate vault envelope from version {} down to {target_version}",
+            header.version
+        )));
+    }
+    let plaintext = vault_open_bytes(password, blob)?;
+    if header.version == target_version {
+        return Ok(None);
+    }
+    let kdf = VaultKdf::for_version(target_version);
+    seal_envelope(password, &plaintext, target_version, kdf, header.compressed || header.padded).map(Some)
+}
+
+// Zmiana hasla glownego i/lub KDF: koperta otwarta starym haslem i zapisana nowym (nowa sol,
+// biezaca wersja koperty). Bez kdf: KDF z naglowka, a przy zmianie wersji domyslny nowej wersji.
+// Koperta to jedno wywolanie AEAD na calym vaulcie, wiec unwrap i rewrap raportuja tylko
+// 0/1 -> 1/1 (czas idzie glownie na KDF). Klucze wpisow pochodza z klucza vaulta, nie z hasla,
+// wiec sama zmiana hasla ich nie rusza - przy wymianie klucza vaulta zob. rotate_vault_keys_bytes.
+pub fn rotate_master_key_bytes(
+    old_password: &[u8],
+    new_password: &[u8],
+    blob: &[u8],
+    kdf: Option<VaultKdf>,
+    progress: &mut dyn FnMut(RotationProgress),
+) -> Result<Vec<u8>, CryptoError> {
+    rotate_envelope(old_password, new_password, blob, kdf, None, progress)
+}
+
+// Cala hierarchia naraz: haslo -> koperta -> klucz vaulta -> klucze wpisow. Payload koperty to
+// vault w CBOR (Vault::export_cbor); kazdy wpis jest odszyfrowany starym kluczem vaulta
+// i zaszyfrowany nowym (etap reencrypt, postep per wpis), potem vault trafia do nowej koperty.
+// Przy bledzie na dowolnym etapie zwracany jest blad, a stara koperta pozostaje wazna.
+pub fn rotate_vault_keys_bytes(
+    old_password: &[u8],
+    new_password: &[u8],
+    blob: &[u8],
+    old_vault_key: &MasterKey,
+    new_vault_key: &MasterKey,
+    kdf: Option<VaultKdf>,
+    progress: &mut dyn FnMut(RotationProgress),
+) -> Result<Vec<u8>, CryptoError> {
+    rotate_envelope(old_password, new_password, blob, kdf, Some((old_vault_key, new_vault_key)), progress)
+}
+
+fn rotate_envelope(
+    old_password: &[u8],
+    new_password: &[u8],
+    blob: &[u8],
+    kdf: Option<VaultKdf>,
+    vault_keys: Option<(&MasterKey, &MasterKey)>,
+    progress: &mut dyn FnMut(RotationProgress),
+) -> Result<Vec<u8>, CryptoError> {
+    let header = read_envelope_header(blob)?;
+    let (old_version, old_kdf, compressed) = (header.version, header.kdf, header.compressed || header.padded);
+    progress(RotationProgress::step(RotationStage::Unwrap, 0, 1));
+    let mut plaintext = vault_open_bytes(old_password, blob)?;
+    progress(RotationProgress::step(RotationStage::Unwrap, 1, 1));
+    if let Some((old_key, new_key)) = vault_keys {
+        let mut vault = Vault::load_cbor(&plaintext)?;
+        vault.unlock_with(old_key, 0)?;
+        vault.rotate_key_with(new_key, progress)?;
+        plaintext = Zeroizing::new(vault.export_cbor());
+    }
+    let version = CURRENT_ENVELOPE_VERSION;
+    let kdf = kdf.unwrap_or(if old_version == version { old_kdf } else { VaultKdf::for_version(version) });
+    progress(RotationProgress::step(RotationStage::Rewrap, 0, 1));
+    let rotated = seal_envelope(new_password, &plaintext, version, kdf, compressed)?;
+    progress(RotationProgress::step(RotationStage::Rewrap, 1, 1));
+    Ok(rotated)
+}
+
+// Callback dostaje { stage: "unwrap" | "rewrap" | "reencrypt", done, total }.
+#[cfg(feature = "js")]
+fn js_progress(callback: Option<js_sys::Function>) -> impl FnMut(RotationProgress) {
+    move |step| {
+        if let Some(callback) = &callback
+            && let Ok(json) = serde_json::to_string(&step)
+            && let Ok(step) = js_sys::JSON::parse(&json)
+        {
+            let _ = callback.call1(&JsValue::NULL, &step);
+        }
+    }
+}
+
+// Cala koperta (KDF, AEAD, MAC) po stronie Rusta - JS przekazuje tylko haslo i dane.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_seal(master_password: &str, plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    vault_seal_raw(master_password.as_bytes(), plaintext)
+}
+
+// Warianty _raw przyjmuja haslo jako bajty UTF-8 (Uint8Array), ktore JS moze wyczyscic.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_seal_raw(master_password: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let version = CURRENT_ENVELOPE_VERSION;
+    vault_seal_bytes(master_password, plaintext, version, VaultKdf::for_version(version))
+}
+
+// Parametry z calibrate_kdf zamiast domyslnych.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_seal_with(master_password: &str, plaintext: &[u8], params: &KdfParams) -> Result<Vec<u8>, CryptoError> {
+    vault_seal_with_raw(master_password.as_bytes(), plaintext, params)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_seal_with_raw(master_password: &[u8], plaintext: &[u8], params: &KdfParams) -> Result<Vec<u8>, CryptoError> {
+    let version = CURRENT_ENVELOPE_VERSION;
+    vault_seal_bytes(master_password, plaintext, version, params.vault_kdf(version))
+}
+
+// Kompresja przed szyfrowaniem - dla duzych vaultow; bez params domyslny KDF.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_seal_compressed(
+    master_password: &str,
+    plaintext: &[u8],
+    params: Option<KdfParams>,
+) -> Result<Vec<u8>, CryptoError> {
+    vault_seal_compressed_raw(master_password.as_bytes(), plaintext, params)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_seal_compressed_raw(
+    master_password: &[u8],
+    plaintext: &[u8],
+    params: Option<KdfParams>,
+) -> Result<Vec<u8>, CryptoError> {
+    let version = CURRENT_ENVELOPE_VERSION;
+    let kdf = params.map_or(VaultKdf::for_version(version), |params| params.vault_kdf(version));
+    vault_seal_compressed_bytes(master_password, plaintext, version, kdf)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_open(master_password: &str, blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    vault_open_raw(master_password.as_bytes(), blob)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_open_raw(master_password: &[u8], blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut plaintext = vault_open_bytes(master_password, blob)?;
+    Ok(std::mem::take(&mut *plaintext))
+}
+
+// Przy odblokowaniu: wersja < CURRENT_ENVELOPE_VERSION -> migrate_vault i zapis wyniku.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_envelope_version(blob: &[u8]) -> Result<u8, CryptoError> {
+    Ok(read_envelope_header(blob)?.version)
+}
+
+// Naglowek koperty jako kanoniczny CBOR { version, kdf: { id, ... }, salt, compressed, padded }.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_envelope_header(blob: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(read_envelope_header(blob)?.to_cbor().encode())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn vault_envelope_compressed(blob: &[u8]) -> Result<bool, CryptoError> {
+    Ok(read_envelope_header(blob)?.compressed)
+}
+
+// Zawsze zwraca koperte w wersji docelowej (kopie wejscia, jesli migracja nie byla potrzebna).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn migrate_vault(blob: &[u8], master_password: &str, target_version: u8) -> Result<Vec<u8>, CryptoError> {
+    migrate_vault_raw(blob, master_password.as_bytes(), target_version)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn migrate_vault_raw(blob: &[u8], master_password: &[u8], target_version: u8) -> Result<Vec<u8>, CryptoError> {
+    Ok(migrate_vault_bytes(blob, master_password, target_version)?.unwrap_or_else(|| blob.to_vec()))
+}
+
+// kdf_params z calibrate_kdf przy podnoszeniu parametrow KDF; bez nich KDF koperty zostaje.
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn rotate_master_key(
+    old_password: &str,
+    new_password: &str,
+    vault_blob: &[u8],
+    kdf_params: Option<KdfParams>,
+    progress: Option<js_sys::Function>,
+) -> Result<Vec<u8>, CryptoError> {
+    rotate_master_key_raw(old_password.as_bytes(), new_password.as_bytes(), vault_blob, kdf_params, progress)
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn rotate_master_key_raw(
+    old_password: &[u8],
+    new_password: &[u8],
+    vault_blob: &[u8],
+    kdf_params: Option<KdfParams>,
+    progress: Option<js_sys::Function>,
+) -> Result<Vec<u8>, CryptoError> {
+    let kdf = kdf_params.map(|params| params.vault_kdf(CURRENT_ENVELOPE_VERSION));
+    rotate_master_key_bytes(old_password, new_password, vault_blob, kdf, &mut js_progress(progress))
+}
+
+// Zmiana hasla razem z kluczem vaulta; vault_blob zawiera vault z Vault.export_cbor.
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn rotate_vault_keys(
+    old_password: &str,
+    new_password: &str,
+    vault_blob: &[u8],
+    old_vault_key: &[u8],
+    new_vault_key: &[u8],
+    kdf_params: Option<KdfParams>,
+    progress: Option<js_sys::Function>,
+) -> Result<Vec<u8>, CryptoError> {
+    let (old, new) = (old_password.as_bytes(), new_password.as_bytes());
+    rotate_vault_keys_raw(old, new, vault_blob, old_vault_key, new_vault_key, kdf_params, progress)
+}
+
+#[cfg(feature = "js")]
+#[wasm_bindgen]
+pub fn rotate_vault_keys_raw(
+    old_password: &[u8],
+    new_password: &[u8],
+    vault_blob: &[u8],
+    old_vault_key: &[u8],
+    new_vault_key: &[u8],
+    kdf_params: Option<KdfParams>,
+    progress: Option<js_sys::Function>,
+) -> Result<Vec<u8>, CryptoError> {
+    let kdf = kdf_params.map(|params| params.vault_kdf(CURRENT_ENVELOPE_VERSION));
+    let (old_key, new_key) = (MasterKey::from_slice(old_vault_key), MasterKey::from_slice(new_vault_key));
+    rotate_vault_keys_bytes(old_password, new_password, vault_blob, &old_key, &new_key, kdf, &mut js_progress(progress))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &[u8] = b"correct horse battery staple";
+    const PBKDF2: VaultKdf = VaultKdf::Pbkdf2Sha256 { iterations: 1000 };
+    #[cfg(not(feature = "fips-profile"))]
+    const ARGON2: VaultKdf = VaultKdf::Argon2id { m_cost: 64, t_cost: 1, parallelism: 1 };
+
+    #[test]
+    fn envelope_round_trips() -> Result<(), CryptoError> {
+        let blob = vault_seal_bytes(PASSWORD, b"vault payload", 1, PBKDF2)?;
+        assert_eq!(vault_envelope_version(&blob)?, 1);
+        assert_eq!(&*vault_open_bytes(PASSWORD, &blob)?, b"vault payload");
+        let packed = vault_seal_compressed_bytes(PASSWORD, &[b'a'; 4096], 1, PBKDF2)?;
+        assert!(vault_envelope_compressed(&packed)?);
+        assert_eq!(&*vault_open_bytes(PASSWORD, &packed)?, &[b'a'; 4096]);
+        Ok(())
+    }
+
+    #[test]
+    fn compression_flag_is_set_only_when_deflate_helps() -> Result<(), CryptoError> {
+        let json = "{\"name\":\"example.com\",\"username\":\"alice\"},".repeat(100);
+        let packed = vault_seal_compressed_bytes(PASSWORD, json.as_bytes(), CURRENT_ENVELOPE_VERSION, PBKDF2)?;
+        let plain = vault_seal_bytes(PASSWORD, json.as_bytes(), CURRENT_ENVELOPE_VERSION, PBKDF2)?;
+        assert_ne!(packed[4] & ENVELOPE_FLAG_DEFLATE, 0);
+        assert!(packed.len() < plain.len() / 4);
+        assert_eq!(&*vault_open_bytes(PASSWORD, &packed)?, json.as_bytes());
+
+        // Krotkie dane nie zyskuja na DEFLATE - bez flagi, ale nadal z paddingiem.
+        for data in [&b""[..], b"x"] {
+            let blob = vault_seal_compressed_bytes(PASSWORD, data, CURRENT_ENVELOPE_VERSION, PBKDF2)?;
+            let header = read_envelope_header(&blob)?;
+            assert!(!header.compressed && header.padded);
+            assert_eq!(&*vault_open_bytes(PASSWORD, &blob)?, data);
+        }
+
+        // Flaga ustawiona recznie na nieskompresowanym payloadzie - MAC tego nie przepusci.
+        let mut forged = plain.clone();
+        forged[4] |= ENVELOPE_FLAG_DEFLATE;
+        assert!(vault_open_bytes(PASSWORD, &forged).is_err());
+        Ok(())
+    }
+
+    // Te same koperty co "vault_envelope" w bin/vectors.rs; sprawdzone niezaleznie
+    // (Python: PBKDF2/HKDF/AES-GCM/HMAC, XChaCha20-Poly1305 na kluczu Argon2id z tego crate).
+    const VECTOR_PLAINTEXT: &str = "{\"items\":[]} zażółć";
+    const VECTOR_SALT: [u8; ENVELOPE_SALT_LEN] = [
+        0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+    ];
+    const VECTOR_V1: &str = "504d56450101000003e810101112131415161718191a1b1c1d1e1f202122232425262728292a2bdf3dbcb2\
+        68b465e194942b718f1d01cd0de5b27d4f2f92e27ebc2d9694c88e14b5591d2736cf63da29a67aec8b28043b866fb783f28af857ec61\
+        5b1ecef722aa8db011aab951a0";
+    #[cfg(not(feature = "fips-profile"))]
+    const VECTOR_V2: &str = "504d5645020200000040000000010000000110101112131415161718191a1b1c1d1e1f202122232425262728\
+        292a2b2c2d2e2f303132333435363784158798eee80b22065e8931c55df6464ac36e44c9334edb72015291fdfe01c0a2f357a7548734\
+        ee74d9c25b6274dcccd92be85b378794623b0d3ef209db581c34792d558c3ff1";
+
+    fn vector_nonce(len: usize) -> Vec<u8> {
+        (0x20..0x20 + len as u8).collect()
+    }
+
+    #[test]
+    fn envelope_v1_matches_the_vector() -> Result<(), CryptoError> {
+        let plaintext = VECTOR_PLAINTEXT.as_bytes();
+        let blob = vault_seal_with_nonce(PASSWORD, plaintext, 1, PBKDF2, &VECTOR_SALT, &vector_nonce(12))?;
+        assert_eq!(bytes_to_hex(&blob), VECTOR_V1);
+        assert_eq!(&*vault_open_bytes(PASSWORD, &blob)?, plaintext);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn envelope_v2_matches_the_vector() -> Result<(), CryptoError> {
+        let plaintext = VECTOR_PLAINTEXT.as_bytes();
+        let blob = vault_seal_with_nonce(PASSWORD, plaintext, 2, ARGON2, &VECTOR_SALT, &vector_nonce(24))?;
+        assert_eq!(bytes_to_hex(&blob), VECTOR_V2);
+        assert_eq!(&*vault_open_bytes(PASSWORD, &blob)?, plaintext);
+        Ok(())
+    }
+
+    // Naglowek z m=256 MiB i t=10^7 - bez limitu przebiegow Argon2 liczylby sie tygodniami
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn hostile_argon2_header_is_rejected_before_derivation() -> Result<(), CryptoError> {
+        let mut blob = crate::encoding::hex_to_bytes(VECTOR_V2)?;
+        blob[6..10].copy_from_slice(&(256 * 1024u32).to_be_bytes());
+        blob[10..14].copy_from_slice(&10_000_000u32.to_be_bytes());
+        assert!(matches!(vault_open_bytes(PASSWORD, &blob), Err(CryptoError::InvalidIterations(_))));
+        blob[10..14].copy_from_slice(&17u32.to_be_bytes());
+        assert!(matches!(vault_open_bytes(PASSWORD, &blob), Err(CryptoError::InvalidIterations(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn envelope_nonce_must_match_the_version() {
+        let plaintext = VECTOR_PLAINTEXT.as_bytes();
+        let seal =
+            |version, nonce: &[u8]| vault_seal_with_nonce(PASSWORD, plaintext, version, PBKDF2, &VECTOR_SALT, nonce);
+        assert!(matches!(seal(1, &vector_nonce(24)), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(seal(2, &vector_nonce(12)), Err(CryptoError::InvalidLength(_))));
+        assert!(matches!(seal(3, &vector_nonce(12)), Err(CryptoError::UnsupportedVersion(_))));
+    }
+
+    #[test]
+    fn wrong_password_and_tampering_are_rejected() -> Result<(), CryptoError> {
+        let mut blob = vault_seal_bytes(PASSWORD, b"vault payload", 1, PBKDF2)?;
+        assert!(matches!(vault_open_bytes(b"wrong", &blob), Err(CryptoError::WrongPassword(_))));
+        let at = blob.len() - ENVELOPE_MAC_LEN - 1;
+        blob[at] ^= 1;
+        assert!(matches!(vault_open_bytes(PASSWORD, &blob), Err(CryptoError::WrongPassword(_))));
+        assert!(matches!(vault_open_bytes(PASSWORD, b"PMVE"), Err(CryptoError::CorruptHeader(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn migrating_to_the_same_version_is_a_no_op() -> Result<(), CryptoError> {
+        let blob = vault_seal_bytes(PASSWORD, b"vault payload", 1, PBKDF2)?;
+        assert_eq!(migrate_vault_bytes(&blob, PASSWORD, 1)?, None);
+        assert!(matches!(migrate_vault_bytes(&blob, b"wrong", 1), Err(CryptoError::WrongPassword(_))));
+        assert!(matches!(migrate_vault_bytes(&blob, PASSWORD, 3), Err(CryptoError::UnsupportedVersion(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn raw_exports_take_password_bytes() -> Result<(), CryptoError> {
+        let params = KdfParams { pbkdf2_iterations: 1000, argon2_m_cost: 64, argon2_t_cost: 1, argon2_parallelism: 1 };
+        let blob = vault_seal_with_raw(PASSWORD, b"vault payload", &params)?;
+        assert_eq!(vault_open_raw(PASSWORD, &blob)?, b"vault payload");
+        assert_eq!(vault_open("correct horse battery staple", &blob)?, b"vault payload");
+        assert_eq!(migrate_vault_raw(&blob, PASSWORD, CURRENT_ENVELOPE_VERSION)?, blob);
+        assert!(matches!(vault_open_raw(b"wrong", &blob), Err(CryptoError::WrongPassword(_))));
+        assert!(matches!(migrate_vault_raw(&blob, b"wrong", CURRENT_ENVELOPE_VERSION), Err(CryptoError::WrongPassword(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rotation_rewraps_under_the_new_password() -> Result<(), CryptoError> {
+        let blob = vault_seal_bytes(PASSWORD, b"vault payload", CURRENT_ENVELOPE_VERSION, PBKDF2)?;
+        let mut steps = Vec::new();
+        let rotated = rotate_master_key_bytes(PASSWORD, b"new password", &blob, Some(PBKDF2), &mut |step| {
+            steps.push(serde_json::to_string(&step).unwrap_or_default())
+        })?;
+        assert_eq!(&*vault_open_bytes(b"new password", &rotated)?, b"vault payload");
+        assert!(matches!(vault_open_bytes(PASSWORD, &rotated), Err(CryptoError::WrongPassword(_))));
+        assert_eq!(steps.len(), 4);
+        assert!(steps[0].contains("\"unwrap\"") && steps[3].contains("\"rewrap\""));
+        let mut ignore = |_| {};
+        assert!(matches!(
+            rotate_master_key_bytes(b"wrong", b"new password", &blob, None, &mut ignore),
+            Err(CryptoError::WrongPassword(_))
+        ));
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn migration_upgrades_v1_to_v2() -> Result<(), CryptoError> {
+        let blob = vault_seal_bytes(PASSWORD, b"vault payload", 1, PBKDF2)?;
+        let migrated =
+            migrate_vault_bytes(&blob, PASSWORD, 2)?.ok_or_else(|| CryptoError::InvalidInput("no-op".into()))?;
+        assert_eq!(vault_envelope_version(&migrated)?, 2);
+        assert_eq!(read_envelope_header(&migrated)?.kdf.id(), 2);
+        assert_eq!(&*vault_open_bytes(PASSWORD, &migrated)?, b"vault payload");
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fips-profile"))]
+    #[test]
+    fn migration_refuses_to_downgrade() -> Result<(), CryptoError> {
+        let blob = vault_seal_bytes(PASSWORD, b"vault payload", 2, ARGON2)?;
+        assert!(matches!(migrate_vault_bytes(&blob, PASSWORD, 1), Err(CryptoError::UnsupportedVersion(_))));
+        assert!(matches!(
+            migrate_vault(&blob, "correct horse battery staple", 1),
+            Err(CryptoError::UnsupportedVersion(_))
+        ));
+        Ok(())
+    }
+
+    const VAULT_KEY: [u8; 32] = [0x42; 32];
+
+    type Batches = std::rc::Rc<std::cell::RefCell<Vec<Vec<VaultChange>>>>;
+
+    fn recorded(vault: &mut Vault) -> (Batches, u32) {
+        let batches = Batches::default();
+        let sink = batches.clone();
+        let id = vault.subscribe_with(Box::new(move |changes| sink.borrow_mut().push(changes.to_vec())));
+        (batches, id)
+    }
+
+    fn change(kind: VaultChangeKind, entry_id: &str) -> VaultChange {
+        VaultChange::entry(kind, entry_id)
+    }
+
+    #[test]
+    fn each_mutation_emits_one_batch() -> Result<(), CryptoError> {
+        let mut vault = Vault::create("vault", &MasterKey::from_slice(&VAULT_KEY), 0)?;
+        let (batches, _) = recorded(&mut vault);
+        vault.put_entry("a", "first")?;
+        vault.put_entry("a", "second")?;
+        assert!(vault.remove_entry("a")?);
+        assert!(!vault.remove_entry("a")?);
+        let upserts = [("b".to_string(), b"b".to_vec()), ("c".to_string(), b"c".to_vec())];
+        vault.apply_changes(&upserts, &[])?;
+        vault.apply_changes(&[("b".to_string(), b"b2".to_vec())], &["c".to_string(), "missing".to_string()])?;
+        vault.apply_changes(&[], &["missing".to_string()])?;
+        assert_eq!(
+            *batches.borrow(),
+            [
+                vec![change(VaultChangeKind::Added, "a")],
+                vec![change(VaultChangeKind::Updated, "a")],
+                vec![change(VaultChangeKind::Removed, "a")],
+                vec![change(VaultChangeKind::Added, "b"), change(VaultChangeKind::Added, "c")],
+                vec![change(VaultChangeKind::Updated, "b"), change(VaultChangeKind::Removed, "c")],
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn locking_emits_relocked_once() -> Result<(), CryptoError> {
+        let mut vault = Vault::create("vault", &MasterKey::from_slice(&VAULT_KEY), 100)?;
+        vault.set_lock_timeout(60);
+        let (batches, _) = recorded(&mut vault);
+        let relocked = vec![VaultChange { kind: VaultChangeKind::Relocked, entry_id: None }];
+        assert!(!vault.check_timeout(159));
+        vault.lock();
+        vault.lock();
+        assert_eq!(*batches.borrow(), std::slice::from_ref(&relocked));
+
+        vault.unlock(&VAULT_KEY, 1000)?;
+        assert!(!vault.check_timeout(1059));
+        assert!(vault.check_timeout(1060));
+        assert!(!vault.check_timeout(2000));
+        assert_eq!(*batches.borrow(), [relocked.clone(), relocked]);
+        assert!(vault.put_entry("a", "x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn unsubscribed_listeners_stop_receiving() -> Result<(), CryptoError> {
+        let mut vault = Vault::create("vault", &MasterKey::from_slice(&VAULT_KEY), 0)?;
+        let (first, first_id) = recorded(&mut vault);
+        let (second, _) = recorded(&mut vault);
+        vault.put_entry("a", "x")?;
+        assert!(vault.unsubscribe(first_id));
+        assert!(!vault.unsubscribe(first_id));
+        vault.put_entry("b", "y")?;
+        assert_eq!(first.borrow().len(), 1);
+        assert_eq!(second.borrow().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn hierarchy_rotation_rewraps_every_entry() -> Result<(), CryptoError> {
+        let new_key = MasterKey::from_slice(&[0x43; 32]);
+        let mut vault = Vault::create("vault", &MasterKey::from_slice(&VAULT_KEY), 0)?;
+        for (id, entry) in [("a", "x"), ("b", "y"), ("c", "z")] {
+            vault.put_entry(id, entry)?;
+        }
+        let blob = vault_seal_bytes(PASSWORD, &vault.export_cbor(), CURRENT_ENVELOPE_VERSION, PBKDF2)?;
+        let mut steps = Vec::new();
+        let rotated = rotate_vault_keys_bytes(
+            PASSWORD,
+            b"new password",
+            &blob,
+            &MasterKey::from_slice(&VAULT_KEY),
+            &new_key,
+            Some(PBKDF2),
+            &mut |step| steps.push((step.stage, step.done, step.total)),
+        )?;
+        let reencrypted: Vec<_> =
+            steps.iter().filter(|s| s.0 == RotationStage::Reencrypt).map(|s| (s.1, s.2)).collect();
+        assert_eq!(reencrypted, [(0, 3), (1, 3), (2, 3), (3, 3)]);
+        assert_eq!(steps.first().map(|s| s.0), Some(RotationStage::Unwrap));
+        assert_eq!(steps.last().map(|s| s.0), Some(RotationStage::Rewrap));
+
+        assert!(matches!(vault_open_bytes(PASSWORD, &rotated), Err(CryptoError::WrongPassword(_))));
+        let mut reopened = Vault::load_cbor(&vault_open_bytes(b"new password", &rotated)?)?;
+        assert!(matches!(reopened.unlock(&VAULT_KEY, 0), Err(CryptoError::WrongPassword(_))));
+        reopened.unlock_with(&new_key, 0)?;
+        assert_eq!(reopened.get_entry("a")?, "x");
+        assert_eq!(reopened.get_entry("c")?, "z");
+
+        let mut ignore = |_| {};
+        let wrong_key = MasterKey::from_slice(&[0x44; 32]);
+        assert!(matches!(
+            rotate_vault_keys_bytes(PASSWORD, b"new", &blob, &wrong_key, &new_key, None, &mut ignore),
+            Err(CryptoError::WrongPassword(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn key_rotation_reports_every_entry_as_updated() -> Result<(), CryptoError> {
+        let mut vault = Vault::create("vault", &MasterKey::from_slice(&VAULT_KEY), 0)?;
+        vault.put_entry("a", "x")?;
+        vault.put_entry("b", "y")?;
+        let (batches, _) = recorded(&mut vault);
+        let mut done = Vec::new();
+        vault.rotate_key_with(&MasterKey::from_slice(&[0x43; 32]), &mut |step| done.push(step.done))?;
+        assert_eq!(done, [0, 1, 2]);
+        assert_eq!(
+            *batches.borrow(),
+            [vec![change(VaultChangeKind::Updated, "a"), change(VaultChangeKind::Updated, "b")]]
+        );
+        vault.lock();
+        assert!(matches!(vault.unlock(&VAULT_KEY, 0), Err(CryptoError::WrongPassword(_))));
+        vault.unlock(&[0x43; 32], 0)?;
+        assert_eq!(vault.get_entry("b")?, "y");
+        Ok(())
+    }
+}
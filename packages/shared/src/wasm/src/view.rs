@@ -0,0 +1,331 @@
+use serde_json::{Map, Value};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::ed25519::{self, SIGNATURE_LEN, verify_bytes};
+use crate::error::CryptoError;
+use crate::gcm::{AesGcm, NONCE_LEN, TAG_LEN};
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::rng::random_array;
+use crate::secret::{EntryKey, MasterKey};
+
+// Klucze tylko do odczytu (audytorzy, raport "watchtower"). Rekord wpisu ma dwie czesci:
+// metadane (pola wskazane jako niesekretne) szyfrowane kluczem metadanych i reszte wpisu
+// szyfrowana kluczem tajnym; calosc podpisana Ed25519 kluczem zapisu. Wszystkie trzy klucze
+// wyprowadzane HKDF z klucza vaulta.
+// Klucz widoku = klucz metadanych (32) || klucz publiczny zapisu (32): pozwala odczytac
+// metadane i sprawdzic integralnosc rekordu, ale bez klucza prywatnego nie da sie wytworzyc
+// rekordu, ktory przejdzie weryfikacje (AEAD sam w sobie tego nie daje - kto odszyfrowuje,
+// ten moze zaszyfrowac).
+// Rekord: "PMVR" || 1 || dlugosc metadanych (u32 BE) || nonce || ct || tag (metadane)
+//         || nonce || ct || tag (sekrety) || podpis (64)
+// Podpis obejmuje wszystko przed nim i aad; naglowek jest AAD obu czesci.
+const RECORD_MAGIC: &[u8; 4] = b"PMVR";
+const RECORD_VERSION: u8 = 1;
+const RECORD_HEADER_LEN: usize = 9;
+const SIGNATURE_LABEL: &[u8] = b"pm-view-record-v1\0";
+const METADATA_KEY_INFO: &[u8] = b"pm-view-metadata";
+const SECRET_KEY_INFO: &[u8] = b"pm-view-secret";
+const SIGNING_KEY_INFO: &[u8] = b"pm-view-signing";
+pub const VIEW_KEY_LEN: usize = 64;
+
+struct RecordKeys {
+    metadata: EntryKey,
+    secret: EntryKey,
+    signing: [u8; 32],
+}
+
+impl Drop for RecordKeys {
+    fn drop(&mut self) {
+        self.signing.zeroize();
+    }
+}
+
+pub struct ViewKey {
+    metadata: EntryKey,
+    verify: [u8; 32],
+}
+
+struct ParsedRecord<'a> {
+    header: &'a [u8],
+    metadata: &'a [u8],
+    secret: &'a [u8],
+    signed: &'a [u8],
+    signature: [u8; SIGNATURE_LEN],
+}
+
+fn derive_keys(vault_key: &MasterKey) -> Result<RecordKeys, CryptoError> {
+    if vault_key.len() != 32 {
+        return Err(CryptoError::InvalidLength("vault key must be 32 bytes".to_string()));
+    }
+    let derive = |info: &[u8]| hkdf_sha256_bytes(vault_key.as_bytes(), &[], info, 32);
+    let mut signing = [0u8; 32];
+    signing.copy_from_slice(&Zeroizing::new(derive(SIGNING_KEY_INFO)?));
+    Ok(RecordKeys {
+        metadata: EntryKey::from_vec(derive(METADATA_KEY_INFO)?),
+        secret: EntryKey::from_vec(derive(SECRET_KEY_INFO)?),
+        signing,
+    })
+}
+
+pub fn derive_view_key(vault_key: &MasterKey) -> Result<ViewKey, CryptoError> {
+    let keys = derive_keys(vault_key)?;
+    Ok(ViewKey { metadata: EntryKey::from_slice(keys.metadata.as_bytes()), verify: ed25519::public_key_bytes(&keys.signing) })
+}
+
+impl ViewKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<ViewKey, CryptoError> {
+        if bytes.len() != VIEW_KEY_LEN {
+            return Err(CryptoError::InvalidLength(format!("view key must be {VIEW_KEY_LEN} bytes")));
+        }
+        let mut verify = [0u8; 32];
+        verify.copy_from_slice(&bytes[32..]);
+        Ok(ViewKey { metadata: EntryKey::from_slice(&bytes[..32]), verify })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.metadata.as_bytes(), &self.verify].concat()
+    }
+}
+
+fn signed_message(signed: &[u8], aad: &[u8]) -> Vec<u8> {
+    let mut msg = SIGNATURE_LABEL.to_vec();
+    msg.extend_from_slice(&(aad.len() as u32).to_be_bytes());
+    msg.extend_from_slice(aad);
+    msg.extend_from_slice(signed);
+    msg
+}
+
+fn part_aad(header: &[u8], aad: &[u8]) -> Vec<u8> {
+    [header, aad].concat()
+}
+
+fn seal_part(key: &EntryKey, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let nonce = random_array::<NONCE_LEN>()?;
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&AesGcm::new(key.as_bytes())?.encrypt(&nonce, aad, plaintext)?);
+    Ok(out)
+}
+
+fn open_part(key: &EntryKey, aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let (nonce, sealed) = sealed.split_at(NONCE_LEN);
+    AesGcm::new(key.as_bytes())?.decrypt(nonce, aad, sealed)
+}
+
+fn parse_record(record: &[u8]) -> Result<ParsedRecord<'_>, CryptoError> {
+    let min_part = NONCE_LEN + TAG_LEN;
+    if record.len() < RECORD_HEADER_LEN + 2 * min_part + SIGNATURE_LEN || &record[..4] != RECORD_MAGIC {
+        return Err(CryptoError::CorruptHeader("not a view record".to_string()));
+    }
+    if record[4] != RECORD_VERSION {
+        return Err(CryptoError::UnsupportedVersion(format!("view record version {}", record[4])));
+    }
+    let metadata_len = u32::from_be_bytes([record[5], record[6], record[7], record[8]]) as usize;
+    let (signed, signature) = record.split_at(record.len() - SIGNATURE_LEN);
+    let body = &signed[RECORD_HEADER_LEN..];
+    if metadata_len < min_part || metadata_len > body.len() - min_part {
+        return Err(CryptoError::CorruptHeader("view record metadata length out of range".to_string()));
+    }
+    let (metadata, secret) = body.split_at(metadata_len);
+    let mut sig = [0u8; SIGNATURE_LEN];
+    sig.copy_from_slice(signature);
+    Ok(ParsedRecord { header: &signed[..RECORD_HEADER_LEN], metadata, secret, signed, signature: sig })
+}
+
+fn verify_record(verify_key: &[u8; 32], aad: &[u8], parsed: &ParsedRecord) -> Result<(), CryptoError> {
+    if verify_bytes(verify_key, &signed_message(parsed.signed, aad), &parsed.signature) {
+        Ok(())
+    } else {
+        Err(CryptoError::VerificationFailed("view record signature is invalid".to_string()))
+    }
+}
+
+pub fn seal_record_bytes(
+    vault_key: &MasterKey,
+    aad: &[u8],
+    metadata: &[u8],
+    secret: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let keys = derive_keys(vault_key)?;
+    let sealed_len = (metadata.len() + NONCE_LEN + TAG_LEN) as u32;
+    let mut record = RECORD_MAGIC.to_vec();
+    record.push(RECORD_VERSION);
+    record.extend_from_slice(&sealed_len.to_be_bytes());
+    let header_aad = part_aad(&record, aad);
+    let sealed_metadata = seal_part(&keys.metadata, &header_aad, metadata)?;
+    let sealed_secret = seal_part(&keys.secret, &header_aad, secret)?;
+    record.extend_from_slice(&sealed_metadata);
+    record.extend_from_slice(&sealed_secret);
+    let signature = ed25519::sign_bytes(&keys.signing, &signed_message(&record, aad));
+    record.extend_from_slice(&signature);
+    Ok(record)
+}
+
+// Pelny odczyt (wlasciciel vaulta). Zwraca (metadane, sekrety).
+pub fn open_record_bytes(vault_key: &MasterKey, aad: &[u8], record: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    let keys = derive_keys(vault_key)?;
+    let parsed = parse_record(record)?;
+    verify_record(&ed25519::public_key_bytes(&keys.signing), aad, &parsed)?;
+    let header_aad = part_aad(parsed.header, aad);
+    let metadata = open_part(&keys.metadata, &header_aad, parsed.metadata)?;
+    let secret = open_part(&keys.secret, &header_aad, parsed.secret)?;
+    Ok((metadata, secret))
+}
+
+// Odczyt kluczem widoku: podpis, potem metadane. Sekrety pozostaja nieczytelne.
+pub fn view_record_bytes(view_key: &ViewKey, aad: &[u8], record: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let parsed = parse_record(record)?;
+    verify_record(&view_key.verify, aad, &parsed)?;
+    open_part(&view_key.metadata, &part_aad(parsed.header, aad), parsed.metadata)
+}
+
+pub fn verify_record_bytes(view_key: &ViewKey, aad: &[u8], record: &[u8]) -> Result<(), CryptoError> {
+    verify_record(&view_key.verify, aad, &parse_record(record)?)
+}
+
+// Podzial obiektu JSON wpisu na pola niesekretne (view_fields, tylko pola najwyzszego
+// poziomu) i reszte.
+fn split_item(item: &str, view_fields: &[String]) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), CryptoError> {
+    let value: Value =
+        serde_json::from_str(item).map_err(|e| CryptoError::InvalidInput(format!("malformed item JSON: {e}")))?;
+    let Value::Object(obj) = value else {
+        return Err(CryptoError::InvalidInput("item must be a JSON object".to_string()));
+    };
+    let (metadata, secret): (Map<String, Value>, Map<String, Value>) =
+        obj.into_iter().partition(|(k, _)| view_fields.contains(k));
+    let to_vec = |m: Map<String, Value>| serde_json::to_vec(&m).map_err(|e| CryptoError::InvalidInput(e.to_string()));
+    Ok((to_vec(metadata)?, Zeroizing::new(to_vec(secret)?)))
+}
+
+fn parse_object(bytes: &[u8]) -> Result<Map<String, Value>, CryptoError> {
+    serde_json::from_slice(bytes).map_err(|e| CryptoError::CorruptHeader(format!("malformed view record part: {e}")))
+}
+
+pub fn seal_item_record(
+    vault_key: &MasterKey,
+    aad: &[u8],
+    item: &str,
+    view_fields: &[String],
+) -> Result<Vec<u8>, CryptoError> {
+    let (metadata, secret) = split_item(item, view_fields)?;
+    seal_record_bytes(vault_key, aad, &metadata, &secret)
+}
+
+pub fn open_item_record(vault_key: &MasterKey, aad: &[u8], record: &[u8]) -> Result<String, CryptoError> {
+    let (metadata, secret) = open_record_bytes(vault_key, aad, record)?;
+    let secret = Zeroizing::new(secret);
+    let mut item = parse_object(&metadata)?;
+    item.extend(parse_object(&secret)?);
+    serde_json::to_string(&item).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn view_key(vault_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(derive_view_key(&MasterKey::from_slice(vault_key))?.to_bytes())
+}
+
+// item - wpis jako obiekt JSON, view_fields - pola widoczne dla klucza widoku
+// (np. ["name", "url", "updated_at", "password_strength"]).
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn seal_view_record(
+    vault_key: &[u8],
+    aad: &[u8],
+    item: &str,
+    view_fields: Vec<String>,
+) -> Result<Vec<u8>, CryptoError> {
+    seal_item_record(&MasterKey::from_slice(vault_key), aad, item, &view_fields)
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn open_view_record(vault_key: &[u8], aad: &[u8], record: &[u8]) -> Result<String, CryptoError> {
+    open_item_record(&MasterKey::from_slice(vault_key), aad, record)
+}
+
+// Zwraca metadane wpisu jako JSON.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn view_record_metadata(view_key: &[u8], aad: &[u8], record: &[u8]) -> Result<String, CryptoError> {
+    let metadata = view_record_bytes(&ViewKey::from_bytes(view_key)?, aad, record)?;
+    String::from_utf8(metadata).map_err(|_| CryptoError::CorruptHeader("view metadata is not UTF-8".to_string()))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn view_record_verify(view_key: &[u8], aad: &[u8], record: &[u8]) -> Result<bool, CryptoError> {
+    match verify_record_bytes(&ViewKey::from_bytes(view_key)?, aad, record) {
+        Ok(()) => Ok(true),
+        Err(CryptoError::VerificationFailed(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_KEY: [u8; 32] = [0x21; 32];
+    const AAD: &[u8] = b"vault-1/item-1";
+    const ITEM: &str = r#"{"name":"GitHub","url":"https://github.com","password":"hunter2","notes":"2fa codes"}"#;
+
+    fn fields() -> Vec<String> {
+        vec!["name".to_string(), "url".to_string()]
+    }
+
+    fn sealed() -> Result<Vec<u8>, CryptoError> {
+        seal_view_record(&VAULT_KEY, AAD, ITEM, fields())
+    }
+
+    #[test]
+    fn view_key_reads_metadata_only() -> Result<(), CryptoError> {
+        let record = sealed()?;
+        let view = view_key(&VAULT_KEY)?;
+        let metadata: Value = serde_json::from_str(&view_record_metadata(&view, AAD, &record)?)
+            .map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        assert_eq!(metadata, serde_json::json!({"name": "GitHub", "url": "https://github.com"}));
+
+        // Klucz metadanych nie otwiera czesci z sekretami
+        let parsed = parse_record(&record)?;
+        let view = ViewKey::from_bytes(&view)?;
+        assert!(open_part(&view.metadata, &part_aad(parsed.header, AAD), parsed.secret).is_err());
+
+        let full: Value = serde_json::from_str(&open_view_record(&VAULT_KEY, AAD, &record)?)
+            .map_err(|e| CryptoError::InvalidInput(e.to_string()))?;
+        assert_eq!(full["password"], "hunter2");
+        assert_eq!(full["notes"], "2fa codes");
+        Ok(())
+    }
+
+    #[test]
+    fn tampering_breaks_the_signature() -> Result<(), CryptoError> {
+        let record = sealed()?;
+        let view = view_key(&VAULT_KEY)?;
+        assert!(view_record_verify(&view, AAD, &record)?);
+        assert!(!view_record_verify(&view, b"vault-1/item-2", &record)?);
+        assert!(open_view_record(&VAULT_KEY, b"vault-1/item-2", &record).is_err());
+
+        for i in RECORD_HEADER_LEN..record.len() {
+            let mut flipped = record.clone();
+            flipped[i] ^= 0x01;
+            assert!(!view_record_verify(&view, AAD, &flipped)?, "byte {i}");
+        }
+
+        // Przesuniecie granicy metadane/sekrety o jeden bajt - naglowek dalej poprawny
+        let metadata_len = u32::from_be_bytes([record[5], record[6], record[7], record[8]]);
+        for len in [metadata_len - 1, metadata_len + 1] {
+            let mut moved = record.clone();
+            moved[5..9].copy_from_slice(&len.to_be_bytes());
+            assert!(!view_record_verify(&view, AAD, &moved)?);
+            assert!(matches!(view_record_metadata(&view, AAD, &moved), Err(CryptoError::VerificationFailed(_))));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn other_vault_key_fails_verification() -> Result<(), CryptoError> {
+        let record = seal_view_record(&[0x22; 32], AAD, ITEM, fields())?;
+        let view = view_key(&VAULT_KEY)?;
+        assert!(!view_record_verify(&view, AAD, &record)?);
+        assert!(matches!(view_record_metadata(&view, AAD, &record), Err(CryptoError::VerificationFailed(_))));
+        assert!(matches!(open_view_record(&VAULT_KEY, AAD, &record), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+}
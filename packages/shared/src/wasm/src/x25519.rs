@@ -0,0 +1,100 @@
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroize;
+
+use crate::curve25519::Fe;
+use crate::error::CryptoError;
+use crate::fips::require_approved;
+use crate::rng::random_array;
+
+// X25519 (RFC 7748) - drabinka Montgomery'ego na wspolrzednej u, w stalym czasie.
+pub const BASE_U: [u8; 32] = {
+    let mut b = [0u8; 32];
+    b[0] = 9;
+    b
+};
+
+pub fn x25519_bytes(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+
+    let x1 = Fe::from_bytes(u);
+    let mut x2 = Fe::ONE;
+    let mut z2 = Fe::ZERO;
+    let mut x3 = x1;
+    let mut z3 = Fe::ONE;
+    let mut swap = false;
+
+    for t in (0..255).rev() {
+        let k_t = (k[t / 8] >> (t % 8)) & 1 == 1;
+        swap ^= k_t;
+        Fe::cswap(&mut x2, &mut x3, swap);
+        Fe::cswap(&mut z2, &mut z3, swap);
+        swap = k_t;
+
+        let a = x2.add(&z2);
+        let aa = a.square();
+        let b = x2.sub(&z2);
+        let bb = b.square();
+        let e = aa.sub(&bb);
+        let c = x3.add(&z3);
+        let d = x3.sub(&z3);
+        let da = d.mul(&a);
+        let cb = c.mul(&b);
+        x3 = da.add(&cb).square();
+        z3 = x1.mul(&da.sub(&cb).square());
+        x2 = aa.mul(&bb);
+        z2 = e.mul(&aa.add(&e.mul_small(121665)));
+    }
+    Fe::cswap(&mut x2, &mut x3, swap);
+    Fe::cswap(&mut z2, &mut z3, swap);
+
+    let out = x2.mul(&z2.invert()).to_bytes();
+    k.zeroize();
+    x2.zeroize();
+    z2.zeroize();
+    x3.zeroize();
+    z3.zeroize();
+    out
+}
+
+pub fn public_key_bytes(secret: &[u8; 32]) -> [u8; 32] {
+    x25519_bytes(secret, &BASE_U)
+}
+
+// Wspolny sekret; odrzuca punkty malego rzedu (wynik zerowy).
+pub fn shared_secret(secret: &[u8; 32], their_public: &[u8; 32]) -> Result<[u8; 32], CryptoError> {
+    require_approved("x25519")?;
+    let shared = x25519_bytes(secret, their_public);
+    if shared.iter().all(|&b| b == 0) {
+        return Err(CryptoError::InvalidInput("x25519 public key is a low-order point".to_string()));
+    }
+    Ok(shared)
+}
+
+pub fn generate_secret() -> Result<[u8; 32], CryptoError> {
+    require_approved("x25519")?;
+    random_array::<32>()
+}
+
+pub fn to_key(bytes: &[u8], what: &str) -> Result<[u8; 32], CryptoError> {
+    bytes
+        .try_into()
+        .map_err(|_| CryptoError::InvalidLength(format!("{what} must be 32 bytes")))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn x25519_generate_secret() -> Result<Vec<u8>, CryptoError> {
+    Ok(generate_secret()?.to_vec())
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn x25519_public_key(secret_key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    require_approved("x25519")?;
+    let mut secret = to_key(secret_key, "x25519 secret key")?;
+    let public = public_key_bytes(&secret);
+    secret.zeroize();
+    Ok(public.to_vec())
+}
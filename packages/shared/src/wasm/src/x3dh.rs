@@ -0,0 +1,427 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "js")]
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::ed25519::{self, sign_bytes, verify_strict};
+use crate::encoding::{bytes_to_hex, hex_to_bytes, hex32};
+use crate::error::CryptoError;
+use crate::hkdf::hkdf_sha256_bytes;
+use crate::x25519;
+
+// Asynchroniczne uzgodnienie kluczy X3DH (wg specyfikacji Signal) - udostepnienie wpisu
+// uzytkownikowi, ktory jest offline. Odbiorca publikuje na serwerze pakiet prekeys:
+//   klucz tozsamosci X25519 (IK), klucz podpisujacy Ed25519, podpisany prekey (SPK)
+//   i jednorazowe prekeys (OPK; serwer wydaje kazdy tylko raz).
+// Nadawca sprawdza podpis SPK, generuje klucz efemeryczny EK i liczy
+//   SK = HKDF(0xFF*32 || DH(IK_A, SPK_B) || DH(EK_A, IK_B) || DH(EK_A, SPK_B) [|| DH(EK_A, OPK_B)])
+// AD = IK_A || IK_B. SK i AD inicjuja RatchetSession: nadawca z SPK_B jako kluczem ratchet
+// odbiorcy, odbiorca z sekretem SPK (z zapisanych sekretow prekeys).
+// Zaufanie do klucza podpisujacego odbiorcy (numery bezpieczenstwa) jest poza tym modulem.
+const SIGNED_PREKEY_LABEL: &[u8] = b"pm-x3dh-signed-prekey-v1\0";
+const X3DH_INFO: &[u8] = b"pm-x3dh-v1";
+const MAX_ONE_TIME_PREKEYS: u32 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OneTimePrekey {
+    pub id: u32,
+    #[serde(with = "hex32")]
+    pub key: [u8; 32],
+}
+
+// Czesc publiczna - wysylana na serwer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PrekeyBundle {
+    #[serde(with = "hex32")]
+    pub identity_key: [u8; 32],
+    #[serde(with = "hex32")]
+    pub signing_key: [u8; 32],
+    pub signed_prekey_id: u32,
+    #[serde(with = "hex32")]
+    pub signed_prekey: [u8; 32],
+    pub signed_prekey_signature: String,
+    #[serde(default)]
+    pub one_time_prekeys: Vec<OneTimePrekey>,
+}
+
+// Czesc tajna - przechowywana przez odbiorce (zaszyfrowana w vaulcie).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PrekeySecrets {
+    pub signed_prekey_id: u32,
+    #[serde(with = "hex32")]
+    pub signed_prekey: [u8; 32],
+    pub one_time_prekeys: Vec<OneTimePrekey>,
+}
+
+impl Drop for PrekeySecrets {
+    fn drop(&mut self) {
+        self.signed_prekey.zeroize();
+        for k in self.one_time_prekeys.iter_mut() {
+            k.key.zeroize();
+        }
+    }
+}
+
+// Pierwsza wiadomosc nadawcy - dolaczana do pierwszej wiadomosci ratchet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InitialMessage {
+    #[serde(with = "hex32")]
+    pub identity_key: [u8; 32],
+    #[serde(with = "hex32")]
+    pub ephemeral_key: [u8; 32],
+    pub signed_prekey_id: u32,
+    pub one_time_prekey_id: Option<u32>,
+}
+
+fn parse<'a, T: Deserialize<'a>>(json: &'a str, what: &str) -> Result<T, CryptoError> {
+    serde_json::from_str(json).map_err(|e| CryptoError::InvalidInput(format!("malformed {what}: {e}")))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, CryptoError> {
+    serde_json::to_string(value).map_err(|e| CryptoError::InvalidInput(e.to_string()))
+}
+
+fn signed_prekey_message(identity_key: &[u8; 32], id: u32, signed_prekey: &[u8; 32]) -> Vec<u8> {
+    let mut msg = SIGNED_PREKEY_LABEL.to_vec();
+    msg.extend_from_slice(identity_key);
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(signed_prekey);
+    msg
+}
+
+fn derive_shared_secret(dh: &[[u8; 32]]) -> Result<[u8; 32], CryptoError> {
+    let mut ikm = Zeroizing::new(vec![0xFFu8; 32]);
+    for part in dh {
+        ikm.extend_from_slice(part);
+    }
+    let okm = Zeroizing::new(hkdf_sha256_bytes(&ikm, &[0u8; 32], X3DH_INFO, 32)?);
+    let mut sk = [0u8; 32];
+    sk.copy_from_slice(&okm);
+    Ok(sk)
+}
+
+fn associated_data(initiator_identity: &[u8; 32], responder_identity: &[u8; 32]) -> Vec<u8> {
+    [initiator_identity.as_slice(), responder_identity].concat()
+}
+
+// Nowy podpisany prekey i `one_time_count` jednorazowych (identyfikatory od first_one_time_id).
+// Uzywane tez do rotacji SPK i dokladania OPK - stare sekrety trzyma wywolujacy.
+pub fn generate_prekeys_bytes(
+    identity_secret: &[u8; 32],
+    signing_secret: &[u8; 32],
+    signed_prekey_id: u32,
+    one_time_count: u32,
+    first_one_time_id: u32,
+) -> Result<(PrekeyBundle, PrekeySecrets), CryptoError> {
+    if one_time_count > MAX_ONE_TIME_PREKEYS {
+        return Err(CryptoError::OutOfRange(format!("at most {MAX_ONE_TIME_PREKEYS} one-time prekeys per bundle")));
+    }
+    if first_one_time_id.checked_add(one_time_count).is_none() {
+        return Err(CryptoError::OutOfRange("one-time prekey identifiers overflow".to_string()));
+    }
+    let identity_key = x25519::public_key_bytes(identity_secret);
+    let signed_secret = x25519::generate_secret()?;
+    let signed_prekey = x25519::public_key_bytes(&signed_secret);
+    let signature = sign_bytes(signing_secret, &signed_prekey_message(&identity_key, signed_prekey_id, &signed_prekey));
+
+    let mut secrets = PrekeySecrets { signed_prekey_id, signed_prekey: signed_secret, one_time_prekeys: Vec::new() };
+    let mut public = Vec::new();
+    for id in first_one_time_id..first_one_time_id + one_time_count {
+        let key = x25519::generate_secret()?;
+        public.push(OneTimePrekey { id, key: x25519::public_key_bytes(&key) });
+        secrets.one_time_prekeys.push(OneTimePrekey { id, key });
+    }
+    let bundle = PrekeyBundle {
+        identity_key,
+        signing_key: ed25519::public_key_bytes(signing_secret),
+        signed_prekey_id,
+        signed_prekey,
+        signed_prekey_signature: bytes_to_hex(&signature),
+        one_time_prekeys: public,
+    };
+    Ok((bundle, secrets))
+}
+
+pub fn verify_bundle(bundle: &PrekeyBundle) -> Result<(), CryptoError> {
+    let signature = hex_to_bytes(&bundle.signed_prekey_signature)?;
+    let msg = signed_prekey_message(&bundle.identity_key, bundle.signed_prekey_id, &bundle.signed_prekey);
+    verify_strict(&bundle.signing_key, &msg, &signature)
+        .map_err(|_| CryptoError::VerificationFailed("signed prekey signature is invalid".to_string()))
+}
+
+// Strona nadawcy. Uzywa pierwszego jednorazowego prekey z pakietu (serwer dolacza jeden),
+// bez niego uzgodnienie dziala, ale bez ochrony przed powtorzeniem pierwszej wiadomosci.
+// Zwraca (SK, AD, wiadomosc poczatkowa).
+pub fn x3dh_initiate_bytes(
+    identity_secret: &[u8; 32],
+    bundle: &PrekeyBundle,
+) -> Result<([u8; 32], Vec<u8>, InitialMessage), CryptoError> {
+    verify_bundle(bundle)?;
+    let identity_key = x25519::public_key_bytes(identity_secret);
+    let mut ephemeral = x25519::generate_secret()?;
+    let ephemeral_key = x25519::public_key_bytes(&ephemeral);
+    let one_time = bundle.one_time_prekeys.first();
+
+    let mut dh = Zeroizing::new(Vec::with_capacity(4));
+    let result = (|| {
+        dh.push(x25519::shared_secret(identity_secret, &bundle.signed_prekey)?);
+        dh.push(x25519::shared_secret(&ephemeral, &bundle.identity_key)?);
+        dh.push(x25519::shared_secret(&ephemeral, &bundle.signed_prekey)?);
+        if let Some(otk) = one_time {
+            dh.push(x25519::shared_secret(&ephemeral, &otk.key)?);
+        }
+        derive_shared_secret(&dh)
+    })();
+    ephemeral.zeroize();
+    let sk = result?;
+
+    let message = InitialMessage {
+        identity_key,
+        ephemeral_key,
+        signed_prekey_id: bundle.signed_prekey_id,
+        one_time_prekey_id: one_time.map(|k| k.id),
+    };
+    Ok((sk, associated_data(&identity_key, &bundle.identity_key), message))
+}
+
+// Strona odbiorcy. Zuzyty jednorazowy prekey jest usuwany z sekretow - wywolujacy musi
+// zapisac zaktualizowane sekrety, inaczej ta sama wiadomosc poczatkowa da sie powtorzyc.
+pub fn x3dh_respond_bytes(
+    identity_secret: &[u8; 32],
+    secrets: &mut PrekeySecrets,
+    message: &InitialMessage,
+) -> Result<([u8; 32], Vec<u8>), CryptoError> {
+    if message.signed_prekey_id != secrets.signed_prekey_id {
+        return Err(CryptoError::InvalidInput(format!("unknown signed prekey {}", message.signed_prekey_id)));
+    }
+    let one_time =
+        match message.one_time_prekey_id {
+            Some(id) => {
+                Some(secrets.one_time_prekeys.iter().position(|k| k.id == id).ok_or_else(|| {
+                    CryptoError::InvalidInput(format!("one-time prekey {id} unknown or already used"))
+                })?)
+            }
+            None => None,
+        };
+
+    let mut dh = Zeroizing::new(Vec::with_capacity(4));
+    dh.push(x25519::shared_secret(&secrets.signed_prekey, &message.identity_key)?);
+    dh.push(x25519::shared_secret(identity_secret, &message.ephemeral_key)?);
+    dh.push(x25519::shared_secret(&secrets.signed_prekey, &message.ephemeral_key)?);
+    if let Some(pos) = one_time {
+        dh.push(x25519::shared_secret(&secrets.one_time_prekeys[pos].key, &message.ephemeral_key)?);
+    }
+    let sk = derive_shared_secret(&dh)?;
+    if let Some(pos) = one_time {
+        let mut used = secrets.one_time_prekeys.remove(pos);
+        used.key.zeroize();
+    }
+    let identity_key = x25519::public_key_bytes(identity_secret);
+    Ok((sk, associated_data(&message.identity_key, &identity_key)))
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct GeneratedPrekeys {
+    bundle: String,
+    secrets: String,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl GeneratedPrekeys {
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn bundle(&self) -> String {
+        self.bundle.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn secrets(&self) -> String {
+        self.secrets.clone()
+    }
+}
+
+impl Drop for GeneratedPrekeys {
+    fn drop(&mut self) {
+        self.secrets.zeroize();
+    }
+}
+
+// Wynik uzgodnienia. Po stronie nadawcy `message` to wiadomosc poczatkowa (JSON), a `prekeys`
+// jest pusty; po stronie odbiorcy `prekeys` to sekrety bez zuzytego klucza jednorazowego.
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub struct X3dhAgreement {
+    shared_secret: [u8; 32],
+    associated_data: Vec<u8>,
+    message: String,
+    prekeys: String,
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+impl X3dhAgreement {
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn shared_secret(&self) -> Vec<u8> {
+        self.shared_secret.to_vec()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn associated_data(&self) -> Vec<u8> {
+        self.associated_data.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    #[cfg_attr(feature = "js", wasm_bindgen(getter))]
+    pub fn prekeys(&self) -> String {
+        self.prekeys.clone()
+    }
+}
+
+impl Drop for X3dhAgreement {
+    fn drop(&mut self) {
+        self.shared_secret.zeroize();
+        self.prekeys.zeroize();
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn x3dh_generate_prekeys(
+    identity_secret_key: &[u8],
+    signing_secret_key: &[u8],
+    signed_prekey_id: u32,
+    one_time_count: u32,
+    first_one_time_id: u32,
+) -> Result<GeneratedPrekeys, CryptoError> {
+    let identity = Zeroizing::new(x25519::to_key(identity_secret_key, "identity secret key")?);
+    let signing = Zeroizing::new(x25519::to_key(signing_secret_key, "signing secret key")?);
+    let (bundle, secrets) =
+        generate_prekeys_bytes(&identity, &signing, signed_prekey_id, one_time_count, first_one_time_id)?;
+    Ok(GeneratedPrekeys { bundle: to_json(&bundle)?, secrets: to_json(&secrets)? })
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn x3dh_verify_bundle(bundle: &str) -> Result<bool, CryptoError> {
+    match verify_bundle(&parse(bundle, "prekey bundle")?) {
+        Ok(()) => Ok(true),
+        Err(CryptoError::VerificationFailed(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn x3dh_initiate(identity_secret_key: &[u8], bundle: &str) -> Result<X3dhAgreement, CryptoError> {
+    let identity = Zeroizing::new(x25519::to_key(identity_secret_key, "identity secret key")?);
+    let (shared_secret, associated_data, message) = x3dh_initiate_bytes(&identity, &parse(bundle, "prekey bundle")?)?;
+    Ok(X3dhAgreement { shared_secret, associated_data, message: to_json(&message)?, prekeys: String::new() })
+}
+
+#[cfg_attr(feature = "js", wasm_bindgen)]
+pub fn x3dh_respond(
+    identity_secret_key: &[u8],
+    prekey_secrets: &str,
+    message: &str,
+) -> Result<X3dhAgreement, CryptoError> {
+    let identity = Zeroizing::new(x25519::to_key(identity_secret_key, "identity secret key")?);
+    let mut secrets: PrekeySecrets = parse(prekey_secrets, "prekey secrets")?;
+    let (shared_secret, associated_data) =
+        x3dh_respond_bytes(&identity, &mut secrets, &parse(message, "initial message")?)?;
+    Ok(X3dhAgreement { shared_secret, associated_data, message: String::new(), prekeys: to_json(&secrets)? })
+}
+
+// X25519 poza profilem FIPS
+#[cfg(all(test, not(feature = "fips-profile")))]
+mod tests {
+    use super::*;
+
+    struct Party {
+        identity: [u8; 32],
+        signing: [u8; 32],
+    }
+
+    fn party() -> Result<Party, CryptoError> {
+        Ok(Party { identity: x25519::generate_secret()?, signing: x25519::generate_secret()? })
+    }
+
+    #[test]
+    fn agree_with_one_time_prekey() -> Result<(), CryptoError> {
+        let (alice, bob) = (party()?, party()?);
+        let (bundle, mut secrets) = generate_prekeys_bytes(&bob.identity, &bob.signing, 7, 2, 100)?;
+        let (sk_a, ad_a, message) = x3dh_initiate_bytes(&alice.identity, &bundle)?;
+        assert_eq!(message.one_time_prekey_id, Some(100));
+        let (sk_b, ad_b) = x3dh_respond_bytes(&bob.identity, &mut secrets, &message)?;
+        assert_eq!(sk_a, sk_b);
+        assert_eq!(ad_a, ad_b);
+        assert_eq!(ad_a, associated_data(&x25519::public_key_bytes(&alice.identity), &bundle.identity_key));
+        // Jednorazowy prekey zuzyty - ta sama wiadomosc poczatkowa nie przejdzie drugi raz
+        assert_eq!(secrets.one_time_prekeys.iter().map(|k| k.id).collect::<Vec<_>>(), vec![101]);
+        let replayed = x3dh_respond_bytes(&bob.identity, &mut secrets, &message);
+        assert!(matches!(replayed, Err(CryptoError::InvalidInput(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn agree_without_one_time_prekey() -> Result<(), CryptoError> {
+        let (alice, bob) = (party()?, party()?);
+        let (bundle, mut secrets) = generate_prekeys_bytes(&bob.identity, &bob.signing, 1, 0, 0)?;
+        let (sk_a, ad_a, message) = x3dh_initiate_bytes(&alice.identity, &bundle)?;
+        assert_eq!(message.one_time_prekey_id, None);
+        let (sk_b, ad_b) = x3dh_respond_bytes(&bob.identity, &mut secrets, &message)?;
+        assert_eq!((sk_a, ad_a), (sk_b, ad_b));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_forged_signed_prekey() -> Result<(), CryptoError> {
+        let (alice, bob, mallory) = (party()?, party()?, party()?);
+        let (bundle, _) = generate_prekeys_bytes(&bob.identity, &bob.signing, 1, 1, 0)?;
+        verify_bundle(&bundle)?;
+        let mut forged = bundle.clone();
+        forged.signed_prekey = x25519::public_key_bytes(&x25519::generate_secret()?);
+        assert!(matches!(verify_bundle(&forged), Err(CryptoError::VerificationFailed(_))));
+        assert!(matches!(x3dh_initiate_bytes(&alice.identity, &forged), Err(CryptoError::VerificationFailed(_))));
+        // Podpis z klucza podpisujacego napastnika przy kluczu podpisujacym boba
+        let (mallory_bundle, _) = generate_prekeys_bytes(&bob.identity, &mallory.signing, 1, 1, 0)?;
+        let mut forged = bundle.clone();
+        forged.signed_prekey = mallory_bundle.signed_prekey;
+        forged.signed_prekey_signature = mallory_bundle.signed_prekey_signature;
+        assert!(matches!(verify_bundle(&forged), Err(CryptoError::VerificationFailed(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_mismatched_identity_key() -> Result<(), CryptoError> {
+        let (alice, bob, mallory) = (party()?, party()?, party()?);
+        let (bundle, mut secrets) = generate_prekeys_bytes(&bob.identity, &bob.signing, 1, 1, 0)?;
+        // Serwer podmienia klucz tozsamosci w pakiecie - podpis SPK go obejmuje
+        let mut swapped = bundle.clone();
+        swapped.identity_key = x25519::public_key_bytes(&mallory.identity);
+        assert!(matches!(x3dh_initiate_bytes(&alice.identity, &swapped), Err(CryptoError::VerificationFailed(_))));
+        // Odbiorca z innym kluczem tozsamosci nie dochodzi do tego samego sekretu
+        let (sk_a, _, message) = x3dh_initiate_bytes(&alice.identity, &bundle)?;
+        let (sk_m, _) = x3dh_respond_bytes(&mallory.identity, &mut secrets.clone(), &message)?;
+        assert_ne!(sk_a, sk_m);
+        // Wiadomosc poczatkowa z innym kluczem tozsamosci nadawcy tez nie
+        let mut spoofed = message.clone();
+        spoofed.identity_key = x25519::public_key_bytes(&mallory.identity);
+        let (sk_b, ad_b) = x3dh_respond_bytes(&bob.identity, &mut secrets, &spoofed)?;
+        assert_ne!(sk_a, sk_b);
+        assert_ne!(ad_b, associated_data(&message.identity_key, &bundle.identity_key));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_prekeys_and_bad_counts() -> Result<(), CryptoError> {
+        let (alice, bob) = (party()?, party()?);
+        let (bundle, mut secrets) = generate_prekeys_bytes(&bob.identity, &bob.signing, 1, 1, 0)?;
+        let (_, _, message) = x3dh_initiate_bytes(&alice.identity, &bundle)?;
+        let mut unknown = message.clone();
+        unknown.signed_prekey_id = 2;
+        assert!(matches!(x3dh_respond_bytes(&bob.identity, &mut secrets, &unknown), Err(CryptoError::InvalidInput(_))));
+        let too_many = generate_prekeys_bytes(&bob.identity, &bob.signing, 1, MAX_ONE_TIME_PREKEYS + 1, 0);
+        assert!(matches!(too_many, Err(CryptoError::OutOfRange(_))));
+        let overflow = generate_prekeys_bytes(&bob.identity, &bob.signing, 1, 2, u32::MAX);
+        assert!(matches!(overflow, Err(CryptoError::OutOfRange(_))));
+        Ok(())
+    }
+}